@@ -0,0 +1,117 @@
+//! Time-memory-data tradeoff on the internal state (Babbage–Golić,
+//! Biryukov–Shamir).
+//!
+//! The map from an internal state to the next `n` keystream bits, with `n` the
+//! state bits, is cheap to evaluate and roughly a random function: inverting
+//! it on any `n` bits window of a known keystream gives the state at that
+//! point. With `D` windows, a [`RainbowTable`] covering `N/D` of the `N`
+//! states is enough to hit one of them, for a precomputation of `N/D`
+//! evaluations and an online cost of `D·t²/2` (`TM²D² = N²` with Hellman
+//! tables).
+//!
+//! Against the real cipher the tables are terabytes: here the same attack runs
+//! on the reduced [`TOY`](crate::TOY) registers, where a frame of 228 bits
+//! gives about 200 windows of a 2²⁶ states space.
+//!
+//! The recovered state produces the rest of the frame keystream. Going back to
+//! the session key (clocking backwards to the state after the key setup) is
+//! not implemented.
+
+use crate::{rainbow::RainbowTable, state_bits, Register, A51};
+use rand::Rng;
+use rayon::prelude::*;
+
+/// First `n` keystream bits from the packed state, with `n` the state bits,
+/// packed with the first one in the least significant bit.
+pub fn prefix(registers: [Register; 3], state: u64) -> u64 {
+    let n = state_bits(&registers) as usize;
+    A51::from_state(registers, state)
+        .keystream(n)
+        .iter()
+        .enumerate()
+        .fold(0, |acc, (i, &b)| acc | (b as u64) << i)
+}
+
+/// Precomputed table for the given registers.
+#[derive(Debug, Clone)]
+pub struct Tmto {
+    registers: [Register; 3],
+    table: RainbowTable,
+}
+
+impl Tmto {
+    /// Precomputes `chains` chains of `length` states.
+    pub fn precompute<R: Rng + ?Sized>(
+        registers: [Register; 3],
+        chains: usize,
+        length: usize,
+        rng: &mut R,
+    ) -> Self {
+        let f = |state| prefix(registers, state);
+        let table = RainbowTable::build(&f, state_bits(&registers), chains, length, rng);
+        Tmto { registers, table }
+    }
+
+    /// Generator state within a known `keystream` (one bit per byte).
+    ///
+    /// Returns the offset and the state producing the keystream from there on,
+    /// checked against the whole remaining keystream.
+    pub fn recover(&self, keystream: &[u8]) -> Option<(usize, u64)> {
+        let n = state_bits(&self.registers) as usize;
+        let f = |state| prefix(self.registers, state);
+        (0..keystream.len().saturating_sub(n - 1))
+            .into_par_iter()
+            .find_map_first(|offset| {
+                let window = &keystream[offset..offset + n];
+                let y = pack(window);
+                let state = self.table.lookup(f, y)?;
+                let rest = &keystream[offset..];
+                let produced = A51::from_state(self.registers, state).keystream(rest.len());
+                (produced == rest).then_some((offset, state))
+            })
+    }
+}
+
+fn pack(bits: &[u8]) -> u64 {
+    bits.iter()
+        .enumerate()
+        .fold(0, |acc, (i, &b)| acc | (b as u64) << i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TOY;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn prefix_matches_keystream() {
+        let state = 0x1234567;
+        let bits = A51::from_state(TOY, state).keystream(26);
+        assert_eq!(prefix(TOY, state), pack(&bits));
+    }
+
+    #[test]
+    fn state_recovered_from_a_frame() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let tmto = Tmto::precompute(TOY, 8192, 128, &mut rng);
+        let recovered = (0..5)
+            .filter(|_| {
+                let key: [u8; 8] = rng.gen();
+                let mut a51 = A51::setup(TOY, &key, rng.gen_range(0..1 << 22));
+                let keystream = a51.clone().keystream(228);
+                match tmto.recover(&keystream) {
+                    Some((offset, state)) => {
+                        // Equivalent states may differ, but not in the keystream
+                        (0..offset).for_each(|_| a51.clock());
+                        let mut found = A51::from_state(TOY, state);
+                        assert_eq!(found.keystream(1000), a51.keystream(1000));
+                        true
+                    }
+                    None => false,
+                }
+            })
+            .count();
+        assert!(recovered >= 3, "{recovered}");
+    }
+}
@@ -0,0 +1,204 @@
+//! A5/1 GSM stream cipher.
+//!
+//! Three LFSRs of 19, 22 and 23 bits (64 bits of state) are clocked by
+//! majority: each register has a clocking bit and only the registers agreeing
+//! with the majority of the three step, i.e. two or three of them. The output
+//! bit is the sum of the most significant bits.
+//!
+//! The 64 bits session key and the 22 bits frame number are mixed into the
+//! zero state by clocking every register and adding one bit at a time, then
+//! 100 bits are discarded and 228 are produced: one 114 bits burst for each
+//! direction.
+//!
+//! The registers are parameters, for the [`attacks`] to run against a
+//! reduced state space ([`TOY`]) with the same structure.
+
+pub mod attacks;
+pub mod rainbow;
+
+/// LFSR with Fibonacci feedback, shifting towards the most significant bit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Register {
+    /// Length in bits.
+    pub len: u32,
+    /// Feedback taps mask.
+    pub taps: u32,
+    /// Clocking bit index.
+    pub clock: u32,
+}
+
+impl Register {
+    fn mask(&self) -> u32 {
+        (1 << self.len) - 1
+    }
+
+    fn step(&self, r: u32) -> u32 {
+        ((r << 1) & self.mask()) | ((r & self.taps).count_ones() & 1)
+    }
+
+    fn clocking(&self, r: u32) -> u32 {
+        (r >> self.clock) & 1
+    }
+
+    fn output(&self, r: u32) -> u32 {
+        r >> (self.len - 1)
+    }
+}
+
+/// A5/1 registers: feedback polynomials x^19+x^18+x^17+x^14+1, x^22+x^21+1
+/// and x^23+x^22+x^21+x^8+1.
+pub const A5_1: [Register; 3] = [
+    Register {
+        len: 19,
+        taps: 0x072000,
+        clock: 8,
+    },
+    Register {
+        len: 22,
+        taps: 0x300000,
+        clock: 10,
+    },
+    Register {
+        len: 23,
+        taps: 0x700080,
+        clock: 10,
+    },
+];
+
+/// Reduced registers with a 26 bits state: feedback polynomials x^7+x^6+1,
+/// x^9+x^5+1 and x^10+x^7+1.
+pub const TOY: [Register; 3] = [
+    Register {
+        len: 7,
+        taps: 0x060,
+        clock: 3,
+    },
+    Register {
+        len: 9,
+        taps: 0x110,
+        clock: 4,
+    },
+    Register {
+        len: 10,
+        taps: 0x240,
+        clock: 5,
+    },
+];
+
+/// State bits of the given registers.
+pub fn state_bits(registers: &[Register; 3]) -> u32 {
+    registers.iter().map(|r| r.len).sum()
+}
+
+/// Keystream generator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct A51 {
+    registers: [Register; 3],
+    state: [u32; 3],
+}
+
+impl A51 {
+    /// Generator in the state packed as by [`A51::state`].
+    pub fn from_state(registers: [Register; 3], state: u64) -> Self {
+        let mut shift = 0;
+        let state = registers.map(|r| {
+            let value = (state >> shift) as u32 & r.mask();
+            shift += r.len;
+            value
+        });
+        A51 { registers, state }
+    }
+
+    /// Generator after the key and frame setup, ready to produce the
+    /// keystream. Key bits are taken least significant first.
+    pub fn setup(registers: [Register; 3], key: &[u8; 8], frame: u32) -> Self {
+        let mut a51 = Self::from_state(registers, 0);
+        let key_bits = (0..64).map(|i| (key[i / 8] >> (i % 8)) as u32 & 1);
+        let frame_bits = (0..22).map(|i| (frame >> i) & 1);
+        for bit in key_bits.chain(frame_bits) {
+            a51.clock_all();
+            a51.state.iter_mut().for_each(|r| *r ^= bit);
+        }
+        (0..100).for_each(|_| a51.clock());
+        a51
+    }
+
+    /// Registers packed from the first one in the least significant bits.
+    pub fn state(&self) -> u64 {
+        self.registers
+            .iter()
+            .zip(self.state)
+            .rev()
+            .fold(0, |acc, (r, value)| (acc << r.len) | value as u64)
+    }
+
+    fn clock_all(&mut self) {
+        for (value, r) in self.state.iter_mut().zip(&self.registers) {
+            *value = r.step(*value);
+        }
+    }
+
+    /// Majority clocking.
+    pub fn clock(&mut self) {
+        let bits: [u32; 3] = std::array::from_fn(|i| self.registers[i].clocking(self.state[i]));
+        let majority = (bits[0] + bits[1] + bits[2]) / 2;
+        for (i, r) in self.registers.iter().enumerate() {
+            if bits[i] == majority {
+                self.state[i] = r.step(self.state[i]);
+            }
+        }
+    }
+
+    /// Clock and output a keystream bit.
+    pub fn bit(&mut self) -> u8 {
+        self.clock();
+        self.registers
+            .iter()
+            .zip(self.state)
+            .fold(0, |acc, (r, v)| acc ^ r.output(v) as u8)
+    }
+
+    /// Next `n` keystream bits.
+    pub fn keystream(&mut self, n: usize) -> Vec<u8> {
+        (0..n).map(|_| self.bit()).collect()
+    }
+}
+
+/// The two 114 bits bursts (A to B and B to A) of a GSM frame, packed most
+/// significant bit first.
+pub fn bursts(key: &[u8; 8], frame: u32) -> ([u8; 15], [u8; 15]) {
+    let mut a51 = A51::setup(A5_1, key, frame);
+    let mut pack = || {
+        let mut out = [0; 15];
+        for (i, bit) in a51.keystream(114).into_iter().enumerate() {
+            out[i / 8] |= bit << (7 - i % 8);
+        }
+        out
+    };
+    (pack(), pack())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reference_vector() {
+        // From the Briceno, Goldberg and Wagner reference implementation
+        let key = [0x12, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef];
+        let (a_to_b, b_to_a) = bursts(&key, 0x134);
+        assert_eq!(hex(&a_to_b), "534eaa582fe8151ab6e1855a728c00");
+        assert_eq!(hex(&b_to_a), "24fd35a35d5fb6526d32f906df1ac0");
+    }
+
+    #[test]
+    fn state_packing() {
+        let a51 = A51::from_state(TOY, 0x2abcdef);
+        assert_eq!(a51.state, [0x6f, 0x19b, 0x2ab]);
+        assert_eq!(a51.state(), 0x2abcdef);
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+}
@@ -0,0 +1,41 @@
+use a5_1::{attacks::Tmto, bursts, state_bits, A51, TOY};
+use rand::Rng;
+use std::time::Instant;
+
+fn main() {
+    let mut rng = rand::thread_rng();
+
+    let key = [0x12, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef];
+    let (a_to_b, b_to_a) = bursts(&key, 0x134);
+    println!("A5/1 frame 0x134");
+    println!("  A -> B: {}", hex(&a_to_b));
+    println!("  B -> A: {}", hex(&b_to_a));
+
+    let (chains, length) = (8192, 128);
+    println!(
+        "\nTMTO on the {} bits toy registers, {chains} chains of {length} states",
+        state_bits(&TOY)
+    );
+    let start = Instant::now();
+    let tmto = Tmto::precompute(TOY, chains, length, &mut rng);
+    println!("  precomputation: {:?}", start.elapsed());
+
+    let key: [u8; 8] = rng.gen();
+    let frame = rng.gen_range(0..1 << 22);
+    let keystream = A51::setup(TOY, &key, frame).keystream(228);
+    let start = Instant::now();
+    match tmto.recover(&keystream) {
+        Some((offset, state)) => {
+            println!("  state {state:#09x} at offset {offset}");
+            let mut a51 = A51::from_state(TOY, state);
+            let rest: String = a51.keystream(64).iter().map(|b| b.to_string()).collect();
+            println!("  next keystream bits: {rest}");
+        }
+        None => println!("  state not covered by the table"),
+    }
+    println!("  online phase: {:?}", start.elapsed());
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
@@ -0,0 +1,121 @@
+//! Rainbow tables (Oechslin), inverting a function over the `bits` bits
+//! integers.
+//!
+//! A chain starts from a random point and alternates the function `f` with a
+//! reduction back to the domain, a different one for every column. Only the
+//! start and end points are stored: `m` chains of length `t` cover up to
+//! `m·t` points with `m` entries of memory.
+//!
+//! To invert `y`, for every column `c` (from the last one) the chain is
+//! continued from `y` as if it were the output of `f` in column `c`; when its
+//! end is in the table the stored chain is regenerated from its start up to
+//! column `c`. Two chains merge only when they collide in the same column,
+//! but a collision with a chain in another column still gives a false alarm,
+//! discarded by checking the preimage.
+//!
+//! Chains with the same end point are merged and only one is kept.
+
+use rand::Rng;
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// Chains end points to start points.
+#[derive(Debug, Clone)]
+pub struct RainbowTable {
+    bits: u32,
+    length: usize,
+    ends: HashMap<u64, u64>,
+}
+
+impl RainbowTable {
+    /// Precomputes `chains` chains of `length` evaluations of `f`.
+    pub fn build<F, R>(f: &F, bits: u32, chains: usize, length: usize, rng: &mut R) -> Self
+    where
+        F: Fn(u64) -> u64 + Sync,
+        R: Rng + ?Sized,
+    {
+        let mask = mask(bits);
+        let starts: Vec<u64> = (0..chains).map(|_| rng.gen::<u64>() & mask).collect();
+        let mut table = RainbowTable {
+            bits,
+            length,
+            ends: HashMap::with_capacity(chains),
+        };
+        let ends: Vec<u64> = starts
+            .par_iter()
+            .map(|&start| table.walk(f, start, 0, length))
+            .collect();
+        table.ends = ends.into_iter().zip(starts).collect();
+        table
+    }
+
+    /// Number of stored chains.
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// `true` if no chain is stored.
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    /// Preimage of `y` under `f`, if covered by the table.
+    pub fn lookup(&self, f: impl Fn(u64) -> u64, y: u64) -> Option<u64> {
+        let y = y & mask(self.bits);
+        (0..self.length).rev().find_map(|c| {
+            // y in column c, reduced and walked to the end
+            let end = self.walk(&f, self.reduce(y, c), c + 1, self.length);
+            let start = *self.ends.get(&end)?;
+            let x = self.walk(&f, start, 0, c);
+            (f(x) & mask(self.bits) == y).then_some(x)
+        })
+    }
+
+    // Chain from the point `x` at column `from`, up to the point at column `to`.
+    fn walk(&self, f: impl Fn(u64) -> u64, x: u64, from: usize, to: usize) -> u64 {
+        (from..to).fold(x, |x, c| self.reduce(f(x), c))
+    }
+
+    // Reduction function of column `c`.
+    fn reduce(&self, y: u64, c: usize) -> u64 {
+        (y ^ (c as u64 + 1).wrapping_mul(0x9e3779b97f4a7c15)) & mask(self.bits)
+    }
+}
+
+fn mask(bits: u32) -> u64 {
+    (1 << bits) - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // A random looking (not injective) function on 20 bits
+    fn f(x: u64) -> u64 {
+        let x = x.wrapping_mul(0xff51afd7ed558ccd);
+        (x ^ (x >> 29)) & 0xfffff
+    }
+
+    #[test]
+    fn inverts_covered_points() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let table = RainbowTable::build(&f, 20, 4096, 256, &mut rng);
+        // Chains merging in the last columns are dropped
+        assert!(table.len() > 2000, "{}", table.len());
+        let found = (0..200)
+            .filter(|_| {
+                let x = rng.gen::<u64>() & 0xfffff;
+                match table.lookup(f, f(x)) {
+                    Some(x1) => {
+                        assert_eq!(f(x1), f(x));
+                        true
+                    }
+                    None => false,
+                }
+            })
+            .count();
+        // About 1 - e^(-m·t/N) ≈ 60% in theory, less for the merges
+        assert!(found > 80, "{found}");
+    }
+}
@@ -0,0 +1,177 @@
+//! Instrumentable table based AES-128.
+//!
+//! Classic "T-table" software implementation where `SubBytes`, `ShiftRows` and
+//! `MixColumns` are merged into four 1KB lookup tables. Each table lookup is
+//! reported to a [`Probe`], allowing to model the side effects of secret
+//! dependent memory accesses (e.g. cache timings).
+//!
+//! **Do not use this for anything real.** It is deliberately not constant time.
+
+/// AES S-box.
+pub const SBOX: [u8; 256] = [
+    0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
+    0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
+    0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
+    0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2, 0xeb, 0x27, 0xb2, 0x75,
+    0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6, 0xb3, 0x29, 0xe3, 0x2f, 0x84,
+    0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb, 0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf,
+    0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45, 0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8,
+    0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5, 0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2,
+    0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44, 0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73,
+    0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a, 0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb,
+    0xe0, 0x32, 0x3a, 0x0a, 0x49, 0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79,
+    0xe7, 0xc8, 0x37, 0x6d, 0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08,
+    0xba, 0x78, 0x25, 0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a,
+    0x70, 0x3e, 0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e,
+    0xe1, 0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+    0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb, 0x16,
+];
+
+const RCON: [u8; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+/// Number of lookup tables. Tables `0..4` are the T-tables, table `4` is the
+/// S-box used by the last round.
+pub const TABLES: usize = 5;
+
+/// Table lookups observer.
+pub trait Probe {
+    /// Called on every table lookup.
+    ///
+    /// Params:
+    /// * `round`: encryption round (`1..=10`).
+    /// * `table`: lookup table (`0..TABLES`).
+    /// * `index`: looked up table entry.
+    fn lookup(&mut self, round: usize, table: usize, index: u8);
+}
+
+/// Probe which ignores everything.
+pub struct NoProbe;
+
+impl Probe for NoProbe {
+    fn lookup(&mut self, _round: usize, _table: usize, _index: u8) {}
+}
+
+fn xtime(x: u8) -> u8 {
+    (x << 1) ^ if x & 0x80 != 0 { 0x1b } else { 0 }
+}
+
+/// AES-128 with precomputed T-tables and expanded key.
+#[derive(Clone)]
+pub struct Aes128 {
+    te: [[u32; 256]; 4],
+    rk: [u32; 44],
+}
+
+impl Aes128 {
+    pub fn new(key: &[u8; 16]) -> Self {
+        let mut te = [[0; 256]; 4];
+        for x in 0..256 {
+            let s = SBOX[x];
+            let s2 = xtime(s);
+            let s3 = s2 ^ s;
+            let t = u32::from_be_bytes([s2, s, s, s3]);
+            for (i, table) in te.iter_mut().enumerate() {
+                table[x] = t.rotate_right(8 * i as u32);
+            }
+        }
+        Aes128 {
+            te,
+            rk: expand_key(key),
+        }
+    }
+
+    /// Encrypt a single block, reporting every table lookup to `probe`.
+    pub fn encrypt_block<P: Probe>(&self, block: &[u8; 16], probe: &mut P) -> [u8; 16] {
+        let rk = &self.rk;
+        let mut s = [0_u32; 4];
+        for (i, w) in s.iter_mut().enumerate() {
+            *w = u32::from_be_bytes(block[4 * i..4 * i + 4].try_into().unwrap()) ^ rk[i];
+        }
+
+        for round in 1..10 {
+            let mut t = [0_u32; 4];
+            for (i, w) in t.iter_mut().enumerate() {
+                let idx = [
+                    (s[i] >> 24) as u8,
+                    (s[(i + 1) % 4] >> 16) as u8,
+                    (s[(i + 2) % 4] >> 8) as u8,
+                    s[(i + 3) % 4] as u8,
+                ];
+                *w = rk[4 * round + i];
+                for (table, &index) in idx.iter().enumerate() {
+                    probe.lookup(round, table, index);
+                    *w ^= self.te[table][index as usize];
+                }
+            }
+            s = t;
+        }
+
+        let mut out = [0; 16];
+        for i in 0..4 {
+            let idx = [
+                (s[i] >> 24) as u8,
+                (s[(i + 1) % 4] >> 16) as u8,
+                (s[(i + 2) % 4] >> 8) as u8,
+                s[(i + 3) % 4] as u8,
+            ];
+            let mut bytes = [0; 4];
+            for (b, &index) in bytes.iter_mut().zip(idx.iter()) {
+                probe.lookup(10, 4, index);
+                *b = SBOX[index as usize];
+            }
+            let w = u32::from_be_bytes(bytes) ^ rk[40 + i];
+            out[4 * i..4 * i + 4].copy_from_slice(&w.to_be_bytes());
+        }
+        out
+    }
+
+    /// Encrypt a single block without any probe.
+    pub fn encrypt(&self, block: &[u8; 16]) -> [u8; 16] {
+        self.encrypt_block(block, &mut NoProbe)
+    }
+}
+
+fn sub_word(w: u32) -> u32 {
+    let b = w.to_be_bytes();
+    u32::from_be_bytes([
+        SBOX[b[0] as usize],
+        SBOX[b[1] as usize],
+        SBOX[b[2] as usize],
+        SBOX[b[3] as usize],
+    ])
+}
+
+/// AES-128 key schedule.
+pub fn expand_key(key: &[u8; 16]) -> [u32; 44] {
+    let mut w = [0; 44];
+    for i in 0..4 {
+        w[i] = u32::from_be_bytes(key[4 * i..4 * i + 4].try_into().unwrap());
+    }
+    for i in 4..44 {
+        let mut t = w[i - 1];
+        if i % 4 == 0 {
+            t = sub_word(t.rotate_left(8)) ^ ((RCON[i / 4 - 1] as u32) << 24);
+        }
+        w[i] = w[i - 4] ^ t;
+    }
+    w
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fips197_test_vector() {
+        let key: [u8; 16] = hex::decode("000102030405060708090a0b0c0d0e0f")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let pt: [u8; 16] = hex::decode("00112233445566778899aabbccddeeff")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let ct = Aes128::new(&key).encrypt(&pt);
+        assert_eq!(hex::encode(ct), "69c4e0d86a7b0430d8cdb78070b4c55a");
+    }
+}
@@ -0,0 +1,289 @@
+//! Cache timing attack against table based AES.
+//!
+//! Reproduction of the classic Bernstein attack (*Cache-timing attacks on AES*,
+//! 2005) carried over a simulated cache.
+//!
+//! In the first round a T-table implementation looks up the entries with index
+//! `x[i] = p[i] ⊕ k[i]`. If the latency of a lookup depends on the accessed
+//! entry (cache line evicted by other activity, bank conflicts, ...) then the
+//! whole encryption time is slightly correlated with `x[i]`. That is, the timing
+//! profile of the byte `p[i]` is a function `f(p[i] ⊕ k[i])`.
+//!
+//! The attacker:
+//! 1. profiles an identical machine using a known key `k'`, collecting the
+//!    average encryption time for each plaintext byte position and value;
+//! 2. collects the same statistics from the victim (unknown key);
+//! 3. for each byte position, correlates the two profiles for every candidate
+//!    `k[i]`. The profiles align when `p ⊕ k[i] = p' ⊕ k'[i]`.
+//!
+//! With a pure cache line model only the line index (i.e. the high bits of
+//! `x[i]`) leaks, thus only the high nibble of each key byte is recovered.
+//! Per-entry latency differences leak the full byte.
+
+pub mod aes;
+
+use aes::{Aes128, Probe, TABLES};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use rand_distr::{Distribution, Normal};
+
+/// Number of entries sharing the same cache line for a T-table (64-byte line,
+/// 4-byte entries).
+pub const T_ENTRIES_PER_LINE: usize = 16;
+
+/// Simulated cache latency model.
+///
+/// A lookup of a table entry costs `hit` cycles, plus `miss_penalty` cycles
+/// with probability equal to its cache line eviction probability (i.e. the
+/// line was evicted by concurrent activity). Each entry also carries a fixed
+/// small latency offset (`entry_jitter`), and each encryption suffers Gaussian
+/// measurement noise.
+#[derive(Clone)]
+pub struct CacheModel {
+    pub hit: f64,
+    pub miss_penalty: f64,
+    /// Eviction probability for each table line.
+    pub evict: Vec<Vec<f64>>,
+    /// Fixed latency offset for each table entry.
+    pub entry_jitter: Vec<Vec<f64>>,
+    /// Measurement noise standard deviation.
+    pub noise: f64,
+}
+
+impl CacheModel {
+    /// Random machine model derived from `seed`.
+    ///
+    /// Params:
+    /// * `max_jitter`: max per entry latency offset (`0` to model line
+    ///   granularity only).
+    /// * `noise`: measurement noise standard deviation.
+    pub fn new(seed: u64, max_jitter: f64, noise: f64) -> Self {
+        let mut rng = ChaCha20Rng::seed_from_u64(seed);
+        let evict = (0..TABLES)
+            .map(|t| {
+                let lines = if t < 4 { 256 / T_ENTRIES_PER_LINE } else { 4 };
+                // Some lines are contended by concurrent activity, thus are
+                // frequently evicted, while others are mostly cached.
+                (0..lines)
+                    .map(|_| if rng.gen_bool(0.25) { 0.9 } else { 0.02 })
+                    .collect()
+            })
+            .collect();
+        let entry_jitter = (0..TABLES)
+            .map(|_| (0..256).map(|_| rng.gen_range(0.0..=max_jitter)).collect())
+            .collect();
+        CacheModel {
+            hit: 3.0,
+            miss_penalty: 40.0,
+            evict,
+            entry_jitter,
+            noise,
+        }
+    }
+
+    fn line(table: usize, index: u8) -> usize {
+        // T-tables have 4 bytes entries, the S-box has 1 byte entries.
+        let entries_per_line = if table < 4 { T_ENTRIES_PER_LINE } else { 64 };
+        index as usize / entries_per_line
+    }
+}
+
+// Accumulates the simulated latency of each table lookup.
+struct TimingProbe<'a, R: Rng> {
+    model: &'a CacheModel,
+    rng: &'a mut R,
+    cycles: f64,
+}
+
+impl<R: Rng> Probe for TimingProbe<'_, R> {
+    fn lookup(&mut self, _round: usize, table: usize, index: u8) {
+        let model = self.model;
+        self.cycles += model.hit + model.entry_jitter[table][index as usize];
+        if self
+            .rng
+            .gen_bool(model.evict[table][CacheModel::line(table, index)])
+        {
+            self.cycles += model.miss_penalty;
+        }
+    }
+}
+
+/// Device running table based AES on the simulated cache.
+pub struct Device {
+    aes: Aes128,
+    model: CacheModel,
+    rng: ChaCha20Rng,
+    noise: Normal<f64>,
+}
+
+impl Device {
+    pub fn new(key: &[u8; 16], model: CacheModel, seed: u64) -> Self {
+        let noise = Normal::new(0.0, model.noise).unwrap();
+        Device {
+            aes: Aes128::new(key),
+            model,
+            rng: ChaCha20Rng::seed_from_u64(seed),
+            noise,
+        }
+    }
+
+    /// Encrypt `block` and return the ciphertext with the measured time.
+    pub fn encrypt(&mut self, block: &[u8; 16]) -> ([u8; 16], f64) {
+        let mut probe = TimingProbe {
+            model: &self.model,
+            rng: &mut self.rng,
+            cycles: 0.0,
+        };
+        let ct = self.aes.encrypt_block(block, &mut probe);
+        let cycles = probe.cycles + self.noise.sample(&mut self.rng);
+        (ct, cycles)
+    }
+}
+
+/// Timing profile.
+///
+/// Average encryption time for each plaintext byte position and value.
+pub struct Profile {
+    sum: Vec<[f64; 256]>,
+    count: Vec<[u64; 256]>,
+    total: f64,
+    samples: u64,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Profile {
+            sum: vec![[0.0; 256]; 16],
+            count: vec![[0; 256]; 16],
+            total: 0.0,
+            samples: 0,
+        }
+    }
+}
+
+impl Profile {
+    /// Collect `samples` timings of random plaintexts encrypted by `device`.
+    pub fn collect<R: Rng>(device: &mut Device, rng: &mut R, samples: usize) -> Self {
+        let mut profile = Profile::default();
+        let mut block = [0; 16];
+        for _ in 0..samples {
+            rng.fill(&mut block);
+            let (_, time) = device.encrypt(&block);
+            profile.record(&block, time);
+        }
+        profile
+    }
+
+    /// Record a timing sample.
+    pub fn record(&mut self, block: &[u8; 16], time: f64) {
+        for (i, &b) in block.iter().enumerate() {
+            self.sum[i][b as usize] += time;
+            self.count[i][b as usize] += 1;
+        }
+        self.total += time;
+        self.samples += 1;
+    }
+
+    /// Deviations of the per-value average times from the global average.
+    pub fn deviations(&self) -> Vec<[f64; 256]> {
+        let avg = self.total / self.samples.max(1) as f64;
+        self.sum
+            .iter()
+            .zip(self.count.iter())
+            .map(|(sum, count)| {
+                let mut dev = [0.0; 256];
+                for b in 0..256 {
+                    if count[b] != 0 {
+                        dev[b] = sum[b] / count[b] as f64 - avg;
+                    }
+                }
+                dev
+            })
+            .collect()
+    }
+}
+
+/// Recover the key by correlating the `target` profile with a `reference`
+/// profile collected using the known `reference_key`.
+///
+/// Returns, for each key byte, the candidates sorted by decreasing correlation.
+pub fn correlate(
+    reference: &Profile,
+    reference_key: &[u8; 16],
+    target: &Profile,
+) -> Vec<Vec<(u8, f64)>> {
+    let ref_dev = reference.deviations();
+    let tgt_dev = target.deviations();
+
+    (0..16)
+        .map(|i| {
+            let mut candidates: Vec<_> = (0..=255_u8)
+                .map(|k| {
+                    // Target value p aligns with the reference value p' such that
+                    // p ⊕ k = p' ⊕ k' → p' = p ⊕ k ⊕ k'
+                    let corr = (0..256)
+                        .map(|p| {
+                            let p1 = p ^ (k ^ reference_key[i]) as usize;
+                            tgt_dev[i][p] * ref_dev[i][p1]
+                        })
+                        .sum::<f64>();
+                    (k, corr)
+                })
+                .collect();
+            candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+            candidates
+        })
+        .collect()
+}
+
+/// Most likely key given the candidates returned by `correlate`.
+pub fn best_key(candidates: &[Vec<(u8, f64)>]) -> [u8; 16] {
+    let mut key = [0; 16];
+    key.iter_mut()
+        .zip(candidates)
+        .for_each(|(k, c)| *k = c[0].0);
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(max_jitter: f64, samples: usize) -> ([u8; 16], [u8; 16]) {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        let model = CacheModel::new(1, max_jitter, 5.0);
+
+        let ref_key = [0; 16];
+        let mut reference = Device::new(&ref_key, model.clone(), 2);
+        let reference = Profile::collect(&mut reference, &mut rng, samples);
+
+        let key: [u8; 16] = rng.gen();
+        let mut victim = Device::new(&key, model, 3);
+        let target = Profile::collect(&mut victim, &mut rng, samples);
+
+        let recovered = best_key(&correlate(&reference, &ref_key, &target));
+        (key, recovered)
+    }
+
+    #[test]
+    fn recover_key_high_nibbles() {
+        let (key, recovered) = run(0.0, 1 << 16);
+        let ok = key
+            .iter()
+            .zip(recovered.iter())
+            .filter(|(a, b)| *a >> 4 == *b >> 4)
+            .count();
+        assert!(ok >= 14, "recovered {ok}/16 nibbles");
+    }
+
+    #[test]
+    fn recover_full_key() {
+        let (key, recovered) = run(32.0, 1 << 17);
+        let ok = key
+            .iter()
+            .zip(recovered.iter())
+            .filter(|(a, b)| a == b)
+            .count();
+        assert!(ok >= 14, "recovered {ok}/16 bytes");
+    }
+}
@@ -0,0 +1,36 @@
+use aes_cache_timing::{best_key, correlate, CacheModel, Device, Profile};
+use rand::{rngs::OsRng, Rng};
+
+fn main() {
+    let samples = 1 << 20;
+
+    // Same model for the attacker's and victim's machines.
+    // Use `max_jitter = 0.0` to see what leaks with pure cache line granularity.
+    let model = CacheModel::new(OsRng.gen(), 32.0, 20.0);
+
+    // Attacker profiles its own machine with a known key
+    let ref_key = [0; 16];
+    let mut reference = Device::new(&ref_key, model.clone(), OsRng.gen());
+    println!("Profiling reference machine ({samples} samples)");
+    let reference = Profile::collect(&mut reference, &mut OsRng, samples);
+
+    // Victim
+    let key: [u8; 16] = OsRng.gen();
+    let mut victim = Device::new(&key, model, OsRng.gen());
+    println!("Profiling victim machine ({samples} samples)");
+    let target = Profile::collect(&mut victim, &mut OsRng, samples);
+
+    let candidates = correlate(&reference, &ref_key, &target);
+    let recovered = best_key(&candidates);
+
+    println!("secret    : {}", hex::encode(key));
+    println!("recovered : {}", hex::encode(recovered));
+
+    for (i, c) in candidates.iter().enumerate() {
+        let rank = c.iter().position(|(k, _)| *k == key[i]).unwrap();
+        println!(
+            "byte {i:2}: best {:02x}, real {:02x} (rank {rank})",
+            c[0].0, key[i]
+        );
+    }
+}
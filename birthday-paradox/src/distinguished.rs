@@ -0,0 +1,148 @@
+//! Parallel collision search with distinguished points (van Oorschot–Wiener).
+//!
+//! Every worker repeatedly walks a trail x, f(x), f(f(x)), ... from a fresh
+//! start until it hits a *distinguished point*, i.e. a value with the masked
+//! low bits all zero, and records only that point with the trail start and
+//! length in a table shared by all the workers.
+//!
+//! Once two trails merge they reach the same distinguished point, so a table
+//! hit reveals a collision which is reconstructed by walking again the two
+//! trails. With θ the distinguished points fraction, memory is reduced by a
+//! factor θ and only ≈ 1/θ evaluations are wasted after the merge. Workers
+//! share nothing but the table, thus the speedup is linear in their number.
+
+use crate::Collision;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+// Distinguished point trail
+#[derive(Clone, Copy)]
+struct Trail {
+    start: u64,
+    len: u64,
+}
+
+/// Distinguished points collision search.
+///
+/// Runs `threads` workers iterating `f`. A value `x` is distinguished if
+/// `x & mask == 0`. Trails longer than 20 times the expected length are
+/// abandoned, to not get stuck in cycles without distinguished points.
+///
+/// The returned count is the total number of evaluations across all the
+/// workers. Never terminates if `f` has no collisions.
+pub fn find_collision<F>(f: F, threads: usize, mask: u64) -> Collision<u64>
+where
+    F: Fn(&u64) -> u64 + Sync,
+{
+    let max_len = 20 * (mask + 1);
+    let seeds = AtomicU64::new(0);
+    let count = AtomicUsize::new(0);
+    let done = AtomicBool::new(false);
+    let found = Mutex::new(None);
+    let table = Mutex::new(HashMap::new());
+
+    let worker = || {
+        while !done.load(Ordering::Relaxed) {
+            let start = f(&seeds.fetch_add(1, Ordering::Relaxed));
+            let (mut x, mut len) = (start, 0);
+            while x & mask != 0 && len < max_len {
+                x = f(&x);
+                len += 1;
+            }
+            count.fetch_add(len as usize + 1, Ordering::Relaxed);
+            if x & mask != 0 {
+                continue;
+            }
+            let trail = Trail { start, len };
+            let Some(other) = table.lock().unwrap().insert(x, trail) else {
+                continue;
+            };
+            let (collision, evals) = merge(&f, trail, other);
+            count.fetch_add(evals, Ordering::Relaxed);
+            if let Some(collision) = collision {
+                found.lock().unwrap().get_or_insert(collision);
+                done.store(true, Ordering::Relaxed);
+            }
+        }
+    };
+    thread::scope(|s| {
+        for _ in 0..threads.max(1) {
+            s.spawn(worker);
+        }
+    });
+
+    let (a, b) = found.into_inner().unwrap().unwrap();
+    Collision {
+        a,
+        b,
+        count: count.into_inner(),
+    }
+}
+
+// Walk two trails reaching the same distinguished point up to the merge.
+//
+// Returns the two colliding values, if any, and the number of evaluations.
+// There are no collisions if one trail starts on the other one.
+fn merge<F>(f: &F, mut t1: Trail, mut t2: Trail) -> (Option<(u64, u64)>, usize)
+where
+    F: Fn(&u64) -> u64,
+{
+    if t1.len < t2.len {
+        (t1, t2) = (t2, t1);
+    }
+    let (mut a, mut b) = (t1.start, t2.start);
+    let mut evals = 0;
+    for _ in 0..t1.len - t2.len {
+        a = f(&a);
+        evals += 1;
+    }
+    if a == b {
+        return (None, evals);
+    }
+    loop {
+        let (fa, fb) = (f(&a), f(&b));
+        evals += 2;
+        if fa == fb {
+            return (Some((a, b)), evals);
+        }
+        (a, b) = (fa, fb);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::{Digest, Sha256};
+
+    fn h(x: &u64) -> u64 {
+        let d = Sha256::digest(x.to_le_bytes());
+        u32::from_le_bytes([d[0], d[1], d[2], d[3]]) as u64
+    }
+
+    #[test]
+    fn truncated_hash_collision() {
+        for threads in [1, 3] {
+            let c = find_collision(h, threads, 0xff);
+            assert_ne!(c.a, c.b);
+            assert_eq!(h(&c.a), h(&c.b));
+            // Expected after ≈ 2^16 + 2^8 evaluations per thread
+            assert!(c.count < 1 << 20);
+        }
+    }
+
+    #[test]
+    fn merged_trails() {
+        let f = |x: &u64| x / 2;
+        let t1 = Trail { start: 12, len: 2 };
+        let t2 = Trail { start: 7, len: 1 };
+        assert_eq!(merge(&f, t1, t2), (Some((6, 7)), 3));
+        let t2 = Trail { start: 6, len: 1 };
+        assert_eq!(merge(&f, t1, t2), (None, 1));
+    }
+}
@@ -0,0 +1,176 @@
+//! Birthday paradox PoC
+//!
+//! A collision should be found after ≈ √|D| extractions, with D the set from
+//! where the values are (uniformly) randomly fetched.
+//!
+//! The PoC is given using two separate methods to generate (pseudo)-random
+//! values:
+//! - using a subset of the bytes produced by applying SHA2 to a counter
+//! - using the `rand::thread_rng`, a thread-local random number generator
+//!   seeded by the system.
+//!
+//! The search itself is performed by the generic [`find_collision`] engine,
+//! reusable to attack any function with a small enough output space.
+//!
+//! The engine memory grows as √|D| too, which quickly becomes the bottleneck.
+//! When the function maps its output space into itself, [`find_collision_rho`]
+//! finds a collision with constant memory by walking the sequence x, f(x),
+//! f(f(x)), ... which, D being finite, eventually enters a cycle: the two
+//! distinct predecessors of the cycle entry point collide. The walk is shaped
+//! as the letter ρ, thus the name (Pollard's rho).
+//!
+//! The [`distinguished`] module parallelizes the memoryless search over many
+//! threads using the van Oorschot–Wiener distinguished points method.
+//!
+//! The [`search::CollisionSearch`] builder runs any of the engines against a
+//! truncated hash, for any hash function and truncation policy, while
+//! [`trials::Trials`] repeats it to compare the distribution of the collision
+//! cost against the theory.
+//!
+//! The [`meaningful`] module turns the abstract collision into the classic
+//! fraud: a benign and a malicious document with the same (truncated) hash.
+//!
+//! For some background see https://datawok.net/posts/birthday-paradox
+
+use std::{collections::HashMap, hash::Hash};
+
+pub mod distinguished;
+pub mod meaningful;
+pub mod search;
+pub mod trials;
+
+/// Collision found by [`find_collision`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Collision<T> {
+    /// First input.
+    pub a: T,
+    /// Second input (found later).
+    pub b: T,
+    /// Number of evaluated inputs.
+    pub count: usize,
+}
+
+/// Birthday collision search engine.
+///
+/// Evaluates `f` over the `inputs`, storing the outputs in a hash table, until
+/// two inputs with the same output are found. Inputs are assumed to be
+/// distinct.
+///
+/// Memory is linear in the number of evaluations, which is expected to be
+/// ≈ √|D|, with D the `f` output space.
+pub fn find_collision<I, T, K, F>(inputs: I, mut f: F) -> Option<Collision<T>>
+where
+    I: IntoIterator<Item = T>,
+    K: Hash + Eq,
+    F: FnMut(&T) -> K,
+{
+    let mut seen = HashMap::new();
+    for (i, x) in inputs.into_iter().enumerate() {
+        let key = f(&x);
+        if let Some(a) = seen.insert(key, x) {
+            // Restore the first input, the map now holds the second one.
+            let key = f(&a);
+            let b = seen.remove(&key).unwrap();
+            return Some(Collision { a, b, count: i + 1 });
+        }
+    }
+    None
+}
+
+/// Memoryless collision search engine.
+///
+/// Iterates `f` from `start` and finds the sequence cycle using Brent's cycle
+/// detection, then locates the cycle entry point. Only a couple of values are
+/// kept in memory, at the cost of ≈ 3√|D| evaluations on average.
+///
+/// Returns `None` if `start` lies on the cycle, in which case there is no
+/// entry point: retry with another start. Never terminates if `f` never
+/// cycles (e.g. infinite output space).
+pub fn find_collision_rho<T, F>(start: T, mut f: F) -> Option<Collision<T>>
+where
+    T: Clone + Eq,
+    F: FnMut(&T) -> T,
+{
+    let mut count = 0;
+    let mut f = |x: &T| {
+        count += 1;
+        f(x)
+    };
+
+    // Cycle length (lambda): the tortoise waits at power of two positions
+    let (mut power, mut lambda) = (1_usize, 1_usize);
+    let mut tortoise = start.clone();
+    let mut hare = f(&start);
+    while tortoise != hare {
+        if power == lambda {
+            tortoise = hare.clone();
+            power *= 2;
+            lambda = 0;
+        }
+        hare = f(&hare);
+        lambda += 1;
+    }
+
+    // Cycle entry point (mu): pointers lambda apart meet at the entry point
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..lambda {
+        hare = f(&hare);
+    }
+    if tortoise == hare {
+        return None;
+    }
+    loop {
+        let (t, h) = (f(&tortoise), f(&hare));
+        if t == h {
+            return Some(Collision {
+                a: tortoise,
+                b: hare,
+                count,
+            });
+        }
+        (tortoise, hare) = (t, h);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncated_hash_collision() {
+        use sha2::{Digest, Sha256};
+        let h = |x: &u64| Sha256::digest(x.to_le_bytes())[..3].to_vec();
+        let c = find_collision(0_u64.., h).unwrap();
+        assert_ne!(c.a, c.b);
+        assert_eq!(h(&c.a), h(&c.b));
+        // Expected after ≈ 2^12 attempts
+        assert!(c.count < 1 << 16);
+    }
+
+    #[test]
+    fn truncated_hash_rho_collision() {
+        use sha2::{Digest, Sha256};
+        let h = |x: &u64| {
+            let d = Sha256::digest(x.to_le_bytes());
+            u32::from_le_bytes([d[0], d[1], d[2], d[3]]) as u64
+        };
+        let c = (0..)
+            .find_map(|start| find_collision_rho(start, h))
+            .unwrap();
+        assert_ne!(c.a, c.b);
+        assert_eq!(h(&c.a), h(&c.b));
+        // Expected after ≈ 3 * 2^16 evaluations
+        assert!(c.count < 1 << 20);
+    }
+
+    #[test]
+    fn no_rho_collision_in_permutation() {
+        assert!(find_collision_rho(0_u32, |x| (x + 7) % 1000).is_none());
+    }
+
+    #[test]
+    fn no_collision_in_injective_function() {
+        assert!(find_collision(0..1000_u32, |x| *x).is_none());
+    }
+}
@@ -1,79 +1,186 @@
-//! Birthday paradox PoC
-//!
-//! A collision should be found after ≈ √|D| extractions, with D the set from
-//! where the values are (uniformly) randomly fetched.
-//!
-//! The PoC is given using two separate methods to generate (pseudo)-random
-//! values:
-//! - using a subset of the bytes produced by applying SHA2 to a counter
-//! - using the `rand::thread_rng`, a thread-local random number generator
-//!   seeded by the system.
-//!
-//! For some background see https://datawok.net/posts/birthday-paradox
-
+use birthday_paradox::{
+    find_collision,
+    meaningful::{forge, Template},
+    search::{CollisionSearch, Strategy, Truncation},
+    trials::Trials,
+};
+use clap::{Parser, Subcommand};
+use digest::Digest;
 use rand::Rng;
 use rand::RngCore;
-use sha2::{Digest, Sha256};
-use std::collections::{HashMap, HashSet};
+use sha2::Sha256;
+use std::{fs::File, time::Instant};
 
-pub fn sub_sha_collisions(num_bytes: usize) {
+fn sub_hash_collisions<D: Digest>(search: &CollisionSearch<D>, num_bytes: usize) {
+    let start = Instant::now();
+    let c = search.run().unwrap();
+    println!(
+        "Collision after {:?} hashes, {:.1}s",
+        c.count,
+        start.elapsed().as_secs_f64()
+    );
+    for x in [c.a, c.b] {
+        let digest = D::digest(x.to_le_bytes());
+        println!(
+            "{}-{} = H({})",
+            hex::encode(&digest[..num_bytes]),
+            hex::encode(&digest[num_bytes..]),
+            x,
+        );
+    }
+}
+
+fn scaling<D: Digest>(name: &str) {
     let mut rng = rand::thread_rng();
-    let mut set = HashMap::new();
-    let set_size = 1_u128 << (num_bytes * 8);
-    let mut curr = rng.gen_range(0..set_size);
-
-    let sha256 = |data, len| {
-        let mut hasher = Sha256::new();
-        hasher.update(data);
-        let res = hasher.finalize();
-        res[..len].to_vec()
+    let truncations = [
+        Truncation::Leading(3),
+        Truncation::Trailing(3),
+        // Every other bit of the first 6 bytes
+        Truncation::Mask(vec![0x55; 6]),
+        Truncation::Leading(4),
+    ];
+    for truncation in truncations {
+        let search = CollisionSearch::<D>::new()
+            .truncation(truncation.clone())
+            .seed(rng.gen());
+        let c = search.run().unwrap();
+        println!(
+            "  {name:<8} {:<18} {:>7} hashes (expected {:.0})",
+            truncation.to_string(),
+            c.count,
+            search.expected()
+        );
+    }
+}
+
+#[derive(Parser)]
+#[command(about = "Birthday paradox PoC")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Single collision searches with different engines and hash functions.
+    Demo,
+    /// Repeat the search and compare the distribution against the theory.
+    Trials {
+        /// Number of searches.
+        #[arg(short, long, default_value_t = 1000)]
+        trials: usize,
+        /// Leading SHA-256 bytes kept.
+        #[arg(short, long, default_value_t = 3)]
+        bytes: usize,
+        /// Save the histogram as CSV.
+        #[arg(long)]
+        csv: Option<String>,
+        /// Histogram bins.
+        #[arg(long, default_value_t = 20)]
+        bins: usize,
+    },
+    /// Forge a benign and a malicious document with the same truncated hash.
+    Forge {
+        /// Leading SHA-256 bytes kept.
+        #[arg(short, long, default_value_t = 4)]
+        bytes: usize,
+        /// Benign document template, with `{a|b}` alternatives.
+        #[arg(long)]
+        benign: Option<String>,
+        /// Malicious document template, with `{a|b}` alternatives.
+        #[arg(long)]
+        malicious: Option<String>,
+    },
+}
+
+fn meaningful(num_bytes: usize, benign: Option<String>, malicious: Option<String>) {
+    let load = |path: Option<String>, default: &str| {
+        Template::parse(&path.map_or(default.to_string(), |p| std::fs::read_to_string(p).unwrap()))
     };
+    let benign = load(benign, include_str!("benign.txt"));
+    let malicious = load(malicious, include_str!("malicious.txt"));
+    println!(
+        "Variants: {} benign, {} malicious, expected hashes ≈ {}",
+        benign.variants(),
+        malicious.variants(),
+        1_u64 << (num_bytes * 4 + 1)
+    );
+    let truncation = Truncation::Leading(num_bytes);
+    let Some(forgery) = forge::<Sha256>(&benign, &malicious, &truncation) else {
+        println!("Not enough variants");
+        return;
+    };
+    println!("Collision after {} hashes", forgery.count);
+    for text in [forgery.benign, forgery.malicious] {
+        let digest = Sha256::digest(text.as_bytes());
+        println!(
+            "\n{}-{}",
+            hex::encode(&digest[..num_bytes]),
+            hex::encode(&digest[num_bytes..])
+        );
+        print!("{text}");
+    }
+}
 
-    let mut count = 0;
-    loop {
-        count += 1;
-        curr = curr.wrapping_add(1);
-
-        let hash = sha256(curr.to_le_bytes(), num_bytes);
-        if let Some(old) = set.insert(hash, curr) {
-            println!("Collision after {:?} hashes", count);
-            let old_sha = sha256(old.to_le_bytes(), 32);
-            let new_sha = sha256(curr.to_le_bytes(), 32);
-            println!(
-                "{}-{} = H({})",
-                hex::encode(&old_sha[..num_bytes]),
-                hex::encode(&old_sha[num_bytes..]),
-                old,
-            );
-            println!(
-                "{}-{} = H({})",
-                hex::encode(&new_sha[..num_bytes]),
-                hex::encode(&new_sha[num_bytes..]),
-                curr,
-            );
-            break;
-        }
+fn trials(n: usize, num_bytes: usize, csv: Option<String>, bins: usize) {
+    let search = CollisionSearch::<Sha256>::new().truncation(Truncation::Leading(num_bytes));
+    let trials = Trials::run(&search, n, &mut rand::thread_rng());
+    println!("Search set size: {}", trials.space);
+    println!(
+        "Mean:   {:.1} (expected √(π·|D|/2) = {:.1})",
+        trials.mean(),
+        trials.expected_mean()
+    );
+    println!(
+        "Median: {:.1} (expected √(2·|D|·ln 2) = {:.1})",
+        trials.median(),
+        trials.expected_median()
+    );
+    println!(
+        "{:>8} {:>8} {:>8} {:>10}",
+        "from", "to", "count", "expected"
+    );
+    for (lo, hi, count, expected) in trials.histogram(bins) {
+        println!("{lo:>8} {hi:>8} {count:>8} {expected:>10.1}");
+    }
+    if let Some(path) = csv {
+        trials
+            .write_csv(File::create(&path).unwrap(), bins)
+            .unwrap();
+        println!("Histogram saved to {path}");
     }
 }
 
-pub fn os_rand_collisions(num_bytes: usize) {
-    let mut set = HashSet::new();
+fn os_rand_collisions(num_bytes: usize) {
     let mut rng = rand::thread_rng();
-    let mut buf = vec![0u8; num_bytes];
-
-    let mut count = 0;
-    loop {
-        count += 1;
+    let inputs = (0_usize..).map(|_| {
+        let mut buf = vec![0u8; num_bytes];
         rng.fill_bytes(&mut buf);
-        if !set.insert(buf.clone()) {
-            println!("Collision after {:?} extractions", count);
-            println!("Value: {}", hex::encode(&buf));
-            break;
-        }
-    }
+        buf
+    });
+    let c = find_collision(inputs, |buf| buf.clone()).unwrap();
+    println!("Collision after {:?} extractions", c.count);
+    println!("Value: {}", hex::encode(&c.b));
 }
 
 fn main() {
+    match Cli::parse().command.unwrap_or(Command::Demo) {
+        Command::Demo => demo(),
+        Command::Trials {
+            trials: n,
+            bytes,
+            csv,
+            bins,
+        } => trials(n, bytes, csv, bins),
+        Command::Forge {
+            bytes,
+            benign,
+            malicious,
+        } => meaningful(bytes, benign, malicious),
+    }
+}
+
+fn demo() {
     let num_bytes = 6;
 
     println!("Search set size: {}", 1_u128 << (num_bytes * 8));
@@ -85,6 +192,30 @@ fn main() {
     println!("Random value obtained via thread rng");
     os_rand_collisions(num_bytes);
 
+    let search = CollisionSearch::<Sha256>::new()
+        .truncation(Truncation::Leading(num_bytes))
+        .seed(rand::thread_rng().gen_range(0..1 << (num_bytes * 8)));
+
     println!("Random value obtained via sha256(counter)");
-    sub_sha_collisions(num_bytes);
+    sub_hash_collisions(&search, num_bytes);
+
+    println!("Random value obtained via sha256(counter), memoryless rho search");
+    sub_hash_collisions(&search.clone().strategy(Strategy::Rho), num_bytes);
+
+    println!("Random value obtained via sha256(counter), distinguished points search");
+    for threads in [1, 2, 4] {
+        print!("{threads} threads: ");
+        let strategy = Strategy::Distinguished {
+            threads,
+            mask: (1 << 12) - 1,
+        };
+        sub_hash_collisions(&search.clone().strategy(strategy), num_bytes);
+    }
+
+    println!("Collision cost for different functions and truncations");
+    scaling::<md5::Md5>("MD5");
+    scaling::<sha1::Sha1>("SHA-1");
+    scaling::<Sha256>("SHA-256");
+    scaling::<sha3::Sha3_256>("SHA3-256");
+    scaling::<blake2::Blake2s256>("BLAKE2s");
 }
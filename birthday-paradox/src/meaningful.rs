@@ -0,0 +1,132 @@
+//! Meaningful collisions (Yuval's attack).
+//!
+//! A collision between two counters is harmless, a collision between a benign
+//! and a malicious document is a fraud: the victim signs (the hash of) the
+//! benign one and the signature is valid for the malicious one too.
+//!
+//! Both documents are written as [`Template`]s with many spots accepting
+//! equivalent alternatives (synonyms, trailing spaces, punctuation...): `k`
+//! binary spots give 2^k variants which read the same. Once each template has
+//! more than ≈ √N variants, a cross template collision is expected after
+//! ≈ √N variants of each are hashed.
+
+use crate::search::Truncation;
+use digest::Digest;
+use std::collections::HashMap;
+
+/// Document with alternatives, written as `{first|second|...}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    parts: Vec<Vec<String>>,
+}
+
+impl Template {
+    /// Parse the template `text`. Braces can't be escaped.
+    pub fn parse(text: &str) -> Self {
+        let mut parts = Vec::new();
+        let mut rest = text;
+        while let Some(open) = rest.find('{') {
+            let close = open + rest[open..].find('}').expect("unclosed alternative");
+            parts.push(vec![rest[..open].to_string()]);
+            parts.push(rest[open + 1..close].split('|').map(String::from).collect());
+            rest = &rest[close + 1..];
+        }
+        parts.push(vec![rest.to_string()]);
+        Template { parts }
+    }
+
+    /// Number of variants, saturated at `u64::MAX`.
+    pub fn variants(&self) -> u64 {
+        self.parts
+            .iter()
+            .fold(1_u64, |acc, p| acc.saturating_mul(p.len() as u64))
+    }
+
+    /// The `index`-th variant, reading the index in mixed radix.
+    pub fn render(&self, mut index: u64) -> String {
+        let mut text = String::new();
+        for alternatives in &self.parts {
+            let radix = alternatives.len() as u64;
+            text.push_str(&alternatives[(index % radix) as usize]);
+            index /= radix;
+        }
+        text
+    }
+}
+
+/// Colliding documents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Forgery {
+    pub benign: String,
+    pub malicious: String,
+    /// Number of hashed variants.
+    pub count: usize,
+}
+
+/// Find a variant of each template with the same truncated digest.
+///
+/// Variants of the two templates are hashed in turns and kept in two tables,
+/// collisions between variants of the same template are ignored. `None` if
+/// the variants are exhausted first.
+pub fn forge<D: Digest>(
+    benign: &Template,
+    malicious: &Template,
+    truncation: &Truncation,
+) -> Option<Forgery> {
+    let hash = |text: &str| truncation.apply(&D::digest(text.as_bytes()));
+    let mut tables = [HashMap::new(), HashMap::new()];
+    let templates = [benign, malicious];
+    let mut count = 0;
+    for index in 0..benign.variants().max(malicious.variants()) {
+        for side in 0..2 {
+            if index >= templates[side].variants() {
+                continue;
+            }
+            let key = hash(&templates[side].render(index));
+            count += 1;
+            if let Some(other) = tables[1 - side].get(&key) {
+                let (b, m) = if side == 0 {
+                    (index, *other)
+                } else {
+                    (*other, index)
+                };
+                return Some(Forgery {
+                    benign: benign.render(b),
+                    malicious: malicious.render(m),
+                    count,
+                });
+            }
+            tables[side].entry(key).or_insert(index);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::Sha256;
+
+    #[test]
+    fn template_variants() {
+        let t = Template::parse("{I|We} owe you {10|100|1000}{.|!}");
+        assert_eq!(t.variants(), 12);
+        assert_eq!(t.render(0), "I owe you 10.");
+        assert_eq!(t.render(1 + 2 * 2 + 6), "We owe you 1000!");
+    }
+
+    #[test]
+    fn forged_documents_collide() {
+        let spots = "{a|A}{b|B}{c|C}{d|D}{e|E}{f|F}{g|G}{h|H}{i|I}{j|J}{k|K}{l|L}";
+        let benign = Template::parse(&format!("pay 10 to bob {spots}"));
+        let malicious = Template::parse(&format!("pay 9999 to eve {spots}"));
+        let truncation = Truncation::Leading(2);
+        let f = forge::<Sha256>(&benign, &malicious, &truncation).unwrap();
+        assert!(f.benign.starts_with("pay 10 ") && f.malicious.starts_with("pay 9999 "));
+        let digest = |s: &str| truncation.apply(&Sha256::digest(s.as_bytes()));
+        assert_eq!(digest(&f.benign), digest(&f.malicious));
+
+        let (b, m) = (Template::parse("{a|b}"), Template::parse("{c|d|e}"));
+        assert_eq!(forge::<Sha256>(&b, &m, &Truncation::Leading(8)), None);
+    }
+}
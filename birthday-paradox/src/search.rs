@@ -0,0 +1,203 @@
+//! Configurable truncated hash collision search.
+//!
+//! The [`CollisionSearch`] builder selects the hash function (any
+//! `digest::Digest`, e.g. SHA-1, SHA-3, BLAKE2, MD5), which bits of the digest
+//! are kept ([`Truncation`]) and the search engine ([`Strategy`]), to explore
+//! how the collision cost scales with the functions and widths.
+//!
+//! Inputs are 64 bits integers (hashed as little endian bytes) and the kept
+//! bits are packed into an integer as well, so that the truncated hash can be
+//! iterated by the memoryless engines.
+
+use crate::{distinguished, find_collision, find_collision_rho, Collision};
+use digest::Digest;
+use std::{fmt, marker::PhantomData};
+
+/// Digest bits kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Truncation {
+    /// First bytes.
+    Leading(usize),
+    /// Last bytes.
+    Trailing(usize),
+    /// Bits set in the mask, applied to the first digest bytes.
+    Mask(Vec<u8>),
+}
+
+impl Truncation {
+    /// Number of kept bits.
+    pub fn bits(&self) -> u32 {
+        match self {
+            Truncation::Leading(n) | Truncation::Trailing(n) => 8 * *n as u32,
+            Truncation::Mask(mask) => mask.iter().map(|b| b.count_ones()).sum(),
+        }
+    }
+
+    /// Kept bits of the `digest`, packed in order.
+    pub fn apply(&self, digest: &[u8]) -> u64 {
+        let pack = |bytes: &[u8]| bytes.iter().fold(0, |acc, b| acc << 8 | *b as u64);
+        match self {
+            Truncation::Leading(n) => pack(&digest[..*n]),
+            Truncation::Trailing(n) => pack(&digest[digest.len() - n..]),
+            Truncation::Mask(mask) => mask
+                .iter()
+                .zip(digest)
+                .flat_map(|(m, d)| (0..8).rev().map(move |i| (m >> i & 1, d >> i & 1)))
+                .filter(|(m, _)| *m == 1)
+                .fold(0, |acc, (_, bit)| acc << 1 | bit as u64),
+        }
+    }
+}
+
+impl fmt::Display for Truncation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Truncation::Leading(n) => write!(f, "leading {n} bytes"),
+            Truncation::Trailing(n) => write!(f, "trailing {n} bytes"),
+            Truncation::Mask(mask) => write!(f, "mask {}", hex::encode(mask)),
+        }
+    }
+}
+
+/// Collision search engine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Hash table, see [`find_collision`].
+    Table,
+    /// Memoryless, see [`find_collision_rho`].
+    Rho,
+    /// Parallel distinguished points, see [`distinguished::find_collision`].
+    Distinguished { threads: usize, mask: u64 },
+}
+
+/// Truncated hash collision search builder.
+#[derive(Debug)]
+pub struct CollisionSearch<D> {
+    truncation: Truncation,
+    strategy: Strategy,
+    seed: u64,
+    _digest: PhantomData<fn() -> D>,
+}
+
+// Not derived, to not require `D: Clone`
+impl<D> Clone for CollisionSearch<D> {
+    fn clone(&self) -> Self {
+        CollisionSearch {
+            truncation: self.truncation.clone(),
+            strategy: self.strategy,
+            seed: self.seed,
+            _digest: PhantomData,
+        }
+    }
+}
+
+impl<D: Digest> Default for CollisionSearch<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D: Digest> CollisionSearch<D> {
+    /// Four leading bytes, hash table search.
+    pub fn new() -> Self {
+        CollisionSearch {
+            truncation: Truncation::Leading(4),
+            strategy: Strategy::Table,
+            seed: 0,
+            _digest: PhantomData,
+        }
+    }
+
+    pub fn truncation(mut self, truncation: Truncation) -> Self {
+        self.truncation = truncation;
+        self
+    }
+
+    pub fn strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// First input (or walk start) of the search.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Truncated hash width.
+    pub fn bits(&self) -> u32 {
+        self.truncation.bits()
+    }
+
+    /// Truncated hash of `x`.
+    pub fn hash(&self, x: u64) -> u64 {
+        self.truncation.apply(&D::digest(x.to_le_bytes()))
+    }
+
+    /// Expected number of evaluations for the first collision, ≈ √(πN/2).
+    pub fn expected(&self) -> f64 {
+        (std::f64::consts::PI / 2.0 * 2_f64.powi(self.truncation.bits() as i32)).sqrt()
+    }
+
+    /// Run the search.
+    ///
+    /// Panics if the truncation is wider than 64 bits or than the digest.
+    pub fn run(&self) -> Option<Collision<u64>> {
+        let bits = self.truncation.bits();
+        assert!(bits <= 64, "truncation wider than 64 bits");
+        assert!(
+            bits as usize <= 8 * <D as Digest>::output_size(),
+            "truncation wider than the digest"
+        );
+        let f = |x: &u64| self.hash(*x);
+        match self.strategy {
+            Strategy::Table => find_collision(self.seed.., f),
+            // The walk stays within the truncated values
+            Strategy::Rho => (self.seed..).find_map(|start| find_collision_rho(f(&start), f)),
+            Strategy::Distinguished { threads, mask } => {
+                Some(distinguished::find_collision(f, threads, mask))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncations() {
+        let digest = [0x12, 0x34, 0x56, 0x78];
+        assert_eq!(Truncation::Leading(2).apply(&digest), 0x1234);
+        assert_eq!(Truncation::Trailing(1).apply(&digest), 0x78);
+        let mask = Truncation::Mask(vec![0xf0, 0x00, 0x0f]);
+        assert_eq!(mask.bits(), 8);
+        assert_eq!(mask.apply(&digest), 0x16);
+    }
+
+    #[test]
+    fn generic_digests_and_strategies() {
+        fn check<D: Digest>(truncation: Truncation) {
+            for strategy in [
+                Strategy::Table,
+                Strategy::Rho,
+                Strategy::Distinguished {
+                    threads: 2,
+                    mask: 0xf,
+                },
+            ] {
+                let search = CollisionSearch::<D>::new()
+                    .truncation(truncation.clone())
+                    .strategy(strategy);
+                let c = search.run().unwrap();
+                assert_ne!(c.a, c.b);
+                assert_eq!(search.hash(c.a), search.hash(c.b));
+                assert_ne!(D::digest(c.a.to_le_bytes()), D::digest(c.b.to_le_bytes()));
+            }
+        }
+        check::<sha1::Sha1>(Truncation::Leading(2));
+        check::<sha3::Sha3_256>(Truncation::Trailing(2));
+        check::<md5::Md5>(Truncation::Mask(vec![0x55; 4]));
+        check::<blake2::Blake2s256>(Truncation::Leading(3));
+    }
+}
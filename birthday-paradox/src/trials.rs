@@ -0,0 +1,114 @@
+//! Repeated collision searches.
+//!
+//! The number of extractions `k` needed for the first collision among `N`
+//! equally likely values has
+//!
+//! P(k > n) = ∏ (1 - i/N) ≈ exp(-n²/2N), for i in 1..n
+//!
+//! i.e. it approximately follows a Rayleigh distribution with mean √(πN/2) and
+//! median √(2N·ln 2). A single run says little: the spread is wide, with a
+//! standard deviation ≈ 0.52·√N.
+
+use crate::search::CollisionSearch;
+use digest::Digest;
+use rand::Rng;
+use std::io::{self, Write};
+
+/// Extractions needed by each search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trials {
+    pub counts: Vec<usize>,
+    /// Search space size.
+    pub space: f64,
+}
+
+impl Trials {
+    /// Run the `search` `n` times from random seeds.
+    pub fn run<D: Digest, R: Rng>(search: &CollisionSearch<D>, n: usize, rng: &mut R) -> Self {
+        let counts = (0..n)
+            .map(|_| search.clone().seed(rng.gen()).run().unwrap().count)
+            .collect();
+        Trials {
+            counts,
+            space: 2_f64.powi(search.bits() as i32),
+        }
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.counts.iter().sum::<usize>() as f64 / self.counts.len() as f64
+    }
+
+    pub fn median(&self) -> f64 {
+        let mut sorted = self.counts.clone();
+        sorted.sort_unstable();
+        let n = sorted.len();
+        if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) as f64 / 2.0
+        } else {
+            sorted[n / 2] as f64
+        }
+    }
+
+    /// Expected mean, √(πN/2).
+    pub fn expected_mean(&self) -> f64 {
+        (std::f64::consts::PI * self.space / 2.0).sqrt()
+    }
+
+    /// Expected median, √(2N·ln 2).
+    pub fn expected_median(&self) -> f64 {
+        (2.0 * self.space * std::f64::consts::LN_2).sqrt()
+    }
+
+    /// Probability that the first collision happens within `n` extractions.
+    pub fn cdf(&self, n: f64) -> f64 {
+        1.0 - (-n * n / (2.0 * self.space)).exp()
+    }
+
+    /// Histogram with `bins` equally sized bins from zero to the max count.
+    ///
+    /// Every bin is `(lo, hi, count, expected count)`, for counts in `lo..hi`.
+    pub fn histogram(&self, bins: usize) -> Vec<(usize, usize, usize, f64)> {
+        let max = self.counts.iter().max().copied().unwrap_or(0) + 1;
+        let width = max.div_ceil(bins);
+        (0..bins)
+            .map(|i| {
+                let (lo, hi) = (i * width, (i + 1) * width);
+                let count = self.counts.iter().filter(|c| (lo..hi).contains(c)).count();
+                let prob = self.cdf(hi as f64 - 1.0) - self.cdf(lo as f64 - 1.0);
+                (lo, hi, count, prob * self.counts.len() as f64)
+            })
+            .collect()
+    }
+
+    /// Save the histogram as CSV.
+    pub fn write_csv<W: Write>(&self, mut writer: W, bins: usize) -> io::Result<()> {
+        writeln!(writer, "lo,hi,count,expected")?;
+        for (lo, hi, count, expected) in self.histogram(bins) {
+            writeln!(writer, "{lo},{hi},{count},{expected:.2}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::Truncation;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+    use sha2::Sha256;
+
+    #[test]
+    fn matches_theory() {
+        let search = CollisionSearch::<Sha256>::new().truncation(Truncation::Leading(2));
+        let trials = Trials::run(&search, 500, &mut ChaCha20Rng::seed_from_u64(0));
+        // Standard error of the mean ≈ 0.52·√N/√500 ≈ 6
+        assert!((trials.mean() - trials.expected_mean()).abs() < 25.0);
+        assert!((trials.median() - trials.expected_median()).abs() < 30.0);
+
+        let histogram = trials.histogram(10);
+        assert_eq!(histogram.iter().map(|b| b.2).sum::<usize>(), 500);
+        let expected: f64 = histogram.iter().map(|b| b.3).sum();
+        assert!((expected - 500.0).abs() < 1.0);
+    }
+}
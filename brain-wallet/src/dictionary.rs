@@ -0,0 +1,118 @@
+//! Passphrase candidates generation.
+//!
+//! Candidates are one or more words from a vocabulary, joined by a space,
+//! to which the mangling rules are applied.
+
+/// Builtin vocabulary of common words.
+pub const WORDS: &str = include_str!("words.txt");
+
+/// Vocabulary words.
+pub fn words() -> Vec<&'static str> {
+    WORDS.split_whitespace().collect()
+}
+
+/// Mangling rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    /// Leave the phrase unchanged.
+    Identity,
+    /// Capitalize the first letter.
+    Capitalize,
+    /// Replace `a e i o s` with `4 3 1 0 5`.
+    Leet,
+    /// Remove the spaces.
+    Concatenate,
+    /// Append each number less than the given one.
+    AppendDigits(u32),
+}
+
+impl Rule {
+    /// Variants of `phrase` produced by the rule.
+    pub fn apply(&self, phrase: &str) -> Vec<String> {
+        match self {
+            Rule::Identity => vec![phrase.to_string()],
+            Rule::Capitalize => {
+                let mut chars = phrase.chars();
+                let first = chars.next().map(|c| c.to_uppercase().collect::<String>());
+                vec![first.unwrap_or_default() + chars.as_str()]
+            }
+            Rule::Leet => vec![phrase
+                .chars()
+                .map(|c| match c {
+                    'a' => '4',
+                    'e' => '3',
+                    'i' => '1',
+                    'o' => '0',
+                    's' => '5',
+                    c => c,
+                })
+                .collect()],
+            Rule::Concatenate => vec![phrase.replace(' ', "")],
+            Rule::AppendDigits(n) => (0..*n).map(|i| format!("{phrase}{i}")).collect(),
+        }
+    }
+}
+
+/// Phrases of exactly `len` words of `words` (repetitions allowed).
+pub fn phrases<'a>(words: &'a [&'a str], len: u32) -> impl Iterator<Item = String> + 'a {
+    let count = (words.len() as u64).pow(len);
+    (0..count).map(move |mut i| {
+        let mut phrase = Vec::with_capacity(len as usize);
+        for _ in 0..len {
+            phrase.push(words[(i % words.len() as u64) as usize]);
+            i /= words.len() as u64;
+        }
+        phrase.join(" ")
+    })
+}
+
+/// All the `rules` variants of all the phrases with up to `max_len` words.
+pub fn candidates<'a>(
+    words: &'a [&'a str],
+    max_len: u32,
+    rules: &'a [Rule],
+) -> impl Iterator<Item = String> + 'a {
+    (1..=max_len)
+        .flat_map(move |len| phrases(words, len))
+        .flat_map(move |phrase| rules.iter().flat_map(move |rule| rule.apply(&phrase)))
+}
+
+/// Number of candidates produced by [`candidates`].
+pub fn count(words: usize, max_len: u32, rules: &[Rule]) -> u64 {
+    let per_phrase: u64 = rules
+        .iter()
+        .map(|r| match r {
+            Rule::AppendDigits(n) => *n as u64,
+            _ => 1,
+        })
+        .sum();
+    (1..=max_len)
+        .map(|len| (words as u64).pow(len))
+        .sum::<u64>()
+        * per_phrase
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_and_phrases() {
+        assert_eq!(Rule::Capitalize.apply("red sun"), ["Red sun"]);
+        assert_eq!(Rule::Leet.apply("password"), ["p455w0rd"]);
+        assert_eq!(Rule::Concatenate.apply("red sun"), ["redsun"]);
+        assert_eq!(Rule::AppendDigits(3).apply("x"), ["x0", "x1", "x2"]);
+
+        let words = ["a", "b", "c"];
+        let all: Vec<_> = phrases(&words, 2).collect();
+        assert_eq!(all.len(), 9);
+        assert!(all.contains(&"c b".to_string()));
+
+        let rules = [Rule::Identity, Rule::AppendDigits(10)];
+        assert_eq!(
+            candidates(&words, 2, &rules).count() as u64,
+            count(3, 2, &rules)
+        );
+        assert_eq!(count(3, 2, &rules), 12 * 11);
+    }
+}
@@ -0,0 +1,106 @@
+//! Brain wallet cracking.
+//!
+//! A brain wallet derives the secp256k1 private key from a passphrase chosen
+//! and remembered by the user, typically as `d = SHA256(passphrase)`. No seed
+//! needs to be stored, but the key is exactly as strong as the passphrase.
+//!
+//! Human chosen phrases live in a tiny corner of the key space: a few words
+//! from a common vocabulary, mangled by predictable rules (capitalization,
+//! leet substitutions, trailing digits). The attacker derives the public key
+//! of each dictionary candidate once and matches it against *all* the public
+//! keys seen on chain at the same time, so the cost of the attack is shared by
+//! every victim. Funds sent to brain wallets on Bitcoin were famously swept
+//! within seconds.
+//!
+//! The same applies to mnemonics generated with too little entropy, e.g. a
+//! couple of words out of a short list. See the [`dictionary`] module.
+
+use k256::{elliptic_curve::sec1::ToEncodedPoint, SecretKey};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+
+pub mod dictionary;
+
+/// Compressed SEC1 public key.
+pub type PublicKey = [u8; 33];
+
+/// Private key derived from `passphrase`, `None` for the negligible fraction
+/// of hashes which are not valid scalars.
+pub fn derive_secret(passphrase: &str) -> Option<SecretKey> {
+    SecretKey::from_bytes(&Sha256::digest(passphrase.as_bytes())).ok()
+}
+
+/// Public key of the brain wallet protected by `passphrase`.
+pub fn derive_public(passphrase: &str) -> Option<PublicKey> {
+    let point = derive_secret(passphrase)?
+        .public_key()
+        .to_encoded_point(true);
+    point.as_bytes().try_into().ok()
+}
+
+/// Cracked wallet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Found {
+    pub passphrase: String,
+    pub public: PublicKey,
+}
+
+/// Derive the public key of every candidate passphrase and match it against
+/// the `targets` set.
+pub fn scan<I>(candidates: I, targets: &HashSet<PublicKey>) -> Vec<Found>
+where
+    I: Iterator<Item = String> + Send,
+{
+    let mut found = par_search::filter_map(candidates, |passphrase| {
+        let public = derive_public(&passphrase)?;
+        targets
+            .contains(&public)
+            .then_some(Found { passphrase, public })
+    });
+    found.sort_by(|a, b| a.passphrase.cmp(&b.passphrase));
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_brain_wallet() {
+        // The infamous SHA256("correct horse battery staple")
+        let secret = derive_secret("correct horse battery staple").unwrap();
+        assert_eq!(
+            hex::encode(secret.to_bytes()),
+            "c4bbcb1fbec99d65bf59d85c8cb62ee2db963f0fe106f483d9afa73bd4e39a8a"
+        );
+        let public = derive_public("correct horse battery staple").unwrap();
+        assert_eq!(
+            public[..],
+            secret.public_key().to_encoded_point(true).as_bytes()[..]
+        );
+    }
+
+    #[test]
+    fn scan_targets() {
+        use dictionary::{candidates, words, Rule};
+        use rand::rngs::OsRng;
+
+        let weak = ["Sunshine", "dragon42", "l0v3"];
+        let mut targets: HashSet<_> = weak.iter().map(|p| derive_public(p).unwrap()).collect();
+        let strong = SecretKey::random(&mut OsRng)
+            .public_key()
+            .to_encoded_point(true);
+        targets.insert(strong.as_bytes().try_into().unwrap());
+
+        let words = words();
+        let rules = [
+            Rule::Identity,
+            Rule::Capitalize,
+            Rule::Leet,
+            Rule::AppendDigits(100),
+        ];
+        let found = scan(candidates(&words, 1, &rules), &targets);
+        let cracked: Vec<_> = found.iter().map(|f| f.passphrase.as_str()).collect();
+        assert_eq!(cracked, ["Sunshine", "dragon42", "l0v3"]);
+    }
+}
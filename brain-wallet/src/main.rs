@@ -0,0 +1,61 @@
+use brain_wallet::{
+    derive_public,
+    dictionary::{candidates, count, words, Rule},
+    scan, PublicKey,
+};
+use k256::{elliptic_curve::sec1::ToEncodedPoint, SecretKey};
+use rand::rngs::OsRng;
+use std::{collections::HashSet, time::Instant};
+
+fn main() {
+    // Public keys seen on chain: mostly random keys plus a few brain wallets
+    let brain = [
+        "correct horse battery staple",
+        "Princess",
+        "m0nk3y",
+        "bluesky",
+        "summer19",
+    ];
+    let mut targets: HashSet<PublicKey> = (0..10_000)
+        .map(|_| {
+            let point = SecretKey::random(&mut OsRng)
+                .public_key()
+                .to_encoded_point(true);
+            point.as_bytes().try_into().unwrap()
+        })
+        .collect();
+    targets.extend(brain.iter().map(|p| derive_public(p).unwrap()));
+
+    let words = words();
+    println!(
+        "{} target keys, {} words vocabulary",
+        targets.len(),
+        words.len()
+    );
+    let single = [
+        Rule::Identity,
+        Rule::Capitalize,
+        Rule::Leet,
+        Rule::AppendDigits(100),
+    ];
+    let pairs = [Rule::Identity, Rule::Concatenate];
+    for (len, rules) in [(1, &single[..]), (2, &pairs[..])] {
+        println!(
+            "Phrases up to {len} words, {} candidates",
+            count(words.len(), len, rules)
+        );
+        let start = Instant::now();
+        for found in scan(candidates(&words, len, rules), &targets) {
+            println!("  {:<20} {}", found.passphrase, hex::encode(found.public));
+        }
+        println!("  ({:.2}s)", start.elapsed().as_secs_f64());
+    }
+
+    // The whole vocabulary would take 196^4 ≈ 2^30 derivations: hours here,
+    // seconds for a GPU farm
+    let staple = "correct horse battery staple";
+    let words = ["correct", "horse", "battery", "staple"];
+    let found = scan(candidates(&words, 4, &[Rule::Identity]), &targets);
+    assert!(found.iter().any(|f| f.passphrase == staple));
+    println!("Found '{staple}' among the 4 words phrases of its own words");
+}
@@ -0,0 +1,33 @@
+//! CBC bit flipping demo.
+
+use cbc_padding_oracle::bitflip::{flip, xor_mask, TokenService};
+
+const BLKSIZ: usize = 16;
+
+fn main() {
+    let service = TokenService::new([0x42; 16]);
+    let iv = [0x24; BLKSIZ];
+
+    // "user=" (5 bytes) + 11 bytes name → ";admin=false" starts at block #1
+    let user = "mallory0000";
+    let mut token = service.issue(&iv, user).unwrap();
+    println!("Token: {}", hex::encode(&token));
+    println!("Verify: {:?}", service.verify(&iv, &token).unwrap());
+
+    // Flipping block #0 alters the plaintext of block #1
+    let mask = xor_mask(b";admin=false", b";admin=true;");
+    flip(&mut token, BLKSIZ, 0, 0, &mask);
+    println!("Token: {}", hex::encode(&token));
+    println!("Fields: {:?}", service.fields(&iv, &token).unwrap());
+    let (_, admin) = service.verify(&iv, &token).unwrap();
+    println!("Verify: admin={admin}");
+    assert!(admin);
+
+    // Flipping the IV cleanly alters the plaintext of block #0
+    let mut iv = iv;
+    let token = service.issue(&iv, "alice").unwrap();
+    flip(&mut iv, BLKSIZ, 0, 5, &xor_mask(b"alice", b"carol"));
+    let (user, _) = service.verify(&iv, &token).unwrap();
+    println!("IV tampering: alice → {user}");
+    assert_eq!(user, "carol");
+}
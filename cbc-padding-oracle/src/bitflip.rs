@@ -0,0 +1,130 @@
+//! CBC bit flipping attack.
+//!
+//! In CBC mode the plaintext block `i` is computed as `P[i] = D(C[i]) ⊕ C[i-1]`
+//! (with `C[-1] = IV`). Flipping a bit of the ciphertext block `i-1` thus flips
+//! the same bit of the plaintext block `i`, while the block `i-1` decrypts to
+//! garbage. Flipping a bit of the IV cleanly flips the same bit of the first
+//! plaintext block.
+//!
+//! Without integrity protection an attacker knowing (part of) the plaintext
+//! structure can rewrite it at will, e.g. turn `admin=false` into `admin=true`.
+
+use crate::encrypt;
+use aes::Aes128;
+use cbc::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
+
+/// XOR `xor_mask` into `ct` starting at byte `offset` of block `block`.
+///
+/// This alters the plaintext of block `block + 1` by the same mask. Pass the
+/// IV as `ct` (with `block = 0`) to alter the first plaintext block.
+pub fn flip(ct: &mut [u8], block_size: usize, block: usize, offset: usize, xor_mask: &[u8]) {
+    let start = block * block_size + offset;
+    assert!(start + xor_mask.len() <= ct.len(), "Flip out of bounds");
+    ct[start..start + xor_mask.len()]
+        .iter_mut()
+        .zip(xor_mask)
+        .for_each(|(c, m)| *c ^= m);
+}
+
+/// Mask turning the `known` plaintext into the `wanted` one.
+pub fn xor_mask(known: &[u8], wanted: &[u8]) -> Vec<u8> {
+    assert_eq!(known.len(), wanted.len(), "Length mismatch");
+    known.iter().zip(wanted).map(|(a, b)| a ^ b).collect()
+}
+
+/// Token service errors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// User name contains reserved characters.
+    InvalidUser,
+    /// Token doesn't decrypt to a well padded plaintext.
+    InvalidToken,
+}
+
+/// Service issuing encrypted `user=<name>;admin=false` tokens.
+pub struct TokenService {
+    key: [u8; 16],
+}
+
+impl TokenService {
+    pub fn new(key: [u8; 16]) -> Self {
+        TokenService { key }
+    }
+
+    /// Issue a token for `user`.
+    ///
+    /// Reserved characters (`;` and `=`) are rejected, thus the user can't just
+    /// inject `;admin=true`.
+    pub fn issue(&self, iv: &[u8; 16], user: &str) -> Result<Vec<u8>, Error> {
+        if user.contains([';', '=']) {
+            return Err(Error::InvalidUser);
+        }
+        let plaintext = format!("user={user};admin=false");
+        Ok(encrypt::<Aes128>(&self.key, iv, plaintext.as_bytes()))
+    }
+
+    /// Decrypt the token and return its `(key, value)` fields.
+    ///
+    /// Fields which are not valid `key=value` pairs are ignored.
+    pub fn fields(&self, iv: &[u8], token: &[u8]) -> Result<Vec<(String, String)>, Error> {
+        let mut buf = token.to_vec();
+        let plaintext = cbc::Decryptor::<Aes128>::new_from_slices(&self.key, iv)
+            .map_err(|_| Error::InvalidToken)?
+            .decrypt_padded_mut::<Pkcs7>(&mut buf)
+            .map_err(|_| Error::InvalidToken)?;
+        let plaintext = String::from_utf8_lossy(plaintext);
+        Ok(plaintext
+            .split(';')
+            .filter_map(|field| field.split_once('='))
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect())
+    }
+
+    /// User name and admin flag carried by the token.
+    pub fn verify(&self, iv: &[u8], token: &[u8]) -> Result<(String, bool), Error> {
+        let fields = self.fields(iv, token)?;
+        let user = fields
+            .iter()
+            .find(|(k, _)| k == "user")
+            .map(|(_, v)| v.clone())
+            .unwrap_or_default();
+        let admin = fields.iter().any(|(k, v)| k == "admin" && v == "true");
+        Ok((user, admin))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLKSIZ: usize = 16;
+
+    #[test]
+    fn privilege_escalation() {
+        let service = TokenService::new([0x42; 16]);
+        let iv = [0x24; 16];
+
+        // "user=" + 11 chars fills the first block, thus ";admin=false" is
+        // at the start of the second block.
+        let mut token = service.issue(&iv, "mallory0000").unwrap();
+        assert!(!service.verify(&iv, &token).unwrap().1);
+
+        let mask = xor_mask(b";admin=false", b";admin=true;");
+        flip(&mut token, BLKSIZ, 0, 0, &mask);
+        assert!(service.verify(&iv, &token).unwrap().1);
+    }
+
+    #[test]
+    fn iv_tampering() {
+        let service = TokenService::new([0x42; 16]);
+        let mut iv = [0x24; 16];
+
+        let token = service.issue(&iv, "alice").unwrap();
+        let mask = xor_mask(b"alice", b"carol");
+        flip(&mut iv, BLKSIZ, 0, 5, &mask);
+
+        let (user, admin) = service.verify(&iv, &token).unwrap();
+        assert_eq!(user, "carol");
+        assert!(!admin);
+    }
+}
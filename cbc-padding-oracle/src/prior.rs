@@ -0,0 +1,223 @@
+//! Padding oracle attack driven by a plaintext prior.
+//!
+//! The plain attack tries the 256 values of each byte in a fixed order, thus it
+//! costs 128 queries per byte on average whatever the plaintext is. When the
+//! plaintext is known to be, say, mostly ASCII JSON most of those queries are
+//! wasted on bytes which are very unlikely to show up.
+//!
+//! Here the guesses are tried by decreasing posterior probability:
+//! - bytes are recovered right to left (blocks are attacked last to first), thus
+//!   the byte following the target one is always known and the model is a
+//!   bigram `P(byte | next)` smoothed with the unigram `P(byte)`;
+//! - the model starts from the counts of a sample of the expected plaintext and
+//!   is updated with every recovered byte, so it adapts to the actual message;
+//! - the last block is known to end with a valid PKCS#7 padding, thus its last
+//!   byte is in `1..=block_size` and the padding bytes are fully determined.
+//!
+//! Every byte value is still eventually tried, so a wrong prior only costs
+//! queries, never correctness.
+
+use crate::{attack_block, check_lengths, strip_padding, AttackStats, PaddingOracle};
+use std::time::Instant;
+
+// Unigram smoothing weight (uniform pseudo counts).
+const UNIGRAM_SMOOTHING: f64 = 1.0;
+// Bigram smoothing weight (unigram pseudo counts).
+const BIGRAM_SMOOTHING: f64 = 2.0;
+
+/// A few JSON records, the expected plaintext of [`Prior::json`].
+const JSON_SAMPLE: &str = r#"{"id":1042,"name":"Alice Smith","email":"alice@example.com","active":true,"roles":["admin","user"],"balance":1520.75,"created":"2021-03-14T09:26:53Z"}
+{"id":1043,"name":"Bob Jones","email":"bob.jones@example.org","active":false,"roles":["user"],"balance":0.0,"created":"2022-11-02T17:45:12Z","notes":null}
+{"user":{"id":77,"username":"carol","verified":true},"session":{"token":"f3a9c2e1","expires":3600},"permissions":["read","write"]}
+{"status":"ok","code":200,"message":"Request completed","data":{"items":[{"sku":"A-100","qty":2,"price":9.99},{"sku":"B-220","qty":1,"price":24.5}]}}
+{"event":"login","timestamp":1678886400,"ip":"192.168.1.20","success":true,"user_agent":"Mozilla/5.0"}
+{"type":"transfer","from":"acct-3391","to":"acct-7720","amount":250,"currency":"EUR","description":"rent for march"}"#;
+
+/// Plaintext byte distribution model.
+#[derive(Clone)]
+pub struct Prior {
+    unigram: Vec<f64>,
+    unigram_total: f64,
+    // Indexed by `[next][byte]`.
+    bigram: Vec<Vec<f64>>,
+    bigram_total: Vec<f64>,
+}
+
+impl Prior {
+    /// No prior knowledge, the model only learns from the recovered bytes.
+    pub fn uniform() -> Self {
+        Prior {
+            unigram: vec![0.0; 256],
+            unigram_total: 0.0,
+            bigram: vec![vec![0.0; 256]; 256],
+            bigram_total: vec![0.0; 256],
+        }
+    }
+
+    /// Model trained on a sample of the expected plaintext.
+    pub fn from_sample(sample: &[u8]) -> Self {
+        let mut prior = Self::uniform();
+        for (i, &byte) in sample.iter().enumerate() {
+            prior.observe(byte, sample.get(i + 1).copied());
+        }
+        prior
+    }
+
+    /// Model for ASCII JSON documents.
+    pub fn json() -> Self {
+        Self::from_sample(JSON_SAMPLE.as_bytes())
+    }
+
+    /// Account for `byte` followed by `next`.
+    pub fn observe(&mut self, byte: u8, next: Option<u8>) {
+        self.unigram[byte as usize] += 1.0;
+        self.unigram_total += 1.0;
+        if let Some(next) = next {
+            self.bigram[next as usize][byte as usize] += 1.0;
+            self.bigram_total[next as usize] += 1.0;
+        }
+    }
+
+    /// Probability of `byte` given the `next` one (if known).
+    pub fn probability(&self, byte: u8, next: Option<u8>) -> f64 {
+        let unigram = (self.unigram[byte as usize] + UNIGRAM_SMOOTHING / 256.0)
+            / (self.unigram_total + UNIGRAM_SMOOTHING);
+        match next {
+            Some(next) => {
+                let next = next as usize;
+                (self.bigram[next][byte as usize] + BIGRAM_SMOOTHING * unigram)
+                    / (self.bigram_total[next] + BIGRAM_SMOOTHING)
+            }
+            None => unigram,
+        }
+    }
+
+    /// All the byte values, most likely first. Ties are broken by value.
+    pub fn ranking(&self, next: Option<u8>) -> Vec<u8> {
+        let probs: Vec<_> = (0..=255).map(|b| self.probability(b, next)).collect();
+        let mut bytes: Vec<u8> = (0..=255).collect();
+        bytes.sort_by(|&a, &b| probs[b as usize].total_cmp(&probs[a as usize]));
+        bytes
+    }
+}
+
+// `first` values in front, followed by the remaining ones in `ranking` order.
+fn promote(first: &[u8], ranking: Vec<u8>) -> Vec<u8> {
+    let mut order = first.to_vec();
+    order.extend(ranking.into_iter().filter(|b| !first.contains(b)));
+    order
+}
+
+/// Padding oracle attack trying the guesses in `prior` order.
+///
+/// Blocks are processed sequentially, from the last one, and `prior` is updated
+/// with the recovered plaintext. Parameters are the same as `attack`.
+pub fn attack_with_prior<O: PaddingOracle>(
+    oracle: &O,
+    block_size: usize,
+    iv: &[u8],
+    ciphertext: &[u8],
+    prior: &mut Prior,
+) -> (Vec<u8>, AttackStats) {
+    check_lengths(block_size, iv, ciphertext);
+    let start = Instant::now();
+
+    let blocks: Vec<_> = ciphertext.chunks(block_size).collect();
+    let mut plain_blocks = vec![Vec::new(); blocks.len()];
+    let mut stats = AttackStats::default();
+    // Recovered plaintext (padding excluded) following the current block.
+    let mut suffix: Vec<u8> = Vec::new();
+
+    for i in (0..blocks.len()).rev() {
+        let prev = if i == 0 { iv } else { blocks[i - 1] };
+        let last = i == blocks.len() - 1;
+
+        // Number of padding bytes within the recovered block tail `known`.
+        let padding = |known: &[u8]| match known.last() {
+            Some(&pad) if last => (pad as usize).min(known.len()),
+            _ => 0,
+        };
+
+        let mut order = |known: &[u8]| {
+            let data = &known[..known.len() - padding(known)];
+            let mut right = data.iter().chain(suffix.iter()).copied();
+            let next = right.next();
+            // Learn the byte recovered by the previous step.
+            if let Some(byte) = next.filter(|_| !data.is_empty()) {
+                prior.observe(byte, right.next());
+            }
+            let ranking = prior.ranking(next);
+            match known.last() {
+                // Last byte of the message is the padding length.
+                None if last => promote(&(1..=block_size as u8).collect::<Vec<_>>(), ranking),
+                // Still within the padding, the value is known.
+                Some(&pad) if last && known.len() < pad as usize => promote(&[pad], ranking),
+                _ => ranking,
+            }
+        };
+
+        let (plain, block_stats) = attack_block(oracle, i, prev, blocks[i], Some(&mut order));
+
+        let data = &plain[..plain.len() - padding(&plain)];
+        if let Some(&byte) = data.first() {
+            let next = data.get(1).or(suffix.first()).copied();
+            prior.observe(byte, next);
+        }
+        suffix.splice(0..0, data.iter().copied());
+        plain_blocks[i] = plain;
+        stats.blocks.push(block_stats);
+    }
+
+    stats.blocks.reverse();
+    stats.elapsed = start.elapsed();
+    (strip_padding(plain_blocks.concat(), block_size), stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{attack_with_stats, encrypt, CbcOracle};
+    use aes::Aes128;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+
+    const KEY: [u8; 16] = [0x42; 16];
+    const IV: [u8; 16] = [0x24; 16];
+
+    fn run(plaintext: &[u8], prior: &mut Prior) -> (usize, usize) {
+        let ciphertext = encrypt::<Aes128>(&KEY, &IV, plaintext);
+        let oracle = CbcOracle::<Aes128>::new(&KEY);
+
+        let (recovered, stats) = attack_with_prior(&oracle, 16, &IV, &ciphertext, prior);
+        assert_eq!(recovered, plaintext);
+        let (_, baseline) = attack_with_stats(&oracle, 16, &IV, &ciphertext);
+        (stats.total_queries(), baseline.total_queries())
+    }
+
+    #[test]
+    fn json_prior_saves_queries() {
+        let plaintext =
+            br#"{"id":31337,"name":"Mallory","email":"mallory@evil.com","admin":false}"#;
+        let (queries, baseline) = run(plaintext, &mut Prior::json());
+        // Fewer than a quarter of the uniform order queries.
+        assert!(4 * queries < baseline);
+    }
+
+    #[test]
+    fn wrong_prior_is_still_correct() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let plaintext: Vec<u8> = (0..45).map(|_| rng.gen()).collect();
+        run(&plaintext, &mut Prior::json());
+        // Full padding block.
+        run(b"exactly sixteen!", &mut Prior::uniform());
+    }
+
+    #[test]
+    fn model_learns_from_observations() {
+        let mut prior = Prior::uniform();
+        assert_eq!(prior.ranking(Some(b'u'))[0], 0);
+        prior.observe(b'q', Some(b'u'));
+        assert_eq!(prior.ranking(Some(b'u'))[0], b'q');
+        assert_eq!(prior.ranking(None)[0], b'q');
+    }
+}
@@ -0,0 +1,107 @@
+//! Padding oracle reachable over TCP.
+//!
+//! Line based protocol, one query per line:
+//!
+//! ```text
+//! -> <hex(iv)>:<hex(ct)>\n
+//! <- 1\n   (valid padding)
+//! <- 0\n   (invalid padding)
+//! ```
+//!
+//! Any other answer is treated as an invalid padding. Adapting the attack to a
+//! real service (e.g. an HTTP endpoint returning distinct status codes) only
+//! requires a different [`PaddingOracle`] implementation.
+
+use crate::PaddingOracle;
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    sync::Mutex,
+    thread,
+};
+
+/// Client side of the TCP padding oracle.
+///
+/// Queries are serialized over a single connection.
+pub struct RemoteOracle {
+    conn: Mutex<(BufReader<TcpStream>, TcpStream)>,
+}
+
+impl RemoteOracle {
+    /// Connect to the oracle listening at `addr`.
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+        let reader = BufReader::new(stream.try_clone()?);
+        Ok(RemoteOracle {
+            conn: Mutex::new((reader, stream)),
+        })
+    }
+
+    fn query(&self, iv: &[u8], ct: &[u8]) -> io::Result<bool> {
+        let mut conn = self.conn.lock().unwrap();
+        let (reader, writer) = &mut *conn;
+        writeln!(writer, "{}:{}", hex::encode(iv), hex::encode(ct))?;
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        Ok(line.trim() == "1")
+    }
+}
+
+impl PaddingOracle for RemoteOracle {
+    // Connection errors are fatal, there is no way to go on with the attack.
+    fn check(&self, iv: &[u8], ct: &[u8]) -> bool {
+        self.query(iv, ct).expect("Oracle connection failure")
+    }
+}
+
+fn handle<O: PaddingOracle>(oracle: &O, stream: TcpStream) -> io::Result<()> {
+    // One tiny packet per query, don't wait to coalesce them.
+    stream.set_nodelay(true)?;
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        let valid = line
+            .trim()
+            .split_once(':')
+            .and_then(|(iv, ct)| Some((hex::decode(iv).ok()?, hex::decode(ct).ok()?)))
+            .is_some_and(|(iv, ct)| oracle.check(&iv, &ct));
+        writeln!(writer, "{}", valid as u8)?;
+    }
+    Ok(())
+}
+
+/// Expose `oracle` over TCP.
+///
+/// Each connection is served by a dedicated thread. Never returns unless
+/// `accept` fails.
+pub fn serve<O: PaddingOracle + Sync>(listener: TcpListener, oracle: &O) -> io::Result<()> {
+    thread::scope(|s| loop {
+        let (stream, _) = listener.accept()?;
+        s.spawn(move || handle(oracle, stream));
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{attack, encrypt, CbcOracle};
+    use aes::Aes128;
+
+    #[test]
+    fn remote_attack_works() {
+        let key = [0x42; 16];
+        let iv = [0x24; 16];
+        let plaintext = b"remote oracles leak too";
+        let ciphertext = encrypt::<Aes128>(&key, &iv, plaintext);
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || serve(listener, &CbcOracle::<Aes128>::new(&key)));
+
+        let oracle = RemoteOracle::connect(addr).unwrap();
+        assert_eq!(attack(&oracle, 16, &iv, &ciphertext), plaintext);
+    }
+}
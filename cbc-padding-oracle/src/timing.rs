@@ -0,0 +1,217 @@
+//! Timing based padding oracle.
+//!
+//! Real systems often don't return a distinguishable padding error, but take
+//! measurably longer when the padding is valid. For example in MAC-then-encrypt
+//! constructions the MAC is computed over the unpadded message only when the
+//! padding is correct (Lucky 13 style leak).
+//!
+//! A [`TimingOracle`] returns a latency sample instead of a boolean. The
+//! [`TimingAdapter`] turns it back into a [`PaddingOracle`] by classifying the
+//! samples statistically:
+//! 1. calibration: the median latency of a surely valid query (the original
+//!    ciphertext) and of (almost surely) invalid queries (random tampering of
+//!    the last block) are measured, the decision threshold is their midpoint;
+//! 2. classification: each query is repeated and the sample median compared
+//!    against the threshold. When the samples mean is too close to the threshold
+//!    (w.r.t. its standard error) more samples are taken.
+
+use crate::{CbcOracle, PaddingOracle};
+use cbc::cipher::{BlockCipher, BlockDecryptMut, KeyInit};
+use group_op_timing_attack::stats::{mean, variance};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use rand_distr::{Distribution, Normal};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Mutex,
+};
+
+/// Padding oracle leaking through response time.
+pub trait TimingOracle {
+    /// Returns the response latency for the given query.
+    fn check(&self, iv: &[u8], ct: &[u8]) -> f64;
+}
+
+/// Simulated timing oracle for the block cipher `C`.
+///
+/// Every response takes `Normal(mu, sigma)` time units, plus `valid_delay`
+/// when the padding is valid.
+pub struct SimulatedTimingOracle<C> {
+    oracle: CbcOracle<C>,
+    valid_delay: f64,
+    noise: Normal<f64>,
+    rng: Mutex<ChaCha20Rng>,
+}
+
+impl<C> SimulatedTimingOracle<C>
+where
+    C: BlockCipher + BlockDecryptMut + KeyInit,
+{
+    pub fn new(key: &[u8], valid_delay: f64, mu: f64, sigma: f64, seed: u64) -> Self {
+        SimulatedTimingOracle {
+            oracle: CbcOracle::new(key),
+            valid_delay,
+            noise: Normal::new(mu, sigma).unwrap(),
+            rng: Mutex::new(ChaCha20Rng::seed_from_u64(seed)),
+        }
+    }
+}
+
+impl<C> TimingOracle for SimulatedTimingOracle<C>
+where
+    C: BlockCipher + BlockDecryptMut + KeyInit,
+{
+    fn check(&self, iv: &[u8], ct: &[u8]) -> f64 {
+        let mut delay = self.noise.sample(&mut *self.rng.lock().unwrap());
+        if self.oracle.check(iv, ct) {
+            delay += self.valid_delay;
+        }
+        delay
+    }
+}
+
+/// Median of `samples` (sorts in place), NaN if there are none.
+pub fn median(samples: &mut [f64]) -> f64 {
+    samples.sort_by(|a, b| a.total_cmp(b));
+    match samples.len() {
+        0 => f64::NAN,
+        n if n.is_multiple_of(2) => (samples[n / 2 - 1] + samples[n / 2]) / 2.0,
+        n => samples[n / 2],
+    }
+}
+
+/// Classification parameters.
+#[derive(Debug, Clone, Copy)]
+pub struct TimingConfig {
+    /// Samples per query, at least one is always taken.
+    pub repeats: usize,
+    /// Max samples per query when the decision is uncertain.
+    pub max_repeats: usize,
+    /// Samples used by the calibration step.
+    pub calibration: usize,
+    /// Min distance of the samples mean from the threshold, in standard
+    /// errors, to accept a decision without further samples.
+    pub confidence: f64,
+}
+
+impl Default for TimingConfig {
+    fn default() -> Self {
+        TimingConfig {
+            repeats: 5,
+            max_repeats: 50,
+            calibration: 200,
+            confidence: 4.0,
+        }
+    }
+}
+
+/// Boolean padding oracle built on top of a timing oracle.
+pub struct TimingAdapter<'a, O> {
+    oracle: &'a O,
+    config: TimingConfig,
+    threshold: f64,
+    samples: AtomicUsize,
+}
+
+impl<'a, O: TimingOracle> TimingAdapter<'a, O> {
+    /// Calibrate the decision threshold using the original `iv` and
+    /// `ciphertext`, which are known to be correctly padded.
+    pub fn calibrate(oracle: &'a O, config: TimingConfig, iv: &[u8], ciphertext: &[u8]) -> Self {
+        let block_size = iv.len();
+        // Tampering only needs to be arbitrary, a fixed seed keeps the query
+        // sequence reproducible (see `transcript::Replay`).
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+
+        let mut valid: Vec<_> = (0..config.calibration)
+            .map(|_| oracle.check(iv, ciphertext))
+            .collect();
+
+        // Random last block "iv" tampering. Valid with probability ≈ 1/256.
+        let curr = &ciphertext[ciphertext.len() - block_size..];
+        let mut prev = vec![0; block_size];
+        let mut invalid: Vec<_> = (0..config.calibration)
+            .map(|_| {
+                rng.fill(prev.as_mut_slice());
+                oracle.check(&prev, curr)
+            })
+            .collect();
+
+        let threshold = (median(&mut valid) + median(&mut invalid)) / 2.0;
+        TimingAdapter {
+            oracle,
+            config,
+            threshold,
+            samples: AtomicUsize::new(2 * config.calibration),
+        }
+    }
+
+    /// Decision threshold.
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// Total number of timing samples collected so far (calibration included).
+    pub fn samples(&self) -> usize {
+        self.samples.load(Ordering::Relaxed)
+    }
+}
+
+impl<O: TimingOracle> PaddingOracle for TimingAdapter<'_, O> {
+    fn check(&self, iv: &[u8], ct: &[u8]) -> bool {
+        // At least one sample to decide on
+        let mut samples: Vec<_> = (0..self.config.repeats.max(1))
+            .map(|_| self.oracle.check(iv, ct))
+            .collect();
+        // Repeat the query while the decision is uncertain, i.e. while the
+        // threshold is too close to the samples mean.
+        while samples.len() < self.config.max_repeats {
+            let stderr = (variance(&samples) / samples.len() as f64).sqrt();
+            if (mean(&samples) - self.threshold).abs() > self.config.confidence * stderr {
+                break;
+            }
+            samples.push(self.oracle.check(iv, ct));
+        }
+        self.samples.fetch_add(samples.len(), Ordering::Relaxed);
+        median(&mut samples) > self.threshold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{attack_parallel, encrypt};
+    use aes::Aes128;
+
+    #[test]
+    fn timing_attack_works() {
+        let key = [0x42; 16];
+        let iv = [0x24; 16];
+        let plaintext = b"timing leaks everything";
+        let ciphertext = encrypt::<Aes128>(&key, &iv, plaintext);
+
+        // Valid padding costs 20 more time units, noise σ = 10.
+        let oracle = SimulatedTimingOracle::<Aes128>::new(&key, 20.0, 1000.0, 10.0, 0);
+        let adapter = TimingAdapter::calibrate(&oracle, TimingConfig::default(), &iv, &ciphertext);
+
+        let (recovered, _) = attack_parallel(&adapter, 16, &iv, &ciphertext);
+        assert_eq!(recovered, plaintext);
+    }
+
+    #[test]
+    fn zero_repeats_takes_one_sample() {
+        let key = [0x42; 16];
+        let iv = [0x24; 16];
+        let ciphertext = encrypt::<Aes128>(&key, &iv, b"one is enough");
+        let oracle = SimulatedTimingOracle::<Aes128>::new(&key, 20.0, 1000.0, 1.0, 0);
+        let config = TimingConfig {
+            repeats: 0,
+            max_repeats: 0,
+            ..Default::default()
+        };
+        let adapter = TimingAdapter::calibrate(&oracle, config, &iv, &ciphertext);
+        let samples = adapter.samples();
+        assert!(adapter.check(&iv, &ciphertext));
+        assert_eq!(adapter.samples(), samples + 1);
+        assert!(median(&mut []).is_nan());
+    }
+}
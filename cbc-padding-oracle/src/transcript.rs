@@ -0,0 +1,256 @@
+//! Oracle transcript recording and replay.
+//!
+//! [`Recorder`] wraps any [`PaddingOracle`] or [`TimingOracle`] and logs every
+//! query with its response, one per line:
+//!
+//! ```text
+//! <hex(iv)>:<hex(ct)> <response>
+//! ```
+//!
+//! where the response is `1`/`0` for padding oracles and the latency sample
+//! for timing oracles. Empty lines and lines starting with `#` are ignored.
+//!
+//! [`Replay`] answers queries from a transcript, without the live oracle. The
+//! attacks are deterministic functions of the oracle answers, thus re-running
+//! an attack against the replay issues exactly the recorded queries. Handy to
+//! debug a flaky remote session or to turn it into a regression test.
+//!
+//! Queries are matched by content rather than by position, so a transcript
+//! captured by [`attack_parallel`](crate::attack_parallel) replays fine even if
+//! threads interleave differently. Repeated queries (e.g. timing samples) are
+//! answered in their recorded order.
+
+use crate::{timing::TimingOracle, PaddingOracle};
+use std::{
+    collections::{HashMap, VecDeque},
+    fs::File,
+    io::{self, BufRead, BufReader, Write},
+    path::Path,
+    str::FromStr,
+    sync::Mutex,
+};
+
+/// Oracle response which can be stored in a transcript.
+pub trait Response: Sized {
+    fn encode(&self) -> String;
+    fn decode(s: &str) -> Option<Self>;
+}
+
+impl Response for bool {
+    fn encode(&self) -> String {
+        (*self as u8).to_string()
+    }
+
+    fn decode(s: &str) -> Option<Self> {
+        match s {
+            "1" => Some(true),
+            "0" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+impl Response for f64 {
+    // Rust float formatting is lossless, replayed samples are bit identical.
+    fn encode(&self) -> String {
+        self.to_string()
+    }
+
+    fn decode(s: &str) -> Option<Self> {
+        f64::from_str(s).ok()
+    }
+}
+
+/// Oracle middleware writing every query/response to `W`.
+pub struct Recorder<O, W> {
+    oracle: O,
+    writer: Mutex<W>,
+}
+
+impl<O, W: Write> Recorder<O, W> {
+    pub fn new(oracle: O, writer: W) -> Self {
+        Recorder {
+            oracle,
+            writer: Mutex::new(writer),
+        }
+    }
+
+    /// Flush and return the underlying writer.
+    pub fn into_inner(self) -> io::Result<W> {
+        let mut writer = self.writer.into_inner().unwrap();
+        writer.flush()?;
+        Ok(writer)
+    }
+
+    // Write errors are fatal, a partial transcript is useless for replay.
+    fn record<R: Response>(&self, iv: &[u8], ct: &[u8], response: &R) {
+        let mut writer = self.writer.lock().unwrap();
+        writeln!(
+            writer,
+            "{}:{} {}",
+            hex::encode(iv),
+            hex::encode(ct),
+            response.encode()
+        )
+        .expect("Transcript write failure");
+    }
+}
+
+impl<O: PaddingOracle, W: Write> PaddingOracle for Recorder<O, W> {
+    fn check(&self, iv: &[u8], ct: &[u8]) -> bool {
+        let valid = self.oracle.check(iv, ct);
+        self.record(iv, ct, &valid);
+        valid
+    }
+}
+
+impl<O: TimingOracle, W: Write> TimingOracle for Recorder<O, W> {
+    fn check(&self, iv: &[u8], ct: &[u8]) -> f64 {
+        let latency = self.oracle.check(iv, ct);
+        self.record(iv, ct, &latency);
+        latency
+    }
+}
+
+type Query = (Vec<u8>, Vec<u8>);
+
+/// Oracle answering from a recorded transcript.
+///
+/// A query missing from the transcript (or issued more times than recorded)
+/// means the attack diverged from the captured session and is fatal.
+pub struct Replay<R> {
+    answers: Mutex<HashMap<Query, VecDeque<R>>>,
+}
+
+impl<R: Response> Replay<R> {
+    /// Load the transcript from `reader`.
+    pub fn from_reader<B: BufRead>(reader: B) -> io::Result<Self> {
+        let mut answers: HashMap<_, VecDeque<_>> = HashMap::new();
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let entry = line.split_once(' ').and_then(|(query, response)| {
+                let (iv, ct) = query.split_once(':')?;
+                let query = (hex::decode(iv).ok()?, hex::decode(ct).ok()?);
+                Some((query, R::decode(response.trim())?))
+            });
+            let Some((query, response)) = entry else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Malformed transcript line {}", i + 1),
+                ));
+            };
+            answers.entry(query).or_default().push_back(response);
+        }
+        Ok(Replay {
+            answers: Mutex::new(answers),
+        })
+    }
+
+    /// Load the transcript file at `path`.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Number of recorded answers not consumed yet.
+    ///
+    /// Zero after a faithful replay of the whole session.
+    pub fn remaining(&self) -> usize {
+        self.answers
+            .lock()
+            .unwrap()
+            .values()
+            .map(VecDeque::len)
+            .sum()
+    }
+
+    fn answer(&self, iv: &[u8], ct: &[u8]) -> R {
+        self.answers
+            .lock()
+            .unwrap()
+            .get_mut(&(iv.to_vec(), ct.to_vec()))
+            .and_then(VecDeque::pop_front)
+            .unwrap_or_else(|| {
+                panic!(
+                    "Query {}:{} not in transcript",
+                    hex::encode(iv),
+                    hex::encode(ct)
+                )
+            })
+    }
+}
+
+impl PaddingOracle for Replay<bool> {
+    fn check(&self, iv: &[u8], ct: &[u8]) -> bool {
+        self.answer(iv, ct)
+    }
+}
+
+impl TimingOracle for Replay<f64> {
+    fn check(&self, iv: &[u8], ct: &[u8]) -> f64 {
+        self.answer(iv, ct)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        attack, attack_parallel, encrypt,
+        timing::{SimulatedTimingOracle, TimingAdapter, TimingConfig},
+        CbcOracle,
+    };
+    use aes::Aes128;
+
+    const KEY: [u8; 16] = [0x42; 16];
+    const IV: [u8; 16] = [0x24; 16];
+
+    #[test]
+    fn record_and_replay() {
+        let plaintext = b"captured once, replayed forever";
+        let ciphertext = encrypt::<Aes128>(&KEY, &IV, plaintext);
+
+        let recorder = Recorder::new(CbcOracle::<Aes128>::new(&KEY), Vec::new());
+        let (recorded, _) = attack_parallel(&recorder, 16, &IV, &ciphertext);
+        let transcript = recorder.into_inner().unwrap();
+
+        let replay = Replay::<bool>::from_reader(transcript.as_slice()).unwrap();
+        let (replayed, _) = attack_parallel(&replay, 16, &IV, &ciphertext);
+        assert_eq!(recorded, plaintext);
+        assert_eq!(replayed, plaintext);
+        assert_eq!(replay.remaining(), 0);
+    }
+
+    #[test]
+    fn replay_timing_session() {
+        let plaintext = b"noisy";
+        let ciphertext = encrypt::<Aes128>(&KEY, &IV, plaintext);
+        let oracle = SimulatedTimingOracle::<Aes128>::new(&KEY, 20.0, 1000.0, 10.0, 0);
+
+        let recorder = Recorder::new(oracle, Vec::new());
+        let adapter =
+            TimingAdapter::calibrate(&recorder, TimingConfig::default(), &IV, &ciphertext);
+        let recorded = attack(&adapter, 16, &IV, &ciphertext);
+        let threshold = adapter.threshold();
+        let transcript = recorder.into_inner().unwrap();
+        assert_eq!(recorded, plaintext);
+
+        let replay = Replay::<f64>::from_reader(transcript.as_slice()).unwrap();
+        let adapter = TimingAdapter::calibrate(&replay, TimingConfig::default(), &IV, &ciphertext);
+        assert_eq!(adapter.threshold(), threshold);
+        assert_eq!(attack(&adapter, 16, &IV, &ciphertext), recorded);
+        assert_eq!(replay.remaining(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "not in transcript")]
+    fn diverging_query_panics() {
+        let transcript = "# comment\n\n0011:2233 1\n";
+        let replay = Replay::<bool>::from_reader(transcript.as_bytes()).unwrap();
+        assert!(PaddingOracle::check(&replay, &[0x00, 0x11], &[0x22, 0x33]));
+        PaddingOracle::check(&replay, &[0x00, 0x11], &[0x22, 0x33]);
+    }
+}
@@ -0,0 +1,115 @@
+//! Attacks on the chunked encryption schemes.
+//!
+//! The manipulations don't need the key: an attacker with write access to the
+//! encrypted file (a cloud storage, a backup) just moves the chunks around.
+
+use crate::{EncryptedFile, Scheme};
+
+/// File manipulation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tamper {
+    /// Swap the first two chunks.
+    Reorder,
+    /// Drop the last chunk.
+    Truncate,
+    /// Repeat the first chunk.
+    Duplicate,
+    /// Replace the first chunk with the first chunk of another file encrypted
+    /// with the same key.
+    Splice,
+}
+
+impl Tamper {
+    pub const ALL: [Tamper; 4] = [
+        Tamper::Reorder,
+        Tamper::Truncate,
+        Tamper::Duplicate,
+        Tamper::Splice,
+    ];
+
+    /// Tamper with `file`, using `other` as the source of foreign chunks.
+    ///
+    /// Files are expected to have at least two chunks.
+    pub fn apply(&self, file: &EncryptedFile, other: &EncryptedFile) -> EncryptedFile {
+        let mut file = file.clone();
+        match self {
+            Tamper::Reorder => file.chunks.swap(0, 1),
+            Tamper::Truncate => {
+                file.chunks.pop();
+            }
+            Tamper::Duplicate => file.chunks.insert(1, file.chunks[0].clone()),
+            Tamper::Splice => file.chunks[0] = other.chunks[0].clone(),
+        }
+        file
+    }
+}
+
+/// Check if the tampered file decrypts.
+pub fn undetected(scheme: Scheme, key: &crate::Key, tampered: &EncryptedFile) -> bool {
+    scheme.decrypt(key, tampered).is_ok()
+}
+
+/// Keystream reuse: recover the chunks of a [`Scheme::FixedNonce`] file from
+/// the known plaintext of one of them.
+///
+/// All the chunks are encrypted with the same keystream, so each recovered
+/// chunk is as long as the shorter between it and the known one.
+pub fn keystream_reuse(file: &EncryptedFile, index: usize, known: &[u8]) -> Vec<Vec<u8>> {
+    // The ciphertext is followed by the 16 bytes tag
+    let keystream: Vec<u8> = file.chunks[index]
+        .data
+        .iter()
+        .zip(known)
+        .map(|(c, p)| c ^ p)
+        .collect();
+    file.chunks
+        .iter()
+        .map(|chunk| {
+            let ciphertext = &chunk.data[..chunk.data.len() - 16];
+            ciphertext
+                .iter()
+                .zip(&keystream)
+                .map(|(c, k)| c ^ k)
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn detection_matrix() {
+        let key = [1; 32];
+        let plaintext: Vec<u8> = (0..100).collect();
+        let expected = [
+            // Reorder, truncate, duplicate, splice
+            (Scheme::FixedNonce, [true, true, true, false]),
+            (Scheme::RandomNonce, [true, true, true, true]),
+            (Scheme::CounterNonce, [false, true, false, true]),
+            (Scheme::Stream, [false, false, false, false]),
+        ];
+        for (scheme, undetected_by) in expected {
+            let file = scheme.encrypt(&mut OsRng, &key, &plaintext, 16);
+            let other = scheme.encrypt(&mut OsRng, &key, &[0xff; 100], 16);
+            for (tamper, expected) in Tamper::ALL.iter().zip(undetected_by) {
+                let tampered = tamper.apply(&file, &other);
+                assert_eq!(
+                    undetected(scheme, &key, &tampered),
+                    expected,
+                    "{scheme:?} {tamper:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn fixed_nonce_keystream_reuse() {
+        let plaintext = b"PDF-1.7 header, then the secret report contents...";
+        let file = Scheme::FixedNonce.encrypt(&mut OsRng, &[2; 32], plaintext, 16);
+        let chunks = keystream_reuse(&file, 0, &plaintext[..16]);
+        assert_eq!(chunks.concat(), plaintext);
+    }
+}
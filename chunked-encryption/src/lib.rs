@@ -0,0 +1,172 @@
+//! Chunked file encryption.
+//!
+//! Large files are encrypted in chunks, each one sealed by an AEAD, so that
+//! they can be processed in constant memory and decrypted in a streaming
+//! fashion. Authenticating every chunk is not enough to authenticate the file:
+//! - [`Scheme::FixedNonce`]: the same nonce for every chunk. With a stream
+//!   cipher based AEAD (GCM, ChaCha20-Poly1305) all the chunks share the same
+//!   keystream, one known chunk reveals all the others (and with GCM the
+//!   authentication key too, see the `gcm-forbidden` crate);
+//! - [`Scheme::RandomNonce`]: a random nonce stored with each chunk. Chunks are
+//!   confidential but not bound to their position (nor to their file): they
+//!   can be reordered, duplicated, dropped or spliced from another file;
+//! - [`Scheme::CounterNonce`]: the chunk index as nonce. Reordering is
+//!   detected, but dropping the tail of the file is not, nor splicing chunks
+//!   from another file encrypted with the same key.
+//!
+//! The fix is the STREAM construction (Hoang, Reyhanitabar, Rogaway and Vizár,
+//! "Online authenticated-encryption and its nonce-reuse misuse-resistance"),
+//! [`Scheme::Stream`]: the nonce is `prefix || index || last`, with a random
+//! per file prefix, the chunk index and a flag set for the last chunk only.
+
+use aes_gcm::{aead::Aead, Aes256Gcm, KeyInit};
+use rand::{CryptoRng, RngCore};
+
+pub mod attacks;
+
+/// AES-256-GCM key.
+pub type Key = [u8; 32];
+
+/// Nonce length.
+pub const NONCE_LEN: usize = 12;
+
+/// Chunk encryption scheme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheme {
+    /// Per file random nonce, reused for every chunk.
+    FixedNonce,
+    /// Per chunk random nonce, stored with the chunk.
+    RandomNonce,
+    /// Chunk index as nonce.
+    CounterNonce,
+    /// STREAM: per file random prefix, chunk index and last chunk flag.
+    Stream,
+}
+
+/// Encrypted chunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+    /// Stored nonce, only for [`Scheme::RandomNonce`].
+    pub nonce: Option<[u8; NONCE_LEN]>,
+    /// Ciphertext and tag.
+    pub data: Vec<u8>,
+}
+
+/// Encrypted file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptedFile {
+    /// Random per file header: the fixed nonce or the STREAM prefix.
+    pub header: [u8; NONCE_LEN],
+    pub chunks: Vec<Chunk>,
+}
+
+/// Decryption failures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The chunk with the given index doesn't authenticate.
+    Chunk(usize),
+    /// The file has no chunks.
+    Empty,
+}
+
+impl Scheme {
+    // Nonce of the `index`-th chunk, if derived.
+    fn nonce(&self, header: &[u8; NONCE_LEN], index: usize, last: bool) -> Option<[u8; 12]> {
+        match self {
+            Scheme::FixedNonce => Some(*header),
+            Scheme::RandomNonce => None,
+            Scheme::CounterNonce => {
+                let mut nonce = [0; NONCE_LEN];
+                nonce[4..].copy_from_slice(&(index as u64).to_be_bytes());
+                Some(nonce)
+            }
+            Scheme::Stream => {
+                let mut nonce = [0; NONCE_LEN];
+                nonce[..7].copy_from_slice(&header[..7]);
+                nonce[7..11].copy_from_slice(&(index as u32).to_be_bytes());
+                nonce[11] = last as u8;
+                Some(nonce)
+            }
+        }
+    }
+
+    /// Encrypt the `plaintext` in chunks of `chunk_size` bytes.
+    ///
+    /// There is always at least one chunk, possibly empty.
+    pub fn encrypt<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        key: &Key,
+        plaintext: &[u8],
+        chunk_size: usize,
+    ) -> EncryptedFile {
+        let cipher = Aes256Gcm::new(key.into());
+        let mut header = [0; NONCE_LEN];
+        rng.fill_bytes(&mut header);
+        let count = plaintext.len().div_ceil(chunk_size).max(1);
+        let chunks = (0..count)
+            .map(|i| {
+                let end = ((i + 1) * chunk_size).min(plaintext.len());
+                let chunk = &plaintext[i * chunk_size..end];
+                let (nonce, stored) = match self.nonce(&header, i, i + 1 == count) {
+                    Some(nonce) => (nonce, None),
+                    None => {
+                        let mut nonce = [0; NONCE_LEN];
+                        rng.fill_bytes(&mut nonce);
+                        (nonce, Some(nonce))
+                    }
+                };
+                Chunk {
+                    nonce: stored,
+                    data: cipher.encrypt(&nonce.into(), chunk).unwrap(),
+                }
+            })
+            .collect();
+        EncryptedFile { header, chunks }
+    }
+
+    /// Decrypt and authenticate every chunk.
+    pub fn decrypt(&self, key: &Key, file: &EncryptedFile) -> Result<Vec<u8>, Error> {
+        if file.chunks.is_empty() {
+            return Err(Error::Empty);
+        }
+        let cipher = Aes256Gcm::new(key.into());
+        let mut plaintext = Vec::new();
+        for (i, chunk) in file.chunks.iter().enumerate() {
+            let last = i + 1 == file.chunks.len();
+            let nonce = self
+                .nonce(&file.header, i, last)
+                .or(chunk.nonce)
+                .ok_or(Error::Chunk(i))?;
+            let data = cipher
+                .decrypt(&nonce.into(), &chunk.data[..])
+                .map_err(|_| Error::Chunk(i))?;
+            plaintext.extend_from_slice(&data);
+        }
+        Ok(plaintext)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn round_trip() {
+        let key = [7; 32];
+        for scheme in [
+            Scheme::FixedNonce,
+            Scheme::RandomNonce,
+            Scheme::CounterNonce,
+            Scheme::Stream,
+        ] {
+            for len in [0, 1, 16, 100] {
+                let plaintext = vec![0x42; len];
+                let file = scheme.encrypt(&mut OsRng, &key, &plaintext, 16);
+                assert_eq!(file.chunks.len(), len.div_ceil(16).max(1));
+                assert_eq!(scheme.decrypt(&key, &file), Ok(plaintext));
+            }
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use chunked_encryption::{
+    attacks::{keystream_reuse, undetected, Tamper},
+    Scheme,
+};
+use rand::{rngs::OsRng, RngCore};
+
+fn main() {
+    let mut key = [0; 32];
+    OsRng.fill_bytes(&mut key);
+    let chunk_size = 32;
+    let report = b"Q3 report. Revenue: 4.2M EUR. Layoffs planned for November. \
+        Acquisition target: ACME Corp, offer 12M EUR, keep it confidential.";
+    let other = b"Canteen menu: pasta on Monday, fish on Friday, pizza on Saturday.";
+
+    // Every report starts with the same well known header
+    let file = Scheme::FixedNonce.encrypt(&mut OsRng, &key, report, chunk_size);
+    let chunks = keystream_reuse(&file, 0, &report[..chunk_size]);
+    println!("Fixed nonce, recovered from the first {chunk_size} bytes:");
+    println!("  {}", String::from_utf8_lossy(&chunks.concat()));
+
+    println!("Tampering undetected:");
+    print!("  {:<14}", "");
+    Tamper::ALL
+        .iter()
+        .for_each(|t| print!("{:>10}", format!("{t:?}")));
+    println!();
+    for scheme in [
+        Scheme::FixedNonce,
+        Scheme::RandomNonce,
+        Scheme::CounterNonce,
+        Scheme::Stream,
+    ] {
+        let file = scheme.encrypt(&mut OsRng, &key, report, chunk_size);
+        let foreign = scheme.encrypt(&mut OsRng, &key, other, chunk_size);
+        print!("  {:<14}", format!("{scheme:?}"));
+        for tamper in Tamper::ALL {
+            let tampered = tamper.apply(&file, &foreign);
+            print!("{:>10}", undetected(scheme, &key, &tampered));
+        }
+        println!();
+    }
+
+    let file = Scheme::CounterNonce.encrypt(&mut OsRng, &key, report, chunk_size);
+    let truncated = Tamper::Truncate.apply(&file, &file);
+    let plaintext = Scheme::CounterNonce.decrypt(&key, &truncated).unwrap();
+    println!("Counter nonce, truncated file decrypts to:");
+    println!("  {}", String::from_utf8_lossy(&plaintext));
+}
@@ -0,0 +1,274 @@
+//! ADFGVX cipher, substitution followed by transposition.
+//!
+//! Each plaintext symbol (26 letters and 10 digits) is replaced by its row and
+//! column in a 6×6 Polybius square, named after the letters `ADFGVX`, and the
+//! resulting symbols are then transposed via a [`Columnar`] key.
+//!
+//! The two layers are attacked separately:
+//! 1. under the correct transposition the symbols pairs are a monoalphabetic
+//!    substitution of the plaintext, thus their index of coincidence is the
+//!    English one, much higher than the one of misaligned pairs. The
+//!    transposition maximizing the pairs coincidences is found via annealing;
+//! 2. the pairs substitution is solved as in [`crate::substitution`]. With an
+//!    even width the pairs always come from the same two columns, thus the
+//!    coincidences can't tell the order of the columns pairs: it is searched
+//!    together with the substitution.
+
+use crate::{
+    anneal::{self, Config, Problem, Schedule},
+    scoring::Ngrams,
+    substitution::Alphabet,
+    transposition::Columnar,
+};
+use rand::{seq::SliceRandom, Rng};
+
+/// Ciphertext symbols names.
+pub const SYMBOLS: &str = "ADFGVX";
+
+/// Plaintext symbols: 26 letters and 10 digits.
+pub const PLAIN_SYMBOLS: usize = 36;
+
+// Transposition search parameters, enough for a few hundreds of letters.
+const TRANSPOSITION_CONFIG: Config = Config {
+    steps: 5000,
+    restarts: 8,
+    schedule: Schedule::Geometric {
+        start: 5.0,
+        end: 0.01,
+    },
+};
+
+/// ADFGVX cipher.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Adfgvx {
+    // Plaintext symbol in each square cell, row by row.
+    square: Vec<u32>,
+    // Square cell of each plaintext symbol.
+    cells: Vec<u32>,
+    transposition: Columnar,
+}
+
+impl Adfgvx {
+    /// Cipher from the square content, `None` if not a permutation of
+    /// `0..36`.
+    pub fn new(square: Vec<u32>, transposition: Columnar) -> Option<Self> {
+        let mut cells = vec![u32::MAX; PLAIN_SYMBOLS];
+        for (cell, &x) in square.iter().enumerate() {
+            let slot = cells.get_mut(x as usize)?;
+            if *slot != u32::MAX {
+                return None;
+            }
+            *slot = cell as u32;
+        }
+        (square.len() == PLAIN_SYMBOLS).then_some(Adfgvx {
+            square,
+            cells,
+            transposition,
+        })
+    }
+
+    /// Random square and transposition of the given width.
+    pub fn random<R: Rng + ?Sized>(width: usize, rng: &mut R) -> Self {
+        let mut square: Vec<_> = (0..PLAIN_SYMBOLS as u32).collect();
+        square.shuffle(rng);
+        Self::new(square, Columnar::random(width, rng)).expect("Valid square")
+    }
+
+    pub fn transposition(&self) -> &Columnar {
+        &self.transposition
+    }
+
+    /// Encrypt symbols in `0..36`, returns symbols in `0..6`.
+    pub fn encrypt(&self, pt: &[u32]) -> Vec<u32> {
+        let fractionated: Vec<_> = pt
+            .iter()
+            .flat_map(|&x| {
+                let cell = self.cells[x as usize];
+                [cell / 6, cell % 6]
+            })
+            .collect();
+        self.transposition.encrypt(&fractionated)
+    }
+
+    pub fn decrypt(&self, ct: &[u32]) -> Vec<u32> {
+        pairs(&self.transposition.decrypt(ct))
+            .into_iter()
+            .map(|cell| self.square[cell as usize])
+            .collect()
+    }
+}
+
+/// Ciphertext symbols as `ADFGVX` letters.
+pub fn to_string(ct: &[u32]) -> String {
+    ct.iter()
+        .map(|&x| SYMBOLS.as_bytes()[x as usize] as char)
+        .collect()
+}
+
+// Square cells from the fractionated symbols.
+fn pairs(symbols: &[u32]) -> Vec<u32> {
+    symbols.chunks_exact(2).map(|p| p[0] * 6 + p[1]).collect()
+}
+
+// Transposition maximizing the pairs index of coincidence.
+struct PairsSearch<'a> {
+    ct: &'a [u32],
+    width: usize,
+}
+
+impl Problem for PairsSearch<'_> {
+    type State = Columnar;
+
+    fn random<R: Rng + ?Sized>(&self, rng: &mut R) -> Columnar {
+        Columnar::random(self.width, rng)
+    }
+
+    fn neighbor<R: Rng + ?Sized>(&self, key: &Columnar, rng: &mut R) -> Columnar {
+        key.neighbor(rng)
+    }
+
+    fn score(&self, key: &Columnar) -> f64 {
+        let pairs = pairs(&key.decrypt(self.ct));
+        let mut counts = vec![0usize; PLAIN_SYMBOLS * (PLAIN_SYMBOLS + 1)];
+        pairs.iter().for_each(|&p| counts[p as usize] += 1);
+        pairs
+            .windows(2)
+            .for_each(|w| counts[PLAIN_SYMBOLS * (1 + w[0] as usize) + w[1] as usize] += 1);
+        let coincidences: usize = counts.iter().map(|c| c * c.saturating_sub(1)).sum();
+        coincidences as f64 / pairs.len() as f64
+    }
+}
+
+// Pairs substitution and columns pairs order.
+//
+// Swapping columns pairs doesn't change the set of pairs, thus the alphabet
+// computed under the initial transposition holds for the whole search.
+struct PlaintextSearch<'a> {
+    ct: &'a [u32],
+    transposition: &'a Columnar,
+    alphabet: Alphabet,
+    ngrams: &'a Ngrams,
+}
+
+impl PlaintextSearch<'_> {
+    fn decrypt(&self, (key, subst): &(Columnar, Vec<u32>)) -> Vec<u32> {
+        self.alphabet.decrypt(&pairs(&key.decrypt(self.ct)), subst)
+    }
+}
+
+impl Problem for PlaintextSearch<'_> {
+    type State = (Columnar, Vec<u32>);
+
+    fn random<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::State {
+        (self.transposition.clone(), self.alphabet.random_key(rng))
+    }
+
+    fn neighbor<R: Rng + ?Sized>(&self, state: &Self::State, rng: &mut R) -> Self::State {
+        let (mut key, mut subst) = state.clone();
+        let pairs = key.width() / 2;
+        if key.width() % 2 == 0 && pairs > 1 && rng.gen_bool(0.1) {
+            // Swap the grid columns pairs `a` and `b`.
+            let (a, b) = (rng.gen_range(0..pairs), rng.gen_range(0..pairs));
+            let order = key
+                .order()
+                .iter()
+                .map(|&c| match c / 2 {
+                    p if p == a => 2 * b + c % 2,
+                    p if p == b => 2 * a + c % 2,
+                    _ => c,
+                })
+                .collect();
+            key = Columnar::new(order).expect("Still a permutation");
+        } else {
+            let (i, j) = (rng.gen_range(0..subst.len()), rng.gen_range(0..subst.len()));
+            subst.swap(i, j);
+        }
+        (key, subst)
+    }
+
+    fn score(&self, state: &Self::State) -> f64 {
+        self.ngrams.score(&self.decrypt(state))
+    }
+}
+
+/// ADFGVX ciphertext only attack, for a transposition of the given `width`.
+///
+/// The substitution (and columns pairs order) search is run according to
+/// `config`. Returns the transposition and the plaintext letters: the square
+/// is only recovered up to the letters actually used.
+pub fn solve<R: Rng + ?Sized>(
+    ct: &[u32],
+    width: usize,
+    ngrams: &Ngrams,
+    config: &Config,
+    rng: &mut R,
+) -> (Columnar, Vec<u32>) {
+    let search = PairsSearch { ct, width };
+    let transposition = anneal::optimize(&search, &TRANSPOSITION_CONFIG, rng).state;
+
+    let search = PlaintextSearch {
+        ct,
+        transposition: &transposition,
+        alphabet: Alphabet::new(&pairs(&transposition.decrypt(ct)), PLAIN_SYMBOLS),
+        ngrams,
+    };
+    let solution = anneal::optimize(&search, config, rng);
+    let plaintext = search.decrypt(&solution.state);
+    (solution.state.0, plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::to_nums;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const TEXT: &str = "When I wrote the following pages, or rather the bulk of them, I \
+        lived alone, in the woods, a mile from any neighbor, in a house which I had built \
+        myself, on the shore of Walden Pond, in Concord, Massachusetts, and earned my living \
+        by the labor of my hands only. I lived there two years and two months. At present I \
+        am a sojourner in civilized life again. I should not obtrude my affairs so much on \
+        the notice of my readers if very particular inquiries had not been made by my \
+        townsmen concerning my mode of life, which some would call impertinent, though they \
+        do not appear to me at all impertinent, but, considering the circumstances, very \
+        natural and pertinent.";
+
+    #[test]
+    fn encrypt_decrypt() {
+        // Classic example: square "PH0QG64MEA1YL2NOFDXKR3CVS5ZW7BJ9UTI8" and
+        // key "PRIVACY".
+        let square = "PH0QG64MEA1YL2NOFDXKR3CVS5ZW7BJ9UTI8"
+            .chars()
+            .map(|c| match c {
+                'A'..='Z' => c as u32 - 'A' as u32,
+                _ => 26 + c.to_digit(10).unwrap(),
+            })
+            .collect();
+        let cipher = Adfgvx::new(square, Columnar::from_keyword("PRIVACY")).unwrap();
+        let pt = to_nums("ATTACKAT");
+        let ct = cipher.encrypt(&pt);
+        assert_eq!(to_string(&ct), "XDGDXVDGXGGGGGDG");
+        assert_eq!(cipher.decrypt(&ct), pt);
+    }
+
+    #[test]
+    fn solve_works() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let pt = to_nums(TEXT);
+        for width in [6, 7] {
+            let cipher = Adfgvx::random(width, &mut rng);
+            let ct = cipher.encrypt(&pt);
+            let config = Config {
+                steps: 30000,
+                restarts: 4,
+                schedule: Schedule::Geometric {
+                    start: 20.0,
+                    end: 0.2,
+                },
+            };
+            let (_, found) = solve(&ct, width, &Ngrams::english(3), &config, &mut rng);
+            let correct = found.iter().zip(&pt).filter(|(a, b)| a == b).count();
+            assert!(correct * 100 > 95 * pt.len());
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! Stochastic local search: hill climbing and simulated annealing.
+//!
+//! Most ciphertext only attacks on classical ciphers boil down to maximizing a
+//! plaintext score over a huge key space. The key space shape and the score
+//! are described by a [`Problem`], the search strategy by a [`Config`]:
+//! - a random neighbor of the current state is scored at each step;
+//! - improvements are always accepted, a worse neighbor is accepted with
+//!   probability `exp(Δ/T)`, with the temperature `T` given by the
+//!   [`Schedule`] (zero temperature is plain hill climbing);
+//! - the search is restarted from a fresh random state a few times, to escape
+//!   the local optima the schedule couldn't.
+//!
+//! The best state ever visited is returned, not the last one.
+
+use rand::Rng;
+
+/// Search space and objective function.
+pub trait Problem {
+    type State: Clone;
+
+    /// Random starting point.
+    fn random<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::State;
+
+    /// Random small perturbation of `state`.
+    fn neighbor<R: Rng + ?Sized>(&self, state: &Self::State, rng: &mut R) -> Self::State;
+
+    /// The higher the better.
+    fn score(&self, state: &Self::State) -> f64;
+}
+
+/// Temperature schedule.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Schedule {
+    /// Zero temperature, only improvements (or ties) are accepted.
+    HillClimb,
+    /// Constant temperature.
+    Constant(f64),
+    /// Linear cooling from `start` to `end`.
+    Linear { start: f64, end: f64 },
+    /// Exponential cooling from `start` to `end`.
+    Geometric { start: f64, end: f64 },
+}
+
+impl Schedule {
+    /// Temperature at `step` out of `steps`.
+    pub fn temperature(&self, step: usize, steps: usize) -> f64 {
+        let t = step as f64 / steps.max(1) as f64;
+        match *self {
+            Schedule::HillClimb => 0.0,
+            Schedule::Constant(temp) => temp,
+            Schedule::Linear { start, end } => start + (end - start) * t,
+            Schedule::Geometric { start, end } => start * (end / start).powf(t),
+        }
+    }
+}
+
+/// Search parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Config {
+    /// Steps per run.
+    pub steps: usize,
+    /// Number of runs, each one from a random state.
+    pub restarts: usize,
+    pub schedule: Schedule,
+}
+
+/// Best state found.
+#[derive(Debug, Clone)]
+pub struct Solution<S> {
+    pub state: S,
+    pub score: f64,
+    /// Number of scored states.
+    pub evaluations: usize,
+}
+
+/// Run the search.
+pub fn optimize<P: Problem, R: Rng + ?Sized>(
+    problem: &P,
+    config: &Config,
+    rng: &mut R,
+) -> Solution<P::State> {
+    let mut best: Option<(P::State, f64)> = None;
+    let mut evaluations = 0;
+    for _ in 0..config.restarts.max(1) {
+        let mut state = problem.random(rng);
+        let mut score = problem.score(&state);
+        evaluations += 1;
+        if best.as_ref().is_none_or(|b| score > b.1) {
+            best = Some((state.clone(), score));
+        }
+        for step in 0..config.steps {
+            let next = problem.neighbor(&state, rng);
+            let next_score = problem.score(&next);
+            evaluations += 1;
+            let delta = next_score - score;
+            let temp = config.schedule.temperature(step, config.steps);
+            if delta >= 0.0 || (temp > 0.0 && rng.gen::<f64>() < (delta / temp).exp()) {
+                (state, score) = (next, next_score);
+                if best.as_ref().is_none_or(|b| score > b.1) {
+                    best = Some((state.clone(), score));
+                }
+            }
+        }
+    }
+    let (state, score) = best.expect("At least one run");
+    Solution {
+        state,
+        score,
+        evaluations,
+    }
+}
+
+/// Hill climbing, i.e. [`optimize`] with zero temperature.
+pub fn hill_climb<P: Problem, R: Rng + ?Sized>(
+    problem: &P,
+    steps: usize,
+    restarts: usize,
+    rng: &mut R,
+) -> Solution<P::State> {
+    let config = Config {
+        steps,
+        restarts,
+        schedule: Schedule::HillClimb,
+    };
+    optimize(problem, &config, rng)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+    // Sort a permutation: the score is minus the number of inversions, the
+    // neighbors are the single swaps.
+    struct Sort(usize);
+
+    impl Problem for Sort {
+        type State = Vec<usize>;
+
+        fn random<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<usize> {
+            let mut v: Vec<_> = (0..self.0).collect();
+            v.shuffle(rng);
+            v
+        }
+
+        fn neighbor<R: Rng + ?Sized>(&self, state: &Vec<usize>, rng: &mut R) -> Vec<usize> {
+            let mut v = state.clone();
+            v.swap(rng.gen_range(0..self.0), rng.gen_range(0..self.0));
+            v
+        }
+
+        fn score(&self, state: &Vec<usize>) -> f64 {
+            let inversions = (0..self.0)
+                .flat_map(|i| (i + 1..self.0).map(move |j| (i, j)))
+                .filter(|&(i, j)| state[i] > state[j])
+                .count();
+            -(inversions as f64)
+        }
+    }
+
+    // Deceptive 1D landscape: a local optimum at 20 and the global one at 60,
+    // separated by a valley.
+    struct Valley;
+
+    impl Problem for Valley {
+        type State = i64;
+
+        fn random<R: Rng + ?Sized>(&self, _rng: &mut R) -> i64 {
+            0
+        }
+
+        fn neighbor<R: Rng + ?Sized>(&self, state: &i64, rng: &mut R) -> i64 {
+            (state + rng.gen_range(-5..=5)).clamp(0, 80)
+        }
+
+        fn score(&self, &x: &i64) -> f64 {
+            let (a, b) = ((x - 20) as f64, (x - 60) as f64);
+            (10.0 - a * a / 40.0).max(0.0) + (20.0 - b * b / 10.0).max(0.0)
+        }
+    }
+
+    #[test]
+    fn hill_climb_sorts() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let solution = hill_climb(&Sort(12), 2000, 1, &mut rng);
+        assert_eq!(solution.state, (0..12).collect::<Vec<_>>());
+        assert_eq!(solution.evaluations, 2001);
+    }
+
+    #[test]
+    fn annealing_escapes_local_optimum() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let climb = hill_climb(&Valley, 5000, 1, &mut rng);
+        assert_eq!(climb.state, 20);
+
+        let config = Config {
+            steps: 20000,
+            restarts: 1,
+            schedule: Schedule::Geometric {
+                start: 20.0,
+                end: 0.01,
+            },
+        };
+        let anneal = optimize(&Valley, &config, &mut rng);
+        assert_eq!(anneal.state, 60);
+    }
+
+    #[test]
+    fn schedules() {
+        let linear = Schedule::Linear {
+            start: 10.0,
+            end: 0.0,
+        };
+        assert_eq!(linear.temperature(5, 10), 5.0);
+        let geometric = Schedule::Geometric {
+            start: 100.0,
+            end: 1.0,
+        };
+        assert!((geometric.temperature(5, 10) - 10.0).abs() < 1e-9);
+        assert_eq!(Schedule::HillClimb.temperature(3, 10), 0.0);
+    }
+}
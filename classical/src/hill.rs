@@ -0,0 +1,260 @@
+//! Hill cipher.
+//!
+//! Plaintext is split in blocks of `n` letters, each one encrypted as
+//! `c = K·p mod 26` with `K` an invertible `n×n` matrix.
+//!
+//! Being linear, the cipher falls to:
+//! - known plaintext: `n` blocks pairs give `C = K·P`, thus `K = C·P⁻¹`. When
+//!   the plaintext blocks matrix is singular modulo 26 the key is recovered
+//!   separately modulo 2 and 13 (possibly from different blocks) and combined
+//!   via CRT.
+//! - ciphertext only (2×2): each row of the decryption matrix determines every
+//!   other plaintext letter independently of the other row, thus the rows can
+//!   be found via hill climbing on the letters frequencies distance from
+//!   English.
+
+use crate::{
+    anneal::{self, Problem},
+    scoring,
+    zmod::Matrix,
+    ALPHABET,
+};
+use rand::Rng;
+use std::{cell::RefCell, collections::HashMap};
+
+// Hill climbing steps for each restart of the ciphertext only attack.
+const CLIMB_STEPS: usize = 200;
+
+/// Hill cipher instance.
+#[derive(Debug, Clone)]
+pub struct Hill {
+    key: Matrix,
+    inv: Matrix,
+}
+
+/// Known plaintext attack failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// Plaintext and ciphertext lengths differ.
+    LengthMismatch,
+    /// Not enough linearly independent blocks modulo the given prime.
+    Singular(u32),
+}
+
+impl Hill {
+    /// Returns `None` if the key is not invertible modulo 26.
+    pub fn new(key: Matrix) -> Option<Self> {
+        let inv = key.inverse(ALPHABET)?;
+        Some(Hill { key, inv })
+    }
+
+    /// Random invertible `n×n` key.
+    pub fn random<R: Rng>(n: usize, rng: &mut R) -> Self {
+        loop {
+            let data = (0..n * n).map(|_| rng.gen_range(0..ALPHABET)).collect();
+            if let Some(hill) = Hill::new(Matrix::new(n, data)) {
+                return hill;
+            }
+        }
+    }
+
+    pub fn key(&self) -> &Matrix {
+        &self.key
+    }
+
+    fn apply(m: &Matrix, text: &[u32]) -> Vec<u32> {
+        text.chunks(m.size())
+            .flat_map(|block| {
+                let mut block = block.to_vec();
+                // Pad with 'X'
+                block.resize(m.size(), 23);
+                m.mul_vec(&block, ALPHABET)
+            })
+            .collect()
+    }
+
+    /// Encrypt, padding the last block with `X`.
+    pub fn encrypt(&self, pt: &[u32]) -> Vec<u32> {
+        Self::apply(&self.key, pt)
+    }
+
+    pub fn decrypt(&self, ct: &[u32]) -> Vec<u32> {
+        Self::apply(&self.inv, ct)
+    }
+}
+
+/// Recover the `n×n` key from known plaintext.
+///
+/// Only full blocks are used.
+pub fn known_plaintext(pt: &[u32], ct: &[u32], n: usize) -> Result<Matrix, Error> {
+    if pt.len() != ct.len() {
+        return Err(Error::LengthMismatch);
+    }
+    let pairs: Vec<_> = pt.chunks_exact(n).zip(ct.chunks_exact(n)).collect();
+
+    let mut key = Matrix::new(n, vec![0; n * n]);
+    let mut modulus = 1;
+    for p in [2, 13] {
+        let k = known_plaintext_mod(&pairs, n, p).ok_or(Error::Singular(p))?;
+        key = key.crt(modulus, &k, p);
+        modulus *= p;
+    }
+    Ok(key)
+}
+
+// Key modulo the prime `p`.
+//
+// Greedily select `n` plaintext blocks linearly independent modulo `p`.
+fn known_plaintext_mod(pairs: &[(&[u32], &[u32])], n: usize, p: u32) -> Option<Matrix> {
+    let mut selected: Vec<(&[u32], &[u32])> = Vec::with_capacity(n);
+    // Row echelon basis of the selected plaintext blocks, with pivot column
+    let mut basis: Vec<(usize, Vec<u32>)> = Vec::new();
+    for &(pb, cb) in pairs {
+        let mut v: Vec<u32> = pb.iter().map(|x| x % p).collect();
+        for (col, b) in &basis {
+            let f = v[*col];
+            v.iter_mut()
+                .zip(b)
+                .for_each(|(x, y)| *x = (*x + (p - f) * y) % p);
+        }
+        let Some(col) = v.iter().position(|&x| x != 0) else {
+            continue;
+        };
+        let inv = crate::zmod::inv_mod(v[col], p)?;
+        v.iter_mut().for_each(|x| *x = *x * inv % p);
+        for (_, b) in basis.iter_mut() {
+            let f = b[col];
+            b.iter_mut()
+                .zip(&v)
+                .for_each(|(x, y)| *x = (*x + (p - f) * y) % p);
+        }
+        basis.push((col, v));
+        selected.push((pb, cb));
+        if selected.len() == n {
+            break;
+        }
+    }
+    if selected.len() < n {
+        return None;
+    }
+    let pm = Matrix::from_columns(&selected.iter().map(|s| s.0).collect::<Vec<_>>());
+    let cm = Matrix::from_columns(&selected.iter().map(|s| s.1).collect::<Vec<_>>());
+    Some(cm.mul(&pm.inverse(p)?, p))
+}
+
+// Search space of a decryption matrix row, i.e. the coefficients giving one
+// of the two letters of each plaintext block.
+//
+// Every evaluated row is cached in `scores`.
+struct RowSearch<'a> {
+    ct: &'a [u32],
+    scores: RefCell<HashMap<[u32; 2], f64>>,
+}
+
+impl Problem for RowSearch<'_> {
+    type State = [u32; 2];
+
+    fn random<R: Rng + ?Sized>(&self, rng: &mut R) -> [u32; 2] {
+        [rng.gen_range(0..ALPHABET), rng.gen_range(0..ALPHABET)]
+    }
+
+    fn neighbor<R: Rng + ?Sized>(&self, row: &[u32; 2], rng: &mut R) -> [u32; 2] {
+        let mut row = *row;
+        row[rng.gen_range(0..2)] = rng.gen_range(0..ALPHABET);
+        row
+    }
+
+    fn score(&self, row: &[u32; 2]) -> f64 {
+        *self.scores.borrow_mut().entry(*row).or_insert_with(|| {
+            let text: Vec<_> = self
+                .ct
+                .chunks_exact(2)
+                .map(|b| (row[0] * b[0] + row[1] * b[1]) % ALPHABET)
+                .collect();
+            -scoring::chi_squared(&text)
+        })
+    }
+}
+
+/// Ciphertext only attack on 2×2 keys via hill climbing.
+///
+/// Requires a few hundreds of letters of English text.
+/// Returns `None` if no invertible key was found.
+pub fn ciphertext_only_2x2<R: Rng>(ct: &[u32], restarts: usize, rng: &mut R) -> Option<Hill> {
+    // Both rows maximize the same function, but the second best row may not
+    // be a local optimum (e.g. if it differs from the best one by one entry).
+    // Thus take the best rows among all the ones evaluated while climbing.
+    let search = RowSearch {
+        ct,
+        scores: RefCell::default(),
+    };
+    anneal::hill_climb(&search, CLIMB_STEPS, restarts, rng);
+    let mut rows: Vec<_> = search.scores.into_inner().into_iter().collect();
+    rows.sort_by(|a, b| b.1.total_cmp(&a.1));
+    rows.truncate(12);
+
+    // Try pairs of the best rows, in both orders, and pick the inverse key
+    // giving the more English-like bigrams.
+    let mut best: Option<(Hill, usize)> = None;
+    for r0 in &rows {
+        for r1 in rows.iter().filter(|r| r.0 != r0.0) {
+            let inv = Matrix::new(2, vec![r0.0[0], r0.0[1], r1.0[0], r1.0[1]]);
+            let Some(key) = inv.inverse(ALPHABET) else {
+                continue;
+            };
+            let hill = Hill { key, inv };
+            let score = scoring::common_bigrams(&hill.decrypt(ct));
+            if best.as_ref().is_none_or(|b| score > b.1) {
+                best = Some((hill, score));
+            }
+        }
+    }
+    best.map(|b| b.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_nums, to_nums};
+    use rand::SeedableRng;
+
+    const TEXT: &str = "It was the best of times, it was the worst of times, it was the \
+        age of wisdom, it was the age of foolishness, it was the epoch of belief, it was the \
+        epoch of incredulity, it was the season of Light, it was the season of Darkness, it \
+        was the spring of hope, it was the winter of despair, we had everything before us, we \
+        had nothing before us, we were all going direct to Heaven, we were all going direct \
+        the other way";
+
+    #[test]
+    fn encrypt_decrypt() {
+        let hill = Hill::new(Matrix::new(3, vec![6, 24, 1, 13, 16, 10, 20, 17, 15])).unwrap();
+        let ct = hill.encrypt(&to_nums("ACT"));
+        assert_eq!(from_nums(&ct), "POH");
+        assert_eq!(from_nums(&hill.decrypt(&ct)), "ACT");
+    }
+
+    #[test]
+    fn known_plaintext_with_singular_blocks() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let hill = Hill::random(3, &mut rng);
+        // The first three blocks are singular modulo 2 (all even letters)
+        let pt = to_nums("ACEGIKMOQSUW CAT DOG ANT");
+        let ct = hill.encrypt(&pt);
+        let key = known_plaintext(&pt, &ct, 3).unwrap();
+        assert_eq!(&key, hill.key());
+
+        let pt = to_nums("AAAAAABBB");
+        let ct = hill.encrypt(&pt);
+        assert_eq!(known_plaintext(&pt, &ct, 3), Err(Error::Singular(2)));
+    }
+
+    #[test]
+    fn ciphertext_only_recovery() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        let hill = Hill::random(2, &mut rng);
+        let pt = to_nums(TEXT);
+        let ct = hill.encrypt(&pt);
+        let found = ciphertext_only_2x2(&ct, 100, &mut rng).unwrap();
+        assert_eq!(found.key(), hill.key());
+    }
+}
@@ -0,0 +1,36 @@
+//! Classical ciphers and their cryptanalysis.
+//!
+//! Texts are handled as sequences of numbers in `0..26`, one per letter of the
+//! Latin alphabet (see [`to_nums`] and [`from_nums`]).
+//!
+//! - [`zmod`]: linear algebra over Z_m (e.g. Z_26).
+//! - [`scoring`]: English plaintext scoring.
+//! - [`anneal`]: hill climbing and simulated annealing key search.
+//! - [`hill`]: Hill cipher with known plaintext and ciphertext only attacks.
+//! - [`substitution`]: monoalphabetic substitution solver.
+//! - [`transposition`]: columnar and rail fence ciphers solvers.
+//! - [`adfgvx`]: ADFGVX cipher, combined substitution and transposition solver.
+
+pub mod adfgvx;
+pub mod anneal;
+pub mod hill;
+pub mod scoring;
+pub mod substitution;
+pub mod transposition;
+pub mod zmod;
+
+/// Alphabet size.
+pub const ALPHABET: u32 = 26;
+
+/// Letters to numbers in `0..26`, ignoring case and dropping any non letter.
+pub fn to_nums(text: &str) -> Vec<u32> {
+    text.bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .map(|b| (b.to_ascii_uppercase() - b'A') as u32)
+        .collect()
+}
+
+/// Numbers in `0..26` to uppercase letters.
+pub fn from_nums(nums: &[u32]) -> String {
+    nums.iter().map(|&x| (b'A' + x as u8) as char).collect()
+}
@@ -0,0 +1,80 @@
+use classical::{
+    adfgvx::{self, Adfgvx},
+    anneal::{Config, Schedule},
+    from_nums,
+    hill::{self, Hill},
+    scoring::Ngrams,
+    to_nums,
+    transposition::{self, Columnar},
+};
+
+const TEXT: &str = "It is a truth universally acknowledged, that a single man in possession \
+    of a good fortune, must be in want of a wife. However little known the feelings or views \
+    of such a man may be on his first entering a neighbourhood, this truth is so well fixed \
+    in the minds of the surrounding families, that he is considered the rightful property of \
+    some one or other of their daughters.";
+
+fn main() {
+    let mut rng = rand::thread_rng();
+
+    // Known plaintext
+    let cipher = Hill::random(3, &mut rng);
+    let pt = to_nums(TEXT);
+    let ct = cipher.encrypt(&pt);
+    println!("Hill 3x3 key: {:?}", cipher.key().entries());
+    println!("Ciphertext: {}...", &from_nums(&ct)[..60]);
+    let known = 3 * 8;
+    match hill::known_plaintext(&pt[..known], &ct[..known], 3) {
+        Ok(key) => {
+            println!("Key from {known} known letters: {:?}", key.entries());
+            let dec = Hill::new(key).unwrap().decrypt(&ct);
+            println!("Plaintext: {}...", &from_nums(&dec)[..60]);
+        }
+        Err(err) => println!("Known plaintext attack failure: {err:?}"),
+    }
+
+    // Ciphertext only
+    let cipher = Hill::random(2, &mut rng);
+    let ct = cipher.encrypt(&pt);
+    println!("Hill 2x2 key: {:?}", cipher.key().entries());
+    match hill::ciphertext_only_2x2(&ct, 100, &mut rng) {
+        Some(found) => {
+            println!("Key from ciphertext only: {:?}", found.key().entries());
+            println!("Plaintext: {}...", &from_nums(&found.decrypt(&ct))[..60]);
+        }
+        None => println!("Ciphertext only attack failure"),
+    }
+
+    // Transpositions, ciphertext only
+    let ngrams = Ngrams::english(3);
+    let config = Config {
+        steps: 5000,
+        restarts: 4,
+        schedule: Schedule::Geometric {
+            start: 50.0,
+            end: 0.5,
+        },
+    };
+    let cipher = Columnar::random(7, &mut rng);
+    let ct = cipher.encrypt(&pt);
+    println!("Columnar key: {:?}", cipher.order());
+    let found = transposition::solve_columnar(&ct, 5..=9, &ngrams, &config, &mut rng);
+    println!("Key from ciphertext only: {:?}", found.order());
+    println!("Plaintext: {}...", &from_nums(&found.decrypt(&ct))[..60]);
+
+    let cipher = Adfgvx::random(6, &mut rng);
+    let ct = cipher.encrypt(&pt);
+    println!("ADFGVX transposition: {:?}", cipher.transposition().order());
+    println!("Ciphertext: {}...", &adfgvx::to_string(&ct)[..60]);
+    let config = Config {
+        steps: 30000,
+        restarts: 4,
+        schedule: Schedule::Geometric {
+            start: 20.0,
+            end: 0.2,
+        },
+    };
+    let (found, plain) = adfgvx::solve(&ct, 6, &ngrams, &config, &mut rng);
+    println!("Transposition from ciphertext only: {:?}", found.order());
+    println!("Plaintext: {}...", &from_nums(&plain)[..60]);
+}
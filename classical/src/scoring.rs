@@ -0,0 +1,136 @@
+//! English plaintext scoring.
+
+/// English letters relative frequencies.
+pub const ENGLISH_FREQ: [f64; 26] = [
+    0.08167, 0.01492, 0.02782, 0.04253, 0.12702, 0.02228, 0.02015, 0.06094, 0.06966, 0.00153,
+    0.00772, 0.04025, 0.02406, 0.06749, 0.07507, 0.01929, 0.00095, 0.05987, 0.06327, 0.09056,
+    0.02758, 0.00978, 0.02360, 0.00150, 0.01974, 0.00074,
+];
+
+/// Most frequent English bigrams.
+pub const COMMON_BIGRAMS: [&str; 20] = [
+    "TH", "HE", "IN", "ER", "AN", "RE", "ND", "ON", "EN", "AT", "OU", "ED", "HA", "TO", "OR", "IT",
+    "IS", "HI", "ES", "NG",
+];
+
+/// Letters count.
+pub fn counts(text: &[u32]) -> [usize; 26] {
+    let mut counts = [0; 26];
+    text.iter().for_each(|&x| counts[x as usize] += 1);
+    counts
+}
+
+/// Log-likelihood of the text under the English letters distribution.
+///
+/// The higher the better.
+pub fn monogram_score(text: &[u32]) -> f64 {
+    text.iter().map(|&x| ENGLISH_FREQ[x as usize].ln()).sum()
+}
+
+/// Chi-squared distance from the English letters distribution.
+///
+/// The lower the better.
+pub fn chi_squared(text: &[u32]) -> f64 {
+    let n = text.len() as f64;
+    counts(text)
+        .iter()
+        .zip(ENGLISH_FREQ)
+        .map(|(&c, f)| (c as f64 - n * f).powi(2) / (n * f))
+        .sum()
+}
+
+/// Index of coincidence, ≈ 0.066 for English and ≈ 0.038 for random text.
+pub fn index_of_coincidence(text: &[u32]) -> f64 {
+    let n = text.len() as f64;
+    let sum: usize = counts(text).iter().map(|c| c * c.saturating_sub(1)).sum();
+    sum as f64 / (n * (n - 1.0))
+}
+
+/// English sample text, the training set of [`Ngrams::english`].
+pub const CORPUS: &str = include_str!("corpus.txt");
+
+/// Letters n-grams log-probabilities.
+///
+/// Unlike the letters frequencies, n-grams are sensitive to the letters order,
+/// thus they can score transposition candidates too.
+#[derive(Debug, Clone)]
+pub struct Ngrams {
+    n: usize,
+    log_probs: Vec<f64>,
+}
+
+impl Ngrams {
+    /// Train the model over `text`.
+    ///
+    /// Unseen n-grams get a probability of 0.01/N, with N the number of
+    /// n-grams in `text`.
+    pub fn train(text: &[u32], n: usize) -> Self {
+        let mut counts = vec![0usize; 26usize.pow(n as u32)];
+        text.windows(n).for_each(|w| counts[Self::index(w)] += 1);
+        let total = text.len().saturating_sub(n - 1).max(1) as f64;
+        let floor = (0.01 / total).ln();
+        let log_probs = counts
+            .into_iter()
+            .map(|c| match c {
+                0 => floor,
+                c => (c as f64 / total).ln(),
+            })
+            .collect();
+        Ngrams { n, log_probs }
+    }
+
+    /// Model trained over the English [`CORPUS`].
+    pub fn english(n: usize) -> Self {
+        Self::train(&crate::to_nums(CORPUS), n)
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    fn index(ngram: &[u32]) -> usize {
+        ngram.iter().fold(0, |acc, &x| acc * 26 + x as usize)
+    }
+
+    /// Log-likelihood of the text, the higher the better.
+    pub fn score(&self, text: &[u32]) -> f64 {
+        text.windows(self.n)
+            .map(|w| self.log_probs[Self::index(w)])
+            .sum()
+    }
+}
+
+/// Number of common English bigrams occurrences.
+pub fn common_bigrams(text: &[u32]) -> usize {
+    let bigrams: Vec<_> = COMMON_BIGRAMS.iter().map(|b| crate::to_nums(b)).collect();
+    text.windows(2)
+        .filter(|w| bigrams.iter().any(|b| b == w))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::to_nums;
+
+    #[test]
+    fn english_scores_better_than_gibberish() {
+        let english = to_nums("It was the best of times, it was the worst of times");
+        let gibberish = to_nums("Qz xkv jwq bvzj yx kqmzx, qz xkv jwq fyjxz yx kqmzx");
+        assert!(monogram_score(&english) > monogram_score(&gibberish));
+        assert!(chi_squared(&english) < chi_squared(&gibberish));
+        assert!(common_bigrams(&english) > common_bigrams(&gibberish));
+        assert!((ENGLISH_FREQ.iter().sum::<f64>() - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn ngrams_are_order_sensitive() {
+        let trigrams = Ngrams::english(3);
+        let english = to_nums("It was the best of times, it was the worst of times");
+        let mut shuffled = english.clone();
+        shuffled.reverse();
+        assert!(trigrams.score(&english) > trigrams.score(&shuffled));
+        // Frequencies can't tell them apart
+        assert_eq!(chi_squared(&english), chi_squared(&shuffled));
+    }
+}
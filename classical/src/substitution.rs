@@ -0,0 +1,188 @@
+//! Monoalphabetic substitution.
+//!
+//! Each plaintext letter is replaced by a fixed ciphertext symbol. The key
+//! space (26! for letters) is far too large for brute force, but the n-grams
+//! score of a candidate decryption improves smoothly as more letters are
+//! correctly mapped, thus annealing over the key finds it given a few hundreds
+//! of letters.
+//!
+//! The ciphertext alphabet doesn't need to be the Latin one, any set of
+//! `symbols` values works (e.g. the 36 ADFGVX pairs, see [`crate::adfgvx`]).
+
+use crate::{
+    anneal::{self, Config, Problem},
+    scoring::Ngrams,
+    ALPHABET,
+};
+use rand::{seq::SliceRandom, Rng};
+
+/// Substitution cipher over the Latin alphabet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Substitution {
+    // Ciphertext letter of each plaintext letter.
+    key: Vec<u32>,
+}
+
+impl Substitution {
+    /// Random key.
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut key: Vec<_> = (0..ALPHABET).collect();
+        key.shuffle(rng);
+        Substitution { key }
+    }
+
+    pub fn key(&self) -> &[u32] {
+        &self.key
+    }
+
+    pub fn encrypt(&self, pt: &[u32]) -> Vec<u32> {
+        pt.iter().map(|&x| self.key[x as usize]).collect()
+    }
+
+    pub fn decrypt(&self, ct: &[u32]) -> Vec<u32> {
+        decrypt(ct, &invert(&self.key))
+    }
+}
+
+// Decryption mapping from the encryption one.
+fn invert(key: &[u32]) -> Vec<u32> {
+    let mut inv = vec![0; key.len()];
+    key.iter()
+        .enumerate()
+        .for_each(|(p, &c)| inv[c as usize] = p as u32);
+    inv
+}
+
+/// Apply the decryption `mapping`, from ciphertext symbols to letters.
+pub fn decrypt(ct: &[u32], mapping: &[u32]) -> Vec<u32> {
+    ct.iter().map(|&c| mapping[c as usize]).collect()
+}
+
+// Ciphertext symbols actually used, renamed to `0..m`.
+//
+// Keys are permutations of `0..max(m, 26)`, the symbol renamed `d` decrypting
+// to `key[d] mod 26`. With `m ≤ 26` the used symbols get distinct letters,
+// which swaps preserve: otherwise the search would happily collapse many
+// symbols onto the most frequent n-grams letters.
+pub(crate) struct Alphabet {
+    renamed: Vec<Option<usize>>,
+    used: Vec<u32>,
+}
+
+impl Alphabet {
+    pub(crate) fn new(ct: &[u32], symbols: usize) -> Self {
+        let mut renamed = vec![None; symbols];
+        let mut used = Vec::new();
+        for &c in ct {
+            renamed[c as usize].get_or_insert_with(|| {
+                used.push(c);
+                used.len() - 1
+            });
+        }
+        Alphabet { renamed, used }
+    }
+
+    pub(crate) fn random_key<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<u32> {
+        let mut key: Vec<_> = (0..self.used.len().max(ALPHABET as usize) as u32).collect();
+        key.shuffle(rng);
+        key
+    }
+
+    pub(crate) fn decrypt(&self, ct: &[u32], key: &[u32]) -> Vec<u32> {
+        ct.iter()
+            .map(|&c| self.renamed[c as usize].map_or(0, |d| key[d] % ALPHABET))
+            .collect()
+    }
+
+    // Decryption mapping over all the symbols, unused ones decrypt to 'A'.
+    pub(crate) fn mapping(&self, key: &[u32]) -> Vec<u32> {
+        let mut mapping = vec![0; self.renamed.len()];
+        for (d, &c) in self.used.iter().enumerate() {
+            mapping[c as usize] = key[d] % ALPHABET;
+        }
+        mapping
+    }
+}
+
+// Decryption keys search space.
+struct SubstitutionSearch<'a> {
+    ct: &'a [u32],
+    alphabet: Alphabet,
+    ngrams: &'a Ngrams,
+}
+
+impl Problem for SubstitutionSearch<'_> {
+    type State = Vec<u32>;
+
+    fn random<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<u32> {
+        self.alphabet.random_key(rng)
+    }
+
+    fn neighbor<R: Rng + ?Sized>(&self, key: &Vec<u32>, rng: &mut R) -> Vec<u32> {
+        let mut key = key.clone();
+        let (i, j) = (rng.gen_range(0..key.len()), rng.gen_range(0..key.len()));
+        key.swap(i, j);
+        key
+    }
+
+    fn score(&self, key: &Vec<u32>) -> f64 {
+        self.ngrams.score(&self.alphabet.decrypt(self.ct, key))
+    }
+}
+
+/// Substitution ciphertext only attack.
+///
+/// Ciphertext values are in `0..symbols`. Returns the decryption mapping.
+pub fn solve<R: Rng + ?Sized>(
+    ct: &[u32],
+    symbols: usize,
+    ngrams: &Ngrams,
+    config: &Config,
+    rng: &mut R,
+) -> Vec<u32> {
+    let search = SubstitutionSearch {
+        ct,
+        alphabet: Alphabet::new(ct, symbols),
+        ngrams,
+    };
+    let key = anneal::optimize(&search, config, rng).state;
+    search.alphabet.mapping(&key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{anneal::Schedule, to_nums};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const TEXT: &str = "There was no possibility of taking a walk that day. We had \
+        been wandering, indeed, in the leafless shrubbery an hour in the morning; but since \
+        dinner the cold winter wind had brought with it clouds so sombre, and a rain so \
+        penetrating, that further outdoor exercise was now out of the question. I was glad of \
+        it: I never liked long walks, especially on chilly afternoons: dreadful to me was the \
+        coming home in the raw twilight, with nipped fingers and toes, and a heart saddened by \
+        the chidings of Bessie, the nurse, and humbled by the consciousness of my physical \
+        inferiority to Eliza, John, and Georgiana Reed.";
+
+    #[test]
+    fn solve_works() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let cipher = Substitution::random(&mut rng);
+        let pt = to_nums(TEXT);
+        let ct = cipher.encrypt(&pt);
+        assert_eq!(cipher.decrypt(&ct), pt);
+
+        let config = Config {
+            steps: 20000,
+            restarts: 4,
+            schedule: Schedule::Geometric {
+                start: 20.0,
+                end: 0.2,
+            },
+        };
+        let mapping = solve(&ct, 26, &Ngrams::english(3), &config, &mut rng);
+        let found = decrypt(&ct, &mapping);
+        let correct = found.iter().zip(&pt).filter(|(a, b)| a == b).count();
+        assert!(correct * 100 > 95 * pt.len());
+    }
+}
@@ -0,0 +1,252 @@
+//! Transposition ciphers.
+//!
+//! - Columnar: the plaintext is written in rows of `k` letters and read column
+//!   by column, in the order given by the key. The last row may be incomplete,
+//!   thus the columns lengths differ by at most one.
+//! - Rail fence: the plaintext is written in a zigzag over `r` rails and read
+//!   rail by rail.
+//!
+//! Letters frequencies are preserved, thus candidate keys are scored with
+//! [`Ngrams`]. The rail fence key space is tiny and is searched exhaustively,
+//! while the columnar permutation is found via simulated annealing, once for
+//! each candidate width.
+
+use crate::{
+    anneal::{self, Config, Problem},
+    scoring::Ngrams,
+};
+use rand::{seq::SliceRandom, Rng};
+use std::ops::RangeInclusive;
+
+/// Columnar transposition cipher.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Columnar {
+    // Grid columns in reading order.
+    order: Vec<usize>,
+}
+
+impl Columnar {
+    /// Key from the columns reading order, `None` if not a permutation.
+    pub fn new(order: Vec<usize>) -> Option<Self> {
+        let mut sorted = order.clone();
+        sorted.sort_unstable();
+        sorted
+            .into_iter()
+            .eq(0..order.len())
+            .then_some(Columnar { order })
+    }
+
+    /// Key from a keyword: columns are read in the keyword letters
+    /// alphabetical order, ties broken left to right.
+    pub fn from_keyword(keyword: &str) -> Self {
+        let letters = crate::to_nums(keyword);
+        let mut order: Vec<_> = (0..letters.len()).collect();
+        order.sort_by_key(|&i| letters[i]);
+        Columnar { order }
+    }
+
+    /// Random key of the given width.
+    pub fn random<R: Rng + ?Sized>(width: usize, rng: &mut R) -> Self {
+        let mut order: Vec<_> = (0..width).collect();
+        order.shuffle(rng);
+        Columnar { order }
+    }
+
+    pub fn width(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    // Plaintext position of each ciphertext symbol.
+    fn positions(&self, len: usize) -> impl Iterator<Item = usize> + '_ {
+        let width = self.width();
+        self.order
+            .iter()
+            .flat_map(move |&col| (col..len).step_by(width))
+    }
+
+    // Swap two columns, move one to another place or rotate the grid columns.
+    // The latter escapes the local optima where every row is shifted by a few
+    // letters, which are otherwise many moves away.
+    pub(crate) fn neighbor<R: Rng + ?Sized>(&self, rng: &mut R) -> Columnar {
+        let width = self.width();
+        let mut order = self.order.clone();
+        let (i, j) = (rng.gen_range(0..width), rng.gen_range(0..width));
+        match rng.gen_range(0..10) {
+            0 => order.iter_mut().for_each(|c| *c = (*c + i) % width),
+            1..=5 => order.swap(i, j),
+            _ => {
+                let col = order.remove(i);
+                order.insert(j, col);
+            }
+        }
+        Columnar { order }
+    }
+
+    pub fn encrypt(&self, pt: &[u32]) -> Vec<u32> {
+        self.positions(pt.len()).map(|i| pt[i]).collect()
+    }
+
+    pub fn decrypt(&self, ct: &[u32]) -> Vec<u32> {
+        let mut pt = vec![0; ct.len()];
+        self.positions(ct.len())
+            .zip(ct)
+            .for_each(|(i, &c)| pt[i] = c);
+        pt
+    }
+}
+
+/// Rail fence cipher.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RailFence {
+    pub rails: usize,
+}
+
+impl RailFence {
+    // Plaintext position of each ciphertext symbol.
+    fn positions(&self, len: usize) -> Vec<usize> {
+        let period = (2 * self.rails).saturating_sub(2).max(1);
+        let rail = |i: usize| {
+            let j = i % period;
+            j.min(period - j)
+        };
+        let mut positions: Vec<_> = (0..len).collect();
+        positions.sort_by_key(|&i| rail(i));
+        positions
+    }
+
+    pub fn encrypt(&self, pt: &[u32]) -> Vec<u32> {
+        self.positions(pt.len())
+            .into_iter()
+            .map(|i| pt[i])
+            .collect()
+    }
+
+    pub fn decrypt(&self, ct: &[u32]) -> Vec<u32> {
+        let mut pt = vec![0; ct.len()];
+        self.positions(ct.len())
+            .into_iter()
+            .zip(ct)
+            .for_each(|(i, &c)| pt[i] = c);
+        pt
+    }
+}
+
+/// Rail fence ciphertext only attack, trying every number of rails up to
+/// `max_rails`.
+pub fn solve_rail_fence(ct: &[u32], max_rails: usize, ngrams: &Ngrams) -> RailFence {
+    (2..=max_rails.max(2))
+        .map(|rails| RailFence { rails })
+        .max_by(|a, b| {
+            let (a, b) = (ngrams.score(&a.decrypt(ct)), ngrams.score(&b.decrypt(ct)));
+            a.total_cmp(&b)
+        })
+        .expect("At least two rails")
+}
+
+// Columnar key search space for a given width.
+struct ColumnarSearch<'a> {
+    ct: &'a [u32],
+    width: usize,
+    ngrams: &'a Ngrams,
+}
+
+impl Problem for ColumnarSearch<'_> {
+    type State = Columnar;
+
+    fn random<R: Rng + ?Sized>(&self, rng: &mut R) -> Columnar {
+        Columnar::random(self.width, rng)
+    }
+
+    fn neighbor<R: Rng + ?Sized>(&self, key: &Columnar, rng: &mut R) -> Columnar {
+        key.neighbor(rng)
+    }
+
+    fn score(&self, key: &Columnar) -> f64 {
+        self.ngrams.score(&key.decrypt(self.ct))
+    }
+}
+
+/// Columnar transposition ciphertext only attack.
+///
+/// Every width in `widths` is searched according to `config`, the best scoring
+/// key is returned.
+pub fn solve_columnar<R: Rng + ?Sized>(
+    ct: &[u32],
+    widths: RangeInclusive<usize>,
+    ngrams: &Ngrams,
+    config: &Config,
+    rng: &mut R,
+) -> Columnar {
+    widths
+        .map(|width| {
+            let search = ColumnarSearch { ct, width, ngrams };
+            anneal::optimize(&search, config, rng)
+        })
+        .max_by(|a, b| a.score.total_cmp(&b.score))
+        .expect("Empty widths range")
+        .state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{anneal::Schedule, from_nums, to_nums};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const TEXT: &str = "The Mole had been working very hard all the morning, spring-cleaning \
+        his little home. First with brooms, then with dusters; then on ladders and steps and \
+        chairs, with a brush and a pail of whitewash; till he had dust in his throat and eyes, \
+        and splashes of whitewash all over his black fur, and an aching back and weary arms.";
+
+    #[test]
+    fn columnar_roundtrip() {
+        let key = Columnar::from_keyword("ZEBRAS");
+        assert_eq!(key.order(), [4, 2, 1, 3, 5, 0]);
+        let pt = to_nums("WEAREDISCOVEREDFLEEATONCE");
+        let ct = key.encrypt(&pt);
+        assert_eq!(from_nums(&ct), "EVLNACDTESEAROFODEECWIREE");
+        assert_eq!(key.decrypt(&ct), pt);
+        assert_eq!(Columnar::new(vec![0, 2, 2]), None);
+    }
+
+    #[test]
+    fn rail_fence_roundtrip() {
+        let pt = to_nums("WEAREDISCOVEREDFLEEATONCE");
+        let ct = RailFence { rails: 3 }.encrypt(&pt);
+        assert_eq!(from_nums(&ct), "WECRLTEERDSOEEFEAOCAIVDEN");
+        for rails in 1..8 {
+            let cipher = RailFence { rails };
+            assert_eq!(cipher.decrypt(&cipher.encrypt(&pt)), pt);
+        }
+    }
+
+    #[test]
+    fn solve_rail_fence_works() {
+        let pt = to_nums(TEXT);
+        let ct = RailFence { rails: 7 }.encrypt(&pt);
+        let found = solve_rail_fence(&ct, 20, &Ngrams::english(3));
+        assert_eq!(found.rails, 7);
+    }
+
+    #[test]
+    fn solve_columnar_works() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let pt = to_nums(TEXT);
+        let key = Columnar::random(8, &mut rng);
+        let ct = key.encrypt(&pt);
+        let config = Config {
+            steps: 5000,
+            restarts: 4,
+            schedule: Schedule::Geometric {
+                start: 50.0,
+                end: 0.5,
+            },
+        };
+        let found = solve_columnar(&ct, 6..=9, &Ngrams::english(3), &config, &mut rng);
+        assert_eq!(found, key);
+    }
+}
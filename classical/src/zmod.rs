@@ -0,0 +1,237 @@
+//! Linear algebra over Z_m.
+//!
+//! Matrix inversion is performed via Gauss-Jordan elimination over each prime
+//! field Z_p dividing `m` and the results are then combined via the Chinese
+//! remainder theorem. Thus `m` is required to be square free (e.g. 26 = 2·13).
+
+/// Greatest common divisor.
+pub fn gcd(mut a: u32, mut b: u32) -> u32 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Inverse of `a` modulo `m`, if it exists.
+pub fn inv_mod(a: u32, m: u32) -> Option<u32> {
+    let (mut r0, mut r1) = (m as i64, (a % m) as i64);
+    let (mut t0, mut t1) = (0_i64, 1_i64);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (t0, t1) = (t1, t0 - q * t1);
+    }
+    (r0 == 1).then(|| t0.rem_euclid(m as i64) as u32)
+}
+
+/// Distinct prime factors of a square free `m`.
+///
+/// Panics if `m` is not square free.
+pub fn prime_factors(mut m: u32) -> Vec<u32> {
+    let mut factors = Vec::new();
+    let mut p = 2;
+    while p * p <= m {
+        if m.is_multiple_of(p) {
+            m /= p;
+            assert!(!m.is_multiple_of(p), "Modulus not square free");
+            factors.push(p);
+        }
+        p += 1;
+    }
+    if m > 1 {
+        factors.push(m);
+    }
+    factors
+}
+
+/// Combine `x ≡ a1 (mod m1)` and `x ≡ a2 (mod m2)`, with coprime moduli.
+pub fn crt(a1: u32, m1: u32, a2: u32, m2: u32) -> u32 {
+    let inv = inv_mod(m1 % m2, m2).expect("Moduli not coprime");
+    let k = (a2 + m2 - a1 % m2) % m2 * inv % m2;
+    a1 + m1 * k
+}
+
+/// Square matrix over Z_m (the modulus is given to each operation).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix {
+    n: usize,
+    data: Vec<u32>,
+}
+
+impl Matrix {
+    /// Matrix with the given row major entries.
+    ///
+    /// Panics if `data.len()` is not `n²`.
+    pub fn new(n: usize, data: Vec<u32>) -> Self {
+        assert_eq!(data.len(), n * n, "Bad matrix size");
+        Matrix { n, data }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let mut data = vec![0; n * n];
+        (0..n).for_each(|i| data[i * n + i] = 1);
+        Matrix { n, data }
+    }
+
+    /// Matrix with the given columns.
+    pub fn from_columns(columns: &[&[u32]]) -> Self {
+        let n = columns.len();
+        let mut data = vec![0; n * n];
+        for (j, col) in columns.iter().enumerate() {
+            assert_eq!(col.len(), n, "Bad column size");
+            col.iter()
+                .enumerate()
+                .for_each(|(i, &x)| data[i * n + j] = x);
+        }
+        Matrix { n, data }
+    }
+
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> u32 {
+        self.data[i * self.n + j]
+    }
+
+    pub fn set(&mut self, i: usize, j: usize, x: u32) {
+        self.data[i * self.n + j] = x;
+    }
+
+    /// Row major entries.
+    pub fn entries(&self) -> &[u32] {
+        &self.data
+    }
+
+    /// Entries reduced modulo `m`.
+    pub fn reduce(&self, m: u32) -> Matrix {
+        Matrix::new(self.n, self.data.iter().map(|x| x % m).collect())
+    }
+
+    /// Matrix product modulo `m`.
+    pub fn mul(&self, other: &Matrix, m: u32) -> Matrix {
+        let n = self.n;
+        assert_eq!(n, other.n, "Size mismatch");
+        let mut data = vec![0; n * n];
+        for i in 0..n {
+            for j in 0..n {
+                data[i * n + j] = (0..n)
+                    .map(|k| self.get(i, k) * other.get(k, j) % m)
+                    .sum::<u32>()
+                    % m;
+            }
+        }
+        Matrix { n, data }
+    }
+
+    /// Matrix vector product modulo `m`.
+    pub fn mul_vec(&self, v: &[u32], m: u32) -> Vec<u32> {
+        assert_eq!(v.len(), self.n, "Size mismatch");
+        (0..self.n)
+            .map(|i| {
+                let row = &self.data[i * self.n..(i + 1) * self.n];
+                row.iter().zip(v).map(|(a, b)| a * b % m).sum::<u32>() % m
+            })
+            .collect()
+    }
+
+    // Determinant and inverse modulo a prime `p` via Gauss-Jordan elimination.
+    fn gauss_jordan(&self, p: u32) -> (u32, Option<Matrix>) {
+        let n = self.n;
+        let mut a = self.reduce(p);
+        let mut inv = Matrix::identity(n);
+        let mut det = 1;
+        for col in 0..n {
+            let Some(pivot) = (col..n).find(|&r| a.get(r, col) != 0) else {
+                return (0, None);
+            };
+            if pivot != col {
+                for j in 0..n {
+                    a.data.swap(pivot * n + j, col * n + j);
+                    inv.data.swap(pivot * n + j, col * n + j);
+                }
+                det = (p - det) % p;
+            }
+            let pv = a.get(col, col);
+            det = det * pv % p;
+            let pv_inv = inv_mod(pv, p).expect("Non prime modulus");
+            for j in 0..n {
+                a.set(col, j, a.get(col, j) * pv_inv % p);
+                inv.set(col, j, inv.get(col, j) * pv_inv % p);
+            }
+            for r in (0..n).filter(|&r| r != col) {
+                let f = a.get(r, col);
+                for j in 0..n {
+                    a.set(r, j, (a.get(r, j) + (p - f) * a.get(col, j)) % p);
+                    inv.set(r, j, (inv.get(r, j) + (p - f) * inv.get(col, j)) % p);
+                }
+            }
+        }
+        (det, Some(inv))
+    }
+
+    /// Determinant modulo the square free `m`.
+    pub fn det(&self, m: u32) -> u32 {
+        prime_factors(m)
+            .into_iter()
+            .fold((0, 1), |(acc, modulus), p| {
+                (crt(acc, modulus, self.gauss_jordan(p).0, p), modulus * p)
+            })
+            .0
+    }
+
+    /// Inverse modulo the square free `m`, if it exists.
+    ///
+    /// The matrix is invertible iff `gcd(det, m) = 1`.
+    pub fn inverse(&self, m: u32) -> Option<Matrix> {
+        let mut acc = Matrix::new(self.n, vec![0; self.n * self.n]);
+        let mut modulus = 1;
+        for p in prime_factors(m) {
+            let inv = self.gauss_jordan(p).1?;
+            acc = acc.crt(modulus, &inv, p);
+            modulus *= p;
+        }
+        Some(acc)
+    }
+
+    /// Entry-wise CRT combination of `self` (mod `m1`) and `other` (mod `m2`).
+    pub fn crt(&self, m1: u32, other: &Matrix, m2: u32) -> Matrix {
+        let data = self
+            .data
+            .iter()
+            .zip(&other.data)
+            .map(|(&a, &b)| crt(a, m1, b, m2))
+            .collect();
+        Matrix::new(self.n, data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modular_arithmetic() {
+        assert_eq!(gcd(26, 12), 2);
+        assert_eq!(inv_mod(3, 26), Some(9));
+        assert_eq!(inv_mod(13, 26), None);
+        assert_eq!(prime_factors(26), [2, 13]);
+        assert_eq!(crt(1, 2, 5, 13), 5);
+        assert_eq!(crt(0, 2, 5, 13), 18);
+    }
+
+    #[test]
+    fn inverse_mod_26() {
+        let k = Matrix::new(3, vec![6, 24, 1, 13, 16, 10, 20, 17, 15]);
+        assert_eq!(k.det(26), 25);
+        let inv = k.inverse(26).unwrap();
+        assert_eq!(inv, Matrix::new(3, vec![8, 5, 10, 21, 8, 21, 21, 12, 8]));
+        assert_eq!(k.mul(&inv, 26), Matrix::identity(3));
+
+        // det = 2
+        let singular = Matrix::new(2, vec![2, 0, 0, 1]);
+        assert_eq!(singular.det(26), 2);
+        assert!(singular.inverse(26).is_none());
+        assert!(singular.inverse(13).is_some());
+    }
+}
@@ -0,0 +1,27 @@
+//! Unsalted sealed bids (`commit-reveal-auction` crate).
+
+use crate::{input::Bytes, output::Report, Result};
+use clap::Args;
+use coin_flipping::commitment::HashCommitment;
+use commit_reveal_auction::brute_force;
+
+/// Open an unsalted `H(bid)` commitment before the reveal phase.
+#[derive(Args)]
+pub struct AuctionArgs {
+    /// Published commitment (32 bytes).
+    commitment: Bytes,
+    /// Largest plausible bid.
+    #[arg(short, long, default_value_t = 1_000_000)]
+    max_bid: u64,
+}
+
+pub fn run(args: AuctionArgs) -> Result<Report> {
+    let commitment = args
+        .commitment
+        .0
+        .try_into()
+        .map_err(|_| "Commitments are 32 bytes")?;
+    let bid = brute_force(&HashCommitment(commitment), args.max_bid)
+        .ok_or("Bid not found, salted commitment or larger bid")?;
+    Ok(Report::new().field("bid", bid))
+}
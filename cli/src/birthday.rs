@@ -0,0 +1,99 @@
+//! Truncated hash collisions (`birthday-paradox` crate).
+
+use crate::{output::Report, Result};
+use birthday_paradox::{
+    meaningful::{forge, Template},
+    search::{CollisionSearch, Strategy, Truncation},
+};
+use clap::{Subcommand, ValueEnum};
+use sha2::{Digest, Sha256};
+use std::{fs, path::PathBuf};
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Engine {
+    /// Hash table
+    Table,
+    /// Memoryless Pollard's rho
+    Rho,
+}
+
+#[derive(Subcommand)]
+pub enum BirthdayCommand {
+    /// Two counters with the same leading SHA-256 bytes.
+    Collide {
+        /// Leading bytes kept.
+        #[arg(short, long, default_value_t = 4)]
+        bytes: usize,
+        /// Search engine.
+        #[arg(short, long, value_enum, default_value_t = Engine::Table)]
+        engine: Engine,
+        /// First counter.
+        #[arg(short, long, default_value_t = 0)]
+        seed: u64,
+    },
+    /// A benign and a malicious document with the same leading SHA-256 bytes.
+    Forge {
+        /// Leading bytes kept.
+        #[arg(short, long, default_value_t = 4)]
+        bytes: usize,
+        /// Benign document template, with `{a|b}` alternatives.
+        benign: PathBuf,
+        /// Malicious document template, with `{a|b}` alternatives.
+        malicious: PathBuf,
+    },
+}
+
+fn check_bytes(bytes: usize) -> Result<()> {
+    // Beyond 7 bytes the search doesn't end in reasonable time
+    if !(1..=7).contains(&bytes) {
+        return Err("Keep 1 to 7 bytes".into());
+    }
+    Ok(())
+}
+
+pub fn run(command: BirthdayCommand) -> Result<Report> {
+    match command {
+        BirthdayCommand::Collide {
+            bytes,
+            engine,
+            seed,
+        } => {
+            check_bytes(bytes)?;
+            let strategy = match engine {
+                Engine::Table => Strategy::Table,
+                Engine::Rho => Strategy::Rho,
+            };
+            let search = CollisionSearch::<Sha256>::new()
+                .truncation(Truncation::Leading(bytes))
+                .strategy(strategy)
+                .seed(seed);
+            let c = search.run().ok_or("Inputs exhausted")?;
+            Ok(Report::new()
+                .field("a", c.a.to_string())
+                .field("b", c.b.to_string())
+                .field(
+                    "hash",
+                    format!("{:0width$x}", search.hash(c.a), width = 2 * bytes),
+                )
+                .field("hashes", c.count)
+                .field("expected", search.expected()))
+        }
+        BirthdayCommand::Forge {
+            bytes,
+            benign,
+            malicious,
+        } => {
+            check_bytes(bytes)?;
+            let benign = Template::parse(&fs::read_to_string(benign)?);
+            let malicious = Template::parse(&fs::read_to_string(malicious)?);
+            let forgery = forge::<Sha256>(&benign, &malicious, &Truncation::Leading(bytes))
+                .ok_or("Not enough variants")?;
+            let digest = Sha256::digest(forgery.benign.as_bytes());
+            Ok(Report::new()
+                .field("benign", forgery.benign)
+                .field("malicious", forgery.malicious)
+                .field("hash", hex::encode(&digest[..bytes]))
+                .field("hashes", forgery.count))
+        }
+    }
+}
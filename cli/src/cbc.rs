@@ -0,0 +1,150 @@
+//! CBC padding oracle attack (`cbc-padding-oracle` crate).
+
+use crate::{input::Bytes, output::Report, Result};
+use aes::{Aes128, Aes192, Aes256};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use cbc_padding_oracle::{
+    attack_parallel, attack_with_stats, encrypt,
+    prior::{attack_with_prior, Prior},
+    remote::RemoteOracle,
+    CbcOracle, PaddingOracle,
+};
+use clap::{Args, Subcommand};
+use std::{fs, path::PathBuf};
+
+#[derive(Subcommand)]
+pub enum CbcCommand {
+    /// Decrypt a ciphertext by querying a padding oracle.
+    Attack(AttackArgs),
+    /// AES-CBC encrypt a message.
+    Encrypt {
+        /// AES key (16/24/32 bytes).
+        #[arg(short, long)]
+        key: Bytes,
+        /// Initialization vector.
+        #[arg(short, long)]
+        iv: Bytes,
+        /// Plaintext string.
+        plaintext: String,
+    },
+}
+
+#[derive(Args)]
+pub struct AttackArgs {
+    /// Initialization vector. If missing the first ciphertext block is used
+    /// as IV.
+    #[arg(short, long)]
+    iv: Option<Bytes>,
+    /// Ciphertext.
+    #[arg(
+        short,
+        long,
+        conflicts_with = "ct_file",
+        required_unless_present = "ct_file"
+    )]
+    ct: Option<Bytes>,
+    /// Ciphertext file (hex, base64 or raw binary).
+    #[arg(short = 'f', long)]
+    ct_file: Option<PathBuf>,
+    /// Cipher block size in bytes (at least 2).
+    #[arg(
+        short,
+        long,
+        default_value_t = 16,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(2..)
+    )]
+    block_size: usize,
+    /// Oracle endpoint: `tcp://<host>:<port>` for a remote oracle or
+    /// `key:<hex>` for a local AES oracle.
+    #[arg(short, long)]
+    oracle: String,
+    /// Attack all the blocks concurrently.
+    #[arg(short, long)]
+    parallel: bool,
+    /// Plaintext prior: `json` or a file with a sample of the expected
+    /// plaintext.
+    #[arg(long, conflicts_with = "parallel")]
+    prior: Option<String>,
+}
+
+pub fn run(command: CbcCommand) -> Result<Report> {
+    match command {
+        CbcCommand::Attack(args) => attack(args),
+        CbcCommand::Encrypt { key, iv, plaintext } => {
+            if iv.0.len() != 16 {
+                return Err("IV length must be 16 bytes".into());
+            }
+            let (key, iv, pt) = (&key.0, &iv.0, plaintext.as_bytes());
+            let ct = match key.len() {
+                16 => encrypt::<Aes128>(key, iv, pt),
+                24 => encrypt::<Aes192>(key, iv, pt),
+                32 => encrypt::<Aes256>(key, iv, pt),
+                _ => return Err("AES key must be 16, 24 or 32 bytes".into()),
+            };
+            Ok(Report::new().field("ct", hex::encode(ct)))
+        }
+    }
+}
+
+fn attack(args: AttackArgs) -> Result<Report> {
+    let mut ct = match (&args.ct, &args.ct_file) {
+        (Some(ct), _) => ct.0.clone(),
+        (None, Some(path)) => Bytes::read(path)?.0,
+        (None, None) => unreachable!("Enforced by clap"),
+    };
+    let iv = match &args.iv {
+        Some(iv) => iv.0.clone(),
+        None if ct.len() > args.block_size => ct.drain(..args.block_size).collect(),
+        None => return Err("Ciphertext too short to carry the IV".into()),
+    };
+    if iv.len() != args.block_size {
+        return Err(format!("IV length must be {} bytes", args.block_size).into());
+    }
+    if ct.is_empty() || ct.len() % args.block_size != 0 {
+        return Err(format!(
+            "Ciphertext length must be a multiple of {}",
+            args.block_size
+        )
+        .into());
+    }
+
+    if let Some(addr) = args.oracle.strip_prefix("tcp://") {
+        run_attack(&RemoteOracle::connect(addr)?, &args, &iv, &ct)
+    } else if let Some(key) = args.oracle.strip_prefix("key:") {
+        let key: Bytes = key.parse()?;
+        match key.0.len() {
+            16 => run_attack(&CbcOracle::<Aes128>::new(&key.0), &args, &iv, &ct),
+            24 => run_attack(&CbcOracle::<Aes192>::new(&key.0), &args, &iv, &ct),
+            32 => run_attack(&CbcOracle::<Aes256>::new(&key.0), &args, &iv, &ct),
+            _ => Err("AES key must be 16, 24 or 32 bytes".into()),
+        }
+    } else {
+        Err(format!("Unknown oracle endpoint '{}'", args.oracle).into())
+    }
+}
+
+fn run_attack<O: PaddingOracle + Sync>(
+    oracle: &O,
+    args: &AttackArgs,
+    iv: &[u8],
+    ct: &[u8],
+) -> Result<Report> {
+    let (pt, stats) = match &args.prior {
+        Some(prior) => {
+            let mut prior = match prior.as_str() {
+                "json" => Prior::json(),
+                path => Prior::from_sample(&fs::read(path)?),
+            };
+            attack_with_prior(oracle, args.block_size, iv, ct, &mut prior)
+        }
+        None if args.parallel => attack_parallel(oracle, args.block_size, iv, ct),
+        None => attack_with_stats(oracle, args.block_size, iv, ct),
+    };
+    Ok(Report::new()
+        .field("pt_hex", hex::encode(&pt))
+        .field("pt_base64", BASE64.encode(&pt))
+        .field("pt_utf8", String::from_utf8_lossy(&pt))
+        .field("queries", stats.total_queries())
+        .field("retries", stats.total_retries())
+        .field("elapsed_ms", stats.elapsed.as_millis() as u64))
+}
@@ -0,0 +1,98 @@
+//! Classical ciphers ciphertext only attacks (`classical` crate).
+
+use crate::{output::Report, Result};
+use clap::{Args, ValueEnum};
+use classical::{
+    adfgvx,
+    anneal::{Config, Schedule},
+    from_nums, hill,
+    scoring::Ngrams,
+    substitution, to_nums, transposition,
+};
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Cipher {
+    /// Monoalphabetic substitution
+    Substitution,
+    /// Columnar transposition, width up to `--max-width`
+    Columnar,
+    /// Rail fence, up to `--max-width` rails
+    RailFence,
+    /// ADFGVX, transposition width `--max-width`
+    Adfgvx,
+    /// Hill cipher with 2x2 key
+    Hill,
+}
+
+/// Recover the plaintext of a classical cipher.
+#[derive(Args)]
+pub struct ClassicalArgs {
+    /// Cipher.
+    #[arg(short, long, value_enum)]
+    cipher: Cipher,
+    /// Ciphertext letters (`ADFGVX` symbols for ADFGVX), anything else is
+    /// ignored.
+    ct: String,
+    /// Max transposition width or rails.
+    #[arg(short = 'w', long, default_value_t = 10)]
+    max_width: usize,
+    /// Annealing steps per run.
+    #[arg(long, default_value_t = 20000)]
+    steps: usize,
+    /// Annealing runs.
+    #[arg(long, default_value_t = 4)]
+    restarts: usize,
+}
+
+// ADFGVX symbols to numbers in `0..6`.
+fn adfgvx_symbols(ct: &str) -> Result<Vec<u32>> {
+    ct.chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| match adfgvx::SYMBOLS.find(c.to_ascii_uppercase()) {
+            Some(x) => Ok(x as u32),
+            None => Err(format!("'{c}' is not an ADFGVX symbol").into()),
+        })
+        .collect()
+}
+
+pub fn run(args: ClassicalArgs) -> Result<Report> {
+    let ngrams = Ngrams::english(3);
+    let mut rng = rand::thread_rng();
+    let config = Config {
+        steps: args.steps,
+        restarts: args.restarts,
+        schedule: Schedule::Geometric {
+            start: 20.0,
+            end: 0.2,
+        },
+    };
+    let ct = to_nums(&args.ct);
+    let (key, pt) = match args.cipher {
+        Cipher::Substitution => {
+            let mapping = substitution::solve(&ct, 26, &ngrams, &config, &mut rng);
+            (from_nums(&mapping), substitution::decrypt(&ct, &mapping))
+        }
+        Cipher::Columnar => {
+            let widths = 2..=args.max_width.max(2);
+            let key = transposition::solve_columnar(&ct, widths, &ngrams, &config, &mut rng);
+            (format!("{:?}", key.order()), key.decrypt(&ct))
+        }
+        Cipher::RailFence => {
+            let key = transposition::solve_rail_fence(&ct, args.max_width, &ngrams);
+            (key.rails.to_string(), key.decrypt(&ct))
+        }
+        Cipher::Adfgvx => {
+            let ct = adfgvx_symbols(&args.ct)?;
+            let (key, pt) = adfgvx::solve(&ct, args.max_width, &ngrams, &config, &mut rng);
+            (format!("{:?}", key.order()), pt)
+        }
+        Cipher::Hill => {
+            let cipher =
+                hill::ciphertext_only_2x2(&ct, 100, &mut rng).ok_or("Hill key not found")?;
+            (format!("{:?}", cipher.key().entries()), cipher.decrypt(&ct))
+        }
+    };
+    Ok(Report::new()
+        .field("key", key)
+        .field("plaintext", from_nums(&pt)))
+}
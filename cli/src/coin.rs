@@ -0,0 +1,49 @@
+//! Coin flipping over the phone (`coin-flipping` crate).
+
+use crate::{output::Report, Result};
+use clap::{Args, ValueEnum};
+use coin_flipping::{bob_win_rate, Protocol};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Flavor {
+    /// Alice reveals her bit first
+    RevealFirst,
+    /// H(a)
+    UnsaltedHash,
+    /// H(salt || a)
+    SaltedHash,
+    /// g^a·h^r mod p
+    Pedersen,
+}
+
+/// Coin flips won by a malicious Bob.
+#[derive(Args)]
+pub struct CoinArgs {
+    /// Alice's first message.
+    #[arg(short, long, value_enum, default_value_t = Flavor::UnsaltedHash)]
+    protocol: Flavor,
+    /// Number of flips.
+    #[arg(short, long, default_value_t = 1000)]
+    rounds: usize,
+    /// Seed of the players' choices.
+    #[arg(short, long)]
+    seed: Option<u64>,
+}
+
+pub fn run(args: CoinArgs) -> Result<Report> {
+    if args.rounds == 0 {
+        return Err("At least one flip is required".into());
+    }
+    let protocol = match args.protocol {
+        Flavor::RevealFirst => Protocol::RevealFirst,
+        Flavor::UnsaltedHash => Protocol::UnsaltedHash,
+        Flavor::SaltedHash => Protocol::SaltedHash,
+        Flavor::Pedersen => Protocol::Pedersen,
+    };
+    let seed = args.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let rate = bob_win_rate(&mut StdRng::seed_from_u64(seed), protocol, args.rounds);
+    Ok(Report::new()
+        .field("seed", seed.to_string())
+        .field("bob_win_rate", rate))
+}
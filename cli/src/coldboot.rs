@@ -0,0 +1,75 @@
+//! Key recovery from decayed memory (`cold-boot-attack` crate).
+
+use crate::{
+    input::{Bytes, Uint},
+    output::Report,
+    Result,
+};
+use clap::Subcommand;
+use cold_boot_attack::{
+    aes::{recover_key, SCHEDULE_LEN},
+    pkcs1::{parse, recover, to_pem, Blob, Fragment},
+};
+use num_bigint::BigUint;
+use std::{fs, path::PathBuf};
+
+// Branch and prune search width.
+const MAX_WIDTH: usize = 100_000;
+
+#[derive(Subcommand)]
+pub enum ColdBootCommand {
+    /// Rebuild an RSA private key from a damaged PKCS#1 blob.
+    Rsa {
+        /// PEM (unreadable lines are tolerated) or DER file.
+        key: PathBuf,
+        /// Modulus, required only if it didn't survive in the blob.
+        #[arg(short, long)]
+        modulus: Option<Uint>,
+    },
+    /// Recover an AES-128 key from a decayed key schedule image.
+    Aes {
+        /// Expanded key image (176 bytes).
+        image: Bytes,
+        /// Decayed bits restored per key schedule window.
+        #[arg(short, long, default_value_t = 4)]
+        max_flips: usize,
+    },
+}
+
+pub fn run(command: ColdBootCommand) -> Result<Report> {
+    match command {
+        ColdBootCommand::Rsa { key, modulus } => {
+            let data = fs::read(key)?;
+            let blob = match std::str::from_utf8(&data) {
+                Ok(pem) if pem.contains("-----BEGIN") => Blob::from_pem(pem),
+                _ => Blob::from_der(&data),
+            };
+            let modulus = modulus.map(|n| n.0);
+            let mut frags = parse(
+                &blob,
+                modulus.as_ref().map(|n| n.bits().div_ceil(8) as usize),
+            );
+            if let Some(n) = modulus {
+                frags.n = Some(Fragment::known(n));
+            }
+            // Almost always the case
+            if frags.e.as_ref().and_then(Fragment::value).is_none() {
+                frags.e = Some(Fragment::known(BigUint::from(65537_u32)));
+            }
+            let (key, method) =
+                recover(&frags, MAX_WIDTH).map_err(|e| format!("Recovery failed: {e:?}"))?;
+            Ok(Report::new()
+                .field("method", method.to_string())
+                .field("key", to_pem(&key)))
+        }
+        ColdBootCommand::Aes { image, max_flips } => {
+            if image.0.len() != SCHEDULE_LEN {
+                return Err(format!("The image must be {SCHEDULE_LEN} bytes").into());
+            }
+            let (key, flips) = recover_key(&image.0, max_flips).ok_or("Key not found")?;
+            Ok(Report::new()
+                .field("key", hex::encode(key))
+                .field("decayed_bits", flips))
+        }
+    }
+}
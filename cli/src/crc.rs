@@ -0,0 +1,61 @@
+//! CRC forgery (`crc-forgery` crate).
+
+use crate::{
+    input::{self, Bytes},
+    output::Report,
+    Result,
+};
+use clap::{Args, ValueEnum};
+use crc_forgery::{CRC32, CRC64};
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Width {
+    /// CRC-32 (ISO-HDLC)
+    Crc32,
+    /// CRC-64/XZ
+    Crc64,
+}
+
+/// Compute a CRC or patch a message to get a chosen one.
+#[derive(Args)]
+pub struct CrcArgs {
+    /// Message.
+    data: Bytes,
+    /// CRC variant.
+    #[arg(short, long, value_enum, default_value_t = Width::Crc32)]
+    crc: Width,
+    /// Wanted CRC. Patches the message if given.
+    #[arg(short, long, value_parser = input::u64)]
+    target: Option<u64>,
+    /// Offset of the patched bytes (default: appended to the message).
+    #[arg(short, long, requires = "target")]
+    pos: Option<usize>,
+}
+
+pub fn run(args: CrcArgs) -> Result<Report> {
+    let crc = match args.crc {
+        Width::Crc32 => CRC32,
+        Width::Crc64 => CRC64,
+    };
+    let mut data = args.data.0;
+    let report = Report::new().field("crc", format!("{:x}", crc.checksum(&data)));
+    let Some(target) = args.target else {
+        return Ok(report);
+    };
+    let fits = |pos: usize| {
+        pos.checked_add(crc.bytes())
+            .is_some_and(|end| end <= data.len())
+    };
+    let pos = match args.pos {
+        Some(pos) if fits(pos) => pos,
+        Some(_) => return Err("Patch out of the message".into()),
+        None => {
+            data.resize(data.len() + crc.bytes(), 0);
+            data.len() - crc.bytes()
+        }
+    };
+    crc.forge(&mut data, pos, target);
+    Ok(report
+        .field("forged", hex::encode(&data))
+        .field("forged_crc", format!("{:x}", crc.checksum(&data))))
+}
@@ -0,0 +1,41 @@
+//! Invalid curve attack simulation (`invalid-curve` crate).
+
+use crate::{output::Report, Result};
+use clap::Args;
+use invalid_curve::{attacks::recover_secret, Victim};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Recover the static ECDH key of a simulated server.
+#[derive(Args)]
+pub struct CurveArgs {
+    /// Seed of the victim key and of the attacker points.
+    #[arg(short, long)]
+    seed: Option<u64>,
+    /// The victim checks that the client points are on the curve.
+    #[arg(long)]
+    validate: bool,
+    /// Bound of the small subgroup orders.
+    #[arg(short, long, default_value_t = 1 << 16)]
+    bound: u64,
+    /// Secret bits left to baby-step giant-step.
+    #[arg(long, default_value_t = 24)]
+    bsgs_bits: u32,
+}
+
+pub fn run(args: CurveArgs) -> Result<Report> {
+    if args.bound < 3 || args.bsgs_bits > 40 {
+        return Err("Bound must be at least 3, BSGS bits at most 40".into());
+    }
+    let seed = args.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut victim = Victim::new(&mut rng, args.validate);
+    let recovered = recover_secret(&mut rng, &mut victim, args.bound, args.bsgs_bits);
+    Ok(Report::new()
+        .field("seed", seed.to_string())
+        .field("secret", format!("{:x}", victim.secret()))
+        .field(
+            "recovered",
+            recovered.map_or("handshake rejected".into(), |x| format!("{x:x}")),
+        )
+        .field("handshakes", victim.queries()))
+}
@@ -0,0 +1,47 @@
+//! Diffie-Hellman groups validation (`dh-groups` crate).
+
+use crate::{input::Uint, output::Report, Result};
+use clap::Args;
+use dh_groups::{DhGroup, Named};
+
+/// Validate Diffie-Hellman parameters and public keys.
+#[derive(Args)]
+pub struct DhArgs {
+    /// Standardized group, e.g. ffdhe2048 or modp2048.
+    #[arg(long, conflicts_with = "p", default_value = "ffdhe2048")]
+    group: Named,
+    /// Custom modulus.
+    #[arg(short)]
+    p: Option<Uint>,
+    /// Custom generator.
+    #[arg(short, requires = "p", default_value = "2")]
+    g: Uint,
+    /// Custom subgroup order (default (p - 1)/2).
+    #[arg(short, requires = "p")]
+    q: Option<Uint>,
+    /// Peer public key.
+    #[arg(short = 'y', long)]
+    public: Option<Uint>,
+}
+
+pub fn run(args: DhArgs) -> Result<Report> {
+    let (name, group) = match args.p {
+        Some(p) => (
+            "custom",
+            DhGroup::custom(p.0, args.g.0, args.q.map(|q| q.0)),
+        ),
+        None => (args.group.name(), args.group.group()),
+    };
+    let verdict = |result: std::result::Result<(), _>| match result {
+        Ok(()) => "valid".to_string(),
+        Err(e) => format!("{e:?}"),
+    };
+    let report = Report::new()
+        .field("group", name)
+        .field("bits", group.p.bits())
+        .field("parameters", verdict(group.validate()));
+    match args.public {
+        Some(y) => Ok(report.field("public", verdict(group.validate_public(&y.0)))),
+        None => Ok(report),
+    }
+}
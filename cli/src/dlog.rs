@@ -0,0 +1,49 @@
+//! Discrete logarithm (`shanks` crate).
+
+use crate::{
+    input::{self, Uint},
+    output::Report,
+    Result,
+};
+use clap::Args;
+use shanks::auto::{solve_dlog_auto, Hint};
+use std::ops::Range;
+
+/// Find `x` such that `g^x = h (mod n)`.
+#[derive(Args)]
+pub struct DlogArgs {
+    /// Prime modulus.
+    #[arg(short, long)]
+    n: Uint,
+    /// Generator.
+    #[arg(short, long)]
+    g: Uint,
+    /// Target element.
+    #[arg(long)]
+    h: Uint,
+    /// Order of `g`, if not `n - 1`. Must fit 64 bits.
+    #[arg(long, value_parser = input::u64)]
+    order: Option<u64>,
+    /// Interval known to contain the exponent (`start..end`).
+    #[arg(long, value_parser = input::range)]
+    range: Option<Range<u64>>,
+    /// Memory budget in bytes (default half of the available memory).
+    #[arg(long, value_parser = input::u64)]
+    memory: Option<u64>,
+}
+
+pub fn run(args: DlogArgs) -> Result<Report> {
+    let (n, g, h) = (args.n.0, args.g.0, args.h.0);
+    let hint = Hint {
+        order: args.order,
+        range: args.range,
+        memory: args.memory,
+    };
+    let (x, plan) =
+        solve_dlog_auto(n, g, h, hint).map_err(|e| format!("Unsupported group: {e}"))?;
+    let x = x.ok_or("Logarithm not found")?;
+    Ok(Report::new()
+        .field("x", x.to_string())
+        .field("algorithm", format!("{:?}", plan.algorithm))
+        .field("reasons", plan.reasons))
+}
@@ -0,0 +1,51 @@
+//! DSA/Elgamal parameters audit (`dsa-audit` crate).
+
+use crate::{input::Uint, output::Report, Result};
+use clap::Args;
+use dsa_audit::{audit, Limits, Params};
+
+/// Look for weak domain parameters, recovering the secret when the order of
+/// the generator is smooth.
+#[derive(Args)]
+pub struct DsaArgs {
+    /// Modulus.
+    #[arg(short)]
+    p: Uint,
+    /// Subgroup order (Elgamal parameters may omit it).
+    #[arg(short)]
+    q: Option<Uint>,
+    /// Generator.
+    #[arg(short)]
+    g: Uint,
+    /// Public key.
+    #[arg(short = 'y', long)]
+    public: Uint,
+    /// Bits of the largest prime factor of the generator order worth a
+    /// discrete logarithm.
+    #[arg(long, default_value_t = Limits::default().dlog_bits)]
+    dlog_bits: u64,
+}
+
+pub fn run(args: DsaArgs) -> Result<Report> {
+    let params = Params {
+        p: args.p.0,
+        q: args.q.map(|q| q.0),
+        g: args.g.0,
+        y: args.public.0,
+    };
+    let limits = Limits {
+        dlog_bits: args.dlog_bits,
+        ..Limits::default()
+    };
+    let report = audit(&params, &limits);
+    let findings: Vec<_> = report.findings.iter().map(|f| f.to_string()).collect();
+    Ok(Report::new()
+        .field("p_prime", report.p_prime)
+        .field("q_prime", report.q_prime)
+        .field("findings", findings)
+        .field(
+            "order",
+            report.order.as_ref().map(|(order, _)| order.to_string()),
+        )
+        .field("secret", report.secret.map(|x| x.to_string())))
+}
@@ -0,0 +1,77 @@
+//! Enigma crib attack (`enigma` crate).
+
+use crate::{output::Report, Result};
+use clap::Args;
+use enigma::{
+    attacks::{bombe, crib_offsets},
+    letters, to_string, Enigma, REFLECTORS,
+};
+
+/// Recover rotor order, start position and plugs from a crib.
+#[derive(Args)]
+pub struct EnigmaArgs {
+    /// Ciphertext (letters only are kept).
+    ciphertext: String,
+    /// Known plaintext fragment.
+    #[arg(short, long)]
+    crib: String,
+    /// Crib position in the ciphertext.
+    #[arg(short, long, default_value_t = 0)]
+    offset: usize,
+    /// Ring settings, left to right (e.g. `AAA`).
+    #[arg(short, long, default_value = "AAA")]
+    rings: String,
+    /// Reflector (0 for B, 1 for C).
+    #[arg(long, default_value_t = 0)]
+    reflector: usize,
+}
+
+pub fn run(args: EnigmaArgs) -> Result<Report> {
+    let ct = letters(&args.ciphertext);
+    let crib = letters(&args.crib);
+    if crib.is_empty() || args.offset + crib.len() > ct.len() {
+        return Err("The crib doesn't fit the ciphertext".into());
+    }
+    let rings: [u8; 3] = letters(&args.rings)
+        .try_into()
+        .map_err(|_| "Rings are three letters")?;
+    if args.reflector >= REFLECTORS.len() {
+        return Err("Unknown reflector".into());
+    }
+    // A letter is never encrypted to itself
+    if !crib_offsets(&ct, &crib).contains(&args.offset) {
+        return Err("The crib can't lie at this offset".into());
+    }
+    let stops = bombe(
+        &ct,
+        &crib,
+        args.offset,
+        &[0, 1, 2, 3, 4],
+        args.reflector,
+        rings,
+    );
+    let settings: Vec<_> = stops
+        .iter()
+        .map(|stop| {
+            let plugs: Vec<_> = stop
+                .plugboard
+                .pairs()
+                .iter()
+                .map(|&(a, b)| to_string(&[a, b]))
+                .collect();
+            format!(
+                "rotors {:?}, positions {}, plugs {}",
+                stop.rotors.map(|r| r + 1),
+                to_string(&stop.positions),
+                plugs.join(" ")
+            )
+        })
+        .collect();
+    let plaintexts: Vec<_> = stops
+        .iter()
+        .map(|stop| to_string(&Enigma::new(stop).process(&ct)))
+        .collect();
+    Ok(Report::new()
+        .field("settings", settings)
+        .field("plaintexts", plaintexts))
+}
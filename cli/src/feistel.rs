@@ -0,0 +1,64 @@
+//! Luby-Rackoff distinguishers (`feistel` crate).
+
+use crate::{input::Bytes, output::Report, Result};
+use clap::Args;
+use feistel::{distinguisher, Feistel, Layout, Permutation, Prf, RandomPermutation};
+
+/// Tell a Feistel network with few rounds from a random permutation.
+#[derive(Args)]
+pub struct FeistelArgs {
+    /// Network rounds, 0 for a random permutation.
+    #[arg(short, long, default_value_t = 3)]
+    rounds: usize,
+    /// Bits of each half of the block.
+    #[arg(long, default_value_t = 16)]
+    half_bits: u32,
+    /// Round functions key (default random).
+    #[arg(short, long)]
+    key: Option<Bytes>,
+}
+
+fn verdict(feistel: bool) -> &'static str {
+    if feistel {
+        "feistel"
+    } else {
+        "random"
+    }
+}
+
+fn distinguish<P: Permutation>(oracle: &P, layout: &Layout) -> Report {
+    let queries = 8 * (layout.right as f64).sqrt() as u64;
+    let cpa = distinguisher::three_rounds_cpa(oracle, layout, queries).map_or("-", verdict);
+    Report::new()
+        .field(
+            "one_round",
+            verdict(distinguisher::one_round(oracle, layout, 8)),
+        )
+        .field(
+            "two_rounds",
+            verdict(distinguisher::two_rounds(oracle, layout, 8)),
+        )
+        .field("three_rounds_cpa", cpa)
+        .field(
+            "three_rounds_cca",
+            verdict(distinguisher::three_rounds_cca(oracle, layout, 8)),
+        )
+}
+
+pub fn run(args: FeistelArgs) -> Result<Report> {
+    if !(2..=31).contains(&args.half_bits) {
+        return Err("Halves have 2 to 31 bits".into());
+    }
+    let layout = Layout::binary(args.half_bits);
+    if args.rounds == 0 {
+        return Ok(distinguish(
+            &RandomPermutation::new(layout.domain(), 0),
+            &layout,
+        ));
+    }
+    let key = args
+        .key
+        .map_or_else(|| rand::random::<[u8; 16]>().to_vec(), |k| k.0);
+    let feistel = Feistel::new(Prf::new(&key), args.rounds, layout);
+    Ok(distinguish(&feistel, &layout))
+}
@@ -0,0 +1,67 @@
+//! Lecture notes example sets (`lecture-fixtures` crate).
+
+use crate::{output::Report, Result};
+use clap::Args;
+use lecture_fixtures::{Bundle, Params};
+use std::path::PathBuf;
+
+/// Generate the reproducible fixtures of a seed.
+#[derive(Args)]
+pub struct FixturesArgs {
+    /// Single seed of all the fixtures.
+    #[arg(short, long, default_value_t = 0)]
+    seed: u64,
+    /// RSA modulus bits.
+    #[arg(long, default_value_t = Params::default().rsa_bits)]
+    rsa_bits: u64,
+    /// DLP subgroup order bits (at most 63).
+    #[arg(long, default_value_t = Params::default().dlp_bits)]
+    dlp_bits: u64,
+    /// Write the JSON files and the markdown summary in this directory
+    /// instead of reporting the fixtures (the Fermat trace and the oracle
+    /// queries are only written).
+    #[arg(short, long)]
+    out: Option<PathBuf>,
+}
+
+pub fn run(args: FixturesArgs) -> Result<Report> {
+    // The sample message must fit the modulus
+    if args.rsa_bits < 128 {
+        return Err("At least 128 RSA modulus bits are required".into());
+    }
+    if !(2..64).contains(&args.dlp_bits) {
+        return Err("DLP subgroup order bits must be in 2..64".into());
+    }
+    let params = Params {
+        rsa_bits: args.rsa_bits,
+        dlp_bits: args.dlp_bits,
+    };
+    let bundle = Bundle::generate(args.seed, params);
+    let report = Report::new().field("seed", args.seed);
+    match args.out {
+        Some(dir) => {
+            bundle.write(&dir)?;
+            Ok(report.field("written", dir.display().to_string()))
+        }
+        None => {
+            let (rsa, dlp, oracle) = (&bundle.rsa, &bundle.dlp, &bundle.padding_oracle);
+            Ok(report
+                .field("rsa_n", rsa.n.as_str())
+                .field("rsa_e", rsa.e)
+                .field("rsa_p", rsa.p.as_str())
+                .field("rsa_q", rsa.q.as_str())
+                .field("rsa_ciphertext", rsa.ciphertext.as_str())
+                .field("fermat_steps", rsa.trace.len())
+                .field("dlp_p", dlp.p.as_str())
+                .field("dlp_q", dlp.q)
+                .field("dlp_g", dlp.g.as_str())
+                .field("dlp_h", dlp.h.as_str())
+                .field("dlp_x", dlp.x)
+                .field("oracle_key", oracle.key.as_str())
+                .field("oracle_iv", oracle.iv.as_str())
+                .field("oracle_ciphertext", oracle.ciphertext.as_str())
+                .field("oracle_queries", oracle.queries.len())
+                .field("oracle_recovered", oracle.recovered.as_str()))
+        }
+    }
+}
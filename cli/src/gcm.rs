@@ -0,0 +1,60 @@
+//! AES-GCM nonce reuse (`gcm-forbidden` crate).
+
+use crate::{output::Report, Result};
+use clap::Args;
+use gcm_forbidden::{attacks::recover, Sealed};
+
+/// Recover the GHASH key from messages sharing the nonce and forge tags.
+#[derive(Args)]
+pub struct GcmArgs {
+    /// Messages sealed under the same key and nonce, as `<aad>:<ct>:<tag>`
+    /// (hex, the associated data may be empty).
+    #[arg(required = true, num_args = 2..)]
+    sealed: Vec<String>,
+    /// Ciphertext to forge a tag for, as `<aad>:<ct>` (hex).
+    #[arg(short, long)]
+    forge: Option<String>,
+}
+
+// Split `s` in `N` colon separated hex fields.
+fn fields<const N: usize>(s: &str) -> Result<[Vec<u8>; N]> {
+    let parts: Vec<_> = s
+        .split(':')
+        .map(hex::decode)
+        .collect::<std::result::Result<_, _>>()?;
+    parts
+        .try_into()
+        .map_err(|_| format!("'{s}' doesn't have {N} colon separated fields").into())
+}
+
+pub fn run(args: GcmArgs) -> Result<Report> {
+    let samples = args
+        .sealed
+        .iter()
+        .map(|s| {
+            let [aad, ciphertext, tag] = fields(s)?;
+            let tag = tag.try_into().map_err(|_| "Tags are 16 bytes")?;
+            Ok(Sealed {
+                aad,
+                ciphertext,
+                tag,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let forgers = recover(&samples, &mut rand::thread_rng());
+    let [forger] = forgers[..] else {
+        return Err(format!(
+            "{} keys consistent with the messages, the nonce is not shared or more messages are needed",
+            forgers.len()
+        )
+        .into());
+    };
+    let report = Report::new()
+        .field("h", hex::encode(forger.h.to_bytes()))
+        .field("mask", hex::encode(forger.mask.to_bytes()));
+    let Some(forge) = args.forge else {
+        return Ok(report);
+    };
+    let [aad, ciphertext] = fields(&forge)?;
+    Ok(report.field("forged_tag", hex::encode(forger.tag(&aad, &ciphertext))))
+}
@@ -0,0 +1,54 @@
+//! Hash length extension (`length-extension` crate).
+
+use crate::{input::Bytes, output::Report, Result};
+use clap::{Args, ValueEnum};
+use length_extension::{
+    attacks::{extend, Forgery},
+    sha::{Sha1, Sha256},
+};
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Hash {
+    Sha1,
+    Sha256,
+}
+
+/// Forge a `H(secret || message)` tag for an extended message.
+#[derive(Args)]
+pub struct ExtendArgs {
+    /// Hash function.
+    #[arg(long, value_enum, default_value_t = Hash::Sha256)]
+    hash: Hash,
+    /// Original message (text).
+    #[arg(short, long)]
+    message: String,
+    /// Original message tag.
+    #[arg(short, long)]
+    tag: Bytes,
+    /// Appended text.
+    #[arg(short, long)]
+    suffix: String,
+    /// Secret length in bytes.
+    #[arg(short = 'l', long)]
+    secret_len: usize,
+}
+
+pub fn run(args: ExtendArgs) -> Result<Report> {
+    let (message, tag, suffix) = (args.message.as_bytes(), &args.tag.0, args.suffix.as_bytes());
+    let digest_len = match args.hash {
+        Hash::Sha1 => 20,
+        Hash::Sha256 => 32,
+    };
+    if tag.len() != digest_len {
+        return Err(format!("Tag length must be {digest_len} bytes").into());
+    }
+    let Forgery { message, tag } = match args.hash {
+        Hash::Sha1 => extend::<Sha1>(message, tag, args.secret_len, suffix),
+        Hash::Sha256 => extend::<Sha256>(message, tag, args.secret_len, suffix),
+    }
+    .ok_or("Message too long for the hash length field")?;
+    Ok(Report::new()
+        .field("message", message.escape_ascii().to_string())
+        .field("message_hex", hex::encode(message))
+        .field("tag", hex::encode(tag)))
+}
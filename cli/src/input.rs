@@ -0,0 +1,94 @@
+//! Command line values parsing.
+//!
+//! - Byte strings: hex (optionally `0x` prefixed) or base64, whitespace is
+//!   ignored. Hex wins when both decodings are possible.
+//! - Integers: decimal or `0x` prefixed hex.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use num_bigint::BigUint;
+use num_traits::Num;
+use std::{fs, ops::Range, path::Path, str::FromStr};
+
+/// Hex or base64 encoded bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bytes(pub Vec<u8>);
+
+impl FromStr for Bytes {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let s: String = s.split_whitespace().collect();
+        let h = s.strip_prefix("0x").unwrap_or(&s);
+        if let Ok(bytes) = hex::decode(h) {
+            return Ok(Bytes(bytes));
+        }
+        BASE64
+            .decode(&s)
+            .map(Bytes)
+            .map_err(|_| format!("'{s}' is neither hex nor base64"))
+    }
+}
+
+impl Bytes {
+    /// File content decoded as hex or base64 text, falling back to raw bytes.
+    pub fn read(path: &Path) -> std::io::Result<Self> {
+        let raw = fs::read(path)?;
+        let decoded = std::str::from_utf8(&raw).ok().and_then(|s| s.parse().ok());
+        Ok(decoded.unwrap_or(Bytes(raw)))
+    }
+}
+
+/// Decimal or hex unsigned integer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Uint(pub BigUint);
+
+impl FromStr for Uint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let (digits, radix) = match s.strip_prefix("0x") {
+            Some(h) => (h, 16),
+            None => (s, 10),
+        };
+        BigUint::from_str_radix(&digits.replace('_', ""), radix)
+            .map(Uint)
+            .map_err(|_| format!("'{s}' is not a decimal or 0x prefixed hex integer"))
+    }
+}
+
+/// Parse a `u64` as decimal or `0x` prefixed hex.
+pub fn u64(s: &str) -> Result<u64, String> {
+    let x: Uint = s.parse()?;
+    u64::try_from(x.0).map_err(|_| format!("'{s}' doesn't fit 64 bits"))
+}
+
+/// Parse a `start..end` range of `u64`.
+pub fn range(s: &str) -> Result<Range<u64>, String> {
+    let (start, end) = s
+        .split_once("..")
+        .ok_or_else(|| format!("'{s}' is not a start..end range"))?;
+    Ok(u64(start)?..u64(end)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_encodings() {
+        let hello = Bytes(b"hello".to_vec());
+        assert_eq!("68656c6c6f".parse(), Ok(hello.clone()));
+        assert_eq!("0x68656C6C6F".parse(), Ok(hello.clone()));
+        assert_eq!("aGVs bG8=".parse(), Ok(hello));
+        assert!("not?base64".parse::<Bytes>().is_err());
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!("1_000".parse(), Ok(Uint(BigUint::from(1000_u32))));
+        assert_eq!("0xff".parse(), Ok(Uint(BigUint::from(255_u32))));
+        assert!("ff".parse::<Uint>().is_err());
+        assert_eq!(range("10..0x20"), Ok(10..32));
+        assert!(u64("0x10000000000000000").is_err());
+    }
+}
@@ -0,0 +1,43 @@
+//! LFSR synthesis (`lfsr` crate).
+
+use crate::{output::Report, Result};
+use clap::Args;
+use lfsr::{attacks::predict, berlekamp_massey};
+
+/// Shortest LFSR generating a bit sequence, and its next bits.
+#[derive(Args)]
+pub struct LfsrArgs {
+    /// Known keystream bits, e.g. `0110100`. Whitespace is ignored.
+    bits: String,
+    /// Number of predicted bits.
+    #[arg(short, long, default_value_t = 64)]
+    count: usize,
+}
+
+fn to_string(bits: &[u8]) -> String {
+    bits.iter().map(|b| char::from(b'0' + b)).collect()
+}
+
+pub fn run(args: LfsrArgs) -> Result<Report> {
+    let bits = args
+        .bits
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '0' => Ok(0),
+            '1' => Ok(1),
+            _ => Err(format!("'{c}' is not a bit")),
+        })
+        .collect::<std::result::Result<Vec<u8>, _>>()?;
+    let (poly, len) = berlekamp_massey(&bits);
+    let report = Report::new()
+        .field("complexity", len)
+        .field("polynomial", to_string(&poly[..=len]));
+    match predict(&bits, args.count) {
+        Some((_, next)) => Ok(report.field("next", to_string(&next))),
+        None => Ok(report.field(
+            "next",
+            format!("undetermined, {} bits are required", 2 * len),
+        )),
+    }
+}
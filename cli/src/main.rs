@@ -0,0 +1,173 @@
+//! Single entry point to the attacks of the other crates.
+//!
+//! Each subcommand drives the library API of one PoC with user supplied
+//! parameters. Byte strings are given as hex or base64 and integers as decimal
+//! or hex (see [`input`]). Results are printed as text or, with `--json`, as a
+//! JSON object.
+//!
+//! Not covered, as their only inputs are the knobs of their own simulations
+//! (see each crate binary) or they need a live target: `a5-1`,
+//! `aes-cache-timing`, `chunked-encryption`, `cube-attack`,
+//! `deterministic-encryption`, `dh-mitm`, `ed25519-dalek-secret-recovery`,
+//! `ed25519-malleability`, `fiat-shamir-weak-challenge`, `fpe-small-domain`,
+//! `hash-to-group`, `kdf-domain-separation`, `lorenz`, `merkle-tree`,
+//! `order-preserving`, `power-analysis`, `remote-timing`, `rsa-accumulator`,
+//! `second-preimage` and `sketch-pollution`. The `numutil`, `par-search` and
+//! `prelude` crates are libraries, `des-sbox-eval` and `rsa-fails` Python
+//! scripts.
+
+use clap::{Parser, Subcommand};
+use std::error::Error;
+
+mod auction;
+mod birthday;
+mod cbc;
+mod classical;
+mod coin;
+mod coldboot;
+mod crc;
+mod curve;
+mod dh;
+mod dlog;
+mod dsa;
+mod enigma;
+mod feistel;
+mod fixtures;
+mod gcm;
+mod hash;
+mod input;
+mod lfsr;
+mod mtp;
+mod nonce;
+mod output;
+mod pow;
+mod prime;
+mod prng;
+mod siphash;
+mod sponge;
+mod timing;
+mod vrf;
+mod wallet;
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+#[derive(Parser)]
+#[command(name = "crypto-hacks", about = "Crypto related hacks and stuff")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Print the result as JSON.
+    #[arg(long, global = true)]
+    json: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Discrete logarithm via baby-step giant-step, rho or kangaroo.
+    Shanks(dlog::DlogArgs),
+    /// Random prime generation.
+    Prime(prime::PrimeArgs),
+    /// CBC padding oracle attack.
+    #[command(subcommand)]
+    CbcOracle(cbc::CbcCommand),
+    /// Group operation timing attack simulation.
+    Timing(timing::TimingArgs),
+    /// CRC computation and forgery.
+    Crc(crc::CrcArgs),
+    /// Hash length extension.
+    LengthExtension(hash::ExtendArgs),
+    /// Non cryptographic random generators cracking.
+    #[command(subcommand)]
+    Prng(prng::PrngCommand),
+    /// Classical ciphers ciphertext only attacks.
+    Classical(classical::ClassicalArgs),
+    /// Many-time pad keystream recovery.
+    ManyTimePad(mtp::MtpArgs),
+    /// AES-GCM forbidden attack on repeated nonces.
+    GcmForbidden(gcm::GcmArgs),
+    /// ECDSA nonce reuse key recovery.
+    NonceReuse(nonce::NonceArgs),
+    /// LFSR synthesis via Berlekamp-Massey.
+    Lfsr(lfsr::LfsrArgs),
+    /// Diffie-Hellman group and public key validation.
+    DhGroups(dh::DhArgs),
+    /// Reproducible lecture fixtures.
+    LectureFixtures(fixtures::FixturesArgs),
+    /// SipHash weak key recovery and hash flooding.
+    #[command(subcommand)]
+    Siphash(siphash::SiphashCommand),
+    /// Invalid curve attack simulation.
+    InvalidCurve(curve::CurveArgs),
+    /// Coin flipping commitments simulation.
+    CoinFlipping(coin::CoinArgs),
+    /// VRF output grinding.
+    VrfGrinding(vrf::VrfArgs),
+    /// Feistel network distinguishers.
+    Feistel(feistel::FeistelArgs),
+    /// Sponge construction misuse.
+    #[command(subcommand)]
+    Sponge(sponge::SpongeCommand),
+    /// Cold boot key recovery from decayed memory.
+    #[command(subcommand)]
+    ColdBoot(coldboot::ColdBootCommand),
+    /// Truncated hash collisions.
+    #[command(subcommand)]
+    Birthday(birthday::BirthdayCommand),
+    /// Hashcash puzzle solving.
+    ProofOfWork(pow::PowArgs),
+    /// Unsalted sealed bid opening.
+    CommitReveal(auction::AuctionArgs),
+    /// Enigma crib attack.
+    Enigma(enigma::EnigmaArgs),
+    /// DSA/Elgamal domain parameters audit.
+    DsaAudit(dsa::DsaArgs),
+    /// Brain wallet dictionary attack.
+    BrainWallet(wallet::WalletArgs),
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let report = match cli.command {
+        Command::Shanks(args) => dlog::run(args)?,
+        Command::Prime(args) => prime::run(args)?,
+        Command::CbcOracle(command) => cbc::run(command)?,
+        Command::Timing(args) => timing::run(args)?,
+        Command::Crc(args) => crc::run(args)?,
+        Command::LengthExtension(args) => hash::run(args)?,
+        Command::Prng(command) => prng::run(command)?,
+        Command::Classical(args) => classical::run(args)?,
+        Command::ManyTimePad(args) => mtp::run(args)?,
+        Command::GcmForbidden(args) => gcm::run(args)?,
+        Command::NonceReuse(args) => nonce::run(args)?,
+        Command::Lfsr(args) => lfsr::run(args)?,
+        Command::DhGroups(args) => dh::run(args)?,
+        Command::LectureFixtures(args) => fixtures::run(args)?,
+        Command::Siphash(command) => siphash::run(command)?,
+        Command::InvalidCurve(args) => curve::run(args)?,
+        Command::CoinFlipping(args) => coin::run(args)?,
+        Command::VrfGrinding(args) => vrf::run(args)?,
+        Command::Feistel(args) => feistel::run(args)?,
+        Command::Sponge(command) => sponge::run(command)?,
+        Command::ColdBoot(command) => coldboot::run(command)?,
+        Command::Birthday(command) => birthday::run(command)?,
+        Command::ProofOfWork(args) => pow::run(args)?,
+        Command::CommitReveal(args) => auction::run(args)?,
+        Command::Enigma(args) => enigma::run(args)?,
+        Command::DsaAudit(args) => dsa::run(args)?,
+        Command::BrainWallet(args) => wallet::run(args)?,
+    };
+    report.print(cli.json);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn arguments_are_consistent() {
+        // Clashing flags are otherwise only caught when the subcommand runs
+        Cli::command().debug_assert();
+    }
+}
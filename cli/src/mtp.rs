@@ -0,0 +1,43 @@
+//! Keystream reuse solver (`many-time-pad` crate).
+
+use crate::{input::Bytes, output::Report, Result};
+use clap::Args;
+use many_time_pad::solver::Solver;
+
+/// Decrypt messages encrypted with the same keystream.
+#[derive(Args)]
+pub struct MtpArgs {
+    /// Ciphertexts sharing the keystream.
+    #[arg(required = true)]
+    ciphertexts: Vec<Bytes>,
+    /// Known plaintext `<msg>:<pos>:<text>`, fixing the keystream over it.
+    #[arg(short, long)]
+    crib: Vec<String>,
+}
+
+// Parse a `<msg>:<pos>:<text>` crib.
+fn crib(s: &str) -> Result<(usize, usize, &str)> {
+    let mut parts = s.splitn(3, ':');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(msg), Some(pos), Some(text)) => Ok((msg.parse()?, pos.parse()?, text)),
+        _ => Err(format!("'{s}' is not a <msg>:<pos>:<text> crib").into()),
+    }
+}
+
+pub fn run(args: MtpArgs) -> Result<Report> {
+    let count = args.ciphertexts.len();
+    let mut solver = Solver::new(args.ciphertexts.into_iter().map(|c| c.0).collect());
+    for s in &args.crib {
+        let (msg, pos, text) = crib(s)?;
+        solver
+            .crib(msg, pos, text.as_bytes())
+            .map_err(|e| format!("Crib '{s}': {e:?}"))?;
+    }
+    // Statistical guess for the columns not covered by the cribs
+    solver.auto();
+    let keystream: Vec<u8> = solver.key().iter().map(|k| k.unwrap_or(0)).collect();
+    let plaintexts: Vec<_> = (0..count).map(|i| solver.render(i)).collect();
+    Ok(Report::new()
+        .field("keystream", hex::encode(keystream))
+        .field("plaintexts", plaintexts))
+}
@@ -0,0 +1,44 @@
+//! ECDSA nonce reuse (`nonce-reuse` crate).
+
+use crate::{input::Bytes, output::Report, Result};
+use clap::Args;
+use k256::ecdsa::{Signature, VerifyingKey};
+use nonce_reuse::ecdsa::{find_reuse, recover_key};
+
+/// Recover a secp256k1 signing key from two signatures sharing the nonce.
+#[derive(Args)]
+pub struct NonceArgs {
+    /// Public key (SEC1, compressed or not).
+    #[arg(short, long)]
+    public: Bytes,
+    /// Signed messages, as `<msg>:<sig>` (hex, raw `r || s` or DER
+    /// signature). Messages are hashed with SHA-256.
+    #[arg(required = true, num_args = 2..)]
+    signed: Vec<String>,
+}
+
+pub fn run(args: NonceArgs) -> Result<Report> {
+    let public = VerifyingKey::from_sec1_bytes(&args.public.0).map_err(|_| "Invalid public key")?;
+    let (msgs, sigs): (Vec<_>, Vec<_>) = args
+        .signed
+        .iter()
+        .map(|s| {
+            let (msg, sig) = s
+                .split_once(':')
+                .ok_or(format!("'{s}' is not <msg>:<sig>"))?;
+            let sig = hex::decode(sig)?;
+            let sig = Signature::from_slice(&sig)
+                .or_else(|_| Signature::from_der(&sig))
+                .map_err(|_| format!("Invalid signature '{s}'"))?;
+            Ok((hex::decode(msg)?, sig))
+        })
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .unzip();
+    let (i, j) = find_reuse(&sigs).ok_or("No signatures share the nonce")?;
+    let key = recover_key(&public, (&msgs[i], &sigs[i]), (&msgs[j], &sigs[j]))
+        .ok_or("The signatures don't match the public key")?;
+    Ok(Report::new()
+        .field("signatures", vec![i, j])
+        .field("secret", hex::encode(key.to_bytes())))
+}
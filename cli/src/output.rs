@@ -0,0 +1,86 @@
+//! Commands results, printed as text or as a JSON object.
+
+use serde::{ser::SerializeMap, Serialize, Serializer};
+use serde_json::Value;
+use std::fmt;
+
+/// Ordered list of named values.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Report(Vec<(&'static str, Value)>);
+
+impl Report {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a field. Big integers are given as strings.
+    pub fn field(mut self, name: &'static str, value: impl Into<Value>) -> Self {
+        self.0.push((name, value.into()));
+        self
+    }
+
+    pub fn print(&self, json: bool) {
+        if json {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(self).expect("serializable")
+            );
+        } else {
+            print!("{self}");
+        }
+    }
+}
+
+impl Serialize for Report {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (name, value) in &self.0 {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.0.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+        for (name, value) in &self.0 {
+            match value {
+                Value::String(s) => writeln!(f, "{name:width$} : {s}")?,
+                Value::Array(items) => {
+                    writeln!(f, "{name:width$} :")?;
+                    for item in items {
+                        match item {
+                            Value::String(s) => writeln!(f, "  - {s}")?,
+                            _ => writeln!(f, "  - {item}")?,
+                        }
+                    }
+                }
+                _ => writeln!(f, "{name:width$} : {value}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_and_json() {
+        let report = Report::new()
+            .field("x", "12345678901234567890")
+            .field("found", true)
+            .field("reasons", vec!["cheap", "fast"]);
+        assert_eq!(
+            report.to_string(),
+            "x       : 12345678901234567890\nfound   : true\nreasons :\n  - cheap\n  - fast\n"
+        );
+        // Fields order is preserved.
+        assert_eq!(
+            serde_json::to_string(&report).unwrap(),
+            r#"{"x":"12345678901234567890","found":true,"reasons":["cheap","fast"]}"#
+        );
+    }
+}
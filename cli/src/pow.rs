@@ -0,0 +1,48 @@
+//! Hashcash puzzles (`proof-of-work` crate).
+
+use crate::{output::Report, Result};
+use clap::Args;
+use proof_of_work::{Design, Puzzle};
+use std::time::Instant;
+
+/// Solve a SHA-256 leading zeros puzzle.
+#[derive(Args)]
+pub struct PowArgs {
+    /// Challenge (text).
+    challenge: String,
+    /// Required leading zero bits.
+    #[arg(short, long, default_value_t = 20)]
+    bits: u32,
+    /// Split in this number (a power of two) of easier sub-puzzles.
+    #[arg(long, default_value_t = 1)]
+    parts: u32,
+    /// Search with all the available cores.
+    #[arg(long)]
+    parallel: bool,
+}
+
+pub fn run(args: PowArgs) -> Result<Report> {
+    if !args.parts.is_power_of_two() || args.parts.trailing_zeros() > args.bits {
+        return Err("Parts must be a power of two not greater than 2^bits".into());
+    }
+    // Sub-puzzles are log2(parts) bits easier
+    if args.bits - args.parts.trailing_zeros() > 40 {
+        return Err("At most 40 bits per sub-puzzle".into());
+    }
+    let design = match args.parts {
+        1 => Design::ProgressFree,
+        n => Design::Parts(n),
+    };
+    let puzzle = Puzzle::new(args.challenge.as_bytes(), args.bits, design);
+    let start = Instant::now();
+    let report = Report::new();
+    let (stamp, report) = if args.parallel {
+        (puzzle.solve_parallel(), report)
+    } else {
+        let (stamp, attempts) = puzzle.solve();
+        (stamp, report.field("attempts", attempts))
+    };
+    Ok(report
+        .field("stamp", stamp)
+        .field("seconds", start.elapsed().as_secs_f64()))
+}
@@ -0,0 +1,32 @@
+//! Random primes generation (`miller-rabin` crate).
+
+use crate::{output::Report, Result};
+use clap::Args;
+
+/// Generate a random prime.
+#[derive(Args)]
+pub struct PrimeArgs {
+    /// Prime size in bits.
+    #[arg(short, long, default_value_t = 1024)]
+    bits: usize,
+    /// Safe prime `p = 2q + 1`, with `q` prime.
+    #[arg(short, long)]
+    safe: bool,
+    /// Max number of candidates (default depends on the prime kind).
+    #[arg(short, long)]
+    attempts: Option<usize>,
+}
+
+pub fn run(args: PrimeArgs) -> Result<Report> {
+    if args.bits < 3 {
+        return Err("At least 3 bits are required".into());
+    }
+    let report = Report::new().field("bits", args.bits);
+    if args.safe {
+        let (p, q) = miller_rabin::safe_prime(args.bits, args.attempts).ok_or("No prime found")?;
+        Ok(report.field("p", p.to_string()).field("q", q.to_string()))
+    } else {
+        let p = miller_rabin::prime_num(args.bits, args.attempts).ok_or("No prime found")?;
+        Ok(report.field("p", p.to_string()))
+    }
+}
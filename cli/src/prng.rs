@@ -0,0 +1,78 @@
+//! Non cryptographic generators state recovery (`prng-cracking` crate).
+
+use crate::{input, output::Report, Result};
+use clap::Subcommand;
+use prng_cracking::{
+    attacks::{clone_mt, crack_lcg, crack_mt_seed},
+    mt::N,
+};
+use std::{fs, ops::Range, path::PathBuf};
+
+#[derive(Subcommand)]
+pub enum PrngCommand {
+    /// Clone a MT19937 from 624 consecutive outputs and predict the next ones.
+    MtClone {
+        /// File with the outputs (decimal or 0x hex, whitespace or comma
+        /// separated).
+        outputs: PathBuf,
+        /// Number of predicted outputs.
+        #[arg(short, long, default_value_t = 10)]
+        count: usize,
+    },
+    /// Find the MT19937 seed producing the given first output.
+    MtSeed {
+        /// First output.
+        #[arg(short, long, value_parser = input::u64)]
+        first: u64,
+        /// Seeds to try (`start..end`), e.g. a timestamps window.
+        #[arg(short, long, value_parser = input::range, default_value = "0..0x100000000")]
+        seeds: Range<u64>,
+    },
+    /// Recover the parameters of an LCG and predict the next outputs.
+    Lcg {
+        /// Consecutive outputs (about ten).
+        #[arg(value_parser = input::u64, required = true)]
+        outputs: Vec<u64>,
+        /// Number of predicted outputs.
+        #[arg(short, long, default_value_t = 10)]
+        count: usize,
+    },
+}
+
+// Read numbers separated by whitespaces or commas.
+fn read_numbers(path: &PathBuf) -> Result<Vec<u64>> {
+    fs::read_to_string(path)?
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|s| !s.is_empty())
+        .map(|s| Ok(input::u64(s)?))
+        .collect()
+}
+
+pub fn run(command: PrngCommand) -> Result<Report> {
+    match command {
+        PrngCommand::MtClone { outputs, count } => {
+            let outputs = read_numbers(&outputs)?
+                .into_iter()
+                .map(u32::try_from)
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            let mut mt = clone_mt(&outputs).ok_or(format!("At least {N} outputs are required"))?;
+            let next: Vec<_> = (0..count).map(|_| mt.next_u32()).collect();
+            Ok(Report::new().field("next", next))
+        }
+        PrngCommand::MtSeed { first, seeds } => {
+            let first = u32::try_from(first)?;
+            let seeds = (seeds.start..seeds.end.min(1 << 32)).map(|s| s as u32);
+            let seed = crack_mt_seed(first, seeds).ok_or("Seed not found")?;
+            Ok(Report::new().field("seed", seed))
+        }
+        PrngCommand::Lcg { outputs, count } => {
+            let mut lcg = crack_lcg(&outputs).ok_or("LCG parameters not found")?;
+            let report = Report::new()
+                .field("a", lcg.a)
+                .field("c", lcg.c)
+                .field("m", lcg.m);
+            let next: Vec<_> = (0..count).map(|_| lcg.next_u64()).collect();
+            Ok(report.field("next", next))
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! SipHash weak keys and HashDoS (`siphash-weak-key` crate).
+
+use crate::{
+    input::{self, Bytes},
+    output::Report,
+    Result,
+};
+use clap::Subcommand;
+use siphash_weak_key::{colliding_inputs, recover_key, required_samples, Key, Sample, WeakKey};
+use std::ops::Range;
+
+#[derive(Subcommand)]
+pub enum SiphashCommand {
+    /// Recover a weak key from message/tag pairs.
+    Recover {
+        /// Observed pairs `<msg>:<tag>` (hex message, integer tag).
+        #[arg(required = true)]
+        samples: Vec<String>,
+        /// Tag width in bits (short MACs are truncated).
+        #[arg(short, long, default_value_t = 64)]
+        tag_bits: u32,
+        /// Key with only the given number of low random bits.
+        #[arg(
+            short,
+            long,
+            conflicts_with = "timestamps",
+            required_unless_present = "timestamps"
+        )]
+        low_entropy: Option<u32>,
+        /// Key seeded by a Unix timestamp in this window (`start..end`).
+        #[arg(long, value_parser = input::range)]
+        timestamps: Option<Range<u64>>,
+    },
+    /// Inputs falling in the same bucket of a hash table.
+    Hashdos {
+        /// SipHash-2-4 key (16 bytes).
+        #[arg(short, long)]
+        key: Bytes,
+        /// Table buckets (power of two).
+        #[arg(short, long, default_value_t = 1 << 16)]
+        buckets: u64,
+        /// Number of colliding inputs.
+        #[arg(short, long, default_value_t = 16)]
+        count: usize,
+        /// Inputs prefix.
+        #[arg(short, long, default_value = "")]
+        prefix: String,
+    },
+}
+
+fn sample(s: &str) -> Result<Sample> {
+    let (msg, tag) = s
+        .split_once(':')
+        .ok_or(format!("'{s}' is not <msg>:<tag>"))?;
+    Ok(Sample {
+        msg: hex::decode(msg)?,
+        tag: input::u64(tag)?,
+    })
+}
+
+pub fn run(command: SiphashCommand) -> Result<Report> {
+    match command {
+        SiphashCommand::Recover {
+            samples,
+            tag_bits,
+            low_entropy,
+            timestamps,
+        } => {
+            if !(1..=64).contains(&tag_bits) {
+                return Err("Tags have 1 to 64 bits".into());
+            }
+            let space = match (low_entropy, timestamps) {
+                (Some(bits), _) if bits < 64 => WeakKey::LowEntropy { bits },
+                (Some(_), _) => return Err("Low entropy keys have less than 64 bits".into()),
+                (_, Some(window)) => WeakKey::Timestamp {
+                    start: window.start,
+                    window: window.end.saturating_sub(window.start),
+                },
+                (None, None) => unreachable!("required by the arguments"),
+            };
+            let samples = samples
+                .iter()
+                .map(|s| sample(s))
+                .collect::<Result<Vec<_>>>()?;
+            let key = recover_key(&space, &samples, tag_bits).ok_or("Key not found")?;
+            Ok(Report::new()
+                .field("key", hex::encode(key))
+                .field("required_samples", required_samples(&space, tag_bits)))
+        }
+        SiphashCommand::Hashdos {
+            key,
+            buckets,
+            count,
+            prefix,
+        } => {
+            let key: Key = key.0.try_into().map_err(|_| "Keys are 16 bytes")?;
+            if !buckets.is_power_of_two() {
+                return Err("Buckets must be a power of two".into());
+            }
+            Ok(Report::new().field("inputs", colliding_inputs(&key, &prefix, buckets, count)))
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Sponge capacity and padding misuse (`sponge` crate).
+
+use crate::{input::Bytes, output::Report, Result};
+use clap::Subcommand;
+use sponge::{
+    attacks::{extend, inner_collision, padding_collision, recover_state},
+    Padding, Sponge, WIDTH,
+};
+
+#[derive(Subcommand)]
+pub enum SpongeCommand {
+    /// Two prefixes colliding on the whole state, for any common suffix.
+    Collide {
+        /// Capacity in bytes (the search takes ≈ 2^(4·capacity) permutations).
+        #[arg(short, long, default_value_t = 3)]
+        capacity: usize,
+    },
+    /// Extend a `H(key || msg)` tag, recovering the capacity by exhaustive
+    /// search.
+    Extend {
+        /// Capacity in bytes (at most 3 to stay practical).
+        #[arg(short, long, default_value_t = 3)]
+        capacity: usize,
+        /// Original message (text).
+        #[arg(short, long)]
+        message: String,
+        /// Original tag, at least `rate + capacity` bytes.
+        #[arg(short, long)]
+        tag: Bytes,
+        /// Appended text.
+        #[arg(short, long)]
+        suffix: String,
+        /// Key length in bytes.
+        #[arg(short = 'l', long)]
+        key_len: usize,
+    },
+    /// Second message with the same hash under zero padding.
+    Pad {
+        /// Message (text).
+        message: String,
+        /// Rate in bytes.
+        #[arg(short, long, default_value_t = 16)]
+        rate: usize,
+    },
+}
+
+fn sponge(capacity: usize, padding: Padding) -> Result<Sponge> {
+    if !(1..=WIDTH - 4).contains(&capacity) {
+        return Err(format!("Capacity must be in 1..={}", WIDTH - 4).into());
+    }
+    Ok(Sponge::new(WIDTH - capacity, padding))
+}
+
+pub fn run(command: SpongeCommand) -> Result<Report> {
+    match command {
+        SpongeCommand::Collide { capacity } => {
+            let c = inner_collision(&sponge(capacity, Padding::Pad10Star1)?);
+            Ok(Report::new()
+                .field("prefix1", hex::encode(&c.prefix1))
+                .field("prefix2", hex::encode(&c.prefix2))
+                .field("permutations", c.evaluations))
+        }
+        SpongeCommand::Extend {
+            capacity,
+            message,
+            tag,
+            suffix,
+            key_len,
+        } => {
+            let sponge = sponge(capacity, Padding::Pad10Star1)?;
+            if capacity > 3 {
+                return Err("Capacity too large for an exhaustive search".into());
+            }
+            let tag = tag.0;
+            if tag.len() < sponge.rate() + capacity {
+                return Err(format!("At least {} tag bytes are required", WIDTH).into());
+            }
+            let state = recover_state(&sponge, &tag).ok_or("State not found")?;
+            let forged = extend(&sponge, state, suffix.as_bytes(), tag.len());
+            let mut extended = message.into_bytes();
+            extended.extend(sponge.padding_for(key_len + extended.len()));
+            extended.extend(suffix.as_bytes());
+            Ok(Report::new()
+                .field("message", extended.escape_ascii().to_string())
+                .field("message_hex", hex::encode(&extended))
+                .field("tag", hex::encode(forged)))
+        }
+        SpongeCommand::Pad { message, rate } => {
+            if !(1..WIDTH).contains(&rate) {
+                return Err(format!("Rate must be in 1..{WIDTH}").into());
+            }
+            let sponge = Sponge::new(rate, Padding::Zeros);
+            let other = padding_collision(&sponge, message.as_bytes())
+                .ok_or("The message ends with a full block")?;
+            Ok(Report::new()
+                .field("message_hex", hex::encode(message.as_bytes()))
+                .field("colliding_hex", hex::encode(&other))
+                .field("hash", hex::encode(sponge.hash(&other, 16))))
+        }
+    }
+}
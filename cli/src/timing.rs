@@ -0,0 +1,82 @@
+//! Group operation timing attack simulation (`group-op-timing-attack` crate).
+
+use crate::{output::Report, Result};
+use clap::{Args, ValueEnum};
+use group_op_timing_attack::{
+    recover_secret_with_progress, AttackConfig, Countermeasure, Operation, Progress, VictimDevice,
+};
+use rand::Rng;
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Op {
+    /// m^d mod n
+    SquareAndMultiply,
+    /// d·m mod n
+    DoubleAndAdd,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Defence {
+    None,
+    MessageBlinding,
+    ExponentBlinding,
+    MontgomeryLadder,
+}
+
+/// Recover a simulated victim secret from its operations timing.
+#[derive(Args)]
+pub struct TimingArgs {
+    /// Secret length in bits (8, 16, 32, 64, 128 or 256).
+    #[arg(short, long, default_value_t = 64)]
+    keylen: u64,
+    /// Timing samples batch size.
+    #[arg(short, long, default_value_t = 1000, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    iters: usize,
+    /// Seed of the victim secret and of the attacker messages.
+    #[arg(short, long)]
+    seed: Option<u64>,
+    /// Secret dependent operation run by the victim.
+    #[arg(short, long, value_enum, default_value_t = Op::SquareAndMultiply)]
+    op: Op,
+    /// Countermeasure implemented by the victim.
+    #[arg(long, value_enum, default_value_t = Defence::None)]
+    countermeasure: Defence,
+}
+
+pub fn run(args: TimingArgs) -> Result<Report> {
+    if ![8, 16, 32, 64, 128, 256].contains(&args.keylen) {
+        return Err(format!("No builtin modulus for {} bits secrets", args.keylen).into());
+    }
+    let op = match args.op {
+        Op::SquareAndMultiply => Operation::SquareAndMultiply,
+        Op::DoubleAndAdd => Operation::DoubleAndAdd,
+    };
+    let countermeasure = match args.countermeasure {
+        Defence::None => Countermeasure::None,
+        Defence::MessageBlinding => Countermeasure::MessageBlinding,
+        Defence::ExponentBlinding => Countermeasure::ExponentBlinding,
+        Defence::MontgomeryLadder => Countermeasure::MontgomeryLadder,
+    };
+    let seed = args.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let config = AttackConfig {
+        iters: args.iters,
+        max_backtracks: args.keylen as usize / 4,
+        seed: Some(seed),
+        ..AttackConfig::new(args.keylen, op)
+    };
+    let victim = VictimDevice::with_countermeasure(seed, &config, countermeasure);
+
+    let (mut samples, mut backtracks) = (0, 0);
+    let recovered = recover_secret_with_progress(&victim, &config, |progress| match progress {
+        Progress::Bit { samples: n, .. } => samples += n,
+        Progress::Backtrack => backtracks += 1,
+    });
+    let wrong = (&recovered ^ victim.secret()).count_ones();
+    Ok(Report::new()
+        .field("seed", seed.to_string())
+        .field("secret", victim.secret().to_str_radix(16))
+        .field("recovered", recovered.to_str_radix(16))
+        .field("wrong_bits", wrong)
+        .field("samples", samples)
+        .field("backtracks", backtracks))
+}
@@ -0,0 +1,43 @@
+//! VRF output grinding (`vrf-grinding` crate).
+
+use crate::{output::Report, Result};
+use clap::Args;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use vrf_grinding::{broken, is_winner, SecretKey};
+
+/// Grind a lottery slot against the proof-less VRF, then play it honestly
+/// with the DDH VRF.
+#[derive(Args)]
+pub struct VrfArgs {
+    /// Lottery slot input.
+    #[arg(short, long, default_value = "slot 0")]
+    input: String,
+    /// A slot is won if the first output byte is less than this.
+    #[arg(short, long, default_value_t = 4)]
+    threshold: u8,
+    /// Seed of the secrets.
+    #[arg(short, long)]
+    seed: Option<u64>,
+}
+
+pub fn run(args: VrfArgs) -> Result<Report> {
+    if args.threshold == 0 {
+        return Err("Nobody wins with a zero threshold".into());
+    }
+    let input = args.input.as_bytes();
+    let seed = args.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let mut rng = StdRng::seed_from_u64(seed);
+    let (output, attempts) = broken::grind(&mut rng, input, args.threshold);
+
+    // The output is bound to the registered key by the proof
+    let sk = SecretKey::generate(&mut rng);
+    let (_, proof) = sk.prove(&mut rng, input);
+    let honest = sk.pk.verify(input, &proof).ok_or("Invalid proof")?;
+    Ok(Report::new()
+        .field("seed", seed.to_string())
+        .field("expected_attempts", 256.0 / args.threshold as f64)
+        .field("ground_output", hex::encode(output))
+        .field("grinding_attempts", attempts)
+        .field("verified_output", hex::encode(honest))
+        .field("verified_wins", is_winner(&honest, args.threshold)))
+}
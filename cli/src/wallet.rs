@@ -0,0 +1,64 @@
+//! Brain wallet cracking (`brain-wallet` crate).
+
+use crate::{input::Bytes, output::Report, Result};
+use brain_wallet::{
+    dictionary::{self, Rule},
+    scan, PublicKey,
+};
+use clap::Args;
+use std::{collections::HashSet, fs, path::PathBuf};
+
+/// Look for the passphrases of the target wallets in a dictionary.
+#[derive(Args)]
+pub struct WalletArgs {
+    /// Target compressed public keys (33 bytes).
+    #[arg(required = true)]
+    targets: Vec<Bytes>,
+    /// Vocabulary file, one word per line (default the builtin common words).
+    #[arg(short, long)]
+    words: Option<PathBuf>,
+    /// Words per passphrase.
+    #[arg(short = 'l', long, default_value_t = 2)]
+    max_len: u32,
+    /// Also try capitalized, leet, concatenated and numbered variants.
+    #[arg(short, long)]
+    mangle: bool,
+}
+
+pub fn run(args: WalletArgs) -> Result<Report> {
+    let targets = args
+        .targets
+        .into_iter()
+        .map(|t| PublicKey::try_from(t.0).map_err(|_| "Public keys are 33 bytes".into()))
+        .collect::<Result<HashSet<_>>>()?;
+    let text = args.words.map(fs::read_to_string).transpose()?;
+    let words: Vec<&str> = match &text {
+        Some(text) => text.split_whitespace().collect(),
+        None => dictionary::words(),
+    };
+    let rules: &[Rule] = if args.mangle {
+        &[
+            Rule::Identity,
+            Rule::Capitalize,
+            Rule::Leet,
+            Rule::Concatenate,
+            Rule::AppendDigits(100),
+        ]
+    } else {
+        &[Rule::Identity]
+    };
+    let found = scan(
+        dictionary::candidates(&words, args.max_len, rules),
+        &targets,
+    );
+    let cracked: Vec<_> = found
+        .iter()
+        .map(|f| format!("{} {:?}", hex::encode(f.public), f.passphrase))
+        .collect();
+    Ok(Report::new()
+        .field(
+            "candidates",
+            dictionary::count(words.len(), args.max_len, rules),
+        )
+        .field("cracked", cracked))
+}
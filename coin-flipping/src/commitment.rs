@@ -0,0 +1,161 @@
+//! Commitment schemes.
+//!
+//! A commitment scheme allows to commit to a value while keeping it hidden
+//! (*hiding* property) with the ability to reveal it later without being able
+//! to change it (*binding* property).
+//!
+//! Two flavors are provided:
+//! - hash based: `C = H(salt || value)`, computationally hiding and binding.
+//! - Pedersen: `C = g^v·h^r mod p`, perfectly hiding and computationally binding
+//!   as far as nobody knows `log_g(h)`.
+
+use hash_to_group::zp::Subgroup;
+use num_bigint::{BigUint, RandBigInt};
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+
+/// Salt length used by the hash commitment.
+pub const SALT_LEN: usize = 32;
+
+/// Hash commitment value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashCommitment(pub [u8; 32]);
+
+/// Opening information for a hash commitment.
+#[derive(Debug, Clone)]
+pub struct HashOpening {
+    pub value: Vec<u8>,
+    pub salt: Vec<u8>,
+}
+
+fn sha256(salt: &[u8], value: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(salt);
+    hasher.update(value);
+    hasher.finalize().into()
+}
+
+/// Commit to `value` using a fresh random salt.
+pub fn hash_commit<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    value: &[u8],
+) -> (HashCommitment, HashOpening) {
+    let mut salt = vec![0; SALT_LEN];
+    rng.fill_bytes(&mut salt);
+    let commitment = HashCommitment(sha256(&salt, value));
+    let opening = HashOpening {
+        value: value.to_vec(),
+        salt,
+    };
+    (commitment, opening)
+}
+
+/// Commit to `value` without any salt.
+///
+/// This is **not hiding** when the committed value comes from a small set:
+/// whoever receives the commitment can just try all the possible values.
+pub fn hash_commit_unsalted(value: &[u8]) -> HashCommitment {
+    HashCommitment(sha256(&[], value))
+}
+
+/// Check that the `opening` matches the `commitment`.
+pub fn hash_verify(commitment: &HashCommitment, opening: &HashOpening) -> bool {
+    sha256(&opening.salt, &opening.value) == commitment.0
+}
+
+/// Pedersen commitment parameters.
+///
+/// Group is the subgroup of quadratic residues of `Z_p*`, with `p = 2q + 1`
+/// a safe prime, thus has prime order `q`.
+#[derive(Debug, Clone)]
+pub struct PedersenParams {
+    pub p: BigUint,
+    pub q: BigUint,
+    pub g: BigUint,
+    pub h: BigUint,
+}
+
+impl Default for PedersenParams {
+    /// 256-bit safe prime group ([`Subgroup::safe_prime_256`]).
+    ///
+    /// `g` and `h` are derived by hashing to the group (see the
+    /// `hash-to-group` crate), so that nobody knows `log_g(h)`, otherwise the
+    /// commitment is not binding.
+    fn default() -> Self {
+        let group = Subgroup::safe_prime_256();
+        let [g, h] =
+            <[BigUint; 2]>::try_from(group.generators(b"coin-flipping pedersen", 2)).unwrap();
+        let Subgroup { p, q } = group;
+        PedersenParams { p, q, g, h }
+    }
+}
+
+/// Pedersen commitment value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PedersenCommitment(pub BigUint);
+
+/// Opening information for a Pedersen commitment.
+#[derive(Debug, Clone)]
+pub struct PedersenOpening {
+    pub value: BigUint,
+    pub blinding: BigUint,
+}
+
+impl PedersenParams {
+    /// Commit to `value` (reduced mod `q`) using a fresh random blinding factor.
+    pub fn commit<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        value: &BigUint,
+    ) -> (PedersenCommitment, PedersenOpening) {
+        let value = value % &self.q;
+        let blinding = rng.gen_biguint_below(&self.q);
+        let commitment = PedersenCommitment(self.eval(&value, &blinding));
+        let opening = PedersenOpening { value, blinding };
+        (commitment, opening)
+    }
+
+    /// Check that the `opening` matches the `commitment`.
+    pub fn verify(&self, commitment: &PedersenCommitment, opening: &PedersenOpening) -> bool {
+        self.eval(&opening.value, &opening.blinding) == commitment.0
+    }
+
+    // g^v·h^r mod p
+    fn eval(&self, v: &BigUint, r: &BigUint) -> BigUint {
+        (self.g.modpow(v, &self.p) * self.h.modpow(r, &self.p)) % &self.p
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn hash_commitment_works() {
+        let (c, mut o) = hash_commit(&mut OsRng, b"hello");
+        assert!(hash_verify(&c, &o));
+        o.value = b"world".to_vec();
+        assert!(!hash_verify(&c, &o));
+    }
+
+    #[test]
+    fn pedersen_commitment_works() {
+        let params = PedersenParams::default();
+        let (c, mut o) = params.commit(&mut OsRng, &BigUint::from(1_u8));
+        assert!(params.verify(&c, &o));
+        o.value = BigUint::from(0_u8);
+        assert!(!params.verify(&c, &o));
+    }
+
+    #[test]
+    fn pedersen_generators_are_hashed() {
+        let params = PedersenParams::default();
+        let group = Subgroup::safe_prime_256();
+        assert_eq!((&params.p, &params.q), (&group.p, &group.q));
+        let gens = group.generators(b"coin-flipping pedersen", 2);
+        assert_eq!([&params.g, &params.h], [&gens[0], &gens[1]]);
+        assert!(gens.iter().all(|x| group.is_element(x)));
+        assert_ne!(params.g, params.h);
+    }
+}
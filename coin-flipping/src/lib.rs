@@ -0,0 +1,144 @@
+//! Coin flipping over the phone.
+//!
+//! Alice and Bob want to agree on a fair random bit without trusting each other.
+//! Both parties pick a bit and the outcome is `a ⊕ b`, which is uniform as far
+//! as at least one of the two bits is uniform and independent of the other.
+//!
+//! The independence requirement is the subtle part. If Alice reveals `a` before
+//! Bob has chosen `b` then Bob can just set `b = a ⊕ target` and the outcome is
+//! fully under Bob's control.
+//!
+//! The fix is to let Alice send a *commitment* to `a` first, Bob replies with `b`
+//! and only then Alice opens the commitment. The commitment must be:
+//! - hiding: otherwise Bob learns `a` from the commitment (e.g. unsalted hash
+//!   of a single bit is trivially brute-forced).
+//! - binding: otherwise Alice may open to the value that suits Alice.
+//!
+//! Some background: https://datawok.net/posts/commitment-schemes
+
+pub mod commitment;
+
+use commitment::{hash_commit, hash_commit_unsalted, hash_verify, PedersenParams};
+use num_bigint::BigUint;
+use rand::{CryptoRng, Rng, RngCore};
+
+/// Coin flipping protocol flavor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    /// Alice reveals the bit in the clear before Bob chooses.
+    RevealFirst,
+    /// Alice sends `H(a)` without salt.
+    UnsaltedHash,
+    /// Alice sends `H(salt || a)`.
+    SaltedHash,
+    /// Alice sends `g^a·h^r mod p`.
+    Pedersen,
+}
+
+/// Protocol errors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// Alice's opening doesn't match the commitment.
+    InvalidOpening,
+}
+
+/// A malicious Bob which wants the coin to land on `target`.
+///
+/// Bob is honest-looking, i.e. follows the message flow, but picks the bit
+/// using any information leaked by Alice's first message.
+pub struct MaliciousBob {
+    pub target: bool,
+}
+
+impl MaliciousBob {
+    /// Bob choice when Alice's bit has been leaked.
+    fn choose_knowing(&self, alice_bit: bool) -> bool {
+        alice_bit ^ self.target
+    }
+
+    /// Bob choice when Alice's bit is hidden. Any choice is as good as another.
+    fn choose_blind<R: RngCore>(&self, rng: &mut R) -> bool {
+        rng.gen()
+    }
+}
+
+/// Run one coin flip between an honest Alice and a malicious Bob.
+///
+/// Returns the coin outcome.
+pub fn flip<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    protocol: Protocol,
+    bob: &MaliciousBob,
+) -> Result<bool, Error> {
+    let alice_bit: bool = rng.gen();
+    let alice_bytes = [alice_bit as u8];
+
+    let outcome = match protocol {
+        Protocol::RevealFirst => {
+            // Alice → Bob: a
+            let bob_bit = bob.choose_knowing(alice_bit);
+            alice_bit ^ bob_bit
+        }
+        Protocol::UnsaltedHash => {
+            // Alice → Bob: H(a)
+            let commitment = hash_commit_unsalted(&alice_bytes);
+            // Bob tries all the (two) possible values
+            let guessed = hash_commit_unsalted(&[1]) == commitment;
+            let bob_bit = bob.choose_knowing(guessed);
+            alice_bit ^ bob_bit
+        }
+        Protocol::SaltedHash => {
+            // Alice → Bob: H(salt || a)
+            let (commitment, opening) = hash_commit(rng, &alice_bytes);
+            // Bob → Alice: b
+            let bob_bit = bob.choose_blind(rng);
+            // Alice → Bob: (salt, a)
+            if !hash_verify(&commitment, &opening) {
+                return Err(Error::InvalidOpening);
+            }
+            (opening.value[0] != 0) ^ bob_bit
+        }
+        Protocol::Pedersen => {
+            let params = PedersenParams::default();
+            // Alice → Bob: g^a·h^r
+            let (commitment, opening) = params.commit(rng, &BigUint::from(alice_bit as u8));
+            // Bob → Alice: b
+            let bob_bit = bob.choose_blind(rng);
+            // Alice → Bob: (a, r)
+            if !params.verify(&commitment, &opening) {
+                return Err(Error::InvalidOpening);
+            }
+            (opening.value == BigUint::from(1_u8)) ^ bob_bit
+        }
+    };
+    Ok(outcome)
+}
+
+/// Run `rounds` coin flips and return the fraction of flips won by Bob.
+pub fn bob_win_rate<R: RngCore + CryptoRng>(rng: &mut R, protocol: Protocol, rounds: usize) -> f64 {
+    let bob = MaliciousBob { target: true };
+    let wins = (0..rounds)
+        .filter(|_| flip(rng, protocol, &bob).expect("Alice is honest") == bob.target)
+        .count();
+    wins as f64 / rounds as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn broken_protocols_are_biased() {
+        assert_eq!(bob_win_rate(&mut OsRng, Protocol::RevealFirst, 100), 1.0);
+        assert_eq!(bob_win_rate(&mut OsRng, Protocol::UnsaltedHash, 100), 1.0);
+    }
+
+    #[test]
+    fn committed_protocols_are_fair() {
+        let rate = bob_win_rate(&mut OsRng, Protocol::SaltedHash, 2000);
+        assert!((rate - 0.5).abs() < 0.1);
+        let rate = bob_win_rate(&mut OsRng, Protocol::Pedersen, 500);
+        assert!((rate - 0.5).abs() < 0.1);
+    }
+}
@@ -0,0 +1,21 @@
+use coin_flipping::{bob_win_rate, Protocol};
+use rand::rngs::OsRng;
+
+fn main() {
+    let rounds = 1000;
+
+    println!("Bob wants the coin to land on 1 ({rounds} flips)");
+    for protocol in [
+        Protocol::RevealFirst,
+        Protocol::UnsaltedHash,
+        Protocol::SaltedHash,
+        Protocol::Pedersen,
+    ] {
+        let rate = bob_win_rate(&mut OsRng, protocol, rounds);
+        println!(
+            "{:<14}: Bob wins {:.1}%",
+            format!("{protocol:?}"),
+            rate * 100.0
+        );
+    }
+}
@@ -0,0 +1,171 @@
+//! AES-128 key recovery from a decayed key schedule.
+//!
+//! Implementations usually keep the whole expanded key (176 bytes for AES-128)
+//! in memory. The key schedule is highly redundant: any 4 consecutive words
+//! determine all the others, both forward and backward.
+//!
+//! Recovery procedure:
+//! 1. for each window of 4 consecutive words of the decayed image, rebuild the
+//!    full schedule assuming the window is correct, possibly after restoring
+//!    a few decayed bits (decay only flips `1` → `0`);
+//! 2. discard candidates having a `0` where the image has a `1` (impossible
+//!    under the ground state decay model);
+//! 3. among the survivors pick the one requiring the fewest decayed bits.
+//!
+//! A window holds ~64 set bits, thus with moderate decay rates (≤ 10%) at least
+//! one of the 41 windows is almost surely intact or off by a few bits.
+
+use aes_cache_timing::aes::SBOX;
+
+/// Expanded key length in bytes.
+pub const SCHEDULE_LEN: usize = 176;
+
+const WORDS: usize = SCHEDULE_LEN / 4;
+
+const RCON: [u8; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+/// Expanded key bytes.
+pub fn expand_key(key: &[u8; 16]) -> Vec<u8> {
+    aes_cache_timing::aes::expand_key(key)
+        .iter()
+        .flat_map(|w| w.to_be_bytes())
+        .collect()
+}
+
+// Transformation applied to w[i-1] when computing w[i].
+fn f(i: usize, w: u32) -> u32 {
+    if !i.is_multiple_of(4) {
+        return w;
+    }
+    let b = w.rotate_left(8).to_be_bytes();
+    u32::from_be_bytes([
+        SBOX[b[0] as usize],
+        SBOX[b[1] as usize],
+        SBOX[b[2] as usize],
+        SBOX[b[3] as usize],
+    ]) ^ ((RCON[i / 4 - 1] as u32) << 24)
+}
+
+/// Rebuild the whole schedule from the 4 words starting at word `j`.
+pub fn schedule_from_window(window: &[u32; 4], j: usize) -> [u32; WORDS] {
+    let mut w = [0; WORDS];
+    w[j..j + 4].copy_from_slice(window);
+    // Backward: w[i-4] = w[i] ⊕ f(w[i-1])
+    for i in (4..j + 4).rev() {
+        w[i - 4] = w[i] ^ f(i, w[i - 1]);
+    }
+    // Forward: w[i] = w[i-4] ⊕ f(w[i-1])
+    for i in j + 4..WORDS {
+        w[i] = w[i - 4] ^ f(i, w[i - 1]);
+    }
+    w
+}
+
+fn words(bytes: &[u8]) -> Vec<u32> {
+    bytes
+        .chunks(4)
+        .map(|c| u32::from_be_bytes(c.try_into().unwrap()))
+        .collect()
+}
+
+// Number of decayed bits required to obtain `image` from `schedule`.
+// Returns `None` if impossible (bit set in the image but not in the schedule).
+fn decay_cost(schedule: &[u32; WORDS], image: &[u32]) -> Option<u32> {
+    schedule.iter().zip(image).try_fold(0, |acc, (s, i)| {
+        (i & !s == 0).then(|| acc + (s & !i).count_ones())
+    })
+}
+
+// Call `f` for every `t`-combination of `items`.
+fn combinations<T: Copy>(items: &[T], t: usize, f: &mut impl FnMut(&[T])) {
+    fn recurse<T: Copy>(
+        items: &[T],
+        t: usize,
+        start: usize,
+        acc: &mut Vec<T>,
+        f: &mut impl FnMut(&[T]),
+    ) {
+        if acc.len() == t {
+            return f(acc);
+        }
+        for i in start..items.len() {
+            acc.push(items[i]);
+            recurse(items, t, i + 1, acc, f);
+            acc.pop();
+        }
+    }
+    recurse(items, t, 0, &mut Vec::with_capacity(t), f);
+}
+
+/// Recover the AES-128 key from a decayed expanded key image.
+///
+/// Windows are tried restoring up to `max_flips` decayed bits, fewest flips
+/// first. Any candidate consistent with the whole image is almost surely the
+/// correct one, thus the search stops at the first flips count yielding one.
+///
+/// Returns the key and the number of bits estimated to be decayed.
+pub fn recover_key(image: &[u8], max_flips: usize) -> Option<([u8; 16], u32)> {
+    assert_eq!(image.len(), SCHEDULE_LEN, "Bad schedule length");
+    let image = words(image);
+
+    let mut best: Option<([u32; WORDS], u32)> = None;
+    for flips in 0..=max_flips {
+        for j in 0..=WORDS - 4 {
+            let window: [u32; 4] = image[j..j + 4].try_into().unwrap();
+            // Candidate positions for decayed bits
+            let zeros: Vec<_> = (0..4)
+                .flat_map(|w| (0..32).map(move |b| (w, b)))
+                .filter(|&(w, b)| window[w] & (1 << b) == 0)
+                .collect();
+            combinations(&zeros, flips, &mut |positions| {
+                let mut window = window;
+                positions.iter().for_each(|&(w, b)| window[w] |= 1 << b);
+                let schedule = schedule_from_window(&window, j);
+                if let Some(cost) = decay_cost(&schedule, &image) {
+                    if best.is_none_or(|(_, c)| cost < c) {
+                        best = Some((schedule, cost));
+                    }
+                }
+            });
+        }
+        if best.is_some() {
+            break;
+        }
+    }
+
+    best.map(|(schedule, cost)| {
+        let mut key = [0; 16];
+        for (i, w) in schedule[..4].iter().enumerate() {
+            key[4 * i..4 * i + 4].copy_from_slice(&w.to_be_bytes());
+        }
+        (key, cost)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decay::decay_bytes;
+    use rand::Rng;
+
+    #[test]
+    fn window_rebuild_works() {
+        let key = [0x2b; 16];
+        let schedule = words(&expand_key(&key));
+        for j in 0..=WORDS - 4 {
+            let window = schedule[j..j + 4].try_into().unwrap();
+            assert_eq!(schedule_from_window(&window, j).to_vec(), schedule);
+        }
+    }
+
+    #[test]
+    fn recover_from_decayed_schedule() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..10 {
+            let key: [u8; 16] = rng.gen();
+            let image = decay_bytes(&mut rng, &expand_key(&key), 0.05);
+            let (recovered, _) = recover_key(&image, 2).unwrap();
+            assert_eq!(recovered, key);
+        }
+    }
+}
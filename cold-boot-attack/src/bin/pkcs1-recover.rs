@@ -0,0 +1,115 @@
+//! Rebuild an RSA private key from a damaged PKCS#1 blob.
+//!
+//! Usage: `pkcs1-recover [<key.pem|key.der> [<modulus-hex>]]`
+//!
+//! The modulus (e.g. taken from the certificate) is required only if it
+//! didn't survive in the blob. Without arguments a demo key is damaged and
+//! recovered.
+
+use cold_boot_attack::{
+    pkcs1::{parse, recover, to_pem, Blob, Fragment, Fragments},
+    rsa::PrivateKey,
+};
+use num_bigint::BigUint;
+use std::{env, fs, process};
+
+const MAX_WIDTH: usize = 100_000;
+
+fn describe(name: &str, frag: &Option<Fragment>) {
+    match frag {
+        Some(f) if f.complete => println!("{name:>4}: complete"),
+        Some(f) => {
+            let bits = f.bits.value.bits().max(f.bits.known.bits());
+            println!(
+                "{name:>4}: partial ({:.1}% known)",
+                100.0 * f.bits.known_fraction(bits)
+            )
+        }
+        None => println!("{name:>4}: lost"),
+    }
+}
+
+fn run(blob: &Blob, modulus: Option<BigUint>) -> Option<PrivateKey> {
+    let modulus_len = modulus.as_ref().map(|n| n.bits().div_ceil(8) as usize);
+    let mut frags: Fragments = parse(blob, modulus_len);
+    if let Some(n) = modulus {
+        frags.n = Some(Fragment::known(n));
+    }
+    if frags.e.as_ref().and_then(Fragment::value).is_none() {
+        println!("Public exponent lost, assuming 65537");
+        frags.e = Some(Fragment::known(BigUint::from(65537_u32)));
+    }
+
+    let readable = blob.known.iter().filter(|&&k| k).count();
+    println!("Blob: {} bytes, {readable} readable", blob.bytes.len());
+    for (name, frag) in [
+        ("n", &frags.n),
+        ("e", &frags.e),
+        ("d", &frags.d),
+        ("p", &frags.p),
+        ("q", &frags.q),
+        ("dp", &frags.dp),
+        ("dq", &frags.dq),
+        ("qinv", &frags.qinv),
+    ] {
+        describe(name, frag);
+    }
+
+    match recover(&frags, MAX_WIDTH) {
+        Ok((key, method)) => {
+            println!("Recovered using: {method}");
+            Some(key)
+        }
+        Err(err) => {
+            println!("Recovery failed: {err:?}");
+            None
+        }
+    }
+}
+
+fn demo() {
+    println!("Generating RSA-1024 key");
+    let key = PrivateKey::generate(1024, 65537);
+    // Some lines of the dump are garbage and the tail is lost
+    let pem: Vec<_> = to_pem(&key)
+        .lines()
+        .take(12)
+        .enumerate()
+        .map(|(i, l)| match i {
+            5 | 8 | 10 => "?".repeat(l.len()),
+            _ => l.to_string(),
+        })
+        .collect();
+    let pem = pem.join("\n");
+    println!("{pem}");
+
+    let recovered = run(&Blob::from_pem(&pem), None);
+    assert_eq!(recovered, Some(key));
+}
+
+fn main() {
+    let args: Vec<_> = env::args().skip(1).collect();
+    if args.is_empty() {
+        return demo();
+    }
+
+    let data = fs::read(&args[0]).unwrap_or_else(|err| {
+        eprintln!("Can't read {}: {err}", args[0]);
+        process::exit(1);
+    });
+    let blob = match std::str::from_utf8(&data) {
+        Ok(pem) if pem.contains("-----BEGIN") => Blob::from_pem(pem),
+        _ => Blob::from_der(&data),
+    };
+    let modulus = args.get(1).map(|n| {
+        BigUint::parse_bytes(n.trim_start_matches("0x").as_bytes(), 16).unwrap_or_else(|| {
+            eprintln!("Invalid modulus");
+            process::exit(1);
+        })
+    });
+
+    match run(&blob, modulus) {
+        Some(key) => print!("{}", to_pem(&key)),
+        None => process::exit(1),
+    }
+}
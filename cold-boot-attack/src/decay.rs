@@ -0,0 +1,86 @@
+//! Memory decay simulation.
+//!
+//! When DRAM loses power its cells progressively decay towards a *ground
+//! state*. Here we assume the ground state is `0`, thus decay only flips bits
+//! from `1` to `0`: a `1` read from the image is certainly correct, while a `0`
+//! may be a decayed `1`.
+
+use num_bigint::BigUint;
+use rand::Rng;
+
+/// Flip each `1` bit of `bytes` to `0` with probability `rate`.
+pub fn decay_bytes<R: Rng>(rng: &mut R, bytes: &[u8], rate: f64) -> Vec<u8> {
+    bytes
+        .iter()
+        .map(|&b| {
+            (0..8)
+                .filter(|i| b & (1 << i) != 0 && !rng.gen_bool(rate))
+                .fold(0, |acc, i| acc | (1 << i))
+        })
+        .collect()
+}
+
+/// Flip each `1` bit of `x` to `0` with probability `rate`.
+pub fn decay_biguint<R: Rng>(rng: &mut R, x: &BigUint, rate: f64) -> BigUint {
+    BigUint::from_bytes_le(&decay_bytes(rng, &x.to_bytes_le(), rate))
+}
+
+/// Partially known integer.
+#[derive(Debug, Clone)]
+pub struct Bits {
+    /// Value of the known bits (unknown bits are zero).
+    pub value: BigUint,
+    /// Known bits mask.
+    pub known: BigUint,
+}
+
+impl Bits {
+    /// Bits recovered from a decayed image, assuming a `0` ground state.
+    ///
+    /// Only the set bits are known.
+    pub fn from_decayed(image: &BigUint) -> Self {
+        Bits {
+            value: image.clone(),
+            known: image.clone(),
+        }
+    }
+
+    /// Random erasure model: each bit of `x` (up to `bits`) is known with
+    /// probability `fraction`.
+    pub fn erased<R: Rng>(rng: &mut R, x: &BigUint, bits: u64, fraction: f64) -> Self {
+        let mut known = BigUint::default();
+        for i in 0..bits {
+            if rng.gen_bool(fraction) {
+                known.set_bit(i, true);
+            }
+        }
+        Bits {
+            value: x & &known,
+            known,
+        }
+    }
+
+    /// Returns `false` if bit `i` is known and different from `bit`.
+    pub fn matches(&self, i: u64, bit: bool) -> bool {
+        !self.known.bit(i) || self.value.bit(i) == bit
+    }
+
+    /// Fraction of known bits over the first `bits` bits.
+    pub fn known_fraction(&self, bits: u64) -> f64 {
+        (0..bits).filter(|&i| self.known.bit(i)).count() as f64 / bits as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decay_is_unidirectional() {
+        let mut rng = rand::thread_rng();
+        let bytes: Vec<u8> = (0..=255).collect();
+        let decayed = decay_bytes(&mut rng, &bytes, 0.3);
+        assert!(bytes.iter().zip(&decayed).all(|(b, d)| b & d == *d));
+        assert_ne!(bytes, decayed);
+    }
+}
@@ -0,0 +1,18 @@
+//! Cold boot attack simulation.
+//!
+//! DRAM content doesn't vanish immediately after power loss: bits decay
+//! progressively over seconds (minutes if the chips are cooled). An attacker
+//! with physical access can reboot the machine and dump the memory, obtaining
+//! a degraded image of the keys held in memory.
+//!
+//! The reconstruction exploits the redundancy of the in-memory key formats:
+//! - AES: the expanded key schedule (see [`aes`]);
+//! - RSA: the private key CRT components (see [`rsa`]), possibly carved out
+//!   of a damaged PKCS#1 blob (see [`pkcs1`]).
+//!
+//! Some background: https://citp.princeton.edu/our-work/memory
+
+pub mod aes;
+pub mod decay;
+pub mod pkcs1;
+pub mod rsa;
@@ -0,0 +1,60 @@
+use cold_boot_attack::{
+    aes,
+    decay::{decay_biguint, decay_bytes, Bits},
+    rsa::{reconstruct, PartialKey, PrivateKey},
+};
+use rand::Rng;
+
+fn main() {
+    let mut rng = rand::thread_rng();
+
+    // AES-128
+    let rate = 0.05;
+    let key: [u8; 16] = rng.gen();
+    let schedule = aes::expand_key(&key);
+    let image = decay_bytes(&mut rng, &schedule, rate);
+    let flipped: u32 = schedule
+        .iter()
+        .zip(&image)
+        .map(|(a, b)| (a ^ b).count_ones())
+        .sum();
+    println!("AES-128 schedule decay rate {rate}: {flipped} bits decayed");
+    println!("secret    : {}", hex::encode(key));
+    match aes::recover_key(&image, 3) {
+        Some((recovered, cost)) => {
+            println!(
+                "recovered : {} (decayed bits: {cost})",
+                hex::encode(recovered)
+            )
+        }
+        None => println!("recovery failed"),
+    }
+
+    // RSA-1024
+    let rate = 0.3;
+    println!("Generating RSA-1024 key");
+    let key = PrivateKey::generate(1024, 65537);
+    let mut decayed = |x| Some(Bits::from_decayed(&decay_biguint(&mut rng, x, rate)));
+    let partial = PartialKey {
+        n: key.n.clone(),
+        e: key.e.clone(),
+        p: decayed(&key.p),
+        q: decayed(&key.q),
+        d: decayed(&key.d),
+        dp: decayed(&key.dp),
+        dq: decayed(&key.dq),
+    };
+    let known = partial.p.as_ref().unwrap().known_fraction(512);
+    println!("RSA decay rate {rate}: known bits fraction ≈ {known:.3}");
+
+    let (recovered, stats) = reconstruct(&partial, 100_000);
+    println!("{stats:?}");
+    match recovered {
+        Some(recovered) => {
+            println!("p: {:x}", recovered.p);
+            assert_eq!(recovered, key);
+            println!("Private key recovered");
+        }
+        None => println!("recovery failed"),
+    }
+}
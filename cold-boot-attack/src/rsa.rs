@@ -0,0 +1,324 @@
+//! RSA private key reconstruction from a decayed memory image.
+//!
+//! Implementation of the Heninger–Shacham branch and prune algorithm
+//! (*Reconstructing RSA Private Keys from Random Key Bits*, 2009).
+//!
+//! The private key components are tied by the relations:
+//!
+//! ```text
+//! N    = p·q
+//! e·d  = k·(N - p - q + 1) + 1
+//! e·dp = kp·(p - 1) + 1
+//! e·dq = kq·(q - 1) + 1
+//! ```
+//!
+//! with `0 < k, kp, kq < e`. For small `e`:
+//! - `k` is found by comparing the known upper half bits of `d` with the
+//!   approximation `d̃(k) = ⌊(k·(N + 1) + 1)/e⌋`;
+//! - `kp` and `kq` are the roots of `x² - (k·(N - 1) + 1)·x - k ≡ 0 (mod e)`.
+//!
+//! Then `p` and `q` are reconstructed from the least significant bit upward.
+//! At step `i` the bit `p[i]` is guessed, `q[i]` is fixed by `N mod 2^(i+1)` and
+//! the bits `i` of `d`, `dp`, `dq` follow from the relations above (`e` is odd,
+//! thus invertible modulo `2^(i+1)`). Branches contradicting any known bit are
+//! pruned. Each step has two children, a wrong one survives with probability
+//! `(1 - δ/2)^5`, with `δ` the fraction of known bits. The expected number of
+//! branches stays bounded as far as `δ > ~0.27`.
+
+use crate::decay::Bits;
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive, Zero};
+
+/// RSA private key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrivateKey {
+    pub n: BigUint,
+    pub e: BigUint,
+    pub d: BigUint,
+    pub p: BigUint,
+    pub q: BigUint,
+    pub dp: BigUint,
+    pub dq: BigUint,
+}
+
+impl PrivateKey {
+    /// Generate a key with `bits` modulus length and public exponent `e`.
+    pub fn generate(bits: usize, e: u64) -> Self {
+        let e = BigUint::from(e);
+        let gen_prime = || loop {
+            let p = miller_rabin::prime_num(bits / 2, None).expect("Prime not found");
+            // Full length primes with p - 1 coprime to e
+            if p.bits() as usize == bits / 2 && ((&p - 1_u8) % &e) != BigUint::zero() {
+                return p;
+            }
+        };
+        loop {
+            let p = gen_prime();
+            let q = gen_prime();
+            if p != q {
+                return Self::from_primes(p, q, e);
+            }
+        }
+    }
+
+    /// Build the full key from the factors and the public exponent.
+    pub fn from_primes(p: BigUint, q: BigUint, e: BigUint) -> Self {
+        let phi = (&p - 1_u8) * (&q - 1_u8);
+        let d = e.modinv(&phi).expect("e not invertible");
+        PrivateKey {
+            n: &p * &q,
+            dp: &d % (&p - 1_u8),
+            dq: &d % (&q - 1_u8),
+            e,
+            d,
+            p,
+            q,
+        }
+    }
+}
+
+/// Partially known private key.
+#[derive(Debug, Clone)]
+pub struct PartialKey {
+    pub n: BigUint,
+    pub e: BigUint,
+    pub p: Option<Bits>,
+    pub q: Option<Bits>,
+    pub d: Option<Bits>,
+    pub dp: Option<Bits>,
+    pub dq: Option<Bits>,
+}
+
+/// Reconstruction statistics.
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+    /// Total number of explored branches.
+    pub explored: usize,
+    /// Max number of live branches at any step.
+    pub max_width: usize,
+}
+
+fn matches(bits: &Option<Bits>, x: &BigUint, i: u64) -> bool {
+    bits.as_ref().is_none_or(|b| b.matches(i, x.bit(i)))
+}
+
+/// Find `k` using the known upper half bits of `d`.
+///
+/// Returns the `k` candidates sorted by number of mismatches.
+pub fn find_k(n: &BigUint, e: u64, d: &Bits) -> Vec<u64> {
+    let nbits = n.bits();
+    // Upper half bits (with some margin for carries)
+    let lo = nbits / 2 + 2;
+    let mut candidates: Vec<_> = (1..e)
+        .map(|k| {
+            let d_approx = (k * (n + 1_u8) + 1_u8) / e;
+            let mismatches = (lo..nbits)
+                .filter(|&i| !d.matches(i, d_approx.bit(i)))
+                .count();
+            (mismatches, k)
+        })
+        .collect();
+    candidates.sort();
+    candidates.into_iter().map(|(_, k)| k).collect()
+}
+
+/// Solve `x² - (k·(N - 1) + 1)·x - k ≡ 0 (mod e)`.
+///
+/// The roots are `kp` and `kq`.
+pub fn find_kp_kq(n: &BigUint, e: u64, k: u64) -> Vec<u64> {
+    let b = ((k * (n - 1_u8) + 1_u8) % e).to_u64().unwrap() as u128;
+    let (e, k) = (e as u128, k as u128);
+    (1..e)
+        .filter(|&x| (x * x % e + e - b * x % e + e - k).is_multiple_of(e))
+        .map(|x| x as u64)
+        .collect()
+}
+
+/// Branch and prune reconstruction.
+///
+/// Params:
+/// * `key`: partially known key. At least `n` and `e` must be known.
+/// * `k`, `kp`, `kq`: multipliers of the key relations.
+/// * `max_width`: max number of live branches before giving up.
+pub fn branch_and_prune(
+    key: &PartialKey,
+    k: u64,
+    kp: u64,
+    kq: u64,
+    max_width: usize,
+) -> (Option<PrivateKey>, Stats) {
+    let n = &key.n;
+    let e = &key.e;
+    let half = n.bits().div_ceil(2);
+    let mut stats = Stats::default();
+
+    // e^-1 mod 2^half
+    let modulus = BigUint::one() << (half + 1);
+    let e_inv = e.modinv(&modulus).expect("e must be odd");
+
+    // p and q are odd
+    let mut branches = vec![(BigUint::one(), BigUint::one())];
+
+    for i in 1..half {
+        let m = BigUint::one() << (i + 1);
+        let mask = &m - 1_u8;
+        let n_mod = n & &mask;
+        let mut next = Vec::with_capacity(2 * branches.len());
+
+        for (p, q) in branches {
+            for p_bit in [false, true] {
+                let mut p = p.clone();
+                p.set_bit(i, p_bit);
+                // q[i] is fixed by N mod 2^(i+1)
+                let mut q = q.clone();
+                if (&p * &q) & &mask != n_mod {
+                    q.set_bit(i, true);
+                }
+                stats.explored += 1;
+
+                if !matches(&key.p, &p, i) || !matches(&key.q, &q, i) {
+                    continue;
+                }
+                // e·d = k·(N - p - q + 1) + 1 (mod 2^(i+1))
+                let phi = (&n_mod + 1_u8 + &m + &m - &p - &q) & &mask;
+                let d = (&e_inv * (k * phi + 1_u8)) & &mask;
+                if !matches(&key.d, &d, i) {
+                    continue;
+                }
+                // e·dp = kp·(p - 1) + 1 (mod 2^(i+1))
+                let dp = (&e_inv * (kp * (&p - 1_u8) + 1_u8)) & &mask;
+                if !matches(&key.dp, &dp, i) {
+                    continue;
+                }
+                let dq = (&e_inv * (kq * (&q - 1_u8) + 1_u8)) & &mask;
+                if !matches(&key.dq, &dq, i) {
+                    continue;
+                }
+                next.push((p, q));
+            }
+        }
+
+        stats.max_width = stats.max_width.max(next.len());
+        if next.is_empty() || next.len() > max_width {
+            return (None, stats);
+        }
+        branches = next;
+    }
+
+    let found = branches.into_iter().find_map(|(p, q)| {
+        (p > BigUint::one() && &p * &q == *n).then(|| PrivateKey::from_primes(p, q, e.clone()))
+    });
+    (found, stats)
+}
+
+/// Multipliers `(k, kp, kq)` given `kp`, solving the same quadratic for `k`:
+/// `k ≡ (kp² - kp)·((N - 1)·kp + 1)⁻¹ (mod e)`.
+///
+/// `None` if `kp` leads to no valid `k` or `kq`.
+pub fn find_k_kq(n: &BigUint, e: u64, kp: u64) -> Option<(u64, u64, u64)> {
+    let (big_e, x) = (BigUint::from(e), BigUint::from(kp));
+    let den = ((n - 1_u8) * &x + 1_u8).modinv(&big_e)?;
+    let k = ((&x * &x + &big_e - &x) * den % &big_e).to_u64()?;
+    let b = ((k * (n - 1_u8) + 1_u8) % &big_e).to_u64()?;
+    let kq = (b + e - kp % e) % e;
+    (k != 0 && kq != 0).then_some((k, kp, kq))
+}
+
+/// Reconstruct the private key.
+///
+/// With some upper half bits of `d` known (see [`find_k`]), `k` is found
+/// first and the most likely candidates are tried. Otherwise every `kp` is
+/// tried, which costs up to `e` branch and prune runs (most of them pruned
+/// after a few bits).
+pub fn reconstruct(key: &PartialKey, max_width: usize) -> (Option<PrivateKey>, Stats) {
+    let e = key.e.to_u64().expect("Small public exponent required");
+    let mut stats = Stats::default();
+    // The lower bits of d don't tell anything about k
+    let upper = key.n.bits() / 2 + 2;
+    let d = key.d.as_ref().filter(|d| !(&d.known >> upper).is_zero());
+    let candidates: Box<dyn Iterator<Item = (u64, u64, u64)>> = match d {
+        Some(d) => Box::new(find_k(&key.n, e, d).into_iter().take(4).flat_map(|k| {
+            let roots = find_kp_kq(&key.n, e, k);
+            let (kp, kq) = match roots.as_slice() {
+                [r] => (*r, *r),
+                [r1, r2] => (*r1, *r2),
+                _ => return vec![],
+            };
+            vec![(k, kp, kq), (k, kq, kp)]
+        })),
+        // Both (kp, kq) and (kq, kp) are met
+        None => Box::new((1..e).filter_map(|kp| find_k_kq(&key.n, e, kp))),
+    };
+    for (k, kp, kq) in candidates {
+        let (found, s) = branch_and_prune(key, k, kp, kq, max_width);
+        stats.explored += s.explored;
+        stats.max_width = stats.max_width.max(s.max_width);
+        if found.is_some() {
+            return (found, stats);
+        }
+    }
+    (None, stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decay::{decay_biguint, Bits};
+
+    const E: u64 = 65537;
+
+    #[test]
+    fn multipliers_are_found() {
+        let key = PrivateKey::generate(512, E);
+        let phi = (&key.p - 1_u8) * (&key.q - 1_u8);
+        let k = ((&key.e * &key.d - 1_u8) / phi).to_u64().unwrap();
+        let kp = ((&key.e * &key.dp - 1_u8) / (&key.p - 1_u8))
+            .to_u64()
+            .unwrap();
+        let kq = ((&key.e * &key.dq - 1_u8) / (&key.q - 1_u8))
+            .to_u64()
+            .unwrap();
+
+        let d = Bits::from_decayed(&key.d);
+        assert_eq!(find_k(&key.n, E, &d)[0], k);
+        let roots = find_kp_kq(&key.n, E, k);
+        assert!(roots.contains(&kp) && roots.contains(&kq));
+        assert_eq!(find_k_kq(&key.n, E, kp), Some((k, kp, kq)));
+    }
+
+    #[test]
+    fn reconstruct_from_decayed_key() {
+        let mut rng = rand::thread_rng();
+        let key = PrivateKey::generate(512, E);
+        let mut decayed = |x: &BigUint| Some(Bits::from_decayed(&decay_biguint(&mut rng, x, 0.1)));
+        let partial = PartialKey {
+            n: key.n.clone(),
+            e: key.e.clone(),
+            p: decayed(&key.p),
+            q: decayed(&key.q),
+            d: decayed(&key.d),
+            dp: decayed(&key.dp),
+            dq: decayed(&key.dq),
+        };
+        let (recovered, _) = reconstruct(&partial, 10_000);
+        assert_eq!(recovered, Some(key));
+    }
+
+    #[test]
+    fn reconstruct_without_d() {
+        let mut rng = rand::thread_rng();
+        let key = PrivateKey::generate(512, E);
+        let mut decayed = |x: &BigUint| Some(Bits::from_decayed(&decay_biguint(&mut rng, x, 0.1)));
+        let partial = PartialKey {
+            n: key.n.clone(),
+            e: key.e.clone(),
+            p: decayed(&key.p),
+            q: decayed(&key.q),
+            d: None,
+            dp: decayed(&key.dp),
+            dq: decayed(&key.dq),
+        };
+        let (recovered, _) = reconstruct(&partial, 10_000);
+        assert_eq!(recovered, Some(key));
+    }
+}
@@ -0,0 +1,200 @@
+//! Commit-reveal sealed bid auction front-running.
+//!
+//! A first price sealed bid auction run over a public channel (e.g. a
+//! blockchain): during the commit phase every bidder publishes a commitment to
+//! its bid, then all the bids are revealed and the highest one wins, paying its
+//! own bid. The commitment should prevent anyone from adapting the bid to the
+//! bids of the others.
+//!
+//! If the bid is committed as `H(bid)`, the commitment hides nothing: bids are
+//! small integers and whoever sees the commitments (e.g. a block producer who
+//! also decides the transactions order) recovers them by trying all the
+//! possible amounts. The front-runner then commits last to `max + 1`, winning
+//! every auction it cares about at the lowest possible price.
+//!
+//! The fix is a salted commitment `H(salt || bid)` (see the `coin-flipping`
+//! crate), which leaves the front-runner bidding blind as everyone else.
+
+use coin_flipping::commitment::{
+    hash_commit, hash_commit_unsalted, hash_verify, HashCommitment, HashOpening,
+};
+use rand::{CryptoRng, Rng, RngCore};
+
+/// Bid commitment flavor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheme {
+    /// `H(bid)`.
+    Unsalted,
+    /// `H(salt || bid)`.
+    Salted,
+}
+
+/// Commit to `amount`.
+pub fn seal<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    scheme: Scheme,
+    amount: u64,
+) -> (HashCommitment, HashOpening) {
+    let value = amount.to_be_bytes();
+    match scheme {
+        Scheme::Unsalted => {
+            let opening = HashOpening {
+                value: value.to_vec(),
+                salt: Vec::new(),
+            };
+            (hash_commit_unsalted(&value), opening)
+        }
+        Scheme::Salted => hash_commit(rng, &value),
+    }
+}
+
+/// Sealed bid auction.
+#[derive(Debug, Default)]
+pub struct Auction {
+    commitments: Vec<HashCommitment>,
+}
+
+impl Auction {
+    /// Commit phase. Returns the bidder identifier.
+    pub fn commit(&mut self, commitment: HashCommitment) -> usize {
+        self.commitments.push(commitment);
+        self.commitments.len() - 1
+    }
+
+    /// Commitments published so far.
+    pub fn commitments(&self) -> &[HashCommitment] {
+        &self.commitments
+    }
+
+    /// Reveal phase, with `openings[i]` sent by the `i`-th bidder.
+    ///
+    /// Bids whose opening doesn't match the commitment are discarded, ties are
+    /// won by the first committed bid. Returns the winner and the price.
+    pub fn close(&self, openings: &[HashOpening]) -> Option<(usize, u64)> {
+        self.commitments
+            .iter()
+            .zip(openings)
+            .enumerate()
+            .filter(|(_, (c, o))| hash_verify(c, o) && o.value.len() == 8)
+            .map(|(i, (_, o))| (i, u64::from_be_bytes(o.value[..].try_into().unwrap())))
+            .fold(None, |best: Option<(usize, u64)>, (i, bid)| match best {
+                Some((_, max)) if max >= bid => best,
+                _ => Some((i, bid)),
+            })
+    }
+}
+
+/// Recover a bid not greater than `max_bid` from its unsalted `commitment`.
+pub fn brute_force(commitment: &HashCommitment, max_bid: u64) -> Option<u64> {
+    par_search::find(0..=max_bid, |bid| {
+        &hash_commit_unsalted(&bid.to_be_bytes()) == commitment
+    })
+}
+
+/// Auction simulation outcome.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stats {
+    /// Honest bids recovered from their commitments.
+    pub recovered: usize,
+    /// Auctions won by the front-runner.
+    pub wins: usize,
+    /// Front-runner total profit, i.e. valuation minus price of the won items.
+    pub profit: u64,
+}
+
+/// Simulate `rounds` auctions between `bidders` honest bidders and a
+/// front-runner committing last.
+///
+/// Everyone values the item uniformly in `1..=max_value` and, in the blind
+/// case, shades the bid to the first price auction equilibrium
+/// `v·n/(n+1)`, with `n` the number of opponents. When the honest bids are
+/// known, the front-runner instead bids `max + 1` if it is still profitable.
+pub fn simulate<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    scheme: Scheme,
+    rounds: usize,
+    bidders: usize,
+    max_value: u64,
+) -> Stats {
+    let shade = |v: u64| v * bidders as u64 / (bidders as u64 + 1);
+    let mut stats = Stats::default();
+    for _ in 0..rounds {
+        let mut auction = Auction::default();
+        let mut openings = Vec::with_capacity(bidders + 1);
+        for _ in 0..bidders {
+            let bid = shade(rng.gen_range(1..=max_value));
+            let (commitment, opening) = seal(rng, scheme, bid);
+            auction.commit(commitment);
+            openings.push(opening);
+        }
+
+        // The front-runner inspects the pending commitments
+        let known: Option<Vec<_>> = auction
+            .commitments()
+            .iter()
+            .map(|c| brute_force(c, max_value))
+            .collect();
+        stats.recovered += known.as_ref().map(|k| k.len()).unwrap_or_default();
+        let value = rng.gen_range(1..=max_value);
+        let bid = match known {
+            Some(bids) => (bids.iter().max().unwrap_or(&0) + 1).min(value),
+            None => shade(value),
+        };
+        let (commitment, opening) = seal(rng, scheme, bid);
+        let me = auction.commit(commitment);
+        openings.push(opening);
+
+        if let Some((winner, price)) = auction.close(&openings) {
+            if winner == me {
+                stats.wins += 1;
+                stats.profit += value - price;
+            }
+        }
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn highest_valid_bid_wins() {
+        let mut auction = Auction::default();
+        let mut openings = Vec::new();
+        for (scheme, bid) in [
+            (Scheme::Salted, 10),
+            (Scheme::Unsalted, 30),
+            (Scheme::Salted, 20),
+        ] {
+            let (c, o) = seal(&mut OsRng, scheme, bid);
+            auction.commit(c);
+            openings.push(o);
+        }
+        assert_eq!(auction.close(&openings), Some((1, 30)));
+        // Cheating on the opening disqualifies the bid
+        openings[1].value = 40_u64.to_be_bytes().to_vec();
+        assert_eq!(auction.close(&openings), Some((2, 20)));
+    }
+
+    #[test]
+    fn unsalted_bids_are_recovered() {
+        let (c, _) = seal(&mut OsRng, Scheme::Unsalted, 4321);
+        assert_eq!(brute_force(&c, 10_000), Some(4321));
+        let (c, _) = seal(&mut OsRng, Scheme::Salted, 4321);
+        assert_eq!(brute_force(&c, 10_000), None);
+    }
+
+    #[test]
+    fn front_running_pays_off() {
+        let (rounds, bidders) = (300, 3);
+        let unsalted = simulate(&mut OsRng, Scheme::Unsalted, rounds, bidders, 1000);
+        let salted = simulate(&mut OsRng, Scheme::Salted, rounds, bidders, 1000);
+        assert_eq!(unsalted.recovered, rounds * bidders);
+        assert_eq!(salted.recovered, 0);
+        // Fair share is 1/4 of the auctions, front-running wins about 44%
+        assert!(unsalted.wins > salted.wins);
+        assert!(unsalted.profit > salted.profit);
+    }
+}
@@ -0,0 +1,30 @@
+use commit_reveal_auction::{brute_force, seal, simulate, Scheme};
+use rand::rngs::OsRng;
+use std::time::Instant;
+
+fn main() {
+    // Bids in cents
+    let (rounds, bidders) = (100, 4);
+    let max_value = 10_000;
+
+    let (commitment, _) = seal(&mut OsRng, Scheme::Unsalted, 731_250);
+    let start = Instant::now();
+    let bid = brute_force(&commitment, 1_000_000).unwrap();
+    println!(
+        "Bid {bid} recovered from H(bid) in {:.2}s",
+        start.elapsed().as_secs_f64()
+    );
+
+    println!("{rounds} auctions, {bidders} honest bidders plus a front-runner");
+    for scheme in [Scheme::Unsalted, Scheme::Salted] {
+        let stats = simulate(&mut OsRng, scheme, rounds, bidders, max_value);
+        println!(
+            "  {scheme:?}: recovered bids {}/{}, front-runner wins {}/{rounds} (fair share {}), avg profit {:.2}",
+            stats.recovered,
+            rounds * bidders,
+            stats.wins,
+            rounds / (bidders + 1),
+            stats.profit as f64 / rounds as f64 / 100.0,
+        );
+    }
+}
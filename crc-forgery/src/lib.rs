@@ -0,0 +1,205 @@
+//! CRC is not a MAC.
+//!
+//! A CRC is the remainder of the message polynomial divided by a fixed
+//! generator polynomial over GF(2), thus is an *affine* function of the
+//! message bits: for equal length messages
+//!
+//! ```text
+//! crc(a ⊕ b) = crc(a) ⊕ crc(b) ⊕ crc(0…0)
+//! ```
+//!
+//! Consequently:
+//! - any message can be patched with `width/8` chosen bytes to get any CRC;
+//! - the CRC change caused by a message change `Δ` does not depend on the
+//!   message, thus can be applied to a CRC hidden under an unknown XOR
+//!   (e.g. encrypted with a stream cipher like in WEP) or computed over an
+//!   unknown secret prefix (`crc(key || msg)`).
+
+/// Reflected CRC parameters (up to 64 bits).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crc {
+    /// Width in bits (multiple of 8).
+    pub width: u32,
+    /// Reflected generator polynomial.
+    pub poly: u64,
+    /// Initial register value.
+    pub init: u64,
+    /// Final xor value.
+    pub xorout: u64,
+}
+
+/// CRC-32 (ISO-HDLC), as used by Ethernet, zip, png, ...
+pub const CRC32: Crc = Crc {
+    width: 32,
+    poly: 0xedb88320,
+    init: 0xffffffff,
+    xorout: 0xffffffff,
+};
+
+/// CRC-64/XZ.
+pub const CRC64: Crc = Crc {
+    width: 64,
+    poly: 0xc96c5795d7870f42,
+    init: 0xffffffffffffffff,
+    xorout: 0xffffffffffffffff,
+};
+
+impl Crc {
+    fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.width)
+    }
+
+    /// CRC size in bytes.
+    pub fn bytes(&self) -> usize {
+        self.width as usize / 8
+    }
+
+    // Process `data` starting from the given register value.
+    fn update(&self, mut reg: u64, data: &[u8]) -> u64 {
+        for &b in data {
+            reg ^= b as u64;
+            for _ in 0..8 {
+                reg = if reg & 1 == 1 {
+                    (reg >> 1) ^ self.poly
+                } else {
+                    reg >> 1
+                };
+            }
+        }
+        reg & self.mask()
+    }
+
+    /// CRC of `data`.
+    pub fn checksum(&self, data: &[u8]) -> u64 {
+        self.update(self.init, data) ^ self.xorout
+    }
+
+    /// CRC little endian encoding, as usually appended to the data.
+    pub fn to_bytes(&self, crc: u64) -> Vec<u8> {
+        crc.to_le_bytes()[..self.bytes()].to_vec()
+    }
+
+    /// Linear part of the CRC, i.e. `crc(Δ) ⊕ crc(0…0)`.
+    ///
+    /// A change `Δ` to a message changes its CRC by `delta(Δ)`, whatever the
+    /// message content.
+    pub fn delta(&self, diff: &[u8]) -> u64 {
+        self.update(0, diff)
+    }
+
+    /// Bytes to write at `data[pos..pos + width/8]` to get the `target` CRC.
+    ///
+    /// The current content of the patched range is irrelevant.
+    ///
+    /// Panics if the range doesn't fit in `data`.
+    pub fn patch_bytes(&self, data: &[u8], pos: usize, target: u64) -> Vec<u8> {
+        let n = self.bytes();
+        assert!(pos + n <= data.len(), "Patch out of range");
+
+        let mut zeroed = data.to_vec();
+        zeroed[pos..pos + n].fill(0);
+        let goal = self.checksum(&zeroed) ^ (target & self.mask());
+
+        // Xor basis of the CRC deltas of each patch bit, with the combination
+        // of patch bits producing each basis vector.
+        let suffix = data.len() - pos - n;
+        let mut basis: Vec<(u64, u64)> = Vec::with_capacity(self.width as usize);
+        for j in 0..self.width {
+            let mut bit = vec![0; n];
+            bit[j as usize / 8] = 1 << (j % 8);
+            let reg = self.update(self.update(0, &bit), &vec![0; suffix]);
+            let (mut v, mut comb) = (reg, 1_u64 << j);
+            for &(bv, bc) in &basis {
+                if v ^ bv < v {
+                    v ^= bv;
+                    comb ^= bc;
+                }
+            }
+            if v != 0 {
+                basis.push((v, comb));
+                basis.sort_unstable_by(|a, b| b.cmp(a));
+            }
+        }
+
+        // Express the goal as combination of the patch bits
+        let (mut v, mut comb) = (goal, 0);
+        for &(bv, bc) in &basis {
+            if v ^ bv < v {
+                v ^= bv;
+                comb ^= bc;
+            }
+        }
+        assert_eq!(v, 0, "Singular CRC");
+        comb.to_le_bytes()[..n].to_vec()
+    }
+
+    /// Patch `data` in place so that its CRC becomes `target`.
+    pub fn forge(&self, data: &mut [u8], pos: usize, target: u64) {
+        let patch = self.patch_bytes(data, pos, target);
+        data[pos..pos + patch.len()].copy_from_slice(&patch);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_values() {
+        assert_eq!(CRC32.checksum(b"123456789"), 0xcbf43926);
+        assert_eq!(CRC64.checksum(b"123456789"), 0x995dc9bbdf1939fa);
+    }
+
+    #[test]
+    fn patch_to_any_crc() {
+        for crc in [CRC32, CRC64] {
+            let original = b"The quick brown fox jumps over the lazy dog".to_vec();
+            let target = crc.checksum(&original);
+            let mut forged = b"The quick brown cat jumps over the lazy dog".to_vec();
+            for pos in [0, 10, forged.len() - crc.bytes()] {
+                let mut m = forged.clone();
+                crc.forge(&mut m, pos, target);
+                assert_eq!(crc.checksum(&m), target);
+            }
+            // Appended patch
+            let pos = forged.len();
+            forged.extend(vec![0; crc.bytes()]);
+            crc.forge(&mut forged, pos, 0xdeadbeef);
+            assert_eq!(crc.checksum(&forged), 0xdeadbeef);
+        }
+    }
+
+    #[test]
+    fn encrypted_crc_bit_flipping() {
+        // WEP style: (msg || crc(msg)) ⊕ keystream
+        let msg = b"amount=0010".to_vec();
+        let keystream: Vec<u8> = (0..msg.len() + 4).map(|_| rand::random()).collect();
+        let mut plain = msg.clone();
+        plain.extend(CRC32.to_bytes(CRC32.checksum(&msg)));
+        let mut ct: Vec<u8> = plain.iter().zip(&keystream).map(|(p, k)| p ^ k).collect();
+
+        // Attacker knows the message format, not the keystream
+        let diff: Vec<u8> = msg.iter().zip(b"amount=9990").map(|(a, b)| a ^ b).collect();
+        let crc_diff = CRC32.to_bytes(CRC32.delta(&diff));
+        let full_diff = diff.iter().chain(&crc_diff);
+        ct.iter_mut().zip(full_diff).for_each(|(c, d)| *c ^= d);
+
+        let dec: Vec<u8> = ct.iter().zip(&keystream).map(|(c, k)| c ^ k).collect();
+        let (m, tag) = dec.split_at(msg.len());
+        assert_eq!(m, b"amount=9990");
+        assert_eq!(tag, CRC32.to_bytes(CRC32.checksum(m)));
+    }
+
+    #[test]
+    fn secret_prefix_mac_forgery() {
+        let key = b"secret";
+        let mac = |m: &[u8]| CRC64.checksum(&[&key[..], m].concat());
+        let tag = mac(b"user=guest");
+        let diff: Vec<u8> = b"user=guest"
+            .iter()
+            .zip(b"user=admin")
+            .map(|(a, b)| a ^ b)
+            .collect();
+        assert_eq!(tag ^ CRC64.delta(&diff), mac(b"user=admin"));
+    }
+}
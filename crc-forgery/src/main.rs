@@ -0,0 +1,43 @@
+use crc_forgery::{CRC32, CRC64};
+
+fn main() {
+    // Same CRC, different message
+    let original = b"Pay 10 EUR to Alice. Regards, Bob".to_vec();
+    let crc = CRC32.checksum(&original);
+    println!("{:?} crc32: {crc:08x}", String::from_utf8_lossy(&original));
+
+    let mut forged = b"Pay 9999 EUR to Mallory. Regards, Bob. ".to_vec();
+    let pos = forged.len();
+    forged.extend([0; 4]);
+    CRC32.forge(&mut forged, pos, crc);
+    println!(
+        "{:?} crc32: {:08x}",
+        String::from_utf8_lossy(&forged),
+        CRC32.checksum(&forged)
+    );
+
+    // Chosen CRC-64 by patching the middle of the message
+    let mut msg = b"header:xxxxxxxx:trailer".to_vec();
+    CRC64.forge(&mut msg, 7, 0x0123456789abcdef);
+    println!("{} crc64: {:016x}", hex::encode(&msg), CRC64.checksum(&msg));
+
+    // CRC encrypted with an unknown keystream: flip message and CRC bits
+    let msg = b"to=alice;amount=0010";
+    let mut plain = msg.to_vec();
+    plain.extend(CRC32.to_bytes(CRC32.checksum(msg)));
+    let keystream: Vec<u8> = (0..plain.len()).map(|_| rand::random()).collect();
+    let mut ct: Vec<u8> = plain.iter().zip(&keystream).map(|(p, k)| p ^ k).collect();
+
+    let target = b"to=alice;amount=9990";
+    let mut diff: Vec<u8> = msg.iter().zip(target).map(|(a, b)| a ^ b).collect();
+    diff.extend(CRC32.to_bytes(CRC32.delta(&diff)));
+    ct.iter_mut().zip(&diff).for_each(|(c, d)| *c ^= d);
+
+    let dec: Vec<u8> = ct.iter().zip(&keystream).map(|(c, k)| c ^ k).collect();
+    let (m, tag) = dec.split_at(msg.len());
+    let valid = tag == CRC32.to_bytes(CRC32.checksum(m));
+    println!(
+        "Receiver decrypts {:?}, CRC valid: {valid}",
+        String::from_utf8_lossy(m)
+    );
+}
@@ -0,0 +1,325 @@
+//! Cube attack (Dinur–Shamir).
+//!
+//! An output bit is a polynomial `p(k, v)` in the key and IV bits. Summing it
+//! over the `2^d` assignments of a set `I` of IV bits (a cube, the other IV
+//! bits fixed to zero) cancels every monomial not divisible by
+//! `t_I = Π_{i ∈ I} v_i` and leaves the quotient, the superpoly of `I`, as a
+//! polynomial in the key alone. With enough rounds it is random, with a few
+//! less some cubes have a linear superpoly.
+//!
+//! - Preprocessing ([`preprocess`]), the key is ours: cubes are searched by a
+//!   random walk until their superpoly passes the BLR linearity tests
+//!   `p(x) ⊕ p(y) ⊕ p(x ⊕ y) ⊕ p(0) = 0`, then it is reconstructed from its
+//!   values at zero and at the unit vectors. Constant superpolys, the common
+//!   case for a cube too large, are useless.
+//! - Online ([`recover_key`]), with chosen IVs: each cube sum is the value of
+//!   its superpoly at the secret key, a linear equation. The system is solved
+//!   and its free unknowns found by exhaustive search.
+//!
+//! The superpolys of a single output bit only involve the key bits which
+//! reached the output taps in a window of rounds: the first [`OUTPUT_BITS`]
+//! are used, the `j`-th one being the first output of `rounds + j` rounds.
+//!
+//! Cube sums are computed for 64 keys at once on the bitsliced cipher: the
+//! linearity tests run 64 at a time.
+
+use crate::trivium::{spread, Sliced, KEY_BITS};
+use lfsr::gf2::{BitVec, System};
+use rand::{seq::index, Rng};
+
+/// Output bits the superpolys are searched for.
+pub const OUTPUT_BITS: usize = 128;
+
+/// Most free unknowns left to the exhaustive search.
+pub const MAX_FREE: usize = 24;
+
+/// Linearity tests a superpoly has to pass, in batches of 64.
+const BLR_BATCHES: usize = 2;
+
+/// Steps of a random walk before giving up.
+const WALK_STEPS: usize = 20;
+
+/// Linear superpoly of a cube of IV bits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Superpoly {
+    /// IV bits indices.
+    pub cube: Vec<usize>,
+    /// Index of the output bit.
+    pub output: usize,
+    pub constant: u8,
+    /// Coefficients of the key bits.
+    pub linear: BitVec,
+}
+
+impl Superpoly {
+    /// Value at `key`.
+    pub fn eval(&self, key: &BitVec) -> u8 {
+        self.constant ^ self.linear.dot(key)
+    }
+}
+
+/// Sum over the `cube` IVs of the first output bit after `rounds`
+/// initialization rounds, for the 64 bitsliced keys.
+pub fn cube_sum(keys: &[u64; KEY_BITS], cube: &[usize], rounds: usize) -> u64 {
+    (0..1_u64 << cube.len()).fold(0, |acc, v| {
+        let mut iv = [0; KEY_BITS];
+        for (j, &i) in cube.iter().enumerate() {
+            iv[i] = spread((v >> j) as u8);
+        }
+        acc ^ Sliced::new(keys, &iv, rounds).clock()
+    })
+}
+
+/// Whether the superpoly of `cube` for the first output bit after `rounds`
+/// looks linear (affine), by `64·batches` BLR tests on random keys.
+pub fn is_linear<R: Rng + ?Sized>(
+    cube: &[usize],
+    rounds: usize,
+    batches: usize,
+    rng: &mut R,
+) -> bool {
+    let zero = cube_sum(&[0; KEY_BITS], cube, rounds);
+    (0..batches).all(|_| {
+        let x: [u64; KEY_BITS] = std::array::from_fn(|_| rng.gen());
+        let y: [u64; KEY_BITS] = std::array::from_fn(|_| rng.gen());
+        let xy = std::array::from_fn(|i| x[i] ^ y[i]);
+        let px = cube_sum(&x, cube, rounds);
+        let py = cube_sum(&y, cube, rounds);
+        let pxy = cube_sum(&xy, cube, rounds);
+        px ^ py ^ pxy ^ zero == 0
+    })
+}
+
+/// Superpoly of `cube` for the `output`-th output bit after `rounds`,
+/// assuming it is linear.
+pub fn superpoly(cube: &[usize], rounds: usize, output: usize) -> Superpoly {
+    let rounds = rounds + output;
+    let constant = cube_sum(&[0; KEY_BITS], cube, rounds) as u8 & 1;
+    let mut linear = BitVec::zeros(KEY_BITS);
+    // Unit vectors, 64 at a time
+    for first in (0..KEY_BITS).step_by(64) {
+        let keys = std::array::from_fn(|i| match i.checked_sub(first) {
+            Some(lane) if lane < 64 => 1 << lane,
+            _ => 0,
+        });
+        let sum = cube_sum(&keys, cube, rounds);
+        for i in first..KEY_BITS.min(first + 64) {
+            linear.set(i, (sum >> (i - first)) as u8 ^ constant);
+        }
+    }
+    let mut cube = cube.to_vec();
+    cube.sort_unstable();
+    Superpoly {
+        cube,
+        output,
+        constant,
+        linear,
+    }
+}
+
+/// Nonconstant linear superpoly for the `output`-th output bit after `rounds`,
+/// by a random walk from a random cube of `dim` IV bits: a bit is dropped
+/// while the superpoly is constant, as the cube is too large, and one is added
+/// while it is nonlinear. `None` after `steps` steps.
+pub fn random_walk<R: Rng + ?Sized>(
+    rounds: usize,
+    output: usize,
+    dim: usize,
+    steps: usize,
+    rng: &mut R,
+) -> Option<Superpoly> {
+    let mut cube = index::sample(rng, KEY_BITS, dim).into_vec();
+    for _ in 0..steps {
+        if !is_linear(&cube, rounds + output, BLR_BATCHES, rng) {
+            if cube.len() == KEY_BITS {
+                return None;
+            }
+            let outside: Vec<usize> = (0..KEY_BITS).filter(|i| !cube.contains(i)).collect();
+            cube.push(outside[rng.gen_range(0..outside.len())]);
+            continue;
+        }
+        let poly = superpoly(&cube, rounds, output);
+        if !poly.linear.is_zero() {
+            return Some(poly);
+        }
+        if cube.len() == 1 {
+            return None;
+        }
+        cube.swap_remove(rng.gen_range(0..cube.len()));
+    }
+    None
+}
+
+/// Linearly independent superpolys out of `walks` random walks from cubes of
+/// `dim` IV bits, for random output bits.
+pub fn preprocess<R: Rng + ?Sized>(
+    rounds: usize,
+    dim: usize,
+    walks: usize,
+    rng: &mut R,
+) -> Vec<Superpoly> {
+    let mut found = Vec::new();
+    let mut system = System::new(KEY_BITS);
+    for _ in 0..walks {
+        if found.len() == KEY_BITS {
+            break;
+        }
+        let output = rng.gen_range(0..OUTPUT_BITS);
+        let Some(poly) = random_walk(rounds, output, dim, WALK_STEPS, rng) else {
+            continue;
+        };
+        let mut extended = system.clone();
+        extended.push(poly.linear.clone(), 0);
+        if extended.rank() > found.len() {
+            system = extended;
+            found.push(poly);
+        }
+    }
+    found
+}
+
+/// Key of the `oracle`, returning the first [`OUTPUT_BITS`] keystream bits
+/// (packed as by [`keystream`](crate::trivium::keystream)) of the cipher
+/// reduced to `rounds` initialization rounds, for a chosen IV.
+///
+/// The cube sums give the key up to [`MAX_FREE`] unknowns, which are searched
+/// against the keystream for random IVs. `None` if more unknowns are left, or
+/// if no key matches (a superpoly was not really linear).
+pub fn recover_key<F, R>(
+    mut oracle: F,
+    superpolys: &[Superpoly],
+    rounds: usize,
+    rng: &mut R,
+) -> Option<[u8; 10]>
+where
+    F: FnMut(&[u8; 10]) -> Vec<u8>,
+    R: Rng + ?Sized,
+{
+    let bit = |z: &[u8], j: usize| z[j / 8] >> (j % 8) & 1;
+    let mut system = System::new(KEY_BITS);
+    for poly in superpolys {
+        let sum = (0..1_u64 << poly.cube.len()).fold(0, |acc, v| {
+            let mut iv = [0; 10];
+            for (j, &i) in poly.cube.iter().enumerate() {
+                iv[i / 8] |= ((v >> j) as u8 & 1) << (i % 8);
+            }
+            acc ^ bit(&oracle(&iv), poly.output)
+        });
+        system.push(poly.linear.clone(), sum ^ poly.constant);
+    }
+    let (particular, kernel) = system.solutions()?;
+    if kernel.len() > MAX_FREE {
+        return None;
+    }
+
+    // Each keystream bit rules out half of the wrong keys
+    let checks: Vec<([u8; 10], Vec<u8>)> = (0..(kernel.len() + 64).div_ceil(OUTPUT_BITS))
+        .map(|_| {
+            let iv = rng.gen();
+            (iv, oracle(&iv))
+        })
+        .collect();
+    // The 64 candidates of a batch differ in the first six kernel vectors
+    let lanes = [
+        0xaaaa_aaaa_aaaa_aaaa,
+        0xcccc_cccc_cccc_cccc,
+        0xf0f0_f0f0_f0f0_f0f0,
+        0xff00_ff00_ff00_ff00,
+        0xffff_0000_ffff_0000,
+        0xffff_ffff_0000_0000,
+    ];
+    let valid = match kernel.len() {
+        free if free < 6 => (1 << (1 << free)) - 1,
+        _ => u64::MAX,
+    };
+    (0..1_u64 << kernel.len().saturating_sub(6)).find_map(|batch| {
+        let keys: [u64; KEY_BITS] = std::array::from_fn(|i| {
+            kernel
+                .iter()
+                .enumerate()
+                .filter(|(_, v)| v.get(i) == 1)
+                .fold(spread(particular.get(i)), |acc, (b, _)| {
+                    match lanes.get(b) {
+                        Some(mask) => acc ^ mask,
+                        None => acc ^ spread((batch >> (b - 6)) as u8),
+                    }
+                })
+        });
+        let mut alive = valid;
+        for (iv, z) in &checks {
+            let iv = std::array::from_fn(|i| spread(iv[i / 8] >> (i % 8)));
+            let mut state = Sliced::new(&keys, &iv, rounds);
+            for j in 0..OUTPUT_BITS {
+                alive &= !(state.clock() ^ spread(bit(z, j)));
+                if alive == 0 {
+                    return None;
+                }
+            }
+        }
+        let lane = alive.trailing_zeros();
+        let mut key = [0; 10];
+        for (i, word) in keys.iter().enumerate() {
+            key[i / 8] |= ((word >> lane) as u8 & 1) << (i % 8);
+        }
+        Some(key)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trivium::keystream;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const ROUNDS: usize = 400;
+
+    fn key_bits(key: &[u8; 10]) -> BitVec {
+        let bits: Vec<u8> = (0..KEY_BITS).map(|i| key[i / 8] >> (i % 8) & 1).collect();
+        BitVec::from_bits(&bits)
+    }
+
+    #[test]
+    fn superpolys_match_cube_sums() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let superpolys = preprocess(ROUNDS, 3, 50, &mut rng);
+        assert!(superpolys.len() >= 10);
+        let key = rng.gen();
+        for poly in &superpolys {
+            let sum = (0..1_u64 << poly.cube.len()).fold(0, |acc, v| {
+                let mut iv = [0; 10];
+                for (j, &i) in poly.cube.iter().enumerate() {
+                    iv[i / 8] |= ((v >> j) as u8 & 1) << (i % 8);
+                }
+                let z = keystream(&key, &iv, ROUNDS, OUTPUT_BITS / 8);
+                acc ^ z[poly.output / 8] >> (poly.output % 8) & 1
+            });
+            assert_eq!(poly.eval(&key_bits(&key)), sum);
+        }
+    }
+
+    #[test]
+    fn nonlinear_superpoly_detected() {
+        let mut rng = StdRng::seed_from_u64(0);
+        // The empty cube superpoly is the output bit itself
+        assert!(!is_linear(&[], ROUNDS, 1, &mut rng));
+        // After one round the output is s₆₅ ⊕ s₂₈₇ ⊕ ..., the other taps zero
+        assert!(is_linear(&[], 1, 1, &mut rng));
+        let poly = superpoly(&[], 1, 0);
+        assert_eq!(
+            (poly.constant, poly.linear),
+            (1, BitVec::unit(KEY_BITS, 64))
+        );
+    }
+
+    #[test]
+    fn key_recovered() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let superpolys = preprocess(ROUNDS, 3, 600, &mut rng);
+        let key = rng.gen();
+        let oracle = |iv: &[u8; 10]| keystream(&key, iv, ROUNDS, OUTPUT_BITS / 8);
+        assert_eq!(
+            recover_key(oracle, &superpolys, ROUNDS, &mut rng),
+            Some(key)
+        );
+    }
+}
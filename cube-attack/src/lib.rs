@@ -0,0 +1,10 @@
+//! Cube attack on Trivium with reduced initialization rounds.
+//!
+//! Trivium is a hardware oriented stream cipher of the eSTREAM portfolio,
+//! built from nonlinear feedback shift registers with quadratic feedback. Its
+//! output is a polynomial in the key and IV bits whose degree grows with the
+//! initialization rounds: reduced to a few hundreds, enough of it is linear
+//! in the key once summed over chosen IVs to recover the whole key.
+
+pub mod attacks;
+pub mod trivium;
@@ -0,0 +1,61 @@
+use cube_attack::{
+    attacks::{preprocess, recover_key, MAX_FREE, OUTPUT_BITS},
+    trivium::{keystream, FULL_ROUNDS, KEY_BITS},
+};
+use rand::Rng;
+use std::{cell::Cell, time::Instant};
+
+const ROUNDS: usize = 400;
+
+fn main() {
+    let mut rng = rand::thread_rng();
+
+    println!("Trivium, {ROUNDS} of {FULL_ROUNDS} initialization rounds");
+    let start = Instant::now();
+    let superpolys = preprocess(ROUNDS, 3, 1500, &mut rng);
+    println!(
+        "Preprocessing: {} independent linear superpolys in {:.1?}",
+        superpolys.len(),
+        start.elapsed()
+    );
+    for poly in superpolys.iter().take(5) {
+        let terms: Vec<String> = (0..KEY_BITS)
+            .filter(|&i| poly.linear.get(i) == 1)
+            .map(|i| format!("k{i}"))
+            .chain((poly.constant == 1).then(|| "1".to_string()))
+            .collect();
+        println!(
+            "  cube {:?}, output {}: {}",
+            poly.cube,
+            poly.output,
+            terms.join(" + ")
+        );
+    }
+    println!(
+        "  key bits left to search: {} (at most {MAX_FREE})",
+        KEY_BITS - superpolys.len()
+    );
+
+    let key: [u8; 10] = rng.gen();
+    let queries = Cell::new(0);
+    let oracle = |iv: &[u8; 10]| {
+        queries.set(queries.get() + 1);
+        keystream(&key, iv, ROUNDS, OUTPUT_BITS / 8)
+    };
+    let start = Instant::now();
+    let found = recover_key(oracle, &superpolys, ROUNDS, &mut rng);
+    println!(
+        "Online: {} chosen IVs, {:.1?}",
+        queries.get(),
+        start.elapsed()
+    );
+    println!("  secret key:    {}", hex(&key));
+    match found {
+        Some(found) => println!("  recovered key: {} ({})", hex(&found), found == key),
+        None => println!("  too few superpolys, preprocess more"),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
@@ -0,0 +1,123 @@
+//! Trivium with a configurable number of initialization rounds.
+//!
+//! Three nonlinear feedback shift registers of 93, 84 and 111 bits, each fed
+//! by a bit of another one plus an AND of two of its own bits: the state
+//! update is quadratic, the output bit linear. The 80 bits key goes in the
+//! first register, the 80 bits IV in the second one, and the state is
+//! clocked [`FULL_ROUNDS`] times before the first output bit.
+//!
+//! With fewer rounds the output is a low degree polynomial in the key and IV
+//! bits, which is what the [`attacks`](crate::attacks) exploit.
+//!
+//! Everything is bitsliced: [`Sliced`] runs 64 instances at once, one per
+//! bit of a `u64` lane, e.g. the same IV under 64 keys.
+
+/// Key and IV bits.
+pub const KEY_BITS: usize = 80;
+
+/// Initialization rounds of the full cipher.
+pub const FULL_ROUNDS: usize = 4 * 288;
+
+/// 64 instances in parallel, the bits of the `i`-th instance being the `i`-th
+/// bit of every word.
+///
+/// The registers are stored as the sequences of the bits they were fed with,
+/// the oldest bit in the register first: `s₁..s₉₃` at time `t` are
+/// `a[t + 92]..a[t]`, similarly for the others.
+#[derive(Debug, Clone)]
+pub struct Sliced {
+    a: Vec<u64>,
+    b: Vec<u64>,
+    c: Vec<u64>,
+    t: usize,
+}
+
+impl Sliced {
+    /// Instances after the key and IV setup and `rounds` clocks.
+    pub fn new(key: &[u64; KEY_BITS], iv: &[u64; KEY_BITS], rounds: usize) -> Self {
+        let mut a = vec![0; 93];
+        let mut b = vec![0; 84];
+        let mut c = vec![0; 111];
+        // s_k = a[93 - k]
+        (0..KEY_BITS).for_each(|i| a[92 - i] = key[i]);
+        (0..KEY_BITS).for_each(|i| b[83 - i] = iv[i]);
+        c[..3].fill(u64::MAX);
+        let mut state = Sliced { a, b, c, t: 0 };
+        (0..rounds).for_each(|_| {
+            state.clock();
+        });
+        state
+    }
+
+    /// Instances running the same key and IV.
+    pub fn broadcast(key: &[u8], iv: &[u8], rounds: usize) -> Self {
+        let lanes = |bytes: &[u8]| std::array::from_fn(|i| spread(bytes[i / 8] >> (i % 8)));
+        Self::new(&lanes(key), &lanes(iv), rounds)
+    }
+
+    /// Output bits, then clock.
+    pub fn clock(&mut self) -> u64 {
+        let (a, b, c, t) = (&mut self.a, &mut self.b, &mut self.c, self.t);
+        let (t1, t2, t3) = (a[t + 27] ^ a[t], b[t + 15] ^ b[t], c[t + 45] ^ c[t]);
+        let z = t1 ^ t2 ^ t3;
+        let t1 = t1 ^ (a[t + 2] & a[t + 1]) ^ b[t + 6];
+        let t2 = t2 ^ (b[t + 2] & b[t + 1]) ^ c[t + 24];
+        let t3 = t3 ^ (c[t + 2] & c[t + 1]) ^ a[t + 24];
+        a.push(t3);
+        b.push(t1);
+        c.push(t2);
+        self.t += 1;
+        z
+    }
+}
+
+/// Keystream of the cipher reduced to `rounds` initialization rounds, bits
+/// packed least significant first. The key and IV bits are read the same way.
+pub fn keystream(key: &[u8; 10], iv: &[u8; 10], rounds: usize, len: usize) -> Vec<u8> {
+    let mut state = Sliced::broadcast(key, iv, rounds);
+    let mut out = vec![0; len];
+    for i in 0..8 * len {
+        out[i / 8] |= (state.clock() as u8 & 1) << (i % 8);
+    }
+    out
+}
+
+// All ones if the lowest bit is set.
+pub(crate) fn spread(bit: u8) -> u64 {
+    0_u64.wrapping_sub((bit & 1) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_rounds_test_vector() {
+        let z = keystream(&[0; 10], &[0; 10], FULL_ROUNDS, 16);
+        let expected = [
+            0xfb, 0xe0, 0xbf, 0x26, 0x58, 0x59, 0x05, 0x1b, 0x51, 0x7a, 0x2e, 0x4e, 0x23, 0x9f,
+            0xc9, 0x7f,
+        ];
+        assert_eq!(z, expected);
+    }
+
+    #[test]
+    fn lanes_are_independent() {
+        let (k1, k2) = ([0x01; 10], [0x80; 10]);
+        let iv = [0x5a; 10];
+        let bits = |key: &[u8; 10]| keystream(key, &iv, 500, 8);
+        let mut key = [0; KEY_BITS];
+        for (i, word) in key.iter_mut().enumerate() {
+            *word = (k1[i / 8] >> (i % 8) & 1) as u64 | ((k2[i / 8] >> (i % 8) & 1) as u64) << 1;
+        }
+        let iv = std::array::from_fn(|i| spread(iv[i / 8] >> (i % 8)));
+        let mut state = Sliced::new(&key, &iv, 500);
+        let (mut z1, mut z2) = (vec![0; 8], vec![0; 8]);
+        for i in 0..64 {
+            let z = state.clock();
+            z1[i / 8] |= (z as u8 & 1) << (i % 8);
+            z2[i / 8] |= (z as u8 >> 1 & 1) << (i % 8);
+        }
+        assert_eq!((z1, z2), (bits(&k1), bits(&k2)));
+    }
+}
@@ -0,0 +1,45 @@
+//! City names dataset.
+
+use rand::{distributions::WeightedIndex, prelude::Distribution, Rng};
+
+/// Most populated Italian cities with their population in thousands.
+///
+/// This is the public auxiliary information of the attacker.
+pub const CITIES: [(&str, u64); 30] = [
+    ("Roma", 2749),
+    ("Milano", 1372),
+    ("Napoli", 914),
+    ("Torino", 841),
+    ("Palermo", 630),
+    ("Genova", 561),
+    ("Bologna", 390),
+    ("Firenze", 362),
+    ("Bari", 316),
+    ("Catania", 298),
+    ("Verona", 255),
+    ("Venezia", 250),
+    ("Messina", 218),
+    ("Padova", 206),
+    ("Trieste", 199),
+    ("Parma", 196),
+    ("Brescia", 196),
+    ("Prato", 195),
+    ("Taranto", 189),
+    ("Modena", 184),
+    ("Reggio Calabria", 171),
+    ("Reggio Emilia", 171),
+    ("Perugia", 162),
+    ("Ravenna", 155),
+    ("Livorno", 152),
+    ("Rimini", 149),
+    ("Cagliari", 148),
+    ("Foggia", 146),
+    ("Ferrara", 129),
+    ("Salerno", 127),
+];
+
+/// Sample `n` residence cities, following the population distribution.
+pub fn sample<R: Rng>(rng: &mut R, n: usize) -> Vec<&'static str> {
+    let dist = WeightedIndex::new(CITIES.iter().map(|c| c.1)).unwrap();
+    (0..n).map(|_| CITIES[dist.sample(rng)].0).collect()
+}
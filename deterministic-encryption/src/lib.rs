@@ -0,0 +1,87 @@
+//! Deterministic encryption leakage.
+//!
+//! To keep an encrypted database column searchable, each value is encrypted
+//! deterministically: the server compares the encrypted query with the stored
+//! ciphertexts, without the key. Here the cipher is AES-GCM-SIV with a fixed
+//! nonce, the best a deterministic scheme can be: nothing leaks besides the
+//! equality of the plaintexts.
+//!
+//! Equality is enough. Real data is far from uniform and the distribution of
+//! the values is public knowledge (census data, a leaked database, ...):
+//! - frequency analysis maps the most frequent ciphertext to the most
+//!   frequent value and so on, recovering most of the records;
+//! - if the attacker can insert records (sign up with a chosen city) every
+//!   injected value reveals its ciphertext.
+//!
+//! The leakage is quantified by the [`scoring`] module. These are the
+//! inference attacks of Naveed, Kamara and Wright, "Inference attacks on
+//! property-preserving encrypted databases".
+
+use aes_gcm_siv::{aead::Aead, Aes256GcmSiv, KeyInit, Nonce};
+
+pub mod attacks;
+pub mod dataset;
+pub mod scoring;
+
+/// Encrypted value.
+pub type Ciphertext = Vec<u8>;
+
+/// Deterministic cipher.
+pub struct DetCipher {
+    cipher: Aes256GcmSiv,
+}
+
+impl DetCipher {
+    pub fn new(key: &[u8; 32]) -> Self {
+        DetCipher {
+            cipher: Aes256GcmSiv::new(key.into()),
+        }
+    }
+
+    pub fn encrypt(&self, value: &str) -> Ciphertext {
+        self.cipher
+            .encrypt(&Nonce::default(), value.as_bytes())
+            .unwrap()
+    }
+
+    pub fn decrypt(&self, ciphertext: &[u8]) -> Option<String> {
+        let value = self.cipher.decrypt(&Nonce::default(), ciphertext).ok()?;
+        String::from_utf8(value).ok()
+    }
+}
+
+/// Encrypted column, as seen by the server.
+#[derive(Debug, Clone, Default)]
+pub struct Column {
+    pub rows: Vec<Ciphertext>,
+}
+
+impl Column {
+    pub fn encrypt<S: AsRef<str>>(cipher: &DetCipher, values: &[S]) -> Self {
+        Column {
+            rows: values.iter().map(|v| cipher.encrypt(v.as_ref())).collect(),
+        }
+    }
+
+    /// Rows equal to the encrypted query.
+    pub fn search(&self, query: &[u8]) -> Vec<usize> {
+        (0..self.rows.len())
+            .filter(|&i| self.rows[i] == query)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deterministic_search() {
+        let cipher = DetCipher::new(&[3; 32]);
+        let column = Column::encrypt(&cipher, &["Roma", "Milano", "Roma"]);
+        assert_eq!(column.rows[0], column.rows[2]);
+        assert_ne!(column.rows[0], column.rows[1]);
+        assert_eq!(column.search(&cipher.encrypt("Roma")), [0, 2]);
+        assert_eq!(cipher.decrypt(&column.rows[1]).unwrap(), "Milano");
+    }
+}
@@ -0,0 +1,35 @@
+use deterministic_encryption::{
+    attacks::{frequency_analysis, histogram, injection},
+    dataset, scoring, Column, DetCipher,
+};
+use rand::{rngs::OsRng, RngCore};
+
+fn main() {
+    let mut key = [0; 32];
+    OsRng.fill_bytes(&mut key);
+    let cipher = DetCipher::new(&key);
+
+    for records in [100, 1000, 10_000, 100_000] {
+        let values = dataset::sample(&mut OsRng, records);
+        let column = Column::encrypt(&cipher, &values);
+        println!(
+            "{records} records, {} distinct ciphertexts, baseline accuracy {:.3}",
+            histogram(&column).len(),
+            scoring::baseline(&values)
+        );
+        let mapping = frequency_analysis(&column, &dataset::CITIES);
+        println!(
+            "  frequency analysis: records {:.3}, values {:.3}",
+            scoring::record_accuracy(&column, &values, &mapping),
+            scoring::value_accuracy(&column, &values, &mapping)
+        );
+        for budget in [5, 10, 20] {
+            let mapping = injection(&column, &dataset::CITIES, budget, |v| cipher.encrypt(v));
+            println!(
+                "  {budget:>2} injected records: records {:.3}, values {:.3}",
+                scoring::record_accuracy(&column, &values, &mapping),
+                scoring::value_accuracy(&column, &values, &mapping)
+            );
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//! Leakage scoring.
+
+use crate::{attacks::Mapping, Column};
+use std::collections::HashMap;
+
+/// Fraction of the records correctly recovered.
+///
+/// `truth` are the plaintexts of the column rows.
+pub fn record_accuracy<S: AsRef<str>>(column: &Column, truth: &[S], mapping: &Mapping) -> f64 {
+    let hits = column
+        .rows
+        .iter()
+        .zip(truth)
+        .filter(|(c, v)| mapping.get(*c).map(String::as_str) == Some(v.as_ref()))
+        .count();
+    hits as f64 / column.rows.len() as f64
+}
+
+/// Fraction of the distinct values correctly recovered.
+pub fn value_accuracy<S: AsRef<str>>(column: &Column, truth: &[S], mapping: &Mapping) -> f64 {
+    let distinct: HashMap<_, _> = column.rows.iter().zip(truth).collect();
+    let hits = distinct
+        .iter()
+        .filter(|(c, v)| mapping.get(**c).map(String::as_str) == Some(v.as_ref()))
+        .count();
+    hits as f64 / distinct.len() as f64
+}
+
+/// Record accuracy with no leakage at all: guess the most frequent value for
+/// every record.
+pub fn baseline<S: AsRef<str>>(truth: &[S]) -> f64 {
+    let mut counts = HashMap::new();
+    truth
+        .iter()
+        .for_each(|v| *counts.entry(v.as_ref()).or_insert(0) += 1);
+    counts.values().max().copied().unwrap_or(0) as f64 / truth.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accuracies() {
+        let truth = ["a", "a", "a", "b"];
+        let column = Column {
+            rows: vec![vec![1], vec![1], vec![1], vec![2]],
+        };
+        let mapping = Mapping::from([(vec![1], "a".into()), (vec![2], "c".into())]);
+        assert_eq!(record_accuracy(&column, &truth, &mapping), 0.75);
+        assert_eq!(value_accuracy(&column, &truth, &mapping), 0.5);
+        assert_eq!(baseline(&truth), 0.75);
+    }
+}
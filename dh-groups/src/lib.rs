@@ -0,0 +1,231 @@
+//! Diffie-Hellman groups registry.
+//!
+//! Attacks and validators are most relevant against the groups people actually
+//! deploy: the RFC 3526 MODP groups (IKE, SSH) and the RFC 7919 FFDHE groups
+//! (TLS). All of them are safe primes `p = 2q + 1`, with the generator `g = 2`
+//! spanning the prime order `q` subgroup of quadratic residues.
+//!
+//! Custom parameters are supported as well, and [`DhGroup::validate`] checks
+//! them: a composite or non safe modulus (e.g. Logjam era backdoored or weak
+//! parameters) and a generator of a small subgroup all reduce the discrete log
+//! to small problems. Peer public keys must be checked too
+//! ([`DhGroup::validate_public`]): `1`, `p - 1` (order 2) or elements outside
+//! the subgroup leak bits of the secret or force the shared secret.
+
+use num_bigint::{BigUint, RandBigInt};
+use rand::Rng;
+use std::str::FromStr;
+
+pub mod primes;
+
+/// Group validation failures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The modulus is not prime.
+    CompositeModulus,
+    /// The subgroup order is not prime or doesn't divide `p - 1`.
+    BadOrder,
+    /// The generator doesn't span the prime order subgroup.
+    BadGenerator,
+    /// The public key is out of range or not in the prime order subgroup.
+    BadPublic,
+}
+
+/// Standardized groups.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Named {
+    Modp1536,
+    Modp2048,
+    Modp3072,
+    Modp4096,
+    Modp6144,
+    Modp8192,
+    Ffdhe2048,
+    Ffdhe3072,
+    Ffdhe4096,
+    Ffdhe6144,
+    Ffdhe8192,
+}
+
+impl Named {
+    pub const ALL: [Named; 11] = [
+        Named::Modp1536,
+        Named::Modp2048,
+        Named::Modp3072,
+        Named::Modp4096,
+        Named::Modp6144,
+        Named::Modp8192,
+        Named::Ffdhe2048,
+        Named::Ffdhe3072,
+        Named::Ffdhe4096,
+        Named::Ffdhe6144,
+        Named::Ffdhe8192,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Named::Modp1536 => "modp1536",
+            Named::Modp2048 => "modp2048",
+            Named::Modp3072 => "modp3072",
+            Named::Modp4096 => "modp4096",
+            Named::Modp6144 => "modp6144",
+            Named::Modp8192 => "modp8192",
+            Named::Ffdhe2048 => "ffdhe2048",
+            Named::Ffdhe3072 => "ffdhe3072",
+            Named::Ffdhe4096 => "ffdhe4096",
+            Named::Ffdhe6144 => "ffdhe6144",
+            Named::Ffdhe8192 => "ffdhe8192",
+        }
+    }
+
+    fn prime(&self) -> &'static str {
+        match self {
+            Named::Modp1536 => primes::MODP_1536,
+            Named::Modp2048 => primes::MODP_2048,
+            Named::Modp3072 => primes::MODP_3072,
+            Named::Modp4096 => primes::MODP_4096,
+            Named::Modp6144 => primes::MODP_6144,
+            Named::Modp8192 => primes::MODP_8192,
+            Named::Ffdhe2048 => primes::FFDHE2048,
+            Named::Ffdhe3072 => primes::FFDHE3072,
+            Named::Ffdhe4096 => primes::FFDHE4096,
+            Named::Ffdhe6144 => primes::FFDHE6144,
+            Named::Ffdhe8192 => primes::FFDHE8192,
+        }
+    }
+
+    pub fn group(&self) -> DhGroup {
+        let p = BigUint::parse_bytes(self.prime().as_bytes(), 16).unwrap();
+        let q = (&p - 1_u8) >> 1;
+        DhGroup {
+            p,
+            q,
+            g: BigUint::from(2_u8),
+        }
+    }
+}
+
+impl FromStr for Named {
+    type Err = String;
+
+    /// Group name, e.g. `ffdhe2048` or `modp2048` (also `modp_2048`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.to_lowercase().replace(['_', '-'], "");
+        Named::ALL
+            .into_iter()
+            .find(|g| g.name() == name)
+            .ok_or_else(|| format!("unknown group {s}"))
+    }
+}
+
+/// Generator `g` of the order `q` subgroup of `Z_p*`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DhGroup {
+    pub p: BigUint,
+    pub q: BigUint,
+    pub g: BigUint,
+}
+
+impl DhGroup {
+    /// Custom parameters. Without `q`, `p` is assumed to be a safe prime.
+    pub fn custom(p: BigUint, g: BigUint, q: Option<BigUint>) -> Self {
+        // Same as `(p - 1) / 2` for odd `p`, even ones are rejected anyway
+        let q = q.unwrap_or_else(|| &p >> 1);
+        DhGroup { p, q, g }
+    }
+
+    /// Check that `p` and `q` are prime, `q | p - 1` and `g` has order `q`.
+    pub fn validate(&self) -> Result<(), Error> {
+        let one = BigUint::from(1_u8);
+        if self.p < BigUint::from(3_u8) || !miller_rabin::is_prime(&self.p) {
+            return Err(Error::CompositeModulus);
+        }
+        if self.q.bits() == 0
+            || ((&self.p - 1_u8) % &self.q).bits() != 0
+            || !miller_rabin::is_prime(&self.q)
+        {
+            return Err(Error::BadOrder);
+        }
+        if self.g <= one || self.g >= &self.p - 1_u8 || self.g.modpow(&self.q, &self.p) != one {
+            return Err(Error::BadGenerator);
+        }
+        Ok(())
+    }
+
+    /// Check that `1 < y < p - 1` and `y^q = 1`.
+    pub fn validate_public(&self, y: &BigUint) -> Result<(), Error> {
+        let one = BigUint::from(1_u8);
+        if y <= &one || y + 1_u8 >= self.p || y.modpow(&self.q, &self.p) != one {
+            return Err(Error::BadPublic);
+        }
+        Ok(())
+    }
+
+    /// Random secret in `[1, q)` and its public key.
+    pub fn keypair<R: Rng>(&self, rng: &mut R) -> (BigUint, BigUint) {
+        let x = rng.gen_biguint_range(&BigUint::from(1_u8), &self.q);
+        let y = self.g.modpow(&x, &self.p);
+        (x, y)
+    }
+
+    /// Shared secret with the `peer` public key, validated first.
+    pub fn shared(&self, x: &BigUint, peer: &BigUint) -> Result<BigUint, Error> {
+        self.validate_public(peer)?;
+        Ok(peer.modpow(x, &self.p))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn named_groups_structure() {
+        for named in Named::ALL {
+            let group = named.group();
+            let bits: u64 = named
+                .name()
+                .trim_start_matches(char::is_alphabetic)
+                .parse()
+                .unwrap();
+            assert_eq!(group.p.bits(), bits);
+            // The 64 most and least significant bits are all ones
+            let ones = BigUint::from(u64::MAX);
+            assert_eq!(&group.p >> (bits - 64), ones);
+            assert_eq!(&group.p & &ones, ones);
+            assert_eq!(named.name().parse::<Named>(), Ok(named));
+        }
+        assert_eq!("MODP_2048".parse::<Named>(), Ok(Named::Modp2048));
+    }
+
+    #[test]
+    fn validation() {
+        for named in [Named::Modp1536, Named::Ffdhe2048] {
+            assert_eq!(named.group().validate(), Ok(()));
+        }
+        let group = Named::Ffdhe2048.group();
+        let mut bad = group.clone();
+        bad.p += 2_u8;
+        assert_eq!(bad.validate(), Err(Error::CompositeModulus));
+        // Order 2 generator
+        let bad = DhGroup::custom(group.p.clone(), &group.p - 1_u8, None);
+        assert_eq!(bad.validate(), Err(Error::BadGenerator));
+        // Degenerate parameters are rejected before any arithmetic
+        let two = BigUint::from(2_u8);
+        for p in [0_u8, 1, 2] {
+            let bad = DhGroup::custom(p.into(), two.clone(), None);
+            assert_eq!(bad.validate(), Err(Error::CompositeModulus));
+            assert_eq!(bad.validate_public(&two), Err(Error::BadPublic));
+        }
+        let bad = DhGroup::custom(group.p.clone(), two, Some(BigUint::from(0_u8)));
+        assert_eq!(bad.validate(), Err(Error::BadOrder));
+
+        let (x, y) = group.keypair(&mut rand::thread_rng());
+        let (x2, y2) = group.keypair(&mut rand::thread_rng());
+        assert_eq!(group.shared(&x, &y2), group.shared(&x2, &y));
+        // -1 and -2 = -1·g are not quadratic residues
+        for y in [BigUint::from(1_u8), &group.p - 1_u8, &group.p - 2_u8] {
+            assert_eq!(group.shared(&x, &y), Err(Error::BadPublic));
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use clap::{Args, Parser, Subcommand};
+use dh_groups::{DhGroup, Named};
+use num_bigint::BigUint;
+use std::time::Instant;
+
+#[derive(Parser)]
+#[command(about = "Standardized Diffie-Hellman groups and validation")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List the standardized groups.
+    List,
+    /// Validate the group parameters and optionally a public key.
+    Validate {
+        #[command(flatten)]
+        group: GroupArgs,
+        /// Public key, hex encoded.
+        #[arg(long)]
+        public: Option<String>,
+    },
+    /// Run a key exchange, also against invalid public keys.
+    Exchange {
+        #[command(flatten)]
+        group: GroupArgs,
+    },
+}
+
+/// Group selection, by name or custom parameters.
+#[derive(Args)]
+struct GroupArgs {
+    /// Standardized group, e.g. ffdhe2048 or modp2048.
+    #[arg(long, conflicts_with = "p", default_value = "ffdhe2048")]
+    group: Named,
+    /// Custom modulus, hex encoded.
+    #[arg(long)]
+    p: Option<String>,
+    /// Custom generator, hex encoded.
+    #[arg(long, default_value = "2")]
+    g: String,
+    /// Custom subgroup order, hex encoded (default (p - 1)/2).
+    #[arg(long)]
+    q: Option<String>,
+}
+
+fn parse_hex(s: &str) -> BigUint {
+    BigUint::parse_bytes(s.trim_start_matches("0x").as_bytes(), 16).expect("invalid hex number")
+}
+
+impl GroupArgs {
+    fn group(&self) -> (String, DhGroup) {
+        match &self.p {
+            Some(p) => (
+                "custom".into(),
+                DhGroup::custom(
+                    parse_hex(p),
+                    parse_hex(&self.g),
+                    self.q.as_deref().map(parse_hex),
+                ),
+            ),
+            None => (self.group.name().into(), self.group.group()),
+        }
+    }
+}
+
+fn main() {
+    match Cli::parse().command {
+        Command::List => {
+            for named in Named::ALL {
+                let group = named.group();
+                println!(
+                    "{:<10} {} bits, g = {}",
+                    named.name(),
+                    group.p.bits(),
+                    group.g
+                );
+            }
+        }
+        Command::Validate { group, public } => {
+            let (name, group) = group.group();
+            let start = Instant::now();
+            println!(
+                "Group {name} ({} bits): {:?} ({:.2}s)",
+                group.p.bits(),
+                group.validate(),
+                start.elapsed().as_secs_f64()
+            );
+            if let Some(y) = public {
+                println!("Public key: {:?}", group.validate_public(&parse_hex(&y)));
+            }
+        }
+        Command::Exchange { group } => {
+            let (name, group) = group.group();
+            let mut rng = rand::thread_rng();
+            let (a, ya) = group.keypair(&mut rng);
+            let (b, yb) = group.keypair(&mut rng);
+            let (ka, kb) = (group.shared(&a, &yb), group.shared(&b, &ya));
+            println!("Group {name}, shared secrets match: {}", ka == kb);
+            let invalid = [
+                ("1", BigUint::from(1_u8)),
+                ("p - 1 (order 2)", &group.p - 1_u8),
+                ("p - 2 (outside the subgroup)", &group.p - 2_u8),
+            ];
+            for (what, y) in invalid {
+                // Without validation the shared secret is forced or leaks bits
+                let unchecked = y.modpow(&a, &group.p).to_str_radix(16);
+                println!(
+                    "Peer key {what}: unchecked secret {}..., checked {:?}",
+                    &unchecked[..unchecked.len().min(8)],
+                    group.shared(&a, &y)
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! Mallory's channels.
+
+use crate::{derive_key, handshake, open, seal, send, Channel, Message, Party, Role};
+use dh_groups::DhGroup;
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive};
+use numutil::crt;
+use rand::Rng;
+use shanks::{auto::Zmod, bsgs};
+
+/// Payload rewriting rule.
+pub type Rewrite = Box<dyn FnMut(Role, &[u8]) -> Vec<u8>>;
+
+/// Man in the middle: one key exchange with each party.
+pub struct Mitm {
+    p: BigUint,
+    secret: BigUint,
+    public: BigUint,
+    alice_key: Option<[u8; 32]>,
+    bob_key: Option<[u8; 32]>,
+    rewrite: Option<Rewrite>,
+    /// Plaintexts read so far.
+    pub transcript: Vec<(Role, Vec<u8>)>,
+}
+
+impl Mitm {
+    /// Mallory relaying the traffic, rewritten by `rewrite` if any.
+    pub fn new<R: Rng>(rng: &mut R, group: &DhGroup, rewrite: Option<Rewrite>) -> Self {
+        let (secret, public) = group.keypair(rng);
+        Mitm {
+            p: group.p.clone(),
+            secret,
+            public,
+            alice_key: None,
+            bob_key: None,
+            rewrite,
+            transcript: Vec::new(),
+        }
+    }
+}
+
+impl Channel for Mitm {
+    fn deliver(&mut self, from: Role, msg: Message) -> Message {
+        match msg {
+            Message::Public(y) => {
+                let key = Some(derive_key(&y.modpow(&self.secret, &self.p)));
+                match from {
+                    Role::Alice => self.alice_key = key,
+                    Role::Bob => self.bob_key = key,
+                }
+                Message::Public(self.public.clone())
+            }
+            Message::Data(data) => {
+                let (src, dst) = match from {
+                    Role::Alice => (self.alice_key, self.bob_key),
+                    Role::Bob => (self.bob_key, self.alice_key),
+                };
+                let (Some(src), Some(dst)) = (src, dst) else {
+                    return Message::Data(data);
+                };
+                let Some(plain) = open(&src, &data) else {
+                    return Message::Data(data);
+                };
+                let forwarded = match &mut self.rewrite {
+                    Some(rewrite) => rewrite(from, &plain),
+                    None => plain.clone(),
+                };
+                self.transcript.push((from, plain));
+                Message::Data(seal(&dst, &forwarded))
+            }
+        }
+    }
+}
+
+/// Small subgroup confinement of Alice's shared secret.
+///
+/// Bob's public key is replaced by an element of small prime order `r`. The
+/// first message sealed by Alice reveals which of the `r` keys Alice derived.
+pub struct Confiner {
+    p: BigUint,
+    element: BigUint,
+    order: u64,
+    /// Alice's secret modulo `order`.
+    pub residue: Option<u64>,
+    /// Alice's message.
+    pub plaintext: Option<Vec<u8>>,
+}
+
+impl Confiner {
+    /// Confiner to the order `r` subgroup, `r | p - 1`.
+    pub fn new(group: &DhGroup, r: u64) -> Self {
+        let cofactor = (&group.p - 1_u8) / r;
+        let element = (2_u8..)
+            .map(|a| BigUint::from(a).modpow(&cofactor, &group.p))
+            .find(|h| !h.is_one())
+            .unwrap();
+        Confiner {
+            p: group.p.clone(),
+            element,
+            order: r,
+            residue: None,
+            plaintext: None,
+        }
+    }
+}
+
+impl Channel for Confiner {
+    fn deliver(&mut self, from: Role, msg: Message) -> Message {
+        match (from, msg) {
+            (Role::Bob, Message::Public(_)) => Message::Public(self.element.clone()),
+            (Role::Alice, Message::Data(data)) => {
+                let mut shared = BigUint::one();
+                for j in 0..self.order {
+                    if let Some(plain) = open(&derive_key(&shared), &data) {
+                        self.residue = Some(j);
+                        self.plaintext = Some(plain);
+                        break;
+                    }
+                    shared = shared * &self.element % &self.p;
+                }
+                Message::Data(data)
+            }
+            (_, msg) => msg,
+        }
+    }
+}
+
+/// Recover Alice's static secret, in the prime order `q` subgroup of `group`,
+/// with one confined session per small factor of `p - 1`.
+///
+/// The residues are combined with the CRT, the rest is found by BSGS. Fails if
+/// Alice rejects the confining element.
+pub fn recover_secret<R: Rng>(
+    rng: &mut R,
+    group: &DhGroup,
+    factors: &[u64],
+    alice: &mut Party,
+) -> Option<BigUint> {
+    let mut residues = Vec::new();
+    for &r in factors {
+        let mut bob = Party::new(rng, group.clone(), false);
+        let mut confiner = Confiner::new(group, r);
+        handshake(alice, &mut bob, &mut confiner).ok()?;
+        // Bob can't read it, the session breaks only after the leak
+        let _ = send((alice, Role::Alice), &bob, &mut confiner, b"hello");
+        residues.push(BigUint::from(confiner.residue?));
+    }
+    let moduli: Vec<_> = factors.iter().map(|&r| BigUint::from(r)).collect();
+    let x = crt(&residues, &moduli)?;
+    let m: BigUint = moduli.iter().product();
+    if m >= group.q {
+        return Some(x % &group.q);
+    }
+    // a = x + m·t, thus A·g^(-x) = (g^m)^t
+    let q = group.q.to_u64()?;
+    let zmod = Zmod { n: group.p.clone() };
+    let gx_inv = group.g.modpow(&(&group.q - &x % &group.q), &group.p);
+    let h = alice.public() * gx_inv % &group.p;
+    let t = bsgs(
+        &zmod,
+        &group.g.modpow(&m, &group.p),
+        &h,
+        q / m.to_u64()? + 1,
+    )?;
+    Some((x + m * t) % &group.q)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{safe_group, weak_group};
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn mitm_reads_and_rewrites() {
+        let group = safe_group(128);
+        let mut alice = Party::new(&mut OsRng, group.clone(), true);
+        let mut bob = Party::new(&mut OsRng, group.clone(), true);
+        let rewrite: Rewrite = Box::new(|_, msg| msg.to_ascii_uppercase());
+        let mut mallory = Mitm::new(&mut OsRng, &group, Some(rewrite));
+        // Mallory's public key is valid
+        handshake(&mut alice, &mut bob, &mut mallory).unwrap();
+        let read = send(
+            (&alice, Role::Alice),
+            &bob,
+            &mut mallory,
+            b"pay 10 to carol",
+        )
+        .unwrap();
+        assert_eq!(read, b"PAY 10 TO CAROL");
+        assert_eq!(
+            mallory.transcript,
+            [(Role::Alice, b"pay 10 to carol".to_vec())]
+        );
+        assert_ne!(alice.fingerprint(), bob.fingerprint());
+    }
+
+    #[test]
+    fn confinement_recovers_static_secret() {
+        let (group, factors) = weak_group(&mut OsRng, 40, 1 << 10, 24);
+        let mut alice = Party::new(&mut OsRng, group.clone(), false);
+        let secret = recover_secret(&mut OsRng, &group, &factors, &mut alice);
+        assert_eq!(secret.as_ref(), Some(alice.secret()));
+
+        // Validation rejects the small order elements
+        let mut alice = Party::new(&mut OsRng, group.clone(), true);
+        assert_eq!(
+            recover_secret(&mut OsRng, &group, &factors, &mut alice),
+            None
+        );
+    }
+
+    #[test]
+    fn safe_group_leaks_one_bit() {
+        let group = safe_group(128);
+        let mut alice = Party::new(&mut OsRng, group.clone(), false);
+        let mut bob = Party::new(&mut OsRng, group.clone(), false);
+        let mut confiner = Confiner::new(&group, 2);
+        handshake(&mut alice, &mut bob, &mut confiner).unwrap();
+        let _ = send((&alice, Role::Alice), &bob, &mut confiner, b"hi");
+        let parity = alice.secret().bit(0) as u64;
+        assert_eq!(confiner.residue, Some(parity));
+        assert_eq!(confiner.plaintext.as_deref(), Some(&b"hi"[..]));
+    }
+}
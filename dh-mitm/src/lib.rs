@@ -0,0 +1,304 @@
+//! Diffie-Hellman over an untrusted channel.
+//!
+//! Alice and Bob exchange their public keys over a [`Channel`], derive a
+//! session key and use it to protect their messages. The channel is where
+//! Mallory sits:
+//! - without authentication Mallory runs one exchange with each of them and
+//!   relays (and rewrites) the traffic, see [`attacks::Mitm`]. Comparing a
+//!   short fingerprint of the exchanged keys out of band gives Mallory away;
+//! - if the group order has small factors and the peer public keys are not
+//!   validated, Mallory replaces a public key with an element of small order
+//!   `r`: the shared secret is confined to `r` values, which reveals the
+//!   secret modulo `r` as soon as the victim uses the key. Enough small
+//!   factors reveal a static secret, see [`attacks::Confiner`].
+//!
+//! Groups and public key validation come from the `dh-groups` crate, safe
+//! primes from `miller-rabin` and the final discrete logarithms from `shanks`.
+
+use dh_groups::DhGroup;
+use num_bigint::{BigUint, RandBigInt};
+use num_traits::One;
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+pub mod attacks;
+
+/// Protocol failures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The peer public key is not valid.
+    BadPublic,
+    /// The message doesn't authenticate.
+    BadTag,
+    /// No session key yet.
+    NoSession,
+    /// Unexpected message.
+    Unexpected,
+}
+
+/// Message sender.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Alice,
+    Bob,
+}
+
+/// Protocol message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    /// Diffie-Hellman public key.
+    Public(BigUint),
+    /// Sealed payload.
+    Data(Vec<u8>),
+}
+
+/// Transport between Alice and Bob, which may deliver something else.
+pub trait Channel {
+    fn deliver(&mut self, from: Role, msg: Message) -> Message;
+}
+
+/// Honest channel.
+pub struct Wire;
+
+impl Channel for Wire {
+    fn deliver(&mut self, _from: Role, msg: Message) -> Message {
+        msg
+    }
+}
+
+/// Session key from the shared secret.
+pub fn derive_key(shared: &BigUint) -> [u8; 32] {
+    Sha256::new()
+        .chain_update(b"dh-session")
+        .chain_update(shared.to_bytes_be())
+        .finalize()
+        .into()
+}
+
+fn keystream(key: &[u8; 32], len: usize) -> Vec<u8> {
+    (0..len.div_ceil(32) as u64)
+        .flat_map(|i| {
+            Sha256::new()
+                .chain_update(key)
+                .chain_update(i.to_be_bytes())
+                .finalize()
+        })
+        .take(len)
+        .collect()
+}
+
+fn mac(key: &[u8; 32], ciphertext: &[u8]) -> [u8; 16] {
+    let h = Sha256::new()
+        .chain_update(b"mac")
+        .chain_update(key)
+        .chain_update(ciphertext)
+        .finalize();
+    h[..16].try_into().unwrap()
+}
+
+/// Toy authenticated encryption: hash based keystream and tag.
+pub fn seal(key: &[u8; 32], msg: &[u8]) -> Vec<u8> {
+    let mut data: Vec<u8> = msg
+        .iter()
+        .zip(keystream(key, msg.len()))
+        .map(|(m, k)| m ^ k)
+        .collect();
+    let tag = mac(key, &data);
+    data.extend_from_slice(&tag);
+    data
+}
+
+pub fn open(key: &[u8; 32], data: &[u8]) -> Option<Vec<u8>> {
+    let (ciphertext, tag) = data.split_at(data.len().checked_sub(16)?);
+    (mac(key, ciphertext) == tag).then(|| {
+        ciphertext
+            .iter()
+            .zip(keystream(key, ciphertext.len()))
+            .map(|(c, k)| c ^ k)
+            .collect()
+    })
+}
+
+/// Alice or Bob, with a static key pair.
+#[derive(Debug, Clone)]
+pub struct Party {
+    group: DhGroup,
+    secret: BigUint,
+    public: BigUint,
+    validate: bool,
+    peer: Option<BigUint>,
+    key: Option<[u8; 32]>,
+}
+
+impl Party {
+    /// Party with a random key, checking the peer public key if `validate`.
+    pub fn new<R: Rng>(rng: &mut R, group: DhGroup, validate: bool) -> Self {
+        let (secret, public) = group.keypair(rng);
+        Party {
+            group,
+            secret,
+            public,
+            validate,
+            peer: None,
+            key: None,
+        }
+    }
+
+    pub fn public(&self) -> &BigUint {
+        &self.public
+    }
+
+    /// Static secret, to check the attacks outcome.
+    pub fn secret(&self) -> &BigUint {
+        &self.secret
+    }
+
+    pub fn hello(&self) -> Message {
+        Message::Public(self.public.clone())
+    }
+
+    /// Derive the session key from the peer hello.
+    pub fn receive_hello(&mut self, msg: Message) -> Result<(), Error> {
+        let Message::Public(peer) = msg else {
+            return Err(Error::Unexpected);
+        };
+        let shared = if self.validate {
+            self.group
+                .shared(&self.secret, &peer)
+                .map_err(|_| Error::BadPublic)?
+        } else {
+            peer.modpow(&self.secret, &self.group.p)
+        };
+        self.key = Some(derive_key(&shared));
+        self.peer = Some(peer);
+        Ok(())
+    }
+
+    pub fn seal(&self, msg: &[u8]) -> Result<Message, Error> {
+        let key = self.key.as_ref().ok_or(Error::NoSession)?;
+        Ok(Message::Data(seal(key, msg)))
+    }
+
+    pub fn open(&self, msg: &Message) -> Result<Vec<u8>, Error> {
+        let key = self.key.as_ref().ok_or(Error::NoSession)?;
+        let Message::Data(data) = msg else {
+            return Err(Error::Unexpected);
+        };
+        open(key, data).ok_or(Error::BadTag)
+    }
+
+    /// Short fingerprint of the two public keys as seen by this party, to be
+    /// compared out of band (e.g. read over the phone).
+    pub fn fingerprint(&self) -> Option<String> {
+        let peer = self.peer.as_ref()?;
+        let (a, b) = if self.public < *peer {
+            (&self.public, peer)
+        } else {
+            (peer, &self.public)
+        };
+        let h = Sha256::new()
+            .chain_update(a.to_bytes_be())
+            .chain_update(b.to_bytes_be())
+            .finalize();
+        Some(format!("{:02x}{:02x}-{:02x}{:02x}", h[0], h[1], h[2], h[3]))
+    }
+}
+
+/// Key exchange, Alice speaks first.
+pub fn handshake<C: Channel>(
+    alice: &mut Party,
+    bob: &mut Party,
+    channel: &mut C,
+) -> Result<(), Error> {
+    bob.receive_hello(channel.deliver(Role::Alice, alice.hello()))?;
+    alice.receive_hello(channel.deliver(Role::Bob, bob.hello()))
+}
+
+/// Send a message from `from` to `to`, returning what `to` reads.
+pub fn send<C: Channel>(
+    from: (&Party, Role),
+    to: &Party,
+    channel: &mut C,
+    msg: &[u8],
+) -> Result<Vec<u8>, Error> {
+    to.open(&channel.deliver(from.1, from.0.seal(msg)?))
+}
+
+/// Group of prime order `q` of `q_bits` bits in `Z_p^*` with `p - 1 =
+/// 2·q·r₁·...·rₖ·k`, where the `rᵢ` are distinct primes less than `max_factor`
+/// with product at least `2^factors_bits`.
+///
+/// Returns the group and the small factors.
+pub fn weak_group<R: Rng>(
+    rng: &mut R,
+    q_bits: u64,
+    max_factor: u64,
+    factors_bits: u64,
+) -> (DhGroup, Vec<u64>) {
+    let q = loop {
+        let q = rng.gen_biguint(q_bits) | (BigUint::one() << (q_bits - 1)) | BigUint::one();
+        if miller_rabin::is_prime(&q) {
+            break q;
+        }
+    };
+    let mut factors: Vec<u64> = Vec::new();
+    while factors.iter().map(|&r| (r as f64).log2()).sum::<f64>() < factors_bits as f64 {
+        let r = rng.gen_range(max_factor / 2..max_factor);
+        if miller_rabin::is_prime(&r.into()) && !factors.contains(&r) {
+            factors.push(r);
+        }
+    }
+    let base = factors.iter().fold(&q << 1, |acc, &r| acc * r);
+    let mut k = BigUint::one();
+    let p = loop {
+        let p = &base * &k + 1_u8;
+        if miller_rabin::is_prime(&p) {
+            break p;
+        }
+        k += 1_u8;
+    };
+    let cofactor = (&p - 1_u8) / &q;
+    let g = (2_u8..)
+        .map(|a| BigUint::from(a).modpow(&cofactor, &p))
+        .find(|g| !g.is_one())
+        .unwrap();
+    (DhGroup { p, q, g }, factors)
+}
+
+/// Safe prime group of `bits` bits, generator of the order `q` subgroup.
+pub fn safe_group(bits: usize) -> DhGroup {
+    let (p, q) = miller_rabin::safe_prime(bits, None).expect("safe prime");
+    // Squares span the quadratic residues, of order q
+    DhGroup {
+        p,
+        q,
+        g: 4_u8.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn honest_exchange() {
+        let group = safe_group(128);
+        assert_eq!(group.validate(), Ok(()));
+        let mut alice = Party::new(&mut OsRng, group.clone(), true);
+        let mut bob = Party::new(&mut OsRng, group, true);
+        handshake(&mut alice, &mut bob, &mut Wire).unwrap();
+        let read = send((&alice, Role::Alice), &bob, &mut Wire, b"hi Bob").unwrap();
+        assert_eq!(read, b"hi Bob");
+        assert_eq!(alice.fingerprint(), bob.fingerprint());
+    }
+
+    #[test]
+    fn weak_group_structure() {
+        let (group, factors) = weak_group(&mut OsRng, 40, 1 << 12, 30);
+        assert_eq!(group.validate(), Ok(()));
+        for r in factors {
+            assert!(((&group.p - 1_u8) % r).bits() == 0);
+        }
+    }
+}
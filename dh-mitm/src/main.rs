@@ -0,0 +1,43 @@
+use dh_mitm::{
+    attacks::{recover_secret, Mitm, Rewrite},
+    handshake, safe_group, send, weak_group, Party, Role,
+};
+use rand::rngs::OsRng;
+
+fn main() {
+    let group = safe_group(256);
+    println!("Safe prime group, p = {}", group.p);
+    let mut alice = Party::new(&mut OsRng, group.clone(), true);
+    let mut bob = Party::new(&mut OsRng, group.clone(), true);
+    let rewrite: Rewrite = Box::new(|from, msg| match from {
+        Role::Alice => String::from_utf8_lossy(msg)
+            .replace("IT01", "MT66")
+            .into_bytes(),
+        Role::Bob => msg.to_vec(),
+    });
+    let mut mallory = Mitm::new(&mut OsRng, &group, Some(rewrite));
+    handshake(&mut alice, &mut bob, &mut mallory).unwrap();
+    let sent = b"Please wire the money to IT01 2345 6789";
+    let read = send((&alice, Role::Alice), &bob, &mut mallory, sent).unwrap();
+    println!("Man in the middle:");
+    println!("  Alice sends: {}", String::from_utf8_lossy(sent));
+    println!("  Bob reads:   {}", String::from_utf8_lossy(&read));
+    println!(
+        "  fingerprints: Alice {}, Bob {}",
+        alice.fingerprint().unwrap(),
+        bob.fingerprint().unwrap()
+    );
+
+    let (group, factors) = weak_group(&mut OsRng, 64, 1 << 16, 48);
+    println!("Weak group, p - 1 small factors {factors:?}");
+    for validate in [false, true] {
+        let mut alice = Party::new(&mut OsRng, group.clone(), validate);
+        let found = recover_secret(&mut OsRng, &group, &factors, &mut alice);
+        println!("  Alice validating: {validate}");
+        println!("    secret:    {}", alice.secret());
+        match found {
+            Some(secret) => println!("    recovered: {secret}"),
+            None => println!("    confining element rejected"),
+        }
+    }
+}
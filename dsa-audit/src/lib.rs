@@ -0,0 +1,403 @@
+//! DSA and Elgamal weak parameters audit.
+//!
+//! The strength of a key `y = g^x (mod p)` is the one of the discrete
+//! logarithm in the subgroup spanned by `g`, which Pohlig–Hellman reduces to
+//! the prime factors of its order: what matters is the largest of them, not the
+//! size of `p`. Given the domain parameters `(p, q, g)` and the public key `y`,
+//! [`audit`] checks:
+//! - the primality of `p` and `q` and that `q | p - 1` (`miller-rabin`);
+//! - the factorization of `p - 1` (`numutil`), a smooth `p - 1` makes every
+//!   subgroup weak;
+//! - the actual order of `g`, which may be much smaller than `q`;
+//! - that `y` belongs to the subgroup spanned by `g`.
+//!
+//! When the largest prime factor of the order of `g` is small enough the secret
+//! is recovered with [`pohlig_hellman`], each prime order problem solved by
+//! the `shanks` crate (BSGS or Pollard's rho). The secret is recovered modulo
+//! the order of `g`, which is all that matters to sign or decrypt.
+
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive, Zero};
+use numutil::{crt, Factorization};
+use shanks::auto::{solve_dlog_auto, Hint};
+use std::fmt;
+
+/// Domain parameters and public key. Elgamal parameters may come without `q`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    pub p: BigUint,
+    pub q: Option<BigUint>,
+    pub g: BigUint,
+    pub y: BigUint,
+}
+
+/// Audit effort.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// Trial division bound for the factorization of `p - 1`.
+    pub trial_bound: u32,
+    /// Pollard's rho steps per factor of `p - 1`.
+    pub rho_steps: u64,
+    /// Secret recovery is attempted if the prime factors of the order of `g`
+    /// have at most these bits.
+    pub dlog_bits: u64,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            trial_bound: 1 << 16,
+            rho_steps: 1 << 20,
+            dlog_bits: 40,
+        }
+    }
+}
+
+/// Audit findings, each one a weakness.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Finding {
+    /// `p` is not prime.
+    CompositeP,
+    /// `q` is not prime.
+    CompositeQ,
+    /// `q` doesn't divide `p - 1`.
+    QNotDividing,
+    /// `g` is not in `(1, p)`.
+    TrivialGenerator,
+    /// `p - 1` is fully factored and its prime factors have at most `bits`
+    /// bits.
+    SmoothGroup { bits: u64 },
+    /// The order of `g` is not `q`.
+    WrongOrder { order: BigUint },
+    /// The prime factors of the order of `g` have at most `bits` bits.
+    WeakSubgroup { bits: u64 },
+    /// `y` is not in the subgroup spanned by `g`.
+    BadPublic,
+    /// The secret has been recovered.
+    SecretRecovered,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Finding::CompositeP => write!(f, "p is not prime"),
+            Finding::CompositeQ => write!(f, "q is not prime"),
+            Finding::QNotDividing => write!(f, "q doesn't divide p - 1"),
+            Finding::TrivialGenerator => write!(f, "g is not in (1, p)"),
+            Finding::SmoothGroup { bits } => {
+                write!(f, "p - 1 is smooth, factors up to {bits} bits")
+            }
+            Finding::WrongOrder { order } => write!(f, "g has order {order}, not q"),
+            Finding::WeakSubgroup { bits } => {
+                write!(f, "order of g with factors up to {bits} bits")
+            }
+            Finding::BadPublic => write!(f, "y is not in the subgroup of g"),
+            Finding::SecretRecovered => write!(f, "secret recovered"),
+        }
+    }
+}
+
+/// Audit results.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditReport {
+    pub p_prime: bool,
+    pub q_prime: Option<bool>,
+    /// Factorization of `p - 1`, if `p` is prime.
+    pub p_minus_one: Option<Factorization>,
+    /// Order of `g` and its prime factors, if they could be determined.
+    pub order: Option<(BigUint, Vec<(BigUint, u32)>)>,
+    /// Secret modulo the order of `g`.
+    pub secret: Option<BigUint>,
+    pub findings: Vec<Finding>,
+}
+
+impl AuditReport {
+    pub fn is_breakable(&self) -> bool {
+        self.secret.is_some()
+    }
+}
+
+impl fmt::Display for AuditReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let factors = |primes: &[(BigUint, u32)]| {
+            primes
+                .iter()
+                .map(|(r, k)| match k {
+                    1 => r.to_string(),
+                    _ => format!("{r}^{k}"),
+                })
+                .collect::<Vec<_>>()
+                .join("·")
+        };
+        writeln!(f, "p prime: {}", self.p_prime)?;
+        if let Some(q_prime) = self.q_prime {
+            writeln!(f, "q prime: {q_prime}")?;
+        }
+        if let Some(p_minus_one) = &self.p_minus_one {
+            write!(f, "p - 1 = {}", factors(&p_minus_one.primes))?;
+            for c in &p_minus_one.composites {
+                write!(f, "·({c})")?;
+            }
+            writeln!(f)?;
+        }
+        match &self.order {
+            Some((order, primes)) if primes.len() > 1 || primes.iter().any(|(_, k)| *k > 1) => {
+                writeln!(f, "order of g: {order} = {}", factors(primes))?
+            }
+            Some((order, _)) => writeln!(f, "order of g: {order}")?,
+            None => writeln!(f, "order of g: unknown")?,
+        }
+        if let Some(secret) = &self.secret {
+            writeln!(f, "secret: {secret}")?;
+        }
+        for finding in &self.findings {
+            writeln!(f, "- {finding}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Run the checks on `params`, within the given `limits`.
+pub fn audit(params: &Params, limits: &Limits) -> AuditReport {
+    let Params { p, q, g, y } = params;
+    // `is_prime` takes 1 as prime, degenerate sizes are handled here
+    let mut report = AuditReport {
+        p_prime: p >= &BigUint::from(3_u8) && miller_rabin::is_prime(p),
+        q_prime: q
+            .as_ref()
+            .map(|q| q > &BigUint::one() && miller_rabin::is_prime(q)),
+        ..Default::default()
+    };
+    let findings = &mut report.findings;
+    if !report.p_prime {
+        // Z_p* order is not p - 1, nothing else makes sense
+        findings.push(Finding::CompositeP);
+        return report;
+    }
+    if report.q_prime == Some(false) {
+        findings.push(Finding::CompositeQ);
+    }
+    let p_1 = p - 1_u8;
+    let q_divides = q.as_ref().map(|q| !q.is_zero() && (&p_1 % q).is_zero());
+    if q_divides == Some(false) {
+        findings.push(Finding::QNotDividing);
+    }
+    if g <= &BigUint::one() || g >= p {
+        findings.push(Finding::TrivialGenerator);
+        return report;
+    }
+
+    // A prime q dividing p - 1 is a factor for free, the rest is usually
+    // the hard part.
+    let known = match q {
+        Some(q) if report.q_prime == Some(true) && q_divides == Some(true) => Some(q),
+        _ => None,
+    };
+    let mut rest = p_1.clone();
+    let mut k = 0;
+    if let Some(q) = known {
+        while (&rest % q).is_zero() {
+            rest /= q;
+            k += 1;
+        }
+    }
+    let mut factorization = numutil::factor(
+        &rest,
+        limits.trial_bound,
+        limits.rho_steps,
+        miller_rabin::is_prime,
+    );
+    if let Some(q) = known {
+        factorization.insert(q.clone(), k);
+    }
+    let bits = |primes: &[(BigUint, u32)]| primes.last().map_or(0, |(r, _)| r.bits());
+    let smooth = bits(&factorization.primes);
+    if factorization.is_complete() && smooth <= limits.dlog_bits {
+        findings.push(Finding::SmoothGroup { bits: smooth });
+    }
+
+    let order = order(p, g, &factorization).or_else(|| {
+        let q = known?;
+        g.modpow(q, p)
+            .is_one()
+            .then(|| (q.clone(), vec![(q.clone(), 1)]))
+    });
+    report.p_minus_one = Some(factorization);
+    let Some((order, primes)) = order else {
+        return report;
+    };
+    if q.as_ref().is_some_and(|q| q != &order) {
+        findings.push(Finding::WrongOrder {
+            order: order.clone(),
+        });
+    }
+    let weak = bits(&primes);
+    if weak <= limits.dlog_bits {
+        findings.push(Finding::WeakSubgroup { bits: weak });
+    }
+    if y.is_zero() || y >= p || !y.modpow(&order, p).is_one() {
+        findings.push(Finding::BadPublic);
+    } else if weak <= limits.dlog_bits {
+        report.secret = pohlig_hellman(p, g, y, &primes).filter(|x| &g.modpow(x, p) == y);
+        if report.secret.is_some() {
+            findings.push(Finding::SecretRecovered);
+        }
+    }
+    report.order = Some((order, primes));
+    report
+}
+
+/// Order of `g` modulo `p` and its prime factors, given the factorization of
+/// `p - 1`.
+///
+/// Unknown if an unsplit factor of `p - 1` may share a factor with the order.
+fn order(
+    p: &BigUint,
+    g: &BigUint,
+    factorization: &Factorization,
+) -> Option<(BigUint, Vec<(BigUint, u32)>)> {
+    let mut order = p - 1_u8;
+    for c in &factorization.composites {
+        let reduced = &order / c;
+        if !g.modpow(&reduced, p).is_one() {
+            return None;
+        }
+        order = reduced;
+    }
+    let mut primes = Vec::new();
+    for (r, k) in &factorization.primes {
+        let mut e = *k;
+        while e > 0 && g.modpow(&(&order / r), p).is_one() {
+            order /= r;
+            e -= 1;
+        }
+        if e > 0 {
+            primes.push((r.clone(), e));
+        }
+    }
+    Some((order, primes))
+}
+
+/// Pohlig–Hellman: discrete logarithm of `y` to the base `g` modulo `p`, with
+/// the order of `g` given by its prime factors.
+///
+/// The problem is projected onto each prime power `r^e` subgroup and there
+/// solved one base `r` digit at a time, i.e. `e` logarithms in a subgroup of
+/// order `r`. The results are combined with the CRT.
+pub fn pohlig_hellman(
+    p: &BigUint,
+    g: &BigUint,
+    y: &BigUint,
+    primes: &[(BigUint, u32)],
+) -> Option<BigUint> {
+    let order: BigUint = primes.iter().map(|(r, e)| r.pow(*e)).product();
+    let mut residues = Vec::new();
+    let mut moduli = Vec::new();
+    for (r, e) in primes {
+        let modulus = r.pow(*e);
+        let cofactor = &order / &modulus;
+        // Order r^e and order r generators
+        let gi = g.modpow(&cofactor, p);
+        let yi = y.modpow(&cofactor, p);
+        let gamma = gi.modpow(&r.pow(e - 1), p);
+        let mut x = BigUint::zero();
+        for k in 0..*e {
+            // (yi·gi^(-x))^(r^(e-1-k)) = gamma^(k-th digit)
+            let h = (&yi * gi.modpow(&(&modulus - &x), p) % p).modpow(&r.pow(e - 1 - k), p);
+            x += dlog(p, &gamma, &h, r)? * r.pow(k);
+        }
+        residues.push(x);
+        moduli.push(modulus);
+    }
+    crt(&residues, &moduli)
+}
+
+// Logarithm in a subgroup of prime order `r`.
+fn dlog(p: &BigUint, g: &BigUint, h: &BigUint, r: &BigUint) -> Option<BigUint> {
+    if h.is_one() {
+        return Some(BigUint::zero());
+    }
+    let hint = Hint {
+        order: Some(r.to_u64()?),
+        ..Default::default()
+    };
+    solve_dlog_auto(p.clone(), g.clone(), h.clone(), hint)
+        .ok()?
+        .0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dh_groups::Named;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn standard_group_is_sound() {
+        let group = Named::Ffdhe2048.group();
+        let (_, y) = group.keypair(&mut StdRng::seed_from_u64(0));
+        let params = Params {
+            p: group.p,
+            q: Some(group.q.clone()),
+            g: group.g,
+            y,
+        };
+        let report = audit(&params, &Limits::default());
+        assert_eq!(report.findings, []);
+        assert_eq!(report.order.as_ref().unwrap().0, group.q);
+        assert!(!report.is_breakable());
+    }
+
+    #[test]
+    fn small_subgroup_is_broken() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (group, _) = dh_mitm::weak_group(&mut rng, 32, 1 << 16, 48);
+        let (x, y) = group.keypair(&mut rng);
+        let params = Params {
+            p: group.p,
+            q: Some(group.q),
+            g: group.g,
+            y,
+        };
+        let report = audit(&params, &Limits::default());
+        assert!(report
+            .findings
+            .contains(&Finding::WeakSubgroup { bits: 32 }));
+        assert_eq!(report.secret, Some(x));
+    }
+
+    #[test]
+    fn smooth_order_generator() {
+        // Generator of the whole smooth group, despite q.
+        let mut rng = StdRng::seed_from_u64(1);
+        let (group, _) = dh_mitm::weak_group(&mut rng, 24, 1 << 12, 64);
+        let g = BigUint::from(2_u8);
+        let x = BigUint::from(123_456_789_u64);
+        let params = Params {
+            y: g.modpow(&x, &group.p),
+            p: group.p,
+            q: Some(group.q.clone()),
+            g,
+        };
+        let report = audit(&params, &Limits::default());
+        let (order, _) = report.order.clone().unwrap();
+        assert!(report.findings.contains(&Finding::WrongOrder { order }));
+        assert!(report.findings.contains(&Finding::SecretRecovered));
+        assert_eq!(report.secret, Some(x));
+    }
+
+    #[test]
+    fn degenerate_parameters() {
+        let params = |p: u8, q: u8| Params {
+            p: p.into(),
+            q: Some(q.into()),
+            g: 2_u8.into(),
+            y: 3_u8.into(),
+        };
+        let report = audit(&params(1, 11), &Limits::default());
+        assert_eq!(report.findings, [Finding::CompositeP]);
+        let report = audit(&params(23, 0), &Limits::default());
+        assert!(report.findings.contains(&Finding::CompositeQ));
+        assert!(report.findings.contains(&Finding::QNotDividing));
+    }
+}
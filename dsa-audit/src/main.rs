@@ -0,0 +1,54 @@
+use dh_groups::Named;
+use dsa_audit::{audit, Limits, Params};
+use num_bigint::BigUint;
+use rand::rngs::OsRng;
+use std::time::Instant;
+
+fn run(name: &str, params: &Params) {
+    let start = Instant::now();
+    let report = audit(params, &Limits::default());
+    println!("{name} ({:?})", start.elapsed());
+    println!("{report}");
+}
+
+fn main() {
+    let group = Named::Ffdhe2048.group();
+    let (_, y) = group.keypair(&mut OsRng);
+    let params = Params {
+        p: group.p.clone(),
+        q: Some(group.q.clone()),
+        g: group.g.clone(),
+        y,
+    };
+    run("FFDHE 2048", &params);
+
+    // Order 2 generator
+    let g = &group.p - 1_u8;
+    let params = Params {
+        y: g.clone(),
+        g,
+        ..params
+    };
+    run("FFDHE 2048, g = p - 1", &params);
+
+    // 40 bits subgroup of a 256 bits prime
+    let (group, _) = dh_mitm::weak_group(&mut OsRng, 40, 1 << 16, 200);
+    let (x, y) = group.keypair(&mut OsRng);
+    println!("Secret: {x}");
+    let params = Params {
+        p: group.p,
+        q: Some(group.q),
+        g: group.g,
+        y,
+    };
+    run("Small subgroup", &params);
+
+    // Elgamal without q, over the whole smooth group
+    let params = Params {
+        q: None,
+        g: BigUint::from(3_u8),
+        y: BigUint::from(3_u8).modpow(&x, &params.p),
+        ..params
+    };
+    run("Smooth p - 1", &params);
+}
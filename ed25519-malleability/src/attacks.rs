@@ -0,0 +1,154 @@
+//! Signatures splitting the verifiers.
+
+use crate::{challenge, Rules, Verifier};
+use curve25519_dalek::{
+    constants::{ED25519_BASEPOINT_TABLE as B, EIGHT_TORSION},
+    traits::Identity,
+    EdwardsPoint, Scalar,
+};
+use ed25519_dalek::{hazmat::ExpandedSecretKey, Signer, SigningKey};
+use rand::{CryptoRng, RngCore};
+
+/// Verifiers compared by the demo.
+pub const VERIFIERS: [(&str, Verifier); 6] = [
+    (
+        "cofactorless",
+        Verifier::Reference(Rules {
+            cofactored: false,
+            canonical_s: true,
+            reject_small_order: false,
+        }),
+    ),
+    (
+        "cofactorless, S mod L",
+        Verifier::Reference(Rules {
+            cofactored: false,
+            canonical_s: false,
+            reject_small_order: false,
+        }),
+    ),
+    (
+        "cofactored",
+        Verifier::Reference(Rules {
+            cofactored: true,
+            canonical_s: true,
+            reject_small_order: false,
+        }),
+    ),
+    ("ed25519-dalek", Verifier::Dalek),
+    ("ed25519-dalek strict", Verifier::DalekStrict),
+    ("ed25519-zebra", Verifier::Zebra),
+];
+
+/// Group order `L`, little endian.
+const L: [u8; 32] = [
+    0xed, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9, 0xde, 0x14,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10,
+];
+
+/// Signed message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Case {
+    pub public: [u8; 32],
+    pub msg: Vec<u8>,
+    pub sig: [u8; 64],
+}
+
+impl Case {
+    pub fn accepted_by(&self, verifier: &Verifier) -> bool {
+        verifier.verify(&self.public, &self.msg, &self.sig)
+    }
+}
+
+fn case(public: [u8; 32], msg: &[u8], r: &[u8; 32], s: &[u8; 32]) -> Case {
+    let mut sig = [0; 64];
+    sig[..32].copy_from_slice(r);
+    sig[32..].copy_from_slice(s);
+    Case {
+        public,
+        msg: msg.to_vec(),
+        sig,
+    }
+}
+
+/// Honest signature of `msg`.
+pub fn honest(key: &SigningKey, msg: &[u8]) -> Case {
+    Case {
+        public: key.verifying_key().to_bytes(),
+        msg: msg.to_vec(),
+        sig: key.sign(msg).to_bytes(),
+    }
+}
+
+/// The same signature with `S + L` in place of `S`.
+///
+/// Fits in the 253 bits allowed by the lax verifiers unless `S ≥ 2^253 - L`,
+/// which happens with negligible probability.
+pub fn non_canonical_s(case: &Case) -> Case {
+    let mut sig = case.sig;
+    let mut carry = 0;
+    for (b, l) in sig[32..].iter_mut().zip(L) {
+        let sum = *b as u16 + l as u16 + carry;
+        *b = sum as u8;
+        carry = sum >> 8;
+    }
+    Case {
+        sig,
+        ..case.clone()
+    }
+}
+
+/// Signature of `msg` whose nonce commitment has a small order component,
+/// `R' = r·B + T` and `S = r + H(R' || A || M)·a`.
+///
+/// Cofactored verifiers clear `T` and accept, cofactorless ones reject. Any
+/// signer can produce such signatures, e.g. to split a network.
+pub fn torsion_r<R: RngCore + CryptoRng>(rng: &mut R, key: &SigningKey, msg: &[u8]) -> Case {
+    let a = ExpandedSecretKey::from(&key.to_bytes()).scalar;
+    let public = key.verifying_key().to_bytes();
+    let r = Scalar::random(rng);
+    let big_r = (&r * B + EIGHT_TORSION[1]).compress().to_bytes();
+    let s = r + challenge(&big_r, &public, msg) * a;
+    case(public, msg, &big_r, &s.to_bytes())
+}
+
+/// Signature of `msg` under the identity public key, with `R` the identity
+/// and `S = 0`.
+///
+/// Valid for any message unless the verifier rejects small order points.
+pub fn small_order_key(msg: &[u8]) -> Case {
+    let identity = EdwardsPoint::identity().compress().to_bytes();
+    case(identity, msg, &identity, &[0; 32])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    fn verdicts(case: &Case) -> [bool; 6] {
+        VERIFIERS.map(|(_, v)| case.accepted_by(&v))
+    }
+
+    #[test]
+    fn malleable_s() {
+        let key = SigningKey::generate(&mut OsRng);
+        let case = non_canonical_s(&honest(&key, b"msg"));
+        assert_eq!(verdicts(&case), [false, true, false, false, false, false]);
+    }
+
+    #[test]
+    fn cofactored_only() {
+        let key = SigningKey::generate(&mut OsRng);
+        let case = torsion_r(&mut OsRng, &key, b"msg");
+        assert_eq!(verdicts(&case), [false, false, true, false, false, true]);
+    }
+
+    #[test]
+    fn universal_signature() {
+        for msg in [b"a".as_slice(), b"b"] {
+            let case = small_order_key(msg);
+            assert_eq!(verdicts(&case), [true, true, true, true, false, true]);
+        }
+    }
+}
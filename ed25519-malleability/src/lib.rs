@@ -0,0 +1,139 @@
+//! Ed25519 malleability and verification inconsistencies.
+//!
+//! RFC 8032 leaves the verifier some freedom, and implementations use it
+//! differently. Given `(R, S)`, the public key `A` and `h = H(R || A || M)`:
+//! - *cofactorless* verifiers check `S·B = R + h·A`, *cofactored* ones check
+//!   `8·S·B = 8·R + 8·h·A`. A small order component added to `R` (or `A`) is
+//!   cleared by the cofactor, thus the two disagree;
+//! - some verifiers reduce `S` modulo the group order `L` instead of requiring
+//!   `S < L`, then `(R, S + L)` is a second valid signature for the same
+//!   message, different in the bytes;
+//! - small order public keys are accepted by some verifiers and rejected by
+//!   others. With `A` of small order and a small order `R` (e.g. both the
+//!   identity) `S = 0` is valid for any message.
+//!
+//! The opposite split, with a cofactorless verifier accepting a signature
+//! rejected by a cofactored one, is impossible under the same encoding rules
+//! as the cofactored equation follows from the cofactorless one. It shows up
+//! across implementations though, e.g. `(R, S + L)` is accepted by a lax
+//! cofactorless verifier and rejected by the cofactored `ed25519-zebra`.
+//!
+//! Malleable signatures break whatever identifies data by its signed bytes
+//! (e.g. transaction identifiers), while inconsistent verifiers break
+//! consensus: nodes running different implementations accept different
+//! blocks. The [`attacks`] module builds the signatures and the demo runs them
+//! through the reference verifier and through `ed25519-dalek` and
+//! `ed25519-zebra` (ZIP-215 rules).
+//!
+//! See the `ed25519-dalek-secret-recovery` crate for a misuse of the signing
+//! side of the same libraries.
+
+use curve25519_dalek::{edwards::CompressedEdwardsY, traits::IsIdentity, EdwardsPoint, Scalar};
+use ed25519_dalek::{Signature, Verifier as _};
+use sha2::{Digest, Sha512};
+
+pub mod attacks;
+
+/// Reference verifier rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    /// Check the equation multiplied by the cofactor.
+    pub cofactored: bool,
+    /// Require `S < L`, otherwise `S` is reduced (if less than `2^253`).
+    pub canonical_s: bool,
+    /// Reject small order `A` and `R`.
+    pub reject_small_order: bool,
+}
+
+/// Ed25519 verifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verifier {
+    /// Reference implementation with the given rules.
+    Reference(Rules),
+    /// `ed25519-dalek` v2 `verify`: cofactorless, canonical `S`.
+    Dalek,
+    /// `ed25519-dalek` v2 `verify_strict`: as [`Verifier::Dalek`], also
+    /// rejecting small order `A` and `R`.
+    DalekStrict,
+    /// `ed25519-zebra`: cofactored, canonical `S`, small order points
+    /// accepted.
+    Zebra,
+}
+
+/// `h = H(R || A || M)`.
+pub fn challenge(r: &[u8], public: &[u8; 32], msg: &[u8]) -> Scalar {
+    let h = Sha512::new()
+        .chain_update(r)
+        .chain_update(public)
+        .chain_update(msg);
+    Scalar::from_hash(h)
+}
+
+fn verify_reference(rules: &Rules, public: &[u8; 32], msg: &[u8], sig: &[u8; 64]) -> bool {
+    let (r_bytes, s_bytes): ([u8; 32], [u8; 32]) =
+        (sig[..32].try_into().unwrap(), sig[32..].try_into().unwrap());
+    let (Some(a), Some(r)) = (
+        CompressedEdwardsY(*public).decompress(),
+        CompressedEdwardsY(r_bytes).decompress(),
+    ) else {
+        return false;
+    };
+    let s = if rules.canonical_s {
+        match Option::from(Scalar::from_canonical_bytes(s_bytes)) {
+            Some(s) => s,
+            None => return false,
+        }
+    } else if s_bytes[31] & 0xe0 == 0 {
+        Scalar::from_bytes_mod_order(s_bytes)
+    } else {
+        return false;
+    };
+    if rules.reject_small_order && (a.is_small_order() || r.is_small_order()) {
+        return false;
+    }
+    let h = challenge(&r_bytes, public, msg);
+    // S·B - h·A - R
+    let check = EdwardsPoint::vartime_double_scalar_mul_basepoint(&-h, &a, &s) - r;
+    if rules.cofactored {
+        check.mul_by_cofactor().is_identity()
+    } else {
+        check.is_identity()
+    }
+}
+
+impl Verifier {
+    /// Verify the signature `sig` of `msg` under the `public` key.
+    pub fn verify(&self, public: &[u8; 32], msg: &[u8], sig: &[u8; 64]) -> bool {
+        let signature = Signature::from_bytes(sig);
+        match self {
+            Verifier::Reference(rules) => verify_reference(rules, public, msg, sig),
+            Verifier::Dalek => ed25519_dalek::VerifyingKey::from_bytes(public)
+                .map(|key| key.verify(msg, &signature).is_ok())
+                .unwrap_or(false),
+            Verifier::DalekStrict => ed25519_dalek::VerifyingKey::from_bytes(public)
+                .map(|key| key.verify_strict(msg, &signature).is_ok())
+                .unwrap_or(false),
+            Verifier::Zebra => ed25519_zebra::VerificationKey::try_from(*public)
+                .map(|key| key.verify(&signature, msg).is_ok())
+                .unwrap_or(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn honest_signatures_are_accepted() {
+        let key = SigningKey::generate(&mut OsRng);
+        let sig = key.sign(b"msg").to_bytes();
+        let public = key.verifying_key().to_bytes();
+        for verifier in attacks::VERIFIERS.map(|(_, v)| v) {
+            assert!(verifier.verify(&public, b"msg", &sig), "{verifier:?}");
+            assert!(!verifier.verify(&public, b"other", &sig), "{verifier:?}");
+        }
+    }
+}
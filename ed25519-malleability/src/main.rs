@@ -0,0 +1,33 @@
+use ed25519_dalek::SigningKey;
+use ed25519_malleability::attacks::{self, VERIFIERS};
+use rand::rngs::OsRng;
+
+fn main() {
+    let key = SigningKey::generate(&mut OsRng);
+    let msg = b"Transfer 10 coins to Bob";
+    let honest = attacks::honest(&key, msg);
+    let cases = [
+        ("honest", honest.clone()),
+        ("S + L", attacks::non_canonical_s(&honest)),
+        ("R + T", attacks::torsion_r(&mut OsRng, &key, msg)),
+        ("A = R = O, S = 0", attacks::small_order_key(msg)),
+    ];
+
+    print!("{:<22}", "");
+    for (name, _) in &cases {
+        print!("{name:>18}");
+    }
+    println!();
+    for (name, verifier) in VERIFIERS {
+        print!("{name:<22}");
+        for (_, case) in &cases {
+            let verdict = if case.accepted_by(&verifier) {
+                "accept"
+            } else {
+                "reject"
+            };
+            print!("{verdict:>18}");
+        }
+        println!();
+    }
+}
@@ -0,0 +1,252 @@
+//! Known plaintext (crib) attack, in the style of the Turing-Welchman bombe.
+//!
+//! Given a guessed plaintext fragment (the crib) at some ciphertext offset,
+//! each crib letter `p` encrypted to `c` at step `i` gives the relation
+//! `S(c) = E_i(S(p))`, where `S` is the unknown plugboard and `E_i` the
+//! scrambler (rotors and reflector) permutation at step `i`. The scrambler
+//! doesn't depend on the plugboard, thus for every rotor order and start
+//! position:
+//! - a hypothesis `S(L) = x` is made for the most connected crib letter `L`;
+//! - the relations (the "menu") propagate it to the plugs of the other crib
+//!   letters, a contradiction (a letter plugged to two different ones)
+//!   discards the hypothesis;
+//! - the survivors ("stops") are checked by running the machine over the crib.
+//!
+//! The settings are reduced with respect to the real key: the reflector and the
+//! ring settings are assumed known, only the rotor order, the start position
+//! and the plugboard are recovered. Plugs of letters not in the crib are not
+//! determined and are left unplugged. Rotor configurations are searched in
+//! parallel.
+
+use crate::{Enigma, Plugboard, Settings};
+use rayon::prelude::*;
+
+const UNKNOWN: u8 = u8::MAX;
+
+// Rotors start positions.
+const POSITIONS: usize = 26 * 26 * 26;
+
+/// Ciphertext offsets where the crib may lie, i.e. without any crib letter
+/// encrypted to itself.
+pub fn crib_offsets(ct: &[u8], crib: &[u8]) -> Vec<usize> {
+    (0..(ct.len() + 1).saturating_sub(crib.len()))
+        .filter(|&i| ct[i..].iter().zip(crib).all(|(c, p)| c != p))
+        .collect()
+}
+
+// Crib relations: edges of the menu graph.
+struct Menu {
+    // Letters linked to each letter, with the linking crib step.
+    links: [Vec<(usize, u8)>; 26],
+    // Most connected letter.
+    start: u8,
+}
+
+impl Menu {
+    fn new(crib: &[u8], ct: &[u8]) -> Self {
+        let mut links: [Vec<_>; 26] = Default::default();
+        for (i, (&p, &c)) in crib.iter().zip(ct).enumerate() {
+            links[p as usize].push((i, c));
+            links[c as usize].push((i, p));
+        }
+        let start = (0..26)
+            .max_by_key(|&x| links[x as usize].len())
+            .unwrap_or(0);
+        Menu { links, start }
+    }
+
+    // Plugs implied by `S(start) = guess`, `None` on contradiction.
+    fn deduce(&self, scramblers: &mut Scramblers, guess: u8) -> Option<[u8; 26]> {
+        let mut plugs = [UNKNOWN; 26];
+        let mut queue = Vec::with_capacity(26);
+        let assign = |plugs: &mut [u8; 26], queue: &mut Vec<u8>, a: u8, b: u8| {
+            let (i, j) = (a as usize, b as usize);
+            if plugs[i] == b {
+                return true;
+            }
+            if plugs[i] != UNKNOWN || plugs[j] != UNKNOWN {
+                return false;
+            }
+            plugs[i] = b;
+            plugs[j] = a;
+            queue.push(a);
+            if a != b {
+                queue.push(b);
+            }
+            true
+        };
+        assign(&mut plugs, &mut queue, self.start, guess);
+        while let Some(a) = queue.pop() {
+            let x = plugs[a as usize];
+            for &(step, other) in &self.links[a as usize] {
+                if !assign(&mut plugs, &mut queue, other, scramblers.get(step, x)) {
+                    return None;
+                }
+            }
+        }
+        Some(plugs)
+    }
+}
+
+// Scrambler permutations at each crib step, computed on demand: most
+// hypotheses are discarded after a few deductions.
+struct Scramblers {
+    enigma: Enigma,
+    positions: Vec<[u8; 3]>,
+    tables: Vec<[u8; 26]>,
+}
+
+impl Scramblers {
+    // `enigma` is positioned just before the crib.
+    fn new(mut enigma: Enigma, len: usize) -> Self {
+        let positions = (0..len)
+            .map(|_| {
+                enigma.step();
+                enigma.positions()
+            })
+            .collect();
+        Scramblers {
+            enigma,
+            positions,
+            tables: vec![[UNKNOWN; 26]; len],
+        }
+    }
+
+    fn get(&mut self, step: usize, x: u8) -> u8 {
+        let table = &mut self.tables[step];
+        if table[x as usize] == UNKNOWN {
+            // Involution, both entries are known.
+            self.enigma.set_positions(self.positions[step]);
+            let y = self.enigma.scramble(x);
+            table[x as usize] = y;
+            table[y as usize] = x;
+        }
+        table[x as usize]
+    }
+}
+
+/// Crib attack against the ciphertext `ct` containing the `crib` at `offset`.
+///
+/// Every order of three distinct `rotors` (indices in [`crate::ROTORS`]) and
+/// every start position is tried, with the given `reflector` and `rings`.
+/// Returns the settings which encrypt the crib correctly.
+pub fn bombe(
+    ct: &[u8],
+    crib: &[u8],
+    offset: usize,
+    rotors: &[usize],
+    reflector: usize,
+    rings: [u8; 3],
+) -> Vec<Settings> {
+    let ct_crib = &ct[offset..offset + crib.len()];
+    if ct_crib.iter().zip(crib).any(|(c, p)| c == p) {
+        return Vec::new();
+    }
+    let menu = Menu::new(crib, ct_crib);
+    let orders: Vec<[usize; 3]> = rotors
+        .iter()
+        .flat_map(|&a| {
+            rotors
+                .iter()
+                .flat_map(move |&b| rotors.iter().map(move |&c| [a, b, c]))
+        })
+        .filter(|[a, b, c]| a != b && b != c && a != c)
+        .collect();
+    let bases: Vec<_> = orders
+        .iter()
+        .map(|&rotors| {
+            Enigma::new(&Settings {
+                rotors,
+                reflector,
+                rings,
+                positions: [0; 3],
+                plugboard: Plugboard::default(),
+            })
+        })
+        .collect();
+
+    (0..orders.len() * POSITIONS)
+        .into_par_iter()
+        .flat_map_iter(|i| {
+            let (order, p) = (i / POSITIONS, i % POSITIONS);
+            let settings = Settings {
+                rotors: orders[order],
+                reflector,
+                rings,
+                positions: [(p / 676) as u8, (p / 26 % 26) as u8, (p % 26) as u8],
+                plugboard: Plugboard::default(),
+            };
+            stops(&settings, &bases[order], &menu, crib, ct_crib, offset)
+        })
+        .collect()
+}
+
+// Consistent plugboards for the given rotors settings, `base` is the
+// machine with the same rotors.
+fn stops(
+    settings: &Settings,
+    base: &Enigma,
+    menu: &Menu,
+    crib: &[u8],
+    ct_crib: &[u8],
+    offset: usize,
+) -> Vec<Settings> {
+    let mut enigma = base.clone();
+    enigma.set_positions(settings.positions);
+    (0..offset).for_each(|_| enigma.step());
+    let mut scramblers = Scramblers::new(enigma.clone(), crib.len());
+
+    (0..26)
+        .filter_map(|guess| menu.deduce(&mut scramblers, guess))
+        .filter_map(|plugs| {
+            let pairs: Vec<_> = (0..26)
+                .filter(|&a| plugs[a as usize] != UNKNOWN && plugs[a as usize] > a)
+                .map(|a| (a, plugs[a as usize]))
+                .collect();
+            let plugboard = Plugboard::from_pairs(&pairs)?;
+            let mut enigma = Enigma {
+                plugboard,
+                ..enigma.clone()
+            };
+            (enigma.process(crib) == ct_crib).then_some(Settings {
+                plugboard,
+                ..*settings
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::letters;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const MESSAGE: &str = "Wetterbericht fuer die Biskaya heute morgen, leichter Regen und \
+        Wind aus Nordwest, Sicht gut";
+
+    #[test]
+    fn crib_offsets_exclude_self_encryptions() {
+        let ct = letters("QWERTYWETTER");
+        assert_eq!(crib_offsets(&ct, &letters("WETTER")), [0, 4]);
+    }
+
+    #[test]
+    fn bombe_recovers_settings() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut settings = Settings::random(6, &mut rng);
+        // Reduced key: rotors among I, II and III, known rings.
+        settings.rotors = [2, 0, 1];
+        let pt = letters(MESSAGE);
+        let ct = Enigma::new(&settings).process(&pt);
+
+        let crib = letters("WETTERBERICHTFUERDIEBISKAYA");
+        assert!(crib_offsets(&ct, &crib).contains(&0));
+        let found = bombe(&ct, &crib, 0, &[0, 1, 2], 0, settings.rings);
+        let found = found
+            .iter()
+            .find(|s| s.rotors == settings.rotors && s.positions == settings.positions)
+            .expect("Right settings found");
+        assert_eq!(Enigma::new(found).process(&ct), pt);
+    }
+}
@@ -0,0 +1,288 @@
+//! Enigma I machine simulator.
+//!
+//! Each letter goes through the plugboard, three rotors from right to left,
+//! the reflector, the rotors back from left to right and the plugboard again.
+//! The right rotor steps before each letter, the middle one when the right
+//! one leaves its notch position and the left one when the middle one does
+//! (with the middle rotor "double stepping" along with it).
+//!
+//! The reflector makes every machine state an involution without fixed
+//! points: encryption and decryption are the same operation and no letter is
+//! ever encrypted to itself, a weakness exploited by the [`attacks`].
+//!
+//! Letters are handled as numbers in `0..26` (see [`letters`] and
+//! [`to_string`]).
+
+use rand::{seq::SliceRandom, Rng};
+
+pub mod attacks;
+
+/// Rotors wirings and notch letters, from I to V.
+pub const ROTORS: [(&str, u8); 5] = [
+    ("EKMFLGDQVZNTOWYHXUSPAIBRCJ", b'Q'),
+    ("AJDKSIROUXBLHWTMCQGZNPYFVE", b'E'),
+    ("BDFHJLCPRTXVZNYEIWGAKMUSQO", b'V'),
+    ("ESOVPZJAYQUIRHXLNFTGKDCMWB", b'J'),
+    ("VZBRGITYUPSDNHLXAWMJQOFECK", b'Z'),
+];
+
+/// Reflectors wirings, B and C.
+pub const REFLECTORS: [&str; 2] = ["YRUHQSLDPXNGOKMIEBFZCWVJAT", "FVPJIAOYEDRZXWGCTKUQSBNMHL"];
+
+/// Letters to numbers in `0..26`, ignoring case and dropping any non letter.
+pub fn letters(text: &str) -> Vec<u8> {
+    text.bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .map(|b| b.to_ascii_uppercase() - b'A')
+        .collect()
+}
+
+/// Numbers in `0..26` to uppercase letters.
+pub fn to_string(letters: &[u8]) -> String {
+    letters.iter().map(|&x| (b'A' + x) as char).collect()
+}
+
+fn wiring(s: &str) -> [u8; 26] {
+    let mut out = [0; 26];
+    out.iter_mut().zip(letters(s)).for_each(|(o, x)| *o = x);
+    out
+}
+
+/// Plugboard, an involution of the letters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Plugboard([u8; 26]);
+
+impl Default for Plugboard {
+    fn default() -> Self {
+        Plugboard(std::array::from_fn(|i| i as u8))
+    }
+}
+
+impl Plugboard {
+    /// Plugboard from the swapped pairs, `None` if a letter is used twice.
+    pub fn from_pairs(pairs: &[(u8, u8)]) -> Option<Self> {
+        let mut board = Self::default();
+        for &(a, b) in pairs {
+            let (a, b) = (a as usize, b as usize);
+            if a == b || board.0[a] != a as u8 || board.0[b] != b as u8 {
+                return None;
+            }
+            board.0.swap(a, b);
+        }
+        Some(board)
+    }
+
+    /// Plugboard from space separated letters pairs, e.g. `"AB CD"`.
+    pub fn parse(pairs: &str) -> Option<Self> {
+        let pairs: Option<Vec<_>> = pairs
+            .split_whitespace()
+            .map(|p| match letters(p)[..] {
+                [a, b] if p.len() == 2 => Some((a, b)),
+                _ => None,
+            })
+            .collect();
+        Self::from_pairs(&pairs?)
+    }
+
+    /// Random plugboard with `n` pairs (at most 13).
+    pub fn random<R: Rng + ?Sized>(n: usize, rng: &mut R) -> Self {
+        let mut letters: Vec<u8> = (0..26).collect();
+        letters.shuffle(rng);
+        let pairs: Vec<_> = letters.chunks(2).take(n).map(|p| (p[0], p[1])).collect();
+        Self::from_pairs(&pairs).expect("Distinct letters")
+    }
+
+    /// Swapped pairs, sorted.
+    pub fn pairs(&self) -> Vec<(u8, u8)> {
+        (0..26)
+            .filter(|&a| self.0[a as usize] > a)
+            .map(|a| (a, self.0[a as usize]))
+            .collect()
+    }
+
+    pub fn swap(&self, x: u8) -> u8 {
+        self.0[x as usize]
+    }
+}
+
+/// Machine settings (the daily key and the message key).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Settings {
+    /// Rotors, left to right, as indices in [`ROTORS`].
+    pub rotors: [usize; 3],
+    /// Reflector, index in [`REFLECTORS`].
+    pub reflector: usize,
+    /// Ring settings, left to right.
+    pub rings: [u8; 3],
+    /// Initial rotors positions, left to right.
+    pub positions: [u8; 3],
+    pub plugboard: Plugboard,
+}
+
+impl Settings {
+    /// Random settings using reflector B and `plugs` plugboard pairs.
+    pub fn random<R: Rng + ?Sized>(plugs: usize, rng: &mut R) -> Self {
+        let mut rotors = [0, 1, 2, 3, 4];
+        rotors.shuffle(rng);
+        Settings {
+            rotors: [rotors[0], rotors[1], rotors[2]],
+            reflector: 0,
+            rings: std::array::from_fn(|_| rng.gen_range(0..26)),
+            positions: std::array::from_fn(|_| rng.gen_range(0..26)),
+            plugboard: Plugboard::random(plugs, rng),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Rotor {
+    forward: [u8; 26],
+    backward: [u8; 26],
+    notch: u8,
+    ring: u8,
+    position: u8,
+}
+
+impl Rotor {
+    fn new(index: usize, ring: u8, position: u8) -> Self {
+        let (wires, notch) = ROTORS[index];
+        let forward = wiring(wires);
+        let mut backward = [0; 26];
+        forward
+            .iter()
+            .enumerate()
+            .for_each(|(i, &o)| backward[o as usize] = i as u8);
+        Rotor {
+            forward,
+            backward,
+            notch: notch - b'A',
+            ring,
+            position,
+        }
+    }
+
+    fn map(&self, wires: &[u8; 26], x: u8) -> u8 {
+        let shift = (26 + self.position - self.ring) % 26;
+        (wires[((x + shift) % 26) as usize] + 26 - shift) % 26
+    }
+
+    fn step(&mut self) {
+        self.position = (self.position + 1) % 26;
+    }
+}
+
+/// Enigma machine.
+#[derive(Debug, Clone)]
+pub struct Enigma {
+    // Left to right.
+    rotors: [Rotor; 3],
+    reflector: [u8; 26],
+    plugboard: Plugboard,
+}
+
+impl Enigma {
+    pub fn new(settings: &Settings) -> Self {
+        let rotor =
+            |i: usize| Rotor::new(settings.rotors[i], settings.rings[i], settings.positions[i]);
+        Enigma {
+            rotors: [rotor(0), rotor(1), rotor(2)],
+            reflector: wiring(REFLECTORS[settings.reflector]),
+            plugboard: settings.plugboard,
+        }
+    }
+
+    /// Current rotors positions, left to right.
+    pub fn positions(&self) -> [u8; 3] {
+        self.rotors.map(|r| r.position)
+    }
+
+    pub fn set_positions(&mut self, positions: [u8; 3]) {
+        self.rotors
+            .iter_mut()
+            .zip(positions)
+            .for_each(|(r, p)| r.position = p);
+    }
+
+    /// Advance the rotors, as done before each letter.
+    pub fn step(&mut self) {
+        let [left, middle, right] = &mut self.rotors;
+        if middle.position == middle.notch {
+            middle.step();
+            left.step();
+        } else if right.position == right.notch {
+            middle.step();
+        }
+        right.step();
+    }
+
+    /// Rotors and reflector permutation at the current position, i.e. the
+    /// machine without plugboard and stepping.
+    pub fn scramble(&self, x: u8) -> u8 {
+        let x = self
+            .rotors
+            .iter()
+            .rev()
+            .fold(x, |x, r| r.map(&r.forward, x));
+        let x = self.reflector[x as usize];
+        self.rotors.iter().fold(x, |x, r| r.map(&r.backward, x))
+    }
+
+    /// Encrypt (or decrypt) a single letter.
+    pub fn press(&mut self, x: u8) -> u8 {
+        self.step();
+        let x = self.scramble(self.plugboard.swap(x));
+        self.plugboard.swap(x)
+    }
+
+    /// Encrypt (or decrypt) a message.
+    pub fn process(&mut self, text: &[u8]) -> Vec<u8> {
+        text.iter().map(|&x| self.press(x)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(rotors: [usize; 3], rings: &str, positions: &str, plugs: &str) -> Settings {
+        let [a, b, c] = letters(rings)[..] else {
+            unreachable!()
+        };
+        let [x, y, z] = letters(positions)[..] else {
+            unreachable!()
+        };
+        Settings {
+            rotors,
+            reflector: 0,
+            rings: [a, b, c],
+            positions: [x, y, z],
+            plugboard: Plugboard::parse(plugs).unwrap(),
+        }
+    }
+
+    #[test]
+    fn double_stepping() {
+        // ADU, ADV, AEW, BFX.
+        let mut enigma = Enigma::new(&settings([0, 1, 2], "AAA", "ADU", ""));
+        enigma.process(&letters("AAA"));
+        assert_eq!(to_string(&enigma.positions()), "BFX");
+    }
+
+    #[test]
+    fn process_is_an_involution() {
+        let settings = settings([3, 1, 4], "XGM", "QEV", "AZ BY CX DW EV");
+        let pt = letters("Attack at dawn, the weather today is clear");
+        let ct = Enigma::new(&settings).process(&pt);
+        assert!(ct.iter().zip(&pt).all(|(c, p)| c != p));
+        assert_eq!(Enigma::new(&settings).process(&ct), pt);
+    }
+
+    #[test]
+    fn plugboard_pairs() {
+        let board = Plugboard::parse("QA zb").unwrap();
+        assert_eq!(board.pairs(), [(0, 16), (1, 25)]);
+        assert_eq!(Plugboard::parse("AB BC"), None);
+        assert_eq!(Plugboard::parse("AA"), None);
+        assert_eq!(Plugboard::parse("ABC"), None);
+    }
+}
@@ -0,0 +1,40 @@
+use enigma::{
+    attacks::{bombe, crib_offsets},
+    letters, to_string, Enigma, Settings,
+};
+use std::time::Instant;
+
+const MESSAGE: &str = "Wetterbericht fuer die Biskaya: heute morgen leichter Regen, \
+    nachmittags aufklarend. Wind aus Nordwest, Staerke vier. Sicht gut.";
+
+fn main() {
+    let mut rng = rand::thread_rng();
+    let settings = Settings::random(8, &mut rng);
+    let pt = letters(MESSAGE);
+    let ct = Enigma::new(&settings).process(&pt);
+    println!("Settings: {settings:?}");
+    println!("Ciphertext: {}", to_string(&ct));
+
+    // Weather reports start with a predictable header.
+    let crib = letters("WETTERBERICHTFUERDIEBISKAYA");
+    let offsets = crib_offsets(&ct, &crib);
+    println!("Crib possible offsets: {offsets:?}");
+
+    let start = Instant::now();
+    let stops = bombe(&ct, &crib, 0, &[0, 1, 2, 3, 4], 0, settings.rings);
+    println!("Stops: {} in {:?}", stops.len(), start.elapsed());
+    for stop in stops {
+        println!(
+            "Rotors {:?}, positions {}, plugs {}",
+            stop.rotors.map(|r| r + 1),
+            to_string(&stop.positions),
+            stop.plugboard
+                .pairs()
+                .iter()
+                .map(|&(a, b)| to_string(&[a, b]))
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+        println!("Plaintext: {}", to_string(&Enigma::new(&stop).process(&ct)));
+    }
+}
@@ -0,0 +1,150 @@
+//! Luby–Rackoff round count distinguishers.
+//!
+//! Each distinguisher returns `true` if the oracle looks like a Feistel network
+//! with the given number of rounds, and `false` if it looks like a random
+//! permutation. Round functions can be anything, even perfectly random.
+//!
+//! Notation: the input `(a, b)` goes through the rounds
+//!
+//! ```text
+//! x = a ⊙ F0(b)
+//! y = b ⊙ F1(x)
+//! z = x ⊙ F2(y)
+//! ```
+//!
+//! and the output is `(x, b)`, `(x, y)` or `(z, y)` for 1, 2 or 3 rounds.
+
+use crate::{Layout, Permutation};
+
+// Query the oracle with the halves `(a, b)`.
+fn encrypt<P: Permutation>(oracle: &P, layout: &Layout, a: u64, b: u64) -> (u64, u64) {
+    layout.split(oracle.encrypt(layout.join(a, b)))
+}
+
+/// One round: the right half goes through untouched.
+pub fn one_round<P: Permutation>(oracle: &P, layout: &Layout, trials: u64) -> bool {
+    (0..trials).all(|i| {
+        let (a, b) = (i % layout.left, (i * 7 + 1) % layout.right);
+        encrypt(oracle, layout, a, b).1 == b
+    })
+}
+
+/// Two rounds, chosen plaintext.
+///
+/// For a fixed `b`, `x1 ⊙⁻¹ x2 = a1 ⊙⁻¹ a2`: the left output halves keep the
+/// left input halves difference. A random permutation passes each trial with
+/// probability `1/left`.
+pub fn two_rounds<P: Permutation>(oracle: &P, layout: &Layout, trials: u64) -> bool {
+    let op = layout.op;
+    let m = layout.left;
+    (1..=trials).all(|i| {
+        let (a1, a2, b) = (0, i % m, (i * 7) % layout.right);
+        let (x1, _) = encrypt(oracle, layout, a1, b);
+        let (x2, _) = encrypt(oracle, layout, a2, b);
+        op.invert(x1, x2, m) == op.invert(a1, a2, m)
+    })
+}
+
+/// Three rounds, chosen plaintext (birthday bound).
+///
+/// Query `(a_i, b)` for a fixed `b` and distinct `a_i`. Whenever two outputs
+/// share the right half (`y_i = y_j`, i.e. a collision of `F1`) then
+/// `z_i ⊙⁻¹ z_j = x_i ⊙⁻¹ x_j = a_i ⊙⁻¹ a_j`. For a random permutation the
+/// relation holds with probability `1/left`.
+///
+/// About `sqrt(right)` queries are required to see some collisions.
+///
+/// Returns `None` if no collision was found.
+pub fn three_rounds_cpa<P: Permutation>(oracle: &P, layout: &Layout, queries: u64) -> Option<bool> {
+    let op = layout.op;
+    let m = layout.left;
+    let mut outputs: Vec<_> = (0..queries.min(m))
+        .map(|a| {
+            let (z, y) = encrypt(oracle, layout, a, 0);
+            (y, z, a)
+        })
+        .collect();
+    outputs.sort_unstable();
+
+    let (mut consistent, mut total) = (0, 0);
+    for w in outputs.windows(2) {
+        let ((y1, z1, a1), (y2, z2, a2)) = (w[0], w[1]);
+        if y1 == y2 {
+            total += 1;
+            if op.invert(z1, z2, m) == op.invert(a1, a2, m) {
+                consistent += 1;
+            }
+        }
+    }
+    (total > 0).then_some(2 * consistent > total)
+}
+
+/// Three rounds, chosen plaintext and ciphertext.
+///
+/// 1. encrypt `(a1, b)` → `(z1, y1)` and `(a2, b)` → `(z2, y2)`;
+/// 2. decrypt `(z2 ⊙ a1 ⊙⁻¹ a2, y2)` → `(a3, b3)`.
+///
+/// The decryption hits `x3 = x2 ⊙ a1 ⊙⁻¹ a2 = x1`, thus
+/// `b3 = y2 ⊙⁻¹ F1(x1) = b ⊙ y2 ⊙⁻¹ y1`. A random permutation passes each
+/// trial with probability `1/right`.
+pub fn three_rounds_cca<P: Permutation>(oracle: &P, layout: &Layout, trials: u64) -> bool {
+    let op = layout.op;
+    let (l, r) = (layout.left, layout.right);
+    (1..=trials).all(|i| {
+        let (a1, a2, b) = (0, i % l, (i * 7) % r);
+        let (_, y1) = encrypt(oracle, layout, a1, b);
+        let (z2, y2) = encrypt(oracle, layout, a2, b);
+        let z3 = op.invert(op.apply(z2, a1, l), a2, l);
+        let (_, b3) = layout.split(oracle.decrypt(layout.join(z3, y2)));
+        b3 == op.invert(op.apply(b, y2, r), y1, r)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Feistel, Prf, RandomPermutation};
+
+    fn layouts() -> [Layout; 2] {
+        [Layout::binary(12), Layout::radix(10, 6)]
+    }
+
+    #[test]
+    fn one_and_two_rounds() {
+        for layout in layouts() {
+            let random = RandomPermutation::new(layout.domain(), 0);
+            for rounds in 1..=4 {
+                let feistel = Feistel::new(Prf::new(b"key"), rounds, layout);
+                assert_eq!(one_round(&feistel, &layout, 8), rounds == 1);
+                assert_eq!(two_rounds(&feistel, &layout, 8), rounds <= 2);
+            }
+            assert!(!one_round(&random, &layout, 8));
+            assert!(!two_rounds(&random, &layout, 8));
+        }
+    }
+
+    #[test]
+    fn three_rounds_cpa_works() {
+        for layout in layouts() {
+            let queries = 8 * (layout.right as f64).sqrt() as u64;
+            let feistel = Feistel::new(Prf::new(b"key"), 3, layout);
+            assert_eq!(three_rounds_cpa(&feistel, &layout, queries), Some(true));
+            let feistel = Feistel::new(Prf::new(b"key"), 4, layout);
+            assert_eq!(three_rounds_cpa(&feistel, &layout, queries), Some(false));
+            let random = RandomPermutation::new(layout.domain(), 0);
+            assert_eq!(three_rounds_cpa(&random, &layout, queries), Some(false));
+        }
+    }
+
+    #[test]
+    fn three_rounds_cca_works() {
+        for layout in layouts() {
+            let feistel = Feistel::new(Prf::new(b"key"), 3, layout);
+            assert!(three_rounds_cca(&feistel, &layout, 8));
+            let feistel = Feistel::new(Prf::new(b"key"), 4, layout);
+            assert!(!three_rounds_cca(&feistel, &layout, 8));
+            let random = RandomPermutation::new(layout.domain(), 0);
+            assert!(!three_rounds_cca(&random, &layout, 8));
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use feistel::{distinguisher, Feistel, Layout, Permutation, Prf, RandomPermutation};
+use rand::Rng;
+
+fn verdict(b: bool) -> &'static str {
+    if b {
+        "feistel"
+    } else {
+        "random"
+    }
+}
+
+fn run<P: Permutation>(name: &str, oracle: &P, layout: &Layout) {
+    let queries = 8 * (layout.right as f64).sqrt() as u64;
+    let cpa = distinguisher::three_rounds_cpa(oracle, layout, queries).map_or("-", verdict);
+    println!(
+        "{name:>8} | {:>8} | {:>8} | {:>9} | {:>9}",
+        verdict(distinguisher::one_round(oracle, layout, 8)),
+        verdict(distinguisher::two_rounds(oracle, layout, 8)),
+        cpa,
+        verdict(distinguisher::three_rounds_cca(oracle, layout, 8)),
+    );
+}
+
+fn main() {
+    let key: [u8; 16] = rand::thread_rng().gen();
+
+    // 16 bits halves, xor
+    let layout = Layout::binary(16);
+    println!(
+        "{:>8} | {:>8} | {:>8} | {:>9} | {:>9}",
+        "oracle", "1 round", "2 rounds", "3 rds CPA", "3 rds CCA"
+    );
+    for rounds in 1..=5 {
+        let feistel = Feistel::new(Prf::new(&key), rounds, layout);
+        run(&format!("{rounds} rounds"), &feistel, &layout);
+    }
+    let random = RandomPermutation::new(layout.domain(), 0);
+    run("random", &random, &layout);
+}
@@ -0,0 +1,243 @@
+//! Fiat-Shamir with a weak challenge space.
+//!
+//! Schnorr proof of knowledge of `x = log_g(y)`, made non interactive via the
+//! Fiat-Shamir transform and bound to a message (i.e. a Schnorr signature):
+//!
+//! - commitment `t = g^k`, with random `k`
+//! - challenge `c = H(g, y, t, msg)`
+//! - response `s = k + c·x mod q`
+//!
+//! and verified by checking `g^s = t·y^c`.
+//!
+//! A prover not knowing `x` can answer a single challenge per commitment: it
+//! picks `c*` and `s` in advance and sets `t = g^s·y^(-c*)`. In the interactive
+//! protocol this cheats the verifier with probability `1/|C|`, and the cheater
+//! has a single shot. With Fiat-Shamir the challenge is computed offline, thus
+//! the cheater grinds commitments until `H(g, y, t, msg) = c*`, at the cost of
+//! about `|C|` hash evaluations. For this reason the challenge space must be
+//! as large as the security level (e.g. 2^128) and the challenge must depend
+//! on the commitment.
+//!
+//! Two failures are demonstrated (see [`Challenge`]):
+//! - a challenge truncated to a few bits, e.g. to shorten the proofs or when a
+//!   small challenge interactive protocol is transformed without repetitions;
+//! - a "unique" but predictable challenge, such as a counter, which is not
+//!   bound to the commitment and is forged at the first attempt.
+
+use num_bigint::{BigUint, RandBigInt};
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+pub use vrf_grinding::group::Group;
+
+// Forgery attempts per parallel job
+const CHUNK: u64 = 256;
+// Challenge hash output size
+const HASH_BITS: u32 = 256;
+
+/// How the challenge is derived.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Challenge {
+    /// `H(g, y, t, msg) mod q`.
+    Full,
+    /// `H(g, y, t, msg)` truncated to the given number of bits, at most the
+    /// 256 of the hash (larger values are clamped).
+    Truncated(u32),
+    /// Sequence number carried by the first 8 bytes of the message.
+    ///
+    /// Each proof gets a fresh challenge, but it is known in advance.
+    Counter,
+}
+
+impl Challenge {
+    /// Challenge entropy in bits, from the point of view of a cheating prover.
+    pub fn entropy(&self, group: &Group) -> u32 {
+        match self {
+            Challenge::Full => group.q.bits() as u32,
+            Challenge::Truncated(bits) => (*bits).min(HASH_BITS),
+            Challenge::Counter => 0,
+        }
+    }
+
+    /// Challenge for commitment `t` to message `msg`.
+    pub fn compute(&self, group: &Group, y: &BigUint, t: &BigUint, msg: &[u8]) -> BigUint {
+        let hash = || {
+            let mut hasher = Sha256::new();
+            hasher.update(b"fs-challenge");
+            [&group.g, y, t]
+                .iter()
+                .for_each(|x| hasher.update(x.to_bytes_be()));
+            hasher.update(msg);
+            BigUint::from_bytes_be(&hasher.finalize())
+        };
+        match self {
+            Challenge::Full => hash() % &group.q,
+            Challenge::Truncated(bits) => hash() >> (HASH_BITS - bits.min(&HASH_BITS)),
+            Challenge::Counter => {
+                let mut seq = [0; 8];
+                let len = msg.len().min(8);
+                seq[..len].copy_from_slice(&msg[..len]);
+                BigUint::from(u64::from_be_bytes(seq))
+            }
+        }
+    }
+}
+
+/// Proof of knowledge of `log_g(y)`, bound to a message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Proof {
+    /// Commitment.
+    pub t: BigUint,
+    /// Response.
+    pub s: BigUint,
+}
+
+/// Honest prover.
+pub struct Prover {
+    group: Group,
+    x: BigUint,
+    /// Public key `g^x`.
+    pub y: BigUint,
+}
+
+impl Prover {
+    pub fn generate<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let group = Group::default();
+        let x = rng.gen_biguint_range(&BigUint::from(1_u8), &group.q);
+        let y = group.pow(&group.g, &x);
+        Prover { group, x, y }
+    }
+
+    pub fn prove<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        challenge: Challenge,
+        msg: &[u8],
+    ) -> Proof {
+        let group = &self.group;
+        let k = rng.gen_biguint_below(&group.q);
+        let t = group.pow(&group.g, &k);
+        let c = challenge.compute(group, &self.y, &t, msg);
+        let s = (k + c * &self.x) % &group.q;
+        Proof { t, s }
+    }
+}
+
+/// Verify the `proof` of knowledge of `log_g(y)` for `msg`.
+pub fn verify(challenge: Challenge, y: &BigUint, msg: &[u8], proof: &Proof) -> bool {
+    let group = Group::default();
+    if !group.is_element(y) || !group.is_element(&proof.t) {
+        return false;
+    }
+    let c = challenge.compute(&group, y, &proof.t, msg);
+    group.pow(&group.g, &proof.s) == group.mul(&proof.t, &group.pow(y, &c))
+}
+
+/// Forge a proof for `y` and `msg` without knowing `log_g(y)`.
+///
+/// A target challenge `c*` is fixed and commitments `t = g^s·y^(-c*)`, for
+/// increasing `s`, are tried in parallel until the challenge of one of them is
+/// `c*`. About `2^entropy` attempts are required.
+///
+/// Returns the proof and the number of attempts, or `None` if the proof is not
+/// found within `max_attempts`.
+pub fn forge<R: RngCore>(
+    rng: &mut R,
+    challenge: Challenge,
+    y: &BigUint,
+    msg: &[u8],
+    max_attempts: u64,
+) -> Option<(Proof, u64)> {
+    let group = Group::default();
+    let target = match challenge {
+        // Does not depend on the commitment
+        Challenge::Counter => challenge.compute(&group, y, &group.g, msg),
+        Challenge::Full => rng.gen_biguint_below(&group.q),
+        Challenge::Truncated(_) => rng.gen_biguint(challenge.entropy(&group) as u64),
+    };
+    // y^(-c*)
+    let y_inv_c = group.pow(y, &(&group.q - &target % &group.q));
+    let s0 = rng.gen_biguint_below(&group.q);
+    let attempts = AtomicU64::new(0);
+
+    // Within a chunk the next commitment is just `t·g`
+    let proof = par_search::find_map_chunks(0..max_attempts, CHUNK, |chunk| {
+        let mut s = (&s0 + chunk.start) % &group.q;
+        let mut t = group.mul(&group.pow(&group.g, &s), &y_inv_c);
+        for _ in chunk {
+            attempts.fetch_add(1, Ordering::Relaxed);
+            if challenge.compute(&group, y, &t, msg) == target {
+                return Some(Proof { t, s });
+            }
+            s = (s + 1_u8) % &group.q;
+            t = group.mul(&t, &group.g);
+        }
+        None
+    })?;
+    Some((proof, attempts.into_inner()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    const CHALLENGES: [Challenge; 4] = [
+        Challenge::Full,
+        Challenge::Truncated(8),
+        Challenge::Truncated(300),
+        Challenge::Counter,
+    ];
+
+    #[test]
+    fn prove_verify() {
+        let prover = Prover::generate(&mut OsRng);
+        let msg = 42_u64.to_be_bytes();
+        for challenge in CHALLENGES {
+            let proof = prover.prove(&mut OsRng, challenge, &msg);
+            assert!(verify(challenge, &prover.y, &msg, &proof));
+            let mut bad = proof.clone();
+            bad.s += 1_u8;
+            assert!(!verify(challenge, &prover.y, &msg, &bad));
+        }
+    }
+
+    #[test]
+    fn oversized_truncation_is_clamped() {
+        let (group, y) = (Group::default(), Prover::generate(&mut OsRng).y);
+        let (full, over) = (Challenge::Truncated(256), Challenge::Truncated(300));
+        assert_eq!(over.entropy(&group), 256);
+        assert_eq!(
+            over.compute(&group, &y, &group.g, b"msg"),
+            full.compute(&group, &y, &group.g, b"msg")
+        );
+    }
+
+    #[test]
+    fn weak_challenges_are_forged() {
+        let y = Prover::generate(&mut OsRng).y;
+        let msg = 7_u64.to_be_bytes();
+        let (proof, attempts) = forge(&mut OsRng, Challenge::Counter, &y, &msg, 1 << 20).unwrap();
+        assert!(verify(Challenge::Counter, &y, &msg, &proof));
+        assert!(attempts <= rayon::current_num_threads() as u64);
+
+        let challenge = Challenge::Truncated(10);
+        let runs = 10;
+        let mut total = 0;
+        for _ in 0..runs {
+            let (proof, attempts) = forge(&mut OsRng, challenge, &y, &msg, 1 << 20).unwrap();
+            assert!(verify(challenge, &y, &msg, &proof));
+            total += attempts;
+        }
+        // About 2^10 attempts on average
+        let avg = total / runs;
+        assert!((1 << 7..1 << 13).contains(&avg), "avg attempts: {avg}");
+    }
+
+    #[test]
+    fn full_challenge_resists() {
+        let y = Prover::generate(&mut OsRng).y;
+        assert!(forge(&mut OsRng, Challenge::Full, &y, b"msg", 1 << 12).is_none());
+    }
+}
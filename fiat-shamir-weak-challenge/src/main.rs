@@ -0,0 +1,49 @@
+use fiat_shamir_weak_challenge::{forge, verify, Challenge, Group, Prover};
+use rand::rngs::OsRng;
+use std::time::Instant;
+
+fn main() {
+    let group = Group::default();
+    let prover = Prover::generate(&mut OsRng);
+    let msg = b"transfer 100 coins to mallory";
+
+    println!("Forgery time versus challenge entropy");
+    println!(
+        "{:>8} {:>12} {:>12} {:>10}",
+        "bits", "expected", "attempts", "time"
+    );
+    let mut rate = 0.0;
+    for bits in (8..=22).step_by(2) {
+        let challenge = Challenge::Truncated(bits);
+        let start = Instant::now();
+        let (proof, attempts) = forge(&mut OsRng, challenge, &prover.y, msg, u64::MAX).unwrap();
+        let elapsed = start.elapsed();
+        assert!(verify(challenge, &prover.y, msg, &proof));
+        rate = attempts as f64 / elapsed.as_secs_f64();
+        println!(
+            "{bits:>8} {:>12} {attempts:>12} {:>9.2}s",
+            1_u64 << bits,
+            elapsed.as_secs_f64()
+        );
+    }
+
+    // Extrapolated from the last measured rate
+    let full = Challenge::Full.entropy(&group);
+    let years = 2_f64.powi(full as i32) / rate / (365.0 * 24.0 * 3600.0);
+    println!(
+        "{full:>8} {:>12} {:>12} {years:>9.1e}y (estimated at {rate:.0} attempts/s)",
+        format!("2^{full}"),
+        "-",
+    );
+
+    println!("Counter challenge");
+    let seq = 1234_u64.to_be_bytes();
+    let honest = prover.prove(&mut OsRng, Challenge::Counter, &seq);
+    assert!(verify(Challenge::Counter, &prover.y, &seq, &honest));
+    let next = 1235_u64.to_be_bytes();
+    let (forged, attempts) = forge(&mut OsRng, Challenge::Counter, &prover.y, &next, 1).unwrap();
+    println!(
+        "  Forged proof for sequence number 1235: {} ({attempts} attempt)",
+        verify(Challenge::Counter, &prover.y, &next, &forged)
+    );
+}
@@ -0,0 +1,46 @@
+use feistel::distinguisher;
+use fpe_small_domain::{codebook, dictionary_attack, CycleWalking, Fpe, Permutation};
+use rand::Rng;
+use std::collections::BTreeMap;
+
+fn main() {
+    let mut rng = rand::thread_rng();
+    let key: [u8; 32] = rng.gen();
+
+    // 4 digits PINs
+    let fpe = Fpe::new(key, 4, 10);
+    let pins: Vec<u64> = (0..5).map(|_| rng.gen_range(0..10_000)).collect();
+    let encrypted: Vec<_> = pins.iter().map(|&p| fpe.encrypt(p)).collect();
+    println!("Encrypted PINs: {encrypted:04?}");
+
+    let book = codebook(&fpe, fpe.domain());
+    let recovered: Vec<_> = encrypted.iter().map(|c| book[c]).collect();
+    println!(
+        "Recovered PINs: {recovered:04?} ({} queries, no key)",
+        book.len()
+    );
+    assert_eq!(recovered, pins);
+
+    // Birth dates as MMDD. Only 372 candidates out of 10^4.
+    let date = 1225;
+    let ct = fpe.encrypt(date);
+    let dates = (1..=12).flat_map(|m| (1..=31).map(move |d| m * 100 + d));
+    let (found, queries) = dictionary_attack(&fpe, dates, ct).unwrap();
+    println!("Birth date {ct:04} → {found:04} ({queries} queries)");
+
+    // Day of year [0, 366) embedded in 3 digits via cycle walking.
+    // The number of walk steps depends on the plaintext and leaks via timing.
+    let cw = CycleWalking::new(Fpe::new(key, 3, 10), 366);
+    let mut steps = BTreeMap::<usize, usize>::new();
+    for x in 0..366 {
+        *steps.entry(cw.encrypt_walk(x).1).or_default() += 1;
+    }
+    println!("Cycle walking steps histogram (steps: plaintexts): {steps:?}");
+
+    // Reduced rounds
+    for rounds in [2, 3, 10] {
+        let fpe = Fpe::new(key, 6, rounds);
+        let feistel = distinguisher::two_rounds(&fpe, fpe.layout(), 16);
+        println!("{rounds} rounds: two rounds distinguisher says {feistel}");
+    }
+}
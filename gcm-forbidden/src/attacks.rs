@@ -0,0 +1,100 @@
+//! Authentication key recovery and forgery.
+
+use crate::{gf128::Gf128, ghash, Sealed};
+use rand::Rng;
+
+/// Forger for a given nonce: authentication key and mask.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Forger {
+    pub h: Gf128,
+    pub mask: Gf128,
+}
+
+impl Forger {
+    /// Forger with key `h`, deriving the mask from a message under the nonce.
+    pub fn new(h: Gf128, sealed: &Sealed) -> Self {
+        let mask = Gf128::from_bytes(&sealed.tag) + ghash(h, &sealed.aad, &sealed.ciphertext);
+        Forger { h, mask }
+    }
+
+    pub fn tag(&self, aad: &[u8], ciphertext: &[u8]) -> [u8; 16] {
+        (ghash(self.h, aad, ciphertext) + self.mask).to_bytes()
+    }
+
+    pub fn forge(&self, aad: &[u8], ciphertext: &[u8]) -> Sealed {
+        Sealed {
+            aad: aad.to_vec(),
+            ciphertext: ciphertext.to_vec(),
+            tag: self.tag(aad, ciphertext),
+        }
+    }
+
+    /// Check the forger against a genuine message.
+    pub fn check(&self, sealed: &Sealed) -> bool {
+        self.tag(&sealed.aad, &sealed.ciphertext) == sealed.tag
+    }
+}
+
+/// Candidate authentication keys from two messages under the same nonce.
+pub fn candidate_keys<R: Rng>(a: &Sealed, b: &Sealed, rng: &mut R) -> Vec<Gf128> {
+    a.poly().add(&b.poly()).roots(rng)
+}
+
+/// Forgers consistent with all the `samples`, sealed under the same nonce.
+///
+/// Spurious candidates from the first two samples are usually ruled out by a
+/// third one.
+pub fn recover<R: Rng>(samples: &[Sealed], rng: &mut R) -> Vec<Forger> {
+    let [a, b, rest @ ..] = samples else {
+        return Vec::new();
+    };
+    candidate_keys(a, b, rng)
+        .into_iter()
+        .map(|h| Forger::new(h, a))
+        .filter(|f| f.check(b) && rest.iter().all(|s| f.check(s)))
+        .collect()
+}
+
+/// Counter mode malleability: turn the ciphertext of `known` into the
+/// ciphertext of `target` (same length).
+pub fn malleate(ciphertext: &[u8], known: &[u8], target: &[u8]) -> Vec<u8> {
+    ciphertext
+        .iter()
+        .zip(known.iter().zip(target))
+        .map(|(c, (k, t))| c ^ k ^ t)
+        .collect()
+}
+
+/// Degree of the polynomial solved by [`candidate_keys`].
+pub fn equation_degree(a: &Sealed, b: &Sealed) -> usize {
+    a.poly().add(&b.poly()).degree()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn forgery_is_accepted() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (key, nonce) = (rng.gen(), rng.gen());
+        let samples = [
+            Sealed::seal(&key, &nonce, b"v1", b"from=alice&to=bob&amount=0100"),
+            Sealed::seal(&key, &nonce, b"v1", b"from=carol&to=dave&amount=20"),
+            Sealed::seal(&key, &nonce, b"", b"ping"),
+        ];
+        let forgers = recover(&samples, &mut rng);
+        assert_eq!(forgers.len(), 1);
+        let ciphertext = malleate(
+            &samples[0].ciphertext,
+            b"from=alice&to=bob&amount=0100",
+            b"from=alice&to=eve&amount=9999",
+        );
+        let forged = forgers[0].forge(b"v1", &ciphertext);
+        assert_eq!(
+            forged.open(&key, &nonce).unwrap(),
+            b"from=alice&to=eve&amount=9999"
+        );
+    }
+}
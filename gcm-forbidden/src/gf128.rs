@@ -0,0 +1,251 @@
+//! GF(2^128) arithmetic and polynomials over it.
+//!
+//! Elements use the GCM convention: the field is `GF(2)[x] / (x^128 + x^7 +
+//! x^2 + x + 1)` and the most significant bit of the first byte is the
+//! coefficient of `x^0`.
+
+use rand::Rng;
+use std::ops::{Add, Mul};
+
+/// Field element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Gf128(pub u128);
+
+impl Gf128 {
+    pub const ZERO: Gf128 = Gf128(0);
+    pub const ONE: Gf128 = Gf128(1 << 127);
+
+    pub fn from_bytes(bytes: &[u8; 16]) -> Self {
+        Gf128(u128::from_be_bytes(*bytes))
+    }
+
+    /// Block padded with zeros.
+    pub fn from_block(block: &[u8]) -> Self {
+        let mut bytes = [0; 16];
+        bytes[..block.len()].copy_from_slice(block);
+        Self::from_bytes(&bytes)
+    }
+
+    pub fn to_bytes(self) -> [u8; 16] {
+        self.0.to_be_bytes()
+    }
+
+    pub fn pow(self, mut e: u128) -> Self {
+        let (mut r, mut b) = (Gf128::ONE, self);
+        while e != 0 {
+            if e & 1 == 1 {
+                r = r * b;
+            }
+            b = b * b;
+            e >>= 1;
+        }
+        r
+    }
+
+    /// Inverse, zero for zero.
+    pub fn inv(self) -> Self {
+        self.pow(u128::MAX - 1)
+    }
+}
+
+impl Add for Gf128 {
+    type Output = Gf128;
+
+    // Characteristic 2
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, rhs: Gf128) -> Gf128 {
+        Gf128(self.0 ^ rhs.0)
+    }
+}
+
+impl Mul for Gf128 {
+    type Output = Gf128;
+
+    // NIST SP 800-38D, Algorithm 1
+    fn mul(self, rhs: Gf128) -> Gf128 {
+        const R: u128 = 0xe1 << 120;
+        let (mut z, mut v) = (0, rhs.0);
+        for i in (0..128).rev() {
+            if (self.0 >> i) & 1 == 1 {
+                z ^= v;
+            }
+            v = if v & 1 == 1 { (v >> 1) ^ R } else { v >> 1 };
+        }
+        Gf128(z)
+    }
+}
+
+/// Polynomial, coefficients from the lowest degree and no trailing zeros.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Poly(Vec<Gf128>);
+
+impl Poly {
+    pub fn new(mut coeffs: Vec<Gf128>) -> Self {
+        while coeffs.last() == Some(&Gf128::ZERO) {
+            coeffs.pop();
+        }
+        Poly(coeffs)
+    }
+
+    /// `x`.
+    pub fn x() -> Self {
+        Poly(vec![Gf128::ZERO, Gf128::ONE])
+    }
+
+    pub fn coeffs(&self) -> &[Gf128] {
+        &self.0
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Degree, zero for the zero polynomial.
+    pub fn degree(&self) -> usize {
+        self.0.len().saturating_sub(1)
+    }
+
+    pub fn eval(&self, x: Gf128) -> Gf128 {
+        self.0.iter().rev().fold(Gf128::ZERO, |acc, &c| acc * x + c)
+    }
+
+    pub fn add(&self, other: &Poly) -> Poly {
+        let len = self.0.len().max(other.0.len());
+        let get = |p: &Poly, i| p.0.get(i).copied().unwrap_or_default();
+        Poly::new((0..len).map(|i| get(self, i) + get(other, i)).collect())
+    }
+
+    pub fn mul(&self, other: &Poly) -> Poly {
+        if self.is_zero() || other.is_zero() {
+            return Poly::default();
+        }
+        let mut coeffs = vec![Gf128::ZERO; self.0.len() + other.0.len() - 1];
+        for (i, &a) in self.0.iter().enumerate() {
+            for (j, &b) in other.0.iter().enumerate() {
+                coeffs[i + j] = coeffs[i + j] + a * b;
+            }
+        }
+        Poly::new(coeffs)
+    }
+
+    /// Quotient and remainder. Panics on zero divisor.
+    pub fn divrem(&self, divisor: &Poly) -> (Poly, Poly) {
+        let lead = divisor.0.last().expect("non zero divisor").inv();
+        let mut rem = self.0.clone();
+        if rem.len() < divisor.0.len() {
+            return (Poly::default(), self.clone());
+        }
+        let mut quot = vec![Gf128::ZERO; rem.len() - divisor.0.len() + 1];
+        for i in (0..quot.len()).rev() {
+            let q = rem[i + divisor.0.len() - 1] * lead;
+            quot[i] = q;
+            for (j, &d) in divisor.0.iter().enumerate() {
+                rem[i + j] = rem[i + j] + q * d;
+            }
+        }
+        (Poly::new(quot), Poly::new(rem))
+    }
+
+    pub fn rem(&self, modulus: &Poly) -> Poly {
+        self.divrem(modulus).1
+    }
+
+    /// Same polynomial with leading coefficient one.
+    pub fn monic(&self) -> Poly {
+        match self.0.last() {
+            Some(&lead) => {
+                let inv = lead.inv();
+                Poly(self.0.iter().map(|&c| c * inv).collect())
+            }
+            None => Poly::default(),
+        }
+    }
+
+    /// Monic greatest common divisor.
+    pub fn gcd(&self, other: &Poly) -> Poly {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            (a, b) = (b.clone(), a.rem(&b));
+        }
+        a.monic()
+    }
+
+    /// Distinct roots in GF(2^128).
+    ///
+    /// `gcd(f, x^(2^128) - x)` is the product of the linear factors of `f`,
+    /// which is then split by Cantor-Zassenhaus: for a random `a` the trace
+    /// `Σ (a·x)^(2^i)` is zero on about half of the roots.
+    pub fn roots<R: Rng>(&self, rng: &mut R) -> Vec<Gf128> {
+        if self.degree() == 0 {
+            return Vec::new();
+        }
+        let f = self.monic();
+        let mut frobenius = Poly::x();
+        for _ in 0..128 {
+            frobenius = frobenius.mul(&frobenius).rem(&f);
+        }
+        let linear = f.gcd(&frobenius.add(&Poly::x()));
+        let mut roots = Vec::new();
+        split(&linear, rng, &mut roots);
+        roots
+    }
+}
+
+// Roots of a monic product of distinct linear factors.
+fn split<R: Rng>(f: &Poly, rng: &mut R, roots: &mut Vec<Gf128>) {
+    match f.degree() {
+        0 => (),
+        1 => roots.push(f.0[0]),
+        _ => loop {
+            let ax = Poly(vec![Gf128::ZERO, Gf128(rng.gen())]);
+            let (mut trace, mut power) = (ax.clone(), ax);
+            for _ in 1..128 {
+                power = power.mul(&power).rem(f);
+                trace = trace.add(&power);
+            }
+            let g = f.gcd(&trace);
+            if g.degree() > 0 && g.degree() < f.degree() {
+                split(&g, rng, roots);
+                split(&f.divrem(&g).0, rng, roots);
+                return;
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn field_axioms() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..20 {
+            let (a, b, c) = (Gf128(rng.gen()), Gf128(rng.gen()), Gf128(rng.gen()));
+            assert_eq!(a * b, b * a);
+            assert_eq!(a * (b + c), a * b + a * c);
+            assert_eq!(a * Gf128::ONE, a);
+            assert_eq!(a * a.inv(), Gf128::ONE);
+        }
+    }
+
+    #[test]
+    fn polynomial_roots() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let expected: Vec<_> = (0..5).map(|_| Gf128(rng.gen())).collect();
+        // (x - r₀)···(x - r₄) times a random linear factor
+        let mut f = expected.iter().fold(Poly::new(vec![Gf128::ONE]), |f, &r| {
+            f.mul(&Poly::new(vec![r, Gf128::ONE]))
+        });
+        f = f.mul(&Poly::new(vec![Gf128(rng.gen()), Gf128(rng.gen())]));
+        let roots = f.roots(&mut rng);
+        assert!(roots.iter().all(|&r| f.eval(r) == Gf128::ZERO));
+        for r in &expected {
+            assert!(roots.contains(r));
+        }
+        let (q, r) = f.divrem(&Poly::new(vec![expected[0], Gf128::ONE]));
+        assert!(r.is_zero());
+        assert_eq!(q.degree(), f.degree() - 1);
+    }
+}
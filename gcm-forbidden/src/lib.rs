@@ -0,0 +1,121 @@
+//! AES-GCM nonce reuse, the "forbidden attack" (Joux).
+//!
+//! GCM encrypts in counter mode and authenticates with the polynomial MAC
+//! GHASH, keyed by `H = E_K(0)`. The tag of the blocks `B₁..Bₘ` (associated
+//! data, ciphertext and lengths) is
+//!
+//! ```text
+//! T = B₁·H^m + ... + Bₘ·H + E_K(J₀)
+//! ```
+//!
+//! where the mask `E_K(J₀)` only depends on the nonce. Two messages under the
+//! same nonce share the mask, and the sum of their tag equations is a known
+//! polynomial vanishing in `H`. Its roots (a handful at most) are the
+//! candidate keys, and once `H` and the mask are known any ciphertext under
+//! that nonce gets a valid tag. Combined with the counter mode malleability
+//! this gives arbitrary forgeries of known plaintexts.
+//!
+//! See the `chunked-encryption` crate for how nonces get reused in practice.
+
+use aes_gcm::{
+    aead::{Aead, Payload},
+    Aes256Gcm, KeyInit,
+};
+use gf128::{Gf128, Poly};
+
+pub mod attacks;
+pub mod gf128;
+
+/// Sealed message, as seen on the wire.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sealed {
+    pub aad: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+    pub tag: [u8; 16],
+}
+
+impl Sealed {
+    /// AES-256-GCM encryption.
+    pub fn seal(key: &[u8; 32], nonce: &[u8; 12], aad: &[u8], msg: &[u8]) -> Self {
+        let mut ciphertext = Aes256Gcm::new(key.into())
+            .encrypt(nonce.into(), Payload { msg, aad })
+            .unwrap();
+        let tag = ciphertext.split_off(ciphertext.len() - 16);
+        Sealed {
+            aad: aad.to_vec(),
+            ciphertext,
+            tag: tag.try_into().unwrap(),
+        }
+    }
+
+    /// AES-256-GCM decryption.
+    pub fn open(&self, key: &[u8; 32], nonce: &[u8; 12]) -> Option<Vec<u8>> {
+        let msg = [&self.ciphertext[..], &self.tag].concat();
+        Aes256Gcm::new(key.into())
+            .decrypt(
+                nonce.into(),
+                Payload {
+                    msg: &msg,
+                    aad: &self.aad,
+                },
+            )
+            .ok()
+    }
+
+    /// Tag equation as a polynomial in `H`, with the tag as constant term.
+    pub fn poly(&self) -> Poly {
+        let mut coeffs = blocks(&self.aad, &self.ciphertext);
+        coeffs.push(Gf128::from_bytes(&self.tag));
+        coeffs.reverse();
+        Poly::new(coeffs)
+    }
+}
+
+/// GHASH input blocks: associated data and ciphertext padded, then lengths.
+pub fn blocks(aad: &[u8], ciphertext: &[u8]) -> Vec<Gf128> {
+    let mut blocks: Vec<_> = aad
+        .chunks(16)
+        .chain(ciphertext.chunks(16))
+        .map(Gf128::from_block)
+        .collect();
+    let lengths = ((aad.len() as u128 * 8) << 64) | (ciphertext.len() as u128 * 8);
+    blocks.push(Gf128(lengths));
+    blocks
+}
+
+/// GHASH with key `h`.
+pub fn ghash(h: Gf128, aad: &[u8], ciphertext: &[u8]) -> Gf128 {
+    blocks(aad, ciphertext)
+        .into_iter()
+        .fold(Gf128::ZERO, |acc, b| (acc + b) * h)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ghash::{universal_hash::UniversalHash, GHash};
+
+    #[test]
+    fn ghash_matches_reference() {
+        let h = Gf128(0x66e94bd4ef8a2c3b884cfa59ca342b2e);
+        let (aad, ct) = (b"header".as_slice(), [0x42; 37]);
+        let mut reference = GHash::new(&h.to_bytes().into());
+        reference.update_padded(aad);
+        reference.update_padded(&ct);
+        reference.update(&[Gf128(((6 * 8) << 64) | (37 * 8)).to_bytes().into()]);
+        let expected: [u8; 16] = reference.finalize().into();
+        assert_eq!(ghash(h, aad, &ct).to_bytes(), expected);
+    }
+
+    #[test]
+    fn tag_polynomial_vanishes_in_h_plus_mask() {
+        let key = [9; 32];
+        let sealed = Sealed::seal(&key, &[1; 12], b"aad", b"some message");
+        assert!(sealed.open(&key, &[1; 12]).is_some());
+        let poly = sealed.poly();
+        assert_eq!(poly.degree(), 3);
+        let h = Gf128(0x1234);
+        let expected = ghash(h, &sealed.aad, &sealed.ciphertext) + Gf128::from_bytes(&sealed.tag);
+        assert_eq!(poly.eval(h), expected);
+    }
+}
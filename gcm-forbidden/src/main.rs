@@ -0,0 +1,42 @@
+use gcm_forbidden::{
+    attacks::{candidate_keys, equation_degree, malleate, recover},
+    Sealed,
+};
+use rand::{rngs::OsRng, Rng};
+
+fn main() {
+    let (key, nonce): ([u8; 32], [u8; 12]) = (OsRng.gen(), OsRng.gen());
+    // The same nonce for the whole session
+    let known = b"from=alice&to=bob&amount=0100";
+    let samples = [
+        Sealed::seal(&key, &nonce, b"v1", known),
+        Sealed::seal(&key, &nonce, b"v1", b"from=carol&to=dave&amount=20"),
+        Sealed::seal(&key, &nonce, b"v1", b"heartbeat"),
+    ];
+    let degree = equation_degree(&samples[0], &samples[1]);
+    let candidates = candidate_keys(&samples[0], &samples[1], &mut OsRng);
+    println!(
+        "Tag equation of degree {degree}, {} candidate keys",
+        candidates.len()
+    );
+    for h in &candidates {
+        println!("  H = {}", hex::encode(h.to_bytes()));
+    }
+    let forgers = recover(&samples, &mut OsRng);
+    println!(
+        "{} candidates consistent with the third message",
+        forgers.len()
+    );
+    let forger = forgers[0];
+    println!("  H = {}", hex::encode(forger.h.to_bytes()));
+    println!("  E_K(J₀) = {}", hex::encode(forger.mask.to_bytes()));
+
+    let target = b"from=alice&to=eve&amount=9999";
+    let ciphertext = malleate(&samples[0].ciphertext, known, target);
+    let forged = forger.forge(b"v1", &ciphertext);
+    println!("Forged tag: {}", hex::encode(forged.tag));
+    match forged.open(&key, &nonce) {
+        Some(msg) => println!("Accepted: {}", String::from_utf8_lossy(&msg)),
+        None => println!("Rejected"),
+    }
+}
@@ -0,0 +1,202 @@
+//! Real group operations timed on the running machine.
+//!
+//! Instead of sampling the group operations durations from a Gaussian, the
+//! target actually runs a deliberately non constant time implementation and the
+//! latency is measured. Both the victim and the attacker emulation run on the
+//! same machine, thus the attack faces the real noise of the host: scheduler,
+//! frequency scaling, caches, allocator...
+//!
+//! The secret dependent group operation (multiplication or addition of the
+//! message) is deliberately leaky, while the squaring (or doubling) uses the
+//! plain `BigUint` arithmetic. Modular multiplication is implemented with the
+//! interleaved "shift and add" method, whose duration depends on the Hamming
+//! weight of the second operand, and every modular reduction is a conditional
+//! subtraction. Additions only leak through the conditional subtraction, so the
+//! double and add operation is expected to be much harder to attack than square
+//! and multiply.
+//!
+//! Frequency scaling adds a noise proportional to the measured duration, which
+//! favours the hypotheses whose emulated time is closer to the victim one. On
+//! noisy hosts (e.g. virtual machines) it may outweigh the leakage of a single
+//! multiplication and the attack fails.
+//!
+//! Note that the squaring can't leak through the same operand dependent
+//! duration: a wrong guessed multiplication would then take exactly the time of
+//! the victim next squaring, as both process the same intermediate value.
+
+use num_bigint::BigUint;
+use std::{hint::black_box, time::Instant};
+
+/// Time source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Clock {
+    /// Monotonic OS clock, in nanoseconds.
+    Instant,
+    /// CPU time stamp counter, in cycles.
+    ///
+    /// Falls back to [`Clock::Instant`] on architectures other than x86-64.
+    Rdtsc,
+}
+
+/// Latency measurement configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timer {
+    pub clock: Clock,
+    /// Executions per measurement.
+    pub repetitions: usize,
+}
+
+impl Default for Timer {
+    fn default() -> Self {
+        Timer {
+            clock: Clock::Instant,
+            repetitions: 20,
+        }
+    }
+}
+
+impl Timer {
+    /// Latency of `f`.
+    ///
+    /// Minimum over the repetitions: the operands dependent duration is
+    /// deterministic, while interrupts and cache misses only add up.
+    pub fn measure<R, F: FnMut() -> R>(&self, mut f: F) -> f64 {
+        (0..self.repetitions.max(1))
+            .map(|_| match self.clock {
+                #[cfg(target_arch = "x86_64")]
+                Clock::Rdtsc => {
+                    let start = rdtsc();
+                    black_box(f());
+                    rdtsc().wrapping_sub(start) as f64
+                }
+                _ => {
+                    let start = Instant::now();
+                    black_box(f());
+                    start.elapsed().as_nanos() as f64
+                }
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn rdtsc() -> u64 {
+    // SAFETY: the instruction is available on every x86-64 CPU.
+    unsafe { core::arch::x86_64::_rdtsc() }
+}
+
+/// `(x + y) mod p`, for `x, y < p`.
+pub fn add_mod(x: &BigUint, y: &BigUint, p: &BigUint) -> BigUint {
+    let r = x + y;
+    if &r >= p {
+        r - p
+    } else {
+        r
+    }
+}
+
+/// `x·y mod p`, for `x, y < p`.
+///
+/// Shift and add over the bits of `y`: one doubling per bit plus one addition
+/// per set bit.
+pub fn mul_mod(x: &BigUint, y: &BigUint, p: &BigUint) -> BigUint {
+    let mut r = BigUint::from(0_u8);
+    for i in (0..y.bits()).rev() {
+        r = add_mod(&r, &r, p);
+        if y.bit(i) {
+            r = add_mod(&r, x, p);
+        }
+    }
+    r
+}
+
+/// `m^d mod p` using square and multiply.
+///
+/// Multiplications scan the bits of the intermediate result, otherwise all of
+/// them would take the same time for a given message.
+pub fn square_and_multiply(m: &BigUint, d: &BigUint, p: &BigUint) -> BigUint {
+    let m = m % p;
+    let mut r = BigUint::from(1_u8) % p;
+    for i in (0..d.bits()).rev() {
+        r = &r * &r % p;
+        if d.bit(i) {
+            r = mul_mod(&m, &r, p);
+        }
+    }
+    r
+}
+
+/// `d·m mod p` using double and add.
+pub fn double_and_add(m: &BigUint, d: &BigUint, p: &BigUint) -> BigUint {
+    let m = m % p;
+    let mut r = BigUint::from(0_u8);
+    for i in (0..d.bits()).rev() {
+        r = (&r << 1) % p;
+        if d.bit(i) {
+            r = add_mod(&r, &m, p);
+        }
+    }
+    r
+}
+
+/// Montgomery ladder over `nbits` bits using the group operation `op`.
+///
+/// The operations sequence is fixed, but the operands dependent durations of
+/// the group operations are still there.
+pub fn montgomery_ladder<G>(
+    m: &BigUint,
+    d: &BigUint,
+    nbits: u64,
+    identity: BigUint,
+    op: G,
+) -> BigUint
+where
+    G: Fn(&BigUint, &BigUint) -> BigUint,
+{
+    let (mut r0, mut r1) = (identity, m.clone());
+    for i in (0..nbits).rev() {
+        if d.bit(i) {
+            r0 = op(&r0, &r1);
+            r1 = op(&r1, &r1);
+        } else {
+            r1 = op(&r0, &r1);
+            r0 = op(&r0, &r0);
+        }
+    }
+    r0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_modulus;
+    use num_bigint::RandBigInt;
+
+    #[test]
+    fn operations_are_correct() {
+        let p = get_modulus(64);
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let m = rng.gen_biguint_below(&p);
+            let d = rng.gen_biguint(64);
+            let one = BigUint::from(1_u8);
+            assert_eq!(square_and_multiply(&m, &d, &p), m.modpow(&d, &p));
+            assert_eq!(double_and_add(&m, &d, &p), &d * &m % &p);
+            let ladder = montgomery_ladder(&m, &d, 64, one, |x, y| mul_mod(x, y, &p));
+            assert_eq!(ladder, m.modpow(&d, &p));
+        }
+    }
+
+    #[test]
+    fn timer_measures_work() {
+        for clock in [Clock::Instant, Clock::Rdtsc] {
+            let timer = Timer {
+                clock,
+                repetitions: 3,
+            };
+            let p = get_modulus(256);
+            let t = timer.measure(|| square_and_multiply(&p, &p, &p));
+            assert!(t > 0.0 && t.is_finite());
+        }
+    }
+}
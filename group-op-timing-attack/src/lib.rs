@@ -0,0 +1,811 @@
+//! Timing attack simulation for a device implementing some form of group operation
+//! not in constant time.
+//!
+//! For example, given a message `m` and a secret `d`, it can simulate:
+//! - m^d mod n using "square and multiply"
+//! - d·m mod n using "double and add"
+//!
+//! The secret is recovered using the
+//! [variance difference strategy](https://datawok.net/posts/timing-attack).
+//!
+//! It is a probabilistic attack in nature, so you may not be successfull on the
+//! first run. To improve the odds, the sample size of each bit is increased
+//! until the guess is statistically significant and, if the confidence
+//! collapses, the previous bit is re-examined (see
+//! [`recover_secret_with_progress`]).
+//!
+//! The execution times of group operations are not fixed but vary with the value
+//! of `m`. If `m` is chosen randomly, these times follow a Gaussian distribution
+//! with a configurable mean μ and standard deviation σ (with default μ = 1000
+//! and σ = 50, see [`AttackConfig`]).
+//!
+//! Alternatively, the target can run a real non constant time implementation
+//! whose latency is measured on the running machine (see [`Timing`]), to check
+//! whether the attack survives the real measurement noise.
+//!
+//! The simulated victim can also implement some classic defences (see
+//! [`Countermeasure`]) to show the attack failing against them.
+//!
+//! The attack only needs a [`TimingOracle`], thus it can be pointed to any
+//! target (simulated or real) as far as the attacker is able to emulate the
+//! target timings for a guessed secret.
+
+pub mod hardware;
+pub mod stats;
+pub mod trace;
+
+use hardware::Timer;
+use num_bigint::{BigUint, RandBigInt};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use rand_distr::{Distribution, Normal};
+use stats::variance_difference;
+use std::{io, str::FromStr, sync::Mutex};
+use trace::{BitStats, Sample, TraceWriter};
+
+/// Target leaking the duration of the secret dependent operation.
+pub trait TimingOracle {
+    /// Time taken to process the message `m`.
+    fn sign(&self, m: &BigUint) -> f64;
+}
+
+/// Modulus used to reduce the group operation result for the supported key
+/// lengths (8, 16, 32, 64, 128 and 256 bits).
+pub fn get_modulus(keylen: u64) -> BigUint {
+    match keylen {
+        8 => BigUint::from(61_u8),
+        16 => BigUint::from(53759_u16),
+        32 => BigUint::from(2675797811_u32),
+        64 => BigUint::from(8642890157798231327_u64),
+        128 => BigUint::from(249018405283997733407297959207515566297_u128),
+        256 => BigUint::from_str(
+            "44836394558820158783687605622545866580915032641323282158738215690847176590297",
+        )
+        .unwrap(),
+        _ => panic!("Not supported keylen"),
+    }
+}
+
+/// Secret dependent group operation run by the target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    /// `m^d mod p`, multiplicative group.
+    SquareAndMultiply,
+    /// `d·m mod p`, additive group.
+    DoubleAndAdd,
+}
+
+impl Operation {
+    /// Simulated operation duration, with each group operation duration
+    /// sampled from `noise`.
+    pub fn time(self, m: &BigUint, d: &BigUint, p: &BigUint, noise: &Normal<f64>) -> f64 {
+        match self {
+            Operation::SquareAndMultiply => square_and_multiply(m, d, p, noise),
+            Operation::DoubleAndAdd => double_and_add(m, d, p, noise),
+        }
+    }
+
+    /// Order of the group, for a prime modulus `p`.
+    pub fn group_order(self, p: &BigUint) -> BigUint {
+        match self {
+            Operation::SquareAndMultiply => p - 1_u8,
+            Operation::DoubleAndAdd => p.clone(),
+        }
+    }
+
+    /// Measured latency of the real operation.
+    pub fn measure(self, m: &BigUint, d: &BigUint, p: &BigUint, timer: &Timer) -> f64 {
+        match self {
+            Operation::SquareAndMultiply => {
+                timer.measure(|| hardware::square_and_multiply(m, d, p))
+            }
+            Operation::DoubleAndAdd => timer.measure(|| hardware::double_and_add(m, d, p)),
+        }
+    }
+
+    /// Simulated constant time Montgomery ladder over `nbits` bits.
+    pub fn ladder_time(
+        self,
+        m: &BigUint,
+        d: &BigUint,
+        p: &BigUint,
+        nbits: u64,
+        noise: &Normal<f64>,
+    ) -> f64 {
+        match self {
+            Operation::SquareAndMultiply => {
+                montgomery_ladder(m, d, nbits, noise, BigUint::from(1_u8), |x, y| x * y % p)
+            }
+            Operation::DoubleAndAdd => {
+                montgomery_ladder(m, d, nbits, noise, BigUint::from(0_u8), |x, y| (x + y) % p)
+            }
+        }
+    }
+
+    /// Measured latency of the real Montgomery ladder over `nbits` bits.
+    pub fn measure_ladder(
+        self,
+        m: &BigUint,
+        d: &BigUint,
+        p: &BigUint,
+        nbits: u64,
+        timer: &Timer,
+    ) -> f64 {
+        match self {
+            Operation::SquareAndMultiply => timer.measure(|| {
+                hardware::montgomery_ladder(m, d, nbits, BigUint::from(1_u8), |x, y| {
+                    hardware::mul_mod(x, y, p)
+                })
+            }),
+            Operation::DoubleAndAdd => timer.measure(|| {
+                hardware::montgomery_ladder(m, d, nbits, BigUint::from(0_u8), |x, y| {
+                    hardware::add_mod(x, y, p)
+                })
+            }),
+        }
+    }
+}
+
+/// Source of the target operation durations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timing {
+    /// Group operations durations sampled from the configured Gaussian.
+    Simulated,
+    /// Latency of the real operation measured on the running machine.
+    Measured(Timer),
+}
+
+// Montgomery ladder using the given group operation.
+//
+// The two group operations per bit are always executed and, being implemented
+// in constant time, their durations only depend on the public message.
+fn montgomery_ladder<G>(
+    m: &BigUint,
+    d: &BigUint,
+    nbits: u64,
+    noise: &Normal<f64>,
+    identity: BigUint,
+    op: G,
+) -> f64
+where
+    G: Fn(&BigUint, &BigUint) -> BigUint,
+{
+    let (mut r0, mut r1) = (identity, m.clone());
+    let mut delay = 0.0;
+
+    let seed = m.iter_u64_digits().next().unwrap_or_default();
+    let mut rng = ChaCha20Rng::seed_from_u64(seed);
+
+    for i in (0..nbits).rev() {
+        if d.bit(i) {
+            r0 = op(&r0, &r1);
+            r1 = op(&r1, &r1);
+        } else {
+            r1 = op(&r0, &r1);
+            r0 = op(&r0, &r0);
+        }
+        delay += noise.sample(&mut rng) + noise.sample(&mut rng);
+    }
+    delay
+}
+
+// Left to right binary method using the given group "double" and "add".
+//
+// Each operation duration is sampled from the `noise` distribution seeded with
+// the operation input.
+fn binary_method<D, A>(m: &BigUint, d: &BigUint, noise: &Normal<f64>, double: D, add: A) -> f64
+where
+    D: Fn(&BigUint) -> BigUint,
+    A: Fn(&BigUint) -> BigUint,
+{
+    let mut res = BigUint::from(1u64);
+    let mut delay = 0.0;
+
+    let seed = m.iter_u64_digits().next().unwrap_or_default();
+    let mut rng = ChaCha20Rng::seed_from_u64(seed);
+
+    let nbits = d.bits().max(1);
+    for i in 1..=nbits {
+        res = double(&res);
+        delay += noise.sample(&mut rng);
+        if d.bit(nbits - i) {
+            res = add(&res);
+            let seed = res.iter_u64_digits().next().unwrap_or_default();
+            let mut rng = ChaCha20Rng::seed_from_u64(seed);
+            delay += noise.sample(&mut rng);
+        }
+    }
+    delay
+}
+
+/// Simulated `m^d mod p` using square and multiply.
+///
+/// Returns the operation duration.
+pub fn square_and_multiply(m: &BigUint, d: &BigUint, p: &BigUint, noise: &Normal<f64>) -> f64 {
+    binary_method(m, d, noise, |x| x.pow(2) % p, |x| x * m % p)
+}
+
+/// Simulated `d·m mod p` using double and add.
+///
+/// Returns the operation duration.
+pub fn double_and_add(m: &BigUint, d: &BigUint, p: &BigUint, noise: &Normal<f64>) -> f64 {
+    binary_method(m, d, noise, |x| (x << 1) % p, |x| (x + m) % p)
+}
+
+/// Timing attack countermeasure implemented by the victim.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Countermeasure {
+    /// Naive implementation.
+    None,
+    /// The operation is applied to a message randomized with a fresh `r`
+    /// (`m·r` or `m + r`) and the result is then corrected with a precomputed
+    /// `r^-d` (or `-d·r`). The attacker can't emulate the intermediate values.
+    MessageBlinding,
+    /// The operation uses `d + k·ord` with a fresh random `k`, so the
+    /// processed bits change at every call.
+    ExponentBlinding,
+    /// Montgomery ladder: constant operations count, with operations
+    /// durations independent of the operands.
+    MontgomeryLadder,
+}
+
+/// Simulated device holding a secret exponent.
+pub struct VictimDevice {
+    modulus: BigUint,
+    secret: BigUint,
+    op: Operation,
+    noise: Normal<f64>,
+    timing: Timing,
+    keylen: u64,
+    countermeasure: Countermeasure,
+    // Blinding factors generator
+    rng: Mutex<ChaCha20Rng>,
+}
+
+impl VictimDevice {
+    /// Device with a random `keylen` bits secret (most significant bit set),
+    /// running the operation and the noise model of `config`.
+    pub fn new(seed: u64, config: &AttackConfig) -> Self {
+        Self::with_countermeasure(seed, config, Countermeasure::None)
+    }
+
+    /// Same as [`VictimDevice::new`] with the given countermeasure.
+    pub fn with_countermeasure(
+        seed: u64,
+        config: &AttackConfig,
+        countermeasure: Countermeasure,
+    ) -> Self {
+        let mut rng = ChaCha20Rng::seed_from_u64(seed);
+        let mut secret = rng.gen_biguint(config.keylen);
+        secret.set_bit(config.keylen - 1, true);
+        VictimDevice {
+            modulus: config.modulus.clone(),
+            secret,
+            op: config.op,
+            noise: config.noise(),
+            timing: config.timing,
+            keylen: config.keylen,
+            countermeasure,
+            rng: Mutex::new(rng),
+        }
+    }
+
+    pub fn countermeasure(&self) -> Countermeasure {
+        self.countermeasure
+    }
+
+    /// Device secret, to check the attack outcome.
+    pub fn secret(&self) -> &BigUint {
+        &self.secret
+    }
+
+    fn time(&self, m: &BigUint, d: &BigUint) -> f64 {
+        match &self.timing {
+            Timing::Simulated => self.op.time(m, d, &self.modulus, &self.noise),
+            Timing::Measured(timer) => self.op.measure(m, d, &self.modulus, timer),
+        }
+    }
+}
+
+impl TimingOracle for VictimDevice {
+    fn sign(&self, m: &BigUint) -> f64 {
+        let p = &self.modulus;
+        let mut rng = self.rng.lock().unwrap();
+        // Unblinding cost is constant, thus not accounted.
+        match self.countermeasure {
+            Countermeasure::None => self.time(m, &self.secret),
+            Countermeasure::MessageBlinding => {
+                let r = rng.gen_biguint_range(&BigUint::from(1_u8), p);
+                let m = match self.op {
+                    Operation::SquareAndMultiply => m * r % p,
+                    Operation::DoubleAndAdd => (m + r) % p,
+                };
+                self.time(&m, &self.secret)
+            }
+            Countermeasure::ExponentBlinding => {
+                let k = rng.gen_biguint(32);
+                let d = &self.secret + k * self.op.group_order(p);
+                self.time(m, &d)
+            }
+            Countermeasure::MontgomeryLadder => match &self.timing {
+                Timing::Simulated => {
+                    self.op
+                        .ladder_time(m, &self.secret, p, self.keylen, &self.noise)
+                }
+                Timing::Measured(timer) => {
+                    self.op
+                        .measure_ladder(m, &self.secret, p, self.keylen, timer)
+                }
+            },
+        }
+    }
+}
+
+/// Attacker emulation of the target.
+pub struct AttackerDevice {
+    modulus: BigUint,
+    op: Operation,
+    noise: Normal<f64>,
+    timing: Timing,
+}
+
+impl AttackerDevice {
+    pub fn new(config: &AttackConfig) -> Self {
+        AttackerDevice {
+            modulus: config.modulus.clone(),
+            op: config.op,
+            noise: config.noise(),
+            timing: config.timing,
+        }
+    }
+
+    /// Time taken by the target to process `m` if its secret was `d`.
+    ///
+    /// With [`Timing::Measured`] the attacker runs the same implementation
+    /// and measures it.
+    pub fn sign(&self, m: &BigUint, d: &BigUint) -> f64 {
+        match &self.timing {
+            Timing::Simulated => self.op.time(m, d, &self.modulus, &self.noise),
+            Timing::Measured(timer) => self.op.measure(m, d, &self.modulus, timer),
+        }
+    }
+}
+
+/// Attack configuration.
+#[derive(Debug, Clone)]
+pub struct AttackConfig {
+    /// Secret length in bits.
+    pub keylen: u64,
+    /// Timing samples batch size, must be positive.
+    ///
+    /// The more bits is the key the more this value should be.
+    /// E.g. 64 -> 1000, 128 -> 4000, 256 -> 10000
+    pub iters: usize,
+    /// Max timing samples per recovered bit.
+    ///
+    /// Batches of `iters` samples are collected until the guess reaches the
+    /// required `confidence` or this limit is exceeded.
+    pub max_iters: usize,
+    /// Required confidence, as z-score of the variance difference.
+    ///
+    /// Zero disables both the adaptive sample size and the backtracking.
+    pub confidence: f64,
+    /// Max number of times a previous bit is re-examined.
+    pub max_backtracks: usize,
+    /// Mean duration of a single group operation.
+    pub mu: f64,
+    /// Standard deviation of a single group operation duration.
+    pub sigma: f64,
+    /// Seed of the attacker messages generator. Random if `None`.
+    pub seed: Option<u64>,
+    /// Operation run by the target.
+    pub op: Operation,
+    /// Modulus used by the target.
+    pub modulus: BigUint,
+    /// Source of the operation durations.
+    ///
+    /// `mu` and `sigma` are ignored by [`Timing::Measured`].
+    pub timing: Timing,
+}
+
+impl AttackConfig {
+    /// Default configuration using the builtin modulus for `keylen`.
+    pub fn new(keylen: u64, op: Operation) -> Self {
+        AttackConfig {
+            keylen,
+            iters: 1000,
+            max_iters: 10000,
+            confidence: 3.0,
+            max_backtracks: 8,
+            mu: 1000.0,
+            sigma: 50.0,
+            seed: None,
+            op,
+            modulus: get_modulus(keylen),
+            timing: Timing::Simulated,
+        }
+    }
+
+    /// Group operation duration distribution.
+    ///
+    /// Panics if `sigma` is not a valid standard deviation.
+    pub fn noise(&self) -> Normal<f64> {
+        Normal::new(self.mu, self.sigma).expect("Invalid noise parameters")
+    }
+}
+
+/// Attack progress event.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Progress {
+    /// Next bit guessed.
+    Bit {
+        value: bool,
+        /// Timing samples used for the guess.
+        samples: usize,
+        /// Variance difference z-score.
+        confidence: f64,
+    },
+    /// The last guessed bit was wrong and has been retracted.
+    ///
+    /// A new `Bit` event with the corrected value follows.
+    Backtrack,
+}
+
+// Outcome of the measurements for one bit.
+struct Guess {
+    value: bool,
+    samples: usize,
+    confidence: f64,
+}
+
+struct Attack<'a, O, T: ?Sized> {
+    oracle: &'a O,
+    attacker: AttackerDevice,
+    config: &'a AttackConfig,
+    rng: ChaCha20Rng,
+    trace: &'a mut T,
+}
+
+impl<O: TimingOracle, T: TraceWriter + ?Sized> Attack<'_, O, T> {
+    // Guess the least significant bit of `prefix`, the `bit`-th of the secret.
+    //
+    // New samples are appended to `deltas` until the guess is confident or
+    // `limit` samples are collected.
+    fn guess(
+        &mut self,
+        bit: u64,
+        prefix: &mut BigUint,
+        deltas: &mut Vec<(f64, f64)>,
+        limit: usize,
+    ) -> io::Result<Guess> {
+        let config = self.config;
+        loop {
+            for _ in 0..config.iters {
+                let m = self.rng.gen_biguint(config.keylen);
+
+                let t_vic = self.oracle.sign(&m);
+
+                // Attempt with i-th bit = 0
+                prefix.set_bit(0, false);
+                let t_att0 = self.attacker.sign(&m, prefix);
+
+                // Attempt with i-th bit = 1
+                prefix.set_bit(0, true);
+                let t_att1 = self.attacker.sign(&m, prefix);
+
+                self.trace.sample(&Sample {
+                    bit,
+                    message: &m,
+                    victim_time: t_vic,
+                    attacker_time0: t_att0,
+                    attacker_time1: t_att1,
+                })?;
+                deltas.push((t_vic - t_att0, t_vic - t_att1));
+            }
+            let (var0, var1, z) = variance_difference(deltas);
+            if z.abs() >= config.confidence || deltas.len() >= limit {
+                let value = var0 >= var1;
+                prefix.set_bit(0, value);
+                let guess = Guess {
+                    value,
+                    samples: deltas.len(),
+                    confidence: z.abs(),
+                };
+                self.trace.bit(&BitStats {
+                    bit,
+                    value,
+                    samples: guess.samples,
+                    var0,
+                    var1,
+                    confidence: guess.confidence,
+                })?;
+                return Ok(guess);
+            }
+        }
+    }
+}
+
+/// Recover the `config.keylen` bits secret of the `oracle`.
+///
+/// Bits are recovered from the most significant one.
+pub fn recover_secret<O: TimingOracle>(oracle: &O, config: &AttackConfig) -> BigUint {
+    recover_secret_with_progress(oracle, config, |_| ())
+}
+
+/// Same as [`recover_secret`], calling `on_progress` as soon as each bit is
+/// guessed or retracted.
+///
+/// A wrong bit poisons all the subsequent ones: with a wrong prefix both
+/// hypotheses mismatch the victim and their variances get close. Thus, when
+/// the confidence of a guess collapses even after `config.max_iters` samples,
+/// the previous bit is re-examined with `config.max_iters` additional samples
+/// and flipped if required.
+pub fn recover_secret_with_progress<O, F>(
+    oracle: &O,
+    config: &AttackConfig,
+    on_progress: F,
+) -> BigUint
+where
+    O: TimingOracle,
+    F: FnMut(Progress),
+{
+    recover_secret_with_trace(oracle, config, on_progress, &mut ()).expect("Null trace can't fail")
+}
+
+/// Same as [`recover_secret_with_progress`], recording every timing sample and
+/// the per-bit statistics to `trace`.
+///
+/// Fails only if the trace can't be written. Panics if `config.iters` is
+/// zero, as no guess could ever be made.
+pub fn recover_secret_with_trace<O, F, T>(
+    oracle: &O,
+    config: &AttackConfig,
+    mut on_progress: F,
+    trace: &mut T,
+) -> io::Result<BigUint>
+where
+    O: TimingOracle,
+    F: FnMut(Progress),
+    T: TraceWriter + ?Sized,
+{
+    assert!(config.iters > 0, "Empty samples batch");
+    let rng = match config.seed {
+        Some(seed) => ChaCha20Rng::seed_from_u64(seed),
+        None => ChaCha20Rng::from_entropy(),
+    };
+    let mut attack = Attack {
+        oracle,
+        attacker: AttackerDevice::new(config),
+        config,
+        rng,
+        trace,
+    };
+    let emit = |f: &mut F, g: &Guess| {
+        f(Progress::Bit {
+            value: g.value,
+            samples: g.samples,
+            confidence: g.confidence,
+        })
+    };
+
+    // Recovered secret
+    let mut recovered = BigUint::from(0_u64);
+    let mut backtracks = 0;
+    // Samples of the previous bit, kept to be extended on re-examination
+    let mut prev_deltas = Vec::new();
+
+    for i in 0..config.keylen {
+        recovered <<= 1;
+        let mut deltas = Vec::with_capacity(config.iters);
+        let mut guess = attack.guess(i, &mut recovered, &mut deltas, config.max_iters)?;
+        if guess.confidence < config.confidence && i > 0 && backtracks < config.max_backtracks {
+            backtracks += 1;
+            let mut prefix: BigUint = &recovered >> 1;
+            let prev = prefix.bit(0);
+            let limit = prev_deltas.len() + config.max_iters;
+            let check = attack.guess(i - 1, &mut prefix, &mut prev_deltas, limit)?;
+            if check.value != prev {
+                on_progress(Progress::Backtrack);
+                emit(&mut on_progress, &check);
+                recovered = prefix << 1;
+                deltas.clear();
+                guess = attack.guess(i, &mut recovered, &mut deltas, config.max_iters)?;
+            }
+        }
+        emit(&mut on_progress, &guess);
+        prev_deltas = deltas;
+    }
+    attack.trace.finish()?;
+    Ok(recovered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recover_32_bits_secret(op: Operation) {
+        let config = AttackConfig {
+            iters: 4000,
+            seed: Some(0),
+            ..AttackConfig::new(32, op)
+        };
+        let victim = VictimDevice::new(42, &config);
+        let recovered = recover_secret(&victim, &config);
+        assert_eq!(&recovered, victim.secret());
+    }
+
+    #[test]
+    fn recover_square_and_multiply_secret() {
+        recover_32_bits_secret(Operation::SquareAndMultiply);
+    }
+
+    #[test]
+    fn recover_double_and_add_secret() {
+        recover_32_bits_secret(Operation::DoubleAndAdd);
+    }
+
+    #[test]
+    fn custom_oracle() {
+        // User supplied target, e.g. wrapping a real device measurement.
+        struct Target(BigUint);
+        impl TimingOracle for Target {
+            fn sign(&self, m: &BigUint) -> f64 {
+                let noise = Normal::new(1000.0, 50.0).unwrap();
+                square_and_multiply(m, &self.0, &get_modulus(16), &noise)
+            }
+        }
+        let secret = BigUint::from(0xbeef_u32);
+        let config = AttackConfig {
+            iters: 4000,
+            seed: Some(0),
+            ..AttackConfig::new(16, Operation::SquareAndMultiply)
+        };
+        let recovered = recover_secret(&Target(secret.clone()), &config);
+        assert_eq!(recovered, secret);
+    }
+
+    #[test]
+    fn success_rate_over_random_secrets() {
+        // Undersized batches: without adaptive sampling most runs fail.
+        let config = AttackConfig {
+            iters: 250,
+            seed: Some(3),
+            ..AttackConfig::new(32, Operation::SquareAndMultiply)
+        };
+        let runs = 20;
+        let success = (0..runs)
+            .filter(|&seed| {
+                let victim = VictimDevice::new(seed, &config);
+                &recover_secret(&victim, &config) == victim.secret()
+            })
+            .count();
+        assert!(
+            success >= runs as usize * 9 / 10,
+            "success: {success}/{runs}"
+        );
+    }
+
+    #[test]
+    fn backtracking_events_replay_to_secret() {
+        // Starved sample budget to force some low confidence guesses
+        let config = AttackConfig {
+            iters: 250,
+            max_iters: 250,
+            seed: Some(1),
+            ..AttackConfig::new(32, Operation::DoubleAndAdd)
+        };
+        let (mut bits, mut backtracks) = (Vec::new(), 0);
+        for seed in 0..10 {
+            let victim = VictimDevice::new(seed, &config);
+            let recovered = recover_secret_with_progress(&victim, &config, |p| match p {
+                Progress::Bit { value, .. } => bits.push(value),
+                Progress::Backtrack => {
+                    backtracks += 1;
+                    bits.pop();
+                }
+            });
+            let replay = bits.drain(..).fold(BigUint::from(0_u8), |acc, b| {
+                acc << 1 | BigUint::from(b as u8)
+            });
+            assert_eq!(replay, recovered);
+        }
+        assert!(backtracks > 0);
+    }
+
+    #[test]
+    fn countermeasures_defeat_the_attack() {
+        for op in [Operation::SquareAndMultiply, Operation::DoubleAndAdd] {
+            let config = AttackConfig {
+                max_iters: 2000,
+                seed: Some(0),
+                ..AttackConfig::new(16, op)
+            };
+            for countermeasure in [
+                Countermeasure::None,
+                Countermeasure::MessageBlinding,
+                Countermeasure::ExponentBlinding,
+                Countermeasure::MontgomeryLadder,
+            ] {
+                let victim = VictimDevice::with_countermeasure(5, &config, countermeasure);
+                let recovered = recover_secret(&victim, &config);
+                let success = &recovered == victim.secret();
+                assert_eq!(success, countermeasure == Countermeasure::None);
+            }
+        }
+    }
+
+    #[test]
+    fn ladder_is_constant_time() {
+        let p = get_modulus(32);
+        let noise = Normal::new(1000.0, 50.0).unwrap();
+        let m = BigUint::from(12345_u32);
+        for op in [Operation::SquareAndMultiply, Operation::DoubleAndAdd] {
+            let t1 = op.ladder_time(&m, &BigUint::from(0x80000000_u32), &p, 32, &noise);
+            let t2 = op.ladder_time(&m, &BigUint::from(0xffffffff_u32), &p, 32, &noise);
+            assert_eq!(t1, t2);
+        }
+    }
+
+    #[test]
+    fn trace_records_every_sample() {
+        struct Counter(usize, Vec<BitStats>);
+        impl TraceWriter for Counter {
+            fn sample(&mut self, _: &Sample) -> io::Result<()> {
+                self.0 += 1;
+                Ok(())
+            }
+            fn bit(&mut self, stats: &BitStats) -> io::Result<()> {
+                self.1.push(*stats);
+                Ok(())
+            }
+        }
+        // Without backtracking each bit is traced exactly once
+        let config = AttackConfig {
+            seed: Some(2),
+            max_backtracks: 0,
+            ..AttackConfig::new(8, Operation::SquareAndMultiply)
+        };
+        let victim = VictimDevice::new(3, &config);
+        let mut trace = Counter(0, Vec::new());
+        let mut samples = 0;
+        let recovered = recover_secret_with_trace(
+            &victim,
+            &config,
+            |p| {
+                if let Progress::Bit { samples: n, .. } = p {
+                    samples += n
+                }
+            },
+            &mut trace,
+        )
+        .unwrap();
+        assert_eq!(&recovered, victim.secret());
+        assert_eq!(trace.0, samples);
+        assert_eq!(trace.0, trace.1.iter().map(|b| b.samples).sum::<usize>());
+        assert!(trace.1.iter().map(|b| b.bit).eq(0..8));
+        assert!(trace.1.iter().all(|b| (b.var0 >= b.var1) == b.value));
+    }
+
+    #[test]
+    #[should_panic(expected = "Empty samples batch")]
+    fn empty_batches_are_rejected() {
+        let config = AttackConfig {
+            iters: 0,
+            ..AttackConfig::new(8, Operation::SquareAndMultiply)
+        };
+        recover_secret(&VictimDevice::new(1, &config), &config);
+    }
+
+    #[test]
+    fn seeded_runs_are_reproducible() {
+        let config = AttackConfig {
+            iters: 100,
+            sigma: 200.0,
+            seed: Some(7),
+            ..AttackConfig::new(16, Operation::DoubleAndAdd)
+        };
+        let victim = VictimDevice::new(1, &config);
+        let first = recover_secret(&victim, &config);
+        assert_eq!(recover_secret(&victim, &config), first);
+    }
+}
@@ -1,165 +1,215 @@
-//! Timing attack simulation for a device implementing some form of group operation
-//! not in constant time.
-//!
-//! For example, given a message `m` and a secret `d`, it can simulate:
-//! - m^d mod n using "square and multiply"
-//! - d·m mod n using "double and add"
-//!
-//! The secret is recovered using the
-//! [variance difference strategy](https://datawok.net/posts/timing-attack).
-//!
-//! It is a probabilistic attack in nature, so you may not be successfull on the
-//! first run.
-//!
-//! The execution times of group operations are not fixed but vary with the value
-//! of `m`. If `m` is chosen randomly, these times follow a Gaussian distribution
-//! with a configurable mean μ and standard deviation σ (with default μ = 1000
-//! and σ = 50).
-
-use num_bigint::{BigUint, RandBigInt};
-use rand::{Rng, SeedableRng};
-use rand_chacha::ChaCha20Rng;
-use rand_distr::{Distribution, Normal};
-use std::io::{self, Write};
-use std::str::FromStr;
-
-fn get_modulus(keylen: u64) -> BigUint {
-    match keylen {
-        8 => BigUint::from(61_u8),
-        16 => BigUint::from(53759_u16),
-        32 => BigUint::from(2675797811_u32),
-        64 => BigUint::from(8642890157798231327_u64),
-        128 => BigUint::from(249018405283997733407297959207515566297_u128),
-        256 => BigUint::from_str(
-            "44836394558820158783687605622545866580915032641323282158738215690847176590297",
-        )
-        .unwrap(),
-        _ => panic!("Not supported keylen"),
-    }
+use clap::{builder::PossibleValuesParser, builder::TypedValueParser, Parser, ValueEnum};
+use group_op_timing_attack::{
+    get_modulus,
+    hardware::{Clock, Timer},
+    recover_secret_with_trace,
+    trace::{CsvTrace, JsonTrace, TraceWriter},
+    AttackConfig, Countermeasure, Operation, Progress, Timing, VictimDevice,
+};
+use rand::Rng;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Op {
+    /// m^d mod n
+    SquareAndMultiply,
+    /// d·m mod n
+    DoubleAndAdd,
 }
 
-fn square_and_multiply(m: &BigUint, d: &BigUint, p: &BigUint) -> f64 {
-    let mut res = BigUint::from(1u64);
-    let mut delay = 0.0;
-
-    let seed = m.iter_u64_digits().next().unwrap();
-    let mut rng = ChaCha20Rng::seed_from_u64(seed);
-
-    let normal = Normal::new(1000.0, 50.0).unwrap();
-
-    let mut nbits = d.bits();
-    if nbits == 0 {
-        nbits = 1
-    }
-    for i in 1..=nbits {
-        res = (res.pow(2)) % p;
-        delay += normal.sample(&mut rng);
-        if d.bit(nbits - i) {
-            res = (res * m) % p;
-            let seed = res.iter_u64_digits().next().unwrap();
-            let mut rng = ChaCha20Rng::seed_from_u64(seed);
-            delay += normal.sample(&mut rng);
-        }
-    }
-    delay
+#[derive(Clone, Copy, ValueEnum)]
+enum TimeSource {
+    /// Gaussian group operations durations
+    Simulated,
+    /// Real operations latency measured with the OS clock
+    Instant,
+    /// Real operations latency measured with the CPU time stamp counter
+    Rdtsc,
 }
 
-struct VictimDevice {
-    modulus: BigUint,
-    secret: BigUint,
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Defence {
+    /// Naive victim
+    None,
+    /// Randomized message
+    MessageBlinding,
+    /// Randomized exponent
+    ExponentBlinding,
+    /// Constant time Montgomery ladder
+    MontgomeryLadder,
+    /// Attack each of the above in turn
+    All,
 }
 
-impl VictimDevice {
-    pub fn new(seed: u64, keylen: u64) -> Self {
-        let mut rng = ChaCha20Rng::seed_from_u64(seed);
-        let mut secret = rng.gen_biguint(keylen);
-        secret.set_bit(keylen - 1, true);
-        VictimDevice {
-            modulus: get_modulus(keylen),
-            secret,
+impl Defence {
+    fn countermeasures(self) -> Vec<Countermeasure> {
+        match self {
+            Defence::None => vec![Countermeasure::None],
+            Defence::MessageBlinding => vec![Countermeasure::MessageBlinding],
+            Defence::ExponentBlinding => vec![Countermeasure::ExponentBlinding],
+            Defence::MontgomeryLadder => vec![Countermeasure::MontgomeryLadder],
+            Defence::All => vec![
+                Countermeasure::None,
+                Countermeasure::MessageBlinding,
+                Countermeasure::ExponentBlinding,
+                Countermeasure::MontgomeryLadder,
+            ],
         }
     }
-
-    pub fn sign(&self, m: &BigUint) -> f64 {
-        square_and_multiply(&m, &self.secret, &self.modulus)
-    }
 }
 
-struct AttackerDevice {
-    modulus: BigUint,
+#[derive(Parser)]
+#[command(about = "Variance difference timing attack simulation")]
+struct Cli {
+    /// Secret length in bits.
+    #[arg(
+        short,
+        long,
+        default_value = "64",
+        value_parser = PossibleValuesParser::new(["8", "16", "32", "64", "128", "256"])
+            .map(|s| s.parse::<u64>().unwrap()),
+    )]
+    keylen: u64,
+    /// Timing samples batch size.
+    #[arg(short, long, default_value_t = 1000, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    iters: usize,
+    /// Max timing samples per recovered bit.
+    #[arg(long, default_value_t = 10000)]
+    max_iters: usize,
+    /// Required z-score of each guess (0 to disable adaptive sampling and
+    /// backtracking).
+    #[arg(short, long, default_value_t = 3.0)]
+    confidence: f64,
+    /// Mean duration of a group operation.
+    #[arg(long, default_value_t = 1000.0)]
+    mu: f64,
+    /// Standard deviation of a group operation duration.
+    #[arg(long, default_value_t = 50.0)]
+    sigma: f64,
+    /// Seed for fully reproducible runs (victim secret and attacker messages).
+    #[arg(short, long)]
+    seed: Option<u64>,
+    /// Source of the operations durations.
+    #[arg(short, long, value_enum, default_value_t = TimeSource::Simulated)]
+    timing: TimeSource,
+    /// Executions per measurement, the minimum latency is taken.
+    #[arg(short, long, default_value_t = 20)]
+    repetitions: usize,
+    /// Secret dependent operation run by the victim.
+    #[arg(short, long, value_enum, default_value_t = Op::SquareAndMultiply)]
+    op: Op,
+    /// Countermeasure implemented by the victim.
+    #[arg(long, value_enum, default_value_t = Defence::None)]
+    countermeasure: Defence,
+    /// Record timing samples and per-bit statistics to a CSV (or JSON, if the
+    /// file extension is `.json`) file.
+    ///
+    /// When attacking all the countermeasures, the countermeasure name is
+    /// appended to the file name.
+    #[arg(long)]
+    trace_out: Option<PathBuf>,
 }
 
-impl AttackerDevice {
-    pub fn new(keylen: u64) -> Self {
-        AttackerDevice {
-            modulus: get_modulus(keylen),
-        }
-    }
+fn trace_writer(path: &Path) -> io::Result<Box<dyn TraceWriter>> {
+    let out = BufWriter::new(File::create(path)?);
+    Ok(if path.extension().is_some_and(|e| e == "json") {
+        Box::new(JsonTrace::new(out)?)
+    } else {
+        Box::new(CsvTrace::new(out)?)
+    })
+}
 
-    pub fn sign(&self, m: &BigUint, d: &BigUint) -> f64 {
-        square_and_multiply(&m, d, &self.modulus)
+fn trace_path(path: &Path, countermeasure: Countermeasure) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let mut name = format!("{stem}-{countermeasure:?}");
+    if let Some(ext) = path.extension() {
+        name = format!("{name}.{}", ext.to_string_lossy());
     }
+    path.with_file_name(name)
 }
 
-fn main() {
-    let mut rng = rand::rngs::OsRng;
-
-    // Key length in bits
-    let keylen = 64;
-    // The more bit is keylen the more bit this value should be...
-    // E.g. 64 -> 1000, 128 -> 4000, 256 -> 10000
-    let variance_iters_count = 1000;
-
-    let victim = VictimDevice::new(rng.gen(), keylen);
-    let attacker = AttackerDevice::new(keylen);
-
-    println!("secret    : {:064b}", victim.secret);
+fn attack(victim: &VictimDevice, config: &AttackConfig, trace: &mut dyn TraceWriter) {
+    let width = config.keylen as usize;
+    println!("secret    : {:0width$b}", victim.secret());
     print!("recovered : ");
 
-    // Recovered secret
-    let mut recovered = BigUint::from(0_u64);
-
-    for _ in 0..keylen {
-        let mut sum0 = 0.0;
-        let mut sum0_square = 0.0;
-        let mut sum1 = 0.0;
-        let mut sum1_square = 0.0;
-
-        recovered <<= 1;
-
-        for _ in 0..variance_iters_count {
-            let m = rng.gen_biguint(keylen);
-
-            let t_vic = victim.sign(&m);
-
-            // Attempt with i-th bit = 0
-            recovered.set_bit(0, false);
-            let t_att0 = attacker.sign(&m, &recovered);
-            let delta0 = t_vic - t_att0;
-            sum0 += delta0;
-            sum0_square += delta0 * delta0;
-
-            // Attempt with i-th bit = 1
-            recovered.set_bit(0, true);
-            let t_att1 = attacker.sign(&m, &recovered);
-            let delta1 = t_vic - t_att1;
-            sum1 += delta1;
-            sum1_square += delta1 * delta1;
-        }
-
-        let exp0 = sum0 / variance_iters_count as f64;
-        let var0 = (sum0_square / variance_iters_count as f64) - exp0 * exp0;
-
-        let exp1 = sum1 / variance_iters_count as f64;
-        let var1 = (sum1_square / variance_iters_count as f64) - exp1 * exp1;
-
-        if var0 < var1 {
-            recovered.set_bit(0, false);
-            print!("0")
-        } else {
-            recovered.set_bit(0, true);
-            print!("1")
+    let (mut samples, mut backtracks) = (0, 0);
+    let on_progress = |progress| {
+        match progress {
+            Progress::Bit {
+                value, samples: n, ..
+            } => {
+                samples += n;
+                print!("{}", value as u8);
+            }
+            Progress::Backtrack => {
+                backtracks += 1;
+                print!("\u{8}");
+            }
         }
         io::stdout().flush().unwrap();
+    };
+    let recovered =
+        recover_secret_with_trace(victim, config, on_progress, trace).expect("Trace write failure");
+    println!();
+    println!("Timing samples: {samples}, backtracks: {backtracks}");
+
+    let wrong = (&recovered ^ victim.secret()).count_ones();
+    if wrong == 0 {
+        println!("Secret recovered");
+    } else {
+        println!("Recovery failed: {wrong} wrong bits");
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let op = match cli.op {
+        Op::SquareAndMultiply => Operation::SquareAndMultiply,
+        Op::DoubleAndAdd => Operation::DoubleAndAdd,
+    };
+    let seed = cli.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let measured = |clock| {
+        Timing::Measured(Timer {
+            clock,
+            repetitions: cli.repetitions,
+        })
+    };
+    let timing = match cli.timing {
+        TimeSource::Simulated => Timing::Simulated,
+        TimeSource::Instant => measured(Clock::Instant),
+        TimeSource::Rdtsc => measured(Clock::Rdtsc),
+    };
+    let config = AttackConfig {
+        keylen: cli.keylen,
+        iters: cli.iters,
+        max_iters: cli.max_iters,
+        confidence: cli.confidence,
+        max_backtracks: cli.keylen as usize / 4,
+        mu: cli.mu,
+        sigma: cli.sigma,
+        seed: Some(seed),
+        op,
+        modulus: get_modulus(cli.keylen),
+        timing,
+    };
+    println!("{op:?}, {timing:?}, seed: {seed}");
+
+    let countermeasures = cli.countermeasure.countermeasures();
+    for &countermeasure in &countermeasures {
+        println!("Countermeasure: {countermeasure:?}");
+        let victim = VictimDevice::with_countermeasure(seed, &config, countermeasure);
+        let mut trace: Box<dyn TraceWriter> = match &cli.trace_out {
+            Some(path) if countermeasures.len() > 1 => {
+                trace_writer(&trace_path(path, countermeasure)).expect("Trace file failure")
+            }
+            Some(path) => trace_writer(path).expect("Trace file failure"),
+            None => Box::new(()),
+        };
+        attack(&victim, &config, trace.as_mut());
     }
 }
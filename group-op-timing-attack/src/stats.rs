@@ -0,0 +1,73 @@
+//! Statistics shared by the side channel attacks.
+//!
+//! Variances are population variances (normalized by `n`).
+
+/// Sample mean.
+pub fn mean(xs: &[f64]) -> f64 {
+    xs.iter().sum::<f64>() / xs.len() as f64
+}
+
+/// Sample variance.
+pub fn variance(xs: &[f64]) -> f64 {
+    let exp = mean(xs);
+    xs.iter().map(|x| (x - exp).powi(2)).sum::<f64>() / xs.len() as f64
+}
+
+/// Pearson correlation coefficient of two equally sized samples.
+///
+/// Zero if any of the two samples is constant.
+pub fn pearson(xs: &[f64], ys: &[f64]) -> f64 {
+    debug_assert_eq!(xs.len(), ys.len());
+    let (ex, ey) = (mean(xs), mean(ys));
+    let (mut cov, mut vx, mut vy) = (0.0, 0.0, 0.0);
+    for (x, y) in xs.iter().zip(ys) {
+        let (dx, dy) = (x - ex, y - ey);
+        cov += dx * dy;
+        vx += dx * dx;
+        vy += dy * dy;
+    }
+    if vx > 0.0 && vy > 0.0 {
+        cov / (vx * vy).sqrt()
+    } else {
+        0.0
+    }
+}
+
+/// Variances of two hypotheses and z-score of their difference.
+///
+/// The samples of the two hypotheses share the same messages and victim
+/// timings, thus the difference is estimated on paired samples
+/// `w = (δ0 - E[δ0])² - (δ1 - E[δ1])²`.
+pub fn variance_difference(deltas: &[(f64, f64)]) -> (f64, f64, f64) {
+    let n = deltas.len() as f64;
+    let exp0 = deltas.iter().map(|d| d.0).sum::<f64>() / n;
+    let exp1 = deltas.iter().map(|d| d.1).sum::<f64>() / n;
+    let var0 = deltas.iter().map(|d| (d.0 - exp0).powi(2)).sum::<f64>() / n;
+    let var1 = deltas.iter().map(|d| (d.1 - exp1).powi(2)).sum::<f64>() / n;
+    let w = |d: &(f64, f64)| (d.0 - exp0).powi(2) - (d.1 - exp1).powi(2);
+    let diff = var0 - var1;
+    let var = deltas.iter().map(|d| (w(d) - diff).powi(2)).sum::<f64>() / n;
+    let err = (var / n).sqrt();
+    let z = if err > 0.0 {
+        diff / err
+    } else if diff != 0.0 {
+        diff.signum() * f64::INFINITY
+    } else {
+        0.0
+    };
+    (var0, var1, z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pearson_bounds() {
+        let xs = [1.0, 2.0, 3.0, 4.0];
+        assert!((pearson(&xs, &[2.0, 4.0, 6.0, 8.0]) - 1.0).abs() < 1e-12);
+        assert!((pearson(&xs, &[8.0, 6.0, 4.0, 2.0]) + 1.0).abs() < 1e-12);
+        assert_eq!(pearson(&xs, &[1.0; 4]), 0.0);
+        assert_eq!(variance(&xs), 1.25);
+    }
+}
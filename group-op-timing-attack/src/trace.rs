@@ -0,0 +1,228 @@
+//! Attack traces recording.
+//!
+//! Every timing sample and the per-bit statistics can be dumped for external
+//! analysis (e.g. to plot the variance of the two hypotheses as the attack
+//! proceeds).
+
+use num_bigint::BigUint;
+use std::io::{self, Write};
+
+/// Single timing measurement.
+#[derive(Debug, Clone)]
+pub struct Sample<'a> {
+    /// Index of the bit under attack (0 is the most significant).
+    pub bit: u64,
+    pub message: &'a BigUint,
+    pub victim_time: f64,
+    /// Attacker emulated time with the bit under attack set to 0.
+    pub attacker_time0: f64,
+    /// Attacker emulated time with the bit under attack set to 1.
+    pub attacker_time1: f64,
+}
+
+/// Statistics of a bit guess.
+///
+/// The same bit may be reported more than once if re-examined.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BitStats {
+    /// Index of the bit (0 is the most significant).
+    pub bit: u64,
+    pub value: bool,
+    pub samples: usize,
+    /// Variance of `victim_time - attacker_time0`.
+    pub var0: f64,
+    /// Variance of `victim_time - attacker_time1`.
+    pub var1: f64,
+    /// Variance difference z-score.
+    pub confidence: f64,
+}
+
+/// Attack trace sink.
+pub trait TraceWriter {
+    fn sample(&mut self, sample: &Sample) -> io::Result<()>;
+
+    fn bit(&mut self, stats: &BitStats) -> io::Result<()>;
+
+    /// Called once the attack is over.
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Discards everything.
+impl TraceWriter for () {
+    fn sample(&mut self, _: &Sample) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn bit(&mut self, _: &BitStats) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// CSV trace.
+///
+/// Samples and bit statistics share the same table, with the `kind` column
+/// set to `sample` or `bit` and the columns not pertaining to the record kind
+/// left empty.
+pub struct CsvTrace<W: Write> {
+    out: W,
+}
+
+impl<W: Write> CsvTrace<W> {
+    pub fn new(mut out: W) -> io::Result<Self> {
+        writeln!(
+            out,
+            "kind,bit,message,victim_time,attacker_time0,attacker_time1,value,samples,var0,var1,confidence"
+        )?;
+        Ok(CsvTrace { out })
+    }
+}
+
+impl<W: Write> TraceWriter for CsvTrace<W> {
+    fn sample(&mut self, s: &Sample) -> io::Result<()> {
+        writeln!(
+            self.out,
+            "sample,{},{},{},{},{},,,,,",
+            s.bit, s.message, s.victim_time, s.attacker_time0, s.attacker_time1
+        )
+    }
+
+    fn bit(&mut self, b: &BitStats) -> io::Result<()> {
+        writeln!(
+            self.out,
+            "bit,{},,,,,{},{},{},{},{}",
+            b.bit, b.value as u8, b.samples, b.var0, b.var1, b.confidence
+        )
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// JSON trace.
+///
+/// The document is `{"samples": [...], "bits": [...]}`. Samples are streamed
+/// while bit statistics are buffered until [`TraceWriter::finish`].
+pub struct JsonTrace<W: Write> {
+    out: W,
+    first: bool,
+    bits: Vec<BitStats>,
+}
+
+impl<W: Write> JsonTrace<W> {
+    pub fn new(mut out: W) -> io::Result<Self> {
+        write!(out, "{{\"samples\":[")?;
+        Ok(JsonTrace {
+            out,
+            first: true,
+            bits: Vec::new(),
+        })
+    }
+}
+
+// JSON has no representation for infinity and NaN
+fn number(x: f64) -> String {
+    if x.is_finite() {
+        x.to_string()
+    } else {
+        "null".into()
+    }
+}
+
+impl<W: Write> TraceWriter for JsonTrace<W> {
+    fn sample(&mut self, s: &Sample) -> io::Result<()> {
+        let sep = if self.first { "" } else { "," };
+        self.first = false;
+        write!(
+            self.out,
+            "{sep}\n{{\"bit\":{},\"message\":\"{}\",\"victim_time\":{},\"attacker_time0\":{},\"attacker_time1\":{}}}",
+            s.bit,
+            s.message,
+            number(s.victim_time),
+            number(s.attacker_time0),
+            number(s.attacker_time1)
+        )
+    }
+
+    fn bit(&mut self, stats: &BitStats) -> io::Result<()> {
+        self.bits.push(*stats);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        write!(self.out, "],\"bits\":[")?;
+        for (i, b) in self.bits.iter().enumerate() {
+            let sep = if i == 0 { "" } else { "," };
+            write!(
+                self.out,
+                "{sep}\n{{\"bit\":{},\"value\":{},\"samples\":{},\"var0\":{},\"var1\":{},\"confidence\":{}}}",
+                b.bit,
+                b.value as u8,
+                b.samples,
+                number(b.var0),
+                number(b.var1),
+                number(b.confidence)
+            )?;
+        }
+        writeln!(self.out, "]}}")?;
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record<T: TraceWriter>(trace: &mut T) {
+        let message = BigUint::from(42_u8);
+        let sample = Sample {
+            bit: 0,
+            message: &message,
+            victim_time: 3.0,
+            attacker_time0: 1.0,
+            attacker_time1: 2.0,
+        };
+        trace.sample(&sample).unwrap();
+        trace.sample(&sample).unwrap();
+        trace
+            .bit(&BitStats {
+                bit: 0,
+                value: true,
+                samples: 2,
+                var0: 0.5,
+                var1: 0.25,
+                confidence: f64::INFINITY,
+            })
+            .unwrap();
+        trace.finish().unwrap();
+    }
+
+    #[test]
+    fn csv_format() {
+        let mut out = Vec::new();
+        record(&mut CsvTrace::new(&mut out).unwrap());
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines.iter().all(|l| l.split(',').count() == 11));
+        assert_eq!(lines[1], "sample,0,42,3,1,2,,,,,");
+        assert_eq!(lines[3], "bit,0,,,,,1,2,0.5,0.25,inf");
+    }
+
+    #[test]
+    fn json_format() {
+        let mut out = Vec::new();
+        record(&mut JsonTrace::new(&mut out).unwrap());
+        let out = String::from_utf8(out).unwrap().replace('\n', "");
+        assert_eq!(
+            out,
+            concat!(
+                r#"{"samples":[{"bit":0,"message":"42","victim_time":3,"attacker_time0":1,"attacker_time1":2},"#,
+                r#"{"bit":0,"message":"42","victim_time":3,"attacker_time0":1,"attacker_time1":2}],"#,
+                r#""bits":[{"bit":0,"value":1,"samples":2,"var0":0.5,"var1":0.25,"confidence":null}]}"#
+            )
+        );
+    }
+}
@@ -0,0 +1,163 @@
+//! Hash to the toy curves over `GF(2^61 - 1)`.
+//!
+//! - Try-and-increment: hash to an `x` coordinate until `x³ + ax + b` is a
+//!   square (half of the times). Simple, but the number of attempts depends
+//!   on the input and leaks through timing, which is a problem when the input
+//!   is secret (e.g. Dragonblood against WPA3 password to element).
+//! - Simplified SWU (Brier et al., RFC 9380): a field element `u` is mapped to
+//!   a point with a fixed sequence of operations. Either `x1` or `x2 = Z·u²·x1`
+//!   has `x³ + ax + b` square, since `Z` is a non square. Not uniform on its
+//!   own, thus two field elements are mapped and the points added.
+//!
+//! The point is finally multiplied by the cofactor, to land in the prime order
+//! subgroup.
+
+use crate::hash;
+use invalid_curve::curve::{add, inv, mul, sqrt, sub, Curve, Point, P};
+
+fn sgn0(x: u64) -> u64 {
+    x & 1
+}
+
+// Field element from 128 hash bits, negligible bias
+fn to_field(bytes: &[u8; 32]) -> u64 {
+    (u128::from_be_bytes(bytes[..16].try_into().unwrap()) % P as u128) as u64
+}
+
+/// Hash to a toy curve.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurveHasher {
+    pub curve: Curve,
+    /// Curve order over the prime subgroup order.
+    pub cofactor: u64,
+    /// Simplified SWU non square constant.
+    pub z: u64,
+}
+
+impl CurveHasher {
+    /// Requires `a·b ≠ 0`, as the simplified SWU map does.
+    ///
+    /// `Z` is the first of `1, -1, 2, -2, ...` which is a non square, not `-1`
+    /// and such that `g(b/(Z·a))` is a square, as RFC 9380 recommends (the
+    /// polynomial irreducibility requirement is skipped).
+    pub fn new(curve: Curve, cofactor: u64) -> Self {
+        assert!(
+            curve.a != 0 && curve.b != 0,
+            "simplified SWU requires a·b ≠ 0"
+        );
+        let mut hasher = CurveHasher {
+            curve,
+            cofactor,
+            z: 0,
+        };
+        hasher.z = (1..)
+            .flat_map(|i| [i, P - i])
+            .find(|&z| {
+                let x = mul(curve.b, inv(mul(z, curve.a)));
+                sqrt(z).is_none() && z != P - 1 && sqrt(hasher.rhs(x)).is_some()
+            })
+            .unwrap();
+        hasher
+    }
+
+    // x³ + ax + b
+    fn rhs(&self, x: u64) -> u64 {
+        add(mul(x, add(mul(x, x), self.curve.a)), self.curve.b)
+    }
+
+    /// Field elements derived from `msg`.
+    pub fn hash_to_field(&self, dst: &[u8], msg: &[u8], count: u32) -> Vec<u64> {
+        (0..count).map(|i| to_field(&hash(dst, i, msg))).collect()
+    }
+
+    /// Try-and-increment hash to the prime order subgroup.
+    ///
+    /// Returns the point and the number of attempts, which is input dependent.
+    pub fn try_and_increment(&self, dst: &[u8], msg: &[u8]) -> (Point, u32) {
+        (0_u32..)
+            .find_map(|ctr| {
+                let digest = hash(dst, ctr, msg);
+                let x = to_field(&digest);
+                let y = sqrt(self.rhs(x))?;
+                // Last hash bit picks the root
+                let y = if sgn0(y) == (digest[31] & 1) as u64 {
+                    y
+                } else {
+                    sub(0, y)
+                };
+                let point = self.curve.mul(self.cofactor, &Point::Affine(x, y));
+                (point != Point::Infinity).then_some((point, ctr + 1))
+            })
+            .unwrap()
+    }
+
+    /// Simplified SWU map of a field element to a curve point.
+    pub fn map_to_curve(&self, u: u64) -> Point {
+        let Curve { a, b } = self.curve;
+        let zu2 = mul(self.z, mul(u, u));
+        let tv1 = add(mul(zu2, zu2), zu2);
+        let x1 = if tv1 == 0 {
+            mul(b, inv(mul(self.z, a)))
+        } else {
+            mul(sub(0, mul(b, inv(a))), add(1, inv(tv1)))
+        };
+        let (x, y) = match sqrt(self.rhs(x1)) {
+            Some(y) => (x1, y),
+            None => {
+                let x2 = mul(zu2, x1);
+                (x2, sqrt(self.rhs(x2)).expect("Z is a non square"))
+            }
+        };
+        let y = if sgn0(u) == sgn0(y) { y } else { sub(0, y) };
+        Point::Affine(x, y)
+    }
+
+    /// Simplified SWU hash to the prime order subgroup.
+    pub fn hash_to_curve(&self, dst: &[u8], msg: &[u8]) -> Point {
+        let u = self.hash_to_field(dst, msg, 2);
+        let point = self
+            .curve
+            .add(&self.map_to_curve(u[0]), &self.map_to_curve(u[1]));
+        self.curve.mul(self.cofactor, &point)
+    }
+
+    /// The first `n` generators derived from the label `dst`.
+    pub fn generators(&self, dst: &[u8], n: u32) -> Vec<Point> {
+        (0..n)
+            .map(|i| self.hash_to_curve(dst, &i.to_be_bytes()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use invalid_curve::{CURVE, ORDER};
+
+    #[test]
+    fn points_land_in_the_subgroup() {
+        let hasher = CurveHasher::new(CURVE, 1);
+        assert!(sqrt(hasher.z).is_none());
+        for msg in [&b""[..], b"abc", b"hash to curve"] {
+            let (p1, attempts) = hasher.try_and_increment(b"test", msg);
+            let p2 = hasher.hash_to_curve(b"test", msg);
+            for p in [p1, p2] {
+                assert!(CURVE.contains(&p));
+                assert_eq!(CURVE.mul(ORDER, &p), Point::Infinity);
+            }
+            assert!(attempts >= 1);
+        }
+        for u in [0, 1, 42, P - 1] {
+            assert!(CURVE.contains(&hasher.map_to_curve(u)));
+        }
+    }
+
+    #[test]
+    fn distinct_generators() {
+        let hasher = CurveHasher::new(CURVE, 1);
+        let gens = hasher.generators(b"pedersen", 16);
+        for (i, g) in gens.iter().enumerate() {
+            assert!(!gens[..i].contains(g));
+        }
+    }
+}
@@ -0,0 +1,36 @@
+//! Hash to group and independent generators.
+//!
+//! Many constructions need group elements whose discrete logarithms nobody
+//! knows: the second Pedersen generator (whoever knows `log_g(h)` opens a
+//! commitment to anything), the VRF input point (whoever knows its log with
+//! respect to `g` computes the output from the public key alone), the
+//! generators of vector commitments and so on. A fixed "random looking"
+//! constant is not enough, as nobody can tell how it was chosen.
+//!
+//! Deriving the elements by hashing a public label (a *nothing up my sleeve*
+//! construction) makes the relations as hard to find as the discrete log
+//! itself, provided that the map doesn't go through a known exponent (e.g.
+//! `h = g^H(label)` is useless):
+//! - [`zp`]: prime order subgroups of `Z_p*`, where hashing to `Z_p*` and
+//!   raising to the cofactor does the job;
+//! - [`curve`]: toy curves of the `invalid-curve` crate, with the simple
+//!   try-and-increment method and the constant time simplified SWU map.
+//!
+//! Every input is prefixed by a length prefixed domain separation tag (see
+//! the `kdf-domain-separation` crate for why the length matters).
+
+use sha2::{Digest, Sha256};
+
+pub mod curve;
+pub mod zp;
+
+/// `SHA-256(len(dst) || dst || ctr || msg)`.
+pub(crate) fn hash(dst: &[u8], ctr: u32, msg: &[u8]) -> [u8; 32] {
+    Sha256::new()
+        .chain_update((dst.len() as u32).to_be_bytes())
+        .chain_update(dst)
+        .chain_update(ctr.to_be_bytes())
+        .chain_update(msg)
+        .finalize()
+        .into()
+}
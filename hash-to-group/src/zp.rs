@@ -0,0 +1,114 @@
+//! Prime order subgroups of `Z_p*`.
+
+use crate::hash;
+use num_bigint::BigUint;
+use std::str::FromStr;
+
+/// Order `q` subgroup of `Z_p*`, with `q` prime dividing `p - 1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subgroup {
+    pub p: BigUint,
+    pub q: BigUint,
+}
+
+impl Subgroup {
+    pub fn new(p: BigUint, q: BigUint) -> Self {
+        assert!(((&p - 1_u8) % &q).bits() == 0, "q doesn't divide p - 1");
+        Subgroup { p, q }
+    }
+
+    /// Quadratic residues modulo a 256-bit safe prime `p = 2q + 1`.
+    ///
+    /// Shared by the toy protocols of the other crates (Pedersen commitments,
+    /// VRF). Far too small for real use.
+    pub fn safe_prime_256() -> Self {
+        let p = BigUint::from_str(
+            "89817129016758518604731964897946500854280737740137031558176374960925940532159",
+        )
+        .unwrap();
+        let q = (&p - 1_u8) >> 1;
+        Subgroup::new(p, q)
+    }
+
+    /// `(p - 1) / q`.
+    pub fn cofactor(&self) -> BigUint {
+        (&self.p - 1_u8) / &self.q
+    }
+
+    /// Check if `a` is a non-identity element of the subgroup.
+    pub fn is_element(&self, a: &BigUint) -> bool {
+        let one = BigUint::from(1_u8);
+        a > &one && a < &self.p && a.modpow(&self.q, &self.p) == one
+    }
+
+    /// Hash `msg` to a non-identity subgroup element.
+    ///
+    /// An element of `Z_p*` is obtained by reducing twice as many hash bits as
+    /// `p` has (to make the bias negligible) and is then raised to the
+    /// cofactor. Identity (probability ≈ 1/q) and zero are skipped by
+    /// incrementing a counter.
+    pub fn hash_to_group(&self, dst: &[u8], msg: &[u8]) -> BigUint {
+        let blocks = (2 * self.p.bits() as u32).div_ceil(256);
+        let cofactor = self.cofactor();
+        (0_u32..)
+            .map(|ctr| {
+                let bytes: Vec<_> = (0..blocks)
+                    .flat_map(|i| hash(dst, ctr * blocks + i, msg))
+                    .collect();
+                let x = BigUint::from_bytes_be(&bytes) % &self.p;
+                x.modpow(&cofactor, &self.p)
+            })
+            .find(|h| self.is_element(h))
+            .unwrap()
+    }
+
+    /// The `index`-th generator derived from the label `dst`.
+    ///
+    /// Generators with different labels or indices have unknown discrete log
+    /// relations.
+    pub fn generator(&self, dst: &[u8], index: u32) -> BigUint {
+        self.hash_to_group(dst, &index.to_be_bytes())
+    }
+
+    /// The first `n` generators derived from the label `dst`.
+    pub fn generators(&self, dst: &[u8], n: u32) -> Vec<BigUint> {
+        (0..n).map(|i| self.generator(dst, i)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_to_subgroup() {
+        // p = 2·3·5·q + 1
+        let group = Subgroup::new(BigUint::from(2_131_u32), BigUint::from(71_u32));
+        let gens = group.generators(b"test", 8);
+        assert!(gens.iter().all(|g| group.is_element(g)));
+        assert_eq!(gens[3], group.generator(b"test", 3));
+        assert_ne!(gens[0], group.generator(b"other", 0));
+        assert_ne!(
+            group.hash_to_group(b"a", b"bc"),
+            group.hash_to_group(b"ab", b"c")
+        );
+    }
+
+    #[test]
+    fn safe_prime_group() {
+        let group = Subgroup::safe_prime_256();
+        assert_eq!(group.p.bits(), 256);
+        assert_eq!(group.cofactor(), BigUint::from(2_u8));
+        // Fermat witnesses for both p and q
+        let two = BigUint::from(2_u8);
+        assert_eq!(
+            two.modpow(&(&group.p - 1_u8), &group.p),
+            BigUint::from(1_u8)
+        );
+        assert_eq!(
+            two.modpow(&(&group.q - 1_u8), &group.q),
+            BigUint::from(1_u8)
+        );
+        assert!(group.is_element(&group.generator(b"test", 0)));
+    }
+}
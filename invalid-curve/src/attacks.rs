@@ -0,0 +1,157 @@
+//! Small order points on invalid curves and secret recovery.
+
+use crate::{
+    curve::{Curve, Point},
+    tag, Victim, CURVE, G, ORDER,
+};
+use num_bigint::BigUint;
+use rand::Rng;
+use shanks::bsgs;
+
+/// Point of small prime order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SmallPoint {
+    /// Curve the point belongs to, sharing `a` with the victim curve.
+    pub curve: Curve,
+    pub point: Point,
+    pub order: u64,
+}
+
+fn prime_factors(mut n: u64, bound: u64) -> Vec<u64> {
+    let mut factors = Vec::new();
+    let mut p = 2;
+    while p < bound && p * p <= n {
+        if n.is_multiple_of(p) {
+            factors.push(p);
+            while n.is_multiple_of(p) {
+                n /= p;
+            }
+        }
+        p += 1;
+    }
+    if n > 1 && n < bound {
+        factors.push(n);
+    }
+    factors
+}
+
+/// Points of distinct prime orders less than `bound`, on random curves with
+/// the victim `a`, until the orders product reaches `target`.
+pub fn small_order_points<R: Rng>(rng: &mut R, bound: u64, target: u128) -> Vec<SmallPoint> {
+    let mut points: Vec<SmallPoint> = Vec::new();
+    let mut product = 1_u128;
+    while product < target {
+        let curve = Curve {
+            a: CURVE.a,
+            b: rng.gen_range(0..crate::curve::P),
+        };
+        let q = curve.random_point(rng);
+        let n = curve.order_multiple(&q);
+        for order in prime_factors(n, bound) {
+            if points.iter().any(|p| p.order == order) {
+                continue;
+            }
+            let point = curve.mul(n / order, &q);
+            if point != Point::Infinity {
+                points.push(SmallPoint {
+                    curve,
+                    point,
+                    order,
+                });
+                product *= order as u128;
+            }
+        }
+    }
+    points
+}
+
+/// Secret residue modulo the order of the point sent to the victim.
+pub fn residue(victim: &mut Victim, sp: &SmallPoint) -> Option<u64> {
+    let target = victim.handshake(&sp.point)?;
+    let mut q = Point::Infinity;
+    for i in 0..sp.order {
+        if tag(&q) == target {
+            return Some(i);
+        }
+        q = sp.curve.add(&q, &sp.point);
+    }
+    None
+}
+
+/// Chinese remainder theorem for `(residue, prime modulus)` pairs.
+///
+/// Returns `x` and the product of the moduli `M`, with `x < M`.
+pub fn crt(residues: &[(u64, u64)]) -> (u128, u128) {
+    let (residues, moduli): (Vec<_>, Vec<_>) = residues
+        .iter()
+        .map(|&(a, r)| (BigUint::from(a), BigUint::from(r)))
+        .unzip();
+    let x = numutil::crt(&residues, &moduli).expect("Distinct prime moduli");
+    let m: BigUint = moduli.iter().product();
+    let to_u128 = |n: &BigUint| u128::try_from(n).expect("Moduli product overflow");
+    (to_u128(&x), to_u128(&m))
+}
+
+/// Recover the victim secret.
+///
+/// Residues modulo primes less than `bound` are collected until the last
+/// `bsgs_bits` bits are left, which are then found by BSGS.
+pub fn recover_secret<R: Rng>(
+    rng: &mut R,
+    victim: &mut Victim,
+    bound: u64,
+    bsgs_bits: u32,
+) -> Option<u64> {
+    let target = (ORDER >> bsgs_bits) as u128 + 1;
+    let residues = small_order_points(rng, bound, target)
+        .iter()
+        .map(|sp| residue(victim, sp).map(|x| (x, sp.order)))
+        .collect::<Option<Vec<_>>>()?;
+    let (x, m) = crt(&residues);
+    if m >= ORDER as u128 {
+        return Some(x as u64);
+    }
+    // d = x + m·t, thus d·G - x·G = t·(m·G)
+    let (x, m) = (x as u64, m as u64);
+    let h = CURVE.add(&victim.public(), &CURVE.neg(&CURVE.mul(x, &G)));
+    let t = bsgs(&CURVE, &CURVE.mul(m, &G), &h, ORDER / m + 1)?;
+    Some(((x as u128 + m as u128 * t as u128) % ORDER as u128) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn small_orders() {
+        let points = small_order_points(&mut OsRng, 1 << 12, 1 << 30);
+        let product: u128 = points.iter().map(|p| p.order as u128).product();
+        assert!(product >= 1 << 30);
+        for sp in points {
+            assert!(sp.curve.contains(&sp.point) && !CURVE.contains(&sp.point));
+            assert_ne!(sp.point, Point::Infinity);
+            assert_eq!(sp.curve.mul(sp.order, &sp.point), Point::Infinity);
+        }
+    }
+
+    #[test]
+    fn chinese_remainder() {
+        let x = 123_456_789_u128;
+        let residues: Vec<_> = [101, 103, 107, 109, 113]
+            .map(|r| ((x % r) as u64, r as u64))
+            .to_vec();
+        assert_eq!(crt(&residues), (x, 101 * 103 * 107 * 109 * 113));
+    }
+
+    #[test]
+    fn recover_unvalidated_key() {
+        let mut victim = Victim::new(&mut OsRng, false);
+        let secret = recover_secret(&mut OsRng, &mut victim, 1 << 12, 20);
+        assert_eq!(secret, Some(victim.secret()));
+
+        let mut victim = Victim::new(&mut OsRng, true);
+        assert_eq!(recover_secret(&mut OsRng, &mut victim, 1 << 12, 20), None);
+        assert_eq!(victim.queries(), 1);
+    }
+}
@@ -0,0 +1,147 @@
+//! Short Weierstrass curves `y² = x³ + ax + b` over `GF(2^61 - 1)`.
+
+use rand::Rng;
+use shanks::{bsgs, Group};
+
+/// Field modulus, the Mersenne prime `2^61 - 1`.
+pub const P: u64 = (1 << 61) - 1;
+
+/// Field multiplication.
+pub fn mul(a: u64, b: u64) -> u64 {
+    (a as u128 * b as u128 % P as u128) as u64
+}
+
+/// Field addition.
+pub fn add(a: u64, b: u64) -> u64 {
+    (a + b) % P
+}
+
+/// Field subtraction.
+pub fn sub(a: u64, b: u64) -> u64 {
+    (a + P - b) % P
+}
+
+/// Field exponentiation.
+pub fn pow(mut a: u64, mut e: u64) -> u64 {
+    let mut r = 1;
+    while e != 0 {
+        if e & 1 == 1 {
+            r = mul(r, a);
+        }
+        a = mul(a, a);
+        e >>= 1;
+    }
+    r
+}
+
+/// Field inverse, zero for zero.
+pub fn inv(a: u64) -> u64 {
+    pow(a, P - 2)
+}
+
+/// Square root, if any. `P ≡ 3 mod 4`.
+pub fn sqrt(a: u64) -> Option<u64> {
+    let r = pow(a, (P + 1) / 4);
+    (mul(r, r) == a).then_some(r)
+}
+
+/// Curve point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Point {
+    Infinity,
+    Affine(u64, u64),
+}
+
+/// Curve coefficients.
+///
+/// The group law doesn't depend on `b`: operations on a point which is not on
+/// the curve silently take place on the curve through it with the same `a`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Curve {
+    pub a: u64,
+    pub b: u64,
+}
+
+impl Curve {
+    pub fn contains(&self, point: &Point) -> bool {
+        match *point {
+            Point::Infinity => true,
+            Point::Affine(x, y) => mul(y, y) == add(add(mul(mul(x, x), x), mul(self.a, x)), self.b),
+        }
+    }
+
+    pub fn neg(&self, point: &Point) -> Point {
+        match *point {
+            Point::Infinity => Point::Infinity,
+            Point::Affine(x, y) => Point::Affine(x, sub(0, y)),
+        }
+    }
+
+    pub fn add(&self, p: &Point, q: &Point) -> Point {
+        let (Point::Affine(x1, y1), Point::Affine(x2, y2)) = (*p, *q) else {
+            return if *p == Point::Infinity { *q } else { *p };
+        };
+        let lambda = if x1 != x2 {
+            mul(sub(y2, y1), inv(sub(x2, x1)))
+        } else if y1 == y2 && y1 != 0 {
+            mul(add(mul(3, mul(x1, x1)), self.a), inv(mul(2, y1)))
+        } else {
+            return Point::Infinity;
+        };
+        let x3 = sub(sub(mul(lambda, lambda), x1), x2);
+        let y3 = sub(mul(lambda, sub(x1, x3)), y1);
+        Point::Affine(x3, y3)
+    }
+
+    pub fn mul(&self, mut k: u64, point: &Point) -> Point {
+        let (mut r, mut q) = (Point::Infinity, *point);
+        while k != 0 {
+            if k & 1 == 1 {
+                r = self.add(&r, &q);
+            }
+            q = self.add(&q, &q);
+            k >>= 1;
+        }
+        r
+    }
+
+    /// Random point on the curve.
+    pub fn random_point<R: Rng>(&self, rng: &mut R) -> Point {
+        loop {
+            let x = rng.gen_range(0..P);
+            let rhs = add(add(mul(mul(x, x), x), mul(self.a, x)), self.b);
+            if let Some(y) = sqrt(rhs) {
+                return Point::Affine(x, y);
+            }
+        }
+    }
+
+    /// A multiple of the order of `point` in the Hasse interval
+    /// `[P + 1 - 2√P, P + 1 + 2√P]`, which contains the curve order.
+    ///
+    /// This is the curve order if the point order is greater than `4√P`, as
+    /// it is very likely for a random point.
+    pub fn order_multiple(&self, point: &Point) -> u64 {
+        // N·Q = O with N = low + t ⟺ t·Q = -(low·Q)
+        let width = 4 * ((P as f64).sqrt() as u64 + 1);
+        let low = P + 1 - width / 2;
+        let h = self.neg(&self.mul(low, point));
+        low + bsgs(self, point, &h, width + 1).expect("Hasse bound")
+    }
+}
+
+impl Group for Curve {
+    type Element = Point;
+
+    fn identity(&self) -> Point {
+        Point::Infinity
+    }
+
+    fn op(&self, a: &Point, b: &Point) -> Point {
+        self.add(a, b)
+    }
+
+    fn inverse(&self, a: &Point) -> Point {
+        self.neg(a)
+    }
+}
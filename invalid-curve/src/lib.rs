@@ -0,0 +1,107 @@
+//! Invalid curve attack against static ECDH.
+//!
+//! The victim holds a static key `d` and, for every handshake, multiplies the
+//! client point `Q` by `d` and proves the knowledge of the shared point `d·Q`
+//! (here with a key confirmation tag, in practice by using the derived key).
+//!
+//! The short Weierstrass addition formulas don't involve the `b` coefficient,
+//! thus a victim which doesn't check that `Q` lies on its curve computes on
+//! whatever curve `y² = x³ + ax + b'` passes through `Q`. The attacker picks
+//! curves whose order has small prime factors `r` and sends points of order
+//! `r`: the shared point is one of `r` candidates, which reveal `d mod r` by
+//! brute force. The residues are combined with the CRT and, once their product
+//! covers most of the key, the few remaining bits are found with a
+//! baby-step giant-step (see the `shanks` crate) on the victim curve.
+//!
+//! The curve order being prime doesn't help, validation does. Curves with an
+//! x-only ladder (e.g. X25519) are instead exposed through their quadratic
+//! twist, which is why those are chosen to be twist secure.
+//!
+//! Toy parameters: the field is `GF(2^61 - 1)`, to keep the small order
+//! points search fast while the key is way out of reach of a plain BSGS.
+
+use curve::{Curve, Point, P};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+pub mod attacks;
+pub mod curve;
+
+/// Victim curve `y² = x³ - 3x + 111`.
+pub const CURVE: Curve = Curve { a: P - 3, b: 111 };
+
+/// Victim curve order, prime.
+pub const ORDER: u64 = 2305843010818082053;
+
+/// Victim curve generator.
+pub const G: Point = Point::Affine(1, 509478702933351334);
+
+/// Key confirmation tag of the shared point.
+pub fn tag(shared: &Point) -> [u8; 32] {
+    let mut h = Sha256::new().chain_update(b"ecdh-confirm");
+    if let Point::Affine(x, y) = shared {
+        h.update(x.to_be_bytes());
+        h.update(y.to_be_bytes());
+    }
+    h.finalize().into()
+}
+
+/// Server with a static ECDH key.
+#[derive(Debug)]
+pub struct Victim {
+    secret: u64,
+    public: Point,
+    validate: bool,
+    queries: usize,
+}
+
+impl Victim {
+    /// Server with a random key, checking the client points if `validate`.
+    pub fn new<R: Rng>(rng: &mut R, validate: bool) -> Self {
+        let secret = rng.gen_range(1..ORDER);
+        Victim {
+            secret,
+            public: CURVE.mul(secret, &G),
+            validate,
+            queries: 0,
+        }
+    }
+
+    pub fn public(&self) -> Point {
+        self.public
+    }
+
+    /// Server secret, to check the attack outcome.
+    pub fn secret(&self) -> u64 {
+        self.secret
+    }
+
+    /// Handshakes served so far.
+    pub fn queries(&self) -> usize {
+        self.queries
+    }
+
+    /// Handshake with the client `point`, returning the key confirmation tag
+    /// or `None` if the point is rejected.
+    pub fn handshake(&mut self, point: &Point) -> Option<[u8; 32]> {
+        self.queries += 1;
+        if self.validate && (*point == Point::Infinity || !CURVE.contains(point)) {
+            return None;
+        }
+        Some(tag(&CURVE.mul(self.secret, point)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigUint;
+
+    #[test]
+    fn victim_curve() {
+        assert!(CURVE.contains(&G));
+        assert!(miller_rabin::is_prime(&BigUint::from(ORDER)));
+        assert_eq!(CURVE.mul(ORDER, &G), Point::Infinity);
+        assert_eq!(CURVE.order_multiple(&G), ORDER);
+    }
+}
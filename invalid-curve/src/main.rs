@@ -0,0 +1,23 @@
+use invalid_curve::{attacks, Victim};
+use rand::rngs::OsRng;
+use std::time::Instant;
+
+fn main() {
+    let (bound, bsgs_bits) = (1 << 16, 24);
+    for validate in [false, true] {
+        let mut victim = Victim::new(&mut OsRng, validate);
+        println!(
+            "Victim secret {:#018x}, points validation: {validate}",
+            victim.secret()
+        );
+        let start = Instant::now();
+        match attacks::recover_secret(&mut OsRng, &mut victim, bound, bsgs_bits) {
+            Some(secret) => println!(
+                "  recovered {secret:#018x} after {} handshakes ({:.2}s)",
+                victim.queries(),
+                start.elapsed().as_secs_f64()
+            ),
+            None => println!("  handshake rejected"),
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Key derivation domain separation.
+//!
+//! Protocols often derive keys as `KDF(context)`, with the context built by
+//! serializing a label and a few fields. If the serialization is not
+//! injective, two different contexts, possibly of different protocols, feed
+//! the KDF with the same bytes and get the same key:
+//! - plain concatenation: `"payment" || "alice" || "bob"` is also
+//!   `"pay" || "mentalice" || "bob"`, the boundaries are lost;
+//! - delimiters: `"alice|bob"` is a perfectly valid user name, unless the
+//!   delimiter is escaped everywhere;
+//! - no label: same fields layout in two protocols means same keys.
+//!
+//! If an attacker can obtain the key (or the output) of a context they choose
+//! in one protocol, they obtain the key of a context of another protocol. The
+//! fix is a canonical encoding: every item, the label included, is length
+//! prefixed, so that the bytes can be parsed back in a single way.
+//!
+//! The [`search`] module finds the colliding contexts of any schema pair by
+//! enumerating all the parses of the encoded bytes.
+
+use sha2::{Digest, Sha256};
+
+pub mod search;
+
+/// Context field type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    /// Variable length bytes.
+    Bytes,
+    /// Fixed length bytes (e.g. integers, hashes).
+    Fixed(usize),
+}
+
+/// Context layout of a protocol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schema {
+    pub label: Vec<u8>,
+    pub fields: Vec<Field>,
+}
+
+impl Schema {
+    pub fn new(label: &str, fields: &[Field]) -> Self {
+        Schema {
+            label: label.as_bytes().to_vec(),
+            fields: fields.to_vec(),
+        }
+    }
+
+    /// Check the number of values and the fixed sizes.
+    pub fn accepts(&self, values: &[Vec<u8>]) -> bool {
+        values.len() == self.fields.len()
+            && self.fields.iter().zip(values).all(|(f, v)| match f {
+                Field::Bytes => true,
+                Field::Fixed(n) => v.len() == *n,
+            })
+    }
+}
+
+/// Context serialization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// Label and values concatenated.
+    Concat,
+    /// Label and values joined by the given delimiter.
+    Delimited(u8),
+    /// Label and values each prefixed by its 32 bits big endian length.
+    Canonical,
+}
+
+impl Encoding {
+    /// Serialize the `values` of a `schema` context.
+    pub fn encode(&self, schema: &Schema, values: &[Vec<u8>]) -> Vec<u8> {
+        assert!(schema.accepts(values), "values don't match the schema");
+        let items = std::iter::once(&schema.label).chain(values);
+        match self {
+            Encoding::Concat => items.flatten().copied().collect(),
+            Encoding::Delimited(d) => items.cloned().collect::<Vec<_>>().join(&[*d][..]),
+            Encoding::Canonical => items
+                .flat_map(|v| (v.len() as u32).to_be_bytes().into_iter().chain(v.clone()))
+                .collect(),
+        }
+    }
+}
+
+/// Key derived from a `schema` context.
+pub fn derive_key(encoding: Encoding, schema: &Schema, values: &[Vec<u8>]) -> [u8; 32] {
+    Sha256::new()
+        .chain_update(b"kdf")
+        .chain_update(encoding.encode(schema, values))
+        .finalize()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodings() {
+        let schema = Schema::new("ctx", &[Field::Bytes, Field::Fixed(2)]);
+        let values = [b"ab".to_vec(), b"xy".to_vec()];
+        assert_eq!(Encoding::Concat.encode(&schema, &values), b"ctxabxy");
+        assert_eq!(
+            Encoding::Delimited(b'|').encode(&schema, &values),
+            b"ctx|ab|xy"
+        );
+        assert_eq!(
+            Encoding::Canonical.encode(&schema, &values),
+            b"\0\0\0\x03ctx\0\0\0\x02ab\0\0\0\x02xy"
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use kdf_domain_separation::{derive_key, search, Encoding, Field, Schema};
+
+fn show(values: &[Vec<u8>]) -> String {
+    values
+        .iter()
+        .map(|v| format!("{:?}", String::from_utf8_lossy(v)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn main() {
+    // Payment authorization key, bound to payers, payee and amount
+    let payment = Schema::new("payment", &[Field::Bytes, Field::Bytes, Field::Fixed(8)]);
+    // Session key for a user, with a client chosen nonce
+    let session = Schema::new("pay", &[Field::Bytes, Field::Fixed(8)]);
+    let values: Vec<Vec<u8>> = ["alice|eve", "bob", "00000100"]
+        .iter()
+        .map(|s| s.as_bytes().to_vec())
+        .collect();
+
+    println!("payment({})", show(&values));
+    for encoding in [
+        Encoding::Concat,
+        Encoding::Delimited(b'|'),
+        Encoding::Canonical,
+    ] {
+        println!("  {encoding:?}");
+        for (name, schema) in [("payment", &payment), ("pay", &session)] {
+            let found = search::collisions(encoding, &payment, &values, schema, 3);
+            if found.is_empty() {
+                println!("    {name}: no collision");
+            }
+            for other in found {
+                assert_eq!(
+                    derive_key(encoding, &payment, &values),
+                    derive_key(encoding, schema, &other)
+                );
+                println!(
+                    "    {name}({}) -> same key {}",
+                    show(&other),
+                    &hex::encode(derive_key(encoding, schema, &other))[..16]
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Colliding contexts search.
+
+use crate::{Encoding, Field, Schema};
+
+/// All the `schema` contexts whose encoding is `bytes`, at most `limit`.
+pub fn decodings(
+    encoding: Encoding,
+    schema: &Schema,
+    bytes: &[u8],
+    limit: usize,
+) -> Vec<Vec<Vec<u8>>> {
+    let mut found = Vec::new();
+    match encoding {
+        Encoding::Concat => {
+            if let Some(rest) = bytes.strip_prefix(&schema.label[..]) {
+                split(
+                    &schema.fields,
+                    rest,
+                    None,
+                    &mut Vec::new(),
+                    &mut found,
+                    limit,
+                );
+            }
+        }
+        Encoding::Delimited(d) => {
+            let mut prefix = schema.label.clone();
+            if !schema.fields.is_empty() {
+                prefix.push(d);
+            }
+            if let Some(rest) = bytes.strip_prefix(&prefix[..]) {
+                split(
+                    &schema.fields,
+                    rest,
+                    Some(d),
+                    &mut Vec::new(),
+                    &mut found,
+                    limit,
+                );
+            }
+        }
+        Encoding::Canonical => {
+            // The parse is unique
+            if let Some(values) = parse_canonical(bytes) {
+                if values[0] == schema.label && schema.accepts(&values[1..]) && limit > 0 {
+                    found.push(values[1..].to_vec());
+                }
+            }
+        }
+    }
+    found
+}
+
+// Assign `rest` to the `fields`, with the values separated by `delimiter`.
+fn split(
+    fields: &[Field],
+    rest: &[u8],
+    delimiter: Option<u8>,
+    values: &mut Vec<Vec<u8>>,
+    found: &mut Vec<Vec<Vec<u8>>>,
+    limit: usize,
+) {
+    if found.len() >= limit {
+        return;
+    }
+    let Some((field, others)) = fields.split_first() else {
+        if rest.is_empty() {
+            found.push(values.clone());
+        }
+        return;
+    };
+    let lengths: Vec<usize> = match (field, others.is_empty()) {
+        (Field::Fixed(n), _) => vec![*n],
+        (Field::Bytes, true) => vec![rest.len()],
+        (Field::Bytes, false) => (0..=rest.len()).collect(),
+    };
+    for len in lengths.into_iter().filter(|&len| len <= rest.len()) {
+        let (value, mut tail) = rest.split_at(len);
+        if let (Some(d), false) = (delimiter, others.is_empty()) {
+            match tail.split_first() {
+                Some((b, t)) if *b == d => tail = t,
+                _ => continue,
+            }
+        }
+        values.push(value.to_vec());
+        split(others, tail, delimiter, values, found, limit);
+        values.pop();
+    }
+}
+
+fn parse_canonical(mut bytes: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let mut items = Vec::new();
+    while !bytes.is_empty() {
+        let (len, rest) = bytes.split_at_checked(4)?;
+        let len = u32::from_be_bytes(len.try_into().unwrap()) as usize;
+        let (item, rest) = rest.split_at_checked(len)?;
+        items.push(item.to_vec());
+        bytes = rest;
+    }
+    (!items.is_empty()).then_some(items)
+}
+
+/// Contexts of schema `b` deriving the same key as the `values` context of
+/// schema `a`, excluding the context itself.
+pub fn collisions(
+    encoding: Encoding,
+    a: &Schema,
+    values: &[Vec<u8>],
+    b: &Schema,
+    limit: usize,
+) -> Vec<Vec<Vec<u8>>> {
+    let bytes = encoding.encode(a, values);
+    decodings(encoding, b, &bytes, limit + 1)
+        .into_iter()
+        .filter(|other| a != b || other != values)
+        .take(limit)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::derive_key;
+
+    fn v(items: &[&str]) -> Vec<Vec<u8>> {
+        items.iter().map(|s| s.as_bytes().to_vec()).collect()
+    }
+
+    #[test]
+    fn cross_protocol() {
+        let payment = Schema::new("payment", &[Field::Bytes, Field::Bytes, Field::Fixed(8)]);
+        let login = Schema::new("pay", &[Field::Bytes, Field::Fixed(8)]);
+        let values = v(&["alice", "bob", "00000100"]);
+
+        let found = collisions(Encoding::Concat, &payment, &values, &login, 10);
+        assert_eq!(found, [v(&["mentalicebob", "00000100"])]);
+        assert_eq!(
+            derive_key(Encoding::Concat, &payment, &values),
+            derive_key(Encoding::Concat, &login, &found[0])
+        );
+        for encoding in [Encoding::Delimited(b'|'), Encoding::Canonical] {
+            assert!(collisions(encoding, &payment, &values, &login, 10).is_empty());
+        }
+    }
+
+    #[test]
+    fn same_protocol() {
+        let schema = Schema::new("session", &[Field::Bytes, Field::Bytes]);
+        let values = v(&["alice|x", "bob"]);
+        // Every split point of "alicebob"
+        assert_eq!(
+            collisions(
+                Encoding::Concat,
+                &schema,
+                &v(&["alice", "bob"]),
+                &schema,
+                100
+            )
+            .len(),
+            8
+        );
+        let found = collisions(Encoding::Delimited(b'|'), &schema, &values, &schema, 10);
+        assert_eq!(found, [v(&["alice", "x|bob"])]);
+        assert!(collisions(Encoding::Canonical, &schema, &values, &schema, 10).is_empty());
+    }
+
+    #[test]
+    fn canonical_round_trip() {
+        let schema = Schema::new("ctx", &[Field::Bytes, Field::Fixed(3), Field::Bytes]);
+        let values = v(&["", "abc", "\0\0\0\x01"]);
+        for encoding in [
+            Encoding::Concat,
+            Encoding::Delimited(0),
+            Encoding::Canonical,
+        ] {
+            let bytes = encoding.encode(&schema, &values);
+            assert!(decodings(encoding, &schema, &bytes, 100).contains(&values));
+        }
+        let bytes = Encoding::Canonical.encode(&schema, &values);
+        assert_eq!(
+            decodings(Encoding::Canonical, &schema, &bytes, 100),
+            [values]
+        );
+    }
+}
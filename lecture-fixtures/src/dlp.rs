@@ -0,0 +1,90 @@
+//! Discrete logarithm in a prime order subgroup of `Z_p^*`.
+
+use crate::rsa::next_prime;
+use num_bigint::{BigUint, RandBigInt};
+use num_traits::{One, ToPrimitive};
+use rand::Rng;
+use serde::Serialize;
+use shanks::{auto::Zmod, bsgs};
+
+/// Baby-step giant-step statistics.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BsgsStats {
+    /// Table entries and maximum number of giant steps, `⌈√q⌉`.
+    pub m: u64,
+    pub giant_steps: u64,
+    pub group_operations: u64,
+    /// Average over the exponents, `1.5·m`.
+    pub expected_group_operations: u64,
+}
+
+/// Find `x` such that `g^x = h mod p`, with `g` of prime order `q`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Instance {
+    pub p: String,
+    pub q: u64,
+    pub g: String,
+    pub h: String,
+    pub x: u64,
+    pub bsgs: BsgsStats,
+}
+
+impl Instance {
+    /// Instance with a `bits` subgroup order, in a group about 16 bits larger.
+    pub fn generate<R: Rng>(rng: &mut R, bits: u64) -> Self {
+        let q = next_prime(rng.gen_biguint(bits) | (BigUint::one() << (bits - 1)));
+        let mut k = rng.gen_biguint(16) << 1;
+        let p = loop {
+            let p = &k * &q + 1_u8;
+            if miller_rabin::is_prime(&p) {
+                break p;
+            }
+            k += 2_u8;
+        };
+        let g = (2_u8..)
+            .map(|a| BigUint::from(a).modpow(&k, &p))
+            .find(|g| !g.is_one())
+            .unwrap();
+        let q = q.to_u64().unwrap();
+        let x = rng.gen_range(1..q);
+        let h = g.modpow(&x.into(), &p);
+
+        let found = bsgs(&Zmod { n: p.clone() }, &g, &h, q);
+        assert_eq!(found, Some(x));
+        // Same table size as `bsgs`, which finds x = i·m + j after i + 1 giant steps
+        let m = (q as f64).sqrt().ceil() as u64;
+        let giant_steps = x / m + 1;
+        Instance {
+            p: p.to_string(),
+            q,
+            g: g.to_string(),
+            h: h.to_string(),
+            x,
+            bsgs: BsgsStats {
+                m,
+                giant_steps,
+                group_operations: m + giant_steps,
+                expected_group_operations: m * 3 / 2,
+            },
+        }
+    }
+
+    pub fn markdown(&self) -> String {
+        format!(
+            "## Discrete logarithm\n\n\
+            Find x such that g^x = h mod p, g of order q.\n\n\
+            - p = {}\n- q = {}\n- g = {}\n- h = {}\n- x = {}\n\n\
+            Baby-step giant-step: {} table entries, {} giant steps, \
+            {} group operations (expected {}).\n",
+            self.p,
+            self.q,
+            self.g,
+            self.h,
+            self.x,
+            self.bsgs.m,
+            self.bsgs.giant_steps,
+            self.bsgs.group_operations,
+            self.bsgs.expected_group_operations
+        )
+    }
+}
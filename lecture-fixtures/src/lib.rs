@@ -0,0 +1,121 @@
+//! Coordinated example sets for lecture notes.
+//!
+//! Every fixture is derived from a single seed, so that the numbers quoted in
+//! a post can be regenerated at any time by a single command:
+//! - [`rsa`]: a weak RSA key with close primes and its Fermat factorization
+//!   trace;
+//! - [`dlp`]: a discrete logarithm instance in a prime order subgroup and the
+//!   baby-step giant-step table statistics (see the `shanks` crate);
+//! - [`padding`]: a CBC padding oracle attack transcript (see the
+//!   `cbc-padding-oracle` crate).
+//!
+//! Each fixture gets its own ChaCha20 stream of the seed: adding a fixture or
+//! changing the parameters of one doesn't change the others.
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use serde::Serialize;
+use std::{fs, io, path::Path};
+
+pub mod dlp;
+pub mod padding;
+pub mod rsa;
+
+/// Fixture random generator, one stream per fixture.
+pub fn rng(seed: u64, stream: u64) -> ChaCha20Rng {
+    let mut rng = ChaCha20Rng::seed_from_u64(seed);
+    rng.set_stream(stream);
+    rng
+}
+
+/// Fixtures parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Params {
+    /// RSA modulus bits.
+    pub rsa_bits: u64,
+    /// DLP subgroup order bits.
+    pub dlp_bits: u64,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            rsa_bits: 512,
+            dlp_bits: 36,
+        }
+    }
+}
+
+/// All the fixtures of a seed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Bundle {
+    pub seed: u64,
+    pub params: Params,
+    pub rsa: rsa::WeakRsa,
+    pub dlp: dlp::Instance,
+    pub padding_oracle: padding::Transcript,
+}
+
+impl Bundle {
+    pub fn generate(seed: u64, params: Params) -> Self {
+        Bundle {
+            seed,
+            params,
+            rsa: rsa::WeakRsa::generate(&mut rng(seed, 0), params.rsa_bits),
+            dlp: dlp::Instance::generate(&mut rng(seed, 1), params.dlp_bits),
+            padding_oracle: padding::Transcript::generate(&mut rng(seed, 2)),
+        }
+    }
+
+    /// Markdown summary.
+    pub fn markdown(&self) -> String {
+        format!(
+            "# Fixtures (seed {})\n\n{}\n{}\n{}",
+            self.seed,
+            self.rsa.markdown(),
+            self.dlp.markdown(),
+            self.padding_oracle.markdown()
+        )
+    }
+
+    /// Write one JSON file per fixture, the whole bundle as JSON and the
+    /// markdown summary in `dir`.
+    pub fn write(&self, dir: &Path) -> io::Result<()> {
+        fn json<T: Serialize>(value: &T) -> String {
+            serde_json::to_string_pretty(value).expect("serializable") + "\n"
+        }
+        fs::create_dir_all(dir)?;
+        fs::write(dir.join("rsa.json"), json(&self.rsa))?;
+        fs::write(dir.join("dlp.json"), json(&self.dlp))?;
+        fs::write(dir.join("padding-oracle.json"), json(&self.padding_oracle))?;
+        fs::write(dir.join("bundle.json"), json(self))?;
+        fs::write(dir.join("README.md"), self.markdown())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundles_are_reproducible() {
+        let params = Params {
+            rsa_bits: 256,
+            dlp_bits: 24,
+        };
+        let a = Bundle::generate(42, params);
+        assert_eq!(a, Bundle::generate(42, params));
+        assert_ne!(a.rsa, Bundle::generate(43, params).rsa);
+        // Streams are independent
+        let b = Bundle::generate(
+            42,
+            Params {
+                rsa_bits: 320,
+                ..params
+            },
+        );
+        assert_ne!(a.rsa, b.rsa);
+        assert_eq!(a.dlp, b.dlp);
+        assert!(a.markdown().contains("Fermat"));
+    }
+}
@@ -0,0 +1,52 @@
+use clap::{Parser, Subcommand};
+use lecture_fixtures::{Bundle, Params};
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(about = "Lecture notes example sets")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate the fixtures bundle (JSON and markdown).
+    Fixtures {
+        /// Single seed of all the fixtures.
+        #[arg(short, long, default_value_t = 0)]
+        seed: u64,
+        /// Output directory.
+        #[arg(short, long, default_value = "fixtures")]
+        out: PathBuf,
+        /// RSA modulus bits.
+        #[arg(long, default_value_t = Params::default().rsa_bits)]
+        rsa_bits: u64,
+        /// DLP subgroup order bits (at most 63).
+        #[arg(long, default_value_t = Params::default().dlp_bits)]
+        dlp_bits: u64,
+        /// Print the markdown summary instead of writing the files.
+        #[arg(long)]
+        print: bool,
+    },
+}
+
+fn main() {
+    match Cli::parse().command {
+        Command::Fixtures {
+            seed,
+            out,
+            rsa_bits,
+            dlp_bits,
+            print,
+        } => {
+            let bundle = Bundle::generate(seed, Params { rsa_bits, dlp_bits });
+            if print {
+                print!("{}", bundle.markdown());
+            } else {
+                bundle.write(&out).expect("writable output directory");
+                println!("Fixtures for seed {seed} written to {}", out.display());
+            }
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! CBC padding oracle attack transcript.
+
+use aes::Aes128;
+use cbc_padding_oracle::{attack_with_stats, encrypt, CbcOracle, PaddingOracle};
+use rand::Rng;
+use serde::Serialize;
+use std::cell::RefCell;
+
+/// Oracle query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Query {
+    /// Attacked ciphertext block.
+    pub block: usize,
+    /// Forged previous block (or IV).
+    pub prev: String,
+    pub valid: bool,
+}
+
+/// AES-128-CBC padding oracle attack.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Transcript {
+    pub key: String,
+    pub iv: String,
+    pub plaintext: String,
+    pub ciphertext: String,
+    pub queries: Vec<Query>,
+    pub valid_queries: usize,
+    pub retries: usize,
+    pub recovered: String,
+}
+
+const PLAINTEXT: &str = "user=alice;role=guest;exp=1700000000";
+
+// Oracle recording the queries.
+struct Recorder<'a> {
+    oracle: CbcOracle<Aes128>,
+    ciphertext: &'a [u8],
+    log: RefCell<Vec<Query>>,
+}
+
+impl PaddingOracle for Recorder<'_> {
+    fn check(&self, iv: &[u8], ct: &[u8]) -> bool {
+        let valid = self.oracle.check(iv, ct);
+        let block = self.ciphertext.chunks(16).position(|b| b == ct).unwrap();
+        self.log.borrow_mut().push(Query {
+            block,
+            prev: hex::encode(iv),
+            valid,
+        });
+        valid
+    }
+}
+
+impl Transcript {
+    pub fn generate<R: Rng>(rng: &mut R) -> Self {
+        let (key, iv): ([u8; 16], [u8; 16]) = (rng.gen(), rng.gen());
+        let ciphertext = encrypt::<Aes128>(&key, &iv, PLAINTEXT.as_bytes());
+        let recorder = Recorder {
+            oracle: CbcOracle::new(&key),
+            ciphertext: &ciphertext,
+            log: RefCell::new(Vec::new()),
+        };
+        let (recovered, stats) = attack_with_stats(&recorder, 16, &iv, &ciphertext);
+        let queries = recorder.log.into_inner();
+        Transcript {
+            key: hex::encode(key),
+            iv: hex::encode(iv),
+            plaintext: PLAINTEXT.into(),
+            ciphertext: hex::encode(&ciphertext),
+            valid_queries: queries.iter().filter(|q| q.valid).count(),
+            queries,
+            retries: stats.total_retries(),
+            recovered: String::from_utf8_lossy(&recovered).into(),
+        }
+    }
+
+    pub fn markdown(&self) -> String {
+        let mut md = format!(
+            "## CBC padding oracle\n\n- key = {}\n- iv = {}\n- ciphertext = {}\n\n\
+            {} queries, {} with valid padding, {} false positives. Valid queries:\n\n\
+            | block | forged previous block |\n|---|---|\n",
+            self.key,
+            self.iv,
+            self.ciphertext,
+            self.queries.len(),
+            self.valid_queries,
+            self.retries
+        );
+        for q in self.queries.iter().filter(|q| q.valid) {
+            md += &format!("| {} | {} |\n", q.block, q.prev);
+        }
+        md += &format!("\nRecovered: \"{}\"\n", self.recovered);
+        md
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn transcript_recovers_plaintext() {
+        let t = Transcript::generate(&mut StdRng::seed_from_u64(0));
+        assert_eq!(t.recovered, PLAINTEXT);
+        // At least one valid query per byte, including the padding
+        assert!(t.valid_queries >= 48);
+        assert_eq!(t.queries.iter().map(|q| q.block).max(), Some(2));
+    }
+}
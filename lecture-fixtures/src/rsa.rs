@@ -0,0 +1,138 @@
+//! Weak RSA key with close primes.
+//!
+//! When `|p - q|` is small, `n = a² - b²` with `a = (p + q) / 2` just above
+//! `√n` and `b = (q - p) / 2`. Fermat's method tries `a = ⌈√n⌉, ⌈√n⌉ + 1, ...`
+//! until `a² - n` is a square, which takes about `(p - q)² / (8·√n)` steps.
+
+use num_bigint::{BigUint, RandBigInt};
+use num_traits::One;
+use rand::Rng;
+use serde::Serialize;
+
+/// Fermat's method iteration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Step {
+    pub a: String,
+    /// `a² - n`.
+    pub b2: String,
+    pub square: bool,
+}
+
+/// Weak key, its factorization trace and a sample decryption.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WeakRsa {
+    pub n: String,
+    pub e: u32,
+    pub p: String,
+    pub q: String,
+    pub d: String,
+    /// `q - p`.
+    pub gap: String,
+    pub trace: Vec<Step>,
+    pub message: String,
+    pub ciphertext: String,
+}
+
+const MESSAGE: &str = "Meet me at noon";
+
+/// First prime not less than `n`.
+pub fn next_prime(mut n: BigUint) -> BigUint {
+    if !n.bit(0) {
+        n += 1_u8;
+    }
+    while !miller_rabin::is_prime(&n) {
+        n += 2_u8;
+    }
+    n
+}
+
+/// Fermat factorization, with the trace of the attempts.
+pub fn fermat(n: &BigUint, max_steps: usize) -> (Option<(BigUint, BigUint)>, Vec<Step>) {
+    let mut a = n.sqrt();
+    if &a * &a < *n {
+        a += 1_u8;
+    }
+    let mut trace = Vec::new();
+    for _ in 0..max_steps {
+        let b2 = &a * &a - n;
+        let b = b2.sqrt();
+        let square = &b * &b == b2;
+        trace.push(Step {
+            a: a.to_string(),
+            b2: b2.to_string(),
+            square,
+        });
+        if square {
+            return (Some((&a - &b, &a + &b)), trace);
+        }
+        a += 1_u8;
+    }
+    (None, trace)
+}
+
+impl WeakRsa {
+    /// Key with a `bits` modulus, whose primes are about `2^(bits/4 + 2)`
+    /// apart, for a few Fermat steps.
+    pub fn generate<R: Rng>(rng: &mut R, bits: u64) -> Self {
+        let half = bits / 2;
+        let p = next_prime(rng.gen_biguint(half) | (BigUint::one() << (half - 1)));
+        let low = BigUint::one() << (bits / 4 + 1);
+        let q = next_prime(&p + rng.gen_biguint_range(&low, &(&low << 2)));
+        let n = &p * &q;
+        let e = 65537_u32;
+        let phi = (&p - 1_u8) * (&q - 1_u8);
+        let d = BigUint::from(e).modinv(&phi).expect("e coprime with phi");
+
+        let (factors, trace) = fermat(&n, 1000);
+        assert_eq!(factors, Some((p.clone(), q.clone())));
+        let m = BigUint::from_bytes_be(MESSAGE.as_bytes());
+        let c = m.modpow(&e.into(), &n);
+        WeakRsa {
+            n: n.to_string(),
+            e,
+            gap: (&q - &p).to_string(),
+            p: p.to_string(),
+            q: q.to_string(),
+            d: d.to_string(),
+            trace,
+            message: MESSAGE.into(),
+            ciphertext: c.to_string(),
+        }
+    }
+
+    pub fn markdown(&self) -> String {
+        let mut md = format!(
+            "## Weak RSA key\n\n- n = {}\n- e = {}\n- q - p = {}\n\n\
+            Fermat factorization in {} steps:\n\n| a | a² - n | square |\n|---|---|---|\n",
+            self.n,
+            self.e,
+            self.gap,
+            self.trace.len()
+        );
+        for step in &self.trace {
+            md += &format!("| {} | {} | {} |\n", step.a, step.b2, step.square);
+        }
+        md += &format!(
+            "\n- p = {}\n- q = {}\n- d = {}\n- c = {}\n- m = \"{}\"\n",
+            self.p, self.q, self.d, self.ciphertext, self.message
+        );
+        md
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn key_decrypts_and_factors() {
+        let key = WeakRsa::generate(&mut StdRng::seed_from_u64(3), 256);
+        let parse = |s: &str| s.parse::<BigUint>().unwrap();
+        let (n, d, c) = (parse(&key.n), parse(&key.d), parse(&key.ciphertext));
+        let m = c.modpow(&d, &n);
+        assert_eq!(m.to_bytes_be(), MESSAGE.as_bytes());
+        assert!(key.trace.last().unwrap().square);
+        assert!(key.trace.len() < 20);
+    }
+}
@@ -0,0 +1,120 @@
+//! Secret prefix MAC forgery.
+
+use crate::{digest, padding, Compression, Hasher};
+use std::marker::PhantomData;
+
+/// Naive MAC `H(secret || message)`.
+pub struct NaiveMac<C> {
+    secret: Vec<u8>,
+    _compression: PhantomData<C>,
+}
+
+impl<C: Compression> NaiveMac<C> {
+    pub fn new(secret: &[u8]) -> Self {
+        NaiveMac {
+            secret: secret.to_vec(),
+            _compression: PhantomData,
+        }
+    }
+
+    pub fn tag(&self, message: &[u8]) -> Vec<u8> {
+        digest::<C>(&[&self.secret[..], message].concat())
+    }
+
+    pub fn verify(&self, message: &[u8], tag: &[u8]) -> bool {
+        self.tag(message) == tag
+    }
+}
+
+/// Forged message and tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Forgery {
+    /// `message || padding || suffix`.
+    pub message: Vec<u8>,
+    pub tag: Vec<u8>,
+}
+
+/// Extend the `message` with known `tag` by the `suffix`, assuming the secret
+/// length.
+///
+/// Returns `None` if the forged message length in bits doesn't fit the 64 bits
+/// length field of the padding.
+pub fn extend<C: Compression>(
+    message: &[u8],
+    tag: &[u8],
+    secret_len: usize,
+    suffix: &[u8],
+) -> Option<Forgery> {
+    let len = u64::try_from(secret_len.checked_add(message.len())?).ok()?;
+    len.checked_mul(8)?;
+    let glue = padding(len);
+    let glued = len + glue.len() as u64;
+    glued.checked_add(suffix.len() as u64)?.checked_mul(8)?;
+    let mut hasher = Hasher::<C>::resume(tag, glued);
+    hasher.update(suffix);
+    Some(Forgery {
+        message: [message, &glue, suffix].concat(),
+        tag: hasher.finalize(),
+    })
+}
+
+/// Forge with the secret length unknown, trying the lengths up to
+/// `max_secret_len` against the `verify` oracle.
+///
+/// Returns the forgery and the secret length.
+pub fn forge<C, V>(
+    message: &[u8],
+    tag: &[u8],
+    suffix: &[u8],
+    max_secret_len: usize,
+    verify: V,
+) -> Option<(Forgery, usize)>
+where
+    C: Compression,
+    V: Fn(&[u8], &[u8]) -> bool,
+{
+    (0..=max_secret_len)
+        .map_while(|n| Some((extend::<C>(message, tag, n, suffix)?, n)))
+        .find(|(f, _)| verify(&f.message, &f.tag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sha::{Sha1, Sha256};
+
+    fn check<C: Compression>() {
+        let mac = NaiveMac::<C>::new(b"super secret key");
+        let message = b"user=bob&role=guest";
+        let tag = mac.tag(message);
+        let (forgery, len) =
+            forge::<C, _>(message, &tag, b"&role=admin", 64, |m, t| mac.verify(m, t)).unwrap();
+        assert_eq!(len, 16);
+        assert!(forgery.message.ends_with(b"&role=admin"));
+        assert!(mac.verify(&forgery.message, &forgery.tag));
+        assert_eq!(
+            forgery.message.len(),
+            message.len() + padding(16 + 19).len() + 11
+        );
+    }
+
+    #[test]
+    fn forgery_sha256() {
+        check::<Sha256>();
+    }
+
+    #[test]
+    fn forgery_sha1() {
+        check::<Sha1>();
+    }
+
+    #[test]
+    fn oversized_length() {
+        let tag = digest::<Sha256>(b"");
+        assert_eq!(extend::<Sha256>(b"", &tag, usize::MAX, b""), None);
+        // Glued up to 2^61 - 64 bytes, one more block overflows the bits count
+        let len = (1 << 61) - 129;
+        assert!(extend::<Sha256>(b"", &tag, len, b"").is_some());
+        assert_eq!(extend::<Sha256>(b"", &tag, len, &[0; 64]), None);
+    }
+}
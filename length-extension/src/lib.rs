@@ -0,0 +1,142 @@
+//! Merkle–Damgård length extension.
+//!
+//! SHA-1 and SHA-256 process the padded message in 64 bytes blocks, chaining
+//! the compression function `state = f(state, block)` from a fixed IV, and the
+//! digest is the final state itself. The digest of `m` thus allows to resume
+//! the computation and obtain the digest of `m || pad(m) || suffix` for any
+//! suffix, without knowing `m`, just its length.
+//!
+//! The naive MAC `H(secret || message)` is then forgeable: given the tag of a
+//! message, the attacker computes the tag of the message extended with the
+//! padding and an arbitrary suffix (e.g. `&admin=true`). The secret length is
+//! not needed in advance, as the verifier itself can be used to test the
+//! guesses.
+//!
+//! HMAC, SHA-3 (sponge) and the truncated SHA-2 variants (SHA-224, SHA-384,
+//! SHA-512/256) don't expose the whole final state and are not affected.
+
+use std::marker::PhantomData;
+
+pub mod attacks;
+pub mod sha;
+
+/// Merkle–Damgård compression function, with 64 bytes blocks and big endian
+/// 32 bits words state and length.
+pub trait Compression {
+    /// State words, the digest is the state serialization.
+    const WORDS: usize;
+
+    fn iv() -> Vec<u32>;
+
+    fn compress(state: &mut [u32], block: &[u8; 64]);
+}
+
+/// Block size in bytes.
+pub const BLOCK: usize = 64;
+
+/// Padding of a `len` bytes message: `0x80`, zeros and the bits length, up to
+/// a multiple of the block size.
+pub fn padding(len: u64) -> Vec<u8> {
+    let zeros = (BLOCK * 2 - 9 - len as usize % BLOCK) % BLOCK;
+    let mut pad = vec![0x80];
+    pad.resize(1 + zeros, 0);
+    pad.extend_from_slice(&(len * 8).to_be_bytes());
+    pad
+}
+
+/// Incremental hasher with resumable state.
+#[derive(Debug, Clone)]
+pub struct Hasher<C> {
+    state: Vec<u32>,
+    buffer: Vec<u8>,
+    /// Total bytes processed, including the ones of a resumed state.
+    len: u64,
+    _compression: PhantomData<C>,
+}
+
+impl<C: Compression> Default for Hasher<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: Compression> Hasher<C> {
+    pub fn new() -> Self {
+        Hasher {
+            state: C::iv(),
+            buffer: Vec::new(),
+            len: 0,
+            _compression: PhantomData,
+        }
+    }
+
+    /// Resume from the `digest` of a message which, padded, was `len` bytes
+    /// long (a multiple of the block size).
+    pub fn resume(digest: &[u8], len: u64) -> Self {
+        assert_eq!(digest.len(), 4 * C::WORDS, "wrong digest length");
+        assert_eq!(len % BLOCK as u64, 0, "padded length not block aligned");
+        let state = digest
+            .chunks(4)
+            .map(|w| u32::from_be_bytes(w.try_into().unwrap()))
+            .collect();
+        Hasher {
+            state,
+            buffer: Vec::new(),
+            len,
+            _compression: PhantomData,
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.len += data.len() as u64;
+        self.buffer.extend_from_slice(data);
+        let full = self.buffer.len() / BLOCK * BLOCK;
+        for block in self.buffer[..full].chunks(BLOCK) {
+            C::compress(&mut self.state, block.try_into().unwrap());
+        }
+        self.buffer.drain(..full);
+    }
+
+    pub fn finalize(mut self) -> Vec<u8> {
+        let pad = padding(self.len);
+        self.update(&pad);
+        debug_assert!(self.buffer.is_empty());
+        self.state.iter().flat_map(|w| w.to_be_bytes()).collect()
+    }
+}
+
+/// Digest of `data`.
+pub fn digest<C: Compression>(data: &[u8]) -> Vec<u8> {
+    let mut hasher = Hasher::<C>::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha::{Sha1, Sha256};
+    use sha2::Digest;
+
+    #[test]
+    fn matches_reference_implementations() {
+        for len in [0, 1, 55, 56, 63, 64, 65, 200] {
+            let data: Vec<u8> = (0..len as u8).collect();
+            assert_eq!(
+                digest::<Sha256>(&data),
+                sha2::Sha256::digest(&data).to_vec()
+            );
+            assert_eq!(digest::<Sha1>(&data), sha1::Sha1::digest(&data).to_vec());
+            assert_eq!((len + padding(len as u64).len()) % BLOCK, 0);
+        }
+    }
+
+    #[test]
+    fn resumed_state() {
+        let (m, suffix) = (b"abc".to_vec(), b"def");
+        let mut hasher = Hasher::<Sha256>::resume(&digest::<Sha256>(&m), 64);
+        hasher.update(suffix);
+        let glued = [m.clone(), padding(3), suffix.to_vec()].concat();
+        assert_eq!(hasher.finalize(), digest::<Sha256>(&glued));
+    }
+}
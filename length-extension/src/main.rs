@@ -0,0 +1,35 @@
+use length_extension::{
+    attacks::{forge, NaiveMac},
+    sha::{Sha1, Sha256},
+    Compression,
+};
+use rand::Rng;
+
+fn demo<C: Compression>(name: &str) {
+    // Server secret of unknown length
+    let mut rng = rand::thread_rng();
+    let secret: Vec<u8> = (0..rng.gen_range(8..48)).map(|_| rng.gen()).collect();
+    let mac = NaiveMac::<C>::new(&secret);
+
+    let message = b"from=alice&to=bob&amount=10";
+    let tag = mac.tag(message);
+    println!("{name}");
+    println!("  message: {}", String::from_utf8_lossy(message));
+    println!("  tag:     {}", hex::encode(&tag));
+
+    let suffix = b"&to=eve&amount=1000000";
+    let verify = |m: &[u8], t: &[u8]| mac.verify(m, t);
+    let (forgery, secret_len) = forge::<C, _>(message, &tag, suffix, 64, verify).unwrap();
+    println!("  forged:  {}", forgery.message.escape_ascii());
+    println!("  tag:     {}", hex::encode(&forgery.tag));
+    println!(
+        "  accepted: {}, secret length {secret_len} found in {} queries",
+        mac.verify(&forgery.message, &forgery.tag),
+        secret_len + 1
+    );
+}
+
+fn main() {
+    demo::<Sha1>("SHA-1");
+    demo::<Sha256>("SHA-256");
+}
@@ -0,0 +1,95 @@
+//! Attacks on LFSR based keystream generators.
+//!
+//! - [`predict`]: a stream cipher using a single LFSR (even with secret
+//!   taps) falls to `2L` known keystream bits, from a known plaintext, by
+//!   Berlekamp–Massey.
+//! - [`correlate`] (Siegenthaler): if a combination generator output agrees
+//!   with one of its registers with probability `1/2 + ε`, that register seed
+//!   is the one whose output agrees the most with the keystream. Each
+//!   register is searched on its own: `Σ 2^Lᵢ` instead of `Π 2^Lᵢ` trials.
+//!   A wrong seed agrees on `N/2 ± √N/2` of `N` bits, the right one on
+//!   `(1/2 + ε)N`: it stands out of the `2^L` candidates for
+//!   `ε√N ≫ √(L ln 2 / 2)`, i.e. a few multiples of `L / ε²` bits.
+//! - [`geffe`]: the whole Geffe key, the second and third registers by
+//!   correlation, then the first one given the other two.
+
+use crate::{berlekamp_massey, combiner, extend, Lfsr};
+use rayon::prelude::*;
+
+/// Next `n` keystream bits from the known `prefix`, with the linear
+/// complexity of the generator.
+///
+/// `None` if the prefix is shorter than twice the found complexity, as the
+/// register is not determined.
+pub fn predict(prefix: &[u8], n: usize) -> Option<(usize, Vec<u8>)> {
+    let (poly, len) = berlekamp_massey(prefix);
+    (2 * len <= prefix.len()).then(|| (len, extend(&poly, prefix, n)))
+}
+
+/// Seed of the register with the given connection polynomial exponents (see
+/// [`Lfsr::new`]) whose output agrees the most with the `keystream`.
+///
+/// Returns the seed and the fraction of agreeing bits.
+pub fn correlate(keystream: &[u8], exponents: &[u32]) -> (u64, f64) {
+    let len = *exponents.iter().max().expect("Exponents");
+    let (seed, agree) = (1..1_u64 << len)
+        .into_par_iter()
+        .map(|seed| {
+            let mut lfsr = Lfsr::new(exponents, seed);
+            let agree = keystream.iter().filter(|&&z| lfsr.bit() == z).count();
+            (seed, agree)
+        })
+        .max_by_key(|&(_, agree)| agree)
+        .expect("Seeds");
+    (seed, agree as f64 / keystream.len() as f64)
+}
+
+/// Seeds of a Geffe generator, given the registers connection polynomials
+/// exponents and enough `keystream` to correlate the second and third ones.
+pub fn geffe(keystream: &[u8], exponents: [&[u32]; 3]) -> [u64; 3] {
+    let (s2, _) = correlate(keystream, exponents[1]);
+    let (s3, _) = correlate(keystream, exponents[2]);
+    let x2 = Lfsr::new(exponents[1], s2).keystream(keystream.len());
+    let x3 = Lfsr::new(exponents[2], s3).keystream(keystream.len());
+    // The first register seed must reproduce the whole keystream
+    let len = *exponents[0].iter().max().expect("Exponents");
+    let s1 = (1..1_u64 << len)
+        .into_par_iter()
+        .find_first(|&seed| {
+            let mut x1 = Lfsr::new(exponents[0], seed);
+            (0..keystream.len()).all(|i| combiner::geffe(&[x1.bit(), x2[i], x3[i]]) == keystream[i])
+        })
+        .expect("Consistent seed");
+    [s1, s2, s3]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combiner::Combiner;
+
+    #[test]
+    fn single_register_keystream_predicted() {
+        let mut lfsr = Lfsr::new(&[31, 3], 0x1234567);
+        let bits = lfsr.keystream(1000);
+        let (len, rest) = predict(&bits[..62], 938).unwrap();
+        assert_eq!(len, 31);
+        assert_eq!(rest, &bits[62..]);
+    }
+
+    #[test]
+    fn geffe_seeds_recovered() {
+        let exponents: [&[u32]; 3] = [&[11, 2], &[15, 1], &[17, 3]];
+        let seeds = [0x5a5, 0x1234, 0x1abcd];
+        let mut combiner = Combiner::geffe(
+            Lfsr::new(exponents[0], seeds[0]),
+            Lfsr::new(exponents[1], seeds[1]),
+            Lfsr::new(exponents[2], seeds[2]),
+        );
+        let keystream = combiner.keystream(300);
+        let (seed, agree) = correlate(&keystream, exponents[2]);
+        assert_eq!(seed, seeds[2]);
+        assert!(agree > 0.65);
+        assert_eq!(geffe(&keystream, exponents), seeds);
+    }
+}
@@ -0,0 +1,89 @@
+//! Combination generators: several LFSRs stepped together, their outputs
+//! mixed by a nonlinear boolean function.
+//!
+//! The Geffe generator uses the first register to select between the other
+//! two: `z = x₁x₂ ⊕ x₁x₃ ⊕ x₃`. Its linear complexity is `L₁L₂ + L₁L₃ + L₃`,
+//! out of reach of Berlekamp–Massey for real sizes, but `z = x₂` and
+//! `z = x₃` both hold with probability 3/4: a correlation the [`attacks`]
+//! exploit to recover every register on its own.
+//!
+//! [`attacks`]: crate::attacks
+
+use crate::Lfsr;
+
+/// Combining function, of the registers output bits.
+pub type Function = fn(&[u8]) -> u8;
+
+/// Geffe combining function.
+pub fn geffe(x: &[u8]) -> u8 {
+    (x[0] & x[1]) ^ ((1 ^ x[0]) & x[2])
+}
+
+/// Probability of the output of `f` agreeing with each of its `n` inputs,
+/// over uniformly random inputs.
+pub fn correlations(f: Function, n: usize) -> Vec<f64> {
+    let mut agree = vec![0; n];
+    for v in 0..1_usize << n {
+        let x: Vec<u8> = (0..n).map(|i| (v >> i & 1) as u8).collect();
+        let z = f(&x);
+        agree
+            .iter_mut()
+            .zip(&x)
+            .for_each(|(a, &xi)| *a += (xi == z) as usize);
+    }
+    agree
+        .into_iter()
+        .map(|a| a as f64 / (1 << n) as f64)
+        .collect()
+}
+
+/// Combination generator.
+#[derive(Debug, Clone)]
+pub struct Combiner {
+    pub registers: Vec<Lfsr>,
+    f: Function,
+}
+
+impl Combiner {
+    pub fn new(registers: Vec<Lfsr>, f: Function) -> Self {
+        Combiner { registers, f }
+    }
+
+    /// Geffe generator, the first register selecting the other two.
+    pub fn geffe(x1: Lfsr, x2: Lfsr, x3: Lfsr) -> Self {
+        Self::new(vec![x1, x2, x3], geffe)
+    }
+
+    /// Output a bit and step every register.
+    pub fn bit(&mut self) -> u8 {
+        let x: Vec<u8> = self.registers.iter_mut().map(|r| r.bit()).collect();
+        (self.f)(&x)
+    }
+
+    /// Next `n` output bits.
+    pub fn keystream(&mut self, n: usize) -> Vec<u8> {
+        (0..n).map(|_| self.bit()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::berlekamp_massey;
+
+    #[test]
+    fn geffe_correlations() {
+        assert_eq!(correlations(geffe, 3), [0.5, 0.75, 0.75]);
+    }
+
+    #[test]
+    fn geffe_linear_complexity() {
+        let mut combiner = Combiner::geffe(
+            Lfsr::new(&[3, 1], 1),
+            Lfsr::new(&[4, 1], 1),
+            Lfsr::new(&[5, 2], 1),
+        );
+        let bits = combiner.keystream(200);
+        assert_eq!(berlekamp_massey(&bits).1, 3 * 4 + 3 * 5 + 5);
+    }
+}
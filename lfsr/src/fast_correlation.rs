@@ -0,0 +1,259 @@
+//! Fast correlation attack (Meier–Staffelbach, algorithm B).
+//!
+//! The keystream is seen as the output of one register sent through a binary
+//! symmetric channel: `z_n = a_n` with probability `p` (3/4 for the Geffe
+//! registers). Instead of trying the `2^L` seeds, the errors are corrected
+//! using the linear relations of the register itself.
+//!
+//! With connection polynomial `C(x) = Σ x^e` of weight `t + 1`, every
+//! `C(x)^(2^j) = C(x^(2^j))` is a multiple of the same weight, thus
+//! `Σ_e a_{n - e·2^j} = 0` for every `n` and `j` within the keystream: each
+//! bit is in ≈ `(t + 1)·log₂(N / 2L)` parity checks with `t` other bits. A
+//! satisfied check is evidence that its bits are right: the posterior
+//! probability of every bit is computed from its checks, a few times feeding
+//! the posteriors back, then the bits which are more likely wrong than right
+//! are flipped and the process restarts, until every check holds.
+//!
+//! The work is linear in the keystream length, which has to grow with the
+//! register length, the number of taps and the noise: few taps and `p` far
+//! from 1/2 are the dangerous cases. [`required_keystream`] measures it.
+//!
+//! The corrected sequence gives the seed, checked against the keystream. For
+//! the Geffe generator, with the second and third registers known, the first
+//! one is revealed where their outputs differ and its seed follows by linear
+//! algebra ([`geffe`]).
+
+use crate::{
+    combiner,
+    gf2::{BitVec, System},
+    Lfsr,
+};
+use rand::Rng;
+
+/// Decoding rounds before giving up.
+const ROUNDS: usize = 50;
+
+/// Posterior updates per round.
+const ITERATIONS: usize = 4;
+
+/// Seed of the register with the given connection polynomial exponents (see
+/// [`Lfsr::new`]), from a `keystream` agreeing with its output with
+/// probability `p`.
+///
+/// Returns the seed and the fraction of agreeing bits, the seed is wrong if
+/// this is far from `p`.
+pub fn correlate(keystream: &[u8], exponents: &[u32], p: f64) -> (u64, f64) {
+    let len = *exponents.iter().max().expect("Exponents") as usize;
+    let n = keystream.len();
+    assert!(n >= 2 * len, "Keystream too short");
+    let checks = parity_checks(exponents, n);
+    let mut membership = vec![Vec::new(); n];
+    for (c, check) in checks.iter().enumerate() {
+        check.iter().for_each(|&k| membership[k].push(c));
+    }
+
+    let prior = (p / (1.0 - p)).ln();
+    let mut z = keystream.to_vec();
+    for _ in 0..ROUNDS {
+        let satisfied: Vec<bool> = checks
+            .iter()
+            .map(|c| c.iter().fold(0, |acc, &k| acc ^ z[k]) == 0)
+            .collect();
+        if satisfied.iter().all(|&s| s) {
+            break;
+        }
+        let mut llr = vec![prior; n];
+        for _ in 0..ITERATIONS {
+            // Correlation of each bit with its true value, 2p - 1
+            let corr: Vec<f64> = llr.iter().map(|l| (l / 2.0).tanh()).collect();
+            llr = (0..n)
+                .map(|k| {
+                    membership[k].iter().fold(prior, |acc, &c| {
+                        let s: f64 = checks[c]
+                            .iter()
+                            .filter(|&&i| i != k)
+                            .map(|&i| corr[i])
+                            .product();
+                        let s = s.clamp(-0.999_999, 0.999_999);
+                        let evidence = ((1.0 + s) / (1.0 - s)).ln();
+                        if satisfied[c] {
+                            acc + evidence
+                        } else {
+                            acc - evidence
+                        }
+                    })
+                })
+                .collect();
+        }
+        z.iter_mut()
+            .zip(&llr)
+            .filter(|(_, &l)| l < 0.0)
+            .for_each(|(b, _)| *b ^= 1);
+    }
+
+    // Seeds from a few windows of the corrected sequence, the edges have
+    // fewer checks
+    (0..4)
+        .map(|w| {
+            let seed = rewind(exponents, &z, w * (n - len) / 4);
+            let mut lfsr = Lfsr::new(exponents, seed);
+            let agree = keystream.iter().filter(|&&b| lfsr.bit() == b).count();
+            (seed, agree as f64 / n as f64)
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .expect("Windows")
+}
+
+/// Shortest keystream, among `L·2^(k/2)` for `k = 2, 3, ...`, for which the
+/// attack recovered the seed in every one of `trials` simulated channels with
+/// agreement probability `p`.
+pub fn required_keystream<R: Rng + ?Sized>(
+    exponents: &[u32],
+    p: f64,
+    trials: usize,
+    rng: &mut R,
+) -> usize {
+    let len = *exponents.iter().max().expect("Exponents") as f64;
+    (2..)
+        .map(|k| (len * 2_f64.powf(k as f64 / 2.0)) as usize)
+        .find(|&n| {
+            (0..trials).all(|_| {
+                let seed = rng.gen_range(1..1 << (len as u64));
+                let keystream: Vec<u8> = Lfsr::new(exponents, seed)
+                    .keystream(n)
+                    .into_iter()
+                    .map(|b| b ^ (rng.gen::<f64>() > p) as u8)
+                    .collect();
+                correlate(&keystream, exponents, p).0 == seed
+            })
+        })
+        .expect("Some length")
+}
+
+/// Seeds of a Geffe generator, given the registers connection polynomials
+/// exponents: the second and third registers by fast correlation, the first
+/// one by linear algebra.
+///
+/// `None` if the first register equations are inconsistent, i.e. the
+/// correlation failed.
+pub fn geffe(keystream: &[u8], exponents: [&[u32]; 3]) -> Option<[u64; 3]> {
+    let p = combiner::correlations(combiner::geffe, 3)[1];
+    let (s2, _) = correlate(keystream, exponents[1], p);
+    let (s3, _) = correlate(keystream, exponents[2], p);
+    let n = keystream.len();
+    let x2 = Lfsr::new(exponents[1], s2).keystream(n);
+    let x3 = Lfsr::new(exponents[2], s3).keystream(n);
+    // Where x₂ ≠ x₃ the output is x₂ iff x₁ = 1
+    let len = *exponents[0].iter().max().expect("Exponents") as usize;
+    let mut system = System::new(len);
+    for (k, eq) in output_equations(exponents[0], n).into_iter().enumerate() {
+        if x2[k] != x3[k] {
+            system.push(eq, (keystream[k] == x2[k]) as u8);
+        }
+    }
+    let s1 = system.solve()?;
+    let s1 = s1
+        .to_bits()
+        .iter()
+        .rev()
+        .fold(0, |acc, &b| acc << 1 | b as u64);
+    Some([s1, s2, s3])
+}
+
+/// First `n` output bits of the register as linear functions of its seed bits.
+pub fn output_equations(exponents: &[u32], n: usize) -> Vec<BitVec> {
+    let len = *exponents.iter().max().expect("Exponents") as usize;
+    let mut eqs: Vec<BitVec> = (0..len.min(n)).map(|i| BitVec::unit(len, i)).collect();
+    for k in len..n {
+        let mut eq = BitVec::zeros(len);
+        exponents.iter().for_each(|&e| eq.xor(&eqs[k - e as usize]));
+        eqs.push(eq);
+    }
+    eqs
+}
+
+// Positions of the parity checks `Σ_e a_{n - e·2^j} = 0` within `n` bits.
+fn parity_checks(exponents: &[u32], n: usize) -> Vec<Vec<usize>> {
+    let len = *exponents.iter().max().expect("Exponents") as usize;
+    let mut checks = Vec::new();
+    let mut step = 1;
+    while len * step < n {
+        for last in len * step..n {
+            let check = std::iter::once(last)
+                .chain(exponents.iter().map(|&e| last - e as usize * step))
+                .collect();
+            checks.push(check);
+        }
+        step *= 2;
+    }
+    checks
+}
+
+// Seed of the sequence whose bits from `offset` are `z[offset..]`, running the
+// recurrence backwards (the highest exponent coefficient is one).
+fn rewind(exponents: &[u32], z: &[u8], offset: usize) -> u64 {
+    let len = *exponents.iter().max().expect("Exponents") as usize;
+    let mut seq = vec![0; offset + len];
+    seq[offset..].copy_from_slice(&z[offset..offset + len]);
+    for k in (0..offset).rev() {
+        // a_{k+L} = Σ_e a_{k+L-e}, solved for a_k
+        let bit = exponents
+            .iter()
+            .filter(|&&e| e as usize != len)
+            .fold(seq[k + len], |acc, &e| acc ^ seq[k + len - e as usize]);
+        seq[k] = bit;
+    }
+    seq[..len]
+        .iter()
+        .rev()
+        .fold(0, |acc, &b| acc << 1 | b as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combiner::Combiner;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn equations_and_rewind() {
+        let exponents = [23, 5];
+        let bits = Lfsr::new(&exponents, 0x2abcde).keystream(100);
+        let seed = BitVec::from_bits(&bits[..23]);
+        for (k, eq) in output_equations(&exponents, 100).iter().enumerate() {
+            assert_eq!(eq.dot(&seed), bits[k]);
+        }
+        assert_eq!(rewind(&exponents, &bits, 60), 0x2abcde);
+        for check in parity_checks(&exponents, 100) {
+            assert_eq!(check.iter().fold(0, |acc, &k| acc ^ bits[k]), 0);
+        }
+    }
+
+    #[test]
+    fn noisy_register_decoded() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let exponents = [41, 3];
+        let seed = 0x0123_4567_89ab;
+        let keystream: Vec<u8> = Lfsr::new(&exponents, seed)
+            .keystream(4000)
+            .into_iter()
+            .map(|b| b ^ (rng.gen::<f64>() > 0.75) as u8)
+            .collect();
+        let (found, agree) = correlate(&keystream, &exponents, 0.75);
+        assert_eq!(found, seed);
+        assert!((agree - 0.75).abs() < 0.05);
+    }
+
+    #[test]
+    fn geffe_key_recovered() {
+        let exponents: [&[u32]; 3] = [&[33, 13], &[41, 3], &[47, 5]];
+        let seeds = [0x1_2345_6789, 0x1ab_cdef_0123, 0x7abc_def0_1234];
+        let mut combiner = Combiner::geffe(
+            Lfsr::new(exponents[0], seeds[0]),
+            Lfsr::new(exponents[1], seeds[1]),
+            Lfsr::new(exponents[2], seeds[2]),
+        );
+        let keystream = combiner.keystream(6000);
+        assert_eq!(geffe(&keystream, exponents), Some(seeds));
+    }
+}
@@ -0,0 +1,229 @@
+//! Linear algebra over GF(2).
+//!
+//! Vectors are packed in 64 bits words, systems are solved by Gaussian
+//! elimination. Enough for the equations of the attacks: the output bits of an
+//! LFSR as linear functions of its seed, the superpolys of a cube attack.
+
+/// Packed bit vector.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BitVec {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitVec {
+    pub fn zeros(len: usize) -> Self {
+        BitVec {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    /// The `i`-th unit vector.
+    pub fn unit(len: usize, i: usize) -> Self {
+        let mut v = Self::zeros(len);
+        v.set(i, 1);
+        v
+    }
+
+    /// From bits, one per byte.
+    pub fn from_bits(bits: &[u8]) -> Self {
+        let mut v = Self::zeros(bits.len());
+        bits.iter().enumerate().for_each(|(i, &b)| v.set(i, b));
+        v
+    }
+
+    /// Bits, one per byte.
+    pub fn to_bits(&self) -> Vec<u8> {
+        (0..self.len).map(|i| self.get(i)).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, i: usize) -> u8 {
+        (self.words[i / 64] >> (i % 64) & 1) as u8
+    }
+
+    pub fn set(&mut self, i: usize, bit: u8) {
+        let mask = 1 << (i % 64);
+        if bit & 1 == 1 {
+            self.words[i / 64] |= mask;
+        } else {
+            self.words[i / 64] &= !mask;
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    /// Number of ones.
+    pub fn weight(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Inner product.
+    pub fn dot(&self, other: &BitVec) -> u8 {
+        let ones: u32 = self
+            .words
+            .iter()
+            .zip(&other.words)
+            .map(|(a, b)| (a & b).count_ones())
+            .sum();
+        (ones & 1) as u8
+    }
+
+    pub fn xor(&mut self, other: &BitVec) {
+        self.words
+            .iter_mut()
+            .zip(&other.words)
+            .for_each(|(a, b)| *a ^= b);
+    }
+
+    // Index of the first one.
+    fn leading(&self) -> Option<usize> {
+        self.words
+            .iter()
+            .position(|&w| w != 0)
+            .map(|i| 64 * i + self.words[i].trailing_zeros() as usize)
+    }
+}
+
+/// Linear system `a·x = b` in `vars` unknowns.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct System {
+    vars: usize,
+    rows: Vec<(BitVec, u8)>,
+}
+
+impl System {
+    pub fn new(vars: usize) -> Self {
+        System {
+            vars,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Adds the equation `a·x = b`.
+    pub fn push(&mut self, a: BitVec, b: u8) {
+        assert_eq!(a.len(), self.vars, "Equation length");
+        self.rows.push((a, b & 1));
+    }
+
+    /// Rank of the coefficients matrix.
+    pub fn rank(&self) -> usize {
+        self.echelon().map_or(0, |rows| rows.len())
+    }
+
+    /// A solution, with the free unknowns set to zero.
+    ///
+    /// `None` if the system is inconsistent.
+    pub fn solve(&self) -> Option<BitVec> {
+        let rows = self.echelon()?;
+        let mut x = BitVec::zeros(self.vars);
+        // Pivots are distinct and every row is reduced against the others
+        for (a, b) in &rows {
+            x.set(a.leading().expect("Pivot"), *b);
+        }
+        Some(x)
+    }
+
+    /// Every solution, as a particular one and a basis of the solutions of
+    /// the homogeneous system (one vector per free unknown).
+    ///
+    /// `None` if the system is inconsistent.
+    pub fn solutions(&self) -> Option<(BitVec, Vec<BitVec>)> {
+        let rows = self.echelon()?;
+        let mut x = BitVec::zeros(self.vars);
+        let mut pivots = Vec::with_capacity(rows.len());
+        for (a, b) in &rows {
+            let lead = a.leading().expect("Pivot");
+            x.set(lead, *b);
+            pivots.push(lead);
+        }
+        let kernel = (0..self.vars)
+            .filter(|i| !pivots.contains(i))
+            .map(|free| {
+                let mut v = BitVec::unit(self.vars, free);
+                for ((a, _), &lead) in rows.iter().zip(&pivots) {
+                    v.set(lead, a.get(free));
+                }
+                v
+            })
+            .collect();
+        Some((x, kernel))
+    }
+
+    // Reduced row echelon form, without zero rows. `None` if a zero row has
+    // a nonzero right hand side.
+    fn echelon(&self) -> Option<Vec<(BitVec, u8)>> {
+        let mut pivots: Vec<(BitVec, u8)> = Vec::new();
+        for (a, b) in &self.rows {
+            let (mut a, mut b) = (a.clone(), *b);
+            for (p, pb) in &pivots {
+                if a.get(p.leading().expect("Pivot")) == 1 {
+                    a.xor(p);
+                    b ^= pb;
+                }
+            }
+            let Some(lead) = a.leading() else {
+                if b == 1 {
+                    return None;
+                }
+                continue;
+            };
+            for (p, pb) in pivots.iter_mut() {
+                if p.get(lead) == 1 {
+                    p.xor(&a);
+                    *pb ^= b;
+                }
+            }
+            pivots.push((a, b));
+        }
+        Some(pivots)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bit_vectors() {
+        let mut v = BitVec::from_bits(&[1, 0, 1, 1]);
+        assert_eq!((v.len(), v.weight()), (4, 3));
+        v.xor(&BitVec::unit(4, 2));
+        assert_eq!(v.to_bits(), [1, 0, 0, 1]);
+        assert_eq!(v.dot(&BitVec::from_bits(&[1, 1, 1, 1])), 0);
+        assert_eq!(BitVec::unit(100, 70).leading(), Some(70));
+    }
+
+    #[test]
+    fn solve_systems() {
+        // x0 + x1 = 1, x1 + x2 = 0, x0 + x2 = 1 (dependent), x3 free
+        let mut system = System::new(4);
+        for (row, b) in [([1, 1, 0, 0], 1), ([0, 1, 1, 0], 0), ([1, 0, 1, 0], 1)] {
+            system.push(BitVec::from_bits(&row), b);
+        }
+        assert_eq!(system.rank(), 2);
+        let x = system.solve().unwrap();
+        for (row, b) in &system.rows {
+            assert_eq!(row.dot(&x), *b);
+        }
+        let (_, kernel) = system.solutions().unwrap();
+        assert_eq!(kernel.len(), 2);
+        for v in kernel {
+            let mut y = x.clone();
+            y.xor(&v);
+            assert!(system.rows.iter().all(|(row, b)| row.dot(&y) == *b));
+        }
+        system.push(BitVec::from_bits(&[1, 0, 1, 0]), 0);
+        assert_eq!(system.solve(), None);
+    }
+}
@@ -0,0 +1,192 @@
+//! Linear feedback shift registers over GF(2).
+//!
+//! An LFSR of length `L` with connection polynomial
+//! `C(x) = 1 + c₁x + … + c_L·x^L` outputs the sequence defined by its first
+//! `L` bits (the seed) and the recurrence
+//!
+//! `s_n = c₁·s_{n-1} ⊕ c₂·s_{n-2} ⊕ … ⊕ c_L·s_{n-L}`
+//!
+//! With a primitive polynomial the period is `2^L - 1`, yet the sequence is
+//! as weak as a keystream can be: the shortest LFSR generating a sequence (its
+//! *linear complexity*) is found from `2L` bits by [`berlekamp_massey`], and
+//! then the whole keystream follows. Nonlinear combinations of several
+//! registers ([`combiner`]) raise the linear complexity but may leak the
+//! single registers through correlations ([`attacks`], [`fast_correlation`]).
+//!
+//! Bit sequences are one bit per byte.
+
+pub mod attacks;
+pub mod combiner;
+pub mod fast_correlation;
+pub mod gf2;
+
+/// LFSR of length up to 64 (Fibonacci form).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lfsr {
+    len: u32,
+    // c_i is the bit L - i
+    taps: u64,
+    // Next L output bits, the next one in the least significant bit
+    state: u64,
+}
+
+impl Lfsr {
+    /// Register with connection polynomial `1 + Σ x^e` for the given
+    /// exponents (e.g. `[17, 3]` for `1 + x³ + x¹⁷`), the length being the
+    /// highest one. The seed gives the first output bits, least significant
+    /// first.
+    pub fn new(exponents: &[u32], seed: u64) -> Self {
+        let len = *exponents.iter().max().expect("Exponents");
+        assert!((1..=64).contains(&len), "Length in 1..=64");
+        let taps = exponents.iter().fold(0, |acc, e| acc | 1 << (len - e));
+        Lfsr {
+            len,
+            taps,
+            state: seed & mask(len),
+        }
+    }
+
+    /// Register with the given connection polynomial coefficients, from the
+    /// constant term (as returned by [`berlekamp_massey`]).
+    pub fn from_poly(poly: &[u8], seed: u64) -> Self {
+        let exponents: Vec<u32> = (1..poly.len() as u32)
+            .filter(|&e| poly[e as usize] == 1)
+            .collect();
+        Self::new(&exponents, seed)
+    }
+
+    pub fn length(&self) -> u32 {
+        self.len
+    }
+
+    /// Connection polynomial coefficients, from the constant term.
+    pub fn poly(&self) -> Vec<u8> {
+        (0..=self.len)
+            .map(|e| (e == 0 || self.taps >> (self.len - e) & 1 == 1) as u8)
+            .collect()
+    }
+
+    /// Next `length()` output bits, least significant first.
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// Output a bit and step.
+    pub fn bit(&mut self) -> u8 {
+        let out = self.state & 1;
+        let feedback = (self.state & self.taps).count_ones() as u64 & 1;
+        self.state = self.state >> 1 | feedback << (self.len - 1);
+        out as u8
+    }
+
+    /// Next `n` output bits.
+    pub fn keystream(&mut self, n: usize) -> Vec<u8> {
+        (0..n).map(|_| self.bit()).collect()
+    }
+}
+
+/// Shortest LFSR generating the `bits`.
+///
+/// Returns the connection polynomial coefficients, from the constant term,
+/// and the linear complexity `L` (the polynomial degree may be less than `L`).
+/// The LFSR is unique if there are at least `2L` bits.
+pub fn berlekamp_massey(bits: &[u8]) -> (Vec<u8>, usize) {
+    let n = bits.len();
+    let mut c = vec![0_u8; n + 1];
+    c[0] = 1;
+    // Polynomial before the last length change, `m` steps ago
+    let mut b = c.clone();
+    let (mut len, mut m) = (0, 1);
+    for i in 0..n {
+        let discrepancy = (0..=len).fold(0, |acc, j| acc ^ (c[j] & bits[i - j]));
+        if discrepancy == 0 {
+            m += 1;
+            continue;
+        }
+        let prev = c.clone();
+        for j in m..=n {
+            c[j] ^= b[j - m];
+        }
+        if 2 * len <= i {
+            len = i + 1 - len;
+            b = prev;
+            m = 1;
+        } else {
+            m += 1;
+        }
+    }
+    c.truncate(len + 1);
+    (c, len)
+}
+
+/// Continues the `bits` for `n` more bits with the recurrence of the
+/// connection polynomial `poly`.
+///
+/// Requires at least `poly.len() - 1` bits.
+pub fn extend(poly: &[u8], bits: &[u8], n: usize) -> Vec<u8> {
+    let len = poly.len() - 1;
+    assert!(bits.len() >= len, "Not enough bits");
+    let mut seq = bits.to_vec();
+    for _ in 0..n {
+        let k = seq.len();
+        let next = (1..=len).fold(0, |acc, i| acc ^ (poly[i] & seq[k - i]));
+        seq.push(next);
+    }
+    seq.split_off(bits.len())
+}
+
+/// Bytes to bits, least significant first.
+pub fn to_bits(bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .iter()
+        .flat_map(|b| (0..8).map(move |i| b >> i & 1))
+        .collect()
+}
+
+/// Bits to bytes, least significant first.
+pub fn to_bytes(bits: &[u8]) -> Vec<u8> {
+    bits.chunks(8)
+        .map(|c| c.iter().rev().fold(0, |acc, b| acc << 1 | b))
+        .collect()
+}
+
+fn mask(len: u32) -> u64 {
+    u64::MAX >> (64 - len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maximal_period() {
+        // 1 + x + x⁴ is primitive
+        let mut lfsr = Lfsr::new(&[4, 1], 0b0001);
+        let start = lfsr.state();
+        let period = (1..).find(|_| {
+            lfsr.bit();
+            lfsr.state() == start
+        });
+        assert_eq!(period, Some(15));
+        assert_eq!(lfsr.poly(), [1, 1, 0, 0, 1]);
+    }
+
+    #[test]
+    fn berlekamp_massey_recovers_the_register() {
+        let exponents = [32, 22, 2, 1];
+        let lfsr = Lfsr::new(&exponents, 0xdeadbeef);
+        let bits = lfsr.clone().keystream(200);
+        let (poly, len) = berlekamp_massey(&bits[..64]);
+        assert_eq!(len, 32);
+        assert_eq!(poly, lfsr.poly());
+        assert_eq!(extend(&poly, &bits[..64], 136), &bits[64..]);
+        assert_eq!(Lfsr::from_poly(&poly, 0xdeadbeef), lfsr);
+        assert_eq!(berlekamp_massey(&[0, 0, 0, 1]).1, 4);
+    }
+
+    #[test]
+    fn bits_round_trip() {
+        assert_eq!(to_bits(&[0x81]), [1, 0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(to_bytes(&to_bits(b"lfsr")), b"lfsr");
+    }
+}
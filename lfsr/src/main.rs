@@ -0,0 +1,100 @@
+use lfsr::{
+    attacks::{correlate, geffe, predict},
+    combiner::{correlations, Combiner},
+    fast_correlation::{self, required_keystream},
+    to_bits, to_bytes, Lfsr,
+};
+use rand::Rng;
+use std::time::Instant;
+
+fn main() {
+    let mut rng = rand::thread_rng();
+
+    // Secret taps and seed
+    let message = b"Subject: wire transfer. Send 10000 EUR to IT60X0542811101000000123456";
+    let mut lfsr = Lfsr::new(&[32, 22, 2, 1], rng.gen());
+    let keystream = lfsr.keystream(8 * message.len());
+    let ct: Vec<u8> = to_bits(message)
+        .iter()
+        .zip(&keystream)
+        .map(|(m, k)| m ^ k)
+        .collect();
+    // Known plaintext prefix
+    let known = b"Subject:";
+    let prefix: Vec<u8> = to_bits(known).iter().zip(&ct).map(|(m, c)| m ^ c).collect();
+    let (len, rest) = predict(&prefix, ct.len() - prefix.len()).expect("Enough bits");
+    let pt: Vec<u8> = prefix
+        .iter()
+        .chain(&rest)
+        .zip(&ct)
+        .map(|(k, c)| k ^ c)
+        .collect();
+    println!("Single LFSR stream cipher, {} known bits", prefix.len());
+    println!("  linear complexity: {len}");
+    println!("  decrypted: {}", String::from_utf8_lossy(&to_bytes(&pt)));
+
+    let exponents: [&[u32]; 3] = [&[13, 4, 3, 1], &[17, 3], &[20, 3]];
+    let seeds = exponents.map(|e| rng.gen_range(1..1 << e[0]));
+    let registers = exponents
+        .iter()
+        .zip(seeds)
+        .map(|(e, s)| Lfsr::new(e, s))
+        .collect();
+    let mut combiner = Combiner::new(registers, lfsr::combiner::geffe);
+    let n = 400;
+    let keystream = combiner.keystream(n);
+    println!("\nGeffe generator, registers 13, 17 and 20 bits, {n} keystream bits");
+    println!(
+        "  correlations: {:?}",
+        correlations(lfsr::combiner::geffe, 3)
+    );
+    let start = Instant::now();
+    for (i, e) in exponents.iter().enumerate().skip(1) {
+        let (seed, agree) = correlate(&keystream, e);
+        println!("  register {}: seed {seed:#x}, agreement {agree:.3}", i + 1);
+    }
+    let found = geffe(&keystream, exponents);
+    println!(
+        "  key {:x?} ({}) in {:.1}s, vs 2^50 exhaustive",
+        found,
+        if found == seeds { "correct" } else { "wrong" },
+        start.elapsed().as_secs_f64()
+    );
+
+    println!("\nFast correlation, keystream for 3 of 3 successes");
+    for p in [0.75, 0.7] {
+        for exponents in [&[31, 3][..], &[41, 3], &[47, 5], &[60, 1], &[32, 22, 2, 1]] {
+            let n = required_keystream(exponents, p, 3, &mut rng);
+            println!(
+                "  p = {p}, L = {:2}, {} taps: {:6} bits ({:.0}·L)",
+                exponents[0],
+                exponents.len(),
+                n,
+                n as f64 / exponents[0] as f64
+            );
+        }
+    }
+
+    let exponents: [&[u32]; 3] = [&[33, 13], &[41, 3], &[47, 5]];
+    let seeds = exponents.map(|e| rng.gen_range(1..1 << e[0]));
+    let mut combiner = Combiner::geffe(
+        Lfsr::new(exponents[0], seeds[0]),
+        Lfsr::new(exponents[1], seeds[1]),
+        Lfsr::new(exponents[2], seeds[2]),
+    );
+    let n = 6000;
+    let keystream = combiner.keystream(n);
+    println!("\nGeffe generator, registers 33, 41 and 47 bits, {n} keystream bits");
+    let start = Instant::now();
+    let found = fast_correlation::geffe(&keystream, exponents);
+    println!(
+        "  key {:x?} ({}) in {:.2}s, vs 2^121 exhaustive",
+        found,
+        if found == Some(seeds) {
+            "correct"
+        } else {
+            "wrong"
+        },
+        start.elapsed().as_secs_f64()
+    );
+}
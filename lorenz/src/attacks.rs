@@ -0,0 +1,257 @@
+//! Chi wheels setting by Tutte's double delta method.
+//!
+//! Differencing adjacent characters, `ΔZ = ΔP ⊕ Δχ ⊕ Δψ'`. The psi wheels stand
+//! still about half of the times, then `Δψ' = 0`, and the plaintext has its own
+//! biases: for some impulses `i` and `j`, `ΔP_i ⊕ ΔP_j` is a dot (zero) with a
+//! probability away from 1/2 (in German traffic the impulses 1 and 2, thanks to
+//! doubled letters and shifts). Both biases survive the sum, thus with the
+//! right chi positions
+//!
+//! ΔZ_i ⊕ ΔZ_j ⊕ Δχ_i ⊕ Δχ_j = (ΔP_i ⊕ ΔP_j) ⊕ (Δψ'_i ⊕ Δψ'_j)
+//!
+//! is biased as well, while with the wrong ones it's a coin toss. As Colossus
+//! did, the two chi wheels of the most biased pair of impulses are set together
+//! by counting the dots for every pair of positions.
+//!
+//! Once some wheels are set, the de-chi'd deltas `ΔD = ΔP ⊕ Δψ'` of their
+//! impulses are known and every further wheel is set alone: each position is
+//! scored by the log-likelihood of the de-chi'd deltas of the set impulses plus
+//! the candidate one, under the plaintext deltas distribution when the psi
+//! wheels stand still and uniform otherwise. The most informative wheel is
+//! set first. A run is trusted when its best score stands out of the others by
+//! several standard deviations.
+//!
+//! The plaintext statistics come from a sample of the language, by default the
+//! English corpus of the `classical` crate.
+
+use crate::{encode, impulse, Wheels};
+use classical::scoring::CORPUS;
+
+/// Plaintext statistics: frequencies of the differences of adjacent codes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Language {
+    pub deltas: [f64; 32],
+}
+
+impl Language {
+    /// Statistics of a sample plaintext, every delta seen at least once.
+    pub fn train(codes: &[u8]) -> Self {
+        let mut counts = [1_usize; 32];
+        codes
+            .windows(2)
+            .for_each(|w| counts[(w[0] ^ w[1]) as usize] += 1);
+        let total = counts.iter().sum::<usize>() as f64;
+        Language {
+            deltas: counts.map(|c| c as f64 / total),
+        }
+    }
+
+    /// Statistics of the English [`CORPUS`].
+    pub fn english() -> Self {
+        Self::train(&encode(CORPUS))
+    }
+
+    /// Probability of a dot in `ΔP_i ⊕ ΔP_j`, minus 1/2.
+    pub fn bias(&self, i: usize, j: usize) -> f64 {
+        (0..32)
+            .filter(|&v| impulse(v, i) == impulse(v, j))
+            .map(|v| self.deltas[v as usize])
+            .sum::<f64>()
+            - 0.5
+    }
+
+    // Most biased pair of distinct impulses.
+    fn best_pair(&self) -> (usize, usize) {
+        (0..5)
+            .flat_map(|i| (i + 1..5).map(move |j| (i, j)))
+            .max_by(|&(a, b), &(c, d)| self.bias(a, b).abs().total_cmp(&self.bias(c, d).abs()))
+            .unwrap()
+    }
+
+    // Log-likelihoods of the de-chi'd deltas of the impulses in `mask`, with
+    // the psi wheels standing still with probability `stand`.
+    fn log_likelihoods(&self, mask: u8, stand: f64) -> [f64; 32] {
+        let mut marginal = [0.0; 32];
+        (0..32).for_each(|v| marginal[v & mask as usize] += self.deltas[v]);
+        let uniform = 1.0 / (1 << mask.count_ones()) as f64;
+        marginal.map(|p| (stand * p + (1.0 - stand) * uniform).ln())
+    }
+
+    // Expected log-likelihood gain over random text of the de-chi'd deltas of
+    // the impulses in `mask`, per character.
+    fn information(&self, mask: u8, stand: f64) -> f64 {
+        let table = self.log_likelihoods(mask, stand);
+        let uniform = (1.0 / (1 << mask.count_ones()) as f64).ln();
+        (0..32)
+            .filter(|&v| v & mask == v)
+            .map(|v| table[v as usize].exp() * (table[v as usize] - uniform))
+            .sum()
+    }
+}
+
+/// Counting run outcome.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Run {
+    /// Chi wheels set by the run.
+    pub wheels: Vec<usize>,
+    /// Their best positions.
+    pub positions: Vec<usize>,
+    /// Distance of the best score from the mean of the others, in standard
+    /// deviations.
+    pub sigma: f64,
+}
+
+/// Chi wheels start positions of the ciphertext `ct`, given the wheels
+/// patterns (the psi ones are not used).
+///
+/// Returns the positions and the runs which found them.
+pub fn set_chi(ct: &[u8], wheels: &Wheels, language: &Language) -> ([usize; 5], Vec<Run>) {
+    let chi = &wheels.chi;
+    let dz: Vec<_> = (0..5).map(|i| delta(ct, i)).collect();
+    // Δχ_i at step t from position s is dchi[i][(s + t) % len]
+    let dchi: Vec<Vec<u8>> = chi
+        .iter()
+        .map(|w| (0..w.len()).map(|k| w[k] ^ w[(k + 1) % w.len()]).collect())
+        .collect();
+    let dechi = |i: usize, pos: usize| -> Vec<u8> {
+        let len = dchi[i].len();
+        dz[i]
+            .iter()
+            .enumerate()
+            .map(|(t, &z)| z ^ dchi[i][(pos + t) % len])
+            .collect()
+    };
+    // The psi wheels move when the second motor wheel shows a cross
+    let mu = &wheels.mu[1];
+    let stand = mu.iter().filter(|&&m| m == 0).count() as f64 / mu.len() as f64;
+
+    let mut runs = Vec::new();
+    let mut positions = [0; 5];
+    let (a, b) = language.best_pair();
+    let bs: Vec<_> = (0..chi[b].len()).map(|pos| dechi(b, pos)).collect();
+    let sign = language.bias(a, b).signum();
+    let scores: Vec<(usize, usize, f64)> = (0..chi[a].len())
+        .flat_map(|pa| {
+            let xa = dechi(a, pa);
+            bs.iter()
+                .enumerate()
+                .map(|(pb, xb)| (pa, pb, sign * agreement(&xa, xb) as f64))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    let ((pa, pb, _), sigma) = best(&scores, |s| s.2);
+    runs.push(Run {
+        wheels: vec![a, b],
+        positions: vec![pa, pb],
+        sigma,
+    });
+    positions[a] = pa;
+    positions[b] = pb;
+
+    // De-chi'd deltas of the set impulses
+    let bit = |i: usize| 1_u8 << (4 - i);
+    let mut mask = bit(a) | bit(b);
+    let mut known: Vec<u8> = dechi(a, pa)
+        .iter()
+        .zip(&bs[pb])
+        .map(|(&x, &y)| (x * bit(a)) | (y * bit(b)))
+        .collect();
+    while mask != 0b11111 {
+        let k = (0..5)
+            .filter(|&k| mask & bit(k) == 0)
+            .max_by(|&x, &y| {
+                let info = |k| language.information(mask | bit(k), stand);
+                info(x).total_cmp(&info(y))
+            })
+            .unwrap();
+        let table = language.log_likelihoods(mask | bit(k), stand);
+        let scores: Vec<(usize, Vec<u8>, f64)> = (0..chi[k].len())
+            .map(|pos| {
+                let x: Vec<u8> = known
+                    .iter()
+                    .zip(dechi(k, pos))
+                    .map(|(&d, x)| d | (x * bit(k)))
+                    .collect();
+                let score = x.iter().map(|&d| table[d as usize]).sum();
+                (pos, x, score)
+            })
+            .collect();
+        let ((pos, x, _), sigma) = best(&scores, |s| s.2);
+        runs.push(Run {
+            wheels: vec![k],
+            positions: vec![pos],
+            sigma,
+        });
+        positions[k] = pos;
+        mask |= bit(k);
+        known = x;
+    }
+    (positions, runs)
+}
+
+// Impulse `i` differences of adjacent codes.
+fn delta(codes: &[u8], i: usize) -> Vec<u8> {
+    codes
+        .windows(2)
+        .map(|w| impulse(w[0], i) ^ impulse(w[1], i))
+        .collect()
+}
+
+// Dots minus crosses of the sum of two bit streams.
+fn agreement(x: &[u8], y: &[u8]) -> i64 {
+    x.iter()
+        .zip(y)
+        .map(|(a, b)| if a == b { 1 } else { -1 })
+        .sum()
+}
+
+// Best scoring candidate, with its distance from the mean of the others in
+// standard deviations.
+fn best<T: Clone>(candidates: &[T], score: impl Fn(&T) -> f64) -> (T, f64) {
+    let (top, _) = candidates
+        .iter()
+        .enumerate()
+        .max_by(|x, y| score(x.1).total_cmp(&score(y.1)))
+        .expect("Candidates");
+    let others: Vec<f64> = candidates
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| i != top)
+        .map(|(_, c)| score(c))
+        .collect();
+    let n = others.len() as f64;
+    let mean = others.iter().sum::<f64>() / n;
+    let var = others.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n;
+    let top = &candidates[top];
+    (top.clone(), (score(top) - mean) / var.sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Lorenz, Settings};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn english_deltas_are_biased() {
+        let language = Language::english();
+        let (a, b) = language.best_pair();
+        assert!(language.bias(a, b).abs() > 0.05);
+        assert!((language.bias(0, 0) - 0.5).abs() < 1e-9);
+        assert!((language.deltas.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn chi_setting_recovered() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (wheels, settings) = (Wheels::random(&mut rng), Settings::random(&mut rng));
+        let corpus = encode(CORPUS);
+        let start = rng.gen_range(0..corpus.len() - 4000);
+        let pt = &corpus[start..start + 4000];
+        let ct = Lorenz::new(wheels.clone(), settings).process(pt);
+
+        let (positions, runs) = set_chi(&ct, &wheels, &Language::english());
+        assert_eq!(positions, settings.chi);
+        assert!(runs[0].sigma > 4.0, "{runs:?}");
+    }
+}
@@ -0,0 +1,209 @@
+//! Lorenz SZ40 teleprinter cipher ("Tunny"), toy model.
+//!
+//! Characters are 5 bits teleprinter codes (ITA2), each bit an "impulse". The
+//! key is the sum of two streams, each one made by five wheels with pins
+//! (one per impulse):
+//! - the chi wheels, of coprime lengths, step at every character;
+//! - the psi wheels step all together, but only when the motor wheels say so:
+//!   the first motor wheel steps at every character, the second one when the
+//!   first one shows a cross, the psi wheels when the second one does.
+//!
+//! The ciphertext is `Z = P ⊕ χ ⊕ ψ'`, with `ψ'` the extended (irregularly
+//! stepped) psi stream. Encryption and decryption are the same operation.
+//!
+//! The psi wheels standing still is the weakness exploited by the [`attacks`]:
+//! the stream differences `Δψ'` are often zero, thus `ΔZ` leaks the statistics
+//! of `ΔP ⊕ Δχ`. Wheel limitations and the other historical refinements are
+//! not modeled.
+//!
+//! Codes are printed with the Bletchley Park notation (see [`ALPHABET`]).
+
+use rand::Rng;
+
+pub mod attacks;
+
+/// Chi wheels lengths.
+pub const CHI: [usize; 5] = [41, 31, 29, 26, 23];
+
+/// Psi wheels lengths.
+pub const PSI: [usize; 5] = [43, 47, 51, 53, 59];
+
+/// Motor wheels lengths.
+pub const MU: [usize; 2] = [61, 37];
+
+/// Teleprinter codes, with the first impulse as the most significant bit.
+/// `9` is the space, `3` and `4` line feed and carriage return, `5` and `8`
+/// the figure and letter shifts, `/` the null code.
+pub const ALPHABET: &[u8; 32] = b"/T3O9HNM4LRGIPCVEZDBSYFXAWJ5UQK8";
+
+/// Impulse `i` (in `0..5`) of a code.
+pub fn impulse(code: u8, i: usize) -> u8 {
+    (code >> (4 - i)) & 1
+}
+
+/// Text to codes: letters and spaces, anything else is dropped and a run of
+/// whitespaces becomes a single space.
+pub fn encode(text: &str) -> Vec<u8> {
+    let space = code(b'9');
+    let mut codes = Vec::with_capacity(text.len());
+    for b in text.bytes() {
+        if b.is_ascii_alphabetic() {
+            codes.push(code(b.to_ascii_uppercase()));
+        } else if b.is_ascii_whitespace() && codes.last() != Some(&space) {
+            codes.push(space);
+        }
+    }
+    codes
+}
+
+/// Codes to text, in Bletchley notation except the space.
+pub fn decode(codes: &[u8]) -> String {
+    codes
+        .iter()
+        .map(|&c| match ALPHABET[c as usize] {
+            b'9' => ' ',
+            b => b as char,
+        })
+        .collect()
+}
+
+fn code(symbol: u8) -> u8 {
+    ALPHABET.iter().position(|&a| a == symbol).expect("Symbol") as u8
+}
+
+/// Wheels pin patterns, crosses are ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Wheels {
+    pub chi: [Vec<u8>; 5],
+    pub psi: [Vec<u8>; 5],
+    pub mu: [Vec<u8>; 2],
+}
+
+impl Wheels {
+    /// Random patterns, about half crosses.
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut pattern = |len: usize| (0..len).map(|_| rng.gen_range(0..2)).collect();
+        Wheels {
+            chi: CHI.map(&mut pattern),
+            psi: PSI.map(&mut pattern),
+            mu: MU.map(&mut pattern),
+        }
+    }
+}
+
+/// Wheels start positions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Settings {
+    pub chi: [usize; 5],
+    pub psi: [usize; 5],
+    pub mu: [usize; 2],
+}
+
+impl Settings {
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Settings {
+            chi: CHI.map(|len| rng.gen_range(0..len)),
+            psi: PSI.map(|len| rng.gen_range(0..len)),
+            mu: MU.map(|len| rng.gen_range(0..len)),
+        }
+    }
+}
+
+/// Lorenz machine.
+#[derive(Debug, Clone)]
+pub struct Lorenz {
+    wheels: Wheels,
+    settings: Settings,
+}
+
+impl Lorenz {
+    pub fn new(wheels: Wheels, settings: Settings) -> Self {
+        Lorenz { wheels, settings }
+    }
+
+    /// Current wheels positions.
+    pub fn positions(&self) -> Settings {
+        self.settings
+    }
+
+    fn chi(&self) -> u8 {
+        stream(&self.wheels.chi, &self.settings.chi)
+    }
+
+    fn psi(&self) -> u8 {
+        stream(&self.wheels.psi, &self.settings.psi)
+    }
+
+    /// Key character at the current position, then step the wheels.
+    pub fn key(&mut self) -> u8 {
+        let key = self.chi() ^ self.psi();
+        let Settings { chi, psi, mu } = &mut self.settings;
+        let [mu1, mu2] = &self.wheels.mu;
+        let (m1, m2) = (mu1[mu[0]], mu2[mu[1]]);
+        step(chi, &CHI);
+        if m2 == 1 {
+            step(psi, &PSI);
+        }
+        if m1 == 1 {
+            mu[1] = (mu[1] + 1) % MU[1];
+        }
+        mu[0] = (mu[0] + 1) % MU[0];
+        key
+    }
+
+    /// Encrypt (or decrypt) a sequence of codes.
+    pub fn process(&mut self, codes: &[u8]) -> Vec<u8> {
+        codes.iter().map(|&c| c ^ self.key()).collect()
+    }
+}
+
+// Code made by the pins of the five wheels at the given positions.
+fn stream(wheels: &[Vec<u8>; 5], positions: &[usize; 5]) -> u8 {
+    (0..5).fold(0, |acc, i| acc << 1 | wheels[i][positions[i]])
+}
+
+fn step(positions: &mut [usize; 5], lens: &[usize; 5]) {
+    positions
+        .iter_mut()
+        .zip(lens)
+        .for_each(|(p, len)| *p = (*p + 1) % len);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn codes() {
+        assert_eq!(encode("Hi,\n  there!"), [5, 12, 4, 1, 5, 16, 10, 16]);
+        assert_eq!(decode(&encode("Hi,\n  there!")), "HI THERE");
+        assert_eq!(impulse(code(b'A'), 0), 1);
+        assert_eq!(impulse(code(b'A'), 2), 0);
+    }
+
+    #[test]
+    fn psi_wheels_follow_the_motor() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let wheels = Wheels::random(&mut rng);
+        let mut lorenz = Lorenz::new(wheels.clone(), Settings::random(&mut rng));
+        for _ in 0..200 {
+            let before = lorenz.positions();
+            lorenz.key();
+            let after = lorenz.positions();
+            let moved = wheels.mu[1][before.mu[1]] == 1;
+            assert_eq!(after.psi != before.psi, moved);
+            assert_eq!(after.chi[0], (before.chi[0] + 1) % CHI[0]);
+        }
+    }
+
+    #[test]
+    fn process_is_an_involution() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let (wheels, settings) = (Wheels::random(&mut rng), Settings::random(&mut rng));
+        let pt = encode("Attack at dawn");
+        let ct = Lorenz::new(wheels.clone(), settings).process(&pt);
+        assert_ne!(ct, pt);
+        assert_eq!(Lorenz::new(wheels, settings).process(&ct), pt);
+    }
+}
@@ -0,0 +1,50 @@
+use classical::scoring::CORPUS;
+use lorenz::{
+    attacks::{set_chi, Language},
+    decode, encode, Lorenz, Settings, Wheels,
+};
+use rand::Rng;
+
+const TRIALS: usize = 20;
+
+fn main() {
+    let mut rng = rand::thread_rng();
+    let language = Language::english();
+    let corpus = encode(CORPUS);
+    let message = |rng: &mut rand::rngs::ThreadRng, len: usize| {
+        let start = rng.gen_range(0..corpus.len() - len);
+        corpus[start..start + len].to_vec()
+    };
+
+    let wheels = Wheels::random(&mut rng);
+    let settings = Settings::random(&mut rng);
+    let pt = message(&mut rng, 4000);
+    let ct = Lorenz::new(wheels.clone(), settings).process(&pt);
+    println!("Plaintext:  {}...", decode(&pt[..60]));
+    println!("Ciphertext: {}...", decode(&ct[..60]));
+    println!("Chi settings: {:?}", settings.chi);
+    let (positions, runs) = set_chi(&ct, &wheels, &language);
+    for run in runs {
+        let wheels: Vec<_> = run.wheels.iter().map(|w| w + 1).collect();
+        println!(
+            "  run χ{wheels:?}: positions {:?}, {:.1}σ",
+            run.positions, run.sigma
+        );
+    }
+    println!("Recovered:    {positions:?}");
+
+    // Success rate against the message length
+    println!("Length  success  ({TRIALS} trials)");
+    for len in [1000, 1500, 2000, 3000, 4000] {
+        let found = (0..TRIALS)
+            .filter(|_| {
+                let wheels = Wheels::random(&mut rng);
+                let settings = Settings::random(&mut rng);
+                let pt = message(&mut rng, len);
+                let ct = Lorenz::new(wheels.clone(), settings).process(&pt);
+                set_chi(&ct, &wheels, &language).0 == settings.chi
+            })
+            .count();
+        println!("{len:6}  {:6.0}%", 100.0 * found as f64 / TRIALS as f64);
+    }
+}
@@ -0,0 +1,72 @@
+//! Many-time pad.
+//!
+//! A stream cipher encrypts by XORing the message with a keystream which only
+//! depends on the key and the nonce. Reusing the nonce reuses the keystream,
+//! and `c₁ ⊕ c₂ = m₁ ⊕ m₂`: the key is gone from the equation. Given enough
+//! ciphertexts under the same keystream, every column (the bytes at the same
+//! offset) is a single byte XOR of natural language text, solved by trying the
+//! 256 keystream bytes against a letter and space frequency model. Crib
+//! dragging, sliding a guessed word over `c₁ ⊕ c₂`, fixes what the statistics
+//! get wrong.
+//!
+//! The letter frequencies are the ones of the `classical` crate scoring.
+
+use chacha20::{
+    cipher::{KeyIvInit, StreamCipher},
+    ChaCha20,
+};
+
+pub mod solver;
+
+/// ChaCha20 encryptor which (wrongly) uses the same nonce for every message.
+pub struct Encryptor {
+    key: [u8; 32],
+    nonce: [u8; 12],
+}
+
+impl Encryptor {
+    pub fn new(key: [u8; 32], nonce: [u8; 12]) -> Self {
+        Encryptor { key, nonce }
+    }
+
+    pub fn encrypt(&self, msg: &[u8]) -> Vec<u8> {
+        let mut buf = msg.to_vec();
+        ChaCha20::new(&self.key.into(), &self.nonce.into()).apply_keystream(&mut buf);
+        buf
+    }
+}
+
+/// English text probability of a byte.
+pub fn byte_probability(b: u8) -> f64 {
+    use classical::scoring::ENGLISH_FREQ;
+    match b {
+        b' ' => 0.15,
+        b'a'..=b'z' => 0.78 * ENGLISH_FREQ[(b - b'a') as usize],
+        b'A'..=b'Z' => 0.03 * ENGLISH_FREQ[(b - b'A') as usize],
+        b'.' | b',' | b'\'' | b'"' | b'-' | b'?' | b'!' | b';' | b':' => 0.004,
+        b'0'..=b'9' | b'\n' => 0.001,
+        0x21..=0x7e => 1e-4,
+        _ => 1e-8,
+    }
+}
+
+/// Log-likelihood of the bytes as English text, the higher the better.
+pub fn score(text: &[u8]) -> f64 {
+    text.iter().map(|&b| byte_probability(b).ln()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keystream_is_reused() {
+        let enc = Encryptor::new([1; 32], [2; 12]);
+        let (m1, m2) = (b"attack at dawn", b"defend at dusk");
+        let (c1, c2) = (enc.encrypt(m1), enc.encrypt(m2));
+        let xor: Vec<u8> = c1.iter().zip(&c2).map(|(a, b)| a ^ b).collect();
+        let expected: Vec<u8> = m1.iter().zip(m2).map(|(a, b)| a ^ b).collect();
+        assert_eq!(xor, expected);
+        assert!(score(m1) > score(&c1));
+    }
+}
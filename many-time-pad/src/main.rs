@@ -0,0 +1,168 @@
+use clap::{Parser, Subcommand};
+use many_time_pad::{
+    solver::{crib_drag, Solver},
+    Encryptor,
+};
+use rand::{rngs::OsRng, Rng};
+use std::{
+    fs,
+    io::{self, BufRead, Write},
+    path::PathBuf,
+};
+
+#[derive(Parser)]
+#[command(about = "Many-time pad solver")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Encrypt some sentences with a reused nonce and solve them.
+    Demo,
+    /// Encrypt the lines of a file with a random key and a reused nonce.
+    Encrypt { file: PathBuf },
+    /// Solve hex ciphertexts, one per line.
+    Solve {
+        file: PathBuf,
+        /// Confirm and correct the statistical solution.
+        #[arg(short, long)]
+        interactive: bool,
+    },
+}
+
+const HELP: &str = "Commands:
+  show                       print the decryptions
+  cand <col>                 most likely keystream bytes for a column
+  key <col> <hex|?>          set or clear a keystream byte
+  crib <msg> <pos> <text>    fix the keystream from a known plaintext
+  drag <i> <j> <text>        drag a crib over the XOR of two messages
+  quit";
+
+fn show(solver: &Solver) {
+    for i in 0..solver.ciphertexts().len() {
+        println!("{i:>3} {}", solver.render(i));
+    }
+}
+
+fn command(solver: &mut Solver, line: &str) -> Result<(), String> {
+    let mut words = line.splitn(4, ' ');
+    let arg = |s: Option<&str>| -> Result<usize, String> {
+        s.and_then(|s| s.parse().ok()).ok_or("bad number".into())
+    };
+    match words.next().unwrap_or("") {
+        "" => (),
+        "show" => show(solver),
+        "cand" => {
+            let col = arg(words.next())?;
+            if col >= solver.key().len() {
+                return Err("no such column".into());
+            }
+            for (k, score) in solver.candidates(col, 5) {
+                let text: String = solver.column(col).iter().map(|c| (c ^ k) as char).collect();
+                println!("  {k:02x} {score:>8.1} {text:?}");
+            }
+        }
+        "key" => {
+            let col = arg(words.next())?;
+            if col >= solver.key().len() {
+                return Err("no such column".into());
+            }
+            let byte = match words.next() {
+                Some("?") => None,
+                Some(h) => Some(u8::from_str_radix(h, 16).map_err(|e| e.to_string())?),
+                None => return Err("missing byte".into()),
+            };
+            solver.set_key(col, byte);
+            show(solver);
+        }
+        "crib" => {
+            let (msg, pos) = (arg(words.next())?, arg(words.next())?);
+            let text = words.next().ok_or("missing text")?;
+            solver
+                .crib(msg, pos, text.as_bytes())
+                .map_err(|e| format!("{e:?}"))?;
+            show(solver);
+        }
+        "drag" => {
+            let (i, j) = (arg(words.next())?, arg(words.next())?);
+            let text = words.next().ok_or("missing text")?;
+            let cts = solver.ciphertexts();
+            let (ci, cj) = (cts.get(i).ok_or("bad i")?, cts.get(j).ok_or("bad j")?);
+            for (pos, found) in crib_drag(ci, cj, text.as_bytes()).iter().take(10) {
+                println!("  {pos:>3} {:?}", String::from_utf8_lossy(found));
+            }
+        }
+        _ => println!("{HELP}"),
+    }
+    Ok(())
+}
+
+fn interactive(solver: &mut Solver) {
+    println!("{HELP}");
+    let stdin = io::stdin();
+    loop {
+        print!("> ");
+        io::stdout().flush().unwrap();
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap() == 0 {
+            break;
+        }
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line == "quit" {
+            break;
+        }
+        if let Err(err) = command(solver, line) {
+            println!("  error: {err}");
+        }
+    }
+}
+
+fn main() {
+    match Cli::parse().command.unwrap_or(Command::Demo) {
+        Command::Demo => {
+            let enc = Encryptor::new(OsRng.gen(), OsRng.gen());
+            let messages: Vec<_> = include_str!("sentences.txt").lines().collect();
+            let cts: Vec<_> = messages.iter().map(|m| enc.encrypt(m.as_bytes())).collect();
+            println!("{} messages encrypted under the same nonce", cts.len());
+            let mut solver = Solver::new(cts);
+            solver.auto();
+            show(&solver);
+            // Guess the sentence start and the end of the longest message
+            let i = (0..messages.len())
+                .max_by_key(|&i| messages[i].len())
+                .unwrap();
+            let tail = &messages[i][messages[i].len() - 12..];
+            println!("Cribs: message 0 starts with \"The \", message {i} ends with {tail:?}");
+            solver.crib(0, 0, b"The ").expect("in range");
+            solver
+                .crib(i, messages[i].len() - tail.len(), tail.as_bytes())
+                .expect("in range");
+            show(&solver);
+        }
+        Command::Encrypt { file } => {
+            let enc = Encryptor::new(OsRng.gen(), OsRng.gen());
+            for line in fs::read_to_string(file).unwrap().lines() {
+                println!("{}", hex::encode(enc.encrypt(line.as_bytes())));
+            }
+        }
+        Command::Solve {
+            file,
+            interactive: i,
+        } => {
+            let cts = fs::read_to_string(file)
+                .unwrap()
+                .lines()
+                .filter(|l| !l.trim().is_empty())
+                .map(|l| hex::decode(l.trim()).expect("hex ciphertext"))
+                .collect();
+            let mut solver = Solver::new(cts);
+            solver.auto();
+            show(&solver);
+            if i {
+                interactive(&mut solver);
+            }
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Keystream recovery.
+
+use crate::{byte_probability, score};
+
+/// Solver errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// No such message.
+    Message,
+    /// The crib exceeds the message.
+    Range,
+}
+
+/// Keystream recovery state, one optional byte per column.
+#[derive(Debug, Clone)]
+pub struct Solver {
+    ciphertexts: Vec<Vec<u8>>,
+    key: Vec<Option<u8>>,
+}
+
+impl Solver {
+    pub fn new(ciphertexts: Vec<Vec<u8>>) -> Self {
+        let len = ciphertexts.iter().map(Vec::len).max().unwrap_or(0);
+        Solver {
+            ciphertexts,
+            key: vec![None; len],
+        }
+    }
+
+    pub fn ciphertexts(&self) -> &[Vec<u8>] {
+        &self.ciphertexts
+    }
+
+    pub fn key(&self) -> &[Option<u8>] {
+        &self.key
+    }
+
+    /// Ciphertext bytes at the given offset.
+    pub fn column(&self, col: usize) -> Vec<u8> {
+        self.ciphertexts
+            .iter()
+            .filter_map(|c| c.get(col).copied())
+            .collect()
+    }
+
+    /// The `n` most likely keystream bytes for a column, with their score.
+    pub fn candidates(&self, col: usize, n: usize) -> Vec<(u8, f64)> {
+        let column = self.column(col);
+        let mut candidates: Vec<_> = (0..=255)
+            .map(|k| {
+                let text: Vec<u8> = column.iter().map(|c| c ^ k).collect();
+                (k, score(&text))
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+        candidates.truncate(n);
+        candidates
+    }
+
+    /// Fill the unknown keystream bytes with the most likely ones.
+    pub fn auto(&mut self) {
+        for col in 0..self.key.len() {
+            if self.key[col].is_none() {
+                self.key[col] = Some(self.candidates(col, 1)[0].0);
+            }
+        }
+    }
+
+    pub fn set_key(&mut self, col: usize, byte: Option<u8>) {
+        self.key[col] = byte;
+    }
+
+    /// Fix the keystream assuming the message `msg` contains `text` at `pos`.
+    pub fn crib(&mut self, msg: usize, pos: usize, text: &[u8]) -> Result<(), Error> {
+        let c = self.ciphertexts.get(msg).ok_or(Error::Message)?;
+        let c = c.get(pos..pos + text.len()).ok_or(Error::Range)?;
+        for (i, (c, p)) in c.iter().zip(text).enumerate() {
+            self.key[pos + i] = Some(c ^ p);
+        }
+        Ok(())
+    }
+
+    /// Decryption of a message, unknown bytes are `None`.
+    pub fn plaintext(&self, msg: usize) -> Vec<Option<u8>> {
+        self.ciphertexts[msg]
+            .iter()
+            .zip(&self.key)
+            .map(|(c, k)| k.map(|k| c ^ k))
+            .collect()
+    }
+
+    /// Printable decryption, with `_` for unknown and `·` for unprintable bytes.
+    pub fn render(&self, msg: usize) -> String {
+        self.plaintext(msg)
+            .into_iter()
+            .map(|b| match b {
+                None => '_',
+                Some(b @ 0x20..=0x7e) => b as char,
+                Some(_) => '·',
+            })
+            .collect()
+    }
+}
+
+/// Slide the `crib` over `c₁ ⊕ c₂`.
+///
+/// Returns the offsets where the crib in one message reveals printable text
+/// in the other one, with the revealed text, the most English-like first.
+pub fn crib_drag(c1: &[u8], c2: &[u8], crib: &[u8]) -> Vec<(usize, Vec<u8>)> {
+    let xor: Vec<u8> = c1.iter().zip(c2).map(|(a, b)| a ^ b).collect();
+    let mut found: Vec<_> = (0..(xor.len() + 1).saturating_sub(crib.len()))
+        .map(|pos| {
+            let text: Vec<u8> = crib.iter().zip(&xor[pos..]).map(|(a, b)| a ^ b).collect();
+            (pos, text)
+        })
+        .filter(|(_, text)| text.iter().all(|&b| byte_probability(b) >= 1e-4))
+        .collect();
+    found.sort_by(|a, b| score(&b.1).total_cmp(&score(&a.1)));
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Encryptor;
+
+    fn setup() -> (Vec<&'static str>, Solver) {
+        let messages: Vec<_> = include_str!("sentences.txt").lines().collect();
+        let enc = Encryptor::new([7; 32], [9; 12]);
+        let cts = messages.iter().map(|m| enc.encrypt(m.as_bytes())).collect();
+        (messages, Solver::new(cts))
+    }
+
+    fn accuracy(messages: &[&str], solver: &Solver) -> f64 {
+        let (mut hits, mut total) = (0, 0);
+        for (i, m) in messages.iter().enumerate() {
+            let p = solver.plaintext(i);
+            hits += m.bytes().zip(p).filter(|(a, b)| Some(*a) == *b).count();
+            total += m.len();
+        }
+        hits as f64 / total as f64
+    }
+
+    #[test]
+    fn statistical_then_crib() {
+        let (messages, mut solver) = setup();
+        solver.auto();
+        assert!(accuracy(&messages, &solver) > 0.9);
+        solver.crib(0, 0, messages[0].as_bytes()).unwrap();
+        // The longest message covers the tail columns
+        let longest = (0..messages.len())
+            .max_by_key(|&i| messages[i].len())
+            .unwrap();
+        solver
+            .crib(longest, 0, messages[longest].as_bytes())
+            .unwrap();
+        assert_eq!(accuracy(&messages, &solver), 1.0);
+        assert_eq!(solver.render(3), messages[3]);
+        assert_eq!(solver.crib(0, 60, b"too long"), Err(Error::Range));
+    }
+
+    #[test]
+    fn crib_dragging() {
+        let (messages, solver) = setup();
+        let cts = solver.ciphertexts();
+        // " meeting " is at offset 3 of the first message
+        let found = crib_drag(&cts[0], &cts[1], b" meeting ");
+        let (pos, text) = found.iter().find(|(pos, _)| *pos == 3).unwrap();
+        assert_eq!(&text[..], &messages[1].as_bytes()[*pos..pos + 9]);
+    }
+}
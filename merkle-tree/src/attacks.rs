@@ -0,0 +1,88 @@
+//! Root ambiguities of the [`Scheme::Bitcoin`](crate::Scheme::Bitcoin) trees.
+
+use crate::{MerkleTree, Proof};
+
+/// Leaves with the same root as `leaves`, obtained by duplicating the last
+/// subtree of the lowest level with an odd number of nodes.
+///
+/// Returns `None` if the number of leaves is a power of two.
+pub fn duplicate_tail<T: Clone>(leaves: &[T]) -> Option<Vec<T>> {
+    let n = leaves.len();
+    let size = 1 << n.trailing_zeros();
+    if n == size {
+        return None;
+    }
+    let mut mutated = leaves.to_vec();
+    mutated.extend_from_slice(&leaves[n - size..]);
+    Some(mutated)
+}
+
+/// Fake leaf hashing to the parent of the `index`-th leaf.
+fn fake_leaf(tree: &MerkleTree, index: usize) -> Vec<u8> {
+    let leaves = &tree.levels()[0];
+    let left = index & !1;
+    let right = leaves.get(left + 1).unwrap_or(&leaves[left]);
+    [leaves[left], *right].concat()
+}
+
+/// Leaves with the same root as `tree`, half as many: the concatenations of
+/// the leaf hashes pairs.
+pub fn internal_as_leaves(tree: &MerkleTree) -> Vec<Vec<u8>> {
+    (0..tree.levels()[0].len())
+        .step_by(2)
+        .map(|i| fake_leaf(tree, i))
+        .collect()
+}
+
+/// Inclusion proof of a 64 bytes leaf which is not in `tree`, made of the
+/// `index`-th leaf hash and its sibling.
+pub fn forge_proof(tree: &MerkleTree, index: usize) -> (Vec<u8>, Proof) {
+    (fake_leaf(tree, index), tree.path(1, index / 2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{verify, Scheme};
+
+    fn is_ambiguous(scheme: Scheme) -> bool {
+        scheme == Scheme::Bitcoin
+    }
+
+    fn leaves(n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("tx{i}")).collect()
+    }
+
+    #[test]
+    fn duplicated_leaves() {
+        assert!(duplicate_tail(&leaves(8)).is_none());
+        for n in [3, 5, 6, 7, 12] {
+            let leaves = leaves(n);
+            let mutated = duplicate_tail(&leaves).unwrap();
+            assert!(mutated.len() > n);
+            for scheme in [Scheme::Bitcoin, Scheme::Rfc6962] {
+                let root = MerkleTree::new(scheme, &leaves).root();
+                let other = MerkleTree::new(scheme, &mutated).root();
+                assert_eq!(root == other, is_ambiguous(scheme));
+            }
+        }
+    }
+
+    #[test]
+    fn nodes_as_leaves() {
+        for scheme in [Scheme::Bitcoin, Scheme::Rfc6962] {
+            let tree = MerkleTree::new(scheme, &leaves(7));
+            let fake = MerkleTree::new(scheme, &internal_as_leaves(&tree));
+            assert_eq!(fake.levels()[0].len(), 4);
+            assert_eq!(fake.root() == tree.root(), is_ambiguous(scheme));
+            for i in 0..7 {
+                let (leaf, proof) = forge_proof(&tree, i);
+                assert_eq!(leaf.len(), 64);
+                assert_eq!(
+                    verify(scheme, &tree.root(), &leaf, &proof),
+                    is_ambiguous(scheme)
+                );
+            }
+        }
+    }
+}
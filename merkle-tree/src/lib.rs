@@ -0,0 +1,197 @@
+//! Binary Merkle tree and its ambiguities.
+//!
+//! Bitcoin commits to the block transactions with a Merkle tree where:
+//! - leaves and internal nodes are hashed the same way, `H = SHA256(SHA256())`;
+//! - the last node of a level with an odd number of nodes is paired with
+//!   itself.
+//!
+//! Both choices make the root ambiguous, i.e. distinct leaf lists share it:
+//! - duplicating the last subtree of a level with an odd number of nodes
+//!   doesn't change the root (CVE-2012-2459). A node receiving the mutated
+//!   (invalid, as it double spends) block could cache the header as invalid,
+//!   and then reject the valid block with the same header;
+//! - the 64 bytes concatenation of two nodes hashes as a leaf to their parent,
+//!   thus internal nodes can be passed off as leaves. A light client checking
+//!   an inclusion proof accepts a "transaction" which is never in the block.
+//!
+//! The fix, as done by RFC 6962 (Certificate Transparency), is to prefix the
+//! leaves with `0x00` and the internal nodes with `0x01` and to promote the
+//! odd last node to the next level unchanged. See the [`attacks`] module.
+
+use sha2::{Digest, Sha256};
+
+pub mod attacks;
+
+pub type Hash = [u8; 32];
+
+/// Tree hashing rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheme {
+    /// No domain separation, odd last node paired with itself.
+    Bitcoin,
+    /// Leaves and nodes domain separation, odd last node promoted.
+    Rfc6962,
+}
+
+fn sha256(parts: &[&[u8]]) -> Hash {
+    parts
+        .iter()
+        .fold(Sha256::new(), |h, p| h.chain_update(p))
+        .finalize()
+        .into()
+}
+
+impl Scheme {
+    pub fn leaf(&self, data: &[u8]) -> Hash {
+        match self {
+            Scheme::Bitcoin => sha256(&[&sha256(&[data])]),
+            Scheme::Rfc6962 => sha256(&[&[0x00], data]),
+        }
+    }
+
+    pub fn node(&self, left: &Hash, right: &Hash) -> Hash {
+        match self {
+            Scheme::Bitcoin => sha256(&[&sha256(&[left, right])]),
+            Scheme::Rfc6962 => sha256(&[&[0x01], left, right]),
+        }
+    }
+
+    fn parents(&self, level: &[Hash]) -> Vec<Hash> {
+        level
+            .chunks(2)
+            .map(|pair| match (pair, self) {
+                ([left, right], _) => self.node(left, right),
+                ([last], Scheme::Bitcoin) => self.node(last, last),
+                ([last], Scheme::Rfc6962) => *last,
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+}
+
+/// Inclusion proof step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub sibling: Hash,
+    /// Sibling is the left child.
+    pub left: bool,
+}
+
+/// Inclusion proof, from the leaf to the root.
+pub type Proof = Vec<Step>;
+
+/// Merkle tree keeping all the levels, from the leaves to the root.
+#[derive(Debug, Clone)]
+pub struct MerkleTree {
+    scheme: Scheme,
+    levels: Vec<Vec<Hash>>,
+}
+
+impl MerkleTree {
+    /// Tree over the `leaves` data. Panics if there are no leaves.
+    pub fn new<T: AsRef<[u8]>>(scheme: Scheme, leaves: &[T]) -> Self {
+        let hashes = leaves.iter().map(|l| scheme.leaf(l.as_ref())).collect();
+        Self::from_leaf_hashes(scheme, hashes)
+    }
+
+    /// Tree over already hashed leaves (e.g. Bitcoin transaction identifiers).
+    pub fn from_leaf_hashes(scheme: Scheme, hashes: Vec<Hash>) -> Self {
+        assert!(!hashes.is_empty(), "empty tree");
+        let mut levels = vec![hashes];
+        while levels[levels.len() - 1].len() > 1 {
+            let next = scheme.parents(&levels[levels.len() - 1]);
+            levels.push(next);
+        }
+        MerkleTree { scheme, levels }
+    }
+
+    pub fn scheme(&self) -> Scheme {
+        self.scheme
+    }
+
+    pub fn levels(&self) -> &[Vec<Hash>] {
+        &self.levels
+    }
+
+    pub fn root(&self) -> Hash {
+        self.levels[self.levels.len() - 1][0]
+    }
+
+    /// Inclusion proof of the `index`-th leaf.
+    pub fn prove(&self, index: usize) -> Proof {
+        self.path(0, index)
+    }
+
+    /// Inclusion proof of the `index`-th node of the `level`-th level.
+    pub(crate) fn path(&self, level: usize, mut index: usize) -> Proof {
+        let mut proof = Vec::new();
+        for nodes in &self.levels[level..self.levels.len() - 1] {
+            let sibling = match (nodes.get(index ^ 1), self.scheme) {
+                (Some(sibling), _) => Some(*sibling),
+                (None, Scheme::Bitcoin) => Some(nodes[index]),
+                (None, Scheme::Rfc6962) => None,
+            };
+            if let Some(sibling) = sibling {
+                proof.push(Step {
+                    sibling,
+                    left: index % 2 == 1,
+                });
+            }
+            index /= 2;
+        }
+        proof
+    }
+}
+
+/// Check that `data` is a leaf of the tree with the given `root`.
+pub fn verify(scheme: Scheme, root: &Hash, data: &[u8], proof: &[Step]) -> bool {
+    let node = proof.iter().fold(scheme.leaf(data), |node, step| {
+        if step.left {
+            scheme.node(&step.sibling, &node)
+        } else {
+            scheme.node(&node, &step.sibling)
+        }
+    });
+    &node == root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bitcoin_block_100000() {
+        let txids = [
+            "8c14f0db3df150123e6f3dbbf30f8b955a8249b62ac1d1ff16284aefa3d06d87",
+            "fff2525b8931402dd09222c50775608f75787bd2b87e56995a7bdd30f79702c4",
+            "6359f0868171b1d194cbee1af2f16ea598ae8fad666d9b012c8ed2b79a236ec4",
+            "e9a66845e05d5abc0ad04ec80f774a7e585c6e8db975962d069a522137b80c1d",
+        ];
+        // Displayed hashes are byte reversed
+        let unhex = |s: &str| -> Hash {
+            let mut h: Hash = hex::decode(s).unwrap().try_into().unwrap();
+            h.reverse();
+            h
+        };
+        let tree = MerkleTree::from_leaf_hashes(Scheme::Bitcoin, txids.map(unhex).to_vec());
+        assert_eq!(
+            tree.root(),
+            unhex("f3e94742aca4b5ef85488dc37c06c3282295ffec960994b2c0d5ac2a25a95766")
+        );
+    }
+
+    #[test]
+    fn proofs_verify() {
+        for scheme in [Scheme::Bitcoin, Scheme::Rfc6962] {
+            for n in 1..=9 {
+                let leaves: Vec<_> = (0..n).map(|i| format!("tx{i}")).collect();
+                let tree = MerkleTree::new(scheme, &leaves);
+                for (i, leaf) in leaves.iter().enumerate() {
+                    let proof = tree.prove(i);
+                    assert!(verify(scheme, &tree.root(), leaf.as_bytes(), &proof));
+                    assert!(!verify(scheme, &tree.root(), b"tx", &proof));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use merkle_tree::{attacks, verify, MerkleTree, Scheme};
+
+fn main() {
+    let txs: Vec<_> = (0..6).map(|i| format!("tx{i}")).collect();
+    let mutated = attacks::duplicate_tail(&txs).unwrap();
+    println!("Block transactions:   {}", txs.join(" "));
+    println!("Mutated transactions: {}", mutated.join(" "));
+
+    for scheme in [Scheme::Bitcoin, Scheme::Rfc6962] {
+        println!("{scheme:?}");
+        let tree = MerkleTree::new(scheme, &txs);
+        println!("  root:              {}", hex::encode(tree.root()));
+        let root = MerkleTree::new(scheme, &mutated).root();
+        println!("  mutated root:      {}", hex::encode(root));
+        let root = MerkleTree::new(scheme, &attacks::internal_as_leaves(&tree)).root();
+        println!("  nodes as leaves:   {}", hex::encode(root));
+        let (fake, proof) = attacks::forge_proof(&tree, 2);
+        println!(
+            "  fake tx {}... included: {}",
+            hex::encode(&fake[..8]),
+            verify(scheme, &tree.root(), &fake, &proof)
+        );
+    }
+}
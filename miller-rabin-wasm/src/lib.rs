@@ -0,0 +1,5 @@
+//! WebAssembly package of the `miller-rabin` crate JavaScript bindings.
+//!
+//! Build with `wasm-pack build --target web`.
+
+pub use miller_rabin::wasm::*;
@@ -1,11 +1,18 @@
 //! Miller-Rabin primality test using `num-bigint` crate.
 //!
+//! Candidates are tested in parallel, except on `wasm32` where everything
+//! runs on the calling thread. The `wasm` feature provides JavaScript bindings
+//! (`wasm` module), the `montgomery` feature runs the witness test with the
+//! `numutil` Montgomery arithmetic.
+//!
 //! Some background: https://datawok.net/posts/random-primes
 
 use num_bigint::{BigUint, RandBigInt};
 use num_integer::Integer;
 use num_traits::{One, Zero};
-use rand::rngs::OsRng;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 const MILLER_RABIN_MAX_ITER: usize = 8;
 const PRIME_GEN_MAX_ATTEMPTS: usize = 5000;
@@ -22,7 +29,87 @@ const SMALL_PRIMES: [u16; 175] = [
     937, 941, 947, 953, 967, 971, 977, 983, 991, 997, 1009, 1013, 1019, 1021, 1031, 1033, 1039,
 ];
 
-use rayon::prelude::*;
+// Randomness source: the OS generator, or a generator seeded via `getrandom`
+// on wasm.
+#[cfg(not(target_arch = "wasm32"))]
+fn rng() -> rand::rngs::OsRng {
+    rand::rngs::OsRng
+}
+
+#[cfg(target_arch = "wasm32")]
+fn rng() -> rand::rngs::StdRng {
+    let mut seed = [0; 32];
+    getrandom::getrandom(&mut seed).expect("Entropy source available");
+    rand::SeedableRng::from_seed(seed)
+}
+
+// Runs `f` up to `attempts` times, returning the first value found.
+#[cfg(not(target_arch = "wasm32"))]
+fn find_any<T: Send>(attempts: usize, f: impl Fn() -> Option<T> + Sync + Send) -> Option<T> {
+    use rayon::prelude::*;
+    (0..attempts).into_par_iter().find_map_any(|_| f())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn find_any<T>(attempts: usize, f: impl Fn() -> Option<T>) -> Option<T> {
+    (0..attempts).find_map(|_| f())
+}
+
+// Runs `f` `count` times, `true` if every run succeeds.
+#[cfg(not(target_arch = "wasm32"))]
+fn all(count: usize, f: impl Fn() -> bool + Sync + Send) -> bool {
+    use rayon::prelude::*;
+    (0..count).into_par_iter().all(|_| f())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn all(count: usize, f: impl Fn() -> bool) -> bool {
+    (0..count).all(|_| f())
+}
+
+// `n` is a strong probable prime to base `a`, with `n - 1 = d·2^s`.
+#[cfg(not(feature = "montgomery"))]
+fn strong_probable_prime(a: &BigUint, n: &BigUint, d: &BigUint, s: &BigUint) -> bool {
+    let one = BigUint::one();
+    let n_minus_one = n - &one;
+
+    let mut x = a.modpow(d, n);
+    if x == one || x == n_minus_one {
+        return true;
+    }
+
+    let mut count = BigUint::one();
+    while &count < s {
+        x = &x * &x % n;
+        if x == n_minus_one {
+            return true;
+        }
+        count += 1u8;
+    }
+    false
+}
+
+// Same, with the squarings in Montgomery representation. Here `n` is odd.
+#[cfg(feature = "montgomery")]
+fn strong_probable_prime(a: &BigUint, n: &BigUint, d: &BigUint, s: &BigUint) -> bool {
+    let ctx = numutil::Montgomery::new(n).expect("Odd modulus");
+    let (one, n_minus_one) = (ctx.one(), ctx.to_mont(&(n - 1u8)));
+
+    let mut x = ctx.pow(&ctx.to_mont(a), d);
+    if x == one || x == n_minus_one {
+        return true;
+    }
+
+    let mut count = BigUint::one();
+    while &count < s {
+        x = ctx.sqr(&x);
+        if x == n_minus_one {
+            return true;
+        }
+        count += 1u8;
+    }
+    false
+}
 
 fn miller_rabin_test(n: &BigUint, limit: usize) -> bool {
     let one = BigUint::one();
@@ -37,26 +124,12 @@ fn miller_rabin_test(n: &BigUint, limit: usize) -> bool {
         d >>= 1;
     }
 
-    let predicate = |_| {
-        let x = OsRng.gen_biguint_range(&two, &n_minus_one);
-
-        let mut x = x.modpow(&d, &n);
-        if x == one || x == n_minus_one {
-            return true;
-        }
-
-        let mut count = BigUint::one();
-        while count < s {
-            x = x.modpow(&two, n);
-            if x == n_minus_one {
-                return true;
-            }
-            count += 1u8;
-        }
-        false
+    let predicate = || {
+        let a = rng().gen_biguint_range(&two, &n_minus_one);
+        strong_probable_prime(&a, n, &d, &s)
     };
 
-    (0..limit).into_par_iter().all(predicate)
+    all(limit, predicate)
 }
 
 /// Returns `true` if probably prime, `false` otherwise.
@@ -94,10 +167,10 @@ pub fn is_prime(n: &BigUint) -> bool {
 pub fn prime_num(bits: usize, attempts: Option<usize>) -> Option<BigUint> {
     let attempts = attempts.unwrap_or(PRIME_GEN_MAX_ATTEMPTS);
 
-    (0..attempts).into_par_iter().find_map_any(|_| {
-        let mut n = OsRng.gen_biguint(bits as u64);
+    find_any(attempts, || {
+        let mut n = rng().gen_biguint(bits as u64);
         if n.is_even() {
-            n = n + BigUint::one();
+            n += BigUint::one();
         }
         is_prime(&n).then_some(n)
     })
@@ -113,9 +186,9 @@ pub fn prime_num_serial(bits: usize, attempts: Option<usize>) -> Option<(BigUint
     let attempts = attempts.unwrap_or(PRIME_GEN_MAX_ATTEMPTS);
 
     (0..attempts).into_iter().find_map(|i| {
-        let mut n = OsRng.gen_biguint(bits as u64);
+        let mut n = rng().gen_biguint(bits as u64);
         if n.is_even() {
-            n = n + BigUint::one();
+            n += BigUint::one();
         }
         if is_prime(&n) {
             Some((n, i))
@@ -125,6 +198,28 @@ pub fn prime_num_serial(bits: usize, attempts: Option<usize>) -> Option<(BigUint
     })
 }
 
+/// Find a safe prime `p = 2q + 1`, with `q` prime, with the given number of
+/// `bits`.
+///
+/// Returns `p` and `q`. Safe primes are rarer than primes by a factor about
+/// `bits·log(2)/2`, so are the default attempts. The smallest one, 7, has 3
+/// bits: `None` is returned for fewer.
+pub fn safe_prime(bits: usize, attempts: Option<usize>) -> Option<(BigUint, BigUint)> {
+    if bits < 3 {
+        return None;
+    }
+    let attempts = attempts.unwrap_or(PRIME_GEN_MAX_ATTEMPTS * bits);
+
+    find_any(attempts, || {
+        let mut q = rng().gen_biguint(bits as u64 - 1);
+        q.set_bit(bits as u64 - 2, true);
+        q.set_bit(0, true);
+        // Cheap test of p first, most candidates fail here
+        let p = (&q << 1) + BigUint::one();
+        (is_prime(&p) && is_prime(&q)).then_some((p, q))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -137,6 +232,23 @@ mod tests {
         assert!(n.is_some());
     }
 
+    #[test]
+    fn generate_safe_prime() {
+        let (p, q) = safe_prime(128, None).unwrap();
+        assert_eq!(p.bits(), 128);
+        assert_eq!(p, (&q << 1) + 1_u8);
+        assert!(is_prime(&q));
+    }
+
+    #[test]
+    fn tiny_safe_primes() {
+        for bits in 0..3 {
+            assert_eq!(safe_prime(bits, None), None);
+        }
+        let (p, q) = safe_prime(3, None).unwrap();
+        assert_eq!((p, q), (7_u8.into(), 3_u8.into()));
+    }
+
     #[test]
     fn small_primes_test_works() {
         let n = BigUint::from(0_u32);
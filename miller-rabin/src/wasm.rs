@@ -0,0 +1,54 @@
+//! JavaScript bindings, packaged by the `miller-rabin-wasm` crate.
+//!
+//! Numbers are exchanged as decimal strings or as big-endian bytes
+//! (`Uint8Array`). Functions taking `attempts` use the library defaults when
+//! it is `undefined`.
+
+use crate::{is_prime, prime_num, safe_prime};
+use num_bigint::BigUint;
+use wasm_bindgen::prelude::*;
+
+/// Returns `true` if the decimal number `n` is probably prime.
+#[wasm_bindgen(js_name = isPrime)]
+pub fn is_prime_str(n: &str) -> Result<bool, JsError> {
+    Ok(is_prime(&n.parse()?))
+}
+
+/// Returns `true` if the big-endian number `n` is probably prime.
+#[wasm_bindgen(js_name = isPrimeBytes)]
+pub fn is_prime_bytes(n: &[u8]) -> bool {
+    is_prime(&BigUint::from_bytes_be(n))
+}
+
+/// Prime with the given number of `bits`, as a decimal string.
+#[wasm_bindgen(js_name = primeNum)]
+pub fn prime_num_str(bits: usize, attempts: Option<usize>) -> Option<String> {
+    prime_num(bits, attempts).map(|p| p.to_string())
+}
+
+/// Prime with the given number of `bits`, as big-endian bytes.
+#[wasm_bindgen(js_name = primeNumBytes)]
+pub fn prime_num_bytes(bits: usize, attempts: Option<usize>) -> Option<Vec<u8>> {
+    prime_num(bits, attempts).map(|p| p.to_bytes_be())
+}
+
+/// Safe prime `p = 2q + 1` with the given number of `bits`, as a decimal
+/// string. `undefined` for less than 3 bits.
+#[wasm_bindgen(js_name = safePrime)]
+pub fn safe_prime_str(bits: usize, attempts: Option<usize>) -> Option<String> {
+    safe_prime(bits, attempts).map(|(p, _)| p.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strings_and_bytes() {
+        assert!(is_prime_str("1000003").unwrap());
+        assert!(!is_prime_bytes(&[0x0f, 0x42, 0x41]));
+        let p = prime_num_bytes(64, None).unwrap();
+        assert!(p.len() <= 8);
+        assert!(is_prime_str(&BigUint::from_bytes_be(&p).to_string()).unwrap());
+    }
+}
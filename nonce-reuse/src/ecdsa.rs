@@ -0,0 +1,113 @@
+//! ECDSA over secp256k1.
+//!
+//! The signature of the message digest `z` is `(r, s)` with `r = x(k·G)` and
+//! `s = k⁻¹(z + r·d)`. Two signatures under the same `k` share `r`, which gives
+//! the reuse away, and yield
+//!
+//! ```text
+//! k = (z₁ - z₂) / (s₁ - s₂)
+//! d = (s₁·k - z₁) / r
+//! ```
+//!
+//! Signatures are normalized to the lower `s` (as Bitcoin mandates), so each
+//! `s` is known up to the sign. Only the relative sign matters, thus the two
+//! resulting candidates are checked against the public key.
+
+use k256::ecdsa::{hazmat::SignPrimitive, Signature, SigningKey, VerifyingKey};
+use k256::elliptic_curve::ops::Reduce;
+use k256::{NonZeroScalar, Scalar, U256};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// Message digest as a scalar.
+pub fn digest(msg: &[u8]) -> Scalar {
+    <Scalar as Reduce<U256>>::reduce_bytes(&Sha256::digest(msg))
+}
+
+/// Sign `msg` using the nonce `k` instead of a fresh random one.
+///
+/// The signature is the same as the one produced by [`SigningKey`] and is
+/// accepted by [`VerifyingKey`].
+pub fn sign_with_nonce(key: &SigningKey, k: &NonZeroScalar, msg: &[u8]) -> Signature {
+    key.as_nonzero_scalar()
+        .try_sign_prehashed(**k, &Sha256::digest(msg))
+        .expect("non zero nonce")
+        .0
+}
+
+/// Find two signatures sharing the nonce, i.e. the `r` component.
+pub fn find_reuse(signatures: &[Signature]) -> Option<(usize, usize)> {
+    let mut seen = HashMap::new();
+    signatures
+        .iter()
+        .enumerate()
+        .find_map(|(j, sig)| seen.insert(sig.r().to_bytes(), j).map(|i| (i, j)))
+}
+
+/// Recover the signing key of `public` from the signatures of two different
+/// messages produced with the same nonce.
+///
+/// Returns `None` if the nonce was not reused.
+pub fn recover_key(
+    public: &VerifyingKey,
+    (msg1, sig1): (&[u8], &Signature),
+    (msg2, sig2): (&[u8], &Signature),
+) -> Option<SigningKey> {
+    if *sig1.r() != *sig2.r() {
+        return None;
+    }
+    let (r, s1) = sig1.split_scalars();
+    let (z1, z2) = (digest(msg1), digest(msg2));
+    // `r` is non zero
+    let r_inv = r.invert().unwrap();
+    [*sig2.s(), -*sig2.s()].into_iter().find_map(|s2| {
+        let k = (z1 - z2) * Option::<Scalar>::from((*s1 - s2).invert())?;
+        let d = (*s1 * k - z1) * r_inv;
+        let key = SigningKey::from(Option::<NonZeroScalar>::from(NonZeroScalar::new(d))?);
+        (key.verifying_key() == public).then_some(key)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::ecdsa::signature::Verifier;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn signatures_are_valid() {
+        let key = SigningKey::random(&mut OsRng);
+        let k = NonZeroScalar::random(&mut OsRng);
+        let sig = sign_with_nonce(&key, &k, b"hello");
+        assert!(key.verifying_key().verify(b"hello", &sig).is_ok());
+    }
+
+    #[test]
+    fn recover_key_from_reused_nonce() {
+        // Random keys and nonces cover both the relative signs of `s`
+        for _ in 0..8 {
+            let key = SigningKey::random(&mut OsRng);
+            let k = NonZeroScalar::random(&mut OsRng);
+            let (m1, m2) = (b"pay alice 10".as_slice(), b"pay bob 20".as_slice());
+            let sigs = [
+                sign_with_nonce(&key, &NonZeroScalar::random(&mut OsRng), b"noise"),
+                sign_with_nonce(&key, &k, m1),
+                sign_with_nonce(&key, &k, m2),
+            ];
+            assert_eq!(find_reuse(&sigs), Some((1, 2)));
+            let found = recover_key(key.verifying_key(), (m1, &sigs[1]), (m2, &sigs[2]));
+            assert_eq!(found, Some(key));
+        }
+    }
+
+    #[test]
+    fn fresh_nonces_are_safe() {
+        let key = SigningKey::random(&mut OsRng);
+        let sigs: Vec<_> = [b"a", b"b"]
+            .iter()
+            .map(|m| sign_with_nonce(&key, &NonZeroScalar::random(&mut OsRng), *m))
+            .collect();
+        assert_eq!(find_reuse(&sigs), None);
+        assert!(recover_key(key.verifying_key(), (b"a", &sigs[0]), (b"b", &sigs[1])).is_none());
+    }
+}
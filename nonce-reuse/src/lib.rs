@@ -0,0 +1,17 @@
+//! Private key recovery from signatures sharing the nonce.
+//!
+//! Schnorr-like signatures mix a fresh secret nonce `k` with the private key.
+//! Each signature is a linear equation in `k` and in the key, thus two
+//! signatures of different messages under the same `k` are a system which
+//! anyone can solve. This is how the Sony PS3 firmware signing key leaked
+//! (constant `k`) and how several Bitcoin wallets were drained (repeated `k`
+//! from broken RNGs, detected by scanning the chain for repeated `r`).
+//!
+//! The attacks are shown against ECDSA over secp256k1 and against Schnorr over
+//! Ristretto255. In both cases the recovered key is checked by re-deriving the
+//! public key. See the `ed25519-dalek-secret-recovery` crate for the related
+//! misuse of EdDSA, where the nonce is deterministic but derived from the
+//! wrong public key.
+
+pub mod ecdsa;
+pub mod schnorr;
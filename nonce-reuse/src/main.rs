@@ -0,0 +1,40 @@
+use k256::{ecdsa::SigningKey, NonZeroScalar};
+use nonce_reuse::{ecdsa, schnorr};
+use rand::rngs::OsRng;
+
+fn main() {
+    let (m1, m2) = (
+        b"Transfer 1 BTC to Alice".as_slice(),
+        b"Transfer 2 BTC to Bob".as_slice(),
+    );
+
+    println!("ECDSA secp256k1");
+    let key = SigningKey::random(&mut OsRng);
+    println!("  secret:    {}", hex::encode(key.to_bytes()));
+    let k = NonZeroScalar::random(&mut OsRng);
+    let mut signatures: Vec<_> = (0..10_u8)
+        .map(|i| ecdsa::sign_with_nonce(&key, &NonZeroScalar::random(&mut OsRng), &[i]))
+        .collect();
+    signatures.insert(3, ecdsa::sign_with_nonce(&key, &k, m1));
+    signatures.push(ecdsa::sign_with_nonce(&key, &k, m2));
+    let (i, j) = ecdsa::find_reuse(&signatures).unwrap();
+    println!("  signatures {i} and {j} share r");
+    let found = ecdsa::recover_key(
+        key.verifying_key(),
+        (m1, &signatures[i]),
+        (m2, &signatures[j]),
+    )
+    .unwrap();
+    println!("  recovered: {}", hex::encode(found.to_bytes()));
+
+    println!("Schnorr Ristretto255");
+    let keypair = schnorr::Keypair::generate(&mut OsRng);
+    println!("  secret:    {}", hex::encode(keypair.secret().as_bytes()));
+    let k = curve25519_dalek::Scalar::random(&mut OsRng);
+    let (sig1, sig2) = (
+        keypair.sign_with_nonce(&k, m1),
+        keypair.sign_with_nonce(&k, m2),
+    );
+    let found = schnorr::recover_key(&keypair.public, (m1, &sig1), (m2, &sig2)).unwrap();
+    println!("  recovered: {}", hex::encode(found.secret().as_bytes()));
+}
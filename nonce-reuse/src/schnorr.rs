@@ -0,0 +1,127 @@
+//! Schnorr over Ristretto255.
+//!
+//! The signature of `m` under the key `x` (public `X = x·G`) is `(R, s)` with
+//! `R = k·G`, `s = k + c·x` and the challenge `c = H(R || X || m)`. Two
+//! signatures under the same `k` share `R` and yield
+//!
+//! ```text
+//! x = (s₁ - s₂) / (c₁ - c₂)
+//! ```
+
+use curve25519_dalek::{
+    constants::RISTRETTO_BASEPOINT_TABLE as G,
+    ristretto::{CompressedRistretto, RistrettoPoint},
+    scalar::Scalar,
+};
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha512};
+
+/// Schnorr signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signature {
+    pub r: CompressedRistretto,
+    pub s: Scalar,
+}
+
+fn challenge(r: &CompressedRistretto, public: &RistrettoPoint, msg: &[u8]) -> Scalar {
+    let hasher = Sha512::new()
+        .chain_update(b"schnorr-ristretto")
+        .chain_update(r.as_bytes())
+        .chain_update(public.compress().as_bytes())
+        .chain_update(msg);
+    Scalar::from_hash(hasher)
+}
+
+/// Signing key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keypair {
+    secret: Scalar,
+    pub public: RistrettoPoint,
+}
+
+impl Keypair {
+    pub fn generate<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        Self::from_secret(Scalar::random(rng))
+    }
+
+    pub fn from_secret(secret: Scalar) -> Self {
+        Keypair {
+            secret,
+            public: &secret * G,
+        }
+    }
+
+    pub fn secret(&self) -> &Scalar {
+        &self.secret
+    }
+
+    /// Sign `msg` with a fresh random nonce.
+    pub fn sign<R: RngCore + CryptoRng>(&self, rng: &mut R, msg: &[u8]) -> Signature {
+        self.sign_with_nonce(&Scalar::random(rng), msg)
+    }
+
+    /// Sign `msg` using the nonce `k`.
+    pub fn sign_with_nonce(&self, k: &Scalar, msg: &[u8]) -> Signature {
+        let r = (k * G).compress();
+        let s = k + challenge(&r, &self.public, msg) * self.secret;
+        Signature { r, s }
+    }
+}
+
+/// Check `s·G == R + c·X`.
+pub fn verify(public: &RistrettoPoint, msg: &[u8], sig: &Signature) -> bool {
+    let Some(r) = sig.r.decompress() else {
+        return false;
+    };
+    &sig.s * G == r + challenge(&sig.r, public, msg) * public
+}
+
+/// Recover the signing key of `public` from the signatures of two different
+/// messages produced with the same nonce.
+///
+/// Returns `None` if the nonce was not reused.
+pub fn recover_key(
+    public: &RistrettoPoint,
+    (msg1, sig1): (&[u8], &Signature),
+    (msg2, sig2): (&[u8], &Signature),
+) -> Option<Keypair> {
+    if sig1.r != sig2.r {
+        return None;
+    }
+    let dc = challenge(&sig1.r, public, msg1) - challenge(&sig2.r, public, msg2);
+    if dc == Scalar::ZERO {
+        return None;
+    }
+    let keypair = Keypair::from_secret((sig1.s - sig2.s) * dc.invert());
+    (&keypair.public == public).then_some(keypair)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn sign_verify() {
+        let keypair = Keypair::generate(&mut OsRng);
+        let sig = keypair.sign(&mut OsRng, b"hello");
+        assert!(verify(&keypair.public, b"hello", &sig));
+        assert!(!verify(&keypair.public, b"hallo", &sig));
+    }
+
+    #[test]
+    fn recover_key_from_reused_nonce() {
+        let keypair = Keypair::generate(&mut OsRng);
+        let (m1, m2) = (b"first".as_slice(), b"second".as_slice());
+        let k = Scalar::random(&mut OsRng);
+        let (sig1, sig2) = (
+            keypair.sign_with_nonce(&k, m1),
+            keypair.sign_with_nonce(&k, m2),
+        );
+        let found = recover_key(&keypair.public, (m1, &sig1), (m2, &sig2)).unwrap();
+        assert_eq!(found.secret(), keypair.secret());
+
+        let sig2 = keypair.sign(&mut OsRng, m2);
+        assert!(recover_key(&keypair.public, (m1, &sig1), (m2, &sig2)).is_none());
+    }
+}
@@ -0,0 +1,78 @@
+//! Montgomery arithmetic against the stock `BigUint` operations.
+//!
+//! `cargo bench --bench montgomery`
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use num_bigint::{BigUint, RandBigInt};
+use numutil::Montgomery;
+use rand::{rngs::StdRng, SeedableRng};
+
+const BITS: [u64; 3] = [1024, 2048, 4096];
+
+// Odd modulus of exactly `bits` bits and two operands below it.
+fn operands(bits: u64) -> (BigUint, BigUint, BigUint) {
+    let mut rng = StdRng::seed_from_u64(bits);
+    let mut n = rng.gen_biguint(bits);
+    n.set_bit(bits - 1, true);
+    n.set_bit(0, true);
+    (rng.gen_biguint_below(&n), rng.gen_biguint_below(&n), n)
+}
+
+fn mulmod(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mulmod");
+    for bits in BITS {
+        let (a, b, n) = operands(bits);
+        group.bench_with_input(BenchmarkId::new("biguint", bits), &bits, |bench, _| {
+            bench.iter(|| &a * &b % &n)
+        });
+        let ctx = Montgomery::new(&n).unwrap();
+        let (am, bm) = (ctx.to_mont(&a), ctx.to_mont(&b));
+        group.bench_with_input(BenchmarkId::new("montgomery", bits), &bits, |bench, _| {
+            bench.iter(|| ctx.mul(&am, &bm))
+        });
+    }
+    group.finish();
+}
+
+// Squarings as done by the Miller-Rabin witness loop, against the plain product
+// and, for reference, the generic exponentiation.
+fn sqrmod(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sqrmod");
+    let two = BigUint::from(2_u8);
+    for bits in BITS {
+        let (a, _, n) = operands(bits);
+        group.bench_with_input(BenchmarkId::new("biguint", bits), &bits, |bench, _| {
+            bench.iter(|| &a * &a % &n)
+        });
+        group.bench_with_input(
+            BenchmarkId::new("biguint-modpow", bits),
+            &bits,
+            |bench, _| bench.iter(|| a.modpow(&two, &n)),
+        );
+        let ctx = Montgomery::new(&n).unwrap();
+        let am = ctx.to_mont(&a);
+        group.bench_with_input(BenchmarkId::new("montgomery", bits), &bits, |bench, _| {
+            bench.iter(|| ctx.sqr(&am))
+        });
+    }
+    group.finish();
+}
+
+fn modpow(c: &mut Criterion) {
+    let mut group = c.benchmark_group("modpow");
+    group.sample_size(10);
+    for bits in BITS {
+        let (a, e, n) = operands(bits);
+        group.bench_with_input(BenchmarkId::new("biguint", bits), &bits, |bench, _| {
+            bench.iter(|| a.modpow(&e, &n))
+        });
+        let ctx = Montgomery::new(&n).unwrap();
+        group.bench_with_input(BenchmarkId::new("montgomery", bits), &bits, |bench, _| {
+            bench.iter(|| ctx.modpow(&a, &e))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, mulmod, sqrmod, modpow);
+criterion_main!(benches);
@@ -0,0 +1,210 @@
+//! Integer factorization: trial division and Pollard's rho.
+//!
+//! - [`trial_division`] strips the factors below a small bound;
+//! - [`pollard_rho`] finds a factor `d` of `n` in ≈ √d steps of the map
+//!   `x ← x² + c (mod n)`, which modulo `d` enters a cycle after ≈ √d steps
+//!   (Brent's cycle detection, with the gcds batched);
+//! - [`factor`] combines them, with a budget of rho steps per factor.
+//!
+//! Good enough for the smooth part of a number (e.g. of `p - 1` for
+//! Pohlig–Hellman), not for a product of two big primes. The primality test is
+//! given by the caller (e.g. `miller_rabin::is_prime`).
+
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::{One, Zero};
+
+// Rho steps between two gcds
+const BATCH: u64 = 128;
+
+/// Prime factors with their multiplicities, sorted, and the factors which
+/// could not be split.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Factorization {
+    pub primes: Vec<(BigUint, u32)>,
+    pub composites: Vec<BigUint>,
+}
+
+impl Factorization {
+    /// `true` if every factor is prime.
+    pub fn is_complete(&self) -> bool {
+        self.composites.is_empty()
+    }
+
+    /// Largest prime factor.
+    pub fn largest_prime(&self) -> Option<&BigUint> {
+        self.primes.last().map(|(p, _)| p)
+    }
+
+    /// Adds the prime factor `p` with multiplicity `k`.
+    pub fn insert(&mut self, p: BigUint, k: u32) {
+        match self.primes.binary_search_by(|(q, _)| q.cmp(&p)) {
+            Ok(i) => self.primes[i].1 += k,
+            Err(i) => self.primes.insert(i, (p, k)),
+        }
+    }
+}
+
+/// Divides out the factors less than `bound`.
+///
+/// Returns the found primes with their multiplicities and the cofactor, which
+/// has no prime factor less than `bound` (`1` if `n` is fully factored).
+pub fn trial_division(n: &BigUint, bound: u32) -> (Vec<(BigUint, u32)>, BigUint) {
+    let mut factors = Vec::new();
+    let mut n = n.clone();
+    let mut d = 2_u32;
+    while d < bound && BigUint::from(d).pow(2) <= n {
+        let mut k = 0;
+        while (&n % d).is_zero() {
+            n /= d;
+            k += 1;
+        }
+        if k > 0 {
+            factors.push((BigUint::from(d), k));
+        }
+        d += if d == 2 { 1 } else { 2 };
+    }
+    // Whatever is left below d² is prime
+    if n > BigUint::one() && n < BigUint::from(d).pow(2) {
+        factors.push((n, 1));
+        n = BigUint::one();
+    }
+    (factors, n)
+}
+
+/// Non trivial factor of the composite `n`, by Pollard's rho.
+///
+/// Gives up after `steps` iterations, restarting with a different map when a
+/// cycle doesn't split `n`.
+pub fn pollard_rho(n: &BigUint, steps: u64) -> Option<BigUint> {
+    if n.is_even() {
+        return (n > &BigUint::from(2_u8)).then(|| BigUint::from(2_u8));
+    }
+    let f = |x: &BigUint, c: u32| (x * x + c) % n;
+    let mut budget = steps;
+    let mut c = 0;
+    while budget > 0 {
+        c += 1;
+        let (mut y, mut r) = (BigUint::from(2_u8), 1_u64);
+        let (mut x, mut ys) = (y.clone(), y.clone());
+        let (mut q, mut d) = (BigUint::one(), BigUint::one());
+        while d.is_one() && budget > 0 {
+            x = y.clone();
+            (0..r).for_each(|_| y = f(&y, c));
+            let mut k = 0;
+            while k < r && d.is_one() && budget > 0 {
+                ys = y.clone();
+                let batch = BATCH.min(r - k).min(budget);
+                for _ in 0..batch {
+                    y = f(&y, c);
+                    q = q * abs_diff(&x, &y) % n;
+                }
+                d = q.gcd(n);
+                k += batch;
+                budget -= batch;
+            }
+            r *= 2;
+        }
+        if &d == n {
+            // The batch overshot, redo its steps one by one
+            loop {
+                ys = f(&ys, c);
+                d = abs_diff(&x, &ys).gcd(n);
+                if !d.is_one() {
+                    break;
+                }
+            }
+        }
+        if !d.is_one() && &d != n {
+            return Some(d);
+        }
+    }
+    None
+}
+
+/// Factorization of `n` by trial division up to `bound`, then Pollard's rho,
+/// giving up on a factor after `steps` iterations.
+pub fn factor(
+    n: &BigUint,
+    bound: u32,
+    steps: u64,
+    is_prime: impl Fn(&BigUint) -> bool,
+) -> Factorization {
+    let (primes, cofactor) = trial_division(n, bound);
+    let mut result = Factorization {
+        primes,
+        composites: Vec::new(),
+    };
+    let mut pending = vec![cofactor];
+    while let Some(m) = pending.pop() {
+        if m.is_one() {
+            continue;
+        }
+        if is_prime(&m) {
+            result.insert(m, 1);
+            continue;
+        }
+        match pollard_rho(&m, steps) {
+            Some(d) => {
+                pending.push(&m / &d);
+                pending.push(d);
+            }
+            None => result.composites.push(m),
+        }
+    }
+    result.composites.sort();
+    result
+}
+
+fn abs_diff(a: &BigUint, b: &BigUint) -> BigUint {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Trial division is enough for the tests numbers
+    fn is_prime(n: &BigUint) -> bool {
+        let (factors, cofactor) = trial_division(n, u32::MAX);
+        cofactor.is_one() && factors == [(n.clone(), 1)]
+    }
+
+    #[test]
+    fn trial_division_splits_small_factors() {
+        let n = BigUint::from(2_u32.pow(5) * 3 * 49 * 101);
+        let (factors, cofactor) = trial_division(&(&n * 1000003_u32), 200);
+        let expected = [(2_u32, 5), (3, 1), (7, 2), (101, 1)];
+        assert_eq!(factors, expected.map(|(p, k)| (BigUint::from(p), k)));
+        assert_eq!(cofactor, BigUint::from(1000003_u32));
+    }
+
+    #[test]
+    fn rho_finds_factors() {
+        let (p, q) = (BigUint::from(1000003_u32), BigUint::from(999983_u32));
+        let d = pollard_rho(&(&p * &q), 1 << 16).unwrap();
+        assert!(d == p || d == q);
+
+        let n = &p * &q * 65537_u32 * 65537_u32 * 12_u32;
+        let factors = factor(&n, 100, 1 << 16, is_prime);
+        assert!(factors.is_complete());
+        assert_eq!(factors.largest_prime(), Some(&p));
+        let product = factors
+            .primes
+            .iter()
+            .fold(BigUint::one(), |acc, (p, k)| acc * p.pow(*k));
+        assert_eq!(product, n);
+    }
+
+    #[test]
+    fn budget_exhaustion_leaves_composites() {
+        let n = BigUint::from(1000003_u64 * 999983 * 4);
+        let factors = factor(&n, 100, 10, is_prime);
+        assert_eq!(factors.primes, [(BigUint::from(2_u8), 2)]);
+        assert_eq!(factors.composites, [BigUint::from(1000003_u64 * 999983)]);
+    }
+}
@@ -0,0 +1,118 @@
+//! Integer arithmetic: extended GCD, modular inverse, CRT and integer roots.
+
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+
+/// Extended Euclidean algorithm.
+///
+/// Returns `(g, x, y)` with `g = gcd(a, b) = a·x + b·y`.
+pub fn egcd(a: &BigUint, b: &BigUint) -> (BigUint, BigInt, BigInt) {
+    let (mut r0, mut r1) = (BigInt::from(a.clone()), BigInt::from(b.clone()));
+    let (mut x0, mut x1) = (BigInt::one(), BigInt::zero());
+    let (mut y0, mut y1) = (BigInt::zero(), BigInt::one());
+    while !r1.is_zero() {
+        let q = &r0 / &r1;
+        (r0, r1) = (r1.clone(), r0 - &q * r1);
+        (x0, x1) = (x1.clone(), x0 - &q * x1);
+        (y0, y1) = (y1.clone(), y0 - &q * y1);
+    }
+    (r0.magnitude().clone(), x0, y0)
+}
+
+/// Inverse of `a` modulo `m`, if `gcd(a, m) = 1`.
+pub fn mod_inv(a: &BigUint, m: &BigUint) -> Option<BigUint> {
+    let (g, x, _) = egcd(&(a % m), m);
+    if !g.is_one() {
+        return None;
+    }
+    let m = BigInt::from(m.clone());
+    x.mod_floor(&m).to_biguint()
+}
+
+/// Chinese remainder theorem.
+///
+/// Returns the least `x` such that `x ≡ residues[i] (mod moduli[i])` for every
+/// `i`, thus `x < lcm(moduli)`. The moduli are not required to be coprime, in
+/// which case the system may have no solution and `None` is returned.
+pub fn crt(residues: &[BigUint], moduli: &[BigUint]) -> Option<BigUint> {
+    assert_eq!(residues.len(), moduli.len(), "One modulus per residue");
+    let (mut x, mut m) = (BigUint::zero(), BigUint::one());
+    for (a, n) in residues.iter().zip(moduli) {
+        // x + m·k ≡ a (mod n) ⇒ (m/g)·k ≡ (a - x)/g (mod n/g)
+        let (g, inv, _) = egcd(&m, n);
+        let diff = BigInt::from(a % n) - BigInt::from(&x % n);
+        let (diff, rem) = diff.div_rem(&BigInt::from(g.clone()));
+        if !rem.is_zero() {
+            return None;
+        }
+        let n_g = BigInt::from(n / &g);
+        let k = (diff * inv).mod_floor(&n_g);
+        x += &m * k.magnitude();
+        m *= n_g.magnitude();
+        x %= &m;
+    }
+    Some(x)
+}
+
+/// Integer `n`-th root.
+///
+/// Returns `⌊x^(1/n)⌋` and whether the root is exact, e.g. a small RSA message
+/// encrypted with a small exponent and no padding.
+pub fn nth_root(x: &BigUint, n: u32) -> (BigUint, bool) {
+    let root = x.nth_root(n);
+    let exact = &root.pow(n) == x;
+    (root, exact)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn big() -> impl Strategy<Value = BigUint> {
+        prop::collection::vec(any::<u8>(), 1..40).prop_map(|b| BigUint::from_bytes_be(&b))
+    }
+
+    proptest! {
+        #[test]
+        fn bezout_identity(a in big(), b in big()) {
+            let (g, x, y) = egcd(&a, &b);
+            prop_assert_eq!(g.clone(), a.gcd(&b));
+            prop_assert_eq!(BigInt::from(a) * x + BigInt::from(b) * y, BigInt::from(g));
+        }
+
+        #[test]
+        fn inverse_or_common_factor(a in big(), m in big()) {
+            prop_assume!(m > BigUint::one());
+            match mod_inv(&a, &m) {
+                Some(inv) => prop_assert!((a * inv % &m).is_one()),
+                None => prop_assert!(!a.gcd(&m).is_one()),
+            }
+        }
+
+        #[test]
+        fn crt_solves_system(x in big(), moduli in prop::collection::vec(2..u32::MAX, 1..6)) {
+            let moduli: Vec<_> = moduli.into_iter().map(BigUint::from).collect();
+            let residues: Vec<_> = moduli.iter().map(|m| &x % m).collect();
+            let lcm = moduli.iter().fold(BigUint::one(), |l, m| l.lcm(m));
+            prop_assert_eq!(crt(&residues, &moduli), Some(x % lcm));
+        }
+
+        #[test]
+        fn exact_roots(x in big(), n in 1..8u32) {
+            prop_assert_eq!(nth_root(&x.pow(n), n), (x.clone(), true));
+            let (r, exact) = nth_root(&(x.pow(n) + 1u32), n);
+            prop_assert!(r.pow(n) <= x.pow(n) + 1u32 && (&r + 1u32).pow(n) > x.pow(n) + 1u32);
+            prop_assert_eq!(exact, n == 1 || x.is_zero());
+        }
+    }
+
+    #[test]
+    fn crt_inconsistent_system() {
+        let (residues, moduli) = ([1u32, 2].map(BigUint::from), [4u32, 6].map(BigUint::from));
+        assert_eq!(crt(&residues, &moduli), None);
+        let residues = [1u32, 3].map(BigUint::from);
+        assert_eq!(crt(&residues, &moduli), Some(BigUint::from(9u32)));
+    }
+}
@@ -0,0 +1,23 @@
+//! Number theory utilities over `BigUint`.
+//!
+//! Shared helpers for the attacks which need some modular arithmetic beyond
+//! `modpow`:
+//! - extended GCD, modular inverse, CRT for arbitrary moduli and integer roots
+//!   ([`integer`]), e.g. for Håstad broadcast, Pohlig–Hellman and RSA-CRT;
+//! - quadratic residuosity and modular square roots ([`sqrt`]), e.g. for point
+//!   decompression and Rabin decryption;
+//! - trial division and Pollard's rho factorization ([`factor`]), e.g. for
+//!   the smooth part of a group order;
+//! - Montgomery multiplication and fixed-window exponentiation
+//!   ([`montgomery`]), for the hot loops of modular products (benchmarked
+//!   against `BigUint` by `cargo bench`).
+
+pub mod factor;
+pub mod integer;
+pub mod montgomery;
+pub mod sqrt;
+
+pub use factor::{factor, pollard_rho, trial_division, Factorization};
+pub use integer::{crt, egcd, mod_inv, nth_root};
+pub use montgomery::{Montgomery, Residue};
+pub use sqrt::{jacobi_symbol, legendre_symbol, sqrt_mod_p, sqrt_mod_pq};
@@ -0,0 +1,300 @@
+//! Montgomery arithmetic and fixed-window exponentiation.
+//!
+//! Modulo an odd `n` of `s` 64 bits limbs, with `R = 2^(64·s)`, the residue
+//! `x` is represented as `x·R mod n`. The product of two representations is
+//! brought back to the representation of the product by the Montgomery
+//! reduction `t ↦ t·R⁻¹ mod n`, made of limb multiplications and shifts only:
+//! no division, which is what makes `a * b % n` on `BigUint` slow for
+//! repeated products.
+//!
+//! [`Montgomery::pow`] scans the exponent in fixed windows of `w` bits, with
+//! the `2^w` powers of the base precomputed: ≈ `bits` squarings and
+//! `bits / w` multiplications.
+//!
+//! Conversions cost a product each, thus the gain comes from long chains of
+//! operations in the representation (exponentiations, walks, tables). Not
+//! constant time.
+
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::One;
+
+/// Residue in Montgomery representation, valid for the context which made
+/// it. Fully reduced, thus equal residues have equal representations.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Residue(Vec<u64>);
+
+/// Montgomery context of an odd modulus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Montgomery {
+    n: BigUint,
+    limbs: Vec<u64>,
+    // -n⁻¹ mod 2^64
+    ninv: u64,
+    // R² mod n
+    r2: Residue,
+    // R mod n
+    one: Residue,
+}
+
+impl Montgomery {
+    /// Context for the modulus `n`, `None` if `n` is even or one.
+    pub fn new(n: &BigUint) -> Option<Self> {
+        if n.is_even() || n.is_one() {
+            return None;
+        }
+        let limbs = n.to_u64_digits();
+        let s = limbs.len();
+        // Newton iteration, each step doubles the correct low bits
+        let inv = (0..6).fold(1_u64, |x, _| {
+            x.wrapping_mul(2_u64.wrapping_sub(limbs[0].wrapping_mul(x)))
+        });
+        let r = BigUint::one() << (64 * s);
+        Some(Montgomery {
+            r2: Residue(pad(&(&r * &r % n), s)),
+            one: Residue(pad(&(r % n), s)),
+            n: n.clone(),
+            limbs,
+            ninv: inv.wrapping_neg(),
+        })
+    }
+
+    pub fn modulus(&self) -> &BigUint {
+        &self.n
+    }
+
+    /// Representation of `x mod n`.
+    pub fn to_mont(&self, x: &BigUint) -> Residue {
+        let x = if x < &self.n { x.clone() } else { x % &self.n };
+        self.mul(&Residue(pad(&x, self.limbs.len())), &self.r2)
+    }
+
+    /// Residue in `0..n` of a representation.
+    pub fn from_mont(&self, x: &Residue) -> BigUint {
+        let mut unit = vec![0; self.limbs.len()];
+        unit[0] = 1;
+        let limbs = self.mul(x, &Residue(unit)).0;
+        BigUint::from_slice(
+            &limbs
+                .iter()
+                .flat_map(|&l| [l as u32, (l >> 32) as u32])
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Representation of one.
+    pub fn one(&self) -> Residue {
+        self.one.clone()
+    }
+
+    /// Product.
+    pub fn mul(&self, a: &Residue, b: &Residue) -> Residue {
+        let mut t = Vec::new();
+        product(&a.0, &b.0, &mut t);
+        let mut out = Vec::new();
+        self.reduce(&mut t, &mut out);
+        Residue(out)
+    }
+
+    /// Square, about a quarter cheaper than a product.
+    pub fn sqr(&self, a: &Residue) -> Residue {
+        let mut t = Vec::new();
+        square(&a.0, &mut t);
+        let mut out = Vec::new();
+        self.reduce(&mut t, &mut out);
+        Residue(out)
+    }
+
+    /// `a^e`, by fixed-window exponentiation.
+    pub fn pow(&self, a: &Residue, e: &BigUint) -> Residue {
+        let bits = e.bits();
+        let w = window(bits);
+        let mut table = vec![self.one()];
+        for i in 1..1 << w {
+            table.push(self.mul(&table[i - 1], a));
+        }
+        // Buffers reused by every operation
+        let (mut t, mut r) = (Vec::new(), self.one.0.clone());
+        let windows = bits.div_ceil(w);
+        for k in (0..windows).rev() {
+            if k + 1 < windows {
+                for _ in 0..w {
+                    square(&r, &mut t);
+                    self.reduce(&mut t, &mut r);
+                }
+            }
+            let digit = (0..w)
+                .rev()
+                .fold(0, |acc, i| acc << 1 | e.bit(k * w + i) as usize);
+            if digit != 0 {
+                product(&r, &table[digit].0, &mut t);
+                self.reduce(&mut t, &mut r);
+            }
+        }
+        Residue(r)
+    }
+
+    // Montgomery reduction of the double width `t` (destroyed) into `out`:
+    // a multiple of `n` is added, one limb at a time, to zero the low half.
+    fn reduce(&self, t: &mut [u64], out: &mut Vec<u64>) {
+        let (n, s) = (&self.limbs, self.limbs.len());
+        // Carry out of the limb i + s, added at the next step
+        let mut top = 0;
+        for i in 0..s {
+            let m = t[i].wrapping_mul(self.ninv);
+            let carry = mac(&mut t[i..i + s], n, m);
+            let (x, o1) = t[i + s].overflowing_add(carry);
+            let (x, o2) = x.overflowing_add(top);
+            t[i + s] = x;
+            top = (o1 || o2) as u64;
+        }
+        t[2 * s] = top;
+        // Here t / R < 2n
+        let high = &mut t[s..];
+        if high[s] != 0 || !less(&high[..s], n) {
+            let mut borrow = false;
+            for (x, &nj) in high.iter_mut().zip(n) {
+                let (d, b1) = x.overflowing_sub(nj);
+                let (d, b2) = d.overflowing_sub(borrow as u64);
+                *x = d;
+                borrow = b1 || b2;
+            }
+        }
+        out.clear();
+        out.extend_from_slice(&high[..s]);
+    }
+
+    /// `base^e mod n`.
+    pub fn modpow(&self, base: &BigUint, e: &BigUint) -> BigUint {
+        self.from_mont(&self.pow(&self.to_mont(base), e))
+    }
+}
+
+/// `base^e mod n`, in Montgomery representation if `n` is odd and by
+/// `BigUint::modpow` otherwise.
+pub fn modpow(base: &BigUint, e: &BigUint, n: &BigUint) -> BigUint {
+    match Montgomery::new(n) {
+        Some(ctx) => ctx.modpow(base, e),
+        None => base.modpow(e, n),
+    }
+}
+
+// t += a·b, over the `a.len()` limbs of `t`, returning the carry limb.
+fn mac(t: &mut [u64], a: &[u64], b: u64) -> u64 {
+    let mut carry = 0;
+    for (x, &aj) in t.iter_mut().zip(a) {
+        let v = *x as u128 + aj as u128 * b as u128 + carry as u128;
+        *x = v as u64;
+        carry = (v >> 64) as u64;
+    }
+    carry
+}
+
+// Double width a·b in `t`, plus a zero limb for the reduction carry.
+fn product(a: &[u64], b: &[u64], t: &mut Vec<u64>) {
+    let s = a.len();
+    t.clear();
+    t.resize(2 * s + 1, 0);
+    for (i, &ai) in a.iter().enumerate() {
+        t[i + s] = mac(&mut t[i..i + s], b, ai);
+    }
+}
+
+// Double width a² in `t`: the cross products are computed once and doubled.
+fn square(a: &[u64], t: &mut Vec<u64>) {
+    let s = a.len();
+    t.clear();
+    t.resize(2 * s + 1, 0);
+    for i in 0..s {
+        t[i + s] = mac(&mut t[2 * i + 1..i + s], &a[i + 1..], a[i]);
+    }
+    let mut top = 0;
+    for x in t[..2 * s].iter_mut() {
+        (*x, top) = (*x << 1 | top, *x >> 63);
+    }
+    let mut carry = 0;
+    for (pair, &ai) in t.chunks_exact_mut(2).zip(a) {
+        let v = ai as u128 * ai as u128;
+        let lo = pair[0] as u128 + (v as u64) as u128 + carry as u128;
+        let hi = pair[1] as u128 + (v >> 64) + (lo >> 64);
+        pair[0] = lo as u64;
+        pair[1] = hi as u64;
+        carry = (hi >> 64) as u64;
+    }
+}
+
+// Window width minimizing the multiplications, table included.
+fn window(bits: u64) -> u64 {
+    match bits {
+        0..=24 => 1,
+        25..=80 => 3,
+        81..=240 => 4,
+        241..=672 => 5,
+        _ => 6,
+    }
+}
+
+fn pad(x: &BigUint, s: usize) -> Vec<u64> {
+    let mut limbs = x.to_u64_digits();
+    limbs.resize(s, 0);
+    limbs
+}
+
+// a < b, for limbs of the same length.
+fn less(a: &[u64], b: &[u64]) -> bool {
+    a.iter().rev().cmp(b.iter().rev()).is_lt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn even_modulus_rejected() {
+        assert!(Montgomery::new(&BigUint::from(10_u8)).is_none());
+        assert!(Montgomery::new(&BigUint::one()).is_none());
+        let n = BigUint::from(10_u8);
+        assert_eq!(
+            modpow(&BigUint::from(3_u8), &BigUint::from(3_u8), &n),
+            7_u8.into()
+        );
+    }
+
+    #[test]
+    fn representation_round_trip() {
+        // Two limbs, top limb saturated
+        let n = BigUint::from(u128::MAX - 158);
+        let ctx = Montgomery::new(&n).unwrap();
+        for x in [
+            0_u128,
+            1,
+            2,
+            u64::MAX as u128 + 5,
+            u128::MAX - 159,
+            u128::MAX,
+        ] {
+            let x = BigUint::from(x);
+            assert_eq!(ctx.from_mont(&ctx.to_mont(&x)), &x % &n);
+        }
+        assert_eq!(ctx.from_mont(&ctx.one()), BigUint::one());
+    }
+
+    proptest! {
+        #[test]
+        fn matches_biguint(
+            n in prop::collection::vec(any::<u32>(), 1..20),
+            a in prop::collection::vec(any::<u32>(), 0..20),
+            b in prop::collection::vec(any::<u32>(), 0..20),
+            e in prop::collection::vec(any::<u32>(), 0..10),
+        ) {
+            let n = BigUint::from_slice(&n) | BigUint::one();
+            prop_assume!(!n.is_one());
+            let (a, b, e) = (BigUint::from_slice(&a), BigUint::from_slice(&b), BigUint::from_slice(&e));
+            let ctx = Montgomery::new(&n).unwrap();
+            let product = ctx.mul(&ctx.to_mont(&a), &ctx.to_mont(&b));
+            prop_assert_eq!(ctx.from_mont(&product), &a * &b % &n);
+            prop_assert_eq!(ctx.modpow(&a, &e), a.modpow(&e, &n));
+        }
+    }
+}
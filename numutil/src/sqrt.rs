@@ -0,0 +1,214 @@
+//! Quadratic residues and modular square roots.
+//!
+//! - [`legendre_symbol`] via Euler's criterion `a^((p-1)/2) mod p`;
+//! - [`jacobi_symbol`] via quadratic reciprocity, without factoring `n`;
+//! - [`sqrt_mod_p`] via Tonelli–Shanks: write `p - 1 = 2^s·q` with `q` odd,
+//!   then the candidate root `a^((q+1)/2)` is fixed up with powers of a
+//!   quadratic non-residue, lowering the order of the error term `a^q` in the
+//!   2-Sylow subgroup by at least one bit per iteration;
+//! - [`sqrt_mod_pq`] combines the roots modulo the two primes via CRT.
+
+use crate::crt;
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::{One, Zero};
+
+/// Legendre symbol `(a/p)` for the odd prime `p`.
+///
+/// Returns 0 if `p` divides `a`, 1 if `a` is a quadratic residue modulo `p`
+/// and -1 otherwise.
+pub fn legendre_symbol(a: &BigUint, p: &BigUint) -> i8 {
+    let a = a % p;
+    if a.is_zero() {
+        return 0;
+    }
+    let exp = (p - 1u32) >> 1;
+    if a.modpow(&exp, p).is_one() {
+        1
+    } else {
+        -1
+    }
+}
+
+/// Jacobi symbol `(a/n)` for the odd positive `n`.
+///
+/// Coincides with the Legendre symbol when `n` is prime. For composite `n` the
+/// value 1 doesn't imply that `a` is a quadratic residue.
+pub fn jacobi_symbol(a: &BigUint, n: &BigUint) -> i8 {
+    assert!(n.is_odd(), "Jacobi symbol is defined for odd moduli");
+    let mut a = a % n;
+    let mut n = n.clone();
+    let mut result = 1;
+    while !a.is_zero() {
+        // (2/n) = -1 iff n ≡ 3, 5 (mod 8)
+        let twos = a.trailing_zeros().unwrap_or(0);
+        a >>= twos;
+        if twos % 2 == 1 && matches!(low_bits(&n, 3), 3 | 5) {
+            result = -result;
+        }
+        // Reciprocity: (a/n) = -(n/a) iff a ≡ n ≡ 3 (mod 4)
+        std::mem::swap(&mut a, &mut n);
+        if low_bits(&a, 2) == 3 && low_bits(&n, 2) == 3 {
+            result = -result;
+        }
+        a %= &n;
+    }
+    if n.is_one() {
+        result
+    } else {
+        0
+    }
+}
+
+// n mod 2^bits, for small bits.
+fn low_bits(n: &BigUint, bits: u32) -> u32 {
+    n.iter_u32_digits().next().unwrap_or(0) & ((1 << bits) - 1)
+}
+
+/// Square root of `a` modulo the prime `p`.
+///
+/// Returns `None` if `a` is not a quadratic residue. Otherwise returns one of
+/// the two roots `r`, the other one being `p - r`.
+pub fn sqrt_mod_p(a: &BigUint, p: &BigUint) -> Option<BigUint> {
+    let a = a % p;
+    if a.is_zero() || p == &BigUint::from(2u32) {
+        return Some(a);
+    }
+    if legendre_symbol(&a, p) != 1 {
+        return None;
+    }
+    // Fast path: r = a^((p+1)/4)
+    if low_bits(p, 2) == 3 {
+        return Some(a.modpow(&((p + 1u32) >> 2), p));
+    }
+
+    // p - 1 = 2^s·q
+    let p_minus_one = p - 1u32;
+    let s = p_minus_one.trailing_zeros().expect("p > 2");
+    let q = &p_minus_one >> s;
+
+    // Any quadratic non-residue, half of the elements are.
+    let mut z = BigUint::from(2u32);
+    while legendre_symbol(&z, p) != -1 {
+        z += 1u32;
+    }
+
+    // Invariants: r² = a·t and t has order 2^m.
+    let mut m = s;
+    let mut c = z.modpow(&q, p);
+    let mut t = a.modpow(&q, p);
+    let mut r = a.modpow(&((&q + 1u32) >> 1), p);
+    while !t.is_one() {
+        // Least i such that t^(2^i) = 1.
+        let mut i = 0;
+        let mut t2 = t.clone();
+        while !t2.is_one() {
+            t2 = &t2 * &t2 % p;
+            i += 1;
+        }
+        // b = c^(2^(m-i-1)) has order 2^(i+1).
+        let b = c.modpow(&(BigUint::one() << (m - i - 1)), p);
+        m = i;
+        c = &b * &b % p;
+        t = t * &c % p;
+        r = r * b % p;
+    }
+    Some(r)
+}
+
+/// Square roots of `a` modulo `n = p·q`, for the distinct primes `p` and `q`.
+///
+/// Returns the (up to four) roots in increasing order, or an empty vector if
+/// `a` is not a quadratic residue modulo both the primes.
+pub fn sqrt_mod_pq(a: &BigUint, p: &BigUint, q: &BigUint) -> Vec<BigUint> {
+    let (Some(rp), Some(rq)) = (sqrt_mod_p(a, p), sqrt_mod_p(a, q)) else {
+        return Vec::new();
+    };
+    let moduli = [p.clone(), q.clone()];
+    let mut roots: Vec<_> = [rp.clone(), p - &rp]
+        .into_iter()
+        .flat_map(|xp| [rq.clone(), q - &rq].map(|xq| [xp.clone(), xq]))
+        .filter_map(|residues| crt(&residues, &moduli))
+        .collect();
+    roots.sort();
+    roots.dedup();
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    // Primes covering all the Tonelli–Shanks paths, from p ≡ 3 (mod 4) up to
+    // the NIST P-224 prime with p - 1 divisible by 2^96.
+    const PRIMES: &[&str] = &[
+        "3",
+        "17",
+        "97",
+        "7681",
+        "65537",
+        "1000000007",
+        "170141183460469231731687303715884105727",
+        "57896044618658097711785492504343953926634992332820282019728792003956564819949",
+        "26959946667150639794667015087019630673557916260026308143510066298881",
+    ];
+
+    fn primes() -> impl Strategy<Value = BigUint> {
+        prop::sample::select(PRIMES).prop_map(|p| p.parse().unwrap())
+    }
+
+    fn big() -> impl Strategy<Value = BigUint> {
+        prop::collection::vec(any::<u8>(), 1..40).prop_map(|b| BigUint::from_bytes_be(&b))
+    }
+
+    proptest! {
+        #[test]
+        fn square_has_root(p in primes(), x in big()) {
+            let a = &x * &x % &p;
+            let r = sqrt_mod_p(&a, &p).unwrap();
+            prop_assert_eq!(&r * &r % &p, a.clone());
+            prop_assert_eq!(legendre_symbol(&a, &p), if a.is_zero() { 0 } else { 1 });
+        }
+
+        #[test]
+        fn non_residue_has_no_root(p in primes().prop_filter("odd", |p| p.is_odd()), x in big()) {
+            let mut z = BigUint::from(2u32);
+            while legendre_symbol(&z, &p) != -1 {
+                z += 1u32;
+            }
+            let a = &x * &x % &p * z % &p;
+            prop_assume!(!a.is_zero());
+            prop_assert_eq!(legendre_symbol(&a, &p), -1);
+            prop_assert_eq!(sqrt_mod_p(&a, &p), None);
+        }
+
+        #[test]
+        fn jacobi_matches_legendre(p in primes(), a in big()) {
+            prop_assert_eq!(jacobi_symbol(&a, &p), legendre_symbol(&a, &p));
+        }
+
+        #[test]
+        fn jacobi_is_multiplicative(a in big(), m in 0..u32::MAX, n in 0..u32::MAX) {
+            let (m, n) = (BigUint::from(m | 1), BigUint::from(n | 1));
+            prop_assert_eq!(
+                jacobi_symbol(&a, &(&m * &n)),
+                jacobi_symbol(&a, &m) * jacobi_symbol(&a, &n)
+            );
+        }
+
+        #[test]
+        fn four_roots_modulo_pq(x in big()) {
+            let (p, q) = (BigUint::from(1000000007u32), BigUint::from(998244353u32));
+            let n = &p * &q;
+            let a = &x * &x % &n;
+            prop_assume!(!(&a % &p).is_zero() && !(&a % &q).is_zero());
+            let roots = sqrt_mod_pq(&a, &p, &q);
+            prop_assert_eq!(roots.len(), 4);
+            prop_assert!(roots.contains(&(&x % &n)));
+            for r in roots {
+                prop_assert_eq!(&r * &r % &n, a.clone());
+            }
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! Plaintext recovery attacks on an OPE column.
+
+/// Confidence of the [`window`] bounds, in standard deviations.
+pub const Z: f64 = 4.0;
+
+// Indices sorting the values, with the middle rank of each run of equal values.
+fn mid_ranks(values: &[u64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by_key(|&i| values[i]);
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let end = start
+            + order[start..]
+                .iter()
+                .take_while(|&&i| values[i] == values[order[start]])
+                .count();
+        let mid = (start + end - 1) as f64 / 2.0;
+        order[start..end].iter().for_each(|&i| ranks[i] = mid);
+        start = end;
+    }
+    ranks
+}
+
+/// Sorting attack on a dense column, where every value of `min..` is present.
+///
+/// The `i`-th smallest distinct ciphertext encrypts `min + i`.
+pub fn sorting(ciphertexts: &[u64], min: u64) -> Vec<u64> {
+    let mut distinct = ciphertexts.to_vec();
+    distinct.sort_unstable();
+    distinct.dedup();
+    ciphertexts
+        .iter()
+        .map(|c| min + distinct.binary_search(c).unwrap() as u64)
+        .collect()
+}
+
+/// Cumulative distribution attack: map each ciphertext to the `auxiliary`
+/// value with the same quantile.
+pub fn cdf_matching(ciphertexts: &[u64], auxiliary: &[u64]) -> Vec<u64> {
+    let mut auxiliary = auxiliary.to_vec();
+    auxiliary.sort_unstable();
+    let n = ciphertexts.len() as f64;
+    mid_ranks(ciphertexts)
+        .into_iter()
+        .map(|r| {
+            let q = (r + 0.5) / n;
+            auxiliary[((q * auxiliary.len() as f64) as usize).min(auxiliary.len() - 1)]
+        })
+        .collect()
+}
+
+/// Plaintext bounds from the ciphertext alone, knowing the public scheme
+/// parameter (in Boldyreva et al. the domain and range sizes).
+///
+/// The ciphertext of `x` is the sum of `x + 1` gaps, uniform in
+/// `1..=max_gap` with mean `μ` and standard deviation `σ`. Then
+/// `x + 1 ≈ c / μ`, within `Z·σ·√(x + 1) / μ`.
+pub fn window(c: u64, max_gap: u64) -> (u64, u64) {
+    let g = max_gap as f64;
+    let (mu, sigma) = ((g + 1.0) / 2.0, ((g * g - 1.0) / 12.0).sqrt());
+    let n = c as f64 / mu;
+    let half = Z * sigma * n.sqrt() / mu;
+    let lo = (n - 1.0 - half).max(0.0).floor() as u64;
+    let hi = (n - 1.0 + half).ceil() as u64;
+    (lo, hi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{salaries, scoring, Ope, MAX_SALARY};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn dense_column_is_sorted_out() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let ope = Ope::new(&rng.gen(), 120, 1 << 16);
+        let mut ages: Vec<u64> = (18..=67).collect();
+        ages.extend((0..500).map(|_| rng.gen_range(18..=67)));
+        let cts: Vec<_> = ages.iter().map(|&x| ope.encrypt(x)).collect();
+        assert_eq!(sorting(&cts, 18), ages);
+    }
+
+    #[test]
+    fn sparse_column_with_auxiliary_data() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let ope = Ope::new(&rng.gen(), MAX_SALARY, 1 << 16);
+        let truth = salaries(&mut rng, 2000);
+        let auxiliary = salaries(&mut rng, 2000);
+        let cts: Vec<_> = truth.iter().map(|&x| ope.encrypt(x)).collect();
+
+        let bounds: Vec<_> = cts.iter().map(|&c| window(c, ope.max_gap())).collect();
+        let (covered, width) = scoring::coverage(&truth, &bounds);
+        assert!(covered > 0.99 && width < 2000.0);
+
+        let cdf = scoring::Metrics::new(&truth, &cdf_matching(&cts, &auxiliary), 1000);
+        assert!(cdf.within > 0.5);
+    }
+}
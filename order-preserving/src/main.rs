@@ -0,0 +1,42 @@
+use order_preserving::{attacks, salaries, scoring, Ope, MAX_SALARY};
+use rand::{rngs::OsRng, Rng};
+
+fn main() {
+    let max_gap = 1 << 16;
+
+    let ope = Ope::new(&OsRng.gen(), 120, max_gap);
+    let ages: Vec<u64> = (0..1000).map(|_| OsRng.gen_range(18..=67)).collect();
+    let cts: Vec<_> = ages.iter().map(|&x| ope.encrypt(x)).collect();
+    let m = scoring::Metrics::new(&ages, &attacks::sorting(&cts, 18), 0);
+    println!("Ages (dense), sorting attack: {:.3} exact", m.exact);
+
+    let ope = Ope::new(&OsRng.gen(), MAX_SALARY, max_gap);
+    let auxiliary = salaries(&mut OsRng, 5000);
+    let tolerance = 1000;
+    println!("Salaries (sparse), errors within {tolerance} EUR:");
+    for n in [100, 1000, 10_000] {
+        let truth = salaries(&mut OsRng, n);
+        let cts: Vec<_> = truth.iter().map(|&x| ope.encrypt(x)).collect();
+        println!("  {n} records");
+        let bounds: Vec<_> = cts.iter().map(|&c| attacks::window(c, max_gap)).collect();
+        let (covered, width) = scoring::coverage(&truth, &bounds);
+        println!(
+            "    window:       {:.3} covered, mean width {width:.0} EUR",
+            covered
+        );
+        let midpoints: Vec<_> = bounds.iter().map(|(lo, hi)| (lo + hi) / 2).collect();
+        for (name, estimates) in [
+            ("window center", midpoints),
+            ("cdf matching", attacks::cdf_matching(&cts, &auxiliary)),
+        ] {
+            let m = scoring::Metrics::new(&truth, &estimates, tolerance);
+            println!(
+                "    {:<13} {:.3} exact, mean error {:>6.0} EUR, {:.3} within",
+                format!("{name}:"),
+                m.exact,
+                m.mean_abs,
+                m.within
+            );
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! Plaintext recovery error metrics.
+
+/// Point estimates errors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Metrics {
+    /// Fraction of exactly recovered values.
+    pub exact: f64,
+    /// Mean absolute error.
+    pub mean_abs: f64,
+    /// Fraction of estimates within the tolerance.
+    pub within: f64,
+}
+
+impl Metrics {
+    pub fn new(truth: &[u64], estimates: &[u64], tolerance: u64) -> Self {
+        let n = truth.len() as f64;
+        let errors: Vec<u64> = truth
+            .iter()
+            .zip(estimates)
+            .map(|(t, e)| t.abs_diff(*e))
+            .collect();
+        Metrics {
+            exact: errors.iter().filter(|&&e| e == 0).count() as f64 / n,
+            mean_abs: errors.iter().sum::<u64>() as f64 / n,
+            within: errors.iter().filter(|&&e| e <= tolerance).count() as f64 / n,
+        }
+    }
+}
+
+/// Interval estimates quality: fraction of values in their interval and mean
+/// interval width.
+pub fn coverage(truth: &[u64], bounds: &[(u64, u64)]) -> (f64, f64) {
+    let n = truth.len() as f64;
+    let covered = truth
+        .iter()
+        .zip(bounds)
+        .filter(|(t, (lo, hi))| (lo..=hi).contains(t))
+        .count();
+    let width: u64 = bounds.iter().map(|(lo, hi)| hi - lo).sum();
+    (covered as f64 / n, width as f64 / n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metrics() {
+        let m = Metrics::new(&[10, 20, 30, 40], &[10, 25, 30, 100], 5);
+        assert_eq!(m.exact, 0.5);
+        assert_eq!(m.mean_abs, 65.0 / 4.0);
+        assert_eq!(m.within, 0.75);
+        assert_eq!(coverage(&[1, 5], &[(0, 2), (0, 4)]), (0.5, 3.0));
+    }
+}
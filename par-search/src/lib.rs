@@ -0,0 +1,97 @@
+//! Parallel exhaustive search.
+//!
+//! The brute force attacks (weak keys, short challenges, unsalted
+//! commitments, proofs of work, brain wallets) all scan a candidate space on
+//! every core and stop at the first hit. These helpers are that loop, so the
+//! attacks only describe what a hit is:
+//! - [`find`] and [`find_map`]: any candidate of a range (or of any rayon
+//!   source) satisfying a test, the search stops as soon as one is found;
+//! - [`find_map_chunks`]: same, over a range split into chunks, for searches
+//!   where moving to the next candidate is cheaper than starting from scratch;
+//! - [`filter_map`]: every hit in a sequential stream of candidates, e.g. a
+//!   wordlist.
+//!
+//! With several hits which one is returned is unspecified.
+
+use rayon::prelude::*;
+use std::ops::Range;
+
+/// Any candidate satisfying `pred`.
+pub fn find<I, F>(candidates: I, pred: F) -> Option<I::Item>
+where
+    I: IntoParallelIterator,
+    F: Fn(&I::Item) -> bool + Sync + Send,
+{
+    candidates.into_par_iter().find_any(pred)
+}
+
+/// Any `Some` returned by `f` on the candidates.
+pub fn find_map<I, T, F>(candidates: I, f: F) -> Option<T>
+where
+    I: IntoParallelIterator,
+    T: Send,
+    F: Fn(I::Item) -> Option<T> + Sync + Send,
+{
+    candidates.into_par_iter().find_map_any(f)
+}
+
+/// Any `Some` returned by `f` on the chunks of `range`, each chunk (at most
+/// `chunk` candidates) being scanned sequentially by `f`.
+pub fn find_map_chunks<T, F>(range: Range<u64>, chunk: u64, f: F) -> Option<T>
+where
+    T: Send,
+    F: Fn(Range<u64>) -> Option<T> + Sync + Send,
+{
+    assert!(chunk > 0, "Empty chunks");
+    let chunks = (range.end.saturating_sub(range.start)).div_ceil(chunk);
+    find_map(0..chunks, |i| {
+        let start = range.start + i * chunk;
+        f(start..range.end.min(start + chunk))
+    })
+}
+
+/// Every `Some` returned by `f` on the `candidates` stream, in no particular
+/// order.
+pub fn filter_map<I, T, F>(candidates: I, f: F) -> Vec<T>
+where
+    I: Iterator + Send,
+    I::Item: Send,
+    T: Send,
+    F: Fn(I::Item) -> Option<T> + Sync + Send,
+{
+    candidates.par_bridge().filter_map(f).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_only_hit() {
+        assert_eq!(find(0..1_000_000_u64, |&i| i * i == 4_000_000), Some(2000));
+        assert_eq!(find(0..10_u64, |&i| i > 10), None);
+        assert_eq!(
+            find_map(0..=u8::MAX, |i| (i == u8::MAX).then_some("max")),
+            Some("max")
+        );
+    }
+
+    #[test]
+    fn chunks_cover_the_range() {
+        let hit = |target| find_map_chunks(3..1000, 64, |r| r.clone().find(|&i| i == target));
+        assert_eq!(hit(3), Some(3));
+        assert_eq!(hit(999), Some(999));
+        assert_eq!(hit(1000), None);
+        assert_eq!(find_map_chunks(5..5, 8, |_| Some(())), None);
+    }
+
+    #[test]
+    fn stream_hits() {
+        let mut hits = filter_map((0..100).map(|i| i.to_string()), |s| {
+            s.ends_with('7').then_some(s)
+        });
+        hits.sort();
+        assert_eq!(hits.len(), 10);
+        assert_eq!(hits[0], "17");
+    }
+}
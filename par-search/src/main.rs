@@ -0,0 +1,11 @@
+use std::time::Instant;
+
+fn main() {
+    // A 28 bits "preimage" of a toy hash
+    let hash = |x: u64| x.wrapping_mul(0x9e37_79b9_7f4a_7c15) >> 32;
+    let target = hash(0x0c0f_fee4);
+    println!("Searching x < 2^28 with hash(x) = {target:#x}");
+    let start = Instant::now();
+    let x = par_search::find(0..1_u64 << 28, |&x| hash(x) == target);
+    println!("  found {x:x?} in {:?}", start.elapsed());
+}
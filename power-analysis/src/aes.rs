@@ -0,0 +1,122 @@
+//! CPA against the first round of a table based AES-128.
+//!
+//! The victim is the T-table implementation of the `aes-cache-timing` crate,
+//! leaking the Hamming weight of every looked up table entry. In the first
+//! round the entry index is `p[i] ⊕ k[i]` and the entry is `S(p[i] ⊕ k[i])`
+//! multiplied by the `MixColumns` coefficients `(2, 1, 1, 3)`.
+//!
+//! The attacker doesn't model the table layout and just predicts the weight of
+//! the S-box output `HW(S(p[i] ⊕ k))` for each guess `k`: the prediction is
+//! only partially correlated with the actual leakage, yet enough to single out
+//! the right guess among the 256.
+
+use crate::{max_correlation, points, Leakage, Trace};
+use aes_cache_timing::aes::{Aes128, Probe, SBOX};
+
+fn xtime(x: u8) -> u8 {
+    (x << 1) ^ if x & 0x80 != 0 { 0x1b } else { 0 }
+}
+
+// Records the leakage of the table lookups.
+struct PowerProbe<'a> {
+    leakage: &'a mut Leakage,
+    trace: Trace,
+}
+
+impl Probe for PowerProbe<'_> {
+    fn lookup(&mut self, _round: usize, table: usize, index: u8) {
+        let s = SBOX[index as usize];
+        // The rotations of (2s, s, s, 3s) have all the same weight
+        let weight = if table < 4 {
+            xtime(s).count_ones() + 2 * s.count_ones() + (xtime(s) ^ s).count_ones()
+        } else {
+            s.count_ones()
+        };
+        self.trace.push(self.leakage.sample(weight));
+    }
+}
+
+/// Device encrypting with a secret key.
+pub struct Victim {
+    aes: Aes128,
+    leakage: Leakage,
+}
+
+impl Victim {
+    /// Device with the given `key` and leakage noise.
+    pub fn new(key: &[u8; 16], sigma: f64, seed: u64) -> Self {
+        Victim {
+            aes: Aes128::new(key),
+            leakage: Leakage::new(sigma, seed),
+        }
+    }
+
+    /// Encrypt `block`, returning the ciphertext and the power trace.
+    pub fn encrypt(&mut self, block: &[u8; 16]) -> ([u8; 16], Trace) {
+        let mut probe = PowerProbe {
+            leakage: &mut self.leakage,
+            trace: Vec::with_capacity(160),
+        };
+        let ct = self.aes.encrypt_block(block, &mut probe);
+        (ct, probe.trace)
+    }
+}
+
+/// Candidates for the `byte`-th key byte, sorted by decreasing correlation.
+pub fn rank_byte(points: &[Vec<f64>], plaintexts: &[[u8; 16]], byte: usize) -> Vec<(u8, f64)> {
+    let mut ranking: Vec<_> = (0..=255_u8)
+        .map(|k| {
+            let predictions: Vec<_> = plaintexts
+                .iter()
+                .map(|p| SBOX[(p[byte] ^ k) as usize].count_ones() as f64)
+                .collect();
+            (k, max_correlation(points, &predictions).1)
+        })
+        .collect();
+    ranking.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranking
+}
+
+/// Recover the key from the `traces` of the known `plaintexts` encryptions.
+pub fn recover_key(traces: &[Trace], plaintexts: &[[u8; 16]]) -> [u8; 16] {
+    let points = points(traces);
+    let mut key = [0; 16];
+    for (i, k) in key.iter_mut().enumerate() {
+        *k = rank_byte(&points, plaintexts, i)[0].0;
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+
+    fn collect(victim: &mut Victim, n: usize) -> (Vec<[u8; 16]>, Vec<Trace>) {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let plaintexts: Vec<[u8; 16]> = (0..n).map(|_| rng.gen()).collect();
+        let traces = plaintexts.iter().map(|p| victim.encrypt(p).1).collect();
+        (plaintexts, traces)
+    }
+
+    #[test]
+    fn traces_do_not_alter_encryption() {
+        let key = [0x2b; 16];
+        let mut victim = Victim::new(&key, 1.0, 0);
+        let (ct, trace) = victim.encrypt(&[0; 16]);
+        assert_eq!(ct, Aes128::new(&key).encrypt(&[0; 16]));
+        assert_eq!(trace.len(), 160);
+    }
+
+    #[test]
+    fn recover_key_from_noisy_traces() {
+        let key: [u8; 16] = hex::decode("2b7e151628aed2a6abf7158809cf4f3c")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let mut victim = Victim::new(&key, 2.0, 1);
+        let (plaintexts, traces) = collect(&mut victim, 300);
+        assert_eq!(recover_key(&traces, &plaintexts), key);
+    }
+}
@@ -0,0 +1,137 @@
+//! CPA against a left to right square and multiply `m^d mod p`.
+//!
+//! The victim leaks the Hamming weight of the accumulator at the end of each
+//! iteration, i.e. one sample per exponent bit. Traces are thus aligned even if
+//! the number of multiplications depends on the secret.
+//!
+//! Bits are recovered from the most significant one. Given the recovered
+//! prefix, the attacker computes the accumulator of every message under the
+//! two hypotheses for the next bit (`r²` or `r²·m`) and keeps the one whose
+//! weights correlate with the next sample. Unlike the timing attack (see the
+//! `group-op-timing-attack` crate) no emulation of the victim is required.
+
+use crate::{points, stats::pearson, Leakage, Trace};
+use group_op_timing_attack::get_modulus;
+use num_bigint::{BigUint, RandBigInt};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+fn weight(x: &BigUint) -> f64 {
+    x.count_ones() as f64
+}
+
+/// Device holding a secret exponent.
+pub struct Victim {
+    modulus: BigUint,
+    secret: BigUint,
+    leakage: Leakage,
+}
+
+impl Victim {
+    /// Device with a random `keylen` bits secret (most significant bit set)
+    /// and the builtin modulus for `keylen` (see [`get_modulus`]).
+    pub fn new(keylen: u64, sigma: f64, seed: u64) -> Self {
+        let mut rng = ChaCha20Rng::seed_from_u64(seed);
+        let mut secret = rng.gen_biguint(keylen);
+        secret.set_bit(keylen - 1, true);
+        Victim {
+            modulus: get_modulus(keylen),
+            secret,
+            leakage: Leakage::new(sigma, seed),
+        }
+    }
+
+    pub fn modulus(&self) -> &BigUint {
+        &self.modulus
+    }
+
+    /// Device secret, to check the attack outcome.
+    pub fn secret(&self) -> &BigUint {
+        &self.secret
+    }
+
+    /// Compute `m^d mod p`, returning the result and the power trace.
+    pub fn sign(&mut self, m: &BigUint) -> (BigUint, Trace) {
+        let p = &self.modulus;
+        let mut r = BigUint::from(1_u8);
+        let mut trace = Vec::with_capacity(self.secret.bits() as usize);
+        for i in (0..self.secret.bits()).rev() {
+            r = &r * &r % p;
+            if self.secret.bit(i) {
+                r = r * m % p;
+            }
+            trace.push(self.leakage.sample(r.count_ones() as u32));
+        }
+        (r, trace)
+    }
+}
+
+/// Recover the exponent from the `traces` of the `messages` exponentiations.
+///
+/// Returns the exponent and the correlation of each recovered bit.
+pub fn recover_secret(
+    messages: &[BigUint],
+    traces: &[Trace],
+    modulus: &BigUint,
+) -> (BigUint, Vec<f64>) {
+    let points = points(traces);
+    let ms: Vec<_> = messages.iter().map(|m| m % modulus).collect();
+
+    // The first bit is set, thus the accumulator is `m`
+    let mut acc = ms.clone();
+    let mut secret = BigUint::from(1_u8);
+    let mut confidence = vec![pearson(
+        &points[0],
+        &acc.iter().map(weight).collect::<Vec<_>>(),
+    )];
+
+    for point in &points[1..] {
+        let squares: Vec<_> = acc.iter().map(|r| r * r % modulus).collect();
+        let products: Vec<_> = squares
+            .iter()
+            .zip(&ms)
+            .map(|(r, m)| r * m % modulus)
+            .collect();
+        let rho0 = pearson(point, &squares.iter().map(weight).collect::<Vec<_>>());
+        let rho1 = pearson(point, &products.iter().map(weight).collect::<Vec<_>>());
+        let bit = rho1 > rho0;
+        secret = secret << 1 | BigUint::from(bit as u8);
+        confidence.push(rho0.max(rho1));
+        acc = if bit { products } else { squares };
+    }
+    (secret, confidence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attack(keylen: u64, sigma: f64, n: usize) -> bool {
+        let mut victim = Victim::new(keylen, sigma, 3);
+        let mut rng = ChaCha20Rng::seed_from_u64(4);
+        let messages: Vec<_> = (0..n).map(|_| rng.gen_biguint(keylen)).collect();
+        let traces: Vec<_> = messages.iter().map(|m| victim.sign(m).1).collect();
+        let (secret, _) = recover_secret(&messages, &traces, victim.modulus());
+        &secret == victim.secret()
+    }
+
+    #[test]
+    fn sign_is_modpow() {
+        let mut victim = Victim::new(64, 1.0, 0);
+        let m = BigUint::from(0xdead_beef_u32);
+        let (r, trace) = victim.sign(&m);
+        assert_eq!(r, m.modpow(victim.secret(), victim.modulus()));
+        assert_eq!(trace.len(), 64);
+    }
+
+    #[test]
+    fn recover_exponent() {
+        assert!(attack(64, 4.0, 200));
+        assert!(attack(128, 4.0, 200));
+    }
+
+    #[test]
+    fn too_few_traces() {
+        assert!(!attack(64, 8.0, 10));
+    }
+}
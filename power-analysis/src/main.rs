@@ -0,0 +1,39 @@
+use num_bigint::RandBigInt;
+use power_analysis::{aes, exp, points};
+use rand::Rng;
+
+fn main() {
+    let mut rng = rand::thread_rng();
+    let seed = rng.gen();
+
+    let key: [u8; 16] = rng.gen();
+    println!(
+        "AES-128 first round S-box output, key: {}",
+        hex::encode(key)
+    );
+    for sigma in [1.0, 4.0, 8.0] {
+        let mut victim = aes::Victim::new(&key, sigma, seed);
+        for n in [50, 200, 500] {
+            let plaintexts: Vec<[u8; 16]> = (0..n).map(|_| rng.gen()).collect();
+            let traces: Vec<_> = plaintexts.iter().map(|p| victim.encrypt(p).1).collect();
+            let points = points(&traces);
+            let right = (0..16)
+                .filter(|&i| aes::rank_byte(&points, &plaintexts, i)[0].0 == key[i])
+                .count();
+            println!("  σ = {sigma}, {n:>4} traces: {right:>2}/16 key bytes");
+        }
+    }
+
+    let keylen = 256;
+    println!("Square and multiply, {keylen} bits exponent");
+    for sigma in [1.0, 4.0, 8.0] {
+        let mut victim = exp::Victim::new(keylen, sigma, seed);
+        for n in [20, 100, 500] {
+            let messages: Vec<_> = (0..n).map(|_| rng.gen_biguint(keylen)).collect();
+            let traces: Vec<_> = messages.iter().map(|m| victim.sign(m).1).collect();
+            let (secret, _) = exp::recover_secret(&messages, &traces, victim.modulus());
+            let wrong = (&secret ^ victim.secret()).count_ones();
+            println!("  σ = {sigma}, {n:>4} traces: {wrong:>3} wrong bits");
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! Curated building blocks of the crypto hacks.
+//!
+//! Every PoC is a crate on its own, with names chosen for its own story
+//! (two different `TimingOracle`s, a `Target`, a `Problem`...). This crate
+//! picks the reusable parts and exports them under one consistent naming
+//! scheme, so that new experiments can start from
+//! `use crypto_hacks_prelude::*` and compose existing pieces:
+//!
+//! - [`oracle`]: what an attacker queries. Traits answering queries are named
+//!   `<leak>Oracle`, the leak coming first (`PaddingOracle`,
+//!   `PaddingTimingOracle`, `SigningTimingOracle`, ...).
+//! - [`arith`]: modular arithmetic, primes and factoring over [`BigUint`].
+//! - [`group`]: the [`Group`] abstraction with its implementations (`Z_n*`,
+//!   toy curves), named groups, hashing to groups and linear algebra over
+//!   GF(2). Generic discrete log solvers are `dlog_<method>`.
+//! - [`search`]: the generic engines, parallel exhaustive search
+//!   (`par_<search>`), collision search and annealing, plus the generic
+//!   attacks driven by the oracles.
+//! - [`results`]: what attacks and engines return, named after what they
+//!   hold, prefixed with the attack when the bare name would be ambiguous.
+//!
+//! Modules meant to be used as a namespace ([`gf61`]) are not glob exported.
+//! Everything else is deliberately left in the PoC crates: demo victims,
+//! protocol specifics and knobs of a single attack.
+
+pub use num_bigint::BigUint;
+
+pub use arith::*;
+pub use group::*;
+pub use oracle::*;
+pub use results::*;
+pub use search::*;
+
+/// Query interfaces to the victims, with their in-process implementations
+/// and middleware.
+pub mod oracle {
+    pub use aes_cache_timing::aes::Probe as CacheProbe;
+    pub use cbc_padding_oracle::{
+        timing::{
+            SimulatedTimingOracle as SimulatedPaddingTimingOracle, TimingAdapter,
+            TimingOracle as PaddingTimingOracle,
+        },
+        transcript::{
+            Recorder as TranscriptRecorder, Replay as TranscriptReplay,
+            Response as TranscriptResponse,
+        },
+        CbcOracle, PaddingOracle,
+    };
+    pub use dh_mitm::{Channel, Message as DhMessage, Role as DhRole};
+    pub use group_op_timing_attack::TimingOracle as SigningTimingOracle;
+    pub use remote_timing::target::Target as RemoteTimingOracle;
+}
+
+/// Number theory over [`BigUint`].
+pub mod arith {
+    pub use miller_rabin::{is_prime, prime_num as random_prime, safe_prime as random_safe_prime};
+    pub use numutil::{
+        crt, egcd, factor, jacobi_symbol, legendre_symbol, mod_inv, montgomery::modpow, nth_root,
+        pollard_rho, sqrt_mod_p, sqrt_mod_pq, trial_division, Montgomery, Residue,
+    };
+}
+
+/// Groups, fields and their elements.
+pub mod group {
+    pub use dh_groups::{DhGroup, Error as DhGroupError, Named as NamedGroup};
+    pub use hash_to_group::{curve::CurveHasher, zp::Subgroup};
+    pub use invalid_curve::{
+        curve::{Curve, Point},
+        CURVE as TOY_CURVE, G as TOY_GENERATOR, ORDER as TOY_ORDER,
+    };
+    pub use lfsr::gf2::{BitVec, System as Gf2System};
+    pub use shanks::{
+        auto::{solve_dlog_auto, Error as DlogError, Hint as DlogHint, Zmod},
+        bsgs as dlog_bsgs,
+        kangaroo::kangaroo as dlog_kangaroo,
+        pow as group_pow,
+        rho::rho as dlog_rho,
+        Group,
+    };
+}
+
+/// Arithmetic of `GF(2^61 - 1)`, the field of the toy curves.
+pub mod gf61 {
+    pub use invalid_curve::curve::{add, inv, mul, pow, sqrt, sub, P};
+}
+
+/// Generic search engines and oracle driven attacks.
+pub mod search {
+    pub use birthday_paradox::{
+        distinguished::find_collision as find_collision_parallel, find_collision,
+        find_collision_rho,
+    };
+    pub use cbc_padding_oracle::{
+        attack as padding_oracle_attack, attack_parallel as padding_oracle_attack_parallel,
+        attack_with_stats as padding_oracle_attack_with_stats,
+    };
+    pub use classical::anneal::{
+        hill_climb, optimize as anneal, Config as AnnealConfig, Problem as SearchProblem,
+        Schedule as AnnealSchedule,
+    };
+    pub use par_search::{
+        filter_map as par_filter_map, find as par_find, find_map as par_find_map,
+        find_map_chunks as par_find_map_chunks,
+    };
+}
+
+/// Outcomes of the engines and attacks.
+pub mod results {
+    pub use birthday_paradox::Collision;
+    pub use cbc_padding_oracle::{AttackStats as PaddingAttackStats, BlockStats};
+    pub use classical::anneal::Solution;
+    pub use numutil::Factorization;
+    pub use shanks::auto::Plan as DlogPlan;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aes::Aes128;
+
+    #[test]
+    fn dlog_on_toy_curve() {
+        let x = 0x5_f00d;
+        let h = TOY_CURVE.mul(x, &TOY_GENERATOR);
+        assert_eq!(dlog_bsgs(&TOY_CURVE, &TOY_GENERATOR, &h, 1 << 20), Some(x));
+        assert_eq!(group_pow(&TOY_CURVE, &TOY_GENERATOR, x), h);
+    }
+
+    #[test]
+    fn parallel_dlog_search() {
+        let x = 0x1_2345;
+        let h = TOY_CURVE.mul(x, &TOY_GENERATOR);
+        let found = par_find_map_chunks(0..1 << 20, 1 << 10, |chunk| {
+            let mut p = TOY_CURVE.mul(chunk.start, &TOY_GENERATOR);
+            for i in chunk {
+                if p == h {
+                    return Some(i);
+                }
+                p = TOY_CURVE.add(&p, &TOY_GENERATOR);
+            }
+            None
+        });
+        assert_eq!(found, Some(x));
+    }
+
+    #[test]
+    fn padding_oracle_through_transcript() {
+        let key = [7; 16];
+        let iv = [1; 16];
+        let pt = b"composed from the prelude";
+        let ct = cbc_padding_oracle::encrypt::<Aes128>(&key, &iv, pt);
+        let mut transcript = Vec::new();
+        let recorder = TranscriptRecorder::new(CbcOracle::<Aes128>::new(&key), &mut transcript);
+        let (found, stats): (_, PaddingAttackStats) =
+            padding_oracle_attack_with_stats(&recorder, 16, &iv, &ct);
+        assert_eq!(found, pt);
+        drop(recorder);
+        let replay = TranscriptReplay::<bool>::from_reader(&transcript[..]).unwrap();
+        assert_eq!(padding_oracle_attack(&replay, 16, &iv, &ct), pt);
+        assert_eq!(
+            transcript.iter().filter(|&&b| b == b'\n').count(),
+            stats.total_queries()
+        );
+    }
+}
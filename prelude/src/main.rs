@@ -0,0 +1,56 @@
+use aes::Aes128;
+use crypto_hacks_prelude::*;
+use num_bigint::RandBigInt;
+use rand::Rng;
+
+fn main() {
+    let mut rng = rand::thread_rng();
+
+    // Pohlig-Hellman, from the factoring, discrete log and CRT pieces
+    let p = loop {
+        let mut m = BigUint::from(2_u8);
+        while m.bits() < 96 {
+            m *= rng.gen_range(2_u32..1 << 16);
+        }
+        if is_prime(&(&m + 1_u8)) {
+            break m + 1_u8;
+        }
+    };
+    let n = &p - 1_u8;
+    let g = rng.gen_biguint_range(&BigUint::from(2_u8), &n);
+    let h = modpow(&g, &rng.gen_biguint_below(&n), &p);
+    println!("Smooth prime p = {p}");
+    let factors = factor(&n, 1 << 16, 1 << 20, is_prime);
+    println!("  p - 1 = {:?}", factors.primes);
+    let group = Zmod { n: p.clone() };
+    let (residues, moduli): (Vec<_>, Vec<_>) = factors
+        .primes
+        .iter()
+        .map(|(q, e)| {
+            let qe = q.pow(*e);
+            let cofactor = &n / &qe;
+            let (gi, hi) = (modpow(&g, &cofactor, &p), modpow(&h, &cofactor, &p));
+            let bound = u64::try_from(&qe).expect("Smooth order");
+            let x = dlog_bsgs(&group, &gi, &hi, bound).expect("Subgroup log");
+            (BigUint::from(x), qe)
+        })
+        .unzip();
+    let x = crt(&residues, &moduli).expect("Coprime moduli");
+    println!("  log_g(h) = {x}, check: {}", modpow(&g, &x, &p) == h);
+
+    // Padding oracle attack recorded once, then replayed offline
+    let key: [u8; 16] = rng.gen();
+    let iv: [u8; 16] = rng.gen();
+    let ct = cbc_padding_oracle::encrypt::<Aes128>(&key, &iv, b"Replayed from the transcript");
+    let mut transcript = Vec::new();
+    let recorder = TranscriptRecorder::new(CbcOracle::<Aes128>::new(&key), &mut transcript);
+    let (_, stats) = padding_oracle_attack_with_stats(&recorder, 16, &iv, &ct);
+    drop(recorder);
+    println!(
+        "\nPadding oracle attack, {} recorded queries",
+        stats.total_queries()
+    );
+    let replay = TranscriptReplay::<bool>::from_reader(&transcript[..]).expect("Transcript");
+    let pt = padding_oracle_attack(&replay, 16, &iv, &ct);
+    println!("  replayed: {}", String::from_utf8_lossy(&pt));
+}
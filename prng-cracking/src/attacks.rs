@@ -0,0 +1,156 @@
+//! State recovery attacks.
+
+use crate::{
+    lcg::{JavaRandom, Lcg},
+    mt::{untemper, Mt19937, N},
+};
+
+/// Clone a MT19937 from (at least) 624 consecutive outputs.
+///
+/// The returned generator continues the sequence after the last output.
+/// Any alignment works, the twist recurrence holds on every window of the
+/// untempered outputs.
+pub fn clone_mt(outputs: &[u32]) -> Option<Mt19937> {
+    let window = outputs.get(outputs.len().checked_sub(N)?..)?;
+    let mut state = [0; N];
+    state
+        .iter_mut()
+        .zip(window)
+        .for_each(|(s, &y)| *s = untemper(y));
+    Some(Mt19937::from_state(state))
+}
+
+/// Find the seed among the `candidates` (e.g. timestamps around the service
+/// start) which produces the given first output.
+pub fn crack_mt_seed<I: IntoIterator<Item = u32>>(first: u32, candidates: I) -> Option<u32> {
+    candidates
+        .into_iter()
+        .find(|&seed| Mt19937::new(seed).next_u32() == first)
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn inv_mod(a: i128, m: i128) -> Option<i128> {
+    let (mut r0, mut r1, mut s0, mut s1) = (a.rem_euclid(m), m, 1_i128, 0_i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (s0, s1) = (s1, s0 - q * s1);
+    }
+    (r0 == 1).then(|| s0.rem_euclid(m))
+}
+
+// Solve `a` and `c` for a known modulus `m` and check them on all the outputs.
+fn solve(xs: &[i128], m: i128) -> Option<Lcg> {
+    let a = xs.windows(3).find_map(|w| {
+        let inv = inv_mod(w[1] - w[0], m)?;
+        Some((w[2] - w[1]).rem_euclid(m) * inv % m)
+    })?;
+    let c = (xs[1] - a * xs[0]).rem_euclid(m);
+    let ok = xs.windows(2).all(|w| (a * w[0] + c).rem_euclid(m) == w[1]);
+    ok.then(|| Lcg {
+        a: a as u64,
+        c: c as u64,
+        m: m as u64,
+        state: *xs.last().unwrap() as u64,
+    })
+}
+
+/// Recover the parameters of an LCG with modulus up to `2^63` from its
+/// outputs.
+///
+/// With `t_i = x_{i+1} - x_i` we have `t_{i+1} = a·t_i mod m`, then
+/// `t_{i+2}·t_i - t_{i+1}²` is a multiple of `m`. The gcd of a few of them is
+/// `m` with good probability, and `a` and `c` follow by linear algebra.
+/// About ten outputs are usually enough.
+///
+/// The returned generator continues the sequence after the last output.
+pub fn crack_lcg(outputs: &[u64]) -> Option<Lcg> {
+    let xs: Vec<i128> = outputs.iter().map(|&x| x as i128).collect();
+    let t: Vec<i128> = xs.windows(2).map(|w| w[1] - w[0]).collect();
+    let mut m = t.windows(3).map(|w| w[2] * w[0] - w[1] * w[1]).fold(0, gcd);
+    let max = *xs.iter().max()?;
+    // Drop the small spurious factors of the gcd
+    let mut d = 2;
+    while m > max && d < 1000 {
+        if let Some(lcg) = solve(&xs, m) {
+            return Some(lcg);
+        }
+        if m % d == 0 && m / d > max {
+            m /= d;
+        } else {
+            d += 1;
+        }
+    }
+    None
+}
+
+/// Recover the `java.util.Random` state from two consecutive `nextInt()`.
+///
+/// Each output is the high 32 bits of the 48 bits state, the 16 missing bits
+/// are brute forced. The returned generator continues the sequence.
+pub fn crack_java(first: i32, second: i32) -> Option<JavaRandom> {
+    let high = (first as u32 as u64) << 16;
+    (0..1 << 16).find_map(|low| {
+        let mut rng = JavaRandom { state: high | low };
+        (rng.next_int() == second).then_some(rng)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mt_clone_predicts() {
+        let mut mt = Mt19937::new(0xc0ffee);
+        // Unaligned window
+        let outputs: Vec<u32> = (0..1000).map(|_| mt.next_u32()).collect();
+        let mut clone = clone_mt(&outputs).unwrap();
+        for _ in 0..2000 {
+            assert_eq!(clone.next_u32(), mt.next_u32());
+        }
+        assert!(clone_mt(&outputs[..N - 1]).is_none());
+    }
+
+    #[test]
+    fn mt_seed() {
+        let first = Mt19937::new(1_700_000_123).next_u32();
+        let seed = crack_mt_seed(first, 1_700_000_000..1_700_001_000);
+        assert_eq!(seed, Some(1_700_000_123));
+    }
+
+    #[test]
+    fn lcg_parameters() {
+        let mut glibc = Lcg::glibc(31337);
+        let outputs: Vec<_> = (0..12).map(|_| glibc.next_u64()).collect();
+        let mut clone = crack_lcg(&outputs).unwrap();
+        assert_eq!(clone, glibc);
+        assert_eq!(clone.next_u64(), glibc.next_u64());
+
+        let mut lcg = Lcg {
+            a: 6364136223846793005,
+            c: 1442695040888963407,
+            m: 9223372036854775783,
+            state: 42,
+        };
+        let outputs: Vec<_> = (0..8).map(|_| lcg.next_u64()).collect();
+        assert_eq!(crack_lcg(&outputs), Some(lcg));
+    }
+
+    #[test]
+    fn java_random() {
+        let mut rng = JavaRandom::new(0xdecaf);
+        let (a, b) = (rng.next_int(), rng.next_int());
+        let mut clone = crack_java(a, b).unwrap();
+        for _ in 0..10 {
+            assert_eq!(clone.next_int(), rng.next_int());
+        }
+    }
+}
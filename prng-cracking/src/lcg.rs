@@ -0,0 +1,70 @@
+//! Linear congruential generators `x' = a·x + c mod m`.
+
+/// LCG with modulus up to `2^63`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lcg {
+    pub a: u64,
+    pub c: u64,
+    pub m: u64,
+    pub state: u64,
+}
+
+impl Lcg {
+    /// Parameters of the glibc `rand` TYPE_0 generator.
+    pub fn glibc(seed: u64) -> Self {
+        Lcg {
+            a: 1103515245,
+            c: 12345,
+            m: 1 << 31,
+            state: seed,
+        }
+    }
+
+    /// Next state, which is also the output.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state =
+            ((self.a as u128 * self.state as u128 + self.c as u128) % self.m as u128) as u64;
+        self.state
+    }
+}
+
+/// `java.util.Random`: 48 bit LCG returning the high bits of the state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JavaRandom {
+    pub state: u64,
+}
+
+impl JavaRandom {
+    pub const A: u64 = 0x5deece66d;
+    pub const C: u64 = 0xb;
+    pub const MASK: u64 = (1 << 48) - 1;
+
+    pub fn new(seed: u64) -> Self {
+        JavaRandom {
+            state: (seed ^ Self::A) & Self::MASK,
+        }
+    }
+
+    fn next(&mut self, bits: u32) -> u32 {
+        self.state = (self.state.wrapping_mul(Self::A).wrapping_add(Self::C)) & Self::MASK;
+        (self.state >> (48 - bits)) as u32
+    }
+
+    /// `nextInt()`.
+    pub fn next_int(&mut self) -> i32 {
+        self.next(32) as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn java_reference_outputs() {
+        // new Random(42).nextInt()
+        let mut rng = JavaRandom::new(42);
+        assert_eq!(rng.next_int(), -1170105035);
+        assert_eq!(rng.next_int(), 234785527);
+    }
+}
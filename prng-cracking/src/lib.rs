@@ -0,0 +1,18 @@
+//! Non cryptographic PRNGs state recovery.
+//!
+//! General purpose generators are designed for statistical quality and speed,
+//! not for unpredictability. Their outputs are a simple function of the
+//! internal state, which can be recovered from a few observed values. After
+//! that all the future (and past) "random" values are known: session tokens,
+//! password reset codes, shuffled decks, ...
+//!
+//! - [`mt`]: MT19937, the default generator of Python, Ruby, PHP and C++
+//!   `std::mt19937`. The output tempering is invertible, 624 consecutive
+//!   outputs are the whole state.
+//! - [`lcg`]: linear congruential generators, like C `rand` and
+//!   `java.util.Random`. Unknown parameters are solved from a handful of
+//!   outputs, truncated outputs are completed by brute force.
+
+pub mod attacks;
+pub mod lcg;
+pub mod mt;
@@ -0,0 +1,65 @@
+use prng_cracking::{
+    attacks::{clone_mt, crack_java, crack_lcg, crack_mt_seed},
+    lcg::{JavaRandom, Lcg},
+    mt::{Mt19937, N},
+};
+use rand::{rngs::OsRng, Rng};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Password reset tokens service, seeded with the boot time.
+struct TokenService {
+    rng: Mt19937,
+}
+
+impl TokenService {
+    fn token(&mut self) -> String {
+        format!("{:08x}{:08x}", self.rng.next_u32(), self.rng.next_u32())
+    }
+}
+
+fn main() {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as u32;
+    let boot = now - OsRng.gen_range(0..86400);
+    let mut service = TokenService {
+        rng: Mt19937::new(boot),
+    };
+
+    // The attacker requests tokens for accounts under their control
+    let tokens: Vec<String> = (0..N / 2).map(|_| service.token()).collect();
+    let outputs: Vec<u32> = tokens
+        .iter()
+        .flat_map(|t| [&t[..8], &t[8..]])
+        .map(|h| u32::from_str_radix(h, 16).unwrap())
+        .collect();
+    let seed = crack_mt_seed(outputs[0], (now - 86400..=now).rev());
+    println!("MT19937 seed from the first token (last 24h): {seed:?}, actual {boot}");
+    let mut clone = TokenService {
+        rng: clone_mt(&outputs).unwrap(),
+    };
+    println!("MT19937 cloned from {} tokens", tokens.len());
+    println!("  victim token:    {}", service.token());
+    println!("  predicted token: {}", clone.token());
+
+    let mut glibc = Lcg::glibc(OsRng.gen_range(0..1 << 31));
+    let outputs: Vec<u64> = (0..10).map(|_| glibc.next_u64()).collect();
+    let mut clone = crack_lcg(&outputs).unwrap();
+    println!(
+        "LCG from {} outputs: a = {}, c = {}, m = {}",
+        outputs.len(),
+        clone.a,
+        clone.c,
+        clone.m
+    );
+    println!("  next: {:?}", [glibc.next_u64(), glibc.next_u64()]);
+    println!("  predicted: {:?}", [clone.next_u64(), clone.next_u64()]);
+
+    let mut java = JavaRandom::new(OsRng.gen());
+    let (a, b) = (java.next_int(), java.next_int());
+    let mut clone = crack_java(a, b).unwrap();
+    println!("java.util.Random from nextInt() = {a}, {b}");
+    println!("  next: {:?}", [java.next_int(), java.next_int()]);
+    println!("  predicted: {:?}", [clone.next_int(), clone.next_int()]);
+}
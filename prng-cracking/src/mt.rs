@@ -0,0 +1,109 @@
+//! MT19937 Mersenne Twister.
+
+/// State size in words.
+pub const N: usize = 624;
+const M: usize = 397;
+const MATRIX_A: u32 = 0x9908b0df;
+const UPPER_MASK: u32 = 0x80000000;
+const LOWER_MASK: u32 = 0x7fffffff;
+
+/// MT19937 32 bit generator.
+#[derive(Debug, Clone)]
+pub struct Mt19937 {
+    state: [u32; N],
+    index: usize,
+}
+
+impl Mt19937 {
+    pub fn new(seed: u32) -> Self {
+        let mut state = [0; N];
+        state[0] = seed;
+        for i in 1..N {
+            let prev = state[i - 1];
+            state[i] = 1812433253_u32
+                .wrapping_mul(prev ^ (prev >> 30))
+                .wrapping_add(i as u32);
+        }
+        Mt19937 { state, index: N }
+    }
+
+    /// Generator with the given state, the next output is computed after a
+    /// twist.
+    pub fn from_state(state: [u32; N]) -> Self {
+        Mt19937 { state, index: N }
+    }
+
+    fn twist(&mut self) {
+        for i in 0..N {
+            let y = (self.state[i] & UPPER_MASK) | (self.state[(i + 1) % N] & LOWER_MASK);
+            let mag = if y & 1 == 1 { MATRIX_A } else { 0 };
+            self.state[i] = self.state[(i + M) % N] ^ (y >> 1) ^ mag;
+        }
+        self.index = 0;
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        if self.index == N {
+            self.twist();
+        }
+        let y = self.state[self.index];
+        self.index += 1;
+        temper(y)
+    }
+}
+
+/// Output tempering.
+pub fn temper(mut y: u32) -> u32 {
+    y ^= y >> 11;
+    y ^= (y << 7) & 0x9d2c5680;
+    y ^= (y << 15) & 0xefc60000;
+    y ^ (y >> 18)
+}
+
+// Invert `y = x ^ (x >> shift)`, the high bits of `x` are the ones of `y` and
+// each iteration fixes `shift` more bits.
+fn undo_right(y: u32, shift: u32) -> u32 {
+    let mut x = y;
+    for _ in 0..32 / shift {
+        x = y ^ (x >> shift);
+    }
+    x
+}
+
+// Invert `y = x ^ ((x << shift) & mask)`, from the low bits.
+fn undo_left(y: u32, shift: u32, mask: u32) -> u32 {
+    let mut x = y;
+    for _ in 0..32 / shift {
+        x = y ^ ((x << shift) & mask);
+    }
+    x
+}
+
+/// Inverse of [`temper`].
+pub fn untemper(y: u32) -> u32 {
+    let y = undo_right(y, 18);
+    let y = undo_left(y, 15, 0xefc60000);
+    let y = undo_left(y, 7, 0x9d2c5680);
+    undo_right(y, 11)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reference_outputs() {
+        // First outputs of the reference implementation with the default seed
+        let mut mt = Mt19937::new(5489);
+        assert_eq!(mt.next_u32(), 3499211612);
+        assert_eq!(mt.next_u32(), 581869302);
+        assert_eq!((0..9998).map(|_| mt.next_u32()).last(), Some(4123659995));
+    }
+
+    #[test]
+    fn untemper_inverts_temper() {
+        for x in [0, 1, 0xdeadbeef, u32::MAX, 0x80000000, 12345678] {
+            assert_eq!(untemper(temper(x)), x);
+        }
+    }
+}
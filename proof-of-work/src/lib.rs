@@ -0,0 +1,272 @@
+//! Hashcash proof of work.
+//!
+//! A stamp for a challenge is a nonce such that `SHA256(challenge || nonce)`
+//! has `bits` leading zero bits. Every attempt succeeds with probability
+//! `2^-bits`, independently of the others, thus the number of attempts is
+//! geometric with mean `2^bits` and standard deviation about the same.
+//!
+//! Independence makes the puzzle *progress free*: past work doesn't bring a
+//! solver any closer to the solution. In a race (e.g. block mining) everyone
+//! wins with probability proportional to its hash power, even the slowest
+//! participants.
+//!
+//! Splitting the puzzle into `n` sub-puzzles, each `log2(n)` bits easier, keeps
+//! the expected work but sums `n` independent solving times. The total
+//! concentrates around the mean (the deviation shrinks by `√n`), the solver
+//! makes progress with each solved part and the fastest miner wins nearly
+//! every race. Mining becomes winner takes all.
+
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use std::time::Instant;
+
+pub use group_op_timing_attack::stats;
+
+/// Puzzle design.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Design {
+    /// Single puzzle of the full difficulty.
+    ProgressFree,
+    /// The given number (a power of two) of easier sub-puzzles.
+    Parts(u32),
+}
+
+fn zeros(challenge: &[u8], part: u32, nonce: u64) -> u32 {
+    let hash = Sha256::new()
+        .chain_update(challenge)
+        .chain_update(part.to_be_bytes())
+        .chain_update(nonce.to_be_bytes())
+        .finalize();
+    let mut zeros = 0;
+    for b in hash {
+        zeros += b.leading_zeros();
+        if b != 0 {
+            break;
+        }
+    }
+    zeros
+}
+
+/// Hashcash puzzle. The stamp is the list of the sub-puzzles nonces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    pub challenge: Vec<u8>,
+    pub bits: u32,
+    pub design: Design,
+}
+
+impl Puzzle {
+    pub fn new(challenge: &[u8], bits: u32, design: Design) -> Self {
+        if let Design::Parts(n) = design {
+            assert!(n.is_power_of_two() && n.trailing_zeros() <= bits);
+        }
+        Puzzle {
+            challenge: challenge.to_vec(),
+            bits,
+            design,
+        }
+    }
+
+    pub fn parts(&self) -> u32 {
+        match self.design {
+            Design::ProgressFree => 1,
+            Design::Parts(n) => n,
+        }
+    }
+
+    /// Difficulty of each sub-puzzle.
+    pub fn part_bits(&self) -> u32 {
+        self.bits - self.parts().trailing_zeros()
+    }
+
+    pub fn check(&self, part: u32, nonce: u64) -> bool {
+        zeros(&self.challenge, part, nonce) >= self.part_bits()
+    }
+
+    pub fn verify(&self, stamp: &[u64]) -> bool {
+        stamp.len() == self.parts() as usize
+            && (0..)
+                .zip(stamp)
+                .all(|(part, &nonce)| self.check(part, nonce))
+    }
+
+    /// Solve the puzzle, returning the stamp and the number of attempts.
+    pub fn solve(&self) -> (Vec<u64>, u64) {
+        let mut attempts = 0;
+        let stamp = (0..self.parts())
+            .map(|part| {
+                let nonce = (0..).find(|&n| self.check(part, n)).unwrap();
+                attempts += nonce + 1;
+                nonce
+            })
+            .collect();
+        (stamp, attempts)
+    }
+
+    /// Solve the puzzle using all the available cores.
+    pub fn solve_parallel(&self) -> Vec<u64> {
+        (0..self.parts())
+            .map(|part| par_search::find(0..u64::MAX, |&n| self.check(part, n)).unwrap())
+            .collect()
+    }
+}
+
+/// Incremental solver, making a given number of attempts at a time.
+#[derive(Debug, Clone)]
+pub struct Miner {
+    puzzle: Puzzle,
+    stamp: Vec<u64>,
+    nonce: u64,
+}
+
+impl Miner {
+    pub fn new(puzzle: Puzzle) -> Self {
+        Miner {
+            puzzle,
+            stamp: Vec::new(),
+            nonce: 0,
+        }
+    }
+
+    pub fn stamp(&self) -> &[u64] {
+        &self.stamp
+    }
+
+    /// Make up to `attempts` attempts. Returns the number of attempts made if
+    /// the stamp has been completed.
+    pub fn step(&mut self, attempts: u64) -> Option<u64> {
+        for i in 1..=attempts {
+            let part = self.stamp.len() as u32;
+            if self.puzzle.check(part, self.nonce) {
+                self.stamp.push(self.nonce);
+                self.nonce = 0;
+                if self.stamp.len() == self.puzzle.parts() as usize {
+                    return Some(i);
+                }
+            } else {
+                self.nonce += 1;
+            }
+        }
+        None
+    }
+}
+
+/// Run `rounds` races between miners with the given hash `powers`, i.e.
+/// attempts per time unit. Each miner works on its own challenge.
+///
+/// Returns the races won by each miner.
+pub fn race<R: Rng>(
+    rng: &mut R,
+    bits: u32,
+    design: Design,
+    powers: &[u64],
+    rounds: usize,
+) -> Vec<usize> {
+    let mut wins = vec![0; powers.len()];
+    for _ in 0..rounds {
+        let round: [u8; 16] = rng.gen();
+        let mut miners: Vec<_> = (0..powers.len() as u8)
+            .map(|i| Miner::new(Puzzle::new(&[&round[..], &[i]].concat(), bits, design)))
+            .collect();
+        let winner = loop {
+            // Earliest completion within the time unit
+            let done = miners
+                .iter_mut()
+                .zip(powers)
+                .enumerate()
+                .filter_map(|(i, (m, &p))| m.step(p).map(|used| (used as f64 / p as f64, i)))
+                .min_by(|a, b| a.0.total_cmp(&b.0));
+            if let Some((_, i)) = done {
+                break i;
+            }
+        };
+        wins[winner] += 1;
+    }
+    wins
+}
+
+/// Solving attempts and time statistics.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    /// Expected attempts, `2^bits`.
+    pub expected: f64,
+    /// Expected attempts standard deviation, ≈ `2^bits / √parts`.
+    pub expected_std_dev: f64,
+    /// Measured attempts mean.
+    pub mean: f64,
+    /// Measured attempts standard deviation.
+    pub std_dev: f64,
+    /// Measured mean solving time in seconds.
+    pub seconds: f64,
+}
+
+/// Solve `trials` random puzzles.
+pub fn analyze<R: Rng>(rng: &mut R, bits: u32, design: Design, trials: usize) -> Analysis {
+    let mut attempts = Vec::with_capacity(trials);
+    let start = Instant::now();
+    for _ in 0..trials {
+        let puzzle = Puzzle::new(&rng.gen::<[u8; 16]>(), bits, design);
+        attempts.push(puzzle.solve().1 as f64);
+    }
+    let seconds = start.elapsed().as_secs_f64() / trials as f64;
+    let puzzle = Puzzle::new(&[], bits, design);
+    let parts = puzzle.parts() as f64;
+    // Sum of geometric variables with success probability `p`
+    let p = (-(puzzle.part_bits() as f64)).exp2();
+    Analysis {
+        expected: parts / p,
+        expected_std_dev: (parts * (1.0 - p)).sqrt() / p,
+        mean: stats::mean(&attempts),
+        std_dev: stats::variance(&attempts).sqrt(),
+        seconds,
+    }
+}
+
+/// Attempts per second.
+pub fn hash_rate(attempts: u64) -> f64 {
+    let start = Instant::now();
+    let found = (0..attempts).filter(|&n| zeros(b"rate", 0, n) >= 8).count();
+    std::hint::black_box(found);
+    attempts as f64 / start.elapsed().as_secs_f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn solve_and_verify() {
+        for design in [Design::ProgressFree, Design::Parts(4)] {
+            let puzzle = Puzzle::new(b"alice@example.com", 12, design);
+            let (stamp, attempts) = puzzle.solve();
+            assert!(puzzle.verify(&stamp));
+            assert!(attempts >= stamp.len() as u64);
+            assert!(puzzle.verify(&puzzle.solve_parallel()));
+            let mut other = puzzle.clone();
+            other.challenge = b"mallory@example.com".to_vec();
+            assert!(!other.verify(&stamp));
+        }
+    }
+
+    #[test]
+    fn attempts_distribution() {
+        let free = analyze(&mut OsRng, 10, Design::ProgressFree, 300);
+        let parts = analyze(&mut OsRng, 10, Design::Parts(16), 300);
+        for a in [&free, &parts] {
+            assert!((a.mean - a.expected).abs() < a.expected * 0.25, "{a:?}");
+            assert!((a.std_dev - a.expected_std_dev).abs() < a.expected_std_dev * 0.3);
+        }
+        assert!(parts.std_dev < free.std_dev / 2.0);
+    }
+
+    #[test]
+    fn fastest_takes_all() {
+        let powers = [1, 3];
+        let free = race(&mut OsRng, 10, Design::ProgressFree, &powers, 200);
+        let parts = race(&mut OsRng, 10, Design::Parts(32), &powers, 200);
+        // Fair share is 25%
+        assert!((20..=80).contains(&free[0]), "{free:?}");
+        assert!(parts[0] < 10, "{parts:?}");
+    }
+}
@@ -0,0 +1,117 @@
+use clap::{Parser, Subcommand};
+use proof_of_work::{analyze, hash_rate, race, Design, Puzzle};
+use rand::rngs::OsRng;
+use std::time::Instant;
+
+#[derive(Parser)]
+#[command(about = "Hashcash proof of work")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Mint a stamp using all the cores.
+    Solve {
+        /// Stamp resource, e.g. the mail recipient.
+        challenge: String,
+        /// Difficulty in bits.
+        #[arg(short, long, default_value_t = 20)]
+        bits: u32,
+    },
+    /// Compare expected and measured solving effort across difficulties.
+    Analyze {
+        /// Max difficulty in bits.
+        #[arg(short, long, default_value_t = 18)]
+        max_bits: u32,
+        /// Puzzles solved per difficulty.
+        #[arg(short, long, default_value_t = 20)]
+        trials: usize,
+        /// Number of sub-puzzles (1 for the progress free design).
+        #[arg(short, long, default_value_t = 1)]
+        parts: u32,
+    },
+    /// Race miners with different hash power under both designs.
+    Race {
+        /// Difficulty in bits.
+        #[arg(short, long, default_value_t = 12)]
+        bits: u32,
+        /// Miners hash power.
+        #[arg(long, value_delimiter = ',', default_value = "1,2,3,4")]
+        powers: Vec<u64>,
+        /// Number of sub-puzzles of the non progress free design.
+        #[arg(short, long, default_value_t = 64)]
+        parts: u32,
+        #[arg(short, long, default_value_t = 200)]
+        rounds: usize,
+    },
+}
+
+fn design(parts: u32) -> Design {
+    if parts == 1 {
+        Design::ProgressFree
+    } else {
+        Design::Parts(parts)
+    }
+}
+
+fn main() {
+    match Cli::parse().command {
+        Command::Solve { challenge, bits } => {
+            let puzzle = Puzzle::new(challenge.as_bytes(), bits, Design::ProgressFree);
+            let start = Instant::now();
+            let stamp = puzzle.solve_parallel();
+            println!(
+                "1:{bits}:{challenge}:{:x} ({:.2}s)",
+                stamp[0],
+                start.elapsed().as_secs_f64()
+            );
+        }
+        Command::Analyze {
+            max_bits,
+            trials,
+            parts,
+        } => {
+            let rate = hash_rate(1 << 20);
+            println!("Hash rate: {:.0} H/s", rate);
+            println!(
+                "{:>4} {:>12} {:>12} {:>12} {:>12} {:>10} {:>10}",
+                "bits", "E[tries]", "tries", "E[σ]", "σ", "E[time]", "time"
+            );
+            let min_bits = parts.trailing_zeros().max(4);
+            for bits in (min_bits..=max_bits).step_by(2) {
+                let a = analyze(&mut OsRng, bits, design(parts), trials);
+                println!(
+                    "{bits:>4} {:>12.0} {:>12.0} {:>12.0} {:>12.0} {:>9.4}s {:>9.4}s",
+                    a.expected,
+                    a.mean,
+                    a.expected_std_dev,
+                    a.std_dev,
+                    a.expected / rate,
+                    a.seconds
+                );
+            }
+        }
+        Command::Race {
+            bits,
+            powers,
+            parts,
+            rounds,
+        } => {
+            let total: u64 = powers.iter().sum();
+            println!("{rounds} races, difficulty {bits} bits");
+            for design in [Design::ProgressFree, design(parts)] {
+                let wins = race(&mut OsRng, bits, design, &powers, rounds);
+                println!("  {design:?}");
+                for (power, wins) in powers.iter().zip(wins) {
+                    println!(
+                        "    power {power}: {:>5.1}% (fair share {:.1}%)",
+                        100.0 * wins as f64 / rounds as f64,
+                        100.0 * *power as f64 / total as f64
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! Remote timing measurements.
+//!
+//! The timing attacks in this repository (`cbc-padding-oracle`,
+//! `group-op-timing-attack`, `aes-cache-timing`) mostly run against simulated
+//! targets, with well behaved Gaussian noise. Remote targets are harder: every
+//! sample crosses the network stack of two hosts and the leak (often less than a
+//! microsecond) is buried in the jitter. Measurement hygiene is then what makes
+//! the attack work:
+//! - warm-up: the first queries pay for cold caches, lazy initialization, CPU
+//!   frequency ramp-up and are discarded;
+//! - interleaving: the query classes are sampled in rounds, in random order, so
+//!   that any drift of the target (load, thermal throttling...) affects all of
+//!   them the same way;
+//! - filtering: the long tail of the distribution is cut away, the lowest
+//!   percentiles being the most stable statistic (see [`stats`]);
+//! - box test: classes are compared through a percentile interval calibrated on
+//!   known different classes (Crosby–Wallach).
+//!
+//! The cleaned [`Dataset`] can be saved as CSV for the distinguishers.
+
+pub mod stats;
+pub mod target;
+
+use rand::seq::SliceRandom;
+use std::io::{self, Write};
+use target::Target;
+
+/// Samples collection parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Collector {
+    /// Discarded queries per class.
+    pub warmup: usize,
+    /// Sampling rounds, one sample per class each.
+    pub rounds: usize,
+}
+
+impl Default for Collector {
+    fn default() -> Self {
+        Collector {
+            warmup: 100,
+            rounds: 1000,
+        }
+    }
+}
+
+impl Collector {
+    /// Collect the response times of the `classes` requests.
+    pub fn collect<T: Target + ?Sized>(
+        &self,
+        target: &mut T,
+        classes: &[Vec<u8>],
+    ) -> io::Result<Dataset> {
+        let mut rng = rand::thread_rng();
+        let mut order: Vec<_> = (0..classes.len()).collect();
+        for _ in 0..self.warmup {
+            for class in classes {
+                target.query(class)?;
+            }
+        }
+        let mut samples = vec![Vec::with_capacity(self.rounds); classes.len()];
+        for _ in 0..self.rounds {
+            order.shuffle(&mut rng);
+            for &i in &order {
+                samples[i].push(target.query(&classes[i])?);
+            }
+        }
+        Ok(Dataset { samples })
+    }
+}
+
+/// Response times of each class.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dataset {
+    pub samples: Vec<Vec<f64>>,
+}
+
+impl Dataset {
+    /// Sorted samples between the `lo` and `hi` percentiles of each class.
+    pub fn filter(&self, lo: f64, hi: f64) -> Dataset {
+        let samples = self
+            .samples
+            .iter()
+            .map(|xs| stats::filter(xs, lo, hi))
+            .collect();
+        Dataset { samples }
+    }
+
+    /// Save as CSV, one `class,nanoseconds` line per sample.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "class,time")?;
+        for (class, xs) in self.samples.iter().enumerate() {
+            for x in xs {
+                writeln!(writer, "{class},{x}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warmup_and_interleaving() {
+        // Drifting target: each query is slower than the previous one
+        let mut queries = Vec::new();
+        let mut target = |req: &[u8]| {
+            queries.push(req[0]);
+            Ok(queries.len() as f64)
+        };
+        let collector = Collector {
+            warmup: 5,
+            rounds: 500,
+        };
+        let classes = [vec![0], vec![1]];
+        let data = collector.collect(&mut target, &classes).unwrap();
+        assert_eq!(queries.len(), 2 * 505);
+        assert_eq!(data.samples[0].len(), 500);
+        assert!(data.samples[0].iter().all(|x| *x > 10.0));
+
+        // The drift is evenly split between the classes
+        let sum = |xs: &[f64]| xs.iter().sum::<f64>();
+        let diff = sum(&data.samples[0]) - sum(&data.samples[1]);
+        assert!(diff.abs() < 500.0 * 10.0);
+
+        let mut csv = Vec::new();
+        data.filter(0.0, 10.0).write_csv(&mut csv).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap().lines().count(), 1 + 2 * 50);
+    }
+}
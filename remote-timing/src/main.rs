@@ -0,0 +1,137 @@
+use clap::Parser;
+use rand::Rng;
+use remote_timing::{stats, target::Http, Collector, Dataset};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    time::{Duration, Instant},
+};
+
+const SECRET: &[u8] = b"s3cr3t-token";
+
+#[derive(Parser, Debug)]
+#[command(about = "Remote timing measurements with jitter filtering")]
+struct Args {
+    /// Target address, a local leaky server is spawned if missing.
+    #[arg(long)]
+    addr: Option<SocketAddr>,
+    /// Target HTTP path.
+    #[arg(long, default_value = "/login")]
+    path: String,
+    /// Calibration classes, the first one is known to be the fastest.
+    #[arg(long, num_args = 2, default_values = ["xxxxxxxxxxxx", "s3cr3t-token"])]
+    calibrate: Vec<String>,
+    /// Classes to compare.
+    #[arg(long, num_args = 2, default_values = ["xxxxxxxxxxxx", "s3xxxxxxxxxx"])]
+    compare: Vec<String>,
+    /// Discarded queries per class.
+    #[arg(long, default_value_t = 100)]
+    warmup: usize,
+    /// Sampling rounds.
+    #[arg(long, default_value_t = 2000)]
+    rounds: usize,
+    /// Save the compared classes samples as CSV.
+    #[arg(long)]
+    output: Option<String>,
+}
+
+// Compare the request body with the secret, byte by byte with early exit.
+// Every matching byte costs a couple of microseconds and a few responses are
+// delayed by a millisecond to emulate a busy host.
+fn serve(mut stream: TcpStream) {
+    let mut reader = BufReader::new(&mut stream);
+    let mut len = 0;
+    let mut line = String::new();
+    while reader.read_line(&mut line).unwrap_or(0) > 2 {
+        if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+            len = value.trim().parse().unwrap_or(0);
+        }
+        line.clear();
+    }
+    let mut body = vec![0; len];
+    if reader.read_exact(&mut body).is_err() {
+        return;
+    }
+
+    let mut ok = body.len() == SECRET.len();
+    for (a, b) in body.iter().zip(SECRET) {
+        if a != b {
+            ok = false;
+            break;
+        }
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_micros(2) {}
+    }
+    if rand::thread_rng().gen_bool(0.05) {
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    let status = if ok { "200 OK" } else { "403 Forbidden" };
+    let _ = write!(stream, "HTTP/1.1 {status}\r\nContent-Length: 0\r\n\r\n");
+}
+
+fn spawn_server() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || listener.incoming().flatten().for_each(serve));
+    addr
+}
+
+fn summary(name: &str, data: &Dataset) {
+    for (class, xs) in data.samples.iter().enumerate() {
+        let mean = xs.iter().sum::<f64>() / xs.len() as f64;
+        let sorted = stats::filter(xs, 0.0, 100.0);
+        println!(
+            "  {name} class {class}: mean {:>9.0} ns, p10 {:>7.0} ns, median {:>7.0} ns",
+            mean,
+            stats::percentile(&sorted, 10.0),
+            stats::percentile(&sorted, 50.0),
+        );
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+    let addr = args.addr.unwrap_or_else(|| {
+        let addr = spawn_server();
+        println!("Leaky server listening on {addr}");
+        addr
+    });
+    let mut target = Http {
+        addr,
+        host: addr.to_string(),
+        path: args.path,
+    };
+    let collector = Collector {
+        warmup: args.warmup,
+        rounds: args.rounds,
+    };
+    let classes = |v: &[String]| v.iter().map(|s| s.as_bytes().to_vec()).collect::<Vec<_>>();
+
+    println!("Calibration: {:?}", args.calibrate);
+    let data = collector
+        .collect(&mut target, &classes(&args.calibrate))
+        .unwrap();
+    summary("calibration", &data);
+    let sorted = data.filter(0.0, 100.0);
+    let Some(bx) = stats::find_box(&sorted.samples[0], &sorted.samples[1], 1.0, 10.0) else {
+        println!("No box distinguishes the calibration classes, try more rounds");
+        return;
+    };
+    println!("  box: percentiles {}-{}", bx.lo, bx.hi);
+
+    println!("Comparison: {:?}", args.compare);
+    let data = collector
+        .collect(&mut target, &classes(&args.compare))
+        .unwrap();
+    summary("comparison", &data);
+    let sorted = data.filter(0.0, 100.0);
+    let verdict = stats::box_test(&sorted.samples[0], &sorted.samples[1], bx);
+    println!("  box test: {verdict:?}");
+
+    if let Some(path) = args.output {
+        let cleaned = data.filter(0.0, bx.hi);
+        cleaned.write_csv(File::create(&path).unwrap()).unwrap();
+        println!("Filtered samples saved to {path}");
+    }
+}
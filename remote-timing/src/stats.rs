@@ -0,0 +1,122 @@
+//! Jitter filtering and box test.
+//!
+//! Network latencies are far from Gaussian: the distribution has a sharp lower
+//! bound (the fixed processing and propagation time) and a very long tail
+//! (scheduling, interrupts, congestion, retransmissions...). The mean is thus
+//! dominated by the tail while the low percentiles are remarkably stable.
+//!
+//! The box test (Crosby, Wallach and Riedi, "Opportunities and limits of remote
+//! timing attacks") compares two sample sets through a percentile interval, the
+//! *box*, of each one: the sets are distinguished only if the boxes don't
+//! overlap. The best box for a target is found on calibration samples whose
+//! classes are known to differ.
+
+use std::cmp::Ordering;
+
+/// Percentile `p` in `[0, 100]` of the `sorted` samples (nearest rank).
+pub fn percentile(sorted: &[f64], p: f64) -> f64 {
+    debug_assert!(sorted.windows(2).all(|w| w[0] <= w[1]));
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Sort the samples and keep the ones between the `lo` and `hi` percentiles.
+pub fn filter(samples: &[f64], lo: f64, hi: f64) -> Vec<f64> {
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let (lo, hi) = (percentile(&sorted, lo), percentile(&sorted, hi));
+    sorted.retain(|x| (lo..=hi).contains(x));
+    sorted
+}
+
+/// Percentile interval.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Box {
+    pub lo: f64,
+    pub hi: f64,
+}
+
+impl Box {
+    /// Values of the box percentiles over the `sorted` samples.
+    pub fn bounds(&self, sorted: &[f64]) -> (f64, f64) {
+        (percentile(sorted, self.lo), percentile(sorted, self.hi))
+    }
+}
+
+/// Box test of two sorted sample sets.
+///
+/// `Less` if the box of `a` is strictly below the box of `b`, `Greater` if
+/// strictly above, `None` if they overlap (undistinguishable).
+pub fn box_test(a: &[f64], b: &[f64], bx: Box) -> Option<Ordering> {
+    let (a, b) = (bx.bounds(a), bx.bounds(b));
+    if a.1 < b.0 {
+        Some(Ordering::Less)
+    } else if b.1 < a.0 {
+        Some(Ordering::Greater)
+    } else {
+        None
+    }
+}
+
+/// Find the box best distinguishing two sorted calibration sets, with `a`
+/// known to be faster than `b`.
+///
+/// Boxes start at the percentiles `0, step, 2·step, ...` below 50 and have
+/// widths `step, 2·step, ...` up to `max_width`. The chosen box separates the
+/// two sets with the largest gap, relative to the boxes widths. `None` if no
+/// box distinguishes the sets.
+pub fn find_box(a: &[f64], b: &[f64], step: f64, max_width: f64) -> Option<Box> {
+    let starts = (0..).map(|i| i as f64 * step).take_while(|lo| *lo < 50.0);
+    starts
+        .flat_map(|lo| {
+            (1..)
+                .map(move |i| i as f64 * step)
+                .take_while(move |w| *w <= max_width)
+                .map(move |w| Box { lo, hi: lo + w })
+        })
+        .filter(|bx| box_test(a, b, *bx) == Some(Ordering::Less))
+        .map(|bx| {
+            let ((a0, a1), (b0, b1)) = (bx.bounds(a), bx.bounds(b));
+            let width = (a1 - a0) + (b1 - b0);
+            (bx, (b0 - a1) / width.max(f64::MIN_POSITIVE))
+        })
+        .max_by(|x, y| x.1.total_cmp(&y.1))
+        .map(|(bx, _)| bx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_and_filter() {
+        let xs: Vec<_> = (1..=100).map(f64::from).collect();
+        assert_eq!(percentile(&xs, 0.0), 1.0);
+        assert_eq!(percentile(&xs, 10.0), 10.0);
+        assert_eq!(percentile(&xs, 100.0), 100.0);
+        assert_eq!(filter(&[5.0, 1e9, 3.0, 4.0], 0.0, 75.0), [3.0, 4.0, 5.0]);
+    }
+
+    #[test]
+    fn box_test_ignores_the_tail() {
+        // Same body, 10% of the samples of `a` have a huge delay
+        let a: Vec<_> = (0..1000)
+            .map(|i| {
+                if i % 10 == 0 {
+                    1e6
+                } else {
+                    100.0 + (i % 7) as f64
+                }
+            })
+            .collect();
+        let b: Vec<_> = (0..1000).map(|i| 110.0 + (i % 7) as f64).collect();
+        let (a, b) = (filter(&a, 0.0, 100.0), filter(&b, 0.0, 100.0));
+        let mean = |xs: &[f64]| xs.iter().sum::<f64>() / xs.len() as f64;
+        assert!(mean(&a) > mean(&b));
+
+        let bx = find_box(&a, &b, 1.0, 10.0).unwrap();
+        assert_eq!(box_test(&a, &b, bx), Some(Ordering::Less));
+        assert_eq!(box_test(&b, &a, bx), Some(Ordering::Greater));
+        assert_eq!(box_test(&a, &a, bx), None);
+    }
+}
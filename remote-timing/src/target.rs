@@ -0,0 +1,116 @@
+//! Network targets.
+//!
+//! A new connection is opened for every query and the connection setup is not
+//! timed: the sample is the time from the request being written to the whole
+//! response being read, i.e. the server closing the connection.
+
+use std::{
+    io::{self, Read, Write},
+    net::{Shutdown, TcpStream, ToSocketAddrs},
+    time::Instant,
+};
+
+/// Remote service whose response time is measured.
+pub trait Target {
+    /// Response time for the `request`, in nanoseconds.
+    fn query(&mut self, request: &[u8]) -> io::Result<f64>;
+}
+
+/// Any closure can be a target, e.g. a simulation.
+impl<F: FnMut(&[u8]) -> io::Result<f64>> Target for F {
+    fn query(&mut self, request: &[u8]) -> io::Result<f64> {
+        self(request)
+    }
+}
+
+/// Raw TCP service.
+///
+/// The request is sent as is, followed by the write half shutdown.
+pub struct Tcp<A> {
+    pub addr: A,
+}
+
+impl<A: ToSocketAddrs> Target for Tcp<A> {
+    fn query(&mut self, request: &[u8]) -> io::Result<f64> {
+        let mut stream = TcpStream::connect(&self.addr)?;
+        stream.set_nodelay(true)?;
+        let start = Instant::now();
+        stream.write_all(request)?;
+        stream.shutdown(Shutdown::Write)?;
+        stream.read_to_end(&mut Vec::new())?;
+        Ok(start.elapsed().as_nanos() as f64)
+    }
+}
+
+/// HTTP/1.1 service.
+///
+/// The request is sent as the body of a `POST` to `path`.
+pub struct Http<A> {
+    pub addr: A,
+    pub host: String,
+    pub path: String,
+}
+
+impl<A> Http<A> {
+    /// Raw HTTP request.
+    pub fn request(&self, body: &[u8]) -> Vec<u8> {
+        let mut request = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            self.path,
+            self.host,
+            body.len()
+        )
+        .into_bytes();
+        request.extend_from_slice(body);
+        request
+    }
+}
+
+impl<A: ToSocketAddrs> Target for Http<A> {
+    fn query(&mut self, body: &[u8]) -> io::Result<f64> {
+        // Built upfront to not time the formatting
+        let request = self.request(body);
+        let mut stream = TcpStream::connect(&self.addr)?;
+        stream.set_nodelay(true)?;
+        let start = Instant::now();
+        stream.write_all(&request)?;
+        stream.read_to_end(&mut Vec::new())?;
+        Ok(start.elapsed().as_nanos() as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn tcp_and_http_round_trip() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for stream in listener.incoming().take(2) {
+                let mut stream = stream.unwrap();
+                let mut buf = vec![0; 4096];
+                let n = stream.read(&mut buf).unwrap();
+                requests.push(buf[..n].to_vec());
+                stream.write_all(b"done").unwrap();
+            }
+            requests
+        });
+
+        assert!(Tcp { addr }.query(b"ping").unwrap() > 0.0);
+        let mut http = Http {
+            addr,
+            host: "localhost".into(),
+            path: "/login".into(),
+        };
+        assert!(http.query(b"pong").unwrap() > 0.0);
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests[0], b"ping");
+        assert_eq!(requests[1], http.request(b"pong"));
+        assert!(requests[1].starts_with(b"POST /login HTTP/1.1\r\n"));
+    }
+}
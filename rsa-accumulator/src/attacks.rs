@@ -0,0 +1,94 @@
+//! Membership witness forgery.
+//!
+//! None of the attacks below needs the modulus factorization: they only exploit
+//! the fact that a divisor `d` of the accumulated product `P` has the trivial
+//! "`d`-th root" `g^(P/d)` of the accumulator value.
+
+use crate::Accumulator;
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::{One, Zero};
+
+/// Forge a witness for the representative `target` using the public set.
+///
+/// Succeeds whenever `target` divides the product of the accumulated
+/// representatives, which includes `1`, any factor of a composite member and
+/// any product of members.
+pub fn forge_witness(acc: &Accumulator, target: &BigUint) -> Option<BigUint> {
+    if target.is_zero() {
+        return None;
+    }
+    let product = acc
+        .elements()
+        .iter()
+        .fold(BigUint::one(), |prod, e| prod * e);
+    let (quotient, rem) = product.div_rem(target);
+    rem.is_zero()
+        .then(|| acc.generator().modpow(&quotient, acc.modulus()))
+}
+
+/// Forge a witness for a `divisor` of the representative `x` of a member.
+///
+/// Requires only the legitimate `witness` of `x`, not the set:
+/// `(w^(x/d))^d = w^x = A`.
+pub fn split_witness(
+    acc: &Accumulator,
+    x: &BigUint,
+    witness: &BigUint,
+    divisor: &BigUint,
+) -> Option<BigUint> {
+    if divisor.is_zero() {
+        return None;
+    }
+    let (quotient, rem) = x.div_rem(divisor);
+    rem.is_zero()
+        .then(|| witness.modpow(&quotient, acc.modulus()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Domain;
+
+    fn id(n: u64) -> [u8; 8] {
+        n.to_be_bytes()
+    }
+
+    fn accumulator(domain: Domain) -> Accumulator {
+        let mut acc = Accumulator::new(512, domain);
+        [15, 77, 101].iter().for_each(|&n| acc.add(&id(n)));
+        acc
+    }
+
+    #[test]
+    fn raw_domain_is_forgeable() {
+        let acc = accumulator(Domain::Raw);
+        // 1, factors of a member and products of members
+        for target in [1, 3, 5, 7, 11, 15 * 77, 7 * 101] {
+            assert!(acc.witness(&id(target)).is_none());
+            let w = forge_witness(&acc, &BigUint::from(target)).unwrap();
+            assert!(acc.verify(&id(target), &w));
+        }
+        assert!(forge_witness(&acc, &BigUint::from(2_u8)).is_none());
+
+        // Only the legitimate witness of 77 at hand
+        let x = BigUint::from(77_u8);
+        let w = acc.witness(&id(77)).unwrap();
+        let forged = split_witness(&acc, &x, &w, &BigUint::from(11_u8)).unwrap();
+        assert!(acc.verify(&id(11), &forged));
+    }
+
+    #[test]
+    fn hash_to_prime_domain_resists() {
+        let acc = accumulator(Domain::HashToPrime);
+        for target in [1, 3, 5, 7, 11, 15 * 77] {
+            let x = acc.domain().map(&id(target));
+            assert!(forge_witness(&acc, &x).is_none());
+        }
+        // Splitting a prime only yields the trivial divisors.
+        let x = acc.domain().map(&id(77));
+        let w = acc.witness(&id(77)).unwrap();
+        let forged = split_witness(&acc, &x, &w, &BigUint::one()).unwrap();
+        assert!(!acc.verify(&id(1), &forged));
+    }
+}
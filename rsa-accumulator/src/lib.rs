@@ -0,0 +1,204 @@
+//! RSA accumulator.
+//!
+//! An accumulator compresses a set `{x₁, …, xₖ}` into a single group element
+//!
+//! ```text
+//! A = g^(x₁·…·xₖ) mod N
+//! ```
+//!
+//! where `N` is an RSA modulus whose factorization has been discarded after a
+//! trusted setup. The membership witness of `xᵢ` is the accumulator of all the
+//! other elements, `wᵢ = g^(∏_{j≠i} xⱼ)`, and it is checked via `wᵢ^xᵢ = A`.
+//!
+//! Without the order of the group, computing `A^(1/y)` for an arbitrary `y` is
+//! the strong RSA problem. The security argument however only holds if the
+//! accumulated values are *primes* and the verifier only accepts primes that
+//! could not have been picked to divide the accumulated product. When elements
+//! are accumulated as plain integers, anyone knowing the set (or just holding
+//! a witness for a composite member) can prove the membership of any divisor
+//! of the product: `1`, a factor of a member, the product of a few members...
+//!
+//! The fix is to map every element to a prime via a hash function
+//! ([`hash_to_prime`]), so that a non-member representative divides the product
+//! only with negligible probability.
+
+use num_bigint::{BigUint, RandBigInt};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+use sha2::{Digest, Sha256};
+
+pub mod attacks;
+
+/// Bit length of the primes produced by [`hash_to_prime`].
+pub const PRIME_BITS: usize = 256;
+
+/// Map `data` to a [`PRIME_BITS`] prime.
+///
+/// Candidates are `SHA-256(counter || data)` with top and bottom bits set,
+/// the first prime for increasing counter values is returned.
+pub fn hash_to_prime(data: &[u8]) -> BigUint {
+    (0_u32..)
+        .map(|counter| {
+            let digest = Sha256::new()
+                .chain_update(counter.to_be_bytes())
+                .chain_update(data)
+                .finalize();
+            let mut candidate = BigUint::from_bytes_be(&digest);
+            candidate.set_bit(PRIME_BITS as u64 - 1, true);
+            candidate.set_bit(0, true);
+            candidate
+        })
+        .find(miller_rabin::is_prime)
+        .expect("Prime not found")
+}
+
+/// How elements are mapped to the accumulated integers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Domain {
+    /// Element bytes taken as a big endian integer (insecure).
+    Raw,
+    /// Element hashed to a prime.
+    HashToPrime,
+}
+
+impl Domain {
+    /// Integer representative of `data`.
+    pub fn map(&self, data: &[u8]) -> BigUint {
+        match self {
+            Domain::Raw => BigUint::from_bytes_be(data),
+            Domain::HashToPrime => hash_to_prime(data),
+        }
+    }
+}
+
+/// RSA accumulator.
+#[derive(Debug, Clone)]
+pub struct Accumulator {
+    n: BigUint,
+    g: BigUint,
+    domain: Domain,
+    elements: Vec<BigUint>,
+    value: BigUint,
+}
+
+impl Accumulator {
+    /// Trusted setup of an empty accumulator with a `bits` modulus.
+    ///
+    /// The modulus factors are thrown away and the generator is a random
+    /// quadratic residue.
+    pub fn new(bits: usize, domain: Domain) -> Self {
+        let gen_prime = || loop {
+            let p = miller_rabin::prime_num(bits / 2, None).expect("Prime not found");
+            if p.bits() as usize == bits / 2 {
+                return p;
+            }
+        };
+        let n = loop {
+            let (p, q) = (gen_prime(), gen_prime());
+            if p != q {
+                break p * q;
+            }
+        };
+        let g = loop {
+            let r = rand::thread_rng().gen_biguint_below(&n);
+            if r > BigUint::one() && r.gcd(&n).is_one() {
+                break r.modpow(&BigUint::from(2_u8), &n);
+            }
+        };
+        Self::with_params(n, g, domain)
+    }
+
+    /// Empty accumulator with the given public parameters.
+    pub fn with_params(n: BigUint, g: BigUint, domain: Domain) -> Self {
+        Accumulator {
+            value: g.clone(),
+            n,
+            g,
+            domain,
+            elements: Vec::new(),
+        }
+    }
+
+    /// RSA modulus.
+    pub fn modulus(&self) -> &BigUint {
+        &self.n
+    }
+
+    /// Generator, i.e. the value of the empty accumulator.
+    pub fn generator(&self) -> &BigUint {
+        &self.g
+    }
+
+    /// Elements domain.
+    pub fn domain(&self) -> Domain {
+        self.domain
+    }
+
+    /// Current accumulator value.
+    pub fn value(&self) -> &BigUint {
+        &self.value
+    }
+
+    /// Representatives of the accumulated elements.
+    pub fn elements(&self) -> &[BigUint] {
+        &self.elements
+    }
+
+    /// Add `data` to the set.
+    pub fn add(&mut self, data: &[u8]) {
+        let x = self.domain.map(data);
+        self.value = self.value.modpow(&x, &self.n);
+        self.elements.push(x);
+    }
+
+    /// Membership witness of `data`.
+    ///
+    /// Returns `None` if `data` has not been accumulated.
+    pub fn witness(&self, data: &[u8]) -> Option<BigUint> {
+        let x = self.domain.map(data);
+        let i = self.elements.iter().position(|e| e == &x)?;
+        let w = self
+            .elements
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .fold(self.g.clone(), |w, (_, e)| w.modpow(e, &self.n));
+        Some(w)
+    }
+
+    /// Check the membership `witness` of `data`.
+    pub fn verify(&self, data: &[u8], witness: &BigUint) -> bool {
+        let x = self.domain.map(data);
+        !x.is_zero() && witness.modpow(&x, &self.n) == self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_to_prime_is_deterministic_prime() {
+        let p = hash_to_prime(b"alice");
+        assert_eq!(p.bits() as usize, PRIME_BITS);
+        assert!(miller_rabin::is_prime(&p));
+        assert_eq!(p, hash_to_prime(b"alice"));
+        assert_ne!(p, hash_to_prime(b"bob"));
+    }
+
+    #[test]
+    fn members_verify() {
+        for domain in [Domain::Raw, Domain::HashToPrime] {
+            let mut acc = Accumulator::new(512, domain);
+            let members: [&[u8]; 3] = [b"alice", b"bob", b"carol"];
+            members.iter().for_each(|m| acc.add(m));
+            for m in members {
+                let w = acc.witness(m).unwrap();
+                assert!(acc.verify(m, &w));
+            }
+            assert!(acc.witness(b"mallory").is_none());
+            let w = acc.witness(b"alice").unwrap();
+            assert!(!acc.verify(b"bob", &w));
+        }
+    }
+}
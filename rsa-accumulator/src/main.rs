@@ -0,0 +1,44 @@
+use num_bigint::BigUint;
+use rsa_accumulator::{
+    attacks::{forge_witness, split_witness},
+    Accumulator, Domain,
+};
+
+const BITS: usize = 1024;
+
+fn id(n: u64) -> [u8; 8] {
+    n.to_be_bytes()
+}
+
+fn main() {
+    // Allow-list of user identifiers
+    let users = [15, 77, 1009, 4096];
+    let outsiders = [1, 3, 7, 11, 2048, 15 * 77];
+
+    for domain in [Domain::Raw, Domain::HashToPrime] {
+        println!("Domain: {domain:?}");
+        let mut acc = Accumulator::new(BITS, domain);
+        users.iter().for_each(|&u| acc.add(&id(u)));
+        println!("Accumulated users: {users:?}");
+
+        // Forgery knowing the public set
+        for target in outsiders {
+            let x = domain.map(&id(target));
+            let result = match forge_witness(&acc, &x) {
+                Some(w) if acc.verify(&id(target), &w) => "forged",
+                _ => "failed",
+            };
+            println!("  witness for non-member {target}: {result}");
+        }
+
+        // Forgery by user 77, knowing only its own witness
+        let x = domain.map(&id(77));
+        let w = acc.witness(&id(77)).unwrap();
+        let forged =
+            split_witness(&acc, &x, &w, &BigUint::from(7_u8)).filter(|w| acc.verify(&id(7), w));
+        println!(
+            "  user 77 lends membership to 7: {}",
+            if forged.is_some() { "forged" } else { "failed" }
+        );
+    }
+}
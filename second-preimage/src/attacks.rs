@@ -0,0 +1,198 @@
+//! Long message second preimage (Kelsey–Schneier).
+//!
+//! A target message of 2^L blocks goes through 2^L chaining values: a single
+//! block mapping to any of them (the *linking* block) costs 2^(n-L), and the
+//! target tail after that point can be appended as is. The padding, however,
+//! includes the length, thus the prefix leading to the linking block must be
+//! exactly as long as the target one.
+//!
+//! An *expandable message* provides a prefix of any length in `k..k+2^k`
+//! blocks ending in the same chaining value. It is a sequence of `k`
+//! collisions, the `i`-th between a single block and a `2^i + 1` blocks
+//! message (a filler of 2^i blocks plus a colliding one) from the same
+//! chaining value: choosing the long or the short message at every step
+//! selects the length bit by bit. Each collision is a birthday search, for a
+//! total of ≈ `k·2^(n/2+1) + 2^k` compressions.
+
+use crate::{Md, BLOCK};
+use digest::Digest;
+use std::collections::HashMap;
+
+// Filler of the long messages
+const FILLER: [u8; BLOCK] = [0xff; BLOCK];
+
+/// Expandable message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expandable {
+    /// Single and `2^i + 1` blocks messages of each step.
+    pub pairs: Vec<(Vec<u8>, Vec<u8>)>,
+    /// Final chaining value.
+    pub end: u64,
+    /// Compressions spent.
+    pub work: u64,
+}
+
+impl Expandable {
+    /// Expandable message with `k` steps, from the IV.
+    pub fn build<D: Digest>(md: &Md<D>, k: u32) -> Self {
+        let (mut h, mut work) = (0, 0);
+        let mut pairs = Vec::new();
+        for i in 0..k {
+            let filler = FILLER.repeat(1 << i);
+            let long = md.chain(h, &filler);
+            let (x, y, next, count) = cross_collision(md, h, long, i as u8);
+            work += (1 << i) + count;
+            pairs.push((x.to_vec(), [filler, y.to_vec()].concat()));
+            h = next;
+        }
+        Expandable {
+            pairs,
+            end: h,
+            work,
+        }
+    }
+
+    /// Shortest message length, in blocks.
+    pub fn min_blocks(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Longest message length, in blocks.
+    pub fn max_blocks(&self) -> usize {
+        self.pairs.len() + (1 << self.pairs.len()) - 1
+    }
+
+    /// Message of `blocks` blocks, in `min_blocks()..=max_blocks()`.
+    pub fn message(&self, blocks: usize) -> Vec<u8> {
+        assert!((self.min_blocks()..=self.max_blocks()).contains(&blocks));
+        let extra = blocks - self.pairs.len();
+        self.pairs
+            .iter()
+            .enumerate()
+            .flat_map(|(i, (short, long))| match extra >> i & 1 {
+                0 => short.clone(),
+                _ => long.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Attack outcome.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecondPreimage {
+    pub message: Vec<u8>,
+    /// Compressions spent for the expandable message.
+    pub expandable_work: u64,
+    /// Compressions spent for the linking block.
+    pub linking_work: u64,
+}
+
+/// Second preimage of `target`, with an expandable message of `k` steps.
+///
+/// The target chaining values after `k + 1` to `k + 2^k` blocks are linking
+/// candidates: `k` should be about the log of the target blocks. `None` if the
+/// target has no candidates.
+pub fn second_preimage<D: Digest>(md: &Md<D>, target: &[u8], k: u32) -> Option<SecondPreimage> {
+    let expandable = Expandable::build(md, k);
+    let range = expandable.min_blocks()..=expandable.max_blocks();
+    // Chaining value after j blocks, with j - 1 a valid expandable length
+    let links: HashMap<u64, usize> = md
+        .states(target)
+        .into_iter()
+        .enumerate()
+        .map(|(i, h)| (h, i + 1))
+        .filter(|(_, j)| range.contains(&(j - 1)))
+        .collect();
+    if links.is_empty() {
+        return None;
+    }
+    let (block, j, count) = (0..).find_map(|counter| {
+        let block = candidate(u8::MAX, counter);
+        let j = links.get(&md.compress(expandable.end, &block))?;
+        Some((block, *j, counter + 1))
+    })?;
+    let message = [
+        expandable.message(j - 1),
+        block.to_vec(),
+        target[j * BLOCK..].to_vec(),
+    ]
+    .concat();
+    Some(SecondPreimage {
+        message,
+        expandable_work: expandable.work,
+        linking_work: count,
+    })
+}
+
+// Blocks `x` and `y` with `compress(a, x) == compress(b, y)`, the common
+// chaining value and the compressions spent.
+//
+// Candidates of the two sides are hashed in turns and kept in two tables, as
+// collisions on the same side are useless.
+fn cross_collision<D: Digest>(
+    md: &Md<D>,
+    a: u64,
+    b: u64,
+    tag: u8,
+) -> ([u8; BLOCK], [u8; BLOCK], u64, u64) {
+    let mut tables = [HashMap::new(), HashMap::new()];
+    let mut count = 0;
+    for counter in 0.. {
+        for (side, h) in [a, b].into_iter().enumerate() {
+            let block = candidate(tag, counter);
+            let out = md.compress(h, &block);
+            count += 1;
+            if let Some(&other) = tables[1 - side].get(&out) {
+                let other = candidate(tag, other);
+                let (x, y) = if side == 0 {
+                    (block, other)
+                } else {
+                    (other, block)
+                };
+                return (x, y, out, count);
+            }
+            tables[side].insert(out, counter);
+        }
+    }
+    unreachable!("counter space exhausted")
+}
+
+// Candidate block, distinct for every tag and counter.
+fn candidate(tag: u8, counter: u64) -> [u8; BLOCK] {
+    let mut block = [0; BLOCK];
+    block[0] = tag;
+    block[1..9].copy_from_slice(&counter.to_le_bytes());
+    block
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use birthday_paradox::search::Truncation;
+    use sha2::Sha256;
+
+    #[test]
+    fn expandable_lengths() {
+        let md = Md::<Sha256>::new(Truncation::Leading(2));
+        let expandable = Expandable::build(&md, 4);
+        assert_eq!((expandable.min_blocks(), expandable.max_blocks()), (4, 19));
+        for blocks in 4..=19 {
+            let message = expandable.message(blocks);
+            assert_eq!(message.len(), blocks * BLOCK);
+            assert_eq!(md.chain(0, &message), expandable.end);
+        }
+    }
+
+    #[test]
+    fn long_message_second_preimage() {
+        let md = Md::<Sha256>::new(Truncation::Leading(3));
+        // 2^12 blocks and a partial one
+        let target: Vec<u8> = (0..(BLOCK << 12) + 10).map(|i| (i % 251) as u8).collect();
+        let found = second_preimage(&md, &target, 12).unwrap();
+        assert_ne!(found.message, target);
+        assert_eq!(found.message.len(), target.len());
+        assert_eq!(md.hash(&found.message), md.hash(&target));
+        // Way less than the 2^24 of a brute force search
+        assert!(found.expandable_work + found.linking_work < 1 << 20);
+    }
+}
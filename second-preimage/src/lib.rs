@@ -0,0 +1,112 @@
+//! Merkle–Damgård hash with a truncated chaining value.
+//!
+//! The compression function is a truncated digest (any `digest::Digest`, with
+//! the same [`Truncation`]s as the `birthday-paradox` collision search) of the
+//! chaining value and a 64 bytes block:
+//!
+//! `h' = trunc(D(h || block))`
+//!
+//! and the message is padded with its length (Merkle–Damgård strengthening),
+//! as SHA-2 does. With an `n` bits chaining value a second preimage should
+//! cost 2^n compressions, but for a message of 2^L blocks the [`attacks`] find
+//! one with ≈ `k·2^(n/2+1) + 2^(n-L)`.
+
+use birthday_paradox::search::Truncation;
+use digest::Digest;
+use std::marker::PhantomData;
+
+pub use length_extension::BLOCK;
+
+pub mod attacks;
+
+/// Truncated Merkle–Damgård hash, with a zero IV.
+#[derive(Debug)]
+pub struct Md<D> {
+    truncation: Truncation,
+    _digest: PhantomData<fn() -> D>,
+}
+
+// Not derived, to not require `D: Clone`
+impl<D> Clone for Md<D> {
+    fn clone(&self) -> Self {
+        Md {
+            truncation: self.truncation.clone(),
+            _digest: PhantomData,
+        }
+    }
+}
+
+impl<D: Digest> Md<D> {
+    /// Panics if the truncation is wider than 64 bits or than the digest.
+    pub fn new(truncation: Truncation) -> Self {
+        let bits = truncation.bits();
+        assert!(bits <= 64, "truncation wider than 64 bits");
+        assert!(
+            bits as usize <= 8 * <D as Digest>::output_size(),
+            "truncation wider than the digest"
+        );
+        Md {
+            truncation,
+            _digest: PhantomData,
+        }
+    }
+
+    /// Chaining value width.
+    pub fn bits(&self) -> u32 {
+        self.truncation.bits()
+    }
+
+    /// Compression function.
+    pub fn compress(&self, h: u64, block: &[u8]) -> u64 {
+        debug_assert_eq!(block.len(), BLOCK);
+        let digest = D::new()
+            .chain_update(h.to_be_bytes())
+            .chain_update(block)
+            .finalize();
+        self.truncation.apply(&digest)
+    }
+
+    /// Chaining value after the `blocks` (a multiple of the block size) from
+    /// the chaining value `h`.
+    pub fn chain(&self, h: u64, blocks: &[u8]) -> u64 {
+        blocks
+            .chunks(BLOCK)
+            .fold(h, |h, block| self.compress(h, block))
+    }
+
+    /// Chaining values after each full block of the `message`, starting from
+    /// the IV.
+    pub fn states(&self, message: &[u8]) -> Vec<u64> {
+        message
+            .chunks_exact(BLOCK)
+            .scan(0, |h, block| {
+                *h = self.compress(*h, block);
+                Some(*h)
+            })
+            .collect()
+    }
+
+    /// Digest of the `message`, padded with its length.
+    pub fn hash(&self, message: &[u8]) -> u64 {
+        let padded = [message, &length_extension::padding(message.len() as u64)].concat();
+        self.chain(0, &padded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::Sha256;
+
+    #[test]
+    fn chaining_values() {
+        let md = Md::<Sha256>::new(Truncation::Leading(3));
+        let message: Vec<u8> = (0..200).map(|i| i as u8).collect();
+        let states = md.states(&message);
+        assert_eq!(states.len(), 3);
+        assert_eq!(states[2], md.chain(states[0], &message[BLOCK..3 * BLOCK]));
+        assert!(states.iter().all(|&h| h < 1 << 24));
+        // Same blocks, different lengths
+        assert_ne!(md.hash(&message[..190]), md.hash(&message[..191]));
+    }
+}
@@ -0,0 +1,51 @@
+use birthday_paradox::search::Truncation;
+use second_preimage::{attacks::second_preimage, Md, BLOCK};
+use sha2::Sha256;
+use std::time::Instant;
+
+fn main() {
+    let md = Md::<Sha256>::new(Truncation::Leading(4));
+    let (log_blocks, k) = (16, 16);
+    let target: Vec<u8> = (0..BLOCK << log_blocks)
+        .map(|i| (i as u32).wrapping_mul(2654435761).to_be_bytes()[0])
+        .collect();
+    println!(
+        "Target: {} MiB ({} blocks), {} bits SHA-256 Merkle-Damgard hash {:08x}",
+        target.len() >> 20,
+        1 << log_blocks,
+        md.bits(),
+        md.hash(&target)
+    );
+
+    let start = Instant::now();
+    let found = second_preimage(&md, &target, k).expect("linking candidates");
+    let work = found.expandable_work + found.linking_work;
+    let differ = found
+        .message
+        .iter()
+        .zip(&target)
+        .filter(|(a, b)| a != b)
+        .count();
+    println!(
+        "Second preimage in {:.1}s: hash {:08x}, {} bytes differ",
+        start.elapsed().as_secs_f64(),
+        md.hash(&found.message),
+        differ
+    );
+    println!(
+        "  expandable message: {:>10} compressions (k·2^(n/2+1) ≈ {})",
+        found.expandable_work,
+        k << (md.bits() / 2 + 1)
+    );
+    println!(
+        "  linking block:      {:>10} compressions (2^(n-L) = {})",
+        found.linking_work,
+        1_u64 << (md.bits() - log_blocks)
+    );
+    println!(
+        "  total:              {:>10} compressions, 2^{:.1} vs 2^{} naive",
+        work,
+        (work as f64).log2(),
+        md.bits()
+    );
+}
@@ -0,0 +1,325 @@
+//! Discrete logarithm algorithm auto selection.
+//!
+//! The three algorithms trade memory for time in different ways:
+//! - [`bsgs`]: ≈ 1.5·√N group operations plus ≈ 1.5·√N table accesses, with a
+//!   √N entries table;
+//! - [`rho`]: ≈ 3·√(πN/2) group operations, constant memory;
+//! - [`kangaroo`]: ≈ 2·√w group operations, constant memory, only for exponents
+//!   known to lie in an interval of width w.
+//!
+//! The costs of a group operation and of a table access are measured on the
+//! spot by a short benchmark over the actual group ([`Calibration`]), the
+//! memory budget defaults to half of the available memory. The cheapest
+//! algorithm whose memory fits the budget is chosen and the estimates are kept
+//! in the [`Plan`] to explain the decision.
+
+use crate::{bsgs, kangaroo::kangaroo, pow, rho::rho, Group};
+use num::{Integer, One, ToPrimitive};
+use num_bigint::BigUint;
+use std::{collections::HashMap, fmt, hint::black_box, mem::size_of, ops::Range, time::Instant};
+
+// Random walks restarts
+const ATTEMPTS: u64 = 16;
+
+/// Multiplicative group of integers modulo a prime.
+pub struct Zmod {
+    pub n: BigUint,
+}
+
+impl Group for Zmod {
+    type Element = BigUint;
+
+    fn identity(&self) -> BigUint {
+        BigUint::from(1_u8)
+    }
+
+    fn op(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        a * b % &self.n
+    }
+
+    fn inverse(&self, a: &BigUint) -> BigUint {
+        a.modpow(&(&self.n - 2_u8), &self.n)
+    }
+}
+
+/// Optional knowledge about the problem.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Hint {
+    /// Order of `g`, if not `n - 1` (e.g. a subgroup generator).
+    pub order: Option<u64>,
+    /// Interval known to contain the exponent.
+    pub range: Option<Range<u64>>,
+    /// Memory budget in bytes.
+    pub memory: Option<u64>,
+}
+
+/// Measured costs of the basic operations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Calibration {
+    /// Group operation, in nanoseconds.
+    pub op: f64,
+    /// Table insertion or lookup, in nanoseconds.
+    pub access: f64,
+    /// Estimated memory of a table entry, in bytes.
+    pub entry_bytes: u64,
+}
+
+impl Calibration {
+    /// Benchmark the group operations over the powers of `g`.
+    ///
+    /// `heap_bytes` is the heap memory owned by an element, if any.
+    pub fn measure<G: Group>(group: &G, g: &G::Element, heap_bytes: usize) -> Self {
+        const ROUNDS: u32 = 2000;
+        let start = Instant::now();
+        let mut e = g.clone();
+        let powers: Vec<_> = (0..ROUNDS)
+            .map(|_| {
+                e = group.op(&e, g);
+                e.clone()
+            })
+            .collect();
+        let op = start.elapsed().as_nanos() as f64 / ROUNDS as f64;
+
+        let start = Instant::now();
+        let mut table = HashMap::new();
+        for (i, e) in powers.into_iter().enumerate() {
+            table.entry(e).or_insert(i);
+        }
+        black_box(&table);
+        let access = start.elapsed().as_nanos() as f64 / ROUNDS as f64;
+
+        // Hash tables keep some free room, here assumed to double the size
+        let entry = size_of::<G::Element>() + heap_bytes + size_of::<u64>();
+        Calibration {
+            op,
+            access,
+            entry_bytes: 2 * entry as u64,
+        }
+    }
+}
+
+/// Chosen algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Bsgs,
+    Rho,
+    Kangaroo,
+}
+
+/// Algorithm choice with its motivations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plan {
+    pub algorithm: Algorithm,
+    pub reasons: Vec<String>,
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:?}", self.algorithm)?;
+        for reason in &self.reasons {
+            writeln!(f, "  - {reason}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Choose the algorithm for a generator of the given `order`, with the
+/// exponent optionally in `range`, and a `memory` budget in bytes.
+pub fn plan(
+    calibration: &Calibration,
+    order: u64,
+    range: Option<&Range<u64>>,
+    memory: u64,
+) -> Plan {
+    let mut reasons = vec![format!(
+        "group operation {:.0} ns, table access {:.0} ns, table entry {} bytes",
+        calibration.op, calibration.access, calibration.entry_bytes
+    )];
+    let width = range.map_or(order, |r| r.end - r.start).min(order);
+    let root = (width as f64).sqrt();
+    let secs = |ns: f64| ns / 1e9;
+    let mut candidates = Vec::new();
+
+    let bsgs_memory = root * calibration.entry_bytes as f64;
+    let bsgs_time = root * 1.5 * (calibration.op + calibration.access);
+    if bsgs_memory <= memory as f64 {
+        reasons.push(format!(
+            "BSGS: ≈ {:.3} s, {:.1} MiB table",
+            secs(bsgs_time),
+            bsgs_memory / (1 << 20) as f64
+        ));
+        candidates.push((Algorithm::Bsgs, bsgs_time));
+    } else {
+        reasons.push(format!(
+            "BSGS: {:.1} MiB table over the {:.1} MiB budget",
+            bsgs_memory / (1 << 20) as f64,
+            memory as f64 / (1 << 20) as f64
+        ));
+    }
+
+    match range {
+        Some(_) => {
+            let time = 2.0 * root * calibration.op;
+            reasons.push(format!("kangaroo: ≈ {:.3} s", secs(time)));
+            candidates.push((Algorithm::Kangaroo, time));
+        }
+        None => reasons.push("kangaroo: no interval hint".into()),
+    }
+
+    // Rho ignores the interval
+    let time = 3.0 * (std::f64::consts::PI * order as f64 / 2.0).sqrt() * calibration.op;
+    reasons.push(format!("rho: ≈ {:.3} s", secs(time)));
+    candidates.push((Algorithm::Rho, time));
+
+    let algorithm = candidates
+        .into_iter()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(algorithm, _)| algorithm)
+        .unwrap();
+    Plan { algorithm, reasons }
+}
+
+/// Available memory in bytes, on Linux.
+pub fn available_memory() -> Option<u64> {
+    let info = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let line = info.lines().find(|l| l.starts_with("MemAvailable:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+/// Problems out of reach of [`solve_dlog_auto`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The modulus is less than 3.
+    Modulus,
+    /// The generator is not coprime to the modulus.
+    Generator,
+    /// The order of `g` is zero.
+    ZeroOrder,
+    /// The order of `g` doesn't fit 64 bits.
+    Order,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Modulus => write!(f, "the modulus must be a prime greater than 2"),
+            Error::Generator => write!(f, "the generator must be coprime to the modulus"),
+            Error::ZeroOrder => write!(f, "the group order can't be zero"),
+            Error::Order => write!(
+                f,
+                "the group order must fit 64 bits, give the subgroup order"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Discrete logarithm with automatic algorithm selection.
+///
+/// Finds `x` such that `g^x = h (mod n)`, with `n` prime and `g` coprime to
+/// `n`. The order of `g` (by default `n - 1`) must fit 64 bits. The memory budget defaults to half
+/// of the available memory (1 GiB if unknown).
+pub fn solve_dlog_auto(
+    n: BigUint,
+    g: BigUint,
+    h: BigUint,
+    hint: Hint,
+) -> Result<(Option<BigUint>, Plan), Error> {
+    if n < BigUint::from(3_u8) {
+        return Err(Error::Modulus);
+    }
+    if !g.gcd(&n).is_one() {
+        return Err(Error::Generator);
+    }
+    if hint.order == Some(0) {
+        return Err(Error::ZeroOrder);
+    }
+    let order = hint
+        .order
+        .or_else(|| (&n - 1_u8).to_u64())
+        .ok_or(Error::Order)?;
+    let memory = hint
+        .memory
+        .unwrap_or_else(|| available_memory().unwrap_or(2 << 30) / 2);
+    let heap_bytes = n.to_u64_digits().len() * size_of::<u64>();
+    let group = Zmod { n };
+    let calibration = Calibration::measure(&group, &g, heap_bytes);
+    let plan = plan(&calibration, order, hint.range.as_ref(), memory);
+
+    let range = hint.range.unwrap_or(0..order);
+    let x = match plan.algorithm {
+        Algorithm::Bsgs => {
+            // g^x = h ⇒ g^(x - start) = h·g^(-start)
+            let shifted = group.op(&h, &group.inverse(&pow(&group, &g, range.start)));
+            bsgs(&group, &g, &shifted, range.end - range.start).map(|x| x + range.start)
+        }
+        Algorithm::Rho => rho(&group, &g, &h, order, ATTEMPTS),
+        Algorithm::Kangaroo => kangaroo(&group, &g, &h, range, ATTEMPTS),
+    };
+    Ok((x.map(BigUint::from), plan))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn choice_follows_costs_and_memory() {
+        let calibration = Calibration {
+            op: 100.0,
+            access: 10.0,
+            entry_bytes: 64,
+        };
+        let order = 1 << 40;
+        let plan = |range, memory| plan(&calibration, order, range, memory).algorithm;
+        assert_eq!(plan(None, 1 << 30), Algorithm::Bsgs);
+        assert_eq!(plan(None, 1 << 20), Algorithm::Rho);
+        assert_eq!(plan(Some(&(0..1 << 20)), 1 << 20), Algorithm::Bsgs);
+        assert_eq!(plan(Some(&(0..1 << 36)), 1 << 20), Algorithm::Kangaroo);
+    }
+
+    #[test]
+    fn every_choice_solves() {
+        // 2 generates Z*_1000003
+        let n = BigUint::from(1000003_u32);
+        let x = 765_432_u32;
+        let h = BigUint::from(2_u8).modpow(&BigUint::from(x), &n);
+        for (range, memory) in [(None, 1 << 30), (None, 0), (Some(700_000..800_000), 0)] {
+            let hint = Hint {
+                range,
+                memory: Some(memory),
+                ..Default::default()
+            };
+            let (found, plan) =
+                solve_dlog_auto(n.clone(), BigUint::from(2_u8), h.clone(), hint).unwrap();
+            assert_eq!(found, Some(BigUint::from(x)), "{plan}");
+        }
+    }
+
+    #[test]
+    fn out_of_reach_problems() {
+        let solve = |n: u128, order| {
+            let hint = Hint {
+                order,
+                ..Default::default()
+            };
+            let (g, h) = (BigUint::from(2_u8), BigUint::from(2_u8));
+            solve_dlog_auto(BigUint::from(n), g, h, hint).map(|(x, _)| x)
+        };
+        assert_eq!(solve(0, None), Err(Error::Modulus));
+        assert_eq!(solve(2, Some(1)), Err(Error::Modulus));
+        assert_eq!(solve((1 << 89) - 1, None), Err(Error::Order));
+        assert_eq!(solve(1000003, Some(0)), Err(Error::ZeroOrder));
+        assert_eq!(
+            solve((1 << 89) - 1, Some(89)),
+            Ok(Some(BigUint::from(1_u8)))
+        );
+        for g in [0_u8, 3, 6] {
+            let (g, h) = (BigUint::from(g), BigUint::from(0_u8));
+            let x = solve_dlog_auto(BigUint::from(3_u8), g, h, Hint::default());
+            assert_eq!(x, Err(Error::Generator));
+        }
+    }
+}
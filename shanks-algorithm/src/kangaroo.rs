@@ -0,0 +1,78 @@
+//! Pollard's kangaroo (lambda) for discrete logarithms in an interval.
+//!
+//! When `x` is known to lie in an interval of width `w`, a tame kangaroo starts
+//! from the known `g^end` and jumps ahead, the jump lengths (powers of two with
+//! mean ≈ √w/2) being determined by the current position only. At the end it
+//! sets a trap. A wild kangaroo then starts from `h = g^x` and follows the
+//! same rule: once it lands on a point visited by the tame one the two paths
+//! coincide and it falls into the trap, revealing `x` from the travelled
+//! distances.
+//!
+//! Memory is constant and ≈ 2·√w group operations are expected, with a small
+//! failure probability (retried with a different jump rule).
+
+use crate::{partition, pow, Group};
+use std::ops::Range;
+
+/// Finds `x` in `range` such that `g^x = h`.
+///
+/// Up to `attempts` different jump rules are tried.
+pub fn kangaroo<G: Group>(
+    group: &G,
+    g: &G::Element,
+    h: &G::Element,
+    range: Range<u64>,
+    attempts: u64,
+) -> Option<u64> {
+    let width = range.end.checked_sub(range.start)?;
+    // Jumps 2^0, ..., 2^(k-1), with mean (2^k - 1)/k ≈ √w/2
+    let target = (width as f64).sqrt() / 2.0;
+    let k = (1..63).find(|k| ((1_u64 << k) - 1) as f64 / *k as f64 >= target)?;
+    let jumps: Vec<_> = (0..k).map(|i| pow(group, g, 1 << i)).collect();
+    let mean = ((1_u64 << k) - 1) as u128 / k as u128;
+
+    (0..attempts).find_map(|salt| {
+        let jump = |y: &G::Element| partition(y, salt, k);
+
+        // Tame kangaroo, about 4 mean jumps
+        let mut tame = pow(group, g, range.end);
+        let mut dist = 0_u128;
+        for _ in 0..4 * mean.max(1) {
+            let i = jump(&tame);
+            tame = group.op(&tame, &jumps[i]);
+            dist += 1 << i;
+        }
+
+        // Wild kangaroo, until it passes the trap
+        let mut wild = h.clone();
+        let mut wild_dist = 0_u128;
+        while wild_dist <= width as u128 + dist {
+            if wild == tame {
+                let x = (range.end as u128 + dist).checked_sub(wild_dist)?;
+                return u64::try_from(x).ok().filter(|x| range.contains(x));
+            }
+            let i = jump(&wild);
+            wild = group.op(&wild, &jumps[i]);
+            wild_dist += 1 << i;
+        }
+        None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::Zmod;
+
+    #[test]
+    fn exponent_in_interval() {
+        let group = Zmod(1000003);
+        let range = 500_000..600_000;
+        for x in [500_000, 543_210, 599_999] {
+            let h = pow(&group, &2, x);
+            assert_eq!(kangaroo(&group, &2, &h, range.clone(), 8), Some(x));
+        }
+        let h = pow(&group, &2, 10);
+        assert_eq!(kangaroo(&group, &2, &h, range, 2), None);
+    }
+}
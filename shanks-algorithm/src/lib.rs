@@ -10,11 +10,90 @@
 //! Further optimizations are possible by partitioning the table construction
 //! and lookup tasks on multiple execution threads (e.g. via `rayon`).
 //!
+//! The generic [`bsgs`] works in any group implementing [`Group`] (e.g. the
+//! points of an elliptic curve) and for exponents in a given range.
+//!
+//! The table is the weak spot: when it doesn't fit in memory the constant
+//! memory random walks of [`rho`] (Pollard's rho) and [`kangaroo`] (Pollard's
+//! lambda, for exponents in a known interval) do the job at the cost of a few
+//! more group operations. The [`auto`] dispatcher picks the best one.
+//!
+//! The `wasm` feature provides JavaScript bindings (`wasm` module), the
+//! `montgomery` feature makes [`shanks`] use the `numutil` Montgomery
+//! arithmetic for odd moduli.
+//!
 //! Some background: https://datawok.net/posts/discrete-logarithm/#shanks-algorithm
 
 use num::ToPrimitive;
 use num_bigint::{BigUint, ToBigUint};
-use std::collections::HashMap;
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+};
+
+pub mod auto;
+pub mod kangaroo;
+pub mod rho;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+/// Finite abelian group, written multiplicatively.
+pub trait Group {
+    type Element: Clone + Eq + Hash;
+
+    fn identity(&self) -> Self::Element;
+
+    fn op(&self, a: &Self::Element, b: &Self::Element) -> Self::Element;
+
+    fn inverse(&self, a: &Self::Element) -> Self::Element;
+}
+
+/// Generic baby-step giant-step.
+///
+/// Finds `x` such that `g^x = h`, provided that one less than `bound` exists.
+/// Requires ≈ `2·√bound` group operations and a `√bound` entries table.
+pub fn bsgs<G: Group>(group: &G, g: &G::Element, h: &G::Element, bound: u64) -> Option<u64> {
+    let m = (bound as f64).sqrt().ceil().max(1.0) as u64;
+    let mut table = HashMap::with_capacity(m as usize);
+    let mut e = group.identity();
+    for j in 0..m {
+        // Keep the smallest exponent if the order of g is less than m
+        table.entry(e.clone()).or_insert(j);
+        e = group.op(&e, g);
+    }
+
+    // Here e = g^m
+    let factor = group.inverse(&e);
+    let mut e = h.clone();
+    for i in 0..m {
+        if let Some(j) = table.get(&e) {
+            return Some(i * m + j);
+        }
+        e = group.op(&e, &factor);
+    }
+    None
+}
+
+/// `g^e` via square and multiply.
+pub fn pow<G: Group>(group: &G, g: &G::Element, mut e: u64) -> G::Element {
+    let (mut r, mut b) = (group.identity(), g.clone());
+    while e > 0 {
+        if e & 1 == 1 {
+            r = group.op(&r, &b);
+        }
+        b = group.op(&b, &b);
+        e >>= 1;
+    }
+    r
+}
+
+// Pseudo random partition of the group elements, used by the random walks.
+pub(crate) fn partition<T: Hash>(x: &T, salt: u64, parts: usize) -> usize {
+    let mut hasher = DefaultHasher::new();
+    salt.hash(&mut hasher);
+    x.hash(&mut hasher);
+    (hasher.finish() % parts as u64) as usize
+}
 
 /// Shanks algorithm.
 ///
@@ -29,6 +108,11 @@ use std::collections::HashMap;
 /// 2. Compute h·g^(-m·x_g) for 0 ≤ x_g < m
 /// 3. Check for a collision
 pub fn shanks(n: BigUint, g: BigUint, h: BigUint) -> Option<BigUint> {
+    #[cfg(feature = "montgomery")]
+    if let Some(ctx) = numutil::Montgomery::new(&n) {
+        return shanks_montgomery(&ctx, &g, &h);
+    }
+
     let mut table = HashMap::new();
     let m = n.sqrt().to_usize().expect("Can't convert √{n} to f64") + 1;
     let mut e = BigUint::from(1_u8);
@@ -56,6 +140,32 @@ pub fn shanks(n: BigUint, g: BigUint, h: BigUint) -> Option<BigUint> {
     None
 }
 
+// Same as `shanks`, with the products in Montgomery representation.
+#[cfg(feature = "montgomery")]
+fn shanks_montgomery(ctx: &numutil::Montgomery, g: &BigUint, h: &BigUint) -> Option<BigUint> {
+    let n = ctx.modulus();
+    let mut table = HashMap::new();
+    let m = n.sqrt().to_usize().expect("Can't convert √{n} to f64") + 1;
+    let g = ctx.to_mont(g);
+    let mut e = ctx.one();
+
+    for j in 0..m {
+        table.insert(e.clone(), j);
+        e = ctx.mul(&e, &g);
+    }
+
+    let factor = ctx.pow(&g, &(n - 1_u8 - m));
+
+    let mut e = ctx.to_mont(h);
+    for i in 0..m {
+        if let Some(j) = table.get(&e) {
+            return (i * m + j).to_biguint();
+        }
+        e = ctx.mul(&e, &factor);
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -68,4 +178,32 @@ mod tests {
 
         assert_eq!(shanks(n, g, h), Some(BigUint::from(103_u32)));
     }
+
+    pub(crate) struct Zmod(pub(crate) u64);
+
+    impl Group for Zmod {
+        type Element = u64;
+
+        fn identity(&self) -> u64 {
+            1
+        }
+
+        fn op(&self, a: &u64, b: &u64) -> u64 {
+            (*a as u128 * *b as u128 % self.0 as u128) as u64
+        }
+
+        fn inverse(&self, a: &u64) -> u64 {
+            // Prime modulus
+            pow(self, a, self.0 - 2)
+        }
+    }
+
+    #[test]
+    fn generic_bsgs() {
+        let group = Zmod(433);
+        assert_eq!(bsgs(&group, &5, &71, 432), Some(103));
+        // 71 = 5^103 is not a power of 125 = 5^3, as 3 doesn't divide 103 mod 432
+        assert_eq!(bsgs(&group, &125, &71, 30), None);
+        assert_eq!(bsgs(&group, &5, &1, 432), Some(0));
+    }
 }
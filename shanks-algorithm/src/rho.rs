@@ -0,0 +1,142 @@
+//! Pollard's rho for discrete logarithms.
+//!
+//! The walk `x ← x·M_i`, with the multiplier `M_i = g^a_i·h^b_i` chosen by a
+//! pseudo random partition of the group, behaves like a random mapping and
+//! thus, after ≈ √(πN/2) steps, enters a cycle (Teske's r-adding walk). Every
+//! point is kept as `g^a·h^b`, so Floyd's cycle detection finds two
+//! representations of the same point:
+//!
+//! g^a1·h^b1 = g^a2·h^b2 ⇒ (b2 - b1)·x = a1 - a2 (mod N)
+//!
+//! Just a couple of points are stored, at the cost of ≈ 3·√(πN/2) group
+//! operations.
+
+use crate::{partition, pow, Group};
+
+// Multipliers of the r-adding walk
+const PARTS: usize = 16;
+
+// Walk point `g^a·h^b`.
+struct Point<E> {
+    x: E,
+    a: u64,
+    b: u64,
+}
+
+/// Finds `x` such that `g^x = h`, with `order` the order of `g`.
+///
+/// The walk is restarted with a different partition when the collision is
+/// useless (e.g. `b1 = b2`), up to `attempts` times.
+pub fn rho<G: Group>(
+    group: &G,
+    g: &G::Element,
+    h: &G::Element,
+    order: u64,
+    attempts: u64,
+) -> Option<u64> {
+    (0..attempts).find_map(|salt| walk(group, g, h, order, salt))
+}
+
+fn walk<G: Group>(group: &G, g: &G::Element, h: &G::Element, order: u64, salt: u64) -> Option<u64> {
+    let mut seed = salt;
+    let mut rand = || splitmix(&mut seed) % order;
+    let multipliers: Vec<_> = (0..PARTS)
+        .map(|_| {
+            let (a, b) = (rand(), rand());
+            let x = group.op(&pow(group, g, a), &pow(group, h, b));
+            Point { x, a, b }
+        })
+        .collect();
+    let step = |p: &Point<G::Element>| {
+        let m = &multipliers[partition(&p.x, salt, PARTS)];
+        Point {
+            x: group.op(&p.x, &m.x),
+            a: add_mod(p.a, m.a, order),
+            b: add_mod(p.b, m.b, order),
+        }
+    };
+
+    let (a, b) = (rand(), rand());
+    let start = Point {
+        x: group.op(&pow(group, g, a), &pow(group, h, b)),
+        a,
+        b,
+    };
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+    while tortoise.x != hare.x {
+        tortoise = step(&tortoise);
+        hare = step(&step(&hare));
+    }
+
+    // (b2 - b1)·x = a1 - a2 (mod N)
+    let lhs = sub_mod(hare.b, tortoise.b, order);
+    let rhs = sub_mod(tortoise.a, hare.a, order);
+    solve_congruence(lhs, rhs, order)
+        .into_iter()
+        .find(|x| pow(group, g, *x) == *h)
+}
+
+/// Solutions of `a·x = b (mod n)`.
+///
+/// There are `d = gcd(a, n)` solutions if `d` divides `b`. Too many solutions
+/// (more than 2^16) are not enumerated.
+pub fn solve_congruence(a: u64, b: u64, n: u64) -> Vec<u64> {
+    let (d, inv, _) = egcd(a as i128, n as i128);
+    if a == 0 || !b.is_multiple_of(d as u64) || d > 1 << 16 {
+        return Vec::new();
+    }
+    let (d, m) = (d as u64, n / d as u64);
+    let inv = inv.rem_euclid(m as i128) as u128;
+    let x0 = ((b / d) as u128 * inv % m as u128) as u64;
+    (0..d).map(|k| x0 + k * m).collect()
+}
+
+// (gcd, x, y) with a·x + b·y = gcd
+fn egcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (d, x, y) = egcd(b, a % b);
+        (d, y, x - a / b * y)
+    }
+}
+
+fn add_mod(a: u64, b: u64, n: u64) -> u64 {
+    ((a as u128 + b as u128) % n as u128) as u64
+}
+
+fn sub_mod(a: u64, b: u64, n: u64) -> u64 {
+    add_mod(a, n - b, n)
+}
+
+fn splitmix(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::Zmod;
+
+    #[test]
+    fn congruences() {
+        assert_eq!(solve_congruence(3, 2, 7), [3]);
+        assert_eq!(solve_congruence(4, 6, 10), [4, 9]);
+        assert!(solve_congruence(4, 3, 10).is_empty());
+    }
+
+    #[test]
+    fn composite_order() {
+        // 2 generates Z*_1000003, order 1000002 = 2·3·166667
+        let group = Zmod(1000003);
+        for x in [0, 1, 12345, 999_999] {
+            let h = pow(&group, &2, x);
+            assert_eq!(rho(&group, &2, &h, 1000002, 8), Some(x));
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! JavaScript bindings, packaged by the `shanks-wasm` crate.
+//!
+//! Numbers are exchanged as decimal strings or as big-endian bytes
+//! (`Uint8Array`). The [`crate::auto`] dispatcher is not exposed, its
+//! calibration relies on the system clock.
+
+use num_bigint::BigUint;
+use wasm_bindgen::prelude::*;
+
+fn shanks_checked(n: BigUint, g: BigUint, h: BigUint) -> Result<Option<BigUint>, JsError> {
+    if n < BigUint::from(3_u8) {
+        return Err(JsError::new("Modulus must be a prime greater than 2"));
+    }
+    Ok(crate::shanks(n, g, h))
+}
+
+/// Discrete logarithm of `h` to the base `g` modulo the prime `n`, all as
+/// decimal strings. Returns `undefined` if not found.
+#[wasm_bindgen(js_name = shanks)]
+pub fn shanks_str(n: &str, g: &str, h: &str) -> Result<Option<String>, JsError> {
+    let x = shanks_checked(n.parse()?, g.parse()?, h.parse()?)?;
+    Ok(x.map(|x| x.to_string()))
+}
+
+/// As [`shanks_str`], with big-endian numbers.
+#[wasm_bindgen(js_name = shanksBytes)]
+pub fn shanks_bytes(n: &[u8], g: &[u8], h: &[u8]) -> Result<Option<Vec<u8>>, JsError> {
+    let x = shanks_checked(
+        BigUint::from_bytes_be(n),
+        BigUint::from_bytes_be(g),
+        BigUint::from_bytes_be(h),
+    )?;
+    Ok(x.map(|x| x.to_bytes_be()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strings_and_bytes() {
+        assert_eq!(shanks_str("433", "5", "71").unwrap().unwrap(), "103");
+        assert_eq!(
+            shanks_bytes(&[0x01, 0xb1], &[5], &[71]).unwrap().unwrap(),
+            [103]
+        );
+    }
+}
@@ -0,0 +1,5 @@
+//! WebAssembly package of the `shanks-algorithm` crate JavaScript bindings.
+//!
+//! Build with `wasm-pack build --target web`.
+
+pub use shanks::wasm::*;
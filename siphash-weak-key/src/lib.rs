@@ -0,0 +1,262 @@
+//! SipHash key recovery under weak key deployments.
+//!
+//! SipHash is a keyed PRF with 128 bits keys, designed to protect hash tables
+//! against *HashDoS*: without the key an attacker can't craft many inputs
+//! landing in the same bucket and turn the table operations from O(1) to O(n).
+//! It is also often used as short MAC.
+//!
+//! The security obviously relies on the key. If the application uses a
+//! constant key, or derives it from a guessable source (a timestamp, a
+//! truncated random value, ...), the key space is small enough to be
+//! exhaustively searched given a few input/output pairs (e.g. leaked via the
+//! iteration order of a table or via the MACs themselves).
+//!
+//! Once the key is known, the attacker can produce as many colliding inputs as
+//! they want and the HashDoS protection is gone.
+
+use rayon::prelude::*;
+
+/// SipHash key.
+pub type Key = [u8; 16];
+
+#[inline(always)]
+fn sip_round(v: &mut [u64; 4]) {
+    v[0] = v[0].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(13) ^ v[0];
+    v[0] = v[0].rotate_left(32);
+    v[2] = v[2].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(16) ^ v[2];
+    v[0] = v[0].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(21) ^ v[0];
+    v[2] = v[2].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(17) ^ v[2];
+    v[2] = v[2].rotate_left(32);
+}
+
+/// SipHash-2-4 with 64 bits output.
+pub fn siphash24(key: &Key, msg: &[u8]) -> u64 {
+    let k0 = u64::from_le_bytes(key[..8].try_into().unwrap());
+    let k1 = u64::from_le_bytes(key[8..].try_into().unwrap());
+    let mut v = [
+        k0 ^ 0x736f6d6570736575,
+        k1 ^ 0x646f72616e646f6d,
+        k0 ^ 0x6c7967656e657261,
+        k1 ^ 0x7465646279746573,
+    ];
+
+    let mut compress = |m: u64| {
+        v[3] ^= m;
+        sip_round(&mut v);
+        sip_round(&mut v);
+        v[0] ^= m;
+    };
+
+    let mut chunks = msg.chunks_exact(8);
+    for chunk in &mut chunks {
+        compress(u64::from_le_bytes(chunk.try_into().unwrap()));
+    }
+    let mut last = [0; 8];
+    last[..chunks.remainder().len()].copy_from_slice(chunks.remainder());
+    last[7] = msg.len() as u8;
+    compress(u64::from_le_bytes(last));
+
+    v[2] ^= 0xff;
+    for _ in 0..4 {
+        sip_round(&mut v);
+    }
+    v[0] ^ v[1] ^ v[2] ^ v[3]
+}
+
+/// Toy key derivation from a 64 bits seed (SplitMix64).
+///
+/// Stands for any deterministic expansion of a small seed, e.g. a PRNG seeded
+/// with the current time.
+pub fn key_from_seed(seed: u64) -> Key {
+    let mut state = seed;
+    let mut next = || {
+        state = state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    };
+    let mut key = [0; 16];
+    key[..8].copy_from_slice(&next().to_le_bytes());
+    key[8..].copy_from_slice(&next().to_le_bytes());
+    key
+}
+
+/// Weak key generation strategies.
+#[derive(Debug, Clone, Copy)]
+pub enum WeakKey {
+    /// Hard coded key, shared by all the deployments (e.g. all zeros).
+    Constant(Key),
+    /// Only the first `bits` bits are random, the others are zero.
+    ///
+    /// E.g. a 32 bits random value copied into the key buffer.
+    LowEntropy { bits: u32 },
+    /// Key derived from a Unix timestamp (in seconds) via [`key_from_seed`].
+    ///
+    /// The attacker knows the process was started within `window` seconds from
+    /// `start`.
+    Timestamp { start: u64, window: u64 },
+}
+
+impl WeakKey {
+    /// Number of candidate keys.
+    pub fn size(&self) -> u64 {
+        match *self {
+            WeakKey::Constant(_) => 1,
+            WeakKey::LowEntropy { bits } => {
+                assert!(bits < 64, "Key space too large");
+                1 << bits
+            }
+            WeakKey::Timestamp { window, .. } => window,
+        }
+    }
+
+    /// Entropy in bits.
+    pub fn entropy(&self) -> f64 {
+        (self.size() as f64).log2()
+    }
+
+    /// The `i`-th candidate key.
+    pub fn key(&self, i: u64) -> Key {
+        match *self {
+            WeakKey::Constant(key) => key,
+            WeakKey::LowEntropy { .. } => {
+                let mut key = [0; 16];
+                key[..8].copy_from_slice(&i.to_le_bytes());
+                key
+            }
+            WeakKey::Timestamp { start, .. } => key_from_seed(start + i),
+        }
+    }
+}
+
+/// Observed input/output pair.
+///
+/// For short MACs the output is truncated to the `tag_bits` least significant
+/// bits.
+#[derive(Debug, Clone)]
+pub struct Sample {
+    pub msg: Vec<u8>,
+    pub tag: u64,
+}
+
+fn truncate(h: u64, tag_bits: u32) -> u64 {
+    if tag_bits >= 64 {
+        h
+    } else {
+        h & ((1 << tag_bits) - 1)
+    }
+}
+
+/// Short MAC, i.e. SipHash output truncated to `tag_bits`.
+pub fn mac(key: &Key, msg: &[u8], tag_bits: u32) -> u64 {
+    truncate(siphash24(key, msg), tag_bits)
+}
+
+/// Pairs required to single out the key with overwhelming probability.
+///
+/// Each pair filters out all but a `2^-tag_bits` fraction of the wrong keys.
+pub fn required_samples(space: &WeakKey, tag_bits: u32) -> usize {
+    (space.entropy() / tag_bits as f64).floor() as usize + 1
+}
+
+/// Exhaustive parallel search of the key matching all the `samples`.
+pub fn recover_key(space: &WeakKey, samples: &[Sample], tag_bits: u32) -> Option<Key> {
+    par_search::find_map(0..space.size(), |i| {
+        let key = space.key(i);
+        samples
+            .iter()
+            .all(|s| mac(&key, &s.msg, tag_bits) == s.tag)
+            .then_some(key)
+    })
+}
+
+/// HashDoS: find `count` distinct inputs (with the given `prefix`) falling in
+/// the same bucket of a table with `buckets` buckets (power of two) indexed by
+/// the hash least significant bits.
+///
+/// About `count · buckets` hashes are required.
+pub fn colliding_inputs(key: &Key, prefix: &str, buckets: u64, count: usize) -> Vec<String> {
+    assert!(buckets.is_power_of_two(), "Buckets must be a power of two");
+    let mask = buckets - 1;
+    let target = siphash24(key, prefix.as_bytes()) & mask;
+    // Candidates are checked in parallel batches
+    (0_u64..)
+        .step_by(1 << 16)
+        .flat_map(|base| {
+            (base..base + (1 << 16))
+                .into_par_iter()
+                .map(|i| format!("{prefix}{i}"))
+                .filter(|s| siphash24(key, s.as_bytes()) & mask == target)
+                .collect::<Vec<_>>()
+        })
+        .take(count)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: Key = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+
+    #[test]
+    fn siphash_test_vectors() {
+        let msg: Vec<u8> = (0..64).collect();
+        assert_eq!(siphash24(&KEY, &msg[..0]), 0x726fdb47dd0e0e31);
+        assert_eq!(siphash24(&KEY, &msg[..15]), 0xa129ca6149be45e5);
+        #[allow(deprecated)]
+        for len in 0..64 {
+            use std::hash::{Hasher, SipHasher};
+            let mut hasher = SipHasher::new_with_keys(
+                u64::from_le_bytes(KEY[..8].try_into().unwrap()),
+                u64::from_le_bytes(KEY[8..].try_into().unwrap()),
+            );
+            hasher.write(&msg[..len]);
+            assert_eq!(siphash24(&KEY, &msg[..len]), hasher.finish());
+        }
+    }
+
+    #[test]
+    fn recover_low_entropy_short_mac_key() {
+        let space = WeakKey::LowEntropy { bits: 20 };
+        let key = space.key(0xcafe5);
+        let tag_bits = 16;
+        let samples: Vec<_> = (0..required_samples(&space, tag_bits))
+            .map(|i| {
+                let msg = format!("message {i}").into_bytes();
+                let tag = mac(&key, &msg, tag_bits);
+                Sample { msg, tag }
+            })
+            .collect();
+        assert_eq!(samples.len(), 2);
+        assert_eq!(recover_key(&space, &samples, tag_bits), Some(key));
+    }
+
+    #[test]
+    fn recover_timestamp_key_and_collide() {
+        let space = WeakKey::Timestamp {
+            start: 1_700_000_000,
+            window: 86400,
+        };
+        let key = key_from_seed(1_700_012_345);
+        let msg = b"GET /?a=1".to_vec();
+        let samples = [Sample {
+            tag: siphash24(&key, &msg),
+            msg,
+        }];
+        let found = recover_key(&space, &samples, 64).unwrap();
+        assert_eq!(found, key);
+
+        let inputs = colliding_inputs(&found, "k", 256, 10);
+        assert_eq!(inputs.len(), 10);
+        let bucket = siphash24(&key, inputs[0].as_bytes()) & 255;
+        assert!(inputs
+            .iter()
+            .all(|s| siphash24(&key, s.as_bytes()) & 255 == bucket));
+    }
+}
@@ -0,0 +1,110 @@
+use siphash_weak_key::{
+    colliding_inputs, key_from_seed, mac, recover_key, required_samples, siphash24, Sample, WeakKey,
+};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+fn human(secs: f64) -> String {
+    const UNITS: [(f64, &str); 5] = [
+        (365.0 * 86400.0, "years"),
+        (86400.0, "days"),
+        (3600.0, "hours"),
+        (60.0, "minutes"),
+        (1.0, "seconds"),
+    ];
+    match UNITS.iter().find(|(s, _)| secs >= *s) {
+        Some((s, unit)) if secs / s >= 1e6 => format!("{:.1e} {unit}", secs / s),
+        Some((s, unit)) => format!("{:.1} {unit}", secs / s),
+        None => format!("{:.3} seconds", secs),
+    }
+}
+
+fn samples(key: &[u8; 16], n: usize, tag_bits: u32) -> Vec<Sample> {
+    (0..n)
+        .map(|i| {
+            let msg = format!("session={i}").into_bytes();
+            let tag = mac(key, &msg, tag_bits);
+            Sample { msg, tag }
+        })
+        .collect()
+}
+
+fn attack(name: &str, space: WeakKey, key: [u8; 16], tag_bits: u32) -> [u8; 16] {
+    let n = required_samples(&space, tag_bits);
+    let start = Instant::now();
+    let found = recover_key(&space, &samples(&key, n, tag_bits), tag_bits).unwrap();
+    println!(
+        "{name}: {:.0} bits entropy, {n} pairs of {tag_bits} bits, key found in {:?}",
+        space.entropy(),
+        start.elapsed()
+    );
+    assert_eq!(found, key);
+    found
+}
+
+// Max bucket length of a chained table indexed by the hash low bits.
+fn max_chain(key: &[u8; 16], inputs: &[String], buckets: u64) -> usize {
+    let mut table = vec![0; buckets as usize];
+    for s in inputs {
+        table[(siphash24(key, s.as_bytes()) & (buckets - 1)) as usize] += 1;
+    }
+    table.into_iter().max().unwrap_or_default()
+}
+
+fn main() {
+    // Key search throughput on this machine
+    let bits = 24;
+    let space = WeakKey::LowEntropy { bits };
+    let probe = samples(&[0xff; 16], 1, 64);
+    let start = Instant::now();
+    assert!(recover_key(&space, &probe, 64).is_none());
+    let rate = (1_u64 << bits) as f64 / start.elapsed().as_secs_f64();
+    println!("Throughput: {:.1} M keys/s", rate / 1e6);
+    println!("Expected time to exhaust the key space:");
+    for bits in [16, 24, 32, 40, 48, 56, 64, 128] {
+        let secs = 2_f64.powi(bits) / rate;
+        let feasible = if secs < 86400.0 {
+            "practical"
+        } else if secs < 1e3 * 365.0 * 86400.0 {
+            "feasible with a cluster"
+        } else {
+            "infeasible"
+        };
+        println!("  {bits:>3} bits: {:>20} ({feasible})", human(secs));
+    }
+
+    attack("Constant key", WeakKey::Constant([0; 16]), [0; 16], 64);
+
+    let space = WeakKey::LowEntropy { bits: 26 };
+    let key = space.key(rand::random::<u64>() % space.size());
+    attack("32 bits MAC, 26 bits key", space, key, 32);
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs();
+    let window = 30 * 86400;
+    let space = WeakKey::Timestamp {
+        start: now - window,
+        window,
+    };
+    let key = key_from_seed(now - 12345);
+    let key = attack("Key seeded with boot time (last month)", space, key, 64);
+
+    // HashDoS against a table with 2^12 buckets
+    let buckets = 1 << 12;
+    let n = 1000;
+    let start = Instant::now();
+    let evil = colliding_inputs(&key, "id=", buckets, n);
+    println!(
+        "HashDoS: {n} colliding inputs crafted in {:?}",
+        start.elapsed()
+    );
+    let honest: Vec<_> = (0..n)
+        .map(|_| format!("id={}", rand::random::<u64>()))
+        .collect();
+    println!(
+        "  max chain length: {} (honest inputs), {} (crafted inputs)",
+        max_chain(&key, &honest, buckets),
+        max_chain(&key, &evil, buckets)
+    );
+}
@@ -0,0 +1,180 @@
+//! Attacks against sketches using the public [`ItemHash::Fnv1a32`].
+//!
+//! The attacker knows the construction and the parameters, and at most sees
+//! the sketch state. Against a keyed sketch the same items behave as random
+//! ones.
+
+use crate::{bloom::Bloom, fnv1a32, hll::HyperLogLog, ItemHash};
+use birthday_paradox::{find_collision, Collision};
+
+const PUBLIC: ItemHash = ItemHash::Fnv1a32;
+
+fn items(prefix: &str) -> impl Iterator<Item = String> + '_ {
+    (0_u64..).map(move |i| format!("{prefix}{i}"))
+}
+
+/// Candidates reported as members by the filter with the published `bits`.
+pub fn leak_members<T: AsRef<[u8]>>(
+    bits: &[bool],
+    hashes: usize,
+    candidates: impl IntoIterator<Item = T>,
+) -> Vec<T> {
+    let view = Bloom::from_bits(bits.to_vec(), hashes, PUBLIC);
+    candidates
+        .into_iter()
+        .filter(|c| view.contains(c.as_ref()))
+        .collect()
+}
+
+/// Two items with the same hash, thus the same positions in any filter.
+///
+/// Once one is inserted, the other is reported as member as well.
+pub fn colliding_pair(prefix: &str) -> Collision<String> {
+    find_collision(items(prefix), |item| fnv1a32(item.as_bytes())).expect("infinite inputs")
+}
+
+/// Items whose insertion into the filter with the published `bits` makes
+/// `target` a member, without inserting it.
+pub fn cover(bits: &[bool], hashes: usize, target: &[u8], prefix: &str) -> Vec<String> {
+    let view = Bloom::from_bits(bits.to_vec(), hashes, PUBLIC);
+    let mut missing: Vec<_> = view.indices(target).filter(|&i| !bits[i]).collect();
+    let mut found = Vec::new();
+    for item in items(prefix) {
+        if missing.is_empty() {
+            break;
+        }
+        let indices: Vec<_> = view.indices(item.as_bytes()).collect();
+        if missing.iter().any(|i| indices.contains(i)) {
+            missing.retain(|i| !indices.contains(i));
+            found.push(item);
+        }
+    }
+    found
+}
+
+/// `count` items setting as many bits as possible in the filter with the
+/// published `bits`, each the best of `tries` candidates.
+pub fn pollute(
+    bits: &[bool],
+    hashes: usize,
+    count: usize,
+    tries: usize,
+    prefix: &str,
+) -> Vec<String> {
+    let mut view = Bloom::from_bits(bits.to_vec(), hashes, PUBLIC);
+    let mut candidates = items(prefix);
+    (0..count)
+        .map(|_| {
+            let item = candidates
+                .by_ref()
+                .take(tries)
+                .max_by_key(|item| {
+                    let mut indices: Vec<_> = view.indices(item.as_bytes()).collect();
+                    indices.sort_unstable();
+                    indices.dedup();
+                    indices.iter().filter(|&&i| !view.bits()[i]).count()
+                })
+                .expect("infinite inputs");
+            view.insert(item.as_bytes());
+            item
+        })
+        .collect()
+}
+
+/// One item per register with the given `rank`, which makes the estimate
+/// ≈ `0.72·m·2^rank` with `m = 2^precision` items.
+pub fn inflate(precision: u32, rank: u8, prefix: &str) -> Vec<String> {
+    let view = HyperLogLog::new(precision, PUBLIC);
+    let mut found = vec![None; 1 << precision];
+    let mut missing = found.len();
+    for item in items(prefix) {
+        let (index, r) = view.position(item.as_bytes());
+        if r == rank && found[index].is_none() {
+            found[index] = Some(item);
+            missing -= 1;
+            if missing == 0 {
+                break;
+            }
+        }
+    }
+    found.into_iter().flatten().collect()
+}
+
+/// `count` distinct items falling into the first register with rank 1,
+/// estimated as a single item.
+pub fn deflate(precision: u32, count: usize, prefix: &str) -> Vec<String> {
+    let view = HyperLogLog::new(precision, PUBLIC);
+    items(prefix)
+        .filter(|item| view.position(item.as_bytes()) == (0, 1))
+        .take(count)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::OsRng, Rng};
+
+    const BITS: usize = 1 << 14;
+    const HASHES: usize = 7;
+
+    fn filters() -> [Bloom; 2] {
+        [PUBLIC, ItemHash::SipHash(OsRng.gen())].map(|hasher| {
+            let mut filter = Bloom::new(BITS, HASHES, hasher);
+            (0..500).for_each(|i| filter.insert(format!("user{i}").as_bytes()));
+            filter
+        })
+    }
+
+    #[test]
+    fn members_and_collisions() {
+        let [public, keyed] = filters();
+        let candidates = (0..10_000).map(|i| format!("user{i}"));
+        let leaked = leak_members(public.bits(), HASHES, candidates.clone());
+        assert!(leaked.len() >= 500 && leaked.len() < 520);
+        assert!(leak_members(keyed.bits(), HASHES, candidates).len() < 100);
+
+        let Collision { a, b, .. } = colliding_pair("url");
+        for mut filter in [public, keyed] {
+            let positives = filter.contains(b.as_bytes());
+            filter.insert(a.as_bytes());
+            assert_eq!(
+                filter.contains(b.as_bytes()),
+                positives || *filter.hasher() == PUBLIC
+            );
+        }
+    }
+
+    #[test]
+    fn forge_membership() {
+        for mut filter in filters() {
+            let items = cover(filter.bits(), HASHES, b"admin", "x");
+            assert!(items.len() <= HASHES);
+            items.iter().for_each(|item| filter.insert(item.as_bytes()));
+            assert_eq!(filter.contains(b"admin"), *filter.hasher() == PUBLIC);
+        }
+    }
+
+    #[test]
+    fn sketch_pollution() {
+        let [public, keyed] = filters().map(|mut filter| {
+            let items = pollute(filter.bits(), HASHES, 2000, 64, "p");
+            items.iter().for_each(|item| filter.insert(item.as_bytes()));
+            filter.false_positive_rate()
+        });
+        // About 40% against 5%
+        assert!(public > 4.0 * keyed);
+
+        let keyed = ItemHash::SipHash(OsRng.gen());
+        for (items, expected) in [(inflate(8, 8, "i"), 47_000.0), (deflate(8, 5000, "d"), 1.0)] {
+            let [mut public, mut keyed] = [PUBLIC, keyed].map(|h| HyperLogLog::new(8, h));
+            for item in &items {
+                public.insert(item.as_bytes());
+                keyed.insert(item.as_bytes());
+            }
+            let n = items.len() as f64;
+            assert!((public.estimate() - expected).abs() < expected * 0.1 + 1.0);
+            assert!((keyed.estimate() - n).abs() < n * 0.3);
+        }
+    }
+}
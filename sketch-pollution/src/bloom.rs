@@ -0,0 +1,89 @@
+//! Bloom filter.
+
+use crate::ItemHash;
+
+/// Bloom filter with `k` positions per item derived by double hashing.
+#[derive(Debug, Clone)]
+pub struct Bloom {
+    bits: Vec<bool>,
+    hashes: usize,
+    hasher: ItemHash,
+}
+
+impl Bloom {
+    /// Empty filter of `bits` bits, setting `hashes` bits per item.
+    pub fn new(bits: usize, hashes: usize, hasher: ItemHash) -> Self {
+        Self::from_bits(vec![false; bits], hashes, hasher)
+    }
+
+    /// Filter with the given (e.g. published) `bits`.
+    pub fn from_bits(bits: Vec<bool>, hashes: usize, hasher: ItemHash) -> Self {
+        Bloom {
+            bits,
+            hashes,
+            hasher,
+        }
+    }
+
+    pub fn bits(&self) -> &[bool] {
+        &self.bits
+    }
+
+    pub fn hashes(&self) -> usize {
+        self.hashes
+    }
+
+    pub fn hasher(&self) -> &ItemHash {
+        &self.hasher
+    }
+
+    /// Positions of `item`, `h1 + i·h2` with `h2` derived from `h1`.
+    ///
+    /// All the positions are a function of the single item hash.
+    pub fn indices(&self, item: &[u8]) -> impl Iterator<Item = usize> {
+        let m = self.bits.len() as u64;
+        let h1 = self.hasher.hash(item);
+        let h2 = h1.wrapping_mul(0x9e37_79b9_7f4a_7c15).rotate_left(31) | 1;
+        (0..self.hashes as u64).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % m) as usize)
+    }
+
+    pub fn insert(&mut self, item: &[u8]) {
+        for i in self.indices(item).collect::<Vec<_>>() {
+            self.bits[i] = true;
+        }
+    }
+
+    pub fn contains(&self, item: &[u8]) -> bool {
+        self.indices(item).all(|i| self.bits[i])
+    }
+
+    /// Fraction of set bits.
+    pub fn fill_ratio(&self) -> f64 {
+        self.bits.iter().filter(|&&b| b).count() as f64 / self.bits.len() as f64
+    }
+
+    /// False positive probability for a random item.
+    pub fn false_positive_rate(&self) -> f64 {
+        self.fill_ratio().powi(self.hashes as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::OsRng, Rng};
+
+    #[test]
+    fn no_false_negatives() {
+        let mut filter = Bloom::new(1 << 14, 7, ItemHash::SipHash(OsRng.gen()));
+        let items: Vec<_> = (0..1000).map(|i| format!("item{i}")).collect();
+        items.iter().for_each(|item| filter.insert(item.as_bytes()));
+        assert!(items.iter().all(|item| filter.contains(item.as_bytes())));
+        // Optimal load, expected false positive rate is ≈ 0.6%
+        let positives = (0..10_000)
+            .filter(|i| filter.contains(format!("other{i}").as_bytes()))
+            .count();
+        assert!(positives < 200);
+        assert!((positives as f64 / 10_000.0 - filter.false_positive_rate()).abs() < 0.01);
+    }
+}
@@ -0,0 +1,53 @@
+//! Probabilistic sketches built over short non keyed hashes.
+//!
+//! Bloom filters and HyperLogLog counters map each item to a few positions
+//! through a hash function, trusting the items to be spread uniformly. This
+//! holds for honest inputs, but when the hash is public (and short, e.g. the
+//! ubiquitous 32 bits FNV-1a) anyone can compute the positions of any item and
+//! choose the inputs accordingly:
+//!
+//! - a published Bloom filter (e.g. BIP37 SPV filters) leaks its members to
+//!   whoever can enumerate the plausible candidates;
+//! - two items with the same hash, found with the `birthday-paradox` engine,
+//!   are indistinguishable to the filter. FNV-1a spreads counters poorly,
+//!   thus the search takes up to a few millions evaluations instead of the
+//!   ≈ 2^16 of a random function, still a matter of seconds;
+//! - a few crafted items make a chosen item a member without ever inserting
+//!   it, or saturate the filter with far fewer insertions than random items;
+//! - a HyperLogLog counter can be inflated by one item per register, or kept
+//!   close to zero while flooded with distinct items.
+//!
+//! Keying the hash (here with SipHash, see the `siphash-weak-key` crate)
+//! denies the attacker the positions and turns the crafted items back into
+//! random ones.
+
+use siphash_weak_key::{siphash24, Key};
+
+pub mod attacks;
+pub mod bloom;
+pub mod hll;
+
+/// 32 bits FNV-1a.
+pub fn fnv1a32(data: &[u8]) -> u32 {
+    data.iter().fold(0x811c_9dc5, |h, &b| {
+        (h ^ b as u32).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// Hash mapping the items to the sketch positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemHash {
+    /// 32 bits FNV-1a, public and short.
+    Fnv1a32,
+    /// SipHash-2-4 with a secret key.
+    SipHash(Key),
+}
+
+impl ItemHash {
+    pub fn hash(&self, item: &[u8]) -> u64 {
+        match self {
+            ItemHash::Fnv1a32 => fnv1a32(item) as u64,
+            ItemHash::SipHash(key) => siphash24(key, item),
+        }
+    }
+}
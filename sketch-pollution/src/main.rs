@@ -0,0 +1,78 @@
+use rand::{rngs::OsRng, Rng};
+use sketch_pollution::{attacks, bloom::Bloom, hll::HyperLogLog, ItemHash};
+
+fn main() {
+    let (bits, hashes) = (1 << 16, 7);
+    let hashers = [
+        ("FNV-1a", ItemHash::Fnv1a32),
+        ("SipHash", ItemHash::SipHash(OsRng.gen())),
+    ];
+
+    // Phone numbers of the registered users
+    let users: Vec<_> = (0..2000)
+        .map(|_| format!("+39 3{:09}", OsRng.gen_range(0..1_000_000_000)))
+        .collect();
+    let c = attacks::colliding_pair("https://example.com/");
+    println!("Colliding items after {} hashes: {} {}", c.count, c.a, c.b);
+
+    println!(
+        "Bloom filter, {bits} bits, {hashes} hashes, {} users",
+        users.len()
+    );
+    for (name, hasher) in hashers {
+        let mut filter = Bloom::new(bits, hashes, hasher);
+        users.iter().for_each(|u| filter.insert(u.as_bytes()));
+
+        let candidates =
+            users.iter().take(100).cloned().chain(
+                (0..10_000).map(|_| format!("+39 3{:09}", OsRng.gen_range(0..1_000_000_000))),
+            );
+        let leaked = attacks::leak_members(filter.bits(), hashes, candidates).len();
+
+        let mut forged = filter.clone();
+        let cover = attacks::cover(forged.bits(), hashes, b"admin", "x");
+        cover.iter().for_each(|i| forged.insert(i.as_bytes()));
+
+        let mut polluted = filter.clone();
+        let items = attacks::pollute(polluted.bits(), hashes, 5000, 64, "p");
+        items.iter().for_each(|i| polluted.insert(i.as_bytes()));
+
+        let mut collided = filter.clone();
+        collided.insert(c.a.as_bytes());
+
+        println!("  {name}:");
+        println!("    candidates reported as members: {leaked}/10100 (100 users)");
+        println!(
+            "    'admin' member after {} insertions: {}",
+            cover.len(),
+            forged.contains(b"admin")
+        );
+        println!(
+            "    second colliding item member: {}",
+            collided.contains(c.b.as_bytes())
+        );
+        println!(
+            "    false positive rate {:.4} -> {:.4} after {} insertions",
+            filter.false_positive_rate(),
+            polluted.false_positive_rate(),
+            items.len()
+        );
+    }
+
+    let precision = 10;
+    println!("HyperLogLog, {} registers", 1 << precision);
+    let inflate = attacks::inflate(precision, 10, "i");
+    let deflate = attacks::deflate(precision, 10_000, "d");
+    for (name, hasher) in hashers {
+        println!("  {name}:");
+        for items in [&inflate, &deflate] {
+            let mut hll = HyperLogLog::new(precision, hasher);
+            items.iter().for_each(|i| hll.insert(i.as_bytes()));
+            println!(
+                "    {:>6} items estimated as {:.0}",
+                items.len(),
+                hll.estimate()
+            );
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Generic attacks against sponges with undersized capacity or ambiguous
+//! padding.
+//!
+//! None of the attacks exploits the permutation structure: they only depend on
+//! the capacity size and on the padding rule.
+
+use crate::{keccak_f200, Padding, Sponge, State, WIDTH};
+use birthday_paradox::find_collision;
+
+/// Pair of colliding two blocks prefixes.
+#[derive(Debug, Clone)]
+pub struct InnerCollision {
+    pub prefix1: Vec<u8>,
+    pub prefix2: Vec<u8>,
+    /// Permutation evaluations required by the birthday search.
+    pub evaluations: usize,
+}
+
+/// Find two prefixes leading to the same full internal state.
+///
+/// 1. Absorb distinct single blocks `B_i` until two states `S_i`, `S_j` share
+///    the capacity part (≈ 2^(c/2) permutations).
+/// 2. The rate parts are directly controlled by the next message block, thus
+///    `B_i || 0` and `B_j || (S_i ⊕ S_j)` lead to the same state.
+///
+/// Since the states are equal, `H(prefix1 || s) = H(prefix2 || s)` for any
+/// suffix `s`.
+///
+/// Panics if the rate is less than 4 bytes.
+pub fn inner_collision(sponge: &Sponge) -> InnerCollision {
+    let rate = sponge.rate();
+    assert!(rate >= 4, "Rate too small");
+    let block = |i: &u32| {
+        let mut block = vec![0; rate];
+        block[..4].copy_from_slice(&i.to_le_bytes());
+        block
+    };
+    let absorb = |i: &u32| {
+        let mut state = [0; WIDTH];
+        sponge.absorb(&mut state, &block(i));
+        state
+    };
+
+    let c = find_collision(0_u32.., |i| absorb(i)[rate..].to_vec()).expect("Capacity too large");
+    let (s1, s2) = (absorb(&c.a), absorb(&c.b));
+
+    let mut prefix1 = block(&c.a);
+    prefix1.extend(vec![0; rate]);
+    let mut prefix2 = block(&c.b);
+    prefix2.extend(s1[..rate].iter().zip(&s2[..rate]).map(|(a, b)| a ^ b));
+
+    InnerCollision {
+        prefix1,
+        prefix2,
+        evaluations: c.count,
+    }
+}
+
+/// Recover the state from which `output` was squeezed.
+///
+/// The first output block discloses the rate part, the remaining capacity
+/// bytes are exhaustively searched (2^c permutations) and the candidates are
+/// checked against the rest of the output. At least `capacity` bytes past the
+/// first block are needed to get a unique solution.
+pub fn recover_state(sponge: &Sponge, output: &[u8]) -> Option<State> {
+    let (rate, capacity) = (sponge.rate(), sponge.capacity());
+    assert!(capacity <= 4, "Capacity too large");
+    assert!(output.len() > rate, "Output too short");
+
+    let mut state = [0; WIDTH];
+    state[..rate].copy_from_slice(&output[..rate]);
+    (0..1_u64 << (8 * capacity)).find_map(|guess| {
+        state[rate..].copy_from_slice(&guess.to_le_bytes()[..capacity]);
+        let mut next = state;
+        keccak_f200(&mut next);
+        let tail = sponge.squeeze(next, output.len() - rate);
+        (tail == output[rate..]).then_some(state)
+    })
+}
+
+/// Length extension from a recovered state.
+///
+/// Given the state after absorbing `pad(m)`, returns the `len` bytes hash of
+/// `m || padding || suffix`, where `padding` is [`Sponge::padding_for`] applied
+/// to `m` length.
+pub fn extend(sponge: &Sponge, mut state: State, suffix: &[u8], len: usize) -> Vec<u8> {
+    sponge.absorb(&mut state, &sponge.pad(suffix));
+    sponge.squeeze(state, len)
+}
+
+/// Second preimage for free when the padding is ambiguous.
+///
+/// With [`Padding::Zeros`], `msg` and `msg || 0` hash to the same value
+/// (unless `msg` ends on a block boundary).
+pub fn padding_collision(sponge: &Sponge, msg: &[u8]) -> Option<Vec<u8>> {
+    let mut other = msg.to_vec();
+    other.push(0);
+    (sponge.padding() == Padding::Zeros && sponge.pad(msg) == sponge.pad(&other)).then_some(other)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inner_collision_extends_to_any_suffix() {
+        let sponge = Sponge::new(WIDTH - 3, Padding::Pad10Star1);
+        let c = inner_collision(&sponge);
+        assert_ne!(c.prefix1, c.prefix2);
+        for suffix in [&b""[..], b"x", b"some longer suffix spanning blocks"] {
+            let m1 = [&c.prefix1[..], suffix].concat();
+            let m2 = [&c.prefix2[..], suffix].concat();
+            assert_eq!(sponge.hash(&m1, 32), sponge.hash(&m2, 32));
+        }
+    }
+
+    #[test]
+    fn keyed_hash_forgery() {
+        let sponge = Sponge::new(WIDTH - 2, Padding::Pad10Star1);
+        let (key, msg) = (b"secret key", b"amount=10");
+        let keyed = [&key[..], msg].concat();
+        let tag = sponge.hash(&keyed, 2 * sponge.rate());
+
+        let state = recover_state(&sponge, &tag).unwrap();
+        let forged = extend(&sponge, state, b"0000", tag.len());
+
+        let mut extended = keyed.clone();
+        extended.extend(sponge.padding_for(keyed.len()));
+        extended.extend(b"0000");
+        assert_eq!(sponge.hash(&extended, tag.len()), forged);
+    }
+
+    #[test]
+    fn zero_padding_is_ambiguous() {
+        let sponge = Sponge::new(8, Padding::Zeros);
+        let other = padding_collision(&sponge, b"pay 1").unwrap();
+        assert_eq!(sponge.hash(b"pay 1", 16), sponge.hash(&other, 16));
+
+        let sponge = Sponge::new(8, Padding::Pad10Star1);
+        assert!(padding_collision(&sponge, b"pay 1").is_none());
+    }
+}
@@ -0,0 +1,206 @@
+//! Toy Keccak-like sponge.
+//!
+//! The sponge construction operates on a `b = r + c` bits state by
+//! alternately xoring `r` bits (the *rate*) of message into the state and
+//! applying a fixed permutation. The output is then squeezed `r` bits at a
+//! time. The remaining `c` bits (the *capacity*) are never directly touched by
+//! the input nor exposed by the output, and the generic security level of the
+//! construction is `c/2` bits.
+//!
+//! Here the permutation is Keccak-f\[200\] (the 8 bits lanes member of the
+//! Keccak family), thus `b = 200` and rate and capacity can be tuned to be
+//! small enough to show in practice what happens when:
+//! - the capacity is too small: inner collisions are found via birthday search
+//!   after ≈ 2^(c/2) permutations, then extended to full collisions for any
+//!   suffix; the inner state is recovered from the output after 2^c
+//!   permutations, allowing to forge keyed hashes via length extension.
+//! - the padding is ambiguous: messages differing by trailing zeros collide.
+//!
+//! See the [`attacks`] module.
+
+pub mod attacks;
+
+/// Permutation width in bytes.
+pub const WIDTH: usize = 25;
+
+/// Sponge state, as 5x5 lanes of 8 bits (lane `(x, y)` at index `x + 5y`).
+pub type State = [u8; WIDTH];
+
+const ROUNDS: usize = 18;
+
+const ROUND_CONSTANTS: [u8; ROUNDS] = [
+    0x01, 0x82, 0x8a, 0x00, 0x8b, 0x01, 0x81, 0x09, 0x8a, 0x88, 0x09, 0x0a, 0x8b, 0x8b, 0x89, 0x03,
+    0x02, 0x80,
+];
+
+// Rotation offsets indexed by `x + 5y`, already reduced modulo the lane size.
+const ROTATIONS: [u32; WIDTH] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+/// Keccak-f\[200\] permutation.
+pub fn keccak_f200(a: &mut State) {
+    for rc in ROUND_CONSTANTS {
+        // θ
+        let mut c = [0; 5];
+        for x in 0..5 {
+            c[x] = a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                a[x + 5 * y] ^= d;
+            }
+        }
+        // ρ and π
+        let mut b = [0; WIDTH];
+        for x in 0..5 {
+            for y in 0..5 {
+                let i = x + 5 * y;
+                b[y + 5 * ((2 * x + 3 * y) % 5)] = a[i].rotate_left(ROTATIONS[i] % 8);
+            }
+        }
+        // χ
+        for y in 0..5 {
+            for x in 0..5 {
+                a[x + 5 * y] = b[x + 5 * y] ^ (!b[(x + 1) % 5 + 5 * y] & b[(x + 2) % 5 + 5 * y]);
+            }
+        }
+        // ι
+        a[0] ^= rc;
+    }
+}
+
+/// Message padding rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Padding {
+    /// Keccak multi-rate padding: `1 0* 1`.
+    ///
+    /// Always appends at least one byte, thus is injective.
+    Pad10Star1,
+    /// Append zeros up to the next block boundary.
+    ///
+    /// Ambiguous: `m` and `m || 0` are padded to the same string.
+    Zeros,
+}
+
+/// Sponge over Keccak-f\[200\] with byte granular rate.
+#[derive(Debug, Clone)]
+pub struct Sponge {
+    rate: usize,
+    padding: Padding,
+}
+
+impl Sponge {
+    /// Construct a sponge with the given rate (in bytes).
+    ///
+    /// Panics if the rate is not in `1..WIDTH`.
+    pub fn new(rate: usize, padding: Padding) -> Self {
+        assert!((1..WIDTH).contains(&rate), "Invalid rate");
+        Sponge { rate, padding }
+    }
+
+    /// Rate in bytes.
+    pub fn rate(&self) -> usize {
+        self.rate
+    }
+
+    /// Capacity in bytes.
+    pub fn capacity(&self) -> usize {
+        WIDTH - self.rate
+    }
+
+    pub fn padding(&self) -> Padding {
+        self.padding
+    }
+
+    /// Padding bytes appended to a message of `len` bytes.
+    pub fn padding_for(&self, len: usize) -> Vec<u8> {
+        let rem = len % self.rate;
+        match self.padding {
+            Padding::Pad10Star1 => {
+                let mut pad = vec![0; self.rate - rem];
+                pad[0] |= 0x01;
+                *pad.last_mut().unwrap() |= 0x80;
+                pad
+            }
+            Padding::Zeros if rem == 0 && len != 0 => Vec::new(),
+            Padding::Zeros => vec![0; self.rate - rem],
+        }
+    }
+
+    /// Padded message.
+    pub fn pad(&self, msg: &[u8]) -> Vec<u8> {
+        let mut padded = msg.to_vec();
+        padded.extend(self.padding_for(msg.len()));
+        padded
+    }
+
+    /// Absorb full blocks into the state.
+    ///
+    /// Panics if `blocks` length is not a multiple of the rate.
+    pub fn absorb(&self, state: &mut State, blocks: &[u8]) {
+        assert!(blocks.len().is_multiple_of(self.rate), "Partial block");
+        for block in blocks.chunks(self.rate) {
+            state.iter_mut().zip(block).for_each(|(s, b)| *s ^= b);
+            keccak_f200(state);
+        }
+    }
+
+    /// Squeeze `len` bytes out of the state.
+    pub fn squeeze(&self, mut state: State, len: usize) -> Vec<u8> {
+        let mut out = Vec::with_capacity(len);
+        loop {
+            let n = self.rate.min(len - out.len());
+            out.extend_from_slice(&state[..n]);
+            if out.len() == len {
+                return out;
+            }
+            keccak_f200(&mut state);
+        }
+    }
+
+    /// Hash `msg` to `len` bytes.
+    pub fn hash(&self, msg: &[u8], len: usize) -> Vec<u8> {
+        let mut state = [0; WIDTH];
+        self.absorb(&mut state, &self.pad(msg));
+        self.squeeze(state, len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permutation_is_injective_on_samples() {
+        let mut outputs = std::collections::HashSet::new();
+        for i in 0..1000_u16 {
+            let mut state = [0; WIDTH];
+            state[..2].copy_from_slice(&i.to_le_bytes());
+            keccak_f200(&mut state);
+            assert!(outputs.insert(state));
+        }
+    }
+
+    #[test]
+    fn padding_rules() {
+        let sponge = Sponge::new(4, Padding::Pad10Star1);
+        assert_eq!(sponge.pad(b""), [0x01, 0, 0, 0x80]);
+        assert_eq!(sponge.pad(b"abc"), [b'a', b'b', b'c', 0x81]);
+        assert_eq!(sponge.pad(b"abcd").len(), 8);
+
+        let sponge = Sponge::new(4, Padding::Zeros);
+        assert_eq!(sponge.pad(b""), [0; 4]);
+        assert_eq!(sponge.pad(b"abcd"), *b"abcd");
+        assert_eq!(sponge.pad(b"ab"), sponge.pad(b"ab\0"));
+    }
+
+    #[test]
+    fn squeeze_is_prefix_consistent() {
+        let sponge = Sponge::new(10, Padding::Pad10Star1);
+        let long = sponge.hash(b"hello", 64);
+        assert_eq!(sponge.hash(b"hello", 7), long[..7]);
+        assert_ne!(sponge.hash(b"hello!", 64), long);
+    }
+}
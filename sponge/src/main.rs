@@ -0,0 +1,66 @@
+use sponge::{
+    attacks::{extend, inner_collision, padding_collision, recover_state},
+    Padding, Sponge, WIDTH,
+};
+
+fn main() {
+    // 24 bits capacity: ≈ 2^12 permutations to find an inner collision
+    let sponge = Sponge::new(WIDTH - 3, Padding::Pad10Star1);
+    println!(
+        "Sponge rate: {} bits, capacity: {} bits",
+        8 * sponge.rate(),
+        8 * sponge.capacity()
+    );
+
+    let c = inner_collision(&sponge);
+    println!("Inner collision after {} permutations", c.evaluations);
+    println!("  prefix1: {}", hex::encode(&c.prefix1));
+    println!("  prefix2: {}", hex::encode(&c.prefix2));
+    let suffix = b"same suffix, same hash";
+    let h1 = sponge.hash(&[&c.prefix1[..], suffix].concat(), 32);
+    let h2 = sponge.hash(&[&c.prefix2[..], suffix].concat(), 32);
+    println!("  H(prefix1 || suffix): {}", hex::encode(&h1));
+    println!("  H(prefix2 || suffix): {}", hex::encode(&h2));
+    assert_eq!(h1, h2);
+
+    // Keyed hash used as MAC: H(key || msg)
+    let key = rand::random::<[u8; 16]>();
+    let msg = b"user=alice&role=user";
+    let keyed = [&key[..], msg].concat();
+    let tag = sponge.hash(&keyed, 2 * sponge.rate());
+    println!("MAC tag: {}", hex::encode(&tag));
+
+    let state = recover_state(&sponge, &tag).expect("State not found");
+    println!(
+        "Recovered capacity after ≤ 2^{} permutations: {}",
+        8 * sponge.capacity(),
+        hex::encode(&state[sponge.rate()..])
+    );
+    let suffix = b"&role=admin";
+    let forged = extend(&sponge, state, suffix, tag.len());
+    let mut extended = msg.to_vec();
+    extended.extend(sponge.padding_for(keyed.len()));
+    extended.extend(suffix);
+    println!("Forged message: {}", String::from_utf8_lossy(&extended));
+    println!("Forged tag: {}", hex::encode(&forged));
+    assert_eq!(
+        sponge.hash(&[&key[..], &extended].concat(), tag.len()),
+        forged
+    );
+
+    // Ambiguous padding
+    let sponge = Sponge::new(16, Padding::Zeros);
+    let msg = b"transfer 100";
+    let other = padding_collision(&sponge, msg).unwrap();
+    println!("Zero padding collision:");
+    println!(
+        "  H({}): {}",
+        hex::encode(msg),
+        hex::encode(sponge.hash(msg, 16))
+    );
+    println!(
+        "  H({}): {}",
+        hex::encode(&other),
+        hex::encode(sponge.hash(&other, 16))
+    );
+}
@@ -0,0 +1,49 @@
+//! Schnorr group.
+//!
+//! Subgroup of quadratic residues of `Z_p*`, with `p = 2q + 1` a safe prime.
+
+use hash_to_group::zp::Subgroup;
+use num_bigint::BigUint;
+
+/// Prime order `q` subgroup of `Z_p*`.
+#[derive(Debug, Clone)]
+pub struct Group {
+    pub p: BigUint,
+    pub q: BigUint,
+    pub g: BigUint,
+}
+
+impl Default for Group {
+    /// 256-bit safe prime group ([`Subgroup::safe_prime_256`]) with a
+    /// generator derived by hashing to the group (see the `hash-to-group`
+    /// crate).
+    fn default() -> Self {
+        let group = Subgroup::safe_prime_256();
+        let g = group.generator(b"vrf-generator", 0);
+        let Subgroup { p, q } = group;
+        Group { p, q, g }
+    }
+}
+
+impl Group {
+    pub fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        (a * b) % &self.p
+    }
+
+    pub fn pow(&self, a: &BigUint, e: &BigUint) -> BigUint {
+        a.modpow(e, &self.p)
+    }
+
+    /// Check if `a` is a non-identity element of the subgroup.
+    pub fn is_element(&self, a: &BigUint) -> bool {
+        let one = BigUint::from(1_u8);
+        a > &one && a < &self.p && self.pow(a, &self.q) == one
+    }
+
+    /// Hash `data` to a subgroup element.
+    ///
+    /// Nobody knows the discrete log of the result with respect to `g`.
+    pub fn hash_to_group(&self, data: &[u8]) -> BigUint {
+        Subgroup::new(self.p.clone(), self.q.clone()).hash_to_group(b"vrf-h2g", data)
+    }
+}
@@ -0,0 +1,201 @@
+//! Verifiable Random Function misuse PoC.
+//!
+//! A VRF is the public key version of a keyed hash: only the secret key holder
+//! can compute the output for a given input, but everyone can verify that the
+//! output is correct using the public key and a proof. Crucially the output is
+//! *unique*: for a given public key and input there is exactly one valid output.
+//!
+//! A common mistake is to use a plain keyed hash `H(sk || input)` as a "VRF".
+//! With no proof and no binding to a public key, nobody can check the output and
+//! the key holder can grind secrets until the output is a favorable one. For
+//! example in a leader election lottery (win if the output is below some
+//! threshold) the cheater wins every single slot.
+//!
+//! The fix is a proper VRF. Here we provide a toy version with the same
+//! structure as ECVRF (RFC 9381) instantiated over a Schnorr group (prime order
+//! subgroup of `Z_p*`):
+//!
+//! - `pk = g^sk`
+//! - `Γ = hash_to_group(input)^sk`
+//! - `π = (Γ, DLEQ proof that log_g(pk) = log_H(Γ))`
+//! - `output = Hash(Γ)`
+//!
+//! Since `Γ` is uniquely determined by `pk` and `input`, once `pk` is published
+//! there is nothing left to grind.
+
+pub mod group;
+
+use group::Group;
+use num_bigint::{BigUint, RandBigInt};
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+
+/// VRF output.
+pub type Output = [u8; 32];
+
+fn sha256(chunks: &[&[u8]]) -> Output {
+    let mut hasher = Sha256::new();
+    chunks.iter().for_each(|c| hasher.update(c));
+    hasher.finalize().into()
+}
+
+/// Lottery win check.
+///
+/// A slot is won if the first output byte is less than `threshold`, i.e. with
+/// probability `threshold/256` for a uniform output.
+pub fn is_winner(output: &Output, threshold: u8) -> bool {
+    output[0] < threshold
+}
+
+/// Broken "VRF" construction.
+pub mod broken {
+    use super::*;
+
+    /// Output is computed as `H(sk || input)`. There is no proof.
+    pub fn evaluate(sk: &[u8], input: &[u8]) -> Output {
+        sha256(&[sk, input])
+    }
+
+    /// Output grinding.
+    ///
+    /// Nobody can check the output against a public key, thus the cheater just
+    /// tries random secrets until the output is a lottery winner.
+    ///
+    /// Returns the winning output and the number of attempts.
+    pub fn grind<R: RngCore>(rng: &mut R, input: &[u8], threshold: u8) -> (Output, usize) {
+        let mut sk = [0; 32];
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            rng.fill_bytes(&mut sk);
+            let output = evaluate(&sk, input);
+            if is_winner(&output, threshold) {
+                return (output, attempts);
+            }
+        }
+    }
+}
+
+/// VRF proof.
+#[derive(Debug, Clone)]
+pub struct Proof {
+    pub gamma: BigUint,
+    pub c: BigUint,
+    pub s: BigUint,
+}
+
+/// VRF secret key.
+pub struct SecretKey {
+    group: Group,
+    sk: BigUint,
+    pub pk: PublicKey,
+}
+
+/// VRF public key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey(pub BigUint);
+
+impl SecretKey {
+    pub fn generate<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let group = Group::default();
+        let sk = rng.gen_biguint_range(&BigUint::from(1_u8), &group.q);
+        let pk = PublicKey(group.pow(&group.g, &sk));
+        SecretKey { group, sk, pk }
+    }
+
+    /// Evaluate the VRF on `input`.
+    ///
+    /// Returns the output and the proof of correctness.
+    pub fn prove<R: RngCore + CryptoRng>(&self, rng: &mut R, input: &[u8]) -> (Output, Proof) {
+        let group = &self.group;
+        let h = group.hash_to_group(input);
+        let gamma = group.pow(&h, &self.sk);
+
+        // DLEQ proof: log_g(pk) = log_h(gamma)
+        let k = rng.gen_biguint_below(&group.q);
+        let u = group.pow(&group.g, &k);
+        let v = group.pow(&h, &k);
+        let c = challenge(group, &h, &self.pk.0, &gamma, &u, &v);
+        // s = k - c·sk mod q
+        let s = (&k + &group.q - (&c * &self.sk) % &group.q) % &group.q;
+
+        let output = gamma_to_output(&gamma);
+        (output, Proof { gamma, c, s })
+    }
+}
+
+impl PublicKey {
+    /// Verify the VRF `proof` for `input`.
+    ///
+    /// Returns the VRF output if the proof is valid.
+    pub fn verify(&self, input: &[u8], proof: &Proof) -> Option<Output> {
+        let group = Group::default();
+        if !group.is_element(&proof.gamma) || !group.is_element(&self.0) {
+            return None;
+        }
+        let h = group.hash_to_group(input);
+        // u = g^s·pk^c, v = h^s·gamma^c
+        let u = group.mul(
+            &group.pow(&group.g, &proof.s),
+            &group.pow(&self.0, &proof.c),
+        );
+        let v = group.mul(&group.pow(&h, &proof.s), &group.pow(&proof.gamma, &proof.c));
+        let c = challenge(&group, &h, &self.0, &proof.gamma, &u, &v);
+        (c == proof.c).then(|| gamma_to_output(&proof.gamma))
+    }
+}
+
+fn challenge(
+    group: &Group,
+    h: &BigUint,
+    pk: &BigUint,
+    gamma: &BigUint,
+    u: &BigUint,
+    v: &BigUint,
+) -> BigUint {
+    let chunks: Vec<_> = [&group.g, h, pk, gamma, u, v]
+        .iter()
+        .map(|x| x.to_bytes_be())
+        .collect();
+    let chunks: Vec<_> = chunks.iter().map(|c| c.as_slice()).collect();
+    BigUint::from_bytes_be(&sha256(&chunks)) % &group.q
+}
+
+fn gamma_to_output(gamma: &BigUint) -> Output {
+    sha256(&[b"vrf-output", &gamma.to_bytes_be()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn grinding_always_wins() {
+        for _ in 0..10 {
+            let (output, _) = broken::grind(&mut OsRng, b"slot-42", 16);
+            assert!(is_winner(&output, 16));
+        }
+    }
+
+    #[test]
+    fn vrf_prove_verify() {
+        let sk = SecretKey::generate(&mut OsRng);
+        let (output, proof) = sk.prove(&mut OsRng, b"slot-42");
+        assert_eq!(sk.pk.verify(b"slot-42", &proof), Some(output));
+        assert_eq!(sk.pk.verify(b"slot-43", &proof), None);
+    }
+
+    #[test]
+    fn vrf_output_is_unique() {
+        let sk = SecretKey::generate(&mut OsRng);
+        let (output1, _) = sk.prove(&mut OsRng, b"slot-42");
+        let (output2, mut proof) = sk.prove(&mut OsRng, b"slot-42");
+        assert_eq!(output1, output2);
+
+        // Tampering with gamma invalidates the proof
+        let group = Group::default();
+        proof.gamma = group.mul(&proof.gamma, &group.g);
+        assert_eq!(sk.pk.verify(b"slot-42", &proof), None);
+    }
+}
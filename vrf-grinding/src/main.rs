@@ -0,0 +1,38 @@
+use rand::rngs::OsRng;
+use vrf_grinding::{broken, is_winner, SecretKey};
+
+fn main() {
+    // Win probability is 16/256 for honest participants
+    let threshold = 16;
+    let slots = 100;
+
+    println!("Broken VRF: H(sk || input)");
+    let mut attempts = 0;
+    for slot in 0..slots {
+        let input = format!("epoch-seed-slot-{slot}");
+        let (_, count) = broken::grind(&mut OsRng, input.as_bytes(), threshold);
+        attempts += count;
+    }
+    println!(
+        "  Cheater won {slots}/{slots} slots (avg grinding attempts per slot: {})",
+        attempts / slots
+    );
+
+    println!("Toy VRF: ECVRF structure over a Schnorr group");
+    let sk = SecretKey::generate(&mut OsRng);
+    let mut wins = 0;
+    for slot in 0..slots {
+        let input = format!("epoch-seed-slot-{slot}");
+        let (output, proof) = sk.prove(&mut OsRng, input.as_bytes());
+        // Everybody can verify the output using the public key
+        let verified = sk.pk.verify(input.as_bytes(), &proof);
+        assert_eq!(verified, Some(output));
+        if is_winner(&output, threshold) {
+            wins += 1;
+        }
+    }
+    println!(
+        "  Key holder won {wins}/{slots} slots (expected ≈ {})",
+        slots * threshold as usize / 256
+    );
+}